@@ -0,0 +1,139 @@
+//! Utilities for working with angles
+//!
+//! Rotation math is a common source of bugs. Angles "wrap around" after a full turn, so `350`
+//! degrees and `-10` degrees point in exactly the same direction, and the quickest way to get from
+//! a heading of `350` to a heading of `10` is to turn by `20` degrees, not `-340`. The functions
+//! in this module take care of those details for you.
+//!
+//! Each function comes in two versions: one for angles in degrees and one for angles in radians.
+//! Use whichever one matches the unit you are using with [`use_degrees()`] or [`use_radians()`].
+//! To convert between the two units, use the [`to_degrees()`] and [`to_radians()`] methods that
+//! are available on every `f64` value.
+//!
+//! ```rust
+//! use turtle::angle;
+//!
+//! // Bring any angle into the range -180 < angle <= 180
+//! assert_eq!(angle::normalize_degrees(350.0), -10.0);
+//! assert_eq!(angle::normalize_degrees(-540.0), 180.0);
+//!
+//! // The shortest turn from 350 degrees to 10 degrees is 20 degrees counterclockwise
+//! assert_eq!(angle::difference_degrees(350.0, 10.0), 20.0);
+//!
+//! // Halfway between 350 degrees and 10 degrees is 0 degrees, not 180 degrees
+//! assert_eq!(angle::lerp_degrees(350.0, 10.0, 0.5), 0.0);
+//! ```
+//!
+//! [`use_degrees()`]: ../struct.Turtle.html#method.use_degrees
+//! [`use_radians()`]: ../struct.Turtle.html#method.use_radians
+//! [`to_degrees()`]: https://doc.rust-lang.org/std/primitive.f64.html#method.to_degrees
+//! [`to_radians()`]: https://doc.rust-lang.org/std/primitive.f64.html#method.to_radians
+
+use std::f64::consts::PI;
+
+use crate::Angle;
+
+/// Normalizes the angle to be in the range `-half_turn < angle <= half_turn`
+pub(crate) fn normalize(angle: f64, full_turn: f64) -> f64 {
+    let half_turn = full_turn / 2.0;
+    angle - full_turn * ((angle - half_turn) / full_turn).ceil()
+}
+
+/// Returns an equivalent angle in degrees such that -180 &lt; angle &le; 180.
+///
+/// ```rust
+/// use turtle::angle::normalize_degrees;
+///
+/// assert_eq!(normalize_degrees(90.0), 90.0);
+/// assert_eq!(normalize_degrees(270.0), -90.0);
+/// assert_eq!(normalize_degrees(-180.0), 180.0);
+/// assert_eq!(normalize_degrees(720.0), 0.0);
+/// ```
+pub fn normalize_degrees(angle: Angle) -> Angle {
+    normalize(angle, 360.0)
+}
+
+/// Returns an equivalent angle in radians such that -&pi; &lt; angle &le; &pi;.
+///
+/// ```rust
+/// use std::f64::consts::PI;
+/// use turtle::angle::normalize_radians;
+///
+/// assert_eq!(normalize_radians(PI / 2.0), PI / 2.0);
+/// assert_eq!(normalize_radians(-PI), PI);
+/// assert_eq!(normalize_radians(4.0 * PI), 0.0);
+/// ```
+pub fn normalize_radians(angle: Angle) -> Angle {
+    normalize(angle, 2.0 * PI)
+}
+
+/// Returns the smallest angle in degrees that you would need to turn counterclockwise (left) to
+/// get from the angle `from` to the angle `to`.
+///
+/// A negative result means that it is shorter to turn clockwise (right). The result will always
+/// be such that -180 &lt; result &le; 180.
+///
+/// ```rust
+/// use turtle::angle::difference_degrees;
+///
+/// assert_eq!(difference_degrees(10.0, 30.0), 20.0);
+/// assert_eq!(difference_degrees(30.0, 10.0), -20.0);
+/// // Crossing over 0 degrees still takes the short way around
+/// assert_eq!(difference_degrees(350.0, 10.0), 20.0);
+/// assert_eq!(difference_degrees(10.0, 350.0), -20.0);
+/// ```
+pub fn difference_degrees(from: Angle, to: Angle) -> Angle {
+    normalize_degrees(to - from)
+}
+
+/// Returns the smallest angle in radians that you would need to turn counterclockwise (left) to
+/// get from the angle `from` to the angle `to`.
+///
+/// A negative result means that it is shorter to turn clockwise (right). The result will always
+/// be such that -&pi; &lt; result &le; &pi;.
+///
+/// ```rust
+/// use std::f64::consts::PI;
+/// use turtle::angle::difference_radians;
+///
+/// assert_eq!(difference_radians(0.0, PI / 2.0), PI / 2.0);
+/// assert_eq!(difference_radians(PI / 2.0, 0.0), -PI / 2.0);
+/// ```
+pub fn difference_radians(from: Angle, to: Angle) -> Angle {
+    normalize_radians(to - from)
+}
+
+/// Linearly interpolates between two angles in degrees, always going the short way around.
+///
+/// A value of `t = 0.0` returns `from` and `t = 1.0` returns an angle equivalent to `to`. The
+/// result is normalized with [`normalize_degrees()`].
+///
+/// [`normalize_degrees()`]: fn.normalize_degrees.html
+///
+/// ```rust
+/// use turtle::angle::lerp_degrees;
+///
+/// assert_eq!(lerp_degrees(0.0, 90.0, 0.5), 45.0);
+/// assert_eq!(lerp_degrees(350.0, 30.0, 0.25), 0.0);
+/// assert_eq!(lerp_degrees(350.0, 30.0, 1.0), 30.0);
+/// ```
+pub fn lerp_degrees(from: Angle, to: Angle, t: f64) -> Angle {
+    normalize_degrees(from + difference_degrees(from, to) * t)
+}
+
+/// Linearly interpolates between two angles in radians, always going the short way around.
+///
+/// A value of `t = 0.0` returns `from` and `t = 1.0` returns an angle equivalent to `to`. The
+/// result is normalized with [`normalize_radians()`].
+///
+/// [`normalize_radians()`]: fn.normalize_radians.html
+///
+/// ```rust
+/// use std::f64::consts::PI;
+/// use turtle::angle::lerp_radians;
+///
+/// assert_eq!(lerp_radians(0.0, PI, 0.5), PI / 2.0);
+/// ```
+pub fn lerp_radians(from: Angle, to: Angle, t: f64) -> Angle {
+    normalize_radians(from + difference_radians(from, to) * t)
+}
@@ -1,11 +1,16 @@
 use std::fmt::Debug;
+use std::io;
 use std::path::Path;
+use std::net::{SocketAddr, ToSocketAddrs};
 
 use serde::{Serialize, Deserialize};
+use crate::time;
 
-use crate::ipc_protocol::ProtocolClient;
+use crate::ipc_protocol::{CommandLogEntry, ProtocolClient, ShareError, ToolpathFormat, is_valid_share_name};
+use crate::renderer_server::{Minimap, Symmetry, ImageData, BackgroundImage, mm_to_px, is_valid_recording_fps};
+use crate::event::Key;
 use crate::async_turtle::AsyncTurtle;
-use crate::{Drawing, Point, Color, Event, ExportError};
+use crate::{Error, Drawing, DrawingState, Point, Color, Distance, Rect, Event, Filter, ExportError, ExportOptions, PrintOptions, PlotterOptions, ResizeAnchor, CoordinateSystem, WorldCoordinates, Origin, DisconnectBehavior, ImportError, ImportTransform, SessionError, Vertex, Index, Primitive, PrimitiveId, ClearEffect, TextOptions, FontMetrics, ImageError, ImageHandle, BackgroundMode, LayerId};
 
 /// Represents a size
 ///
@@ -54,6 +59,22 @@ impl From<[u32; 2]> for Size {
     }
 }
 
+/// One of the four corners of the window
+///
+/// Used with methods like [`show_minimap()`](struct.Drawing.html#method.show_minimap) to choose
+/// where something should be placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Corner {
+    /// The top-left corner of the window
+    TopLeft,
+    /// The top-right corner of the window
+    TopRight,
+    /// The bottom-left corner of the window
+    BottomLeft,
+    /// The bottom-right corner of the window
+    BottomRight,
+}
+
 pub struct AsyncDrawing {
     client: ProtocolClient,
 }
@@ -72,10 +93,34 @@ impl AsyncDrawing {
         crate::start();
 
         let client = ProtocolClient::new().await
-            .expect("unable to create renderer client");
+            .unwrap_or_else(|err| panic!("unable to create renderer client: {}", err));
         Self {client}
     }
 
+    /// Creates a drawing that uses the given client
+    #[cfg(feature = "unstable")]
+    pub(crate) fn with_client(client: ProtocolClient) -> Self {
+        Self {client}
+    }
+
+    pub async fn join(name: &str) -> Result<Self, ShareError> {
+        assert!(is_valid_share_name(name), "Invalid share name: {:?}", name);
+
+        let client = ProtocolClient::join(name).await?;
+        Ok(Self {client})
+    }
+
+    pub async fn new_shared_tcp<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let addrs = addr.to_socket_addrs()?.collect();
+        let client = ProtocolClient::new_shared_tcp(addrs).await.map_err(io::Error::other)?;
+        Ok(Self {client})
+    }
+
+    pub async fn join_tcp<A: ToSocketAddrs>(addr: A) -> Result<Self, ShareError> {
+        let client = ProtocolClient::join_tcp(addr).await?;
+        Ok(Self {client})
+    }
+
     pub async fn add_turtle(&mut self) -> AsyncTurtle {
         let client = self.client.split().await;
         AsyncTurtle::with_client(client).await
@@ -93,6 +138,18 @@ impl AsyncDrawing {
         self.client.drawing_set_title(title.into())
     }
 
+    pub async fn icon(&self) -> Option<ImageHandle> {
+        self.client.drawing_icon().await
+    }
+
+    pub fn set_icon(&mut self, icon: ImageHandle) {
+        self.client.drawing_set_icon(icon)
+    }
+
+    pub fn reset_icon(&mut self) {
+        self.client.drawing_reset_icon()
+    }
+
     pub async fn background_color(&self) -> Color {
         self.client.drawing_background().await
     }
@@ -107,6 +164,16 @@ impl AsyncDrawing {
         self.client.drawing_set_background(bg_color)
     }
 
+    pub async fn set_background_image<P: AsRef<Path>>(&mut self, path: P, mode: BackgroundMode) -> Result<(), ImageError> {
+        let image = self.load_image(path).await?;
+        self.client.drawing_set_background_image(BackgroundImage {image, mode});
+        Ok(())
+    }
+
+    pub fn clear_background_image(&mut self) {
+        self.client.drawing_reset_background_image()
+    }
+
     pub async fn center(&self) -> Point {
         self.client.drawing_center().await
     }
@@ -134,10 +201,245 @@ impl AsyncDrawing {
         self.client.drawing_set_size(size)
     }
 
+    pub async fn set_size_mm(&mut self, width: f64, height: f64) {
+        assert!(width.is_finite() && width > 0.0 && height.is_finite() && height > 0.0,
+            "Invalid size: {}mm by {}mm", width, height);
+
+        let dpi = self.dpi().await;
+        let width = mm_to_px(width, dpi).round().max(1.0) as u32;
+        let height = mm_to_px(height, dpi).round().max(1.0) as u32;
+        self.set_size(Size {width, height})
+    }
+
     pub fn reset_size(&mut self) {
         self.client.drawing_reset_size()
     }
 
+    pub async fn resize_anchor(&self) -> ResizeAnchor {
+        self.client.drawing_resize_anchor().await
+    }
+
+    pub fn set_resize_anchor(&mut self, anchor: ResizeAnchor) {
+        self.client.drawing_set_resize_anchor(anchor)
+    }
+
+    pub fn show_minimap<S: Into<Size>>(&mut self, corner: Corner, size: S) {
+        let size = size.into();
+        assert!(size.width > 0 && size.height > 0, "The size of the minimap must be non-zero");
+
+        self.client.drawing_set_minimap(Some(Minimap {corner, size}))
+    }
+
+    pub fn hide_minimap(&mut self) {
+        self.client.drawing_set_minimap(None)
+    }
+
+    pub async fn filters(&self) -> Vec<Filter> {
+        self.client.drawing_filters().await
+    }
+
+    pub async fn apply_filter(&mut self, filter: Filter) {
+        assert!(filter.is_valid(), "Invalid filter: {:?}", filter);
+
+        let mut filters = self.filters().await;
+        filters.push(filter);
+        self.client.drawing_set_filters(filters)
+    }
+
+    pub fn clear_filters(&mut self) {
+        self.client.drawing_reset_filters()
+    }
+
+    pub fn set_symmetry(&mut self, axes: u32, mirror: bool) {
+        assert!(axes > 0, "The number of symmetry axes must be non-zero");
+
+        // A single axis without a mirror does not make any copies
+        let symmetry = if axes == 1 && !mirror { None } else { Some(Symmetry {axes, mirror}) };
+        self.client.drawing_set_symmetry(symmetry)
+    }
+
+    pub fn clear_symmetry(&mut self) {
+        self.client.drawing_set_symmetry(None)
+    }
+
+    pub async fn pressure_pen_width(&self) -> Option<f64> {
+        self.client.drawing_pressure_pen_width().await
+    }
+
+    pub fn set_pressure_pen_width(&mut self, max_width: f64) {
+        assert!(max_width.is_finite() && max_width > 0.0, "Invalid pen width: {}", max_width);
+
+        self.client.drawing_set_pressure_pen_width(max_width)
+    }
+
+    pub fn reset_pressure_pen_width(&mut self) {
+        self.client.drawing_reset_pressure_pen_width()
+    }
+
+    pub async fn coordinate_system(&self) -> CoordinateSystem {
+        self.client.drawing_coordinate_system().await
+    }
+
+    pub fn set_coordinate_system(&mut self, origin: Origin, y_up: bool) {
+        self.client.drawing_set_coordinate_system(CoordinateSystem {origin, y_up})
+    }
+
+    pub async fn world_coordinates(&self) -> Option<WorldCoordinates> {
+        self.client.drawing_world_coordinates().await
+    }
+
+    pub fn set_world_coordinates(&mut self, llx: f64, lly: f64, urx: f64, ury: f64) {
+        let world = WorldCoordinates {
+            lower_left: Point {x: llx, y: lly},
+            upper_right: Point {x: urx, y: ury},
+        };
+        assert!(world.is_valid(), "Invalid world coordinates: ({}, {}) to ({}, {})", llx, lly, urx, ury);
+
+        self.client.drawing_set_world_coordinates(world)
+    }
+
+    pub fn reset_world_coordinates(&mut self) {
+        self.client.drawing_reset_world_coordinates()
+    }
+
+    pub async fn zoom(&self) -> f64 {
+        self.client.drawing_zoom().await
+    }
+
+    pub fn set_zoom(&mut self, zoom: f64) {
+        assert!(zoom.is_finite() && zoom > 0.0, "Invalid zoom: {}", zoom);
+
+        self.client.drawing_set_zoom(zoom)
+    }
+
+    pub fn reset_zoom(&mut self) {
+        self.client.drawing_reset_zoom()
+    }
+
+    pub async fn is_navigation_enabled(&self) -> bool {
+        self.client.drawing_navigation().await
+    }
+
+    pub fn enable_navigation(&mut self, enabled: bool) {
+        self.client.drawing_set_navigation(enabled)
+    }
+
+    pub async fn dpi(&self) -> f64 {
+        self.client.drawing_dpi().await
+    }
+
+    pub fn set_dpi(&mut self, dpi: f64) {
+        assert!(dpi.is_finite() && dpi > 0.0, "Invalid DPI: {}", dpi);
+
+        self.client.drawing_set_dpi(dpi)
+    }
+
+    pub async fn disconnect_behavior(&self) -> DisconnectBehavior {
+        self.client.drawing_disconnect_behavior().await
+    }
+
+    pub fn set_disconnect_behavior(&mut self, behavior: DisconnectBehavior) {
+        self.client.drawing_set_disconnect_behavior(behavior)
+    }
+
+    pub async fn tracer(&self) -> u32 {
+        self.client.drawing_tracer().await
+    }
+
+    pub fn set_tracer(&mut self, tracer: u32) {
+        self.client.drawing_set_tracer(tracer)
+    }
+
+    pub fn flush(&mut self) {
+        self.client.drawing_flush()
+    }
+
+    pub async fn is_animated(&self) -> bool {
+        self.client.drawing_is_animated().await
+    }
+
+    pub fn enable_animation(&mut self) {
+        self.client.drawing_set_is_animated(true)
+    }
+
+    pub fn disable_animation(&mut self) {
+        self.client.drawing_set_is_animated(false)
+    }
+
+    pub fn pause(&mut self) {
+        self.client.drawing_set_paused(true)
+    }
+
+    pub fn resume(&mut self) {
+        self.client.drawing_set_paused(false)
+    }
+
+    pub async fn pause_key(&self) -> Option<Key> {
+        self.client.drawing_pause_key().await
+    }
+
+    pub fn set_pause_key(&mut self, key: Option<Key>) {
+        self.client.drawing_set_pause_key(key)
+    }
+
+    pub fn enable_step_mode(&mut self) {
+        self.client.drawing_set_step_mode(true)
+    }
+
+    pub fn disable_step_mode(&mut self) {
+        self.client.drawing_set_step_mode(false)
+    }
+
+    pub fn step(&mut self) {
+        self.client.drawing_step()
+    }
+
+    pub async fn step_key(&self) -> Option<Key> {
+        self.client.drawing_step_key().await
+    }
+
+    pub fn set_step_key(&mut self, key: Option<Key>) {
+        self.client.drawing_set_step_key(key)
+    }
+
+    #[cfg(feature = "shaders")]
+    pub fn set_post_shader(&mut self, source: &str) -> Result<(), crate::ShaderError> {
+        let shader = crate::renderer_server::PostShader::from_wgsl(source)?;
+        self.client.drawing_set_post_shader(shader);
+        Ok(())
+    }
+
+    #[cfg(feature = "shaders")]
+    pub fn clear_post_shader(&mut self) {
+        self.client.drawing_reset_post_shader()
+    }
+
+    pub async fn is_idle(&self) -> bool {
+        self.client.drawing_pending_animations().await == 0
+    }
+
+    pub async fn bounds(&self) -> Option<Rect> {
+        self.client.drawing_bounds().await
+    }
+
+    pub async fn fit_to_content(&mut self, padding: f64) {
+        if !padding.is_finite() {
+            return;
+        }
+
+        let bounds = match self.client.drawing_bounds().await {
+            Some(bounds) => bounds.inflate(padding),
+            // Nothing has been drawn yet, so there is nothing to fit
+            None => return,
+        };
+
+        self.set_center(bounds.center());
+        self.set_size(Size {
+            width: (bounds.width().ceil() as u32).max(1),
+            height: (bounds.height().ceil() as u32).max(1),
+        });
+    }
+
     pub async fn is_maximized(&self) -> bool {
         self.client.drawing_is_maximized().await
     }
@@ -162,16 +464,278 @@ impl AsyncDrawing {
         self.client.drawing_set_is_fullscreen(false)
     }
 
+    pub async fn state(&self) -> DrawingState {
+        DrawingState {
+            title: self.title().await,
+            background: self.background_color().await,
+            center: self.center().await,
+            zoom: self.zoom().await,
+            size: self.size().await,
+            is_maximized: self.is_maximized().await,
+            is_fullscreen: self.is_fullscreen().await,
+        }
+    }
+
+    pub fn set_state(&mut self, state: &DrawingState) {
+        let DrawingState {ref title, background, center, zoom, size, is_maximized, is_fullscreen} = *state;
+
+        self.set_title(title.clone());
+        self.set_background_color(background);
+        self.set_center(center);
+        self.set_zoom(zoom);
+        self.set_size(size);
+        if is_maximized {
+            self.maximize();
+        } else {
+            self.unmaximize();
+        }
+        if is_fullscreen {
+            self.enter_fullscreen();
+        } else {
+            self.exit_fullscreen();
+        }
+    }
+
+    pub fn draw_circle<P: Into<Point>, C: Into<Color> + Copy + Debug>(&mut self, center: P, radius: Distance, fill_color: C) {
+        let center = center.into();
+        if !center.is_finite() || !radius.is_finite() {
+            return;
+        }
+
+        let color = fill_color.into();
+        assert!(
+            color.is_valid(),
+            "Invalid color: {:?}. See the color module documentation for more information.",
+            fill_color
+        );
+        self.client.draw_circle(center, radius.abs(), color)
+    }
+
+    pub async fn add_layer(&mut self) -> LayerId {
+        self.client.add_layer().await
+    }
+
+    pub async fn is_layer_visible(&self, layer: LayerId) -> bool {
+        self.try_is_layer_visible(layer).await
+            .unwrap_or_else(|err| panic!("Unable to check if the layer is visible: {}", err))
+    }
+
+    pub async fn try_is_layer_visible(&self, layer: LayerId) -> Result<bool, Error> {
+        self.client.layer_is_visible(layer).await
+    }
+
+    pub fn set_layer_visible(&mut self, layer: LayerId, is_visible: bool) {
+        self.client.set_layer_visible(layer, is_visible)
+    }
+
+    pub fn raise_layer(&mut self, layer: LayerId) {
+        self.client.move_layer(layer, true)
+    }
+
+    pub fn lower_layer(&mut self, layer: LayerId) {
+        self.client.move_layer(layer, false)
+    }
+
     pub fn clear(&mut self) {
         self.client.clear_all()
     }
 
+    pub async fn clear_animated(&mut self, effect: ClearEffect) {
+        assert!(effect.is_valid(), "Invalid clear effect: {:?}", effect);
+
+        self.client.clear_animated(effect).await
+    }
+
+    pub fn clear_region(&mut self, area: Rect) {
+        if !area.min.is_finite() || !area.max.is_finite() {
+            return;
+        }
+
+        self.client.clear_region(area)
+    }
+
+    pub async fn share(&mut self, name: &str) -> Result<(), ShareError> {
+        assert!(is_valid_share_name(name), "Invalid share name: {:?}", name);
+
+        self.client.share(name.to_string()).await
+    }
+
+    pub async fn share_tcp<A: ToSocketAddrs>(&self, addr: A) -> io::Result<SocketAddr> {
+        let addrs = addr.to_socket_addrs()?.collect();
+        self.client.share_tcp(addrs).await.map_err(io::Error::other)
+    }
+
+    #[cfg(feature = "websocket")]
+    pub async fn serve_websocket_with<A: std::net::ToSocketAddrs>(&self, addr: A, options: crate::WebSocketOptions) -> std::io::Result<std::net::SocketAddr> {
+        let client = self.client.split().await;
+        crate::websocket_bridge::spawn(client, addr, options)
+    }
+
     pub async fn poll_event(&mut self) -> Option<Event> {
         self.client.poll_event().await
     }
 
+    #[cfg(feature = "unstable")]
+    pub async fn wait_for_event(&mut self) -> Option<Event> {
+        self.client.next_event().await
+    }
+
+    #[cfg(feature = "unstable")]
+    pub async fn events(&self) -> crate::event::EventStream {
+        // The stream gets its own client so that waiting for events never holds up other requests
+        let client = self.client.split().await;
+        crate::event::EventStream::new(client)
+    }
+
     pub async fn save_svg<P: AsRef<Path>>(&self, path: P) -> Result<(), ExportError> {
-        self.client.export_svg(path.as_ref().to_path_buf()).await
+        self.save_svg_with(path, ExportOptions::default()).await
+    }
+
+    pub async fn save_svg_with<P: AsRef<Path>>(&self, path: P, options: ExportOptions) -> Result<(), ExportError> {
+        assert!(options.is_valid(), "Invalid export options: {:?}", options);
+
+        self.client.export_svg(path.as_ref().to_path_buf(), options).await
+    }
+
+    pub async fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), ExportError> {
+        self.save_png_with(path, ExportOptions::default()).await
+    }
+
+    pub async fn save_png_with<P: AsRef<Path>>(&self, path: P, options: ExportOptions) -> Result<(), ExportError> {
+        assert!(options.is_valid(), "Invalid export options: {:?}", options);
+
+        self.client.export_png(path.as_ref().to_path_buf(), options).await
+    }
+
+    pub async fn start_recording_gif<P: AsRef<Path>>(&self, path: P, fps: f64) -> Result<(), ExportError> {
+        assert!(is_valid_recording_fps(fps), "Invalid frame rate: {}", fps);
+
+        self.client.start_recording(path.as_ref().to_path_buf(), fps).await
+    }
+
+    pub async fn record_to<P: AsRef<Path>>(&self, path: P) -> Result<(), ExportError> {
+        self.client.record_commands(path.as_ref().to_path_buf()).await
+    }
+
+    /// Runs the requests from a command log, `speed` times as fast as they were recorded
+    ///
+    /// The entire log is checked before anything runs, so a log with a request that would never
+    /// have been written into it (e.g. one that writes a file) is rejected without running any of
+    /// its requests.
+    pub(crate) async fn replay<I>(&mut self, log: I, speed: f64) -> io::Result<()>
+        where I: IntoIterator<Item=io::Result<CommandLogEntry>>,
+    {
+        let entries = log.into_iter().collect::<io::Result<Vec<_>>>()?;
+        if let Some(entry) = entries.iter().find(|entry| !entry.request.is_logged()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("command log contains a request that cannot be replayed: {}", entry.request.name()),
+            ));
+        }
+
+        let start = time::Instant::now();
+        for entry in entries {
+            let CommandLogEntry {time: offset, request} = entry;
+            time::delay_until(start + offset.div_f64(speed)).await;
+            self.client.replay_request(request).await;
+        }
+
+        Ok(())
+    }
+
+    pub async fn stop_recording(&self) -> Result<(), ExportError> {
+        self.client.stop_recording().await
+    }
+
+    pub async fn import_svg_file<P: AsRef<Path>>(&mut self, path: P, transform: ImportTransform) -> Result<(), ImportError> {
+        assert!(transform.is_valid(), "Invalid import transform: {:?}", transform);
+
+        self.client.import_svg(path.as_ref().to_path_buf(), transform).await
+    }
+
+    pub async fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SessionError> {
+        self.client.save_session(path.as_ref().to_path_buf()).await
+    }
+
+    pub async fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), SessionError> {
+        self.client.load_session(path.as_ref().to_path_buf()).await
+    }
+
+    pub async fn load_image<P: AsRef<Path>>(&mut self, path: P) -> Result<ImageHandle, ImageError> {
+        // The image is decoded here so that the window never needs to read any files
+        let image = ImageData::open(path.as_ref())?;
+
+        self.client.load_image(image).await
+    }
+
+    pub async fn load_image_from_bytes(&mut self, bytes: &[u8]) -> Result<ImageHandle, ImageError> {
+        let image = ImageData::decode_png(bytes)?;
+
+        self.client.load_image(image).await
+    }
+
+    pub async fn print(&self, options: PrintOptions) -> Result<(), ExportError> {
+        assert!(options.is_valid(), "Invalid print options: {:?}", options);
+
+        self.client.print(options).await
+    }
+
+    pub async fn save_gcode<P: AsRef<Path>>(&self, path: P, options: PlotterOptions) -> Result<(), ExportError> {
+        assert!(options.is_valid(), "Invalid plotter options: {:?}", options);
+
+        self.client.export_toolpath(path.as_ref().to_path_buf(), ToolpathFormat::Gcode, options).await
+    }
+
+    pub async fn save_hpgl<P: AsRef<Path>>(&self, path: P, options: PlotterOptions) -> Result<(), ExportError> {
+        assert!(options.is_valid(), "Invalid plotter options: {:?}", options);
+
+        self.client.export_toolpath(path.as_ref().to_path_buf(), ToolpathFormat::Hpgl, options).await
+    }
+
+    pub async fn export_mesh(&self) -> (Vec<Vertex>, Vec<Index>) {
+        self.client.drawing_export_mesh().await
+    }
+
+    pub async fn export_svg(&self, options: ExportOptions) -> String {
+        assert!(options.is_valid(), "Invalid export options: {:?}", options);
+
+        self.client.drawing_export_svg(options).await
+    }
+
+    pub async fn primitives(&self) -> Vec<Primitive> {
+        self.client.drawing_primitives().await
+    }
+
+    pub async fn primitive(&self, id: PrimitiveId) -> Option<Primitive> {
+        self.client.drawing_primitive(id).await
+    }
+
+    pub async fn hit_test<P: Into<Point>>(&self, point: P) -> Vec<PrimitiveId> {
+        let point = point.into();
+        assert!(point.is_finite(), "Invalid point: {:?}", point);
+
+        self.client.drawing_hit_test(point).await
+    }
+
+    pub async fn color_at<P: Into<Point>>(&self, point: P) -> Color {
+        let point = point.into();
+        assert!(point.is_finite(), "Invalid point: {:?}", point);
+
+        self.client.drawing_color_at(point).await
+    }
+
+    pub async fn measure_text(&self, text: &str, options: TextOptions) -> Size {
+        assert!(options.is_valid(), "Invalid font size: {}", options.font_size);
+
+        self.client.measure_text(text.to_string(), options).await
+            .unwrap_or_else(|err| panic!("Unable to load font: {}", err))
+    }
+
+    pub async fn font_metrics(&self, options: TextOptions) -> FontMetrics {
+        assert!(options.is_valid(), "Invalid font size: {}", options.font_size);
+
+        self.client.font_metrics(options).await
+            .unwrap_or_else(|err| panic!("Unable to load font: {}", err))
     }
 
     //TODO: If we move to a shared memory architecture, we wouldn't need to make
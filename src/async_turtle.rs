@@ -1,11 +1,12 @@
 use std::fmt::Debug;
 
-use tokio::time;
+use crate::time;
 
 use crate::radians::{self, Radians};
+use crate::path::PathSegment;
 use crate::ipc_protocol::{ProtocolClient, RotationDirection};
-use crate::renderer_server::TurtleId;
-use crate::{Turtle, Color, Point, Speed};
+use crate::renderer_server::{TurtleId, mm_to_px, is_valid_dash_pattern};
+use crate::{Error, Turtle, TurtleState, PenState, Color, Point, Rect, Path, Speed, Easing, Shape, FillRule, BlendMode, TextAlign, TextOptions, ImageHandle, StampId, LayerId};
 
 /// Any distance value (positive or negative)
 pub type Distance = f64;
@@ -59,7 +60,7 @@ impl AsyncTurtle {
         crate::start();
 
         let client = ProtocolClient::new().await
-            .expect("unable to create renderer client");
+            .unwrap_or_else(|err| panic!("unable to create renderer client: {}", err));
         Self::with_client(client).await
     }
 
@@ -123,6 +124,79 @@ impl AsyncTurtle {
             .await
     }
 
+    pub async fn bezier_to<P: Into<Point>>(&mut self, control1: P, control2: P, end: P) {
+        self.client.cubic_bezier_to(self.id, control1.into(), control2.into(), end.into()).await
+    }
+
+    pub async fn quad_bezier_to<P: Into<Point>>(&mut self, control: P, end: P) {
+        self.client.quad_bezier_to(self.id, control.into(), end.into()).await
+    }
+
+    pub async fn follow_path<P: Into<Path>>(&mut self, path: P) {
+        let path = path.into();
+        // The renderer server always uses radians
+        let segments = path.segments().iter().map(|&segment| match segment {
+            PathSegment::Arc(center, extent) => PathSegment::Arc(center, self.angle_unit.to_radians(extent).to_radians()),
+            segment => segment,
+        }).collect();
+
+        self.client.follow_path(self.id, segments).await
+    }
+
+    pub async fn write(&mut self, text: &str) {
+        self.write_with(TextOptions::default().font_size, TextAlign::default(), text).await
+    }
+
+    pub async fn write_with(&mut self, font_size: f64, align: TextAlign, text: &str) {
+        let options = TextOptions {font_size, ..TextOptions::default()};
+        assert!(options.is_valid(), "Invalid font size: {}", font_size);
+
+        self.client.write_text(self.id, text.to_string(), options, align).await
+            .unwrap_or_else(|err| panic!("Unable to load font: {}", err));
+    }
+
+    pub async fn stamp(&mut self) -> StampId {
+        self.client.stamp(self.id).await
+    }
+
+    pub async fn stamp_image(&mut self, image: ImageHandle) -> StampId {
+        self.try_stamp_image(image).await
+            .unwrap_or_else(|err| panic!("Unable to stamp the image: {}", err))
+    }
+
+    pub async fn try_stamp_image(&mut self, image: ImageHandle) -> Result<StampId, Error> {
+        self.client.stamp_image(self.id, image).await
+    }
+
+    pub fn clear_stamp(&mut self, stamp: StampId) {
+        self.client.clear_stamp(self.id, stamp)
+    }
+
+    pub fn clear_stamps(&mut self) {
+        self.client.clear_stamps(self.id)
+    }
+
+    pub fn undo_n(&mut self, count: usize) {
+        self.client.undo(self.id, count)
+    }
+
+    pub fn push_state(&mut self) {
+        self.client.push_state(self.id)
+    }
+
+    pub async fn pop_state(&mut self) {
+        let popped = self.client.pop_state(self.id).await;
+        assert!(popped, "Unable to pop turtle state: no state was saved with push_state()");
+    }
+
+    pub async fn circle(&mut self, radius: Distance) {
+        self.client.move_circle(self.id, radius).await
+    }
+
+    pub async fn pending_animations(&self) -> usize {
+        self.client.turtle_pending_animations(self.id).await
+    }
+
     pub fn into_sync(self) -> Turtle {
         self.into()
     }
@@ -135,6 +209,41 @@ impl AsyncTurtle {
         self.client.turtle_set_speed(self.id, speed.into())
     }
 
+    pub async fn easing(&self) -> Easing {
+        self.client.turtle_easing(self.id).await
+    }
+
+    pub fn set_easing(&mut self, easing: Easing) {
+        assert!(easing.is_valid(), "Invalid easing: {:?}. Every control point must be between 0.0 and 1.0", easing);
+
+        self.client.turtle_set_easing(self.id, easing)
+    }
+
+    pub async fn forward_at<S: Into<Speed>>(&mut self, distance: Distance, speed: S) {
+        let prev_speed = self.speed().await;
+        self.set_speed(speed);
+        self.forward(distance).await;
+        self.set_speed(prev_speed);
+    }
+
+    pub async fn forward_instant(&mut self, distance: Distance) {
+        self.forward_at(distance, Speed::instant()).await
+    }
+
+    pub async fn forward_in(&mut self, distance: Distance, duration: time::Duration) {
+        self.client.timed_move_forward(self.id, distance, duration).await
+    }
+
+    pub async fn right_in(&mut self, angle: Angle, duration: time::Duration) {
+        let angle = self.angle_unit.to_radians(angle);
+        self.client.timed_rotate_in_place(self.id, angle, RotationDirection::Clockwise, duration).await
+    }
+
+    pub async fn left_in(&mut self, angle: Angle, duration: time::Duration) {
+        let angle = self.angle_unit.to_radians(angle);
+        self.client.timed_rotate_in_place(self.id, angle, RotationDirection::Counterclockwise, duration).await
+    }
+
     pub async fn position(&self) -> Point {
         self.client.turtle_position(self.id).await
     }
@@ -172,12 +281,10 @@ impl AsyncTurtle {
 
         let heading = self.client.turtle_heading(self.id).await;
         // Find the amount we need to turn to reach the target heading based on our current heading
-        let angle = angle - heading;
-        // Normalize the angle to be between -180 and 179 so that we rotate as little as possible
-        // Formula from: https://stackoverflow.com/a/24234924/551904
-        let angle = angle - radians::TWO_PI * ((angle + radians::PI) / radians::TWO_PI).floor();
+        // so that we rotate as little as possible
+        let angle = heading.shortest_difference(angle);
 
-        self.client.rotate_in_place(self.id, angle, RotationDirection::Counterclockwise).await
+        self.client.rotate_in_place(self.id, angle, self.increasing_heading_direction().await).await
     }
 
     pub fn is_using_degrees(&self) -> bool {
@@ -222,6 +329,39 @@ impl AsyncTurtle {
         self.client.turtle_pen_set_thickness(self.id, thickness)
     }
 
+    pub async fn pen_dash_pattern(&self) -> Vec<f64> {
+        self.client.turtle_pen_dash_pattern(self.id).await
+    }
+
+    pub fn set_pen_dash_pattern(&mut self, pattern: &[f64]) {
+        assert!(
+            is_valid_dash_pattern(pattern),
+            "Invalid dash pattern: {:?}. Each length must be greater than or equal to zero and at least one must be greater than zero",
+            pattern
+        );
+
+        self.client.turtle_pen_set_dash_pattern(self.id, pattern.to_vec())
+    }
+
+    pub async fn pen_blend_mode(&self) -> BlendMode {
+        self.client.turtle_pen_blend_mode(self.id).await
+    }
+
+    pub fn set_pen_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.client.turtle_pen_set_blend_mode(self.id, blend_mode)
+    }
+
+    pub async fn set_pen_size_mm(&mut self, thickness: f64) {
+        assert!(
+            thickness >= 0.0 && thickness.is_finite(),
+            "Invalid thickness: {}mm. The pen thickness must be greater than or equal to zero",
+            thickness
+        );
+
+        let dpi = self.client.drawing_dpi().await;
+        self.set_pen_size(mm_to_px(thickness, dpi))
+    }
+
     pub async fn pen_color(&self) -> Color {
         self.client.turtle_pen_color(self.id).await
     }
@@ -236,6 +376,16 @@ impl AsyncTurtle {
         self.client.turtle_pen_set_color(self.id, pen_color)
     }
 
+    pub fn animate_pen_color<C: Into<Color> + Copy + Debug>(&mut self, color: C, duration: std::time::Duration) {
+        let pen_color = color.into();
+        assert!(
+            pen_color.is_valid(),
+            "Invalid color: {:?}. See the color module documentation for more information.",
+            color
+        );
+        self.client.turtle_animate_pen_color(self.id, pen_color, duration)
+    }
+
     pub async fn fill_color(&self) -> Color {
         self.client.turtle_fill_color(self.id).await
     }
@@ -250,6 +400,14 @@ impl AsyncTurtle {
         self.client.turtle_set_fill_color(self.id, fill_color)
     }
 
+    pub async fn fill_rule(&self) -> FillRule {
+        self.client.turtle_fill_rule(self.id).await
+    }
+
+    pub fn set_fill_rule(&mut self, fill_rule: FillRule) {
+        self.client.turtle_set_fill_rule(self.id, fill_rule)
+    }
+
     pub async fn is_filling(&self) -> bool {
         self.client.turtle_is_filling(self.id).await
     }
@@ -274,6 +432,105 @@ impl AsyncTurtle {
         self.client.turtle_set_is_visible(self.id, true)
     }
 
+    pub async fn shape(&self) -> Shape {
+        self.client.turtle_shape(self.id).await
+    }
+
+    pub fn set_shape(&mut self, shape: Shape) {
+        assert!(
+            shape.is_valid(),
+            "Invalid shape: {:?}. A polygon must have at least three points and every point must be finite",
+            shape
+        );
+
+        self.client.turtle_set_shape(self.id, shape)
+    }
+
+    pub async fn shape_size(&self) -> f64 {
+        self.client.turtle_shape_size(self.id).await
+    }
+
+    pub fn set_shape_size(&mut self, size: f64) {
+        assert!(
+            size > 0.0 && size.is_finite(),
+            "Invalid shape size: {}. The shape size must be greater than zero",
+            size
+        );
+
+        self.client.turtle_set_shape_size(self.id, size)
+    }
+
+    pub async fn layer(&self) -> LayerId {
+        self.client.turtle_layer(self.id).await
+    }
+
+    pub fn set_layer(&mut self, layer: LayerId) {
+        self.client.turtle_set_layer(self.id, layer)
+    }
+
+    pub async fn state(&self) -> TurtleState {
+        let heading = self.client.turtle_heading(self.id).await;
+
+        TurtleState {
+            position: self.position().await,
+            heading: heading.to_degrees(),
+            speed: self.speed().await,
+            easing: self.easing().await,
+            pen: PenState {
+                is_enabled: self.is_pen_down().await,
+                thickness: self.pen_size().await,
+                color: self.pen_color().await,
+                dash_pattern: self.pen_dash_pattern().await,
+                blend_mode: self.pen_blend_mode().await,
+            },
+            fill_color: self.fill_color().await,
+            fill_rule: self.fill_rule().await,
+            is_visible: self.is_visible().await,
+            shape: self.shape().await,
+            shape_size: self.shape_size().await,
+        }
+    }
+
+    pub async fn set_state(&mut self, state: &TurtleState) {
+        let TurtleState {
+            position,
+            heading,
+            speed,
+            easing,
+            ref pen,
+            fill_color,
+            fill_rule,
+            is_visible,
+            ref shape,
+            shape_size,
+        } = *state;
+
+        // Move the turtle into place without drawing anything or animating
+        self.pen_up();
+        self.set_speed(Speed::instant());
+        self.go_to(position).await;
+        self.set_heading(self.angle_unit.to_angle(Radians::from_degrees_value(heading))).await;
+
+        self.set_speed(speed);
+        self.set_easing(easing);
+        self.set_pen_size(pen.thickness);
+        self.set_pen_color(pen.color);
+        self.set_pen_dash_pattern(&pen.dash_pattern);
+        self.set_pen_blend_mode(pen.blend_mode);
+        if pen.is_enabled {
+            self.pen_down();
+        }
+        self.set_fill_color(fill_color);
+        self.set_fill_rule(fill_rule);
+        if is_visible {
+            self.show();
+        } else {
+            self.hide();
+        }
+        self.set_shape(shape.clone());
+        self.set_shape_size(shape_size);
+    }
+
     pub fn reset(&mut self) {
         self.clear();
         self.client.reset_turtle(self.id);
@@ -283,6 +540,33 @@ impl AsyncTurtle {
         self.client.clear_turtle(self.id)
     }
 
+    pub async fn towards<P: Into<Point>>(&self, target: P) -> Angle {
+        let target: Point = target.into();
+        let position = self.position().await;
+
+        let angle = Radians::from_radians_value(position.angle_to(target));
+        // Use the same range as the heading (between 0 and 2*pi) so the two can be compared
+        let angle = angle - radians::TWO_PI * (angle / radians::TWO_PI).floor();
+        self.angle_unit.to_angle(angle)
+    }
+
+    pub async fn drawing_bounds(&self) -> Option<Rect> {
+        self.client.turtle_drawing_bounds(self.id).await
+    }
+
+    pub async fn distance_to(&self, other: &AsyncTurtle) -> Distance {
+        let position = self.position().await;
+        let other_position = other.position().await;
+
+        position.distance(other_position)
+    }
+
+    pub async fn touches(&self, other: &AsyncTurtle, radius: Distance) -> bool {
+        assert!(radius.is_finite() && radius >= 0.0, "Invalid radius: {}", radius);
+
+        self.distance_to(other).await <= radius
+    }
+
     pub async fn turn_towards<P: Into<Point>>(&mut self, target: P) {
         let target: Point = target.into();
         if !target.is_finite() {
@@ -299,21 +583,32 @@ impl AsyncTurtle {
         let heading = self.client.turtle_heading(self.id).await;
 
         // Calculate the target angle to reach
-        let angle = (target - position).atan2();
-        let angle = Radians::from_radians_value(angle);
-        // Calculate how much turning will be needed (angle - heading)
-        // And clamp it make sure the turtle doesn't turn more than 360 degrees
-        let angle = (angle - heading) % radians::TWO_PI;
-        // Try to rotate as little as possible
-        let angle = if angle.abs() > radians::PI {
-            // Use signum to make sure the angle has the right sign
-            // And the turtle turns the right way
-            -angle.signum() * (radians::TWO_PI - angle.abs())
-        } else {
-            angle
+        let angle = Radians::from_radians_value(position.angle_to(target));
+        // Calculate how much turning will be needed, rotating as little as possible
+        let angle = heading.shortest_difference(angle);
+
+        self.client.rotate_in_place(self.id, angle, self.increasing_heading_direction().await).await
+    }
+
+    /// Returns the direction the turtle needs to rotate in for its heading to increase
+    ///
+    /// Headings are measured clockwise on the screen when the y axis of the drawing points down,
+    /// or when exactly one axis of the world coordinates is flipped.
+    async fn increasing_heading_direction(&self) -> RotationDirection {
+        let counterclockwise = match self.client.drawing_world_coordinates().await {
+            Some(world) => {
+                let flipped_x = world.upper_right.x < world.lower_left.x;
+                let flipped_y = world.upper_right.y < world.lower_left.y;
+                flipped_x == flipped_y
+            },
+            None => self.client.drawing_coordinate_system().await.y_up,
         };
 
-        self.client.rotate_in_place(self.id, angle, RotationDirection::Counterclockwise).await
+        if counterclockwise {
+            RotationDirection::Counterclockwise
+        } else {
+            RotationDirection::Clockwise
+        }
     }
 
     pub async fn wait_for_click(&mut self) {
@@ -332,6 +627,24 @@ impl AsyncTurtle {
         }
     }
 
+    pub async fn breakpoint(&mut self, label: &str) {
+        use crate::{Event::Key, event::PressedState::Pressed};
+
+        let title = self.client.drawing_title().await;
+        self.client.drawing_set_title(format!("{} - {} (press any key to continue)", title, label));
+
+        loop {
+            if let Some(Key(_, Pressed)) = self.client.poll_event().await {
+                break;
+            }
+
+            // Sleep for ~1 frame (at 120fps) to avoid pegging the CPU.
+            self.wait(1.0 / 120.0).await;
+        }
+
+        self.client.drawing_set_title(title);
+    }
+
     //TODO: If we move to a shared memory architecture, we wouldn't need to make
     // any request here and thus would not need this method at all. We should
     // think things through before making this method public.
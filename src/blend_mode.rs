@@ -0,0 +1,42 @@
+use serde::{Serialize, Deserialize};
+
+/// The way that the lines drawn by the pen of a turtle combine with what has already been drawn
+///
+/// By default, lines are drawn on top of everything else, with transparent colors letting what
+/// is underneath show through ([`Alpha`](#variant.Alpha)). The other modes can be used for glow
+/// effects, shadows, or erasing parts of the drawing.
+///
+/// ```rust
+/// use turtle::{Turtle, BlendMode};
+///
+/// let mut turtle = Turtle::new();
+/// turtle.set_pen_size(20.0);
+/// turtle.forward(200.0);
+///
+/// // Erase a gap in the middle of the line that was just drawn
+/// turtle.set_pen_blend_mode(BlendMode::Erase);
+/// turtle.backward(120.0);
+/// turtle.pen_up();
+/// turtle.backward(80.0);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BlendMode {
+    /// Lines are drawn over what is underneath them, which only shows through if the color of the
+    /// pen is transparent (default)
+    #[default]
+    Alpha,
+    /// The color of each line is added to the colors underneath it, so overlapping lines get
+    /// brighter until they are white
+    ///
+    /// This works best on a dark background.
+    Additive,
+    /// The color of each line is multiplied with the colors underneath it, so overlapping lines
+    /// get darker, like layers of ink
+    ///
+    /// This works best on a light background.
+    Multiply,
+    /// Lines remove everything underneath them, uncovering the background
+    ///
+    /// The color of the pen does not matter, except that a transparent pen only erases partway.
+    Erase,
+}
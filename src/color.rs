@@ -391,6 +391,89 @@ impl Color {
         Color::rgba(red, green, blue, alpha)
     }
 
+    /// Create a new `Color` from the given [`HSV`] values with alpha set to 1.0.
+    ///
+    /// HSV (hue, saturation, value) is similar to HSL, except that a `value` of 1.0 gives the
+    /// brightest version of the hue instead of white. This makes it easy to go around the color
+    /// wheel at full brightness, e.g. when drawing a rainbow.
+    ///
+    /// The expected value ranges are:
+    ///
+    /// * 0.0 &le; `hue` &le; 360.0
+    /// * 0.0 &le; `saturation` &le; 1.0
+    /// * 0.0 &le; `value` &le; 1.0
+    ///
+    /// ```rust
+    /// use turtle::Color;
+    /// assert_eq!(Color::hsv(0.0, 1.0, 1.0), Color::rgb(255.0, 0.0, 0.0));
+    /// assert_eq!(Color::hsv(240.0, 0.5, 1.0), Color::rgb(128.0, 128.0, 255.0));
+    ///
+    /// let white: Color = "white".into();
+    /// assert_eq!(white, Color::hsv(0.0, 0.0, 1.0));
+    ///
+    /// let black: Color = "black".into();
+    /// assert_eq!(black, Color::hsv(120.0, 1.0, 0.0));
+    /// ```
+    ///
+    /// Each color of a rainbow spiral can be chosen from the angle that the turtle has turned:
+    ///
+    /// ```rust
+    /// use turtle::{Turtle, Color};
+    ///
+    /// let mut turtle = Turtle::new();
+    /// for i in 0..360 {
+    ///     turtle.set_pen_color(Color::hsv(i as f64, 1.0, 1.0));
+    ///     turtle.forward(i as f64 / 100.0);
+    ///     turtle.right(1.0);
+    /// }
+    /// ```
+    /// [`HSV`]: https://en.wikipedia.org/wiki/HSL_and_HSV
+    pub fn hsv(hue: f64, saturation: f64, value: f64) -> Self {
+        Color::hsva(hue, saturation, value, 1.0)
+    }
+
+    /// Create a new `Color` from the given [`HSV`] values and the given alpha value.
+    ///
+    /// The expected value ranges are:
+    ///
+    /// * 0.0 &le; `hue` &le; 360.0
+    /// * 0.0 &le; `saturation` &le; 1.0
+    /// * 0.0 &le; `value` &le; 1.0
+    /// * 0.0 &le; `alpha` &le; 1.0
+    ///
+    /// ```rust
+    /// use turtle::Color;
+    ///
+    /// let green = Color::rgba(0.0, 255.0, 0.0, 0.5);
+    /// assert_eq!(green, Color::hsva(120.0, 1.0, 1.0, 0.5));
+    /// ```
+    /// [`HSV`]: https://en.wikipedia.org/wiki/HSL_and_HSV
+    pub fn hsva(hue: f64, saturation: f64, value: f64, alpha: f64) -> Self {
+        assert_value_in_range!("hue", hue, COLOR_MIN_VALUE, HUE_MAX_VAL);
+        assert_value_in_range!("saturation", saturation, COLOR_MIN_VALUE, SAL_MAX_VAL);
+        assert_value_in_range!("value", value, COLOR_MIN_VALUE, SAL_MAX_VAL);
+        assert_value_in_range!("alpha", alpha, COLOR_MIN_VALUE, SAL_MAX_VAL);
+
+        // The largest component minus the smallest one
+        let chroma = value * saturation;
+        // The hue is split into six sectors of 60 degrees, between each primary and secondary color
+        let sector = (hue / 60.) % 6.;
+        let x = chroma * (1. - ((sector % 2.) - 1.).abs());
+        let (r, g, b) = match sector {
+            s if s < 1. => (chroma, x, 0.),
+            s if s < 2. => (x, chroma, 0.),
+            s if s < 3. => (0., chroma, x),
+            s if s < 4. => (0., x, chroma),
+            s if s < 5. => (x, 0., chroma),
+            _ => (chroma, 0., x),
+        };
+
+        // Change each percentage to an actual rgb value between 0 and 255
+        let m = value - chroma;
+        let to_rgb = |c: f64| ((c + m) * RGB_MAX_VAL).round();
+        Color::rgba(to_rgb(r), to_rgb(g), to_rgb(b), alpha)
+    }
+
     /// Returns true if the values for each field are valid.
     ///
     /// The documentation above lists the valid range for each field.
@@ -576,6 +659,39 @@ impl Color {
         Color::rgba(r_mod, g_mod, b_mod, a_mod)
     }
 
+    /// Returns the color that is the given fraction of the way from this color to the other color
+    ///
+    /// Each component (including alpha) changes linearly, so a `t` of 0.0 returns this color, 1.0
+    /// returns the other color, and 0.5 returns the color halfway between them. Unlike
+    /// [`mix()`](#method.mix), the components are not rounded, which makes this useful for
+    /// smoothly changing from one color to another.
+    ///
+    /// The value of `t` must be between 0.0 and 1.0 (inclusive).
+    ///
+    /// ```rust
+    /// use turtle::Color;
+    ///
+    /// let black = Color::rgb(0.0, 0.0, 0.0);
+    /// let orange = Color::rgba(255.0, 165.0, 0.0, 0.5);
+    /// assert_eq!(black.lerp(orange, 0.0), black);
+    /// assert_eq!(black.lerp(orange, 1.0), orange);
+    /// assert_eq!(black.lerp(orange, 0.5), Color::rgba(127.5, 82.5, 0.0, 0.75));
+    /// ```
+    pub fn lerp<C: Into<Color> + Copy + Debug>(self, other: C, t: f64) -> Self {
+        assert_color_valid!(self);
+        let other = other.into();
+        assert_color_valid!(other);
+        assert_value_in_range!("t", t, 0., 1.);
+
+        let lerp = |from: f64, to: f64| from + (to - from) * t;
+        Color {
+            red: lerp(self.red, other.red),
+            green: lerp(self.green, other.green),
+            blue: lerp(self.blue, other.blue),
+            alpha: lerp(self.alpha, other.alpha),
+        }
+    }
+
     /// Retrieve the hue for this `Color`. The returned value is in degrees
     /// between 0° and 360° that represents its position on the color wheel.
     ///
@@ -615,6 +731,76 @@ impl Color {
         self.to_hsl().2
     }
 
+    /// Retrieve the [`HSV`] value (brightness) of this `Color`. The returned value is between 0.0
+    /// and 1.0 (inclusive), where 1.0 is the brightest version of the hue of this color.
+    ///
+    /// See [`hsv()`](#method.hsv) for more information.
+    ///
+    /// ```rust
+    /// use turtle::Color;
+    ///
+    /// let c = Color::hsv(30.0, 0.5, 0.6);
+    /// assert_eq!(0.6, c.value());
+    ///
+    /// let white: Color = "white".into();
+    /// assert_eq!(1.0, white.value());
+    /// ```
+    /// [`HSV`]: https://en.wikipedia.org/wiki/HSL_and_HSV
+    pub fn value(self) -> f64 {
+        assert_color_valid!(self);
+        self.red.max(self.green).max(self.blue) / RGB_MAX_VAL
+    }
+
+    /// Return a new color with the same saturation, lightness, and alpha as this color, but with
+    /// the given hue
+    ///
+    /// The hue must be between 0.0 and 360.0. See [`hsl()`](#method.hsl) for more information.
+    ///
+    /// ```rust
+    /// use turtle::Color;
+    ///
+    /// let c = Color::hsla(120.0, 0.5, 0.4, 0.8);
+    /// assert_eq!(c.with_hue(300.0), Color::hsla(300.0, 0.5, 0.4, 0.8));
+    /// ```
+    pub fn with_hue(self, hue: f64) -> Self {
+        let (_, s, l) = self.to_hsl();
+        Color::hsla(hue, s, l, self.alpha)
+    }
+
+    /// Return a new color with the same hue, lightness, and alpha as this color, but with the
+    /// given saturation
+    ///
+    /// The saturation must be between 0.0 and 1.0. See [`hsl()`](#method.hsl) for more
+    /// information.
+    ///
+    /// ```rust
+    /// use turtle::Color;
+    ///
+    /// let c = Color::hsl(200.0, 0.9, 0.5);
+    /// assert_eq!(c.with_saturation(0.25), Color::hsl(200.0, 0.25, 0.5));
+    /// ```
+    pub fn with_saturation(self, saturation: f64) -> Self {
+        let (h, _, l) = self.to_hsl();
+        Color::hsla(h, saturation, l, self.alpha)
+    }
+
+    /// Return a new color with the same hue, saturation, and alpha as this color, but with the
+    /// given lightness
+    ///
+    /// The lightness must be between 0.0 and 1.0. See [`hsl()`](#method.hsl) for more
+    /// information.
+    ///
+    /// ```rust
+    /// use turtle::Color;
+    ///
+    /// let c = Color::hsl(20.0, 0.6, 0.5);
+    /// assert_eq!(c.with_lightness(0.75), Color::hsl(20.0, 0.6, 0.75));
+    /// ```
+    pub fn with_lightness(self, lightness: f64) -> Self {
+        let (h, s, _) = self.to_hsl();
+        Color::hsla(h, s, lightness, self.alpha)
+    }
+
     /// Changes the hue of a color. Takes a color and a number of degrees
     /// (usually between -360° and 360°), and returns a color with the hue
     /// rotated along the color wheel by that amount.
@@ -1090,6 +1276,9 @@ impl Color {
          *  - hue
          *  - saturation
          *  - lightness
+         *  - with_hue
+         *  - with_saturation
+         *  - with_lightness
          *  - complement
          */
         assert_color_valid!(self);
@@ -1236,6 +1425,7 @@ mod tests {
 
     #[test]
     #[should_panic(expected = "Invalid color literal: #fffff")]
+    #[allow(unused_must_use)] // Newer versions of std mark `From::from` as `#[must_use]`
     fn invalid_color1() {
         // Wrong number of digits
         Color::from("#fffff");
@@ -1243,6 +1433,7 @@ mod tests {
 
     #[test]
     #[should_panic(expected = "Invalid color literal: #www")]
+    #[allow(unused_must_use)] // Newer versions of std mark `From::from` as `#[must_use]`
     fn invalid_color2() {
         // Invalid hex character
         Color::from("#www");
@@ -2287,6 +2478,29 @@ mod tests {
         let _ = c.lighten(0.5);
     }
 
+    #[test]
+    fn hsv_matches_rgb() {
+        let cases = [
+            ((0.0, 1.0, 1.0), (255.0, 0.0, 0.0)),
+            ((60.0, 1.0, 1.0), (255.0, 255.0, 0.0)),
+            ((180.0, 1.0, 0.5), (0.0, 128.0, 128.0)),
+            ((240.0, 0.5, 1.0), (128.0, 128.0, 255.0)),
+            ((0.0, 0.0, 0.5), (128.0, 128.0, 128.0)),
+            ((360.0, 1.0, 1.0), (255.0, 0.0, 0.0)),
+        ];
+        for &((h, s, v), (r, g, b)) in &cases {
+            let color = Color::hsv(h, s, v);
+            assert_eq!(color, Color::rgb(r, g, b), "hsv({}, {}, {})", h, s, v);
+            assert!((color.value() - v).abs() < 0.01, "hsv({}, {}, {})", h, s, v);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "1.5 is not a valid value for value, values must be between 0.0 and 1.0.")]
+    fn hsv_rejects_invalid_value() {
+        let _ = Color::hsv(100.0, 0.5, 1.5);
+    }
+
     #[test]
     #[should_panic(
         expected = "Color { red: 255.0, green: 256.0, blue: 255.0, alpha: 1.0 } is not a valid Color. Please see color module documentation."
@@ -12,7 +12,10 @@ use std::fmt;
 
 use serde::{Serialize, Deserialize};
 
-use crate::{Color, Point, Speed};
+use crate::{Color, Point, Speed, Easing, Shape, FillRule, BlendMode, ResizeAnchor, CoordinateSystem, WorldCoordinates, Filter, LayerId, ImageHandle};
+use crate::renderer_server::{Minimap, Symmetry, BackgroundImage};
+use crate::DisconnectBehavior;
+use crate::event::Key;
 
 // None of the struct fields are public because we don't want to expose any
 // internal details. These types are for printing only!
@@ -20,12 +23,29 @@ use crate::{Color, Point, Speed};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Drawing {
     pub(crate) title: String,
+    pub(crate) icon: Option<ImageHandle>,
     pub(crate) background: Color,
+    pub(crate) background_image: Option<BackgroundImage>,
     pub(crate) center: Point,
+    pub(crate) zoom: f64,
     pub(crate) width: u32,
     pub(crate) height: u32,
     pub(crate) is_maximized: bool,
     pub(crate) is_fullscreen: bool,
+    pub(crate) resize_anchor: ResizeAnchor,
+    pub(crate) minimap: Option<Minimap>,
+    pub(crate) filters: Vec<Filter>,
+    pub(crate) symmetry: Option<Symmetry>,
+    pub(crate) pressure_pen_width: Option<f64>,
+    pub(crate) coordinate_system: CoordinateSystem,
+    pub(crate) world_coordinates: Option<WorldCoordinates>,
+    pub(crate) dpi: f64,
+    pub(crate) disconnect_behavior: DisconnectBehavior,
+    pub(crate) tracer: u32,
+    pub(crate) is_animated: bool,
+    pub(crate) pause_key: Option<Key>,
+    pub(crate) step_key: Option<Key>,
+    pub(crate) navigation: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,9 +53,14 @@ pub struct Turtle {
     pub(crate) position: Point,
     pub(crate) heading: DebugAngle,
     pub(crate) speed: Speed,
+    pub(crate) easing: Easing,
     pub(crate) pen: Pen,
     pub(crate) fill_color: Color,
+    pub(crate) fill_rule: FillRule,
     pub(crate) is_visible: bool,
+    pub(crate) shape: Shape,
+    pub(crate) shape_size: f64,
+    pub(crate) layer: LayerId,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -61,4 +86,6 @@ pub struct Pen {
     pub(crate) is_enabled: bool,
     pub(crate) thickness: f64,
     pub(crate) color: Color,
+    pub(crate) dash_pattern: Vec<f64>,
+    pub(crate) blend_mode: BlendMode,
 }
@@ -0,0 +1,157 @@
+//! Tools for developing drawings interactively
+//!
+//! # Unstable
+//!
+//! This module is still experimental and may change or be removed in the future.
+
+use std::fs;
+use std::thread;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::{Drawing, Turtle};
+
+/// How often the watched path is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Runs the given function every time the file or directory at the given path changes
+///
+/// This opens a window and calls `draw` right away. After that, the path is checked for changes a
+/// few times per second. Whenever it changes, the drawing is cleared, the turtle is
+/// [reset](../struct.Turtle.html#method.reset) and `draw` is called again. This allows you to
+/// keep the window open while you tweak a data file, a list of parameters, or a script that
+/// `draw` reads every time that it runs. If the path is a directory, a change to any file inside
+/// it (or inside any directory nested within it) counts.
+///
+/// Only files that are read while the program runs can be reloaded. Changes to the Rust code of
+/// your program itself require it to be recompiled, for example with `cargo watch -x run`.
+///
+/// If `draw` panics, the panic message is printed and the window stays open until the next
+/// change, so that a mistake in the watched file does not end the program. This function never
+/// returns. The program ends when the window is closed.
+///
+/// ```rust,no_run
+/// use std::fs;
+///
+/// fn main() {
+///     // Edit spiral.txt while this is running to see the drawing update
+///     turtle::dev::watch("spiral.txt", |_drawing, turtle| {
+///         let angle: f64 = fs::read_to_string("spiral.txt").unwrap().trim().parse().unwrap();
+///
+///         turtle.set_speed("instant");
+///         for i in 0..100 {
+///             turtle.forward(i as f64 * 2.0);
+///             turtle.right(angle);
+///         }
+///     });
+/// }
+/// ```
+pub fn watch<P, F>(path: P, mut draw: F) -> !
+    where P: AsRef<Path>,
+          F: FnMut(&mut Drawing, &mut Turtle),
+{
+    let path = path.as_ref();
+
+    crate::start();
+    let mut drawing = Drawing::new();
+    let mut turtle = drawing.add_turtle();
+
+    let mut last_change = last_modified(path);
+    loop {
+        // The panic message has already been printed by the panic hook
+        panic::catch_unwind(AssertUnwindSafe(|| draw(&mut drawing, &mut turtle))).unwrap_or(());
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            // Nothing handles the events while waiting, so they are discarded to avoid piling up
+            while drawing.poll_event().is_some() {}
+
+            // Editors often replace a file by deleting it and writing it again, so the path
+            // missing for a moment is not treated as a change
+            let modified = last_modified(path);
+            if modified.is_some() && modified != last_change {
+                last_change = modified;
+                break;
+            }
+        }
+
+        drawing.clear();
+        turtle.reset();
+    }
+}
+
+/// Returns the most recent modification time of the file at the given path, or of any file within
+/// it if it is a directory
+///
+/// Returns `None` if the path does not exist or its modification time is not available.
+fn last_modified(path: &Path) -> Option<SystemTime> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_dir() {
+        return metadata.modified().ok();
+    }
+
+    // The modification time of the directory itself changes when files are added or removed
+    let entries = fs::read_dir(path).ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry_modified(&entry.path()));
+    entries.chain(metadata.modified().ok()).max()
+}
+
+/// Returns the most recent modification time of an entry found within a directory
+///
+/// A symlink to a directory could point back to one of its ancestors, so only the modification
+/// time of the link itself is used for those instead of looking inside of them.
+fn entry_modified(path: &Path) -> Option<SystemTime> {
+    let metadata = fs::symlink_metadata(path).ok()?;
+    if metadata.is_dir() {
+        return last_modified(path);
+    }
+
+    if metadata.file_type().is_symlink() {
+        match fs::metadata(path) {
+            // A symlink to a file changes whenever that file does
+            Ok(target) if !target.is_dir() => return target.modified().ok(),
+            _ => {},
+        }
+    }
+
+    metadata.modified().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_latest_change_in_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let nested = dir.join("nested");
+        fs::create_dir_all(&nested).unwrap();
+
+        let file = nested.join("params.txt");
+        fs::write(&file, "90").unwrap();
+        let file_modified = last_modified(&file).unwrap();
+        assert!(last_modified(&dir).unwrap() >= file_modified);
+        assert_eq!(last_modified(&dir.join("does-not-exist")), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlinks_to_ancestors_are_not_followed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let nested = dir.join("nested");
+        fs::create_dir_all(&nested).unwrap();
+        std::os::unix::fs::symlink(dir, nested.join("loop")).unwrap();
+
+        let file = dir.join("params.txt");
+        fs::write(&file, "90").unwrap();
+        std::os::unix::fs::symlink(&file, nested.join("params-link.txt")).unwrap();
+
+        assert!(last_modified(&nested).unwrap() >= last_modified(&file).unwrap());
+        assert!(last_modified(&dir).is_some());
+    }
+}
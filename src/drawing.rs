@@ -1,8 +1,10 @@
 use std::fmt::{self, Debug};
 use std::path::Path;
 
-use crate::{Turtle, Color, Point, Size, ExportError};
+use crate::{Turtle, DrawingState, Color, Point, Distance, Size, Rect, Corner, Filter, ExportError, ExportOptions, PrintOptions, PlotterOptions, ResizeAnchor, CoordinateSystem, WorldCoordinates, Origin, DisconnectBehavior, ImportError, ImportTransform, SessionError, Vertex, Index, Primitive, PrimitiveId, ClearEffect, TextOptions, FontMetrics, ImageError, ImageHandle, BackgroundMode, LayerId};
 use crate::async_drawing::AsyncDrawing;
+#[cfg(feature = "unstable")]
+use crate::event::Key;
 use crate::sync_runtime::block_on;
 
 /// Provides access to properties of the drawing that the turtle is creating
@@ -81,6 +83,22 @@ impl Drawing {
     ///
     /// To create a new turtle in the image, use the [`add_turtle()`] method.
     ///
+    /// Calling this more than once opens more than one window. Each drawing is completely
+    /// independent: its properties, its turtles, and the events it receives only ever apply to its
+    /// own window.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    ///
+    /// let mut left = Drawing::new();
+    /// left.set_title("Left");
+    /// let mut right = Drawing::new();
+    /// right.set_title("Right");
+    ///
+    /// left.add_turtle().forward(100.0);
+    /// right.add_turtle().right(90.0);
+    /// ```
+    ///
     /// [`add_turtle()`]: struct.Drawing.html#method.add_turtle
     pub fn new() -> Drawing {
         // This needs to be called as close to the start of the program as possible. We call it
@@ -95,6 +113,115 @@ impl Drawing {
         }
     }
 
+    /// Connects to a drawing that another program has shared using [`share()`]
+    ///
+    /// No new window is opened. Instead, turtles added to the returned `Drawing` draw in the window
+    /// of the program that shared it, alongside the turtles of that program and any other programs
+    /// that have joined. Changes to the drawing itself (e.g. its background color) are also
+    /// made to that shared window.
+    ///
+    /// Returns an error if no drawing is currently being shared with the given name.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    ///
+    /// fn main() {
+    ///     // Run this program several times while the program in the `share()` example is running
+    ///     let mut drawing = Drawing::join("classroom").expect("drawing was not shared");
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     turtle.set_pen_color("blue");
+    ///     turtle.right(turtle::rand::random_range(0.0, 360.0));
+    ///     turtle.forward(200.0);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is empty or contains characters other than ASCII letters, digits, `-`,
+    /// or `_`.
+    ///
+    /// [`share()`]: struct.Drawing.html#method.share
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn join(name: &str) -> Result<Drawing, crate::ShareError> {
+        Ok(Drawing {
+            drawing: block_on(AsyncDrawing::join(name))?,
+            turtles: 0,
+        })
+    }
+
+    /// Connects to a drawing on another computer that has been shared using [`share_tcp()`]
+    ///
+    /// This works just like [`join()`], except that the window can be on any computer that this
+    /// one can reach over the network. For example, a program running on a Raspberry Pi could
+    /// draw in a window on a laptop. Both programs must be built with the same version of this
+    /// crate.
+    ///
+    /// Requests that would read or write files on the computer running the window (e.g.
+    /// [`save_svg()`]) are refused by that computer. Those methods return an error when called on
+    /// the returned `Drawing`.
+    ///
+    /// Returns an error if the connection could not be made.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::join_tcp("192.168.1.20:7777").expect("unable to connect");
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     turtle.forward(100.0);
+    /// }
+    /// ```
+    ///
+    /// [`share_tcp()`]: struct.Drawing.html#method.share_tcp
+    /// [`join()`]: struct.Drawing.html#method.join
+    /// [`save_svg()`]: struct.Drawing.html#method.save_svg
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn join_tcp<A: std::net::ToSocketAddrs>(addr: A) -> Result<Drawing, crate::ShareError> {
+        Ok(Drawing {
+            drawing: block_on(AsyncDrawing::join_tcp(addr))?,
+            turtles: 0,
+        })
+    }
+
+    /// Creates a new drawing that programs on other computers can draw in right away by connecting
+    /// to the given address using [`join_tcp()`]
+    ///
+    /// This is the same as calling [`new()`] and then [`share_tcp()`], except that the window
+    /// accepts connections from the moment it opens. Since the address that is listened on is not
+    /// returned, a specific port should be given instead of port `0`.
+    ///
+    /// Returns an error if the address cannot be listened on.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::new_shared_tcp("0.0.0.0:7777").expect("unable to share drawing");
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     turtle.forward(100.0);
+    /// }
+    /// ```
+    ///
+    /// [`join_tcp()`]: struct.Drawing.html#method.join_tcp
+    /// [`new()`]: struct.Drawing.html#method.new
+    /// [`share_tcp()`]: struct.Drawing.html#method.share_tcp
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn new_shared_tcp<A: std::net::ToSocketAddrs>(addr: A) -> std::io::Result<Drawing> {
+        // We can't call start() within another tokio runtime, so we have to do it before block_on
+        crate::start();
+
+        Ok(Drawing {
+            drawing: block_on(AsyncDrawing::new_shared_tcp(addr))?,
+            turtles: 0,
+        })
+    }
+
     /// Adds a new turtle to this drawing and returns it
     ///
     /// The newly created turtle will appear at center of the drawing.
@@ -167,6 +294,55 @@ impl Drawing {
         self.drawing.set_title(title)
     }
 
+    /// Returns the image used as the icon of the window, or `None` if the window has the default
+    /// icon
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let drawing = Drawing::new();
+    /// assert_eq!(drawing.icon(), None);
+    /// ```
+    pub fn icon(&self) -> Option<ImageHandle> {
+        block_on(self.drawing.icon())
+    }
+
+    /// Sets the icon of the window to the given image
+    ///
+    /// The image can be loaded from a file with [`load_image()`](#method.load_image) or embedded
+    /// in the program with [`load_image_from_bytes()`](#method.load_image_from_bytes). The icon
+    /// can be changed at any time. Use [`reset_icon()`](#method.reset_icon) to go back to the
+    /// default icon. The icon stays the same if the image was loaded into a different drawing.
+    ///
+    /// Not every platform shows window icons. Icons are usually shown very small, so simple
+    /// images work best.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ImageError};
+    ///
+    /// fn main() -> Result<(), ImageError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let icon = drawing.load_image_from_bytes(include_bytes!("../docs/assets/images/turtle-logo-512.png"))?;
+    ///     drawing.set_icon(icon);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_icon(&mut self, icon: ImageHandle) {
+        self.drawing.set_icon(icon)
+    }
+
+    /// Returns the window to the default icon
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// # let mut drawing = Drawing::new();
+    /// drawing.reset_icon();
+    /// assert_eq!(drawing.icon(), None);
+    /// ```
+    pub fn reset_icon(&mut self) {
+        self.drawing.reset_icon()
+    }
+
     /// Returns the color of the background.
     ///
     /// ```rust
@@ -206,6 +382,46 @@ impl Drawing {
         self.drawing.set_background_color(color)
     }
 
+    /// Loads the PNG image at the location specified by `path` and draws it behind everything
+    /// else in the drawing, arranged as specified by `mode`.
+    ///
+    /// The background image is drawn on top of the background color, so the color shows through
+    /// any transparent parts of the image and anywhere the image does not cover. Unlike the
+    /// drawing itself, the background image stays in place when the view is moved with
+    /// [`set_center()`](#method.set_center) or [`set_zoom()`](#method.set_zoom). The background
+    /// image is included when the drawing is saved as an SVG or PNG file, arranged within the
+    /// saved area instead of the window.
+    ///
+    /// Setting a new background image replaces the previous one. Use
+    /// [`clear_background_image()`](#method.clear_background_image) to remove it.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ImageError, BackgroundMode};
+    ///
+    /// fn main() -> Result<(), ImageError> {
+    ///     let mut drawing = Drawing::new();
+    ///     drawing.set_background_image("grass.png", BackgroundMode::Tile)?;
+    ///
+    ///     let mut turtle = drawing.add_turtle();
+    ///     turtle.forward(100.0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Returns an error if the image could not be loaded. See
+    /// [`load_image()`](#method.load_image) for more information.
+    pub fn set_background_image<P: AsRef<Path>>(&mut self, path: P, mode: BackgroundMode) -> Result<(), ImageError> {
+        block_on(self.drawing.set_background_image(path, mode))
+    }
+
+    /// Removes the background image set with
+    /// [`set_background_image()`](#method.set_background_image), leaving only the background
+    /// color.
+    pub fn clear_background_image(&mut self) {
+        self.drawing.clear_background_image()
+    }
+
     /// Returns the center of the drawing
     ///
     /// ```rust
@@ -275,8 +491,118 @@ impl Drawing {
         self.drawing.reset_center()
     }
 
+    /// Returns how far the view into the drawing is zoomed in
+    ///
+    /// See [`set_zoom()`](#method.set_zoom) for more information.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// assert_eq!(drawing.zoom(), 1.0);
+    /// drawing.set_zoom(2.5);
+    /// assert_eq!(drawing.zoom(), 2.5);
+    /// ```
+    pub fn zoom(&self) -> f64 {
+        block_on(self.drawing.zoom())
+    }
+
+    /// Zooms the view into the drawing in or out by the given factor, keeping the
+    /// [`center()`](#method.center) of the drawing in the center of the window
+    ///
+    /// A zoom of 2.0 makes everything in the window twice as large and a zoom of 0.5 makes it half
+    /// as large. The default is 1.0. Together with [`set_center()`](#method.set_center), which
+    /// moves the view around the drawing, this works like a camera looking at the drawing.
+    ///
+    /// Zooming only changes how the drawing appears in the window. Nothing that has been drawn
+    /// changes, and turtle positions, distances, and the coordinates of mouse events are the same
+    /// at any zoom. Exporting the drawing (e.g. with [`save_svg()`](#method.save_svg)) also ignores
+    /// the zoom.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///     turtle.forward(100.0);
+    ///
+    ///     // Look closely at the end of the line
+    ///     drawing.set_center(turtle.position());
+    ///     drawing.set_zoom(4.0);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the zoom is not positive.
+    pub fn set_zoom(&mut self, zoom: f64) {
+        self.drawing.set_zoom(zoom)
+    }
+
+    /// Resets the zoom of the drawing back to 1.0
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// drawing.set_zoom(0.25);
+    /// drawing.reset_zoom();
+    /// assert_eq!(drawing.zoom(), 1.0);
+    /// ```
+    pub fn reset_zoom(&mut self) {
+        self.drawing.reset_zoom()
+    }
+
+    /// Returns true if the view into the drawing can be zoomed and moved around with the mouse
+    ///
+    /// See [`enable_navigation()`](#method.enable_navigation) for more information.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// assert!(!drawing.is_navigation_enabled());
+    /// drawing.enable_navigation(true);
+    /// assert!(drawing.is_navigation_enabled());
+    /// ```
+    pub fn is_navigation_enabled(&self) -> bool {
+        block_on(self.drawing.is_navigation_enabled())
+    }
+
+    /// Allows the view into the drawing to be zoomed and moved around with the mouse
+    ///
+    /// While navigation is enabled, scrolling in the window zooms in and out around the mouse
+    /// cursor and dragging with the middle mouse button moves the view around the drawing. This
+    /// is handled by the window itself, so it works even while the turtle is busy drawing. It is
+    /// the same as calling [`set_zoom()`](#method.set_zoom) and
+    /// [`set_center()`](#method.set_center), so those methods return the view that was chosen
+    /// with the mouse. Navigation is disabled by default.
+    ///
+    /// Scrolling and mouse button events are still sent to the program as usual.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::new();
+    ///     drawing.enable_navigation(true);
+    ///
+    ///     let mut turtle = drawing.add_turtle();
+    ///     // Zoom in to see the details of the spiral while it is being drawn
+    ///     for i in 0..720 {
+    ///         turtle.forward(0.5 + i as f64 / 360.0);
+    ///         turtle.right(2.0);
+    ///     }
+    /// }
+    /// ```
+    pub fn enable_navigation(&mut self, enabled: bool) {
+        self.drawing.enable_navigation(enabled)
+    }
+
     /// Returns the size of the drawing
     ///
+    /// This is always the current size of the window, including any changes made by the user
+    /// resizing it. Use [`Event::WindowResized`](event/enum.Event.html#variant.WindowResized) to
+    /// find out when that happens.
+    ///
     /// ```rust
     /// # use turtle::*;
     /// # let mut drawing = Drawing::new();
@@ -346,6 +672,31 @@ impl Drawing {
         self.drawing.set_size(size)
     }
 
+    /// Sets the size of the drawing to the given physical size in millimeters.
+    ///
+    /// The size is converted to logical pixels using the [DPI](#method.set_dpi) of the drawing,
+    /// so make sure to set that first. To use inches instead, multiply each inch by `25.4`.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// // An A4 page at 96 DPI (the default)
+    /// drawing.set_size_mm(210.0, 297.0);
+    /// assert_eq!(drawing.size(), Size {width: 794, height: 1123});
+    ///
+    /// // A 4 by 6 inch card at 300 DPI
+    /// drawing.set_dpi(300.0);
+    /// drawing.set_size_mm(4.0 * 25.4, 6.0 * 25.4);
+    /// assert_eq!(drawing.size(), Size {width: 1200, height: 1800});
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width or height is not positive.
+    pub fn set_size_mm(&mut self, width: f64, height: f64) {
+        block_on(self.drawing.set_size_mm(width, height))
+    }
+
     /// Resets the size of the drawing back to its initial value
     ///
     /// ```rust
@@ -363,313 +714,3328 @@ impl Drawing {
         self.drawing.reset_size()
     }
 
-    /// Returns true if the drawing is currently maximized.
+    /// Returns which part of the window the drawing stays fixed to when the window is resized
     ///
-    /// Note: Even if you set the drawing to the width and height of the current display, it won't
-    /// be maximized unless [`maximize()`](struct.Drawing.html#method.maximize) is called.
+    /// See [`set_resize_anchor()`](struct.Drawing.html#method.set_resize_anchor) for more
+    /// information.
     ///
     /// ```rust
     /// # use turtle::*;
-    /// let mut drawing = Drawing::new();
-    /// assert_eq!(drawing.is_maximized(), false);
-    ///
-    /// drawing.maximize();
-    /// assert_eq!(drawing.is_maximized(), true);
-    ///
-    /// drawing.unmaximize();
-    /// assert_eq!(drawing.is_maximized(), false);
-    ///
-    /// // Calling the same method again doesn't change the result
-    /// drawing.unmaximize();
-    /// assert_eq!(drawing.is_maximized(), false);
+    /// let drawing = Drawing::new();
+    /// assert_eq!(drawing.resize_anchor(), ResizeAnchor::Center);
     /// ```
-    ///
-    /// # Unstable
-    ///
-    /// **This method is currently unstable and unreliable.**
-    /// ([GitHub Issue](https://github.com/sunjay/turtle/issues/49))
-    ///
-    /// Unfortunately, we cannot currently detect when the window is maximized using the maximize
-    /// button on the window. This method is reliable until that button is pressed. Since there is
-    /// no way to tell when that is, treat the value returned from this method as unreliable and
-    /// potentially inaccurate.
-    #[cfg(feature = "unstable")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
-    pub fn is_maximized(&self) -> bool {
-        block_on(self.drawing.is_maximized())
+    pub fn resize_anchor(&self) -> ResizeAnchor {
+        block_on(self.drawing.resize_anchor())
     }
 
-    /// Maximizes the size of the drawing so that it takes up the entire display.
+    /// Sets which part of the window the drawing stays fixed to when the window is resized
     ///
-    /// If the drawing is already maximized, this method does nothing.
+    /// By default, the drawing stays centered in the window ([`ResizeAnchor::Center`]). No matter
+    /// how the window is resized or maximized, the [`center()`] of the drawing will always be in
+    /// the middle of the window.
+    ///
+    /// With [`ResizeAnchor::TopLeft`], the drawing stays in the same place relative to the top-left
+    /// corner of the window instead. Making the window bigger reveals more of the drawing to the
+    /// right and bottom without moving anything that was already visible. To keep it in place,
+    /// the [`center()`] of the drawing is updated whenever the size of the window changes. This
+    /// applies both when the window is resized by the user and when the size is changed with
+    /// [`set_size()`].
     ///
     /// ```rust
     /// # use turtle::*;
-    /// # let mut drawing = Drawing::new();
-    /// drawing.maximize();
-    /// assert_eq!(drawing.is_maximized(), true);
-    /// // Calling this method again does nothing
-    /// drawing.maximize();
-    /// assert_eq!(drawing.is_maximized(), true);
+    /// let mut drawing = Drawing::new();
+    /// drawing.set_size([800, 600]);
+    /// drawing.set_resize_anchor(ResizeAnchor::TopLeft);
+    ///
+    /// // The window grew by 200 pixels on the right and 100 pixels on the bottom
+    /// drawing.set_size([1000, 700]);
+    /// assert_eq!(drawing.center(), Point {x: 100.0, y: -50.0});
     /// ```
-    pub fn maximize(&mut self) {
-        self.drawing.maximize()
+    ///
+    /// [`ResizeAnchor::Center`]: enum.ResizeAnchor.html#variant.Center
+    /// [`ResizeAnchor::TopLeft`]: enum.ResizeAnchor.html#variant.TopLeft
+    /// [`center()`]: struct.Drawing.html#method.center
+    /// [`set_size()`]: struct.Drawing.html#method.set_size
+    pub fn set_resize_anchor(&mut self, anchor: ResizeAnchor) {
+        self.drawing.set_resize_anchor(anchor)
     }
 
-    /// Returns the size of the drawing to its value before it was maximized
+    /// Returns the smallest rectangle that contains everything that has been drawn so far, or
+    /// `None` if nothing has been drawn yet.
     ///
-    /// If the drawing is already unmaximized, this method does nothing.
+    /// The rectangle uses the same coordinates as the turtle and includes the thickness of every
+    /// line. Like [`fit_to_content()`](#method.fit_to_content), the turtles themselves and
+    /// anything in a hidden layer are not included. Use this to move the view to the drawing
+    /// yourself or to pick a size for an export.
     ///
     /// ```rust
-    /// # use turtle::*;
-    /// # let mut drawing = Drawing::new();
-    /// drawing.maximize();
-    /// assert_eq!(drawing.is_maximized(), true);
-    /// drawing.unmaximize();
-    /// assert_eq!(drawing.is_maximized(), false);
-    /// // Calling this again does nothing because the drawing is already unmaximized
-    /// drawing.unmaximize();
-    /// assert_eq!(drawing.is_maximized(), false);
+    /// use turtle::{Drawing, Rect};
+    ///
+    /// let mut drawing = Drawing::new();
+    /// assert_eq!(drawing.bounds(), None);
+    ///
+    /// drawing.draw_circle([100.0, 0.0], 50.0, "red");
+    /// assert_eq!(drawing.bounds(), Some(Rect::from_points([50.0, -50.0], [150.0, 50.0])));
     /// ```
-    pub fn unmaximize(&mut self) {
-        self.drawing.unmaximize()
+    ///
+    /// See also [`Turtle::drawing_bounds()`](struct.Turtle.html#method.drawing_bounds) to only
+    /// include what was drawn by a single turtle.
+    pub fn bounds(&self) -> Option<Rect> {
+        block_on(self.drawing.bounds())
     }
 
-    /// Returns true if the drawing is currently full screen.
+    /// Moves the center of the drawing and resizes the window so that everything that has been
+    /// drawn so far is visible.
+    ///
+    /// The `padding` is the amount of space (in pixels) to leave between the edge of the window and
+    /// the drawing on every side. If nothing has been drawn yet, or if `padding` is `NaN` or
+    /// infinite, this method does nothing.
+    ///
+    /// This is perfect for drawings (like fractals) where it is hard to know ahead of time how big
+    /// the drawing will end up being. Note that the turtles themselves are not considered to be
+    /// part of the drawing, so a turtle may still end up outside of the window.
+    ///
+    /// Just like with [`set_size()`](struct.Drawing.html#method.set_size), the window may not
+    /// actually become the requested size if the drawing is larger than your screen.
+    ///
+    /// # Example
     ///
     /// ```rust
-    /// # use turtle::*;
-    /// let mut drawing = Drawing::new();
-    /// assert_eq!(drawing.is_fullscreen(), false);
+    /// use turtle::{Drawing, Point, Size};
     ///
-    /// drawing.enter_fullscreen();
-    /// assert_eq!(drawing.is_fullscreen(), true);
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    /// turtle.set_pen_size(2.0);
     ///
-    /// drawing.exit_fullscreen();
-    /// assert_eq!(drawing.is_fullscreen(), false);
+    /// turtle.forward(300.0);
+    /// turtle.right(90.0);
+    /// turtle.forward(500.0);
     ///
-    /// // Calling the same method again doesn't change the result
-    /// drawing.exit_fullscreen();
-    /// assert_eq!(drawing.is_fullscreen(), false);
+    /// drawing.fit_to_content(20.0);
+    /// assert_eq!(drawing.center(), Point {x: 250.0, y: 150.0});
+    /// assert_eq!(drawing.size(), Size {width: 542, height: 342});
     /// ```
-    pub fn is_fullscreen(&self) -> bool {
-        block_on(self.drawing.is_fullscreen())
+    pub fn fit_to_content(&mut self, padding: f64) {
+        block_on(self.drawing.fit_to_content(padding))
     }
 
-    /// Makes the drawing take up the entire screen hiding everything else that would have been
-    /// shown on screen otherwise.
+    /// Returns true if none of the turtles in the drawing are currently playing an animation.
     ///
-    /// If the drawing is already fullscreen, this method does nothing.
+    /// When turtles are being moved on other threads, this can be used to avoid generating new
+    /// commands faster than they can be drawn, or to wait until the window has caught up before
+    /// doing something else.
     ///
-    /// ```rust
-    /// # use turtle::*;
-    /// # let mut drawing = Drawing::new();
-    /// drawing.enter_fullscreen();
-    /// assert_eq!(drawing.is_fullscreen(), true);
-    /// // Calling this method again does nothing
-    /// drawing.enter_fullscreen();
-    /// assert_eq!(drawing.is_fullscreen(), true);
+    /// ```rust,no_run
+    /// use std::thread;
+    /// use turtle::Drawing;
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     thread::spawn(move || {
+    ///         for _ in 0..36 {
+    ///             turtle.forward(100.0);
+    ///             turtle.right(170.0);
+    ///         }
+    ///     });
+    ///
+    ///     // Show a message in the title whenever the turtle stops moving
+    ///     loop {
+    ///         let title = if drawing.is_idle() { "Idle" } else { "Drawing..." };
+    ///         drawing.set_title(title);
+    ///         thread::sleep(std::time::Duration::from_millis(100));
+    ///     }
+    /// }
     /// ```
-    pub fn enter_fullscreen(&mut self) {
-        self.drawing.enter_fullscreen()
+    ///
+    /// Use [`Turtle::pending_animations()`] to check a single turtle.
+    ///
+    /// [`Turtle::pending_animations()`]: struct.Turtle.html#method.pending_animations
+    pub fn is_idle(&self) -> bool {
+        block_on(self.drawing.is_idle())
     }
 
-    /// Returns the size of the drawing to its value before it became fullscreen.
+    /// Shows a small overview of the entire drawing in the given corner of the window.
     ///
-    /// If the drawing is already not fullscreen, this method does nothing.
+    /// The minimap is a scaled-down copy of everything that has been drawn, along with a
+    /// rectangle that shows which part of the drawing is currently visible in the window. This is
+    /// useful when you have moved the [`center()`] of the drawing around a large piece of art and
+    /// want to keep track of where you are.
+    ///
+    /// The `size` is the width and height of the minimap in pixels. It can be given in any form
+    /// accepted by [`set_size()`]. Calling this method again replaces the previous minimap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width or height of `size` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, Corner};
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///     drawing.show_minimap(Corner::BottomRight, [200, 150]);
+    ///
+    ///     turtle.forward(1000.0);
+    ///     // Follow the turtle as it moves off the screen
+    ///     drawing.set_center(turtle.position());
+    /// }
+    /// ```
     ///
+    /// [`center()`]: struct.Drawing.html#method.center
+    /// [`set_size()`]: struct.Drawing.html#method.set_size
+    pub fn show_minimap<S: Into<Size>>(&mut self, corner: Corner, size: S) {
+        self.drawing.show_minimap(corner, size)
+    }
+
+    /// Hides the minimap if it was shown with
+    /// [`show_minimap()`](struct.Drawing.html#method.show_minimap).
     ///
     /// ```rust
     /// # use turtle::*;
-    /// # let mut drawing = Drawing::new();
-    /// drawing.enter_fullscreen();
-    /// assert_eq!(drawing.is_fullscreen(), true);
-    /// drawing.exit_fullscreen();
-    /// assert_eq!(drawing.is_fullscreen(), false);
-    /// // Calling this again does nothing because the drawing is already not fullscreen
-    /// drawing.exit_fullscreen();
-    /// assert_eq!(drawing.is_fullscreen(), false);
+    /// let mut drawing = Drawing::new();
+    /// drawing.show_minimap(Corner::TopLeft, [160, 120]);
+    /// drawing.hide_minimap();
     /// ```
-    pub fn exit_fullscreen(&mut self) {
-        self.drawing.exit_fullscreen()
+    pub fn hide_minimap(&mut self) {
+        self.drawing.hide_minimap()
     }
 
-    //TODO(#16): This method is hidden because it hasn't been properly documented
-    #[doc(hidden)]
-    #[cfg(feature = "unstable")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
-    pub fn clear(&mut self) {
-        self.drawing.clear();
+    /// Returns the filters that are currently applied to the drawing, in the order that they are
+    /// applied.
+    ///
+    /// See [`apply_filter()`](#method.apply_filter) for more information.
+    pub fn filters(&self) -> Vec<Filter> {
+        block_on(self.drawing.filters())
     }
 
-    /// Returns the next event (if any). Returns `None` if there are no events to be processed at
-    /// the current moment. This **does not** mean that there will never be events later on as the
-    /// application continues to run.
+    /// Adds a [`Filter`] that changes how the drawing looks in the window.
     ///
-    /// See the [`Event` enum](event/enum.Event.html) for the complete list of events that you can
-    /// handle in your applications.
+    /// Filters are applied in the order they were added, after the drawing and its background
+    /// have been drawn. The turtles and the minimap are drawn on top and are not affected by
+    /// filters (except that the minimap uses the same filtered colors as the drawing). Filters
+    /// are not applied when saving the drawing with methods like [`save_svg()`](#method.save_svg).
     ///
-    /// # Unstable
+    /// ```rust,no_run
+    /// use turtle::{Drawing, Filter};
     ///
-    /// There are still many unanswered questions about the design of the events API in the turtle
-    /// crate. This method may change or be completely removed in the future. There will definitely
-    /// be *some* events API in the future, but it may end up looking different than it does today.
+    /// fn main() {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///     drawing.set_background_color("light blue");
+    ///     turtle.set_pen_size(10.0);
+    ///     turtle.set_pen_color("orange");
+    ///     turtle.forward(200.0);
+    ///
+    ///     // Higher contrast colors that may be easier to see
+    ///     drawing.apply_filter(Filter::Grayscale);
+    ///     drawing.apply_filter(Filter::Invert);
+    ///
+    ///     // Go back to normal
+    ///     drawing.clear_filters();
+    ///     drawing.apply_filter(Filter::Blur(4.0));
+    /// }
+    /// ```
     ///
-    /// # Example
+    /// # Panics
     ///
-    /// To use this advanced method, you need to create what is known as an "event loop". An "event
-    /// loop" is any loop that handles the events generated by the application. The reason that it
-    /// is important to create a loop like this is because events in Turtle are "polled". That
-    /// means that every time an event happens, it is placed in a queue (a list) until you ask to
-    /// look at it. If you do not check for events continuously, there is a chance that the events
-    /// you ask for from `poll_event()` will be outdated.
+    /// Panics if the radius of a blur is negative or not finite.
     ///
-    /// Even if you do not use every kind of event, you should aim to poll events using this method
-    /// until there are none left to poll. If you do not poll events for a significant amount of
-    /// time during your application, favor the events that come later as you poll since those will
-    /// be the most recent. This can happen if you run many animations between loop iterations.
+    /// [`Filter`]: enum.Filter.html
+    pub fn apply_filter(&mut self, filter: Filter) {
+        block_on(self.drawing.apply_filter(filter))
+    }
+
+    /// Removes every filter added with [`apply_filter()`](#method.apply_filter).
+    pub fn clear_filters(&mut self) {
+        self.drawing.clear_filters()
+    }
+
+    /// Replicates everything that is drawn around the origin, making it easy to create
+    /// kaleidoscope and mandala patterns with ordinary turtle code.
     ///
-    /// See the [`examples/`](https://github.com/sunjay/turtle/raw/master/examples) directory in
-    /// the source code of this library for more examples of how to use events.
+    /// The drawing is copied `n_axes` times, each copy rotated by another `360 / n_axes` degrees
+    /// around the point `(0, 0)`. If `mirror` is true, every one of those copies is also reflected
+    /// across the vertical line through the origin. For example, `set_symmetry(6, true)` turns
+    /// each line into 12 lines, like the arms of a snowflake.
     ///
-    /// The following is an example of a basic event loop. Notice that it uses two loops. One to
-    /// move the turtle continuously, and another to handle all the events available at a given
-    /// moment. If it suits your purposes, you may also just use a single loop to handle events
-    /// and move the turtle from within that loop. This example is of a more complex case where
-    /// it really matters that the most recent information is taken into consideration before any
-    /// further movements take place.
+    /// Only the drawing itself is copied, not the turtles. The copies are drawn by the renderer,
+    /// so they are shown in the window and included in [`save_svg()`], but they are not part of
+    /// the shapes returned by [`export_mesh()`]. Calling this method again replaces the previous
+    /// symmetry, including for everything that has already been drawn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n_axes` is zero.
+    ///
+    /// # Example
     ///
     /// ```rust,no_run
-    /// use turtle::{Drawing, Event, event::{Key, PressedState}};
+    /// use turtle::Drawing;
     ///
     /// fn main() {
     ///     let mut drawing = Drawing::new();
     ///     let mut turtle = drawing.add_turtle();
+    ///     drawing.set_symmetry(8, true);
     ///
-    ///     loop {
-    ///         turtle.forward(1.0);
-    ///
-    ///         while let Some(event) = drawing.poll_event() {
-    ///             use Key::{LeftArrow, RightArrow};
-    ///             match event {
-    ///                 Event::Key(key, PressedState::Pressed) => match key {
-    ///                     LeftArrow => {
-    ///                         turtle.set_speed(8);
-    ///                         for _ in 0..20 {
-    ///                             turtle.forward(1.0);
-    ///                             turtle.left(4.5);
-    ///                         }
-    ///                         turtle.set_speed(4);
-    ///                     },
-    ///                     RightArrow => {
-    ///                         turtle.set_speed(8);
-    ///                         for _ in 0..20 {
-    ///                             turtle.forward(1.0);
-    ///                             turtle.right(4.5);
-    ///                         }
-    ///                         turtle.set_speed(4);
-    ///                     },
-    ///                     _ => {},
-    ///                 },
-    ///                 _ => {},
-    ///             }
-    ///         }
+    ///     // A single wavy line becomes a flower with 16 petals
+    ///     for _ in 0..12 {
+    ///         turtle.forward(15.0);
+    ///         turtle.right(10.0);
     ///     }
     /// }
     /// ```
-    #[cfg(feature = "unstable")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
-    pub fn poll_event(&mut self) -> Option<crate::Event> {
-        block_on(self.drawing.poll_event())
+    ///
+    /// [`save_svg()`]: struct.Drawing.html#method.save_svg
+    /// [`export_mesh()`]: struct.Drawing.html#method.export_mesh
+    pub fn set_symmetry(&mut self, n_axes: u32, mirror: bool) {
+        self.drawing.set_symmetry(n_axes, mirror)
     }
 
-    /// Saves the current drawings in SVG format at the location specified by `path`.
+    /// Stops replicating the drawing if a symmetry was set with
+    /// [`set_symmetry()`](struct.Drawing.html#method.set_symmetry).
     ///
-    /// ```rust,no_run
-    /// use turtle::{Drawing, Turtle, Color, ExportError};
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// drawing.set_symmetry(4, false);
+    /// drawing.clear_symmetry();
+    /// ```
+    pub fn clear_symmetry(&mut self) {
+        self.drawing.clear_symmetry()
+    }
+
+    /// Returns the pen thickness used for the hardest possible touch if pressure is mapped to pen
+    /// width, or `None` if it isn't.
     ///
-    /// fn main() -> Result<(), ExportError> {
-    ///     let mut drawing = Drawing::new();
-    ///     let mut turtle = drawing.add_turtle();
-    ///     drawing.set_background_color("pink");
+    /// See [`set_pressure_pen_width()`](struct.Drawing.html#method.set_pressure_pen_width) for
+    /// more information.
     ///
-    ///     for i in 0..36 {
-    ///         let base_color: Color = if i % 2 == 0 {
-    ///             "red".into()
-    ///         } else {
-    ///             "white".into()
-    ///         };
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// assert_eq!(drawing.pressure_pen_width(), None);
+    /// drawing.set_pressure_pen_width(8.0);
+    /// assert_eq!(drawing.pressure_pen_width(), Some(8.0));
+    /// ```
+    pub fn pressure_pen_width(&self) -> Option<f64> {
+        block_on(self.drawing.pressure_pen_width())
+    }
+
+    /// Makes the pen thickness of every turtle follow how hard a stylus or finger presses on the
+    /// window, turning the window into a simple sketching surface.
     ///
-    ///         turtle.set_fill_color(base_color.with_alpha(1.0 - i as f64 / 54.0));
-    ///         turtle.begin_fill();
-    ///         square(&mut turtle);
-    ///         turtle.end_fill();
-    ///         turtle.right(10.0);
-    ///    }
+    /// Whenever a touch reports its pressure, the pen thickness is set to that pressure (from 0.0
+    /// to 1.0) multiplied by `max_width`. Pressure is only reported by some platforms (e.g.
+    /// Windows and iOS) and devices. Touches that don't report pressure leave the pen thickness
+    /// unchanged. The pressure and tilt of each touch are also available through the touch events
+    /// returned by [`poll_event()`].
     ///
-    ///    turtle.hide();
-    ///    drawing.save_svg("squares.svg")?;
+    /// # Panics
     ///
-    ///    Ok(())
-    /// }
+    /// Panics if `max_width` is not greater than zero.
     ///
-    /// fn square(turtle: &mut Turtle) {
-    ///     for _ in 0..4 {
-    ///         turtle.forward(200.0);
-    ///         turtle.right(90.0);
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///     // Light touches make the turtle draw thin lines and the firmest touches make it draw
+    ///     // lines that are 10 pixels thick
+    ///     drawing.set_pressure_pen_width(10.0);
+    ///
+    ///     for _ in 0..360 {
+    ///         turtle.forward(2.0);
+    ///         turtle.right(1.0);
     ///     }
     /// }
     /// ```
     ///
-    /// This will produce the following image in the current directory under the name `squares.svg`:
+    /// [`poll_event()`]: struct.Drawing.html#method.poll_event
+    pub fn set_pressure_pen_width(&mut self, max_width: f64) {
+        self.drawing.set_pressure_pen_width(max_width)
+    }
+
+    /// Stops changing the pen thickness based on touch pressure.
     ///
-    /// ![squares](https://raw.githubusercontent.com/sunjay/turtle/master/docs/assets/images/docs/squares.svg?sanitize=true)
-    pub fn save_svg<P: AsRef<Path>>(&self, path: P) -> Result<(), ExportError> {
-        block_on(self.drawing.save_svg(path))
+    /// The turtles keep whichever pen thickness they had last.
+    pub fn reset_pressure_pen_width(&mut self) {
+        self.drawing.reset_pressure_pen_width()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Returns the coordinate system currently used by the drawing.
+    ///
+    /// See [`set_coordinate_system()`](#method.set_coordinate_system) for more information.
+    pub fn coordinate_system(&self) -> CoordinateSystem {
+        block_on(self.drawing.coordinate_system())
+    }
 
-    #[test]
-    #[should_panic(expected = "Invalid color: Color { red: NaN, green: 0.0, blue: 0.0, alpha: 0.0 }. See the color module documentation for more information.")]
-    fn rejects_invalid_background_color() {
-        let mut drawing = Drawing::new();
-        drawing.set_background_color(Color {
-            red: ::std::f64::NAN,
-            green: 0.0,
-            blue: 0.0,
-            alpha: 0.0,
-        });
+    /// Changes where the origin of the drawing is and which direction the y axis points in.
+    ///
+    /// By default, the origin is in the center of the window and y increases towards the top of
+    /// the window. Programs written for screen coordinates (where the origin is in the top-left
+    /// corner and y increases downwards) can use `set_coordinate_system(Origin::TopLeft, false)`
+    /// instead.
+    ///
+    /// The coordinate system applies to everything that uses a position or a heading: turtle
+    /// positions and headings (including [`go_to()`] and [`set_heading()`]), the drawing
+    /// [`center()`](#method.center), mouse and touch events, the bounds of the drawing, and
+    /// rectangles passed to [`save_svg_with()`](#method.save_svg_with). When the y axis points
+    /// down, headings are measured clockwise, so a heading of 90 degrees faces the bottom of the
+    /// window. Turning with [`left()`] and [`right()`] still turns the same way on the screen.
+    ///
+    /// The corners of the window are measured as if the drawing had not been moved with
+    /// [`set_center()`](#method.set_center). Changing the size of the drawing moves the
+    /// `TopLeft` and `BottomLeft` origins along with the corners of the window, so it is best to
+    /// set the size of the drawing first. Only the coordinates change, so anything that has
+    /// already been drawn stays where it is. Changing the coordinate system also stops using any
+    /// [world coordinates](#method.set_world_coordinates).
+    ///
+    /// ```rust
+    /// use turtle::{Drawing, Origin, Point};
+    ///
+    /// let mut drawing = Drawing::new();
+    /// drawing.set_size((400, 300));
+    /// drawing.set_coordinate_system(Origin::TopLeft, false);
+    ///
+    /// let mut turtle = drawing.add_turtle();
+    /// // The turtle starts in the center of the window...
+    /// assert_eq!(turtle.position(), Point {x: 200.0, y: 150.0});
+    /// // ...facing up, which is now a heading of 270 degrees
+    /// assert_eq!(turtle.heading().round(), 270.0);
+    ///
+    /// turtle.go_to((0.0, 0.0));
+    /// turtle.set_heading(0.0);
+    /// turtle.right(90.0);
+    /// // Turning right from east faces down, which is now a heading of 90 degrees
+    /// assert_eq!(turtle.heading().round(), 90.0);
+    /// ```
+    ///
+    /// [`go_to()`]: struct.Turtle.html#method.go_to
+    /// [`set_heading()`]: struct.Turtle.html#method.set_heading
+    /// [`left()`]: struct.Turtle.html#method.left
+    /// [`right()`]: struct.Turtle.html#method.right
+    pub fn set_coordinate_system(&mut self, origin: Origin, y_up: bool) {
+        self.drawing.set_coordinate_system(origin, y_up)
     }
 
-    #[test]
-    #[should_panic(expected = "The size of the drawing must be non-zero")]
-    fn rejects_size_zero() {
-        let mut drawing = Drawing::new();
+    /// Returns the world coordinates set with
+    /// [`set_world_coordinates()`](#method.set_world_coordinates), if any.
+    pub fn world_coordinates(&self) -> Option<WorldCoordinates> {
+        block_on(self.drawing.world_coordinates())
+    }
 
-        drawing.set_size([0, 0]);
+    /// Changes the coordinates of the window so that (`llx`, `lly`) is its bottom-left corner and
+    /// (`urx`, `ury`) is its top-right corner.
+    ///
+    /// This makes it possible to draw in units that do not match pixels, like a graph that goes
+    /// from 0 to 1 in both directions. The axes can have different scales, and an axis is flipped
+    /// if its lower coordinate is greater than its upper one (e.g. `lly > ury` makes y increase
+    /// towards the bottom of the window). Use [`reset_world_coordinates()`] to go back to the
+    /// [coordinate system](#method.set_coordinate_system) of the drawing.
+    ///
+    /// World coordinates apply to everything that
+    /// [`set_coordinate_system()`](#method.set_coordinate_system) applies to. Distances that
+    /// turtles move, including the radius of an arc or a circle, are also in world units. When the
+    /// axes have different scales, headings are measured in world coordinates as well, so turning
+    /// by 90 degrees still draws a right angle in world coordinates even though it may not look
+    /// like one on the screen, and circles are stretched into ellipses. Sizes that have nothing to
+    /// do with positions, like the pen thickness, font sizes, turtle shapes and the radius of
+    /// [`draw_circle()`](#method.draw_circle), are still in pixels.
+    ///
+    /// The world coordinates always fill the window, so changing the size of the drawing changes
+    /// the scale. It is best to set the size of the drawing first. Only the coordinates change, so
+    /// anything that has already been drawn stays where it is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any coordinate is not finite or if `llx == urx` or `lly == ury`.
+    ///
+    /// ```rust
+    /// use turtle::{Drawing, Point};
+    ///
+    /// let mut drawing = Drawing::new();
+    /// drawing.set_size((400, 300));
+    /// // Each unit is 200 pixels wide and 150 pixels tall
+    /// drawing.set_world_coordinates(-1.0, -1.0, 1.0, 1.0);
+    ///
+    /// let mut turtle = drawing.add_turtle();
+    /// turtle.go_to((-0.5, -0.5));
+    /// turtle.set_heading(0.0);
+    /// for _ in 0..4 {
+    ///     turtle.forward(1.0);
+    ///     turtle.left(90.0);
+    /// }
+    /// // The square is a rectangle on the screen, but it still ends where it started
+    /// assert_eq!((turtle.position() * 10.0).round(), Point {x: -5.0, y: -5.0});
+    /// assert_eq!(turtle.heading().round(), 0.0);
+    /// ```
+    ///
+    /// [`reset_world_coordinates()`]: #method.reset_world_coordinates
+    pub fn set_world_coordinates(&mut self, llx: f64, lly: f64, urx: f64, ury: f64) {
+        self.drawing.set_world_coordinates(llx, lly, urx, ury)
     }
 
-    #[test]
-    fn ignores_center_nan_inf() {
-        let center = Point {x: 5.0, y: 10.0};
+    /// Stops using world coordinates and goes back to the
+    /// [coordinate system](#method.set_coordinate_system) of the drawing.
+    ///
+    /// See [`set_world_coordinates()`](#method.set_world_coordinates) for more information.
+    pub fn reset_world_coordinates(&mut self) {
+        self.drawing.reset_world_coordinates()
+    }
 
-        let mut drawing = Drawing::new();
-        drawing.set_center(center);
+    /// Returns the number of logical pixels in one inch of the drawing.
+    ///
+    /// See [`set_dpi()`](#method.set_dpi) for more information.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let drawing = Drawing::new();
+    /// assert_eq!(drawing.dpi(), 96.0);
+    /// ```
+    pub fn dpi(&self) -> f64 {
+        block_on(self.drawing.dpi())
+    }
 
-        drawing.set_center([::std::f64::NAN, 0.0]);
-        drawing.set_center([0.0, ::std::f64::NAN]);
-        drawing.set_center([::std::f64::NAN, ::std::f64::NAN]);
-        drawing.set_center([::std::f64::INFINITY, 0.0]);
-        drawing.set_center([0.0, ::std::f64::INFINITY]);
-        drawing.set_center([::std::f64::INFINITY, ::std::f64::INFINITY]);
-        drawing.set_center([-::std::f64::INFINITY, 0.0]);
-        drawing.set_center([0.0, -::std::f64::INFINITY]);
-        drawing.set_center([-::std::f64::INFINITY, -::std::f64::INFINITY]);
+    /// Sets the number of logical pixels in one inch of the drawing (dots per inch).
+    ///
+    /// The DPI relates the drawing to physical units. It is used by
+    /// [`set_size_mm()`](#method.set_size_mm) and
+    /// [`Turtle::set_pen_size_mm()`](struct.Turtle.html#method.set_pen_size_mm), and when
+    /// printing or plotting with `true_scale` set in [`PrintOptions`] or [`PlotterOptions`]. The
+    /// default is 96, the same resolution used by web browsers. The DPI does not change the size
+    /// of anything that has already been drawn.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ExportError, PrintOptions};
+    ///
+    /// fn main() -> Result<(), ExportError> {
+    ///     let mut drawing = Drawing::new();
+    ///     drawing.set_dpi(300.0);
+    ///
+    ///     let mut turtle = drawing.add_turtle();
+    ///     turtle.set_pen_size_mm(0.5);
+    ///     // A line that is exactly 50mm long on paper
+    ///     let dpi = drawing.dpi();
+    ///     turtle.forward(50.0 / 25.4 * dpi);
+    ///
+    ///     drawing.print(PrintOptions {
+    ///         true_scale: true,
+    ///         ..PrintOptions::default()
+    ///     })?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the DPI is not positive.
+    ///
+    /// [`PrintOptions`]: struct.PrintOptions.html
+    /// [`PlotterOptions`]: struct.PlotterOptions.html
+    pub fn set_dpi(&mut self, dpi: f64) {
+        self.drawing.set_dpi(dpi)
+    }
+
+    /// Returns what happens to the window once the program finishes.
+    ///
+    /// See [`set_disconnect_behavior()`](#method.set_disconnect_behavior) for more information.
+    pub fn disconnect_behavior(&self) -> DisconnectBehavior {
+        block_on(self.drawing.disconnect_behavior())
+    }
+
+    /// Sets what happens to the window once the program finishes.
+    ///
+    /// By default, the window stays open after the end of `main()` until it is closed, so that the
+    /// finished drawing can be seen. The window can instead close on its own after a delay, and
+    /// can mark its title with "(finished)" so that it is clear that nothing else will be drawn.
+    ///
+    /// This also applies if the program stops unexpectedly, for example because it was killed or
+    /// because another thread panicked. Programs that [joined](#method.join) a shared drawing do
+    /// not control the window, so the window is not affected when any of them finish.
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use turtle::{Drawing, DisconnectBehavior};
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::new();
+    ///     drawing.set_disconnect_behavior(DisconnectBehavior {
+    ///         close_after: Some(Duration::from_secs(5)),
+    ///         show_finished: true,
+    ///     });
+    ///
+    ///     let mut turtle = drawing.add_turtle();
+    ///     turtle.forward(100.0);
+    ///     // The window closes 5 seconds after this
+    /// }
+    /// ```
+    pub fn set_disconnect_behavior(&mut self, behavior: DisconnectBehavior) {
+        self.drawing.set_disconnect_behavior(behavior)
+    }
+
+    /// Returns how many updates are drawn at once before the window is redrawn.
+    ///
+    /// See [`set_tracer()`](#method.set_tracer) for more information.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let drawing = Drawing::new();
+    /// assert_eq!(drawing.tracer(), 1);
+    /// ```
+    pub fn tracer(&self) -> u32 {
+        block_on(self.drawing.tracer())
+    }
+
+    /// Sets how many updates are drawn at once before the window is redrawn.
+    ///
+    /// By default, the tracer is 1: every movement of every turtle is animated and the window is
+    /// redrawn after each change to the drawing. With any other value, turtles move instantly
+    /// no matter what their speed is, and the window is only redrawn once every `tracer` updates.
+    /// A tracer of 0 never redraws the window on its own. Use [`flush()`](#method.flush) to
+    /// show everything that has been drawn so far.
+    ///
+    /// This is the fastest way to draw pictures with tens of thousands of lines, since no time is
+    /// spent animating them or showing the drawing while it is only partly done.
+    ///
+    /// ```rust
+    /// use turtle::Drawing;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    ///
+    /// // Draw the entire spiral at once
+    /// drawing.set_tracer(0);
+    /// for i in 0..500 {
+    ///     turtle.forward(i as f64 * 0.5);
+    ///     turtle.right(59.0);
+    /// }
+    /// drawing.flush();
+    ///
+    /// // Go back to animating every movement
+    /// drawing.set_tracer(1);
+    /// ```
+    pub fn set_tracer(&mut self, tracer: u32) {
+        self.drawing.set_tracer(tracer)
+    }
+
+    /// Redraws the window right away to show everything that has been drawn so far.
+    ///
+    /// The window is normally redrawn whenever the drawing changes, so this is only needed while
+    /// the [tracer](#method.set_tracer) is skipping updates.
+    pub fn flush(&mut self) {
+        self.drawing.flush()
+    }
+
+    /// Returns true if the movements of turtles and other changes to the drawing are animated.
+    ///
+    /// See [`disable_animation()`](#method.disable_animation) for more information.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// assert_eq!(drawing.is_animated(), true);
+    /// drawing.disable_animation();
+    /// assert_eq!(drawing.is_animated(), false);
+    /// ```
+    pub fn is_animated(&self) -> bool {
+        block_on(self.drawing.is_animated())
+    }
+
+    /// Turns animation back on after it was disabled with
+    /// [`disable_animation()`](#method.disable_animation).
+    ///
+    /// Each turtle goes back to moving at its own [speed](struct.Turtle.html#method.set_speed).
+    pub fn enable_animation(&mut self) {
+        self.drawing.enable_animation()
+    }
+
+    /// Makes every command apply right away, without any animation.
+    ///
+    /// This works as if every turtle had its speed set to
+    /// [`"instant"`](struct.Speed.html#method.instant), but it applies to every turtle at once and
+    /// leaves the speed of each turtle unchanged. Clearing the drawing with
+    /// [`clear_animated()`](#method.clear_animated) skips its effect too. The window is still
+    /// redrawn after every change, unlike with [`set_tracer()`](#method.set_tracer).
+    ///
+    /// ```rust
+    /// use turtle::Drawing;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    /// turtle.set_speed("slowest");
+    ///
+    /// drawing.disable_animation();
+    /// // Drawn instantly, even though the turtle is still set to the slowest speed
+    /// for _ in 0..4 {
+    ///     turtle.forward(100.0);
+    ///     turtle.right(90.0);
+    /// }
+    /// assert_eq!(turtle.speed(), 1);
+    /// ```
+    pub fn disable_animation(&mut self) {
+        self.drawing.disable_animation()
+    }
+
+    /// Pauses the drawing, freezing every animation where it is.
+    ///
+    /// While the drawing is paused, every command sent to the drawing or to any of its turtles
+    /// waits until [`resume()`](#method.resume) is called. Since commands that return a value wait
+    /// for a response, this can be used as a breakpoint: the program stops at the first command
+    /// after the pause until the drawing is resumed, for example with the
+    /// [pause key](#method.set_pause_key). Calling this while the drawing is paused does nothing.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    /// use turtle::event::Key;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    /// drawing.set_pause_key(Some(Key::Space));
+    ///
+    /// turtle.forward(100.0);
+    /// drawing.pause();
+    /// // Waits until the space bar is pressed to resume the drawing
+    /// turtle.right(90.0);
+    /// turtle.forward(100.0);
+    /// ```
+    pub fn pause(&mut self) {
+        self.drawing.pause()
+    }
+
+    /// Resumes the drawing after it was paused with [`pause()`](#method.pause).
+    ///
+    /// Every frozen animation continues from where it stopped and every command that was sent
+    /// while the drawing was paused is run in order. Calling this while the drawing is not paused
+    /// does nothing.
+    pub fn resume(&mut self) {
+        self.drawing.resume()
+    }
+
+    /// Returns the key that pauses and resumes the drawing, if any.
+    ///
+    /// See [`set_pause_key()`](#method.set_pause_key) for more information.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let drawing = Drawing::new();
+    /// assert_eq!(drawing.pause_key(), None);
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn pause_key(&self) -> Option<Key> {
+        block_on(self.drawing.pause_key())
+    }
+
+    /// Sets the key that pauses and resumes the drawing when it is pressed in the window.
+    ///
+    /// Pressing the key toggles between [`pause()`](#method.pause) and
+    /// [`resume()`](#method.resume). Presses of this key are not sent as [events](#method.poll_event).
+    /// Pass `None` (the default) to stop using a pause key.
+    ///
+    /// ```rust
+    /// use turtle::Drawing;
+    /// use turtle::event::Key;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// drawing.set_pause_key(Some(Key::Space));
+    /// assert_eq!(drawing.pause_key(), Some(Key::Space));
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn set_pause_key(&mut self, key: Option<Key>) {
+        self.drawing.set_pause_key(key)
+    }
+
+    /// Makes each turtle command wait for a step before it runs, for debugging.
+    ///
+    /// A step is taken by pressing the [step key](#method.set_step_key) or by calling
+    /// [`step()`](#method.step). The command that is waiting is shown in the title of the window.
+    /// Commands that only read the properties of a turtle, like
+    /// [`position()`](struct.Turtle.html#method.position), never wait. Every other command to the
+    /// drawing waits only while a turtle command ahead of it is waiting.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    /// use turtle::event::Key;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    ///
+    /// // Press the space bar to draw each side of the square
+    /// drawing.set_step_key(Some(Key::Space));
+    /// drawing.enable_step_mode();
+    /// for _ in 0..4 {
+    ///     turtle.forward(100.0);
+    ///     turtle.right(90.0);
+    /// }
+    /// ```
+    pub fn enable_step_mode(&mut self) {
+        self.drawing.enable_step_mode()
+    }
+
+    /// Stops waiting for steps after [`enable_step_mode()`](#method.enable_step_mode) was called.
+    ///
+    /// The command waiting for the next step, if any, runs right away.
+    pub fn disable_step_mode(&mut self) {
+        self.drawing.disable_step_mode()
+    }
+
+    /// Lets the next turtle command run while in [step mode](#method.enable_step_mode).
+    ///
+    /// If no command is waiting, the next turtle command runs without waiting. Calling this
+    /// outside of step mode does nothing.
+    ///
+    /// ```rust
+    /// use turtle::Drawing;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    ///
+    /// drawing.enable_step_mode();
+    /// drawing.step();
+    /// // Runs right away since a step was already taken for it
+    /// turtle.forward(100.0);
+    /// ```
+    pub fn step(&mut self) {
+        self.drawing.step()
+    }
+
+    /// Returns the key that takes a step in step mode, if any.
+    ///
+    /// See [`set_step_key()`](#method.set_step_key) for more information.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let drawing = Drawing::new();
+    /// assert_eq!(drawing.step_key(), None);
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn step_key(&self) -> Option<Key> {
+        block_on(self.drawing.step_key())
+    }
+
+    /// Sets the key that lets the next turtle command run when it is pressed in the window while
+    /// in [step mode](#method.enable_step_mode).
+    ///
+    /// Presses of this key are not sent as [events](#method.poll_event). Pass `None` (the default)
+    /// to stop using a step key.
+    ///
+    /// ```rust
+    /// use turtle::Drawing;
+    /// use turtle::event::Key;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// drawing.set_step_key(Some(Key::S));
+    /// assert_eq!(drawing.step_key(), Some(Key::S));
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn set_step_key(&mut self, key: Option<Key>) {
+        self.drawing.set_step_key(key)
+    }
+
+    /// Applies a custom fragment shader, written in [WGSL], to each frame shown in the window.
+    ///
+    /// This can be used for effects like CRT scanlines, glow, or vignettes. The shader runs after
+    /// everything else (including [filters](#method.apply_filter), the turtles, and the minimap)
+    /// has been drawn. Like filters, it is not applied when saving the drawing.
+    ///
+    /// The shader must have exactly one `@fragment` entry point. The frame is provided as a
+    /// texture and sampler at the bindings shown below. The entry point can take the position of
+    /// the current pixel at `@location(0)`, where `(0, 0)` is the top-left corner of the window and
+    /// `(1, 1)` is the bottom-right corner. Uniform buffers and other textures are not supported.
+    ///
+    /// Any previous shader is replaced. Use [`clear_post_shader()`](#method.clear_post_shader) to
+    /// go back to showing the frame as it is.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    ///
+    /// fn main() -> Result<(), turtle::ShaderError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///     drawing.set_background_color("black");
+    ///     turtle.set_pen_color("light green");
+    ///     turtle.set_pen_size(4.0);
+    ///     turtle.forward(200.0);
+    ///
+    ///     // Draw every other row darker and fade out towards the edges
+    ///     drawing.set_post_shader("
+    ///         @group(0) @binding(0) var frame: texture_2d<f32>;
+    ///         @group(0) @binding(1) var frame_sampler: sampler;
+    ///
+    ///         @fragment
+    ///         fn main(@builtin(position) pos: vec4<f32>, @location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    ///             let color = textureSample(frame, frame_sampler, uv);
+    ///             let scanline = select(1.0, 0.6, u32(pos.y) % 2u == 0u);
+    ///             let vignette = 1.0 - length(uv - vec2<f32>(0.5)) * 0.8;
+    ///             return vec4<f32>(color.rgb * scanline * vignette, 1.0);
+    ///         }
+    ///     ")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Returns an error describing the problem if the shader is not valid. The shader in the
+    /// window is not changed in that case.
+    ///
+    /// [WGSL]: https://www.w3.org/TR/WGSL/
+    #[cfg(feature = "shaders")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "shaders")))]
+    pub fn set_post_shader(&mut self, source: &str) -> Result<(), crate::ShaderError> {
+        self.drawing.set_post_shader(source)
+    }
+
+    /// Removes the shader set with [`set_post_shader()`](#method.set_post_shader).
+    #[cfg(feature = "shaders")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "shaders")))]
+    pub fn clear_post_shader(&mut self) {
+        self.drawing.clear_post_shader()
+    }
+
+    /// Returns true if the drawing is currently maximized.
+    ///
+    /// Note: Even if you set the drawing to the width and height of the current display, it won't
+    /// be maximized unless [`maximize()`](struct.Drawing.html#method.maximize) is called.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// assert_eq!(drawing.is_maximized(), false);
+    ///
+    /// drawing.maximize();
+    /// assert_eq!(drawing.is_maximized(), true);
+    ///
+    /// drawing.unmaximize();
+    /// assert_eq!(drawing.is_maximized(), false);
+    ///
+    /// // Calling the same method again doesn't change the result
+    /// drawing.unmaximize();
+    /// assert_eq!(drawing.is_maximized(), false);
+    /// ```
+    ///
+    /// # Unstable
+    ///
+    /// **This method is currently unstable and unreliable.**
+    /// ([GitHub Issue](https://github.com/sunjay/turtle/issues/49))
+    ///
+    /// Unfortunately, we cannot currently detect when the window is maximized using the maximize
+    /// button on the window. This method is reliable until that button is pressed. Since there is
+    /// no way to tell when that is, treat the value returned from this method as unreliable and
+    /// potentially inaccurate.
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn is_maximized(&self) -> bool {
+        block_on(self.drawing.is_maximized())
+    }
+
+    /// Maximizes the size of the drawing so that it takes up the entire display.
+    ///
+    /// If the drawing is already maximized, this method does nothing.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// # let mut drawing = Drawing::new();
+    /// drawing.maximize();
+    /// assert_eq!(drawing.is_maximized(), true);
+    /// // Calling this method again does nothing
+    /// drawing.maximize();
+    /// assert_eq!(drawing.is_maximized(), true);
+    /// ```
+    pub fn maximize(&mut self) {
+        self.drawing.maximize()
+    }
+
+    /// Returns the size of the drawing to its value before it was maximized
+    ///
+    /// If the drawing is already unmaximized, this method does nothing.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// # let mut drawing = Drawing::new();
+    /// drawing.maximize();
+    /// assert_eq!(drawing.is_maximized(), true);
+    /// drawing.unmaximize();
+    /// assert_eq!(drawing.is_maximized(), false);
+    /// // Calling this again does nothing because the drawing is already unmaximized
+    /// drawing.unmaximize();
+    /// assert_eq!(drawing.is_maximized(), false);
+    /// ```
+    pub fn unmaximize(&mut self) {
+        self.drawing.unmaximize()
+    }
+
+    /// Returns true if the drawing is currently full screen.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// assert_eq!(drawing.is_fullscreen(), false);
+    ///
+    /// drawing.enter_fullscreen();
+    /// assert_eq!(drawing.is_fullscreen(), true);
+    ///
+    /// drawing.exit_fullscreen();
+    /// assert_eq!(drawing.is_fullscreen(), false);
+    ///
+    /// // Calling the same method again doesn't change the result
+    /// drawing.exit_fullscreen();
+    /// assert_eq!(drawing.is_fullscreen(), false);
+    /// ```
+    pub fn is_fullscreen(&self) -> bool {
+        block_on(self.drawing.is_fullscreen())
+    }
+
+    /// Makes the drawing take up the entire screen hiding everything else that would have been
+    /// shown on screen otherwise.
+    ///
+    /// If the drawing is already fullscreen, this method does nothing.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// # let mut drawing = Drawing::new();
+    /// drawing.enter_fullscreen();
+    /// assert_eq!(drawing.is_fullscreen(), true);
+    /// // Calling this method again does nothing
+    /// drawing.enter_fullscreen();
+    /// assert_eq!(drawing.is_fullscreen(), true);
+    /// ```
+    pub fn enter_fullscreen(&mut self) {
+        self.drawing.enter_fullscreen()
+    }
+
+    /// Returns the size of the drawing to its value before it became fullscreen.
+    ///
+    /// If the drawing is already not fullscreen, this method does nothing.
+    ///
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// # let mut drawing = Drawing::new();
+    /// drawing.enter_fullscreen();
+    /// assert_eq!(drawing.is_fullscreen(), true);
+    /// drawing.exit_fullscreen();
+    /// assert_eq!(drawing.is_fullscreen(), false);
+    /// // Calling this again does nothing because the drawing is already not fullscreen
+    /// drawing.exit_fullscreen();
+    /// assert_eq!(drawing.is_fullscreen(), false);
+    /// ```
+    pub fn exit_fullscreen(&mut self) {
+        self.drawing.exit_fullscreen()
+    }
+
+    /// Returns a snapshot of the configuration of the drawing that can be restored later with
+    /// [`set_state()`](#method.set_state)
+    ///
+    /// The snapshot includes the title, background color, view, and size of the drawing. See the
+    /// [`DrawingState` struct](struct.DrawingState.html) for more information and for how to save
+    /// it to a file.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// drawing.set_zoom(2.0);
+    /// let state = drawing.state();
+    /// assert_eq!(state.zoom, 2.0);
+    /// assert_eq!(state.size, Size {width: 800, height: 600});
+    /// ```
+    pub fn state(&self) -> DrawingState {
+        block_on(self.drawing.state())
+    }
+
+    /// Restores a snapshot of the configuration of the drawing that was taken with
+    /// [`state()`](#method.state)
+    ///
+    /// Nothing that has been drawn is affected.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// let state = drawing.state();
+    ///
+    /// drawing.set_background_color("black");
+    /// drawing.set_center([100.0, 50.0]);
+    ///
+    /// drawing.set_state(&state);
+    /// assert_eq!(drawing.background_color(), "white".into());
+    /// assert_eq!(drawing.center(), Point::origin());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if any part of the state is invalid, for example if the background color is not
+    /// valid or the zoom is not greater than zero.
+    pub fn set_state(&mut self, state: &DrawingState) {
+        self.drawing.set_state(state)
+    }
+
+    /// Draws a circle filled with the given color, centered at the given point
+    ///
+    /// The circle is drawn right away without any animation and without needing a turtle. It is
+    /// drawn exactly, so its edge stays smooth when it is exported with
+    /// [`save_svg()`](struct.Drawing.html#method.save_svg) or zoomed in on. Use
+    /// [`Turtle::circle()`](struct.Turtle.html#method.circle) to have a turtle draw the outline of
+    /// a circle instead.
+    ///
+    /// Any type that can be converted into a color can be passed into this function. The circle
+    /// is removed when the drawing is cleared, but not when one of its turtles is cleared.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// drawing.draw_circle([50.0, 0.0], 100.0, "yellow");
+    ///
+    /// drawing.fit_to_content(0.0);
+    /// assert_eq!(drawing.center(), Point {x: 50.0, y: 0.0});
+    /// assert_eq!(drawing.size(), Size {width: 200, height: 200});
+    /// ```
+    ///
+    /// Nothing is drawn if the center or the radius is infinite or `NaN`. A negative radius is
+    /// treated as if it was positive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the color is not valid.
+    pub fn draw_circle<P: Into<Point>, C: Into<Color> + Copy + Debug>(&mut self, center: P, radius: Distance, fill_color: C) {
+        self.drawing.draw_circle(center, radius, fill_color)
+    }
+
+    /// Adds a new layer on top of every other layer and returns its ID.
+    ///
+    /// Every drawing starts with a single layer,
+    /// [`LayerId::BASE`](struct.LayerId.html#associatedconstant.BASE). Turtles draw in the base
+    /// layer until they are moved into a different one with
+    /// [`Turtle::set_layer()`](struct.Turtle.html#method.set_layer). Layers are drawn from the
+    /// bottom to the top, so anything in a higher layer is drawn over everything in the layers
+    /// below it, no matter when it was drawn.
+    ///
+    /// Circles drawn with [`draw_circle()`](struct.Drawing.html#method.draw_circle) and imported
+    /// drawings are always placed in the base layer.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// let foreground = drawing.add_layer();
+    /// let mut turtle = drawing.add_turtle();
+    /// turtle.set_layer(foreground);
+    /// // This is drawn over everything in the base layer, even things drawn later
+    /// turtle.forward(100.0);
+    /// ```
+    pub fn add_layer(&mut self) -> LayerId {
+        block_on(self.drawing.add_layer())
+    }
+
+    /// Returns true if the given layer is visible.
+    ///
+    /// Layers are visible when they are added. See
+    /// [`set_layer_visible()`](struct.Drawing.html#method.set_layer_visible) for more
+    /// information.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layer was added to a different drawing. Use
+    /// [`try_is_layer_visible()`](struct.Drawing.html#method.try_is_layer_visible) to get an error
+    /// instead.
+    pub fn is_layer_visible(&self, layer: LayerId) -> bool {
+        block_on(self.drawing.is_layer_visible(layer))
+    }
+
+    /// Returns true if the given layer is visible, or an error if the window was unable to check.
+    ///
+    /// This is the same as [`is_layer_visible()`](struct.Drawing.html#method.is_layer_visible),
+    /// except that it returns [`Error::UnknownLayer`] instead of panicking if the layer was added
+    /// to a different drawing.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// let layer = drawing.add_layer();
+    ///
+    /// let other = Drawing::new();
+    /// assert!(other.try_is_layer_visible(layer).is_err());
+    /// ```
+    ///
+    /// [`Error::UnknownLayer`]: enum.Error.html#variant.UnknownLayer
+    pub fn try_is_layer_visible(&self, layer: LayerId) -> Result<bool, crate::Error> {
+        block_on(self.drawing.try_is_layer_visible(layer))
+    }
+
+    /// Shows or hides the given layer.
+    ///
+    /// Nothing in a hidden layer is shown in the window, exported, or taken into account by
+    /// [`fit_to_content()`](struct.Drawing.html#method.fit_to_content). The turtles in a hidden layer
+    /// are hidden as well. Hiding a layer does not remove anything from it, so everything is
+    /// shown again once the layer is made visible. Nothing happens if the layer was added to a
+    /// different drawing.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// let grid = drawing.add_layer();
+    /// let mut turtle = drawing.add_turtle();
+    /// turtle.set_layer(grid);
+    /// turtle.forward(100.0);
+    ///
+    /// drawing.set_layer_visible(grid, false);
+    /// assert!(!drawing.is_layer_visible(grid));
+    /// let (_, indices) = drawing.export_mesh();
+    /// assert!(indices.is_empty());
+    /// ```
+    pub fn set_layer_visible(&mut self, layer: LayerId, is_visible: bool) {
+        self.drawing.set_layer_visible(layer, is_visible)
+    }
+
+    /// Moves the given layer above every other layer.
+    ///
+    /// Everything in the layer is drawn over everything in the other layers. The order of the
+    /// drawings within the layer does not change. Nothing happens if the layer was added to a
+    /// different drawing.
+    pub fn raise_layer(&mut self, layer: LayerId) {
+        self.drawing.raise_layer(layer)
+    }
+
+    /// Moves the given layer below every other layer.
+    ///
+    /// Everything in the other layers is drawn over everything in the layer. The order of the
+    /// drawings within the layer does not change. Nothing happens if the layer was added to a
+    /// different drawing.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// let background = drawing.add_layer();
+    /// // The new layer starts on top, so move it to the bottom instead
+    /// drawing.lower_layer(background);
+    /// ```
+    pub fn lower_layer(&mut self, layer: LayerId) {
+        self.drawing.lower_layer(layer)
+    }
+
+    //TODO(#16): This method is hidden because it hasn't been properly documented
+    #[doc(hidden)]
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn clear(&mut self) {
+        self.drawing.clear();
+    }
+
+    /// Clears everything that has been drawn so far, using the given effect to transition to an
+    /// empty drawing instead of clearing it all at once.
+    ///
+    /// The visible part of the drawing is gradually covered with the background color. Once it is
+    /// completely covered, everything is removed and this method returns. Any turtle animations
+    /// that are still playing are stopped first. No turtle moves and no properties of the drawing
+    /// change.
+    ///
+    /// ```rust
+    /// use turtle::{Drawing, ClearEffect, WipeDirection};
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    /// turtle.forward(100.0);
+    ///
+    /// // Fade the first scene out over half a second
+    /// drawing.clear_animated(ClearEffect::Fade(0.5));
+    /// assert!(drawing.export_mesh().0.is_empty());
+    ///
+    /// turtle.right(90.0);
+    /// turtle.forward(100.0);
+    ///
+    /// // Sweep the second scene away from left to right over one second
+    /// drawing.clear_animated(ClearEffect::Wipe(WipeDirection::Right, 1.0));
+    /// assert!(drawing.export_mesh().0.is_empty());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duration of the effect is negative, infinite, or `NaN`.
+    pub fn clear_animated(&mut self, effect: ClearEffect) {
+        block_on(self.drawing.clear_animated(effect))
+    }
+
+    /// Clears everything that has been drawn entirely inside of the given rectangle, leaving the
+    /// rest of the drawing as it is
+    ///
+    /// This is useful for redrawing one part of the drawing (e.g. a score in a game) without
+    /// clearing and redrawing everything else. The rectangle is in the same coordinates that the
+    /// turtles move in. Anything that is only partly inside of the rectangle is kept, and so is the
+    /// fill of any turtle that is still filling. Any turtle animations that are still playing are
+    /// stopped first. No turtle moves and no properties of the drawing change.
+    ///
+    /// ```rust
+    /// use turtle::{Drawing, Rect};
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    /// turtle.forward(100.0);
+    /// drawing.draw_circle([200.0, 200.0], 10.0, "red");
+    ///
+    /// // Only the circle is inside of this rectangle, so the line stays where it is
+    /// drawing.clear_region(Rect::from_center([200.0, 200.0], 50.0, 50.0));
+    /// ```
+    ///
+    /// Nothing is cleared if any corner of the rectangle is infinite or `NaN`.
+    pub fn clear_region(&mut self, area: Rect) {
+        self.drawing.clear_region(area)
+    }
+
+    /// Returns the next event (if any). Returns `None` if there are no events to be processed at
+    /// the current moment. This **does not** mean that there will never be events later on as the
+    /// application continues to run.
+    ///
+    /// This method never waits for an event to occur, so it can be called every frame of a game
+    /// loop without stalling any animations. To wait until the next event instead, use
+    /// [`wait_for_event()`](#method.wait_for_event).
+    ///
+    /// See the [`Event` enum](event/enum.Event.html) for the complete list of events that you can
+    /// handle in your applications.
+    ///
+    /// # Unstable
+    ///
+    /// There are still many unanswered questions about the design of the events API in the turtle
+    /// crate. This method may change or be completely removed in the future. There will definitely
+    /// be *some* events API in the future, but it may end up looking different than it does today.
+    ///
+    /// # Example
+    ///
+    /// To use this advanced method, you need to create what is known as an "event loop". An "event
+    /// loop" is any loop that handles the events generated by the application. The reason that it
+    /// is important to create a loop like this is because events in Turtle are "polled". That
+    /// means that every time an event happens, it is placed in a queue (a list) until you ask to
+    /// look at it. If you do not check for events continuously, there is a chance that the events
+    /// you ask for from `poll_event()` will be outdated.
+    ///
+    /// Even if you do not use every kind of event, you should aim to poll events using this method
+    /// until there are none left to poll. If you do not poll events for a significant amount of
+    /// time during your application, favor the events that come later as you poll since those will
+    /// be the most recent. This can happen if you run many animations between loop iterations.
+    ///
+    /// See the [`examples/`](https://github.com/sunjay/turtle/raw/master/examples) directory in
+    /// the source code of this library for more examples of how to use events.
+    ///
+    /// The following is an example of a basic event loop. Notice that it uses two loops. One to
+    /// move the turtle continuously, and another to handle all the events available at a given
+    /// moment. If it suits your purposes, you may also just use a single loop to handle events
+    /// and move the turtle from within that loop. This example is of a more complex case where
+    /// it really matters that the most recent information is taken into consideration before any
+    /// further movements take place.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, Event, event::{Key, PressedState}};
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     loop {
+    ///         turtle.forward(1.0);
+    ///
+    ///         while let Some(event) = drawing.poll_event() {
+    ///             use Key::{LeftArrow, RightArrow};
+    ///             match event {
+    ///                 Event::Key(key, PressedState::Pressed) => match key {
+    ///                     LeftArrow => {
+    ///                         turtle.set_speed(8);
+    ///                         for _ in 0..20 {
+    ///                             turtle.forward(1.0);
+    ///                             turtle.left(4.5);
+    ///                         }
+    ///                         turtle.set_speed(4);
+    ///                     },
+    ///                     RightArrow => {
+    ///                         turtle.set_speed(8);
+    ///                         for _ in 0..20 {
+    ///                             turtle.forward(1.0);
+    ///                             turtle.right(4.5);
+    ///                         }
+    ///                         turtle.set_speed(4);
+    ///                     },
+    ///                     _ => {},
+    ///                 },
+    ///                 _ => {},
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn poll_event(&mut self) -> Option<crate::Event> {
+        block_on(self.drawing.poll_event())
+    }
+
+    /// Waits until the next event occurs and returns it
+    ///
+    /// Unlike [`poll_event()`](#method.poll_event), this blocks the current thread until an event
+    /// is available. This is useful for programs that only draw in response to input, since
+    /// waiting for an event does not use any CPU. Returns `None` only if the window has closed and
+    /// no more events will ever arrive.
+    ///
+    /// # Unstable
+    ///
+    /// There are still many unanswered questions about the design of the events API in the turtle
+    /// crate. This method may change or be completely removed in the future.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, Event};
+    /// use turtle::event::{Key, PressedState};
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     // Only move when an arrow key is pressed
+    ///     while let Some(event) = drawing.wait_for_event() {
+    ///         match event {
+    ///             Event::Key(Key::UpArrow, PressedState::Pressed) => turtle.forward(20.0),
+    ///             Event::Key(Key::LeftArrow, PressedState::Pressed) => turtle.left(30.0),
+    ///             Event::Key(Key::RightArrow, PressedState::Pressed) => turtle.right(30.0),
+    ///             Event::WindowClosed => break,
+    ///             _ => {},
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn wait_for_event(&mut self) -> Option<crate::Event> {
+        block_on(self.drawing.wait_for_event())
+    }
+
+    /// Returns an iterator over the events that have arrived since the last time events were
+    /// checked
+    ///
+    /// The iterator ends as soon as there are no more events available, so this never waits for
+    /// new events to arrive. This is the same as calling
+    /// [`poll_event()`](#method.poll_event) until it returns `None`, which makes it useful for
+    /// handling every event once per iteration of a loop.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, Event};
+    /// use turtle::event::{Key, PressedState};
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     loop {
+    ///         for event in drawing.events() {
+    ///             match event {
+    ///                 Event::Key(Key::LeftArrow, PressedState::Pressed) => turtle.left(15.0),
+    ///                 Event::Key(Key::RightArrow, PressedState::Pressed) => turtle.right(15.0),
+    ///                 _ => {},
+    ///             }
+    ///         }
+    ///
+    ///         turtle.forward(2.0);
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn events(&mut self) -> impl Iterator<Item=crate::Event> + '_ {
+        std::iter::from_fn(move || self.poll_event())
+    }
+
+    /// Returns an asynchronous stream of every event that takes place in the window from now on
+    ///
+    /// The returned [`EventStream`] implements the `Stream` trait from the `futures` crate. This
+    /// is useful for async programs that need to wait for input and do other work at the same
+    /// time (e.g. with `select!`), since waiting for the next event does not use any CPU until an
+    /// event actually occurs. The stream ends once the window has closed.
+    ///
+    /// Each event is only ever returned once. Events taken from the stream will not be returned
+    /// from [`poll_event()`](#method.poll_event) and vice versa. If more than one stream is
+    /// waiting, each event goes to the stream that started waiting first.
+    ///
+    /// ```rust,no_run
+    /// use futures_util::StreamExt;
+    /// use turtle::{Drawing, Event};
+    /// use turtle::event::{Key, PressedState};
+    ///
+    /// fn main() {
+    ///     let drawing = Drawing::new();
+    ///     let mut events = drawing.event_stream();
+    ///
+    ///     let mut runtime = tokio::runtime::Runtime::new().unwrap();
+    ///     runtime.block_on(async {
+    ///         while let Some(event) = events.next().await {
+    ///             if let Event::Key(Key::Esc, PressedState::Pressed) = event {
+    ///                 break;
+    ///             }
+    ///             println!("{:?}", event);
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    ///
+    /// [`EventStream`]: event/struct.EventStream.html
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn event_stream(&self) -> crate::event::EventStream {
+        block_on(self.drawing.events())
+    }
+
+    /// Allows other programs running on the same computer to draw in this drawing
+    ///
+    /// Other programs can connect to this drawing by passing the same name to [`join()`]. Each of
+    /// those programs can add its own turtle and draw at the same time as this program. This is
+    /// useful for collaborative drawing exercises where everyone draws in the same window.
+    ///
+    /// The window stays open for as long as this program keeps running. Programs that joined the
+    /// drawing may come and go at any time.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    ///
+    /// fn main() {
+    ///     let mut drawing = Drawing::new();
+    ///     drawing.share("classroom").expect("unable to share drawing");
+    ///
+    ///     let mut turtle = drawing.add_turtle();
+    ///     turtle.set_pen_color("red");
+    ///     // Draw a circle while other programs join in
+    ///     for _ in 0..360 {
+    ///         turtle.forward(3.0);
+    ///         turtle.right(1.0);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is empty or contains characters other than ASCII letters, digits, `-`,
+    /// or `_`.
+    ///
+    /// [`join()`]: struct.Drawing.html#method.join
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn share(&mut self, name: &str) -> Result<(), crate::ShareError> {
+        block_on(self.drawing.share(name))
+    }
+
+    /// Allows programs on other computers to draw in this drawing by connecting to the given
+    /// address using [`join_tcp()`]
+    ///
+    /// Each program that connects can add its own turtles, just like the programs that join a
+    /// drawing shared with [`share()`]. Returns the address that is being listened on, which is
+    /// useful if you asked for any available port (port `0`). Listen on `0.0.0.0` to accept
+    /// connections from any computer on the network.
+    ///
+    /// Anyone who can reach the address is able to draw, so only use this on a network that you
+    /// trust. Requests that would read or write files on this computer (e.g. exporting the
+    /// drawing) are not allowed over these connections.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    ///
+    /// fn main() {
+    ///     let drawing = Drawing::new();
+    ///     let addr = drawing.share_tcp("0.0.0.0:7777").expect("unable to share drawing");
+    ///     println!("Listening on {}", addr);
+    ///
+    ///     // Keep the program running so the window stays open
+    ///     loop {
+    ///         std::thread::park();
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`join_tcp()`]: struct.Drawing.html#method.join_tcp
+    /// [`share()`]: struct.Drawing.html#method.share
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    pub fn share_tcp<A: std::net::ToSocketAddrs>(&self, addr: A) -> std::io::Result<std::net::SocketAddr> {
+        block_on(self.drawing.share_tcp(addr))
+    }
+
+    /// Allows other programs to control this drawing by connecting to the given address using
+    /// WebSocket
+    ///
+    /// This makes it possible to draw in a turtle window from a web page or from a program written
+    /// in another language. Each WebSocket connection acts like a separate program that can add
+    /// its own turtles. Returns the address that is being listened on, which is useful if you
+    /// asked for any available port (port `0`).
+    ///
+    /// Every text message sent over the connection must be a single request encoded as JSON. If
+    /// that request has a response, it will be sent back (also encoded as JSON) before the next
+    /// request is processed. Requests that access files or other parts of the system (e.g.
+    /// exporting the drawing) are rejected, since any web page is able to connect to a WebSocket
+    /// server on your computer. For the same reason, only web pages served from your computer
+    /// (e.g. from `http://localhost:8000`) are allowed to connect. Use
+    /// [`serve_websocket_with()`](#method.serve_websocket_with) to allow other web pages.
+    ///
+    /// ```rust,no_run
+    /// use turtle::Drawing;
+    ///
+    /// fn main() {
+    ///     let drawing = Drawing::new();
+    ///     let addr = drawing.serve_websocket("127.0.0.1:9001").expect("unable to start server");
+    ///     println!("Connect to ws://{} to start drawing", addr);
+    ///
+    ///     // Keep the program running so the window stays open
+    ///     loop {
+    ///         std::thread::park();
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// A web page could then add a turtle and move it forward:
+    ///
+    /// ```js
+    /// const socket = new WebSocket("ws://127.0.0.1:9001");
+    /// socket.onopen = () => socket.send(JSON.stringify("CreateTurtle"));
+    /// socket.onmessage = (message) => {
+    ///   // {"Response":{"NewTurtle":1}}
+    ///   const turtle = JSON.parse(message.data).Response.NewTurtle;
+    ///   if (turtle !== undefined) {
+    ///     socket.send(JSON.stringify({MoveForward: [turtle, 100.0]}));
+    ///   }
+    /// };
+    /// ```
+    ///
+    /// # Stability
+    ///
+    /// The messages sent over the connection are the same as the messages that are used
+    /// internally to communicate with the window. They are likely to change between releases of
+    /// this crate.
+    #[cfg(feature = "websocket")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+    pub fn serve_websocket<A: std::net::ToSocketAddrs>(&self, addr: A) -> std::io::Result<std::net::SocketAddr> {
+        self.serve_websocket_with(addr, crate::WebSocketOptions::default())
+    }
+
+    /// Same as [`serve_websocket()`](#method.serve_websocket), but with the given options
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, WebSocketOptions};
+    ///
+    /// let drawing = Drawing::new();
+    /// // Allow the web page of a class to draw in this window
+    /// drawing.serve_websocket_with("127.0.0.1:9001", WebSocketOptions {
+    ///     allowed_origins: vec!["https://turtle-class.example".to_string()],
+    /// }).expect("unable to start server");
+    /// ```
+    #[cfg(feature = "websocket")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+    pub fn serve_websocket_with<A: std::net::ToSocketAddrs>(&self, addr: A, options: crate::WebSocketOptions) -> std::io::Result<std::net::SocketAddr> {
+        block_on(self.drawing.serve_websocket_with(addr, options))
+    }
+
+    /// Displays the drawing inline in a Jupyter notebook that is using the [evcxr] kernel
+    ///
+    /// The evcxr kernel calls this method automatically when a `Drawing` is the last expression in
+    /// a cell, so you will rarely need to call it yourself.
+    ///
+    /// ```rust,ignore
+    /// :dep turtle = { version = "*", features = ["evcxr", "headless"] }
+    ///
+    /// use turtle::Drawing;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    /// for _ in 0..4 {
+    ///     turtle.forward(100.0);
+    ///     turtle.right(90.0);
+    /// }
+    ///
+    /// // Shows the square below the cell
+    /// drawing
+    /// ```
+    ///
+    /// The image shown is the area of the drawing that would have been visible in the window, so
+    /// methods like [`set_size()`] and [`set_center()`] still control what you see. Enabling the
+    /// `evcxr` feature does not change how drawings are shown anywhere else. Also enable the
+    /// `headless` feature (as shown above) so that no window is opened from the notebook and every
+    /// animation completes instantly.
+    ///
+    /// [evcxr]: https://github.com/google/evcxr
+    /// [`set_size()`]: #method.set_size
+    /// [`set_center()`]: #method.set_center
+    #[cfg(feature = "evcxr")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "evcxr")))]
+    pub fn evcxr_display(&self) {
+        let svg = block_on(self.drawing.export_svg(crate::ExportOptions::default()));
+        // See: https://github.com/google/evcxr/blob/main/evcxr_jupyter/README.md
+        println!("EVCXR_BEGIN_CONTENT image/svg+xml\n{}\nEVCXR_END_CONTENT", svg);
+    }
+
+    /// Saves the current drawings in SVG format at the location specified by `path`.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, Turtle, Color, ExportError};
+    ///
+    /// fn main() -> Result<(), ExportError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///     drawing.set_background_color("pink");
+    ///
+    ///     for i in 0..36 {
+    ///         let base_color: Color = if i % 2 == 0 {
+    ///             "red".into()
+    ///         } else {
+    ///             "white".into()
+    ///         };
+    ///
+    ///         turtle.set_fill_color(base_color.with_alpha(1.0 - i as f64 / 54.0));
+    ///         turtle.begin_fill();
+    ///         square(&mut turtle);
+    ///         turtle.end_fill();
+    ///         turtle.right(10.0);
+    ///    }
+    ///
+    ///    turtle.hide();
+    ///    drawing.save_svg("squares.svg")?;
+    ///
+    ///    Ok(())
+    /// }
+    ///
+    /// fn square(turtle: &mut Turtle) {
+    ///     for _ in 0..4 {
+    ///         turtle.forward(200.0);
+    ///         turtle.right(90.0);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// This will produce the following image in the current directory under the name `squares.svg`:
+    ///
+    /// ![squares](https://raw.githubusercontent.com/sunjay/turtle/master/docs/assets/images/docs/squares.svg?sanitize=true)
+    pub fn save_svg<P: AsRef<Path>>(&self, path: P) -> Result<(), ExportError> {
+        block_on(self.drawing.save_svg(path))
+    }
+
+    /// Saves part of the drawing in SVG format at the location specified by `path`.
+    ///
+    /// The given [`ExportOptions`] control which area of the drawing is saved and the resolution
+    /// of the saved image. Using `ExportOptions::default()` produces exactly the same image as
+    /// [`save_svg()`](#method.save_svg).
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ExportError, ExportOptions, ExportRegion, Rect};
+    ///
+    /// fn main() -> Result<(), ExportError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     for _ in 0..4 {
+    ///         turtle.forward(1000.0);
+    ///         turtle.right(90.0);
+    ///     }
+    ///
+    ///     // Save the entire square, even though most of it is outside of the window
+    ///     drawing.save_svg_with("square.svg", ExportOptions {
+    ///         region: ExportRegion::Content {margin: 20.0},
+    ///         ..ExportOptions::default()
+    ///     })?;
+    ///
+    ///     // Save only the corner where the turtle started at three times the resolution
+    ///     drawing.save_svg_with("corner.svg", ExportOptions {
+    ///         region: ExportRegion::Rect(Rect::from_points([-50.0, -50.0], [50.0, 50.0])),
+    ///         scale: 3.0,
+    ///     })?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scale is not positive, if the margin is not finite, or if an explicitly
+    /// given rectangle has no area.
+    ///
+    /// [`ExportOptions`]: struct.ExportOptions.html
+    pub fn save_svg_with<P: AsRef<Path>>(&self, path: P, options: ExportOptions) -> Result<(), ExportError> {
+        block_on(self.drawing.save_svg_with(path, options))
+    }
+
+    /// Saves the current drawings in PNG format at the location specified by `path`.
+    ///
+    /// Unlike an SVG image, a PNG image has a fixed resolution. The saved image is the same size
+    /// as the window and contains the same area of the drawing, but the turtles themselves are not
+    /// included. Use [`save_png_with()`](#method.save_png_with) to save a different area or at a
+    /// higher resolution.
+    ///
+    /// The drawing is rendered in software, so this works even if no window can be displayed.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ExportError};
+    ///
+    /// fn main() -> Result<(), ExportError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     for _ in 0..360 {
+    ///         turtle.forward(3.0);
+    ///         turtle.right(1.0);
+    ///     }
+    ///
+    ///     drawing.save_png("circle.png")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), ExportError> {
+        block_on(self.drawing.save_png(path))
+    }
+
+    /// Saves part of the drawing in PNG format at the location specified by `path`.
+    ///
+    /// The given [`ExportOptions`] control which area of the drawing is saved and the resolution
+    /// of the saved image, just like with [`save_svg_with()`](#method.save_svg_with). Returns an
+    /// error if the image would be too large to render.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ExportError, ExportOptions};
+    ///
+    /// fn main() -> Result<(), ExportError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///     turtle.forward(100.0);
+    ///
+    ///     // Twice the width and height of the window, for high DPI screens
+    ///     drawing.save_png_with("line@2x.png", ExportOptions {
+    ///         scale: 2.0,
+    ///         ..ExportOptions::default()
+    ///     })?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scale is not positive, if the margin is not finite, or if an explicitly
+    /// given rectangle has no area.
+    ///
+    /// [`ExportOptions`]: struct.ExportOptions.html
+    pub fn save_png_with<P: AsRef<Path>>(&self, path: P, options: ExportOptions) -> Result<(), ExportError> {
+        block_on(self.drawing.save_png_with(path, options))
+    }
+
+    /// Starts recording the drawing into an animated GIF at the location specified by `path`.
+    ///
+    /// While the recording is running, the area visible in the window is captured `fps` times
+    /// per second, so the GIF shows the lines and fills appearing as the turtles draw them. Just
+    /// like with [`save_png()`](#method.save_png), the turtles themselves are not included. The
+    /// GIF is the size that the window had when the recording started and it loops forever.
+    ///
+    /// Call [`stop_recording()`](#method.stop_recording) to finish writing the file. Only one
+    /// recording can run at a time, so this returns an error if a recording is already running
+    /// or if the file cannot be created.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ExportError};
+    ///
+    /// fn main() -> Result<(), ExportError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     drawing.start_recording_gif("star.gif", 20.0)?;
+    ///     for _ in 0..5 {
+    ///         turtle.forward(200.0);
+    ///         turtle.right(144.0);
+    ///     }
+    ///     drawing.stop_recording()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fps` is not greater than zero or is more than 50. Most GIF viewers cannot play
+    /// frames any faster than that.
+    pub fn start_recording_gif<P: AsRef<Path>>(&mut self, path: P, fps: f64) -> Result<(), ExportError> {
+        block_on(self.drawing.start_recording_gif(path, fps))
+    }
+
+    /// Stops the recording started by [`start_recording_gif()`](#method.start_recording_gif)
+    /// and finishes writing the GIF.
+    ///
+    /// Frames where nothing changed are merged, so pauses in the drawing do not make the file
+    /// much larger. This waits until the whole file has been written. Returns an error if
+    /// nothing is being recorded or if the file could not be written.
+    pub fn stop_recording(&mut self) -> Result<(), ExportError> {
+        block_on(self.drawing.stop_recording())
+    }
+
+    /// Writes every command sent to the drawing from now on into a command log at the location
+    /// specified by `path`.
+    ///
+    /// Each command is saved along with the time it ran, so the log can be used to draw the same
+    /// picture again later without the program that drew it. This keeps going until the window
+    /// closes. Calling this again finishes the current log and starts a new one. Commands that
+    /// read or write files, like [`save_svg()`](#method.save_svg) and
+    /// [`import_svg_file()`](#method.import_svg_file), are not included. Neither is pausing the
+    /// drawing or waiting for events.
+    ///
+    /// The log can only be read by the same version of this crate that wrote it. Returns an error
+    /// if the file cannot be created.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ExportError};
+    ///
+    /// fn main() -> Result<(), ExportError> {
+    ///     let mut drawing = Drawing::new();
+    ///     drawing.record_to("square.turtlelog")?;
+    ///
+    ///     let mut turtle = drawing.add_turtle();
+    ///     for _ in 0..4 {
+    ///         turtle.forward(100.0);
+    ///         turtle.right(90.0);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn record_to<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ExportError> {
+        block_on(self.drawing.record_to(path))
+    }
+
+    /// Adds the contents of the SVG file at the location specified by `path` to the drawing.
+    ///
+    /// The given [`ImportTransform`] controls where the image is placed and how large it is. Once
+    /// imported, the image is part of the drawing just like anything drawn by a turtle, so you can
+    /// use turtles to trace over it or decorate it. Calling [`clear()`](#method.clear) removes it
+    /// along with everything else.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ImportError, ImportTransform};
+    ///
+    /// fn main() -> Result<(), ImportError> {
+    ///     let mut drawing = Drawing::new();
+    ///
+    ///     // Place the image above the turtle at twice its original size
+    ///     drawing.import_svg_file("flower.svg", ImportTransform {
+    ///         center: [0.0, 150.0].into(),
+    ///         scale: 2.0,
+    ///     })?;
+    ///
+    ///     let mut turtle = drawing.add_turtle();
+    ///     turtle.forward(100.0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Filled shapes and strokes are imported with their colors and opacity. Curves are drawn as
+    /// many short lines. Gradients are drawn using a single color, and any images or text in the
+    /// file are skipped. Holes inside of filled shapes are currently filled in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scale is not positive or if the center is not finite.
+    ///
+    /// [`ImportTransform`]: struct.ImportTransform.html
+    pub fn import_svg_file<P: AsRef<Path>>(&mut self, path: P, transform: ImportTransform) -> Result<(), ImportError> {
+        block_on(self.drawing.import_svg_file(path, transform))
+    }
+
+    /// Saves everything that has been drawn, the state of every turtle, and the configuration of
+    /// the drawing to the file at the location specified by `path`.
+    ///
+    /// The file can be loaded with [`load()`](#method.load) in a later run of your program to
+    /// continue a drawing from where you left off. The file uses JSON and includes a version
+    /// number so that files saved by a different version of this crate are rejected with a clear
+    /// error instead of being loaded incorrectly.
+    ///
+    /// ```rust,no_run
+    /// use std::path::Path;
+    /// use turtle::{Drawing, SessionError};
+    ///
+    /// fn main() -> Result<(), SessionError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     // Continue from where the last run of the program left off
+    ///     if Path::new("spiral.json").exists() {
+    ///         drawing.load("spiral.json")?;
+    ///     }
+    ///
+    ///     for _ in 0..20 {
+    ///         turtle.forward(turtle.position().len() + 5.0);
+    ///         turtle.right(91.0);
+    ///     }
+    ///
+    ///     drawing.save("spiral.json")
+    /// }
+    /// ```
+    ///
+    /// The title, background color, view, and size of the drawing are saved. The state of each
+    /// turtle is saved, except for its layer. Only what is drawn in visible layers is saved, and
+    /// images stamped with [`Turtle::stamp_image()`] are left out since their pixels are not
+    /// saved.
+    ///
+    /// [`Turtle::stamp_image()`]: struct.Turtle.html#method.stamp_image
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SessionError> {
+        block_on(self.drawing.save(path))
+    }
+
+    /// Replaces everything that has been drawn, the state of every turtle, and the configuration
+    /// of the drawing with what was saved in the file at the location specified by `path` using
+    /// [`save()`](#method.save).
+    ///
+    /// Turtles are matched up in the order that they were created: the first turtle of this
+    /// drawing gets the state of the first saved turtle, and so on. Create the same number of
+    /// turtles before loading as the drawing had when it was saved. Any extra saved turtles are
+    /// ignored. Everything that was drawn is added to the base layer, and turtles that had an
+    /// image as their shape go back to the default shape.
+    ///
+    /// See [`save()`](#method.save) for an example.
+    ///
+    /// If the file cannot be read or was not saved with [`save()`](#method.save), an error is
+    /// returned and the drawing is left unchanged.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), SessionError> {
+        block_on(self.drawing.load(path))
+    }
+
+    /// Loads the PNG image at the location specified by `path` so that it can be used by the
+    /// turtles in this drawing.
+    ///
+    /// Loading an image does not draw anything. Use the returned [`ImageHandle`] to set the image
+    /// as the shape of a turtle with [`Shape::Image`] or to draw the image on the canvas with
+    /// [`Turtle::stamp_image()`]. Each image can be used as many times as you want, so it only
+    /// needs to be loaded once.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ImageError, Shape};
+    ///
+    /// fn main() -> Result<(), ImageError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let ship = drawing.load_image("ship.png")?;
+    ///
+    ///     let mut turtle = drawing.add_turtle();
+    ///     turtle.set_shape(Shape::Image(ship));
+    ///     for _ in 0..4 {
+    ///         turtle.forward(100.0);
+    ///         turtle.stamp_image(ship);
+    ///         turtle.right(90.0);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Images are drawn pixel by pixel without any smoothing, so enlarging them keeps the edges of
+    /// pixel art crisp. Images are included when the drawing is saved as an SVG or PNG file, but
+    /// not in [`export_mesh()`](#method.export_mesh) or any of the plotter formats.
+    ///
+    /// Returns an error if the file could not be read, is not a PNG image, or has more than 4
+    /// million pixels.
+    ///
+    /// [`ImageHandle`]: struct.ImageHandle.html
+    /// [`Shape::Image`]: enum.Shape.html#variant.Image
+    /// [`Turtle::stamp_image()`]: struct.Turtle.html#method.stamp_image
+    pub fn load_image<P: AsRef<Path>>(&mut self, path: P) -> Result<ImageHandle, ImageError> {
+        block_on(self.drawing.load_image(path))
+    }
+
+    /// Loads a PNG image from the given bytes so that it can be used by the turtles in this
+    /// drawing.
+    ///
+    /// This works just like [`load_image()`](#method.load_image), except that the image does not
+    /// need to be a separate file. Use [`include_bytes!`] to embed the image in the program itself.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ImageError, Shape};
+    ///
+    /// fn main() -> Result<(), ImageError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let logo = drawing.load_image_from_bytes(include_bytes!("../docs/assets/images/turtle-logo-512.png"))?;
+    ///
+    ///     let mut turtle = drawing.add_turtle();
+    ///     turtle.set_shape(Shape::Image(logo));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Returns an error if the bytes are not a PNG image or if the image has more than 4 million
+    /// pixels.
+    pub fn load_image_from_bytes(&mut self, bytes: &[u8]) -> Result<ImageHandle, ImageError> {
+        block_on(self.drawing.load_image_from_bytes(bytes))
+    }
+
+    /// Prints the drawing using the given [`PrintOptions`].
+    ///
+    /// The page is rendered at 300 DPI, sized in physical units so that it prints at the size
+    /// of the paper. The selected region is scaled to fit the paper within its margins.
+    ///
+    /// On Windows and macOS, this opens the print dialog of the operating system and returns once
+    /// the dialog has been closed. On other platforms, the page is sent to the default printer
+    /// using the `lp` command.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ExportError, PrintOptions, ExportRegion};
+    ///
+    /// fn main() -> Result<(), ExportError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     for _ in 0..5 {
+    ///         turtle.forward(200.0);
+    ///         turtle.right(144.0);
+    ///     }
+    ///
+    ///     drawing.print(PrintOptions {
+    ///         region: ExportRegion::Content {margin: 10.0},
+    ///         ..PrintOptions::default()
+    ///     })?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the margins do not leave any room on the paper or if the region is invalid (see
+    /// [`save_svg_with()`](#method.save_svg_with)).
+    ///
+    /// [`PrintOptions`]: struct.PrintOptions.html
+    pub fn print(&self, options: PrintOptions) -> Result<(), ExportError> {
+        block_on(self.drawing.print(options))
+    }
+
+    /// Saves the lines in the drawing as G-code for a pen plotter at the location specified by
+    /// `path`.
+    ///
+    /// Each line drawn by a turtle becomes a pen-down move, and the pen is only lifted when the
+    /// next line does not start where the previous one ended. Fills are not included. The
+    /// [`PlotterOptions`] control the size of the paper, the feed rates, and the heights of the
+    /// Z axis that raise and lower the pen. The file can be sent to GRBL-based plotters (like
+    /// most AxiDraw-style machines) or CNC controllers with a pen attachment.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ExportError, ExportRegion, PlotterOptions};
+    ///
+    /// fn main() -> Result<(), ExportError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///
+    ///     for _ in 0..5 {
+    ///         turtle.forward(200.0);
+    ///         turtle.right(144.0);
+    ///     }
+    ///
+    ///     drawing.save_gcode("star.gcode", PlotterOptions {
+    ///         region: ExportRegion::Content {margin: 10.0},
+    ///         feed_rate: 1000.0,
+    ///         ..PlotterOptions::default()
+    ///     })?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the margins do not leave any room on the paper, if either feed rate is not
+    /// positive, if the pen up height is not above the pen down height, or if the region is
+    /// invalid (see [`save_svg_with()`](#method.save_svg_with)).
+    ///
+    /// [`PlotterOptions`]: struct.PlotterOptions.html
+    pub fn save_gcode<P: AsRef<Path>>(&self, path: P, options: PlotterOptions) -> Result<(), ExportError> {
+        block_on(self.drawing.save_gcode(path, options))
+    }
+
+    /// Saves the lines in the drawing as HPGL for a pen plotter at the location specified by
+    /// `path`.
+    ///
+    /// This works the same way as [`save_gcode()`](#method.save_gcode), but produces the
+    /// Hewlett-Packard Graphics Language understood by many older plotters and vinyl cutters.
+    /// HPGL always lifts the pen the same way, so only the `feed_rate` of the [`PlotterOptions`]
+    /// is used to control the speed of the pen.
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Drawing, ExportError, PlotterOptions};
+    ///
+    /// fn main() -> Result<(), ExportError> {
+    ///     let mut drawing = Drawing::new();
+    ///     let mut turtle = drawing.add_turtle();
+    ///     turtle.forward(100.0);
+    ///
+    ///     drawing.save_hpgl("line.plt", PlotterOptions::default())?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`save_gcode()`](#method.save_gcode).
+    ///
+    /// [`PlotterOptions`]: struct.PlotterOptions.html
+    pub fn save_hpgl<P: AsRef<Path>>(&self, path: P, options: PlotterOptions) -> Result<(), ExportError> {
+        block_on(self.drawing.save_hpgl(path, options))
+    }
+
+    /// Returns everything that has been drawn so far as a mesh of triangles.
+    ///
+    /// This makes it possible to draw something with a turtle and then use it in a game engine
+    /// (e.g. Bevy or ggez) or with any other graphics library. Every three indices refer to the
+    /// vertices of one triangle. Triangles are listed in the order that they should be drawn in,
+    /// so later triangles may overlap earlier ones. The turtles themselves are not included.
+    ///
+    /// ```rust
+    /// use turtle::Drawing;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    ///
+    /// turtle.begin_fill();
+    /// for _ in 0..3 {
+    ///     turtle.forward(100.0);
+    ///     turtle.right(120.0);
+    /// }
+    /// turtle.end_fill();
+    ///
+    /// let (vertices, indices) = drawing.export_mesh();
+    /// // The filled triangle plus the three lines around it
+    /// assert_eq!(indices.len() / 3, 1 + 3 * 2);
+    /// for index in indices {
+    ///     let vertex = vertices[index as usize];
+    ///     // Pass vertex.position and vertex.color to your graphics library of choice
+    /// }
+    /// ```
+    ///
+    /// The positions of the vertices use the same coordinate system as the turtle: the origin is
+    /// at the center of the drawing and the y-axis points up. Some libraries have the y-axis
+    /// pointing down, so you may need to flip the y-coordinate of each vertex.
+    ///
+    /// Lines are drawn with rounded ends in the window, but are approximated using square ends in
+    /// the mesh. Images placed with [`Turtle::stamp_image()`](struct.Turtle.html#method.stamp_image)
+    /// are not included since each vertex can only have a single color.
+    pub fn export_mesh(&self) -> (Vec<Vertex>, Vec<Index>) {
+        block_on(self.drawing.export_mesh())
+    }
+
+    /// Returns a copy of every shape that has been drawn so far.
+    ///
+    /// The shapes are listed in the order that they are drawn in, so later shapes may cover
+    /// earlier ones. Shapes in hidden layers and the turtles themselves are not included. Unlike
+    /// [`export_mesh()`](#method.export_mesh), each shape is given exactly as it was drawn, which
+    /// is useful for writing your own exporter or for checking the shapes in a test.
+    ///
+    /// ```rust
+    /// use turtle::{Drawing, Point, Primitive};
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    /// turtle.forward(100.0);
+    ///
+    /// match &drawing.primitives()[..] {
+    ///     [Primitive::Line {start, end, ..}] => {
+    ///         assert_eq!(*start, Point::origin());
+    ///         assert_eq!(end.round(), Point {x: 0.0, y: 100.0});
+    ///     },
+    ///     primitives => panic!("expected a single line, got {:?}", primitives),
+    /// }
+    /// ```
+    pub fn primitives(&self) -> Vec<Primitive> {
+        block_on(self.drawing.primitives())
+    }
+
+    /// Returns a copy of the shape with the given ID, or `None` if the shape has been removed
+    /// (e.g. with [`clear()`](#method.clear) or [`Turtle::undo()`](struct.Turtle.html#method.undo)).
+    ///
+    /// See [`hit_test()`](#method.hit_test) for an example.
+    pub fn primitive(&self, id: PrimitiveId) -> Option<Primitive> {
+        block_on(self.drawing.primitive(id))
+    }
+
+    /// Returns the IDs of every shape that the given point is on.
+    ///
+    /// A point is on a line or curve if it is within half the pen size of it, and on a filled
+    /// shape or text if it is inside of it. The IDs are in the order that the shapes are drawn
+    /// in, so the last one is the shape on top. Shapes in hidden layers and the turtles themselves
+    /// are not included. Use [`primitive()`](#method.primitive) to find out more about each shape.
+    ///
+    /// ```rust
+    /// use turtle::{Drawing, Primitive};
+    ///
+    /// let mut drawing = Drawing::new();
+    /// drawing.draw_circle([0.0, 0.0], 50.0, "blue");
+    /// let mut turtle = drawing.add_turtle();
+    /// turtle.set_pen_size(10.0);
+    /// turtle.forward(100.0);
+    ///
+    /// // The line is drawn on top of the circle
+    /// let ids = drawing.hit_test([0.0, 20.0]);
+    /// assert_eq!(ids.len(), 2);
+    /// assert!(matches!(drawing.primitive(ids[0]), Some(Primitive::Circle {..})));
+    /// assert!(matches!(drawing.primitive(ids[1]), Some(Primitive::Line {..})));
+    ///
+    /// assert_eq!(drawing.hit_test([20.0, 80.0]), vec![]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the point is infinite or `NaN`.
+    pub fn hit_test<P: Into<Point>>(&self, point: P) -> Vec<PrimitiveId> {
+        block_on(self.drawing.hit_test(point))
+    }
+
+    /// Returns the color of the drawing at the given point.
+    ///
+    /// The drawing is rendered the same way as with [`save_png()`](#method.save_png), so this is
+    /// the color that the pixel centered on the point would have at the original size of the
+    /// drawing. Points that nothing has been drawn over have the background color. The turtles
+    /// themselves are not included. This is useful for flood fills, for games that need to know
+    /// what a turtle is standing on, or for checking a drawing in a test.
+    ///
+    /// ```rust
+    /// use turtle::{Drawing, Color};
+    ///
+    /// let mut drawing = Drawing::new();
+    /// drawing.set_background_color("white");
+    /// drawing.draw_circle([0.0, 0.0], 50.0, "blue");
+    ///
+    /// assert_eq!(drawing.color_at([10.0, 20.0]), Color::from("blue"));
+    /// assert_eq!(drawing.color_at([100.0, 0.0]), Color::from("white"));
+    /// ```
+    ///
+    /// Pixels along the edge of a shape are anti-aliased, so their color is a mix of the shape
+    /// and whatever is behind it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the point is infinite or `NaN`.
+    pub fn color_at<P: Into<Point>>(&self, point: P) -> Color {
+        block_on(self.drawing.color_at(point))
+    }
+
+    /// Returns the size of the box that the given text takes up when written with the given
+    /// options, rounded up to the nearest pixel.
+    ///
+    /// This lets you center, wrap, or put a box around text before writing it. The width is the
+    /// distance from the start of the text to the end of its last character. The height goes from
+    /// the [`ascent`] of the font to its [`descent`], so it is the same for any single line of
+    /// text, no matter which characters it contains. Each `\n` in the text starts a new line
+    /// below the previous one.
+    ///
+    /// Text is measured exactly as it is written: characters take the form that fits with the
+    /// characters around them (e.g. in Arabic or Devanagari), accents are placed over the
+    /// character they belong to, and parts of the text written right-to-left (e.g. Arabic or
+    /// Hebrew) are put in the right order.
+    ///
+    /// ```rust
+    /// use turtle::{Drawing, TextOptions};
+    ///
+    /// let drawing = Drawing::new();
+    /// let options = TextOptions {font_size: 24.0, ..TextOptions::default()};
+    ///
+    /// let size = drawing.measure_text("Hello, turtle!", options.clone());
+    /// assert!(size.width > drawing.measure_text("Hello", options.clone()).width);
+    ///
+    /// let two_lines = drawing.measure_text("Hello,\nturtle!", options);
+    /// assert!(two_lines.height > size.height);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the font size is not greater than zero, or if the font cannot be loaded. The
+    /// generic fonts (e.g. [`Font::SansSerif`]) are looked up in the fonts installed on your
+    /// computer.
+    ///
+    /// [`ascent`]: struct.FontMetrics.html#structfield.ascent
+    /// [`descent`]: struct.FontMetrics.html#structfield.descent
+    /// [`Font::SansSerif`]: enum.Font.html#variant.SansSerif
+    pub fn measure_text(&self, text: &str, options: TextOptions) -> Size {
+        block_on(self.drawing.measure_text(text, options))
+    }
+
+    /// Returns the vertical measurements of the font described by the given options.
+    ///
+    /// All of the measurements are relative to the baseline, the line that the bottom of most
+    /// letters sit on.
+    ///
+    /// ```rust
+    /// use turtle::{Drawing, TextOptions};
+    ///
+    /// let drawing = Drawing::new();
+    /// let metrics = drawing.font_metrics(TextOptions::default());
+    /// assert!(metrics.ascent > 0.0);
+    /// assert!(metrics.descent < 0.0);
+    ///
+    /// // Place lines of text this far apart so they don't overlap
+    /// let line_spacing = metrics.line_height();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the font size is not greater than zero, or if the font cannot be loaded.
+    pub fn font_metrics(&self, options: TextOptions) -> FontMetrics {
+        block_on(self.drawing.font_metrics(options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "unstable")]
+    fn waiting_for_events_does_not_block_drawing() {
+        use futures_util::{FutureExt, StreamExt};
+
+        let mut drawing = Drawing::new();
+        let mut events = drawing.event_stream();
+        // No events are ever sent in tests, so the stream has to keep waiting
+        assert_eq!(events.next().now_or_never(), None);
+
+        drawing.set_title("still responding");
+        assert_eq!(drawing.title(), "still responding");
+        assert_eq!(drawing.poll_event(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "unstable")]
+    fn polling_events_never_waits() {
+        let mut drawing = Drawing::new();
+        // No events are ever sent in tests, so this would never return if it waited
+        for _ in 0..10 {
+            assert_eq!(drawing.poll_event(), None);
+        }
+        assert_eq!(drawing.events().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid color: Color { red: NaN, green: 0.0, blue: 0.0, alpha: 0.0 }. See the color module documentation for more information.")]
+    fn rejects_invalid_background_color() {
+        let mut drawing = Drawing::new();
+        drawing.set_background_color(Color {
+            red: ::std::f64::NAN,
+            green: 0.0,
+            blue: 0.0,
+            alpha: 0.0,
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "The size of the drawing must be non-zero")]
+    fn rejects_size_zero() {
+        let mut drawing = Drawing::new();
+
+        drawing.set_size([0, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The size of the minimap must be non-zero")]
+    fn rejects_minimap_size_zero() {
+        let mut drawing = Drawing::new();
+
+        drawing.show_minimap(Corner::TopRight, [100, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid print options")]
+    fn rejects_print_margin_too_large() {
+        let drawing = Drawing::new();
+
+        let _ = drawing.print(PrintOptions {
+            margin: 200.0,
+            ..PrintOptions::default()
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid plotter options")]
+    fn rejects_zero_feed_rate() {
+        let drawing = Drawing::new();
+
+        let _ = drawing.save_gcode("never-created.gcode", PlotterOptions {
+            feed_rate: 0.0,
+            ..PlotterOptions::default()
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "unstable")]
+    fn join_shared_drawing() {
+        let mut drawing = Drawing::new();
+        drawing.share("test-join-shared-drawing").expect("unable to share drawing");
+
+        let mut guest = Drawing::join("test-join-shared-drawing").expect("unable to join drawing");
+        guest.set_background_color("pink");
+        // Requests from different programs may be processed in any order, but requests from the
+        // same program are always processed in order
+        assert_eq!(guest.background_color(), "pink".into());
+        assert_eq!(drawing.background_color(), "pink".into());
+
+        let mut turtle = guest.add_turtle();
+        turtle.forward(100.0);
+        assert_eq!(turtle.position().round(), Point {x: 0.0, y: 100.0});
+    }
+
+    #[test]
+    #[cfg(feature = "unstable")]
+    #[should_panic(expected = "Invalid share name: \"../oops\"")]
+    fn rejects_invalid_share_name() {
+        let mut drawing = Drawing::new();
+
+        let _ = drawing.share("../oops");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid export options")]
+    fn rejects_export_scale_zero() {
+        let drawing = Drawing::new();
+
+        let _ = drawing.save_svg_with("never-created.svg", ExportOptions {
+            scale: 0.0,
+            ..ExportOptions::default()
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid filter: Blur(-1.0)")]
+    fn rejects_negative_blur() {
+        let mut drawing = Drawing::new();
+        drawing.apply_filter(Filter::Blur(-1.0));
+    }
+
+    #[test]
+    fn idle_after_animations_complete() {
+        let mut drawing = Drawing::new();
+        assert!(drawing.is_idle());
+
+        let mut turtle = drawing.add_turtle();
+        turtle.forward(100.0);
+        turtle.right(90.0);
+        assert_eq!(turtle.pending_animations(), 0);
+        assert!(drawing.is_idle());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid clear effect: Fade(-1.0)")]
+    fn rejects_negative_clear_duration() {
+        let mut drawing = Drawing::new();
+        drawing.clear_animated(ClearEffect::Fade(-1.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "The number of symmetry axes must be non-zero")]
+    fn rejects_zero_symmetry_axes() {
+        let mut drawing = Drawing::new();
+        drawing.set_symmetry(0, true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid font size: 0")]
+    fn rejects_zero_font_size() {
+        let drawing = Drawing::new();
+        drawing.measure_text("turtle", TextOptions {font_size: 0.0, ..TextOptions::default()});
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid pen width: -1")]
+    fn rejects_negative_pressure_pen_width() {
+        let mut drawing = Drawing::new();
+        drawing.set_pressure_pen_width(-1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid zoom: -2")]
+    fn rejects_negative_zoom() {
+        let mut drawing = Drawing::new();
+        drawing.set_zoom(-2.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid DPI: 0")]
+    fn rejects_zero_dpi() {
+        let mut drawing = Drawing::new();
+        drawing.set_dpi(0.0);
+    }
+
+    #[test]
+    fn screen_coordinate_system() {
+        let mut drawing = Drawing::new();
+        drawing.set_size((400, 300));
+        drawing.set_coordinate_system(Origin::BottomLeft, true);
+
+        let mut turtle = drawing.add_turtle();
+        turtle.forward(50.0);
+        assert_eq!(turtle.position().round(), Point {x: 200.0, y: 200.0});
+
+        drawing.set_coordinate_system(Origin::TopLeft, false);
+        assert_eq!(turtle.position().round(), Point {x: 200.0, y: 100.0});
+        assert_eq!(drawing.center().round(), Point {x: 200.0, y: 150.0});
+
+        // Turning towards a point still takes the shortest way around on the screen
+        turtle.turn_towards((300.0, 100.0));
+        assert_eq!(turtle.heading().round(), 0.0);
+        turtle.turn_towards((200.0, 200.0));
+        assert_eq!(turtle.heading().round(), 90.0);
+        turtle.forward(100.0);
+        assert_eq!(turtle.position().round(), Point {x: 200.0, y: 200.0});
+    }
+
+    #[test]
+    fn world_coordinates() {
+        let mut drawing = Drawing::new();
+        drawing.set_size((400, 300));
+        drawing.set_world_coordinates(0.0, 0.0, 4.0, 3.0);
+
+        let mut turtle = drawing.add_turtle();
+        assert_eq!(turtle.position(), Point {x: 2.0, y: 1.5});
+        turtle.forward(1.5);
+        assert_eq!(turtle.position().round(), Point {x: 2.0, y: 3.0});
+
+        // Flipping the y axis measures headings clockwise, but turning still looks the same
+        drawing.set_world_coordinates(0.0, 3.0, 4.0, 0.0);
+        assert_eq!(turtle.position().round(), Point {x: 2.0, y: 0.0});
+        assert_eq!(turtle.heading().round(), 270.0);
+        turtle.right(45.0);
+        assert_eq!(turtle.heading().round(), 315.0);
+        turtle.set_heading(90.0);
+        turtle.forward(1.0);
+        assert_eq!(turtle.position().round(), Point {x: 2.0, y: 1.0});
+
+        // Different scales for each axis keep angles and distances in world coordinates
+        drawing.set_world_coordinates(-1.0, -1.0, 1.0, 1.0);
+        turtle.go_to((0.0, 0.0));
+        turtle.set_heading(45.0);
+        assert_eq!(turtle.heading().round(), 45.0);
+        turtle.forward(2.0f64.sqrt());
+        assert_eq!((turtle.position() * 10.0).round(), Point {x: 10.0, y: 10.0});
+        turtle.circle(0.5);
+        assert_eq!((turtle.position() * 10.0).round(), Point {x: 10.0, y: 10.0});
+        assert_eq!(turtle.heading().round(), 45.0);
+
+        // Choosing a coordinate system stops using the world coordinates
+        drawing.set_coordinate_system(Origin::Center, true);
+        assert_eq!(drawing.world_coordinates(), None);
+        assert_eq!(turtle.position().round(), Point {x: 200.0, y: 150.0});
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid world coordinates: (0, 0) to (0, 1)")]
+    fn rejects_empty_world_coordinates() {
+        let mut drawing = Drawing::new();
+        drawing.set_world_coordinates(0.0, 0.0, 0.0, 1.0);
+    }
+
+    #[test]
+    fn import_svg_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("import-test.svg");
+        std::fs::write(&path, r#"
+            <svg xmlns="http://www.w3.org/2000/svg" width="100" height="50">
+                <rect x="0" y="0" width="20" height="10" fill="red"/>
+            </svg>
+        "#).unwrap();
+
+        let mut drawing = Drawing::new();
+        let res = drawing.import_svg_file(&path, ImportTransform {
+            center: [100.0, 0.0].into(),
+            scale: 2.0,
+        });
+        res.unwrap();
+
+        let (vertices, indices) = drawing.export_mesh();
+        assert_eq!(indices.len(), 2 * 3);
+        let bounds = crate::Rect::bounding(vertices.iter().map(|v| [v.position[0] as f64, v.position[1] as f64]));
+        assert_eq!(bounds, Some(crate::Rect::from_points([0.0, 50.0], [40.0, 30.0])));
+        assert!(vertices.iter().all(|v| v.color == [1.0, 0.0, 0.0, 1.0]));
+
+        let missing = drawing.import_svg_file("does-not-exist.svg", ImportTransform::default());
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn import_svg_file_with_holes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("import-holes-test.svg");
+        // Two squares going around in the same direction, one inside of the other
+        let square = "M 0 0 H 40 V 40 H 0 Z M 10 10 H 30 V 30 H 10 Z";
+        std::fs::write(&path, format!(r#"
+            <svg xmlns="http://www.w3.org/2000/svg" width="40" height="40">
+                <path d="{0}" fill="blue" fill-rule="evenodd"/>
+                <path d="{0}" fill="red" transform="translate(0 40)"/>
+            </svg>
+        "#, square)).unwrap();
+
+        let mut drawing = Drawing::new();
+        drawing.import_svg_file(&path, ImportTransform::default()).unwrap();
+
+        let primitives = drawing.primitives();
+        assert_eq!(primitives.len(), 2);
+        match &primitives[0] {
+            Primitive::Path {subpaths, fill_color, fill_rule} => {
+                assert_eq!(subpaths.len(), 2);
+                assert_eq!(*fill_color, Color::rgb(0.0, 0.0, 255.0));
+                assert_eq!(*fill_rule, crate::FillRule::EvenOdd);
+            },
+            prim => panic!("expected a path, found {:?}", prim),
+        }
+
+        // The hole is only left empty with the even-odd fill rule
+        assert!(drawing.hit_test([0.0, 0.0]).is_empty());
+        assert_eq!(drawing.hit_test([15.0, 0.0]).len(), 1);
+        assert_eq!(drawing.hit_test([0.0, -40.0]).len(), 1);
+    }
+
+    #[test]
+    fn save_and_load_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session-test.json");
+
+        let mut drawing = Drawing::new();
+        drawing.set_title("Saved");
+        let mut turtle = drawing.add_turtle();
+        turtle.set_pen_color("blue");
+        turtle.set_pen_dash_pattern(&[5.0, 5.0]);
+        turtle.forward(100.0);
+        turtle.right(90.0);
+        turtle.forward(50.0);
+        let primitives = drawing.primitives();
+        drawing.save(&path).unwrap();
+
+        let mut drawing = Drawing::new();
+        let turtle = drawing.add_turtle();
+        let res = drawing.load(&path);
+        res.unwrap();
+
+        assert_eq!(drawing.title(), "Saved");
+        assert_eq!(drawing.primitives(), primitives);
+        assert_eq!(turtle.position().round(), Point {x: 50.0, y: 100.0});
+        assert_eq!(turtle.heading(), 0.0);
+        assert_eq!(turtle.pen_color(), "blue".into());
+
+        assert!(drawing.load("does-not-exist.json").is_err());
+        assert_eq!(drawing.primitives(), primitives);
+    }
+
+    #[test]
+    fn saves_png() {
+        let mut drawing = Drawing::new();
+        drawing.set_size([40, 30]);
+        let mut turtle = drawing.add_turtle();
+        turtle.forward(10.0);
+
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("save-png.png");
+        drawing.save_png_with(&path, ExportOptions {scale: 2.0, ..ExportOptions::default()}).unwrap();
+        let data = std::fs::read(&path).unwrap();
+
+        assert!(data.starts_with(b"\x89PNG\r\n\x1a\n"));
+        // The width and height are the first fields of the header chunk
+        assert_eq!(&data[16..24], &[0, 0, 0, 80, 0, 0, 0, 60]);
+    }
+
+    #[test]
+    fn records_gif() {
+        let mut drawing = Drawing::new();
+        drawing.set_size([40, 30]);
+        let mut turtle = drawing.add_turtle();
+
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("record-gif.gif");
+        drawing.start_recording_gif(&path, 50.0).unwrap();
+        assert!(drawing.start_recording_gif(&path, 50.0).is_err());
+        turtle.forward(10.0);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        drawing.stop_recording().unwrap();
+        assert!(drawing.stop_recording().is_err());
+
+        let data = std::fs::read(&path).unwrap();
+
+        assert!(data.starts_with(b"GIF89a"));
+        // The width and height come right after the signature
+        assert_eq!(&data[6..10], &[40, 0, 30, 0]);
+        assert_eq!(data.last(), Some(&0x3B));
+    }
+
+    #[test]
+    fn records_commands() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("record-commands.turtlelog");
+        drawing.record_to(&path).unwrap();
+        let empty_len = std::fs::metadata(&path).unwrap().len();
+        turtle.forward(10.0);
+        turtle.right(90.0);
+        let len = std::fs::metadata(&path).unwrap().len();
+
+        let data = std::fs::read(&path).unwrap();
+
+        assert!(data.starts_with(b"TURTLOG1"));
+        assert!(len > empty_len);
+        // A directory cannot be used as a command log
+        assert!(drawing.record_to(std::env::temp_dir()).is_err());
+    }
+
+    #[test]
+    fn lists_drawn_primitives_in_order() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        turtle.set_speed("instant");
+        turtle.set_fill_color("red");
+        turtle.begin_fill();
+        for _ in 0..3 {
+            turtle.forward(100.0);
+            turtle.right(120.0);
+        }
+        turtle.end_fill();
+        drawing.draw_circle([0.0, 0.0], 10.0, "blue");
+
+        let primitives = drawing.primitives();
+        assert_eq!(primitives.len(), 5, "{:?}", primitives);
+        match &primitives[0] {
+            Primitive::Polygon {points, fill_color, ..} => {
+                assert_eq!(points.len(), 4);
+                assert_eq!(*fill_color, Color::from("red"));
+            },
+            primitive => panic!("expected the filled polygon first, got {:?}", primitive),
+        }
+        assert!(primitives[1..4].iter().all(|prim| matches!(prim, Primitive::Line {..})), "{:?}", primitives);
+        assert!(matches!(primitives[4], Primitive::Circle {radius, ..} if radius == 10.0), "{:?}", primitives);
+    }
+
+    #[test]
+    fn hit_tests_filled_shapes_and_dashed_lines() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        turtle.set_speed("instant");
+        turtle.begin_fill();
+        for _ in 0..4 {
+            turtle.forward(100.0);
+            turtle.right(90.0);
+        }
+        turtle.end_fill();
+        turtle.pen_up();
+        turtle.go_to([0.0, -50.0]);
+        turtle.pen_down();
+        turtle.set_pen_size(4.0);
+        turtle.set_pen_dash_pattern(&[10.0, 10.0]);
+        turtle.forward(40.0);
+
+        // Inside the square
+        let square = drawing.hit_test([50.0, 50.0]);
+        assert_eq!(square.len(), 1);
+        assert!(matches!(drawing.primitive(square[0]), Some(Primitive::Polygon {..})));
+        // Just inside the edge of the square, so also on the outline drawn around it
+        assert_eq!(drawing.hit_test([99.8, 50.0]).len(), 2);
+        // In a dash and in a gap of the dashed line
+        assert_eq!(drawing.hit_test([1.0, -45.0]).len(), 1);
+        assert_eq!(drawing.hit_test([0.0, -35.0]), vec![]);
+
+        drawing.clear();
+        assert_eq!(drawing.primitive(square[0]), None);
+    }
+
+    #[test]
+    fn reads_colors_at_points() {
+        let mut drawing = Drawing::new();
+        drawing.set_background_color("white");
+        let mut turtle = drawing.add_turtle();
+        turtle.set_speed("instant");
+        turtle.set_pen_color("red");
+        turtle.set_pen_size(4.0);
+        turtle.forward(100.0);
+
+        assert_eq!(drawing.color_at([0.0, 50.0]), Color::from("red"));
+        assert_eq!(drawing.color_at([50.0, 50.0]), Color::from("white"));
+        // Half of this pixel is covered by the line, so it is a mix of red and white
+        let edge = drawing.color_at([2.0, 50.0]);
+        let red = Color::from("red");
+        assert!(edge.green > red.green + 50.0 && edge.green < 205.0, "{:?}", edge);
+    }
+
+    #[test]
+    fn exports_exact_circles() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        turtle.circle(50.0);
+        drawing.draw_circle([0.0, 0.0], 20.0, "blue");
+
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("exact-circles.svg");
+        drawing.save_svg(&path).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap();
+
+        // The lines drawn while the turtle was moving are replaced by the circle
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert!(!svg.contains("<line"));
+    }
+
+    #[test]
+    fn exports_svg_without_a_file() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        turtle.forward(100.0);
+
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("in-memory.svg");
+        drawing.save_svg(&path).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+
+        let svg = block_on(drawing.drawing.export_svg(crate::ExportOptions::default()));
+        assert_eq!(svg, saved);
+        assert!(svg.contains("<line"));
+    }
+
+    #[test]
+    fn exports_exact_curves() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        turtle.bezier_to([100.0, 0.0], [-100.0, 200.0], [0.0, 200.0]);
+
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("exact-curves.svg");
+        drawing.save_svg(&path).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap();
+
+        assert_eq!(svg.matches(" C ").count(), 1);
+        assert!(!svg.contains("<line"));
+    }
+
+    #[test]
+    fn writes_text_in_pen_color() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        turtle.pen_up();
+        turtle.set_pen_color(Color::rgb(255.0, 0.0, 0.0));
+        turtle.write_with(24.0, crate::TextAlign::Right, "Hi");
+        assert_eq!(turtle.position(), Point::origin());
+
+        // Right-aligned text ends at the turtle and sits on its baseline
+        let (vertices, _) = drawing.export_mesh();
+        let bounds = crate::Rect::bounding(vertices.iter().map(|v| [v.position[0] as f64, v.position[1] as f64]))
+            .expect("text should have been drawn");
+        assert!(bounds.max.x <= 0.0 && bounds.min.x < -10.0);
+        assert!(bounds.max.y > 10.0 && bounds.min.y >= -1.0);
+        assert!(vertices.iter().all(|v| v.color == [1.0, 0.0, 0.0, 1.0]));
+
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("written-text.svg");
+        drawing.save_svg(&path).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap();
+        assert!(svg.contains("<path") && svg.contains("rgba(255, 0, 0, 1)"), "{}", svg);
+    }
+
+    #[test]
+    fn clears_stamps() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        turtle.set_shape(crate::Shape::Square);
+        let triangles = |drawing: &Drawing| drawing.export_mesh().1.len() / 3;
+
+        // The filled square plus a line along each side
+        let first = turtle.stamp();
+        assert_eq!(triangles(&drawing), 2 + 4 * 2);
+        turtle.forward(100.0);
+        let second = turtle.stamp();
+        assert_ne!(first, second);
+        assert_eq!(triangles(&drawing), 2 * (2 + 4 * 2) + 2);
+
+        // Stamps of other turtles are left alone
+        let mut other = drawing.add_turtle();
+        other.clear_stamp(first);
+        other.clear_stamps();
+        assert_eq!(triangles(&drawing), 2 * (2 + 4 * 2) + 2);
+
+        turtle.clear_stamp(first);
+        assert_eq!(triangles(&drawing), 2 + 4 * 2 + 2);
+        // Clearing a stamp twice does nothing
+        turtle.clear_stamp(first);
+        assert_eq!(triangles(&drawing), 2 + 4 * 2 + 2);
+
+        // Only the line is left
+        turtle.stamp();
+        turtle.clear_stamps();
+        assert_eq!(triangles(&drawing), 2);
+        turtle.clear_stamp(second);
+        assert_eq!(triangles(&drawing), 2);
+    }
+
+    #[test]
+    fn undoes_drawings() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        let triangles = |drawing: &Drawing| drawing.export_mesh().1.len() / 3;
+
+        turtle.forward(100.0);
+        turtle.right(90.0);
+        turtle.forward(100.0);
+        // The classic shape is a triangle with a line along each side
+        let stamp = turtle.stamp();
+        assert_eq!(triangles(&drawing), 2 * 2 + 1 + 3 * 2);
+
+        turtle.undo();
+        assert_eq!(triangles(&drawing), 2 * 2);
+        // The stamp was already removed by undoing
+        turtle.clear_stamp(stamp);
+        turtle.undo_n(2);
+        assert_eq!(triangles(&drawing), 2);
+        assert_eq!(turtle.position().round(), Point {x: 0.0, y: 100.0});
+        assert_eq!(turtle.heading().round(), 90.0);
+
+        // Undoing a movement while filling also removes its point from the fill
+        turtle.begin_fill();
+        turtle.right(90.0);
+        turtle.forward(100.0);
+        turtle.right(90.0);
+        turtle.forward(100.0);
+        turtle.undo_n(2);
+        turtle.end_fill();
+        // The fill only has two points left, so nothing is drawn for it
+        assert_eq!(triangles(&drawing), 2 + 2);
+
+        // Nothing from before the drawings were cleared can be undone
+        drawing.clear();
+        turtle.undo_n(10);
+        assert_eq!(turtle.position().round(), Point {x: 100.0, y: 100.0});
+        assert_eq!(triangles(&drawing), 0);
+    }
+
+    #[test]
+    fn stamps_loaded_images() {
+        use crate::renderer_server::ImageData;
+
+        // A red pixel on the left and a blue pixel on the right
+        let image = ImageData {
+            width: 2,
+            height: 1,
+            pixels: vec![255, 0, 0, 255, 0, 0, 255, 255],
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("load-image.png");
+        std::fs::write(&path, image.encode_png()).unwrap();
+
+        let mut drawing = Drawing::new();
+        let res = drawing.load_image(&path);
+        let handle = res.unwrap();
+        assert_eq!((handle.width(), handle.height()), (2, 1));
+
+        let mut turtle = drawing.add_turtle();
+        turtle.set_shape(crate::Shape::Image(handle));
+        assert_eq!(turtle.shape(), crate::Shape::Image(handle));
+        turtle.set_shape_size(10.0);
+        let stamp = turtle.stamp_image(handle);
+
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("stamped-image.png");
+        drawing.save_png_with(&path, ExportOptions {
+            region: crate::ExportRegion::Content {margin: 0.0},
+            scale: 1.0,
+        }).unwrap();
+        let data = std::fs::read(&path).unwrap();
+
+        // The image is scaled by the shape size and centered on the turtle
+        let exported = ImageData::decode_png(&data[..]).unwrap();
+        assert_eq!((exported.width, exported.height), (20, 10));
+        assert_eq!(exported.pixel(2, 5), [255, 0, 0, 255]);
+        assert_eq!(exported.pixel(17, 5), [0, 0, 255, 255]);
+
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("stamped-image.svg");
+        drawing.save_svg(&path).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap();
+        assert!(svg.contains("href=\"data:image/png;base64,iVBORw0KGgo"), "{}", svg);
+
+        // Images are stamps, so they can be cleared just like any other stamp
+        turtle.clear_stamp(stamp);
+        drawing.save_svg(&path).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap();
+        assert!(!svg.contains("<image"), "{}", svg);
+
+        assert!(drawing.load_image("does-not-exist.png").is_err());
+
+        // A handle can only be used with the drawing that the image was loaded into
+        let mut other = Drawing::new();
+        let mut other_turtle = other.add_turtle();
+        assert_eq!(other_turtle.try_stamp_image(handle), Err(crate::Error::UnknownImage(handle.id.index())));
+    }
+
+    #[test]
+    fn sets_icon_from_bytes() {
+        use crate::renderer_server::ImageData;
+
+        let image = ImageData {width: 1, height: 1, pixels: vec![0, 128, 0, 255]};
+
+        let mut drawing = Drawing::new();
+        let icon = drawing.load_image_from_bytes(&image.encode_png()).unwrap();
+        assert_eq!((icon.width(), icon.height()), (1, 1));
+        drawing.set_icon(icon);
+        assert_eq!(drawing.icon(), Some(icon));
+        drawing.reset_icon();
+        assert_eq!(drawing.icon(), None);
+
+        assert!(drawing.load_image_from_bytes(b"not a png").is_err());
+    }
+
+    #[test]
+    fn clears_regions() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        turtle.forward(100.0);
+        turtle.stamp();
+        drawing.draw_circle([200.0, 200.0], 10.0, "red");
+        let before = drawing.export_mesh().0.len();
+
+        // Only the circle is entirely inside
+        drawing.clear_region(Rect::from_points([150.0, 150.0], [250.0, 250.0]));
+        let after = drawing.export_mesh().0.len();
+        assert!(after > 0 && after < before);
+
+        // The fill that is in progress is kept even though it is inside
+        turtle.begin_fill();
+        turtle.right(90.0);
+        turtle.forward(50.0);
+        turtle.right(90.0);
+        let area = Rect::from_points([-100.0, -100.0], [300.0, 300.0]);
+        drawing.clear_region(area);
+        turtle.forward(50.0);
+        turtle.end_fill();
+        assert!(!drawing.export_mesh().0.is_empty());
+
+        drawing.clear_region(area);
+        assert!(drawing.export_mesh().0.is_empty());
+        // Undoing commands whose drawings were cleared does not bring them back
+        turtle.undo_n(3);
+        turtle.clear_stamps();
+        assert!(drawing.export_mesh().0.is_empty());
+    }
+
+    #[test]
+    fn holds_commands_while_paused() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+
+        drawing.pause();
+        // Pausing again does nothing
+        drawing.pause();
+        drawing.set_background_color("red");
+        turtle.set_pen_size(5.0);
+        drawing.resume();
+
+        assert_eq!(drawing.background_color(), "red".into());
+        assert_eq!(turtle.pen_size(), 5.0);
+        // Resuming again does nothing
+        drawing.resume();
+        turtle.forward(100.0);
+        assert_eq!(turtle.position().round(), Point {x: 0.0, y: 100.0});
+    }
+
+    #[test]
+    fn waits_for_steps() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+
+        drawing.enable_step_mode();
+        drawing.step();
+        drawing.step();
+        turtle.forward(100.0);
+        turtle.right(90.0);
+
+        // Waits for a step that is only taken after disabling step mode
+        turtle.set_pen_size(5.0);
+        drawing.set_background_color("red");
+        drawing.disable_step_mode();
+        assert_eq!(turtle.pen_size(), 5.0);
+        assert_eq!(drawing.background_color(), "red".into());
+
+        turtle.forward(100.0);
+        assert_eq!(turtle.position().round(), Point {x: 100.0, y: 100.0});
+    }
+
+    #[test]
+    fn drawings_are_independent() {
+        let mut first = Drawing::new();
+        let mut second = Drawing::new();
+
+        first.set_title("First");
+        second.set_title("Second");
+        second.set_background_color("blue");
+        let mut turtle = second.add_turtle();
+        turtle.forward(100.0);
+
+        assert_eq!(first.title(), "First");
+        assert_eq!(second.title(), "Second");
+        assert_eq!(first.background_color(), Color::from("white"));
+        assert_eq!(first.add_turtle().position(), Point::origin());
+        assert_eq!(turtle.position().round(), Point {x: 0.0, y: 100.0});
+    }
+
+    #[test]
+    fn draws_background_images() {
+        use crate::renderer_server::ImageData;
+
+        // A red pixel on the left and a blue pixel on the right
+        let image = ImageData {
+            width: 2,
+            height: 1,
+            pixels: vec![255, 0, 0, 255, 0, 0, 255, 255],
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("background-image.png");
+        std::fs::write(&path, image.encode_png()).unwrap();
+
+        let mut drawing = Drawing::new();
+        drawing.set_size([40, 30]);
+        let res = drawing.set_background_image(&path, crate::BackgroundMode::Stretch);
+        res.unwrap();
+        // The background image does not move with the view
+        drawing.set_center([100.0, 100.0]);
+
+        let save_png = |drawing: &Drawing| {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("saved-background.png");
+            drawing.save_png(&path).unwrap();
+            let data = std::fs::read(&path).unwrap();
+            ImageData::decode_png(&data[..]).unwrap()
+        };
+        let exported = save_png(&drawing);
+        assert_eq!((exported.width, exported.height), (40, 30));
+        assert_eq!(exported.pixel(0, 0), [255, 0, 0, 255]);
+        assert_eq!(exported.pixel(39, 29), [0, 0, 255, 255]);
+
+        drawing.clear_background_image();
+        assert_eq!(save_png(&drawing).pixel(0, 0), [255, 255, 255, 255]);
+
+        assert!(drawing.set_background_image("does-not-exist.png", crate::BackgroundMode::Tile).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid font size: -1")]
+    fn rejects_invalid_write_font_size() {
+        let mut turtle = Turtle::new();
+        turtle.write_with(-1.0, crate::TextAlign::Left, "turtle");
+    }
+
+    #[test]
+    fn fills_along_curves() {
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        turtle.pen_up();
+        turtle.begin_fill();
+        turtle.quad_bezier_to([100.0, 100.0], [200.0, 0.0]);
+        turtle.end_fill();
+
+        let (vertices, indices) = drawing.export_mesh();
+        let points: Vec<Point> = vertices.iter()
+            .map(|v| [v.position[0] as f64, v.position[1] as f64].into())
+            .collect();
+        let area: f64 = indices.chunks(3).map(|tri| {
+            let [a, b, c] = [points[tri[0] as usize], points[tri[1] as usize], points[tri[2] as usize]];
+            (b - a).cross(c - a).abs() / 2.0
+        }).sum();
+
+        // The area under a parabola is two thirds of the area of the rectangle around it
+        let expected = 2.0 / 3.0 * 200.0 * 50.0;
+        assert!((area - expected).abs() / expected < 1e-2, "{} vs {}", area, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid frame rate: 0")]
+    fn rejects_invalid_recording_fps() {
+        let mut drawing = Drawing::new();
+        drawing.start_recording_gif("never-created.gif", 0.0).unwrap();
+    }
+
+    #[test]
+    fn ignores_center_nan_inf() {
+        let center = Point {x: 5.0, y: 10.0};
+
+        let mut drawing = Drawing::new();
+        drawing.set_center(center);
+
+        drawing.set_center([::std::f64::NAN, 0.0]);
+        drawing.set_center([0.0, ::std::f64::NAN]);
+        drawing.set_center([::std::f64::NAN, ::std::f64::NAN]);
+        drawing.set_center([::std::f64::INFINITY, 0.0]);
+        drawing.set_center([0.0, ::std::f64::INFINITY]);
+        drawing.set_center([::std::f64::INFINITY, ::std::f64::INFINITY]);
+        drawing.set_center([-::std::f64::INFINITY, 0.0]);
+        drawing.set_center([0.0, -::std::f64::INFINITY]);
+        drawing.set_center([-::std::f64::INFINITY, -::std::f64::INFINITY]);
 
         assert_eq!(drawing.center(), center);
     }
+
+    #[test]
+    fn layers() {
+        let mut drawing = Drawing::new();
+        let layer = drawing.add_layer();
+        let mut turtle = drawing.add_turtle();
+        turtle.set_pen_color("white");
+        turtle.set_layer(layer);
+        turtle.forward(100.0);
+        turtle.set_pen_color("black");
+        turtle.set_layer(LayerId::BASE);
+        turtle.forward(100.0);
+
+        let first_color = |drawing: &Drawing| {
+            let (vertices, indices) = drawing.export_mesh();
+            vertices[indices[0] as usize].color
+        };
+
+        // The new layer is drawn on top, even though its line was drawn first
+        assert_eq!(first_color(&drawing), [0.0, 0.0, 0.0, 1.0]);
+        drawing.lower_layer(layer);
+        assert_eq!(first_color(&drawing), [1.0, 1.0, 1.0, 1.0]);
+        drawing.raise_layer(layer);
+        assert_eq!(first_color(&drawing), [0.0, 0.0, 0.0, 1.0]);
+
+        // Only the line in the visible layer is left
+        drawing.set_layer_visible(LayerId::BASE, false);
+        assert!(!drawing.is_layer_visible(LayerId::BASE));
+        assert_eq!(first_color(&drawing), [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(drawing.export_mesh().1.len() / 3, 2);
+    }
+
+    #[test]
+    fn ignores_layers_and_images_from_other_drawings() {
+        use crate::renderer_server::ImageData;
+
+        let mut other = Drawing::new();
+        other.add_layer();
+        let other_layer = other.add_layer();
+        let image = ImageData {width: 1, height: 1, pixels: vec![0, 128, 0, 255]};
+        let other_image = other.load_image_from_bytes(&image.encode_png()).unwrap();
+
+        let mut drawing = Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        drawing.set_layer_visible(other_layer, false);
+        drawing.raise_layer(other_layer);
+        drawing.lower_layer(other_layer);
+        turtle.set_layer(other_layer);
+        turtle.set_shape(crate::Shape::Image(other_image));
+        drawing.set_icon(other_image);
+
+        // None of the calls above changed anything, and the drawing still works afterwards
+        assert_eq!(turtle.layer(), LayerId::BASE);
+        assert_eq!(turtle.shape(), crate::Shape::default());
+        assert_eq!(drawing.icon(), None);
+        assert!(drawing.try_is_layer_visible(other_layer).is_err());
+        turtle.forward(100.0);
+        assert_eq!(drawing.export_mesh().1.len() / 3, 2);
+    }
 }
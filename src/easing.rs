@@ -0,0 +1,154 @@
+use serde::{Serialize, Deserialize};
+
+/// The number of Newton's method iterations used to find where a cubic Bézier easing curve is at
+/// a given point in time
+const NEWTON_ITERATIONS: usize = 8;
+
+/// How close the time found for a cubic Bézier easing curve needs to be before it is used
+const SOLVE_EPSILON: f64 = 1e-7;
+
+/// Changes how the speed of a turtle varies over the course of each movement or rotation
+///
+/// By default, turtles move at a constant speed from the start of each animation to its end
+/// ([`Linear`](#variant.Linear)). Other easings start slowly, end slowly, or both, which can make
+/// animations look smoother. Every easing takes the same total amount of time, so only the speed
+/// within each animation changes. Use
+/// [`Turtle::set_easing()`](struct.Turtle.html#method.set_easing) to change the easing of a
+/// turtle.
+///
+/// ```rust
+/// use turtle::{Turtle, Easing};
+///
+/// let mut turtle = Turtle::new();
+/// turtle.set_easing(Easing::EaseInOut);
+/// turtle.forward(100.0);
+///
+/// // Starts quickly and slows down at the end, just like the CSS "ease" timing function
+/// turtle.set_easing(Easing::CubicBezier {x1: 0.25, y1: 0.1, x2: 0.25, y2: 1.0});
+/// turtle.right(90.0);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Easing {
+    /// The same speed for the entire animation (default)
+    #[default]
+    Linear,
+    /// Starts slowly and speeds up until the end
+    EaseIn,
+    /// Starts quickly and slows down towards the end
+    EaseOut,
+    /// Starts slowly, speeds up in the middle, and slows down again towards the end
+    EaseInOut,
+    /// A custom easing curve, given by the two control points of a cubic Bézier curve that goes
+    /// from (0, 0) to (1, 1)
+    ///
+    /// The x-axis is the fraction of the duration of the animation that has passed and the y-axis
+    /// is how far the turtle has gotten, both from 0.0 to 1.0. This works just like the
+    /// `cubic-bezier()` timing function in CSS, except that every coordinate must be between 0.0
+    /// and 1.0.
+    CubicBezier { x1: f64, y1: f64, x2: f64, y2: f64 },
+}
+
+impl Easing {
+    /// Returns true if this easing can be used for animations
+    pub(crate) fn is_valid(self) -> bool {
+        match self {
+            Easing::CubicBezier {x1, y1, x2, y2} => [x1, y1, x2, y2].iter()
+                .all(|&value| (0.0..=1.0).contains(&value)),
+            _ => true,
+        }
+    }
+
+    /// Returns how far along an animation should be once the given fraction of its duration has
+    /// passed, both from 0.0 to 1.0
+    pub(crate) fn apply(self, t: f64) -> f64 {
+        // The same curves as the CSS timing functions with the same names
+        let (x1, y1, x2, y2) = match self {
+            Easing::Linear => return t,
+            Easing::EaseIn => (0.42, 0.0, 1.0, 1.0),
+            Easing::EaseOut => (0.0, 0.0, 0.58, 1.0),
+            Easing::EaseInOut => (0.42, 0.0, 0.58, 1.0),
+            Easing::CubicBezier {x1, y1, x2, y2} => (x1, y1, x2, y2),
+        };
+
+        let t = t.clamp(0.0, 1.0);
+        let s = solve_bezier(x1, x2, t);
+        bezier(y1, y2, s)
+    }
+}
+
+/// Evaluates one coordinate of a cubic Bézier curve from 0.0 to 1.0 with the given control
+/// coordinates
+fn bezier(c1: f64, c2: f64, s: f64) -> f64 {
+    let inv = 1.0 - s;
+    3.0 * inv * inv * s * c1 + 3.0 * inv * s * s * c2 + s * s * s
+}
+
+/// Finds the parameter at which the given coordinate of the curve is equal to `x`
+///
+/// The coordinate is always increasing since the control coordinates are between 0.0 and 1.0,
+/// so there is exactly one answer.
+fn solve_bezier(c1: f64, c2: f64, x: f64) -> f64 {
+    // Newton's method usually converges very quickly
+    let mut s = x;
+    for _ in 0..NEWTON_ITERATIONS {
+        let error = bezier(c1, c2, s) - x;
+        if error.abs() < SOLVE_EPSILON {
+            return s;
+        }
+
+        let inv = 1.0 - s;
+        let slope = 3.0 * inv * inv * c1 + 6.0 * inv * s * (c2 - c1) + 3.0 * s * s * (1.0 - c2);
+        if slope.abs() < SOLVE_EPSILON {
+            break;
+        }
+        s -= error / slope;
+    }
+
+    // Fall back to bisection where the curve is too flat for Newton's method
+    let (mut low, mut high) = (0.0, 1.0);
+    s = x;
+    while high - low > SOLVE_EPSILON {
+        if bezier(c1, c2, s) < x {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easings_start_and_end_in_place() {
+        let easings = [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::CubicBezier {x1: 0.0, y1: 1.0, x2: 1.0, y2: 0.0},
+        ];
+        for &easing in &easings {
+            assert!(easing.apply(0.0).abs() < 1e-6, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn ease_in_out_is_symmetric() {
+        let easing = Easing::EaseInOut;
+        assert!((easing.apply(0.5) - 0.5).abs() < 1e-6);
+        assert!(easing.apply(0.25) < 0.25);
+        assert!((easing.apply(0.25) + easing.apply(0.75) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn custom_curves_must_stay_in_range() {
+        assert!(Easing::CubicBezier {x1: 0.5, y1: 0.0, x2: 0.5, y2: 1.0}.is_valid());
+        assert!(!Easing::CubicBezier {x1: 0.5, y1: -0.5, x2: 0.5, y2: 1.0}.is_valid());
+        assert!(!Easing::CubicBezier {x1: f64::NAN, y1: 0.0, x2: 0.5, y2: 1.0}.is_valid());
+    }
+}
@@ -0,0 +1,32 @@
+use thiserror::Error;
+use serde::{Serialize, Deserialize};
+
+/// An error reported by the window when it is unable to complete a request
+///
+/// The methods of [`Drawing`] and [`Turtle`] check their arguments before sending anything to
+/// the window, so these errors are only produced when a request comes from somewhere else, like a
+/// program that joined a shared drawing or a WebSocket connection. Instead of closing the window,
+/// the request is ignored and this error is sent back to whoever sent it.
+///
+/// [`Drawing`]: struct.Drawing.html
+/// [`Turtle`]: struct.Turtle.html
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Error {
+    /// The request referred to a turtle that does not exist
+    #[error("there is no turtle with ID {0}")]
+    UnknownTurtle(usize),
+    /// The request referred to an image that has not been loaded into the drawing
+    #[error("there is no image with ID {0}")]
+    UnknownImage(usize),
+    /// The request referred to a layer that has not been added to the drawing
+    #[error("there is no layer with ID {0}")]
+    UnknownLayer(usize),
+    /// The request contained a value that cannot be used, like a position that is not finite
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    /// The request is not allowed over the connection that it was sent on, like a request to save
+    /// a file on the machine running the window that was sent from another machine
+    #[error("request not allowed over this connection: {0}")]
+    NotAllowed(String),
+}
@@ -8,13 +8,22 @@
 //! crate. This module may change or be completely removed in the future. There will definitely
 //! be *some* events API in the future, but it may end up looking different than it does today.
 
+use std::fmt;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
 use serde::{Serialize, Deserialize};
+use futures_util::stream::{self, Stream, StreamExt, LocalBoxStream};
+// There are no windows (and so no window events) in a web browser
+#[cfg(not(target_arch = "wasm32"))]
 use glutin::{
     dpi::{LogicalSize, PhysicalPosition},
     event::{self as glutin_event, WindowEvent, KeyboardInput},
 };
 
 use crate::Point;
+use crate::ipc_protocol::ProtocolClient;
 
 /// Possible events returned from [`Drawing::poll_event()`](../struct.Drawing.html#method.poll_event).
 ///
@@ -26,6 +35,13 @@ pub enum Event {
     /// Sent when a keyboard key is pressed or released
     Key(Key, PressedState),
 
+    /// Sent when a modifier key (e.g. shift or ctrl) is pressed or released
+    ///
+    /// Contains every modifier key that is held down after the change. This is sent before the
+    /// `Key` event for the modifier key itself, so the modifiers are always up to date when any
+    /// other key is pressed.
+    ModifiersChanged(Modifiers),
+
     /// Sent when a mouse button is pressed or released
     MouseButton(MouseButton, PressedState),
 
@@ -35,11 +51,42 @@ pub enum Event {
     /// Coordinates are relative to the center of the window.
     MouseMove(Point),
 
+    /// Sent right after a mouse button is pressed, with the position of the mouse at that time
+    ///
+    /// The position uses the same coordinates as `MouseMove`. This is only sent if the mouse
+    /// has moved over the window at least once, since the position is unknown before that.
+    MouseClick { button: MouseButton, position: Point },
+
+    /// Sent right after `MouseMove` while a mouse button is held down
+    ///
+    /// `position` is the new position of the mouse and `delta` is how far it moved since the last
+    /// `MouseMove` event, both using the same coordinates as `MouseMove`. If several buttons are
+    /// held down, `button` is the one that was pressed most recently.
+    MouseDrag { button: MouseButton, position: Point, delta: Point },
+
     /// Sent when the mouse is scrolled. Only sent when the mouse is over the window.
     /// `x` and `y` are in scroll ticks.
     MouseScroll { x: f64, y: f64 },
 
+    /// Sent when a finger or stylus touches the window, moves while touching it, or stops
+    /// touching it
+    ///
+    /// Only sent on platforms and devices with touch input. See [`Touch`](struct.Touch.html) for
+    /// the information included with each touch.
+    Touch(Touch),
+
+    /// Sent when a gamepad (game controller) is connected, disconnected, or used
+    ///
+    /// The number identifies the gamepad that sent the event, so that programs can tell several
+    /// gamepads apart. Gamepads are only read when the "gamepad" feature of this crate is enabled.
+    /// Unlike other events, gamepad events are sent even if the window is not in focus.
+    Gamepad(usize, GamepadEvent),
+
     /// Sent when the window gets resized
+    ///
+    /// The new width and height are in logical pixels, just like
+    /// [`Drawing::size()`](struct.Drawing.html#method.size), which is already updated to the new
+    /// size by the time this event is received.
     WindowResized { width: u32, height: u32 },
 
     /// Sent when the window focus changes
@@ -55,9 +102,10 @@ pub enum Event {
     WindowClosed,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl Event {
     /// Returns `None` if the input event is not a supported variant of `Event`
-    #[cfg_attr(any(feature = "test", test), allow(dead_code))]
+    #[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
     pub(crate) fn from_window_event(
         event: WindowEvent,
         scale_factor: f64,
@@ -75,6 +123,7 @@ impl Event {
                     PressedState::from_state(state),
                 ))
             },
+            WindowEvent::ModifiersChanged(state) => Some(Event::ModifiersChanged(Modifiers::from_state(state))),
             WindowEvent::CursorEntered {..} => Some(Event::WindowCursor(true)),
             WindowEvent::CursorLeft {..} => Some(Event::WindowCursor(false)),
             WindowEvent::CursorMoved {position, ..} => {
@@ -84,6 +133,7 @@ impl Event {
                 MouseButton::from_button(button)?,
                 PressedState::from_state(state),
             )),
+            WindowEvent::Touch(touch) => Some(Event::Touch(Touch::from_touch(touch, to_logical))),
             WindowEvent::Focused(focused) => Some(Event::WindowFocused(focused)),
             WindowEvent::Destroyed => Some(Event::WindowClosed),
 
@@ -93,17 +143,417 @@ impl Event {
             WindowEvent::HoveredFile(_) |
             WindowEvent::HoveredFileCancelled |
             WindowEvent::ReceivedCharacter(_) |
-            WindowEvent::ModifiersChanged(_) |
             WindowEvent::MouseWheel {..} |
             WindowEvent::TouchpadPressure {..} |
             WindowEvent::AxisMotion {..} |
-            WindowEvent::Touch(_) |
             WindowEvent::ScaleFactorChanged {..} |
             WindowEvent::ThemeChanged(_) => None, // Not supported
         }
     }
 }
 
+/// Keeps track of the mouse so that clicks and drags can be reported along with their position
+#[derive(Debug, Default)]
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+pub(crate) struct MouseTracker {
+    /// The last known position of the mouse
+    position: Option<Point>,
+    /// The buttons that are held down, in the order that they were pressed
+    pressed: Vec<MouseButton>,
+}
+
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+impl MouseTracker {
+    /// Updates the state of the mouse and returns the event that should be sent right after the
+    /// given event, if any
+    pub(crate) fn update(&mut self, event: &Event) -> Option<Event> {
+        match *event {
+            Event::MouseMove(position) => {
+                let previous = self.position.replace(position)?;
+                let &button = self.pressed.last()?;
+                Some(Event::MouseDrag {button, position, delta: position - previous})
+            },
+
+            Event::MouseButton(button, PressedState::Pressed) => {
+                self.pressed.retain(|&b| b != button);
+                self.pressed.push(button);
+                self.position.map(|position| Event::MouseClick {button, position})
+            },
+
+            Event::MouseButton(button, PressedState::Released) => {
+                self.pressed.retain(|&b| b != button);
+                None
+            },
+
+            // The window does not find out about buttons released while it is not in focus
+            Event::WindowFocused(false) => {
+                self.pressed.clear();
+                None
+            },
+
+            _ => None,
+        }
+    }
+}
+
+/// An asynchronous stream of every event that takes place in the window, returned from
+/// [`Drawing::event_stream()`](../struct.Drawing.html#method.event_stream)
+///
+/// This implements the `Stream` trait from the [`futures`] crate, so it can be used from any
+/// async runtime. Unlike calling [`Drawing::poll_event()`](../struct.Drawing.html#method.poll_event)
+/// in a loop, waiting for the next item of the stream does not use up any CPU until an event
+/// actually occurs. The stream ends once the window closes.
+///
+/// It is safe to stop waiting for the next event (e.g. when another branch of `select!` finishes
+/// first), since the stream keeps waiting in the background. Dropping the stream while it is
+/// waiting does not lose the next event either. It goes to whatever asks for events next instead.
+///
+/// [`futures`]: https://docs.rs/futures
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))] // Only returned from unstable methods
+pub struct EventStream {
+    client: Rc<ProtocolClient>,
+    events: LocalBoxStream<'static, Event>,
+    /// True if the last poll of `events` is still waiting for the response to a `NextEvent`
+    /// request
+    is_waiting: bool,
+}
+
+impl fmt::Debug for EventStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventStream").finish()
+    }
+}
+
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+impl EventStream {
+    /// Creates a stream that waits for events using the given client
+    ///
+    /// The client should not be used for anything else since it will always be waiting for the
+    /// response to its next request.
+    pub(crate) fn new(client: ProtocolClient) -> Self {
+        let client = Rc::new(client);
+        let events = stream::unfold(client.clone(), |client| async move {
+            let event = client.next_event().await?;
+            Some((event, client))
+        });
+
+        Self {client, events: events.boxed_local(), is_waiting: false}
+    }
+}
+
+impl Stream for EventStream {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let poll = self.events.poll_next_unpin(cx);
+        self.is_waiting = poll.is_pending();
+        poll
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        // Otherwise the server would still send the next event to this stream
+        if self.is_waiting {
+            self.client.cancel_next_event();
+        }
+    }
+}
+
+/// A finger or stylus touching the window, sent as part of [`Event::Touch`]
+///
+/// Pressure and tilt are only available on some platforms (e.g. Windows and iOS) and devices. To
+/// have the pressure control how thick lines are, use
+/// [`Drawing::set_pressure_pen_width()`](../struct.Drawing.html#method.set_pressure_pen_width).
+///
+/// [`Event::Touch`]: enum.Event.html#variant.Touch
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Touch {
+    /// Identifies the finger or stylus, stays the same from when it starts touching the window
+    /// until it stops
+    pub id: u64,
+    /// Whether this touch just started, moved, or ended
+    pub phase: TouchPhase,
+    /// The position of the touch, using the same coordinates as
+    /// [`Event::MouseMove`](enum.Event.html#variant.MouseMove)
+    pub position: Point,
+    /// How hard the window is being pressed, from 0.0 (not at all) to 1.0 (as hard as possible)
+    pub pressure: Option<f64>,
+    /// The angle of a stylus away from being perpendicular to the screen, in degrees
+    ///
+    /// This is 0.0 when the stylus is held straight up and 90.0 when it lies flat.
+    pub tilt: Option<f64>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Touch {
+    #[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+    fn from_touch(
+        touch: glutin_event::Touch,
+        to_logical: impl FnOnce(PhysicalPosition<f64>) -> Point,
+    ) -> Self {
+        let glutin_event::Touch {id, phase, location, force, ..} = touch;
+        let tilt = match force {
+            Some(glutin_event::Force::Calibrated {altitude_angle: Some(altitude), ..}) => {
+                Some(90.0 - altitude.to_degrees())
+            },
+            _ => None,
+        };
+
+        Self {
+            id,
+            phase: TouchPhase::from_phase(phase),
+            position: to_logical(location),
+            pressure: force.map(|force| force.normalized()),
+            tilt,
+        }
+    }
+}
+
+/// The modifier keys that are held down, sent as part of [`Event::ModifiersChanged`]
+///
+/// Keep the most recent value around to check whether a modifier is held down when another key
+/// is pressed:
+///
+/// ```rust,no_run
+/// use turtle::{Drawing, Event};
+/// use turtle::event::{Key, Modifiers, PressedState};
+///
+/// fn main() {
+///     let mut drawing = Drawing::new();
+///     let mut turtle = drawing.add_turtle();
+///
+///     let mut modifiers = Modifiers::default();
+///     loop {
+///         for event in drawing.events() {
+///             match event {
+///                 Event::ModifiersChanged(new_modifiers) => modifiers = new_modifiers,
+///                 // Shift + Up moves further
+///                 Event::Key(Key::UpArrow, PressedState::Pressed) if modifiers.shift => turtle.forward(50.0),
+///                 Event::Key(Key::UpArrow, PressedState::Pressed) => turtle.forward(10.0),
+///                 _ => {},
+///             }
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Event::ModifiersChanged`]: enum.Event.html#variant.ModifiersChanged
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Modifiers {
+    /// Either shift key
+    pub shift: bool,
+    /// Either control key
+    pub ctrl: bool,
+    /// Either alt key (the option key on macOS)
+    pub alt: bool,
+    /// The "logo" key (the Windows key on Windows and the command key on macOS)
+    pub logo: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Modifiers {
+    #[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+    fn from_state(state: glutin_event::ModifiersState) -> Self {
+        Self {
+            shift: state.shift(),
+            ctrl: state.ctrl(),
+            alt: state.alt(),
+            logo: state.logo(),
+        }
+    }
+}
+
+/// The stage of a [`Touch`](struct.Touch.html)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TouchPhase {
+    /// The finger or stylus just started touching the window
+    Started,
+    /// The finger or stylus moved while touching the window
+    Moved,
+    /// The finger or stylus stopped touching the window
+    Ended,
+    /// The touch was interrupted, e.g. because the window lost focus
+    Cancelled,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TouchPhase {
+    #[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+    fn from_phase(phase: glutin_event::TouchPhase) -> Self {
+        match phase {
+            glutin_event::TouchPhase::Started => TouchPhase::Started,
+            glutin_event::TouchPhase::Moved => TouchPhase::Moved,
+            glutin_event::TouchPhase::Ended => TouchPhase::Ended,
+            glutin_event::TouchPhase::Cancelled => TouchPhase::Cancelled,
+        }
+    }
+}
+
+/// Something that happened to a gamepad, sent as part of [`Event::Gamepad`]
+///
+/// [`Event::Gamepad`]: enum.Event.html#variant.Gamepad
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GamepadEvent {
+    /// The gamepad was plugged in or turned on
+    Connected,
+    /// The gamepad was unplugged or turned off
+    Disconnected,
+    /// A button on the gamepad was pressed or released
+    Button(GamepadButton, PressedState),
+    /// A stick (or other axis) on the gamepad moved to the given value
+    ///
+    /// The value goes from -1.0 to 1.0 and is 0.0 when the stick is in the middle. For the
+    /// y-axis of each stick, positive values point up, just like in the coordinates of the
+    /// drawing.
+    Axis(GamepadAxis, f64),
+}
+
+/// A button on a gamepad
+///
+/// The buttons are named after their position on the gamepad, since every brand of gamepad
+/// labels its buttons differently. For example, `South` is the "A" button on an Xbox controller
+/// and the "✕" button on a PlayStation controller.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    /// The bottom button of the four action buttons on the right
+    South,
+    /// The right button of the four action buttons on the right
+    East,
+    /// The top button of the four action buttons on the right
+    North,
+    /// The left button of the four action buttons on the right
+    West,
+    /// The upper button on the back of the gamepad on the left (also called the left bumper)
+    LeftTrigger,
+    /// The lower button on the back of the gamepad on the left
+    LeftTrigger2,
+    /// The upper button on the back of the gamepad on the right (also called the right bumper)
+    RightTrigger,
+    /// The lower button on the back of the gamepad on the right
+    RightTrigger2,
+    /// The small button on the left in the middle of the gamepad (also called "Back" or "Share")
+    Select,
+    /// The small button on the right in the middle of the gamepad (also called "Options")
+    Start,
+    /// The button in the very middle of the gamepad, usually with a logo on it
+    Mode,
+    /// Pressing down on the left stick
+    LeftThumb,
+    /// Pressing down on the right stick
+    RightThumb,
+    /// Up on the directional pad
+    DPadUp,
+    /// Down on the directional pad
+    DPadDown,
+    /// Left on the directional pad
+    DPadLeft,
+    /// Right on the directional pad
+    DPadRight,
+}
+
+/// A stick or other axis on a gamepad
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    /// Moving the left stick left (negative) or right (positive)
+    LeftStickX,
+    /// Moving the left stick down (negative) or up (positive)
+    LeftStickY,
+    /// Moving the right stick left (negative) or right (positive)
+    RightStickX,
+    /// Moving the right stick down (negative) or up (positive)
+    RightStickY,
+    /// An additional axis on the left, usually a trigger on the back of the gamepad
+    LeftZ,
+    /// An additional axis on the right, usually a trigger on the back of the gamepad
+    RightZ,
+    /// A directional pad that reports left (negative) and right (positive) as an axis
+    DPadX,
+    /// A directional pad that reports down (negative) and up (positive) as an axis
+    DPadY,
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadEvent {
+    /// Returns `None` if the input event is not a supported kind of gamepad event
+    #[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+    pub(crate) fn from_gilrs(event: gilrs::EventType) -> Option<Self> {
+        use gilrs::EventType::*;
+        match event {
+            Connected => Some(GamepadEvent::Connected),
+            Disconnected => Some(GamepadEvent::Disconnected),
+            ButtonPressed(button, _) => {
+                Some(GamepadEvent::Button(GamepadButton::from_button(button)?, PressedState::Pressed))
+            },
+            ButtonReleased(button, _) => {
+                Some(GamepadEvent::Button(GamepadButton::from_button(button)?, PressedState::Released))
+            },
+            AxisChanged(axis, value, _) => {
+                Some(GamepadEvent::Axis(GamepadAxis::from_axis(axis)?, value as f64))
+            },
+
+            // Held buttons and analog button values are not supported
+            ButtonRepeated(..) |
+            ButtonChanged(..) |
+            Dropped => None,
+        }
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadButton {
+    fn from_button(button: gilrs::Button) -> Option<Self> {
+        use gilrs::Button::*;
+        #[deny(unreachable_patterns, unused_variables)]
+        Some(match button {
+            South => GamepadButton::South,
+            East => GamepadButton::East,
+            North => GamepadButton::North,
+            West => GamepadButton::West,
+            LeftTrigger => GamepadButton::LeftTrigger,
+            LeftTrigger2 => GamepadButton::LeftTrigger2,
+            RightTrigger => GamepadButton::RightTrigger,
+            RightTrigger2 => GamepadButton::RightTrigger2,
+            Select => GamepadButton::Select,
+            Start => GamepadButton::Start,
+            Mode => GamepadButton::Mode,
+            LeftThumb => GamepadButton::LeftThumb,
+            RightThumb => GamepadButton::RightThumb,
+            DPadUp => GamepadButton::DPadUp,
+            DPadDown => GamepadButton::DPadDown,
+            DPadLeft => GamepadButton::DPadLeft,
+            DPadRight => GamepadButton::DPadRight,
+
+            C |
+            Z |
+            Unknown => return None,
+        })
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadAxis {
+    fn from_axis(axis: gilrs::Axis) -> Option<Self> {
+        use gilrs::Axis::*;
+        #[deny(unreachable_patterns, unused_variables)]
+        Some(match axis {
+            LeftStickX => GamepadAxis::LeftStickX,
+            LeftStickY => GamepadAxis::LeftStickY,
+            RightStickX => GamepadAxis::RightStickX,
+            RightStickY => GamepadAxis::RightStickY,
+            LeftZ => GamepadAxis::LeftZ,
+            RightZ => GamepadAxis::RightZ,
+            DPadX => GamepadAxis::DPadX,
+            DPadY => GamepadAxis::DPadY,
+
+            Unknown => return None,
+        })
+    }
+}
+
 //TODO: Documentation
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum PressedState {
@@ -111,8 +561,9 @@ pub enum PressedState {
     Released,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl PressedState {
-    #[cfg_attr(any(feature = "test", test), allow(dead_code))]
+    #[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
     fn from_state(state: glutin_event::ElementState) -> PressedState {
         match state {
             glutin_event::ElementState::Pressed => PressedState::Pressed,
@@ -260,8 +711,9 @@ pub enum Key {
     Tab,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl Key {
-    #[cfg_attr(any(feature = "test", test), allow(dead_code))]
+    #[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
     fn from_keycode(key: glutin_event::VirtualKeyCode) -> Option<Self> {
         use glutin_event::VirtualKeyCode::*;
         #[deny(unreachable_patterns, unused_variables)]
@@ -451,8 +903,9 @@ pub enum MouseButton {
     RightButton,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl MouseButton {
-    #[cfg_attr(any(feature = "test", test), allow(dead_code))]
+    #[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
     fn from_button(button: glutin_event::MouseButton) -> Option<Self> {
         use glutin_event::MouseButton::*;
         #[deny(unreachable_patterns, unused_variables)]
@@ -464,3 +917,33 @@ impl MouseButton {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_clicks_and_drags() {
+        let mut mouse = MouseTracker::default();
+        let pressed = Event::MouseButton(MouseButton::LeftButton, PressedState::Pressed);
+        let released = Event::MouseButton(MouseButton::LeftButton, PressedState::Released);
+
+        // The position is unknown until the mouse moves
+        assert_eq!(mouse.update(&pressed), None);
+        assert_eq!(mouse.update(&released), None);
+
+        assert_eq!(mouse.update(&Event::MouseMove(Point {x: 10.0, y: 20.0})), None);
+        assert_eq!(mouse.update(&pressed), Some(Event::MouseClick {
+            button: MouseButton::LeftButton,
+            position: Point {x: 10.0, y: 20.0},
+        }));
+        assert_eq!(mouse.update(&Event::MouseMove(Point {x: 15.0, y: 18.0})), Some(Event::MouseDrag {
+            button: MouseButton::LeftButton,
+            position: Point {x: 15.0, y: 18.0},
+            delta: Point {x: 5.0, y: -2.0},
+        }));
+
+        assert_eq!(mouse.update(&released), None);
+        assert_eq!(mouse.update(&Event::MouseMove(Point {x: 0.0, y: 0.0})), None);
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Serialize, Deserialize};
+
+/// The rule used to decide which parts of a fill are inside of the shape being filled
+///
+/// The two rules only give different results for fills whose outline crosses over itself, like a
+/// star drawn in a single stroke. They match the `nonzero` and `evenodd` values of the
+/// `fill-rule` property in SVG.
+///
+/// ```rust
+/// use turtle::{Turtle, FillRule};
+///
+/// let mut turtle = Turtle::new();
+/// // Leave the pentagon in the middle of the star unfilled
+/// turtle.set_fill_rule(FillRule::EvenOdd);
+///
+/// turtle.begin_fill();
+/// for _ in 0..5 {
+///     turtle.forward(200.0);
+///     turtle.right(144.0);
+/// }
+/// turtle.end_fill();
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FillRule {
+    /// A point is inside if the outline goes around it a different number of times in each
+    /// direction (default)
+    ///
+    /// Every area enclosed by the outline of a star is filled, including the middle.
+    #[default]
+    NonZero,
+    /// A point is inside if a line from that point out to infinity crosses the outline an odd
+    /// number of times
+    ///
+    /// Areas enclosed by the outline an even number of times are left unfilled, like the middle
+    /// of a star.
+    EvenOdd,
+}
+
+impl FillRule {
+    /// Returns true if an area that the outline winds around the given number of times is filled
+    pub(crate) fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
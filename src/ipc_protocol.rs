@@ -6,75 +6,129 @@
 mod async_ipc_receiver;
 mod messages;
 mod protocol;
+mod invite;
+mod transport;
+mod tcp;
+mod command_log;
+#[cfg(any(feature = "single-process", feature = "wasm", test))]
+mod in_process;
 
 pub use messages::*;
 pub use protocol::*;
+pub use invite::*;
+pub use transport::*;
+pub use tcp::*;
+pub use command_log::*;
 
 use std::io;
+use std::sync::{Arc, Weak};
 use std::future::Future;
 
 use thiserror::Error;
 use serde::{Serialize, Deserialize};
-use ipc_channel::ipc::{self, IpcOneShotServer, IpcSender, IpcError};
+use tokio::sync::mpsc;
+use parking_lot::Mutex;
+use ipc_channel::ipc::{self, IpcOneShotServer, IpcSender, IpcReceiver, IpcError, OpaqueIpcSender};
 
 use crate::renderer_client::ClientId;
 
 use async_ipc_receiver::AsyncIpcReceiver;
 
+/// The version of the protocol used between the client and the server
+///
+/// This must be increased whenever any of the messages change so that a client and server built
+/// from different versions of this crate refuse to connect instead of misreading each other's
+/// messages. A server built before this version was introduced is seen as version 0.
+const PROTOCOL_VERSION: u32 = 4;
+
 #[derive(Debug, Error)]
-#[error(transparent)]
 pub enum ConnectionError {
+    #[error(transparent)]
     IpcChannelError(#[from] ipc_channel::Error),
+    #[error(transparent)]
     IOError(#[from] io::Error),
+    #[error(transparent)]
     JoinError(#[from] tokio::task::JoinError),
+    #[error("the window uses version {server} of the turtle protocol, but this program uses version {client} (this usually means that the window was started from an outdated build of the program, so try rebuilding it)")]
+    VersionMismatch {client: u32, server: u32},
 }
 
-/// Represents either a sender from the connection handshake or a response
+/// The first message sent by the server over a new connection
+///
+/// The layout of this struct must never change, even when `PROTOCOL_VERSION` does, since it is
+/// what allows a client to find out which version the server is using.
 #[derive(Debug, Serialize, Deserialize)]
-enum HandshakeResponse {
-    /// Represents that the handshake has been completed successfully.
+struct Handshake {
+    /// The `PROTOCOL_VERSION` of the server
+    version: u32,
+    /// The sender that the client should use to send further requests to the server
     ///
-    /// Provides the sender that the client should use to send further requests to the server. This
-    /// message is only ever sent **once** for each client at the beginning when the connection is
-    /// first established.
-    HandshakeFinish(IpcSender<(ClientId, ClientRequest)>),
-
-    /// A response from the server sent in response to a request
-    Response(ClientId, ServerResponse),
+    /// This is opaque so that it can be read no matter which version the server is using.
+    sender: OpaqueIpcSender,
 }
 
-/// The sender for the client side of the IPC connection
+/// A response sent by the server to a client after the handshake
+type ClientResponse = (ClientId, ServerResponse);
+
+/// The sender used by the server to send responses over a single connection
+type ResponseSender = Box<dyn TransportSender<ClientResponse>>;
+
+/// A request sent by a client to the server
+type ClientMessage = (ClientId, ClientRequest);
+
+/// The sender for the client side of the connection
 #[derive(Debug, Clone)]
 pub struct ClientSender {
-    sender: IpcSender<(ClientId, ClientRequest)>,
+    /// Shared by every copy of this sender
+    sender: Arc<Mutex<Box<dyn TransportSender<ClientMessage>>>>,
 }
 
 impl ClientSender {
-    /// Sends a request to the server via IPC
+    /// Sends a request to the server over the transport of the connection
     pub fn send(&self, id: ClientId, req: ClientRequest) -> Result<(), ipc_channel::Error> {
-        self.sender.send((id, req))
+        self.sender.lock().send((id, req))
+    }
+
+    /// Creates a copy of this sender that does not keep the connection open
+    ///
+    /// The server only finds out that the client has finished once every `ClientSender` has been
+    /// dropped.
+    pub fn downgrade(&self) -> WeakClientSender {
+        WeakClientSender {sender: Arc::downgrade(&self.sender)}
     }
 }
 
-/// The receiver for the client side of the IPC connection
+/// A copy of a `ClientSender` that does not keep the connection open
+#[derive(Debug, Clone)]
+pub struct WeakClientSender {
+    sender: Weak<Mutex<Box<dyn TransportSender<ClientMessage>>>>,
+}
+
+impl WeakClientSender {
+    /// Returns the sender, unless every `ClientSender` for this connection has been dropped
+    pub fn upgrade(&self) -> Option<ClientSender> {
+        self.sender.upgrade().map(|sender| ClientSender {sender})
+    }
+}
+
+/// The receiver for the client side of the connection
 #[derive(Debug)]
 pub struct ClientReceiver {
-    receiver: AsyncIpcReceiver<HandshakeResponse>,
+    receiver: Box<dyn TransportReceiver<ClientResponse>>,
 }
 
 impl ClientReceiver {
-    /// Waits for a response from the server via IPC
-    pub async fn recv(&self) -> Result<(ClientId, ServerResponse), IpcError> {
-        let response = self.receiver.recv().await?;
-        match response {
-            HandshakeResponse::Response(id, response) => Ok((id, response)),
-            _ => unreachable!("bug: server did not send response after request"),
-        }
+    /// Waits for a response from the server over the transport of the connection
+    pub async fn recv(&self) -> Result<ClientResponse, IpcError> {
+        self.receiver.recv().await
     }
 }
 
 /// Establishes the client side of the IPC connection by providing a oneshot server name and
 /// completing the handshake
+///
+/// Fails with `ConnectionError::VersionMismatch` if the server is using a different version of
+/// the protocol.
 pub async fn connect_client<S, F>(
     send_ipc_oneshot_name: S,
 ) -> Result<(ClientSender, ClientReceiver), ConnectionError>
@@ -85,35 +139,71 @@ pub async fn connect_client<S, F>(
     let (server, server_name) = IpcOneShotServer::new()?;
     send_ipc_oneshot_name(server_name).await?;
 
-    let (receiver, response): (_, HandshakeResponse) = tokio::task::spawn_blocking(|| {
+    let (receiver, handshake): (IpcReceiver<Handshake>, Handshake) = tokio::task::spawn_blocking(|| {
         server.accept()
     }).await??;
 
-    let sender = match response {
-        HandshakeResponse::HandshakeFinish(sender) => sender,
-        _ => unreachable!("bug: server did not send back Sender at the end of handshake"),
-    };
+    if handshake.version != PROTOCOL_VERSION {
+        return Err(ConnectionError::VersionMismatch {client: PROTOCOL_VERSION, server: handshake.version});
+    }
 
-    let sender = ClientSender {sender};
-    let receiver = ClientReceiver {receiver: AsyncIpcReceiver::new(receiver)};
+    // Every message after the handshake is a response
+    let receiver: IpcReceiver<ClientResponse> = receiver.to_opaque().to();
+    let sender: IpcSender<ClientMessage> = handshake.sender.to();
+    let sender = ClientSender {sender: Arc::new(Mutex::new(Box::new(sender)))};
+    let receiver = ClientReceiver {receiver: Box::new(AsyncIpcReceiver::new(receiver))};
 
     Ok((sender, receiver))
 }
 
+/// Establishes the client side of a connection to a server running in this process by providing
+/// the name that the server should pass to `connect_server()`
+///
+/// With the `single-process` or `wasm` feature, the connection uses channels within this process
+/// instead of IPC channels. Otherwise, this is the same as `connect_client()`.
+pub async fn connect_local_client<S, F>(
+    send_server_name: S,
+) -> Result<(ClientSender, ClientReceiver), ConnectionError>
+    where S: FnOnce(String) -> F,
+          F: Future<Output=io::Result<()>>,
+{
+    #[cfg(any(feature = "single-process", feature = "wasm"))]
+    return in_process::connect_client(send_server_name).await;
+
+    #[cfg(not(any(feature = "single-process", feature = "wasm")))]
+    connect_client(send_server_name).await
+}
+
+/// Identifies a client across every connection to the server
+///
+/// A `ClientId` is only unique within a single connection (i.e. a single client process), so the
+/// server also keeps track of which connection each request arrived on. This is what allows
+/// responses to be sent back to the right place when several processes share the same server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ServerClientId {
+    /// The index of the connection that the client is using
+    connection: usize,
+    /// The ID of the client within that connection
+    client: ClientId,
+}
+
+/// A request received by the server from any connection
+type ServerRequest = Result<(ServerClientId, ClientRequest), IpcError>;
+
 /// Provides the ability to send a single response to a client
 #[derive(Debug)]
 pub struct ServerOneshotSender<'a> {
-    client_id: ClientId,
+    client_id: ServerClientId,
     sender: &'a ServerSender,
 }
 
 impl<'a> ServerOneshotSender<'a> {
-    pub fn new(client_id: ClientId, sender: &'a ServerSender) -> Self {
+    pub fn new(client_id: ServerClientId, sender: &'a ServerSender) -> Self {
         Self {client_id, sender}
     }
 
     /// Returns the ID that this sender would send to
-    pub fn client_id(&self) -> ClientId {
+    pub fn client_id(&self) -> ServerClientId {
         self.client_id
     }
 
@@ -127,45 +217,176 @@ impl<'a> ServerOneshotSender<'a> {
 }
 
 /// The sender for the server side of the IPC connection
+///
+/// Every connection to the server shares the same sender. Responses are sent over the connection
+/// that the client is using.
 #[derive(Debug, Clone)]
 pub struct ServerSender {
-    sender: IpcSender<HandshakeResponse>,
+    /// The sender for each connection, indexed by `ServerClientId::connection`
+    ///
+    /// A connection other than the first is set to `None` once it disconnects so that the indexes
+    /// of the connections after it stay the same.
+    senders: Arc<Mutex<Vec<Option<ResponseSender>>>>,
+    /// Used to forward the requests from each connection to the `ServerReceiver`
+    requests: mpsc::UnboundedSender<ServerRequest>,
 }
 
 impl ServerSender {
     /// Sends a response to the client
     ///
     /// This should only ever be done in response to a request
-    pub fn send(&self, id: ClientId, res: ServerResponse) -> Result<(), ipc_channel::Error> {
-        self.sender.send(HandshakeResponse::Response(id, res))
+    ///
+    /// Only the first connection is required to stay open, so failing to send to any other
+    /// connection is treated as that connection having disconnected. The response is dropped and
+    /// nothing more is sent over that connection.
+    pub fn send(&self, id: ServerClientId, res: ServerResponse) -> Result<(), ipc_channel::Error> {
+        let mut senders = self.senders.lock();
+        let sender = match &senders[id.connection] {
+            Some(sender) => sender,
+            // The connection has already disconnected
+            None => return Ok(()),
+        };
+
+        match sender.send((id.client, res)) {
+            Ok(()) => Ok(()),
+            Err(_) if id.connection != 0 => {
+                senders[id.connection] = None;
+                Ok(())
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Establishes an additional connection with the IPC channel oneshot server with the given
+    /// name
+    ///
+    /// Requests from the new connection will be received by the same `ServerReceiver` as the
+    /// requests from every other connection. Only the first connection is required to stay open
+    /// for the server to keep running. Any other connection may disconnect at any time.
+    pub fn connect(&self, oneshot_name: String) -> Result<(), ConnectionError> {
+        let (server_sender, receiver) = ipc::channel::<ClientMessage>()?;
+        let sender = IpcSender::connect(oneshot_name)?;
+
+        // Finish handshake by giving client a sender it can use to send messages to the server
+        sender.send(Handshake {version: PROTOCOL_VERSION, sender: server_sender.to_opaque()})?;
+        // Every message after the handshake is a response
+        let sender: IpcSender<ClientResponse> = sender.to_opaque().to();
+
+        self.add_transport(Box::new(sender), Box::new(AsyncIpcReceiver::new(receiver)));
+
+        Ok(())
+    }
+
+    /// Adds a connection that uses the given transport
+    ///
+    /// The other side of the transport must have already checked that it is using the same
+    /// version of the protocol. Must be called from within the tokio runtime. Any connection added
+    /// this way may disconnect at any time, just like the ones added with `connect()`.
+    pub fn add_transport(
+        &self,
+        sender: ResponseSender,
+        receiver: Box<dyn TransportReceiver<ClientMessage>>,
+    ) {
+        let connection = {
+            let mut senders = self.senders.lock();
+            senders.push(Some(sender));
+            senders.len() - 1
+        };
+
+        let senders = self.senders.clone();
+        let requests = self.requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let req = receiver.recv().await
+                    .map(|(client, req)| (ServerClientId {connection, client}, req));
+
+                let disconnected = matches!(req, Err(IpcError::Disconnected));
+                if disconnected && connection != 0 {
+                    senders.lock()[connection] = None;
+                    break;
+                }
+
+                // The send will only fail if the server has stopped receiving requests
+                if requests.send(req).is_err() || disconnected {
+                    break;
+                }
+            }
+        });
     }
 }
 
 /// The receiver for the server side of the IPC connection
+///
+/// Receives the requests from every connection to the server in the order that they arrive.
 #[derive(Debug)]
 pub struct ServerReceiver {
-    receiver: AsyncIpcReceiver<(ClientId, ClientRequest)>,
+    requests: mpsc::UnboundedReceiver<ServerRequest>,
 }
 
 impl ServerReceiver {
     /// Returns the next request, waiting until one is available
-    pub async fn recv(&self) -> Result<(ClientId, ClientRequest), IpcError> {
-        self.receiver.recv().await
+    pub async fn recv(&mut self) -> Result<(ServerClientId, ClientRequest), IpcError> {
+        // `ServerSender` holds a sender for this channel, so this can only return `None` if every
+        // copy of that has been dropped
+        self.requests.recv().await.unwrap_or(Err(IpcError::Disconnected))
     }
 }
 
 /// Establishes a connection with the IPC channel oneshot server with the given name
+///
+/// With the `single-process` or `wasm` feature, names given out by `connect_local_client()` are
+/// connected to using channels within this process instead.
 pub fn connect_server(
     oneshot_name: String,
 ) -> Result<(ServerSender, ServerReceiver), ConnectionError> {
-    let (server_sender, receiver) = ipc::channel()?;
-    let sender = IpcSender::connect(oneshot_name)?;
-
-    // Finish handshake by giving client a sender it can use to send messages to the server
-    sender.send(HandshakeResponse::HandshakeFinish(server_sender))?;
+    #[cfg(any(feature = "single-process", feature = "wasm"))]
+    if in_process::is_in_process(&oneshot_name) {
+        return in_process::connect_server(&oneshot_name);
+    }
 
-    let sender = ServerSender {sender};
-    let receiver = ServerReceiver {receiver: AsyncIpcReceiver::new(receiver)};
+    let (sender, receiver) = new_server();
+    sender.connect(oneshot_name)?;
 
     Ok((sender, receiver))
 }
+
+/// Creates the server side of the protocol without any connections
+fn new_server() -> (ServerSender, ServerReceiver) {
+    let (requests, requests_receiver) = mpsc::unbounded_channel();
+
+    let sender = ServerSender {senders: Default::default(), requests};
+    let receiver = ServerReceiver {requests: requests_receiver};
+
+    (sender, receiver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sync_runtime::block_on;
+
+    #[test]
+    fn ignores_disconnected_guests() {
+        block_on(async {
+            let mut server = None;
+            let (_sender, _receiver) = connect_client(|name| {
+                server = Some(connect_server(name));
+                async { Ok(()) }
+            }).await.expect("unable to connect host");
+            let (server_sender, _server_receiver) = server.unwrap().expect("unable to connect server");
+
+            let guest = connect_client(|name| {
+                let result = server_sender.connect(name).map_err(io::Error::other);
+                async { result }
+            }).await.expect("unable to connect guest");
+            drop(guest);
+
+            let guest_id = ServerClientId {connection: 1, client: ClientId::new(0)};
+            server_sender.send(guest_id, ServerResponse::ClearComplete)
+                .expect("sending to a disconnected guest should not fail");
+            server_sender.send(guest_id, ServerResponse::ClearComplete)
+                .expect("sending to a disconnected guest should not fail");
+        });
+    }
+}
@@ -0,0 +1,104 @@
+//! A file containing every request sent to the server while a drawing was being drawn
+//!
+//! The file starts with `MAGIC` and is followed by one entry per request. Like the messages
+//! carried over TCP, each entry is encoded with bincode and prefixed by its length as a
+//! little-endian `u32`. Logs can only be read by the version of this crate that wrote them since
+//! the encoding of the requests changes between versions.
+
+use std::io::{self, Read, BufReader, BufWriter};
+use std::fs::File;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Serialize, Deserialize};
+
+use super::ClientRequest;
+use super::tcp::{read_message, write_message};
+
+/// The bytes at the start of every command log, including the version of the format
+const MAGIC: &[u8; 8] = b"TURTLOG1";
+
+/// A single request in a command log
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommandLogEntry {
+    /// The time since the log was started when the request was handled
+    pub time: Duration,
+    /// The request that was handled
+    pub request: ClientRequest,
+}
+
+/// Writes the requests handled by the server into a command log
+#[derive(Debug)]
+pub struct CommandLog {
+    file: BufWriter<File>,
+    start: Instant,
+}
+
+impl CommandLog {
+    /// Creates a new command log at the given path, replacing any file that was already there
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        io::Write::write_all(&mut file, MAGIC)?;
+        io::Write::flush(&mut file)?;
+
+        Ok(Self {file, start: Instant::now()})
+    }
+
+    /// Adds the given request to the end of the log
+    ///
+    /// Each entry is written to the file right away, so the log is complete up to the last
+    /// request even if the program stops unexpectedly.
+    pub fn write(&mut self, request: &ClientRequest) -> io::Result<()> {
+        /// Encoded the same way as `CommandLogEntry`, without needing to own the request
+        #[derive(Serialize)]
+        struct Entry<'a> {
+            time: Duration,
+            request: &'a ClientRequest,
+        }
+
+        write_message(&mut self.file, &Entry {time: self.start.elapsed(), request})
+    }
+}
+
+/// Reads the entries of a command log in the order they were written
+#[derive(Debug)]
+pub struct CommandLogReader<R> {
+    reader: R,
+}
+
+impl CommandLogReader<BufReader<File>> {
+    /// Opens the command log at the given path
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> CommandLogReader<R> {
+    /// Starts reading a command log, returning an error if the reader does not contain one
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a command log written by this version of turtle"));
+        }
+
+        Ok(Self {reader})
+    }
+}
+
+impl<R: Read> Iterator for CommandLogReader<R> {
+    type Item = io::Result<CommandLogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The log ends cleanly if there are no bytes left where the next entry would start
+        let mut first = [0; 1];
+        match self.reader.read(&mut first) {
+            Ok(0) => return None,
+            Ok(_) => {},
+            Err(err) => return Some(Err(err)),
+        }
+
+        let mut reader = (&first[..]).chain(&mut self.reader);
+        Some(read_message(&mut reader))
+    }
+}
@@ -0,0 +1,196 @@
+//! Carries the protocol over channels within a single process (the `single-process` and `wasm`
+//! features)
+//!
+//! The client and the server pass messages to each other directly, without serializing them or
+//! creating any operating system resources. The client still gives the server a name to connect
+//! to, just like with IPC channels, so the backends do not need to know which transport is used.
+//! The name refers to a connection waiting in this process for the server to pick it up.
+
+use std::fmt;
+use std::io;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use tokio::sync::{mpsc, oneshot};
+use futures_util::future::{BoxFuture, FutureExt};
+use ipc_channel::ipc::IpcError;
+
+use super::{
+    ConnectionError,
+    ClientSender,
+    ClientReceiver,
+    ServerSender,
+    ServerReceiver,
+    ClientMessage,
+    ClientResponse,
+    TransportSender,
+    TransportReceiver,
+};
+
+/// The prefix of every name given out by `connect_client()`
+const NAME_PREFIX: &str = "turtle-in-process-";
+
+/// Used to give every connection a different name
+static NEXT_CONNECTION: AtomicUsize = AtomicUsize::new(0);
+
+/// The connections that are waiting for the server to connect to them, by name
+static PENDING: Lazy<Mutex<HashMap<String, PendingConnection>>> = Lazy::new(Default::default);
+
+/// The server side of a connection that the server has not connected to yet
+#[derive(Debug)]
+struct PendingConnection {
+    sender: InProcessSender<ClientResponse>,
+    receiver: InProcessReceiver<ClientMessage>,
+    /// Tells the client that the server has connected
+    connected: oneshot::Sender<()>,
+}
+
+/// Sends messages to the other side of an in-process connection
+struct InProcessSender<T> {
+    sender: mpsc::UnboundedSender<T>,
+}
+
+impl<T> fmt::Debug for InProcessSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InProcessSender").finish()
+    }
+}
+
+impl<T: Send> TransportSender<T> for InProcessSender<T> {
+    fn send(&self, message: T) -> Result<(), ipc_channel::Error> {
+        self.sender.send(message).map_err(|_| {
+            let err = io::Error::new(io::ErrorKind::BrokenPipe, "the other side of the connection has disconnected");
+            Box::new(bincode::ErrorKind::Io(err))
+        })
+    }
+}
+
+/// Receives messages from the other side of an in-process connection
+struct InProcessReceiver<T> {
+    messages: tokio::sync::Mutex<mpsc::UnboundedReceiver<T>>,
+}
+
+impl<T> fmt::Debug for InProcessReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InProcessReceiver").finish()
+    }
+}
+
+impl<T: Send> TransportReceiver<T> for InProcessReceiver<T> {
+    fn recv(&self) -> BoxFuture<'_, Result<T, IpcError>> {
+        async move {
+            self.messages.lock().await.recv().await.ok_or(IpcError::Disconnected)
+        }.boxed()
+    }
+}
+
+/// Returns both ends of a new in-process channel
+fn channel<T>() -> (InProcessSender<T>, InProcessReceiver<T>) {
+    let (sender, messages) = mpsc::unbounded_channel();
+    (InProcessSender {sender}, InProcessReceiver {messages: tokio::sync::Mutex::new(messages)})
+}
+
+/// Establishes the client side of an in-process connection by providing a name for the server to
+/// connect to with `connect_server()`
+///
+/// Returns once the server has connected, just like `ipc_protocol::connect_client()`.
+pub async fn connect_client<S, F>(
+    send_server_name: S,
+) -> Result<(ClientSender, ClientReceiver), ConnectionError>
+    where S: FnOnce(String) -> F,
+          F: Future<Output=io::Result<()>>,
+{
+    let (request_sender, request_receiver) = channel();
+    let (response_sender, response_receiver) = channel();
+    let (connected, server_connected) = oneshot::channel();
+
+    let name = format!("{}{}", NAME_PREFIX, NEXT_CONNECTION.fetch_add(1, Ordering::SeqCst));
+    PENDING.lock().insert(name.clone(), PendingConnection {
+        sender: response_sender,
+        receiver: request_receiver,
+        connected,
+    });
+
+    if let Err(err) = send_server_name(name.clone()).await {
+        PENDING.lock().remove(&name);
+        return Err(err.into());
+    }
+
+    // The pending connection is only ever dropped after the server has taken it
+    server_connected.await.map_err(|_| {
+        io::Error::new(io::ErrorKind::ConnectionAborted, "the server did not connect")
+    })?;
+
+    let sender = ClientSender {sender: Arc::new(Mutex::new(Box::new(request_sender)))};
+    let receiver = ClientReceiver {receiver: Box::new(response_receiver)};
+
+    Ok((sender, receiver))
+}
+
+/// Returns true if the given name was created by `connect_client()` in this module
+pub fn is_in_process(name: &str) -> bool {
+    name.starts_with(NAME_PREFIX)
+}
+
+/// Establishes the server side of the in-process connection with the given name
+///
+/// Must be called from within the tokio runtime.
+pub fn connect_server(name: &str) -> Result<(ServerSender, ServerReceiver), ConnectionError> {
+    let PendingConnection {sender, receiver, connected} = PENDING.lock().remove(name)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no client is waiting to connect as `{}`", name)))?;
+
+    let (server_sender, server_receiver) = super::new_server();
+    server_sender.add_transport(Box::new(sender), Box::new(receiver));
+
+    // The client may have stopped waiting, but then it will never send anything either
+    connected.send(()).unwrap_or(());
+
+    Ok((server_sender, server_receiver))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ipc_protocol::{ClientRequest, ServerResponse};
+    use crate::renderer_client::ClientId;
+    use crate::sync_runtime::block_on;
+
+    #[test]
+    fn sends_requests_and_responses() {
+        block_on(async {
+            let mut server = None;
+            let (sender, receiver) = connect_client(|name| {
+                assert!(is_in_process(&name));
+                server = Some(connect_server(&name));
+                async { Ok(()) }
+            }).await.expect("unable to connect client");
+            let (server_sender, mut server_receiver) = server.unwrap().expect("unable to connect server");
+
+            let id = ClientId::new(3);
+            sender.send(id, ClientRequest::ClearAll).unwrap();
+            let (client_id, request) = server_receiver.recv().await.unwrap();
+            assert!(matches!(request, ClientRequest::ClearAll));
+
+            server_sender.send(client_id, ServerResponse::ClearComplete).unwrap();
+            let (response_id, response) = receiver.recv().await.unwrap();
+            assert_eq!(response_id, id);
+            assert!(matches!(response, ServerResponse::ClearComplete));
+
+            // The server finds out once every sender for the connection is gone
+            drop(sender);
+            assert!(matches!(server_receiver.recv().await, Err(IpcError::Disconnected)));
+        });
+    }
+
+    #[test]
+    fn unknown_names_fail() {
+        block_on(async {
+            assert!(connect_server("turtle-in-process-does-not-exist").is_err());
+        });
+    }
+}
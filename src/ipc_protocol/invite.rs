@@ -0,0 +1,273 @@
+//! Allows separate processes to find and connect to a server that is already running
+//!
+//! A server that is being shared publishes an "invitation" under a name chosen by the user. The
+//! invitation is a file in a directory private to the current user that contains the name of an IPC
+//! oneshot server.
+//! A joining process connects to that oneshot server and sends the name of its own oneshot server,
+//! after which the usual connection handshake takes place. Since a oneshot server can only accept
+//! a single connection, the invitation is renewed every time a process joins.
+
+use std::io::{self, Write};
+use std::fs;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+use parking_lot::Mutex;
+use serde::{Serialize, Deserialize};
+use ipc_channel::ipc::{IpcOneShotServer, IpcSender};
+
+use super::{ConnectionError, ServerSender, ClientSender, ClientReceiver, connect_client};
+
+/// The number of times to try to join before giving up
+///
+/// Joining may fail if another process joins at the same time, since the invitation can only be
+/// used once before it is renewed.
+const JOIN_ATTEMPTS: usize = 10;
+
+/// The amount of time to wait for the invitation to be renewed before trying to join again
+const JOIN_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// An error produced while sharing a drawing or joining a shared drawing
+#[derive(Debug, Error, Serialize, Deserialize)]
+#[error("{0}")]
+pub struct ShareError(String);
+
+impl From<crate::Error> for ShareError {
+    fn from(err: crate::Error) -> Self {
+        ShareError(err.to_string())
+    }
+}
+
+impl From<ConnectionError> for ShareError {
+    fn from(err: ConnectionError) -> Self {
+        ShareError(err.to_string())
+    }
+}
+
+impl From<io::Error> for ShareError {
+    fn from(err: io::Error) -> Self {
+        ShareError(err.to_string())
+    }
+}
+
+/// Returns true if the given name can be used to share a drawing
+///
+/// Names are used as part of a file name, so only a small set of characters are allowed.
+pub fn is_valid_share_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Returns the directory that invitations are published in, creating it if necessary
+///
+/// Only the current user can access this directory, so no other user can read an invitation or
+/// replace it with one of their own.
+fn invite_dir() -> io::Result<PathBuf> {
+    // The runtime directory is already private to the user on systems that have one
+    let dir = match env::var_os("XDG_RUNTIME_DIR") {
+        Some(runtime_dir) => PathBuf::from(runtime_dir).join("turtle"),
+        None => env::temp_dir().join(private_dir_name()),
+    };
+
+    create_private_dir(&dir)?;
+    Ok(dir)
+}
+
+#[cfg(unix)]
+fn private_dir_name() -> String {
+    // Safety: getuid() always succeeds and has no side effects
+    format!("turtle-{}", unsafe { libc::getuid() })
+}
+
+#[cfg(not(unix))]
+fn private_dir_name() -> String {
+    // The temporary directory is already private to each user
+    "turtle".to_string()
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    match fs::DirBuilder::new().mode(0o700).create(dir) {
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {},
+        res => res?,
+    }
+
+    // The directory may have been created by someone else in a shared temporary directory
+    let metadata = fs::symlink_metadata(dir)?;
+    // Safety: getuid() always succeeds and has no side effects
+    let uid = unsafe { libc::getuid() };
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("the invitation directory {} is not private to the current user", dir.display()),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+/// Returns the path of the file containing the invitation with the given name
+fn invite_path(name: &str) -> io::Result<PathBuf> {
+    Ok(invite_dir()?.join(format!("turtle-share-{}", name)))
+}
+
+/// Writes the name of a oneshot server into the invitation at the given path
+///
+/// The name is written into a new file that then replaces the invitation so that a joining process
+/// never reads a partially written invitation.
+fn write_invite(path: &Path, server_name: &str) -> io::Result<()> {
+    let new_path = path.with_extension("new");
+    // Left behind if a previous server stopped while renewing its invitation
+    match fs::remove_file(&new_path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {},
+        res => res?,
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(&new_path)?;
+    file.write_all(server_name.as_bytes())?;
+    drop(file);
+
+    fs::rename(&new_path, path)
+}
+
+/// An invitation published with `publish_invite()`
+///
+/// The invitation is removed when this is dropped, after which no other process can join.
+#[derive(Debug)]
+pub struct Invite {
+    /// The path of the invitation, or `None` once it has been removed
+    ///
+    /// Held while the invitation is renewed so that it cannot be renewed after being removed.
+    path: Arc<Mutex<Option<PathBuf>>>,
+}
+
+impl Drop for Invite {
+    fn drop(&mut self) {
+        if let Some(path) = self.path.lock().take() {
+            // Nothing can be done if the invitation was already removed
+            fs::remove_file(path).unwrap_or(());
+        }
+    }
+}
+
+/// Publishes an invitation with the given name that allows other processes to connect to the
+/// server using the given sender
+///
+/// The invitation is renewed after each process joins. It remains valid until the returned
+/// `Invite` is dropped, which should happen when the server stops.
+pub fn publish_invite(conn: ServerSender, name: &str) -> Result<Invite, ConnectionError> {
+    let path = invite_path(name)?;
+
+    let (mut server, server_name) = IpcOneShotServer::<String>::new()?;
+    write_invite(&path, &server_name)?;
+
+    let invite = Invite {path: Arc::new(Mutex::new(Some(path)))};
+    let task_path = invite.path.clone();
+    tokio::spawn(async move {
+        loop {
+            let accepted = tokio::task::spawn_blocking(move || server.accept()).await;
+            let client_name = match accepted {
+                Ok(Ok((_, client_name))) => client_name,
+                // The invitation can no longer be used, so no one else can join
+                _ => break,
+            };
+
+            // Renew the invitation before connecting so that the next process can start joining
+            let (next_server, next_name) = match IpcOneShotServer::new() {
+                Ok(next) => next,
+                Err(_) => break,
+            };
+            let renewed = match &*task_path.lock() {
+                Some(path) => write_invite(path, &next_name).is_ok(),
+                // The server has stopped sharing
+                None => false,
+            };
+            if !renewed {
+                break;
+            }
+            server = next_server;
+
+            // A process that fails to complete the handshake should not stop others from joining
+            conn.connect(client_name).unwrap_or(());
+        }
+    });
+
+    Ok(invite)
+}
+
+/// Connects to the server that published the invitation with the given name
+pub async fn join_invite(name: &str) -> Result<(ClientSender, ClientReceiver), ConnectionError> {
+    let path = invite_path(name)?;
+
+    let mut attempt = 1;
+    loop {
+        match try_join_invite(&path).await {
+            Err(ConnectionError::IOError(_)) if attempt < JOIN_ATTEMPTS => {
+                attempt += 1;
+                crate::time::delay_for(JOIN_RETRY_DELAY).await;
+            },
+
+            res => return res,
+        }
+    }
+}
+
+async fn try_join_invite(path: &Path) -> Result<(ClientSender, ClientReceiver), ConnectionError> {
+    let invite_name = fs::read_to_string(path)?;
+
+    connect_client(|server_name| async move {
+        let sender = IpcSender::connect(invite_name)?;
+        sender.send(server_name).map_err(io::Error::other)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sync_runtime::block_on;
+    use crate::ipc_protocol::connect_server;
+
+    #[test]
+    fn removes_invite_when_dropped() {
+        block_on(async {
+            let mut server = None;
+            let (_sender, _receiver) = connect_client(|name| {
+                server = Some(connect_server(name));
+                async { Ok(()) }
+            }).await.expect("unable to connect host");
+            let (server_sender, _server_receiver) = server.unwrap().expect("unable to connect server");
+
+            let invite = publish_invite(server_sender, "test-removes-invite").expect("unable to publish invite");
+            let path = invite_path("test-removes-invite").unwrap();
+            let metadata = fs::metadata(&path).expect("invite should be published");
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+            }
+            #[cfg(not(unix))]
+            let _ = metadata;
+
+            drop(invite);
+            assert!(!path.exists());
+        });
+    }
+}
@@ -1,10 +1,17 @@
 use std::path::PathBuf;
+use std::time::Duration;
+use std::net::SocketAddr;
 
 use serde::{Serialize, Deserialize};
 
-use crate::{Color, Point, Speed, Event, Distance, Size};
-use crate::renderer_server::{TurtleId, ExportError};
-use crate::{async_turtle::AngleUnit, radians::Radians, debug};
+use crate::event::Key;
+use crate::{Error, Color, Point, Speed, Easing, Shape, FillRule, BlendMode, Event, Distance, Size, Rect, ResizeAnchor, CoordinateSystem, WorldCoordinates, DisconnectBehavior, ExportOptions, PrintOptions, PlotterOptions, ImportTransform, Filter, Font, TextAlign, TextOptions, FontMetrics, FontError};
+use crate::renderer_server::{TurtleId, StampId, LayerId, ExportError, ImportError, SessionError, Minimap, Symmetry, Vertex, Index, Primitive, PrimitiveId, ClearEffect, ImageData, ImageHandle, BackgroundImage};
+#[cfg(feature = "shaders")]
+use crate::renderer_server::PostShader;
+
+use super::ShareError;
+use crate::{async_turtle::AngleUnit, radians::Radians, path::PathSegment, debug};
 
 /// The different kinds of requests that can be sent from a client
 ///
@@ -24,15 +31,90 @@ pub enum ClientRequest {
     /// Response: `ServerResponse::NewTurtle`
     CreateTurtle,
 
-    /// Export the drawing in its current state to the given path using the given format
+    /// Export the drawing in its current state to the given path using the given format and
+    /// options
     ///
     /// Response: `ServerResponse::ExportComplete`
-    Export(PathBuf, ExportFormat),
+    Export(PathBuf, ExportFormat, ExportOptions),
+
+    /// Import the SVG file at the given path into the drawing, placing it using the given
+    /// transform
+    ///
+    /// Response: `ServerResponse::ImportComplete`
+    Import(PathBuf, ImportTransform),
+
+    /// Save the drawing, the state of every turtle, and everything that has been drawn to the
+    /// given path
+    ///
+    /// Response: `ServerResponse::SessionComplete`
+    SaveSession(PathBuf),
+
+    /// Replace the drawing, the state of every turtle, and everything that has been drawn with
+    /// the session saved at the given path
+    ///
+    /// Response: `ServerResponse::SessionComplete`
+    LoadSession(PathBuf),
+
+    /// Print the drawing in its current state using the given options
+    ///
+    /// Response: `ServerResponse::ExportComplete`
+    Print(PrintOptions),
+
+    /// Convert the lines in the drawing into instructions for a pen plotter and save them to the
+    /// given path using the given format and options
+    ///
+    /// Response: `ServerResponse::ExportComplete`
+    ExportToolpath(PathBuf, ToolpathFormat, PlotterOptions),
+
+    /// Start recording the drawing into an animated GIF at the given path, capturing the given
+    /// number of frames per second
+    ///
+    /// Response: `ServerResponse::ExportComplete`
+    StartRecording(PathBuf, f64),
+    /// Stop the current recording and finish writing the GIF
+    ///
+    /// Response: `ServerResponse::ExportComplete`
+    StopRecording,
+    /// Start writing every request handled after this one into a command log at the given path,
+    /// replacing any command log that was already being written
+    ///
+    /// Response: `ServerResponse::ExportComplete`
+    RecordCommands(PathBuf),
+
+    /// Allow other processes to connect to the server using the given name
+    ///
+    /// Response: `ServerResponse::ShareStarted`
+    Share(String),
+    /// Allow programs on other machines to connect to the server at the first of the given
+    /// addresses that can be used
+    ///
+    /// Response: `ServerResponse::TcpShareStarted`
+    ShareTcp(Vec<SocketAddr>),
 
     /// Get the next event (if any are currently available)
     ///
     /// Response: `ServerResponse::Event`
     PollEvent,
+    /// Wait until the next event is available
+    ///
+    /// Unlike `PollEvent`, the response is only sent once an event occurs. Each event is only sent
+    /// to one of the clients waiting for it, in the order they started waiting.
+    ///
+    /// Response: `ServerResponse::Event`, with `None` only if no more events will ever be sent
+    NextEvent,
+    /// Stop waiting for the next event
+    ///
+    /// Sent when a client stops listening for events before its `NextEvent` request was answered,
+    /// so that the next event goes to another client instead.
+    ///
+    /// Response: N/A
+    CancelNextEvent,
+    /// Put back an event that was sent to a client that no longer exists
+    ///
+    /// The event is sent again before any events that occurred after it.
+    ///
+    /// Response: N/A
+    RequeueEvent(Event),
 
     /// Get the given property of the drawing
     ///
@@ -46,6 +128,115 @@ pub enum ClientRequest {
     ///
     /// Response: N/A
     ResetDrawingProp(DrawingProp),
+    /// Redraws the window right away, even if the tracer of the drawing is skipping updates
+    ///
+    /// Response: N/A
+    Flush,
+    /// Pauses (if true) or resumes (if false) the drawing
+    ///
+    /// While the drawing is paused, every animation is frozen where it is and every other request
+    /// waits to be handled until the drawing is resumed. This request is always handled right
+    /// away, even while paused.
+    ///
+    /// Response: N/A
+    SetPaused(bool),
+    /// Enables (if true) or disables (if false) step mode
+    ///
+    /// While in step mode, each turtle command waits for a `Step` request (or for the step key
+    /// to be pressed) before it runs. The command that is waiting is shown in the window title.
+    /// Disabling step mode runs the waiting command right away. This request is always handled
+    /// right away.
+    ///
+    /// Response: N/A
+    SetStepMode(bool),
+    /// Allows the next turtle command to run while in step mode
+    ///
+    /// If no command is waiting, the next turtle command to arrive runs without waiting. This
+    /// request is always handled right away and does nothing outside of step mode.
+    ///
+    /// Response: N/A
+    Step,
+    /// Draw a circle with the given center and radius, filled with the given color
+    ///
+    /// The circle is not drawn by any turtle, so it is only removed when the entire drawing is
+    /// cleared.
+    ///
+    /// Response: N/A
+    DrawCircle(Point, Distance, Color),
+    /// Create a new layer on top of every existing layer
+    ///
+    /// Turtles can be assigned to the new layer using the `Layer` turtle property. Anything a
+    /// turtle draws is placed on the layer that it was assigned to at the time.
+    ///
+    /// Response: `ServerResponse::NewLayer`
+    AddLayer,
+    /// Get whether the given layer is visible
+    ///
+    /// Response: `ServerResponse::LayerVisible`
+    LayerIsVisible(LayerId),
+    /// Show or hide the given layer
+    ///
+    /// Hidden layers are not rendered, exported, or included in the bounds of the drawing. The
+    /// turtles assigned to a hidden layer are hidden as well.
+    ///
+    /// Response: N/A
+    SetLayerVisible(LayerId, bool),
+    /// Move the given layer above every other layer (if `true`) or below every other layer (if
+    /// `false`)
+    ///
+    /// Response: N/A
+    MoveLayer(LayerId, bool),
+    /// Get the smallest rectangle that contains everything that has been drawn so far
+    ///
+    /// The turtles themselves are not included since they are not part of the drawing.
+    ///
+    /// Response: `ServerResponse::DrawingBounds`
+    DrawingBounds,
+    /// Get the smallest rectangle that contains everything that has been drawn so far by the
+    /// given turtle
+    ///
+    /// Response: `ServerResponse::DrawingBounds`
+    TurtleDrawingBounds(TurtleId),
+    /// Tessellate everything that has been drawn so far into a triangle mesh
+    ///
+    /// Response: `ServerResponse::Mesh`
+    ExportMesh,
+    /// Export the drawing in its current state as an SVG document using the given options
+    ///
+    /// Response: `ServerResponse::Svg`
+    ExportSvg(ExportOptions),
+    /// Get a copy of every primitive that has been drawn so far
+    ///
+    /// Response: `ServerResponse::Primitives`
+    Primitives,
+    /// Get a copy of the primitive with the given ID, if it has not been removed
+    ///
+    /// Response: `ServerResponse::Primitive`
+    Primitive(PrimitiveId),
+    /// Find every primitive that contains the given point
+    ///
+    /// Response: `ServerResponse::HitTest`
+    HitTest(Point),
+    /// Get the color that the drawing has at the given point once it is rendered
+    ///
+    /// The turtles themselves are not included since they are not part of the drawing.
+    ///
+    /// Response: `ServerResponse::ColorAt`
+    ColorAt(Point),
+    /// Measure the size of the given text when written using the given options
+    ///
+    /// Response: `ServerResponse::TextSize`
+    MeasureText(String, TextOptions),
+    /// Get the vertical measurements of the font described by the given options
+    ///
+    /// Response: `ServerResponse::FontMetrics`
+    FontMetrics(TextOptions),
+    /// Store the given image in the drawing so that it can be used by any turtle
+    ///
+    /// The image is decoded by the client so that no files need to be read by the window.
+    ///
+    /// Response: `ServerResponse::ImageLoaded`
+    LoadImage(ImageData),
 
     /// Get the given property of a turtle
     ///
@@ -76,6 +267,13 @@ pub enum ClientRequest {
     ///
     /// Response: `ServerResponse::AnimationComplete`
     MoveForward(TurtleId, Distance),
+    /// Move a turtle forward by the given amount in exactly the given amount of time
+    ///
+    /// This is the same as `MoveForward`, except that the duration of the animation does not
+    /// depend on the turtle's speed. The movement is still instant if the drawing is not animated.
+    ///
+    /// Response: `ServerResponse::AnimationComplete`
+    TimedMoveForward(TurtleId, Distance, Duration),
     /// Move a turtle to the given position
     ///
     /// The turtle movement is animated at its current speed. This may draw a line if the turtle's
@@ -99,6 +297,128 @@ pub enum ClientRequest {
     ///
     /// Response: `ServerResponse::AnimationComplete`
     RotateInPlace(TurtleId, Radians, RotationDirection),
+    /// Rotate a turtle in place by the given angle in the given direction in exactly the given
+    /// amount of time
+    ///
+    /// This is the same as `RotateInPlace`, except that the duration of the animation does not
+    /// depend on the turtle's speed. The rotation is still instant if the drawing is not animated.
+    ///
+    /// Response: `ServerResponse::AnimationComplete`
+    TimedRotateInPlace(TurtleId, Radians, RotationDirection, Duration),
+    /// Gradually change the pen color of a turtle to the given color over the given amount of time
+    ///
+    /// The turtle keeps running other commands while the color changes. Each line drawn while the
+    /// color is changing uses the pen color from the moment the line was started. The color
+    /// changes right away if the drawing is not animated.
+    ///
+    /// Response: N/A
+    AnimatePenColor(TurtleId, Color, Duration),
+    /// Move a turtle along a circular arc with the given radius and extent
+    ///
+    /// The center of the arc is `radius` units away from the turtle, to its left if the direction is
+    /// counterclockwise and to its right if the direction is clockwise. A negative radius places the
+    /// center on the other side. A negative extent moves the turtle backwards. The movement is
+    /// animated at the turtle's current speed and the heading of the turtle changes continuously as
+    /// it moves. This may draw a line if the turtle's pen is down. It may also result in a change to
+    /// the current fill if the turtle is currently filling a shape.
+    ///
+    /// The response to this request provides no additional information, but is necessary to ensure
+    /// that animations that should be sequenced, can be sequenced correctly. Without a signal for
+    /// when the animation is complete, there would be no way to know when to start the next one.
+    ///
+    /// Response: `ServerResponse::AnimationComplete`
+    MoveArc(TurtleId, Distance, Radians, RotationDirection),
+    /// Move a turtle once around a circle with its center `radius` units to the left of the turtle
+    /// (or to its right if the radius is negative)
+    ///
+    /// This is animated just like `MoveArc`. Once the turtle is back where it started, the lines
+    /// drawn on the way are replaced by a single circle so that it is drawn exactly.
+    ///
+    /// Response: `ServerResponse::AnimationComplete`
+    MoveCircle(TurtleId, Distance),
+    /// Move a turtle along a cubic Bézier curve that starts at its current position, bends
+    /// towards the two given control points, and ends at the last given point
+    ///
+    /// The movement is animated at the turtle's current speed and the turtle always faces in the
+    /// direction that the curve is going in. This may draw a curve if the turtle's pen is down. It
+    /// may also result in a change to the current fill if the turtle is currently filling a shape.
+    ///
+    /// Response: `ServerResponse::AnimationComplete`
+    CubicBezierTo(TurtleId, Point, Point, Point),
+    /// Move a turtle along a quadratic Bézier curve that starts at its current position, bends
+    /// towards the given control point, and ends at the last given point
+    ///
+    /// This is animated just like `CubicBezierTo`.
+    ///
+    /// Response: `ServerResponse::AnimationComplete`
+    QuadBezierTo(TurtleId, Point, Point),
+    /// Move a turtle along every segment of the given path in order, starting at its current
+    /// position, with every angle in radians
+    ///
+    /// Every segment is turned into a cubic Bézier curve and the turtle moves along all of them as
+    /// a single animation, just like `CubicBezierTo`. The whole path is undone in a single step.
+    ///
+    /// Response: `ServerResponse::AnimationComplete`
+    FollowPath(TurtleId, Vec<PathSegment>),
+    /// Write the given text at a turtle's current position in the color of its pen
+    ///
+    /// The text is written instantly and the turtle does not move. The response contains the size
+    /// of the text that was written.
+    ///
+    /// Response: `ServerResponse::TextSize`
+    WriteText(TurtleId, String, TextOptions, TextAlign),
+    /// Leave a copy of a turtle's current shape on the canvas at its current position
+    ///
+    /// The stamp is drawn instantly and the turtle does not move. Just like with `WriteText`, the
+    /// stamp is drawn even if the pen is up.
+    ///
+    /// Response: `ServerResponse::NewStamp`
+    Stamp(TurtleId),
+    /// Draw the given image centered at a turtle's current position, rotated to face in the
+    /// direction of its heading and scaled by its shape size
+    ///
+    /// This creates a stamp just like `Stamp`, so the image can be removed using `ClearStamp`.
+    ///
+    /// Response: `ServerResponse::NewStamp`
+    StampImage(TurtleId, ImageHandle),
+    /// Remove the given stamp created by a turtle
+    ///
+    /// If the stamp was already removed (e.g. by clearing the turtle's drawings), this request is
+    /// ignored.
+    ///
+    /// Response: N/A
+    ClearStamp(TurtleId, StampId),
+    /// Remove every stamp created by a turtle, leaving the rest of its drawings
+    ///
+    /// Response: N/A
+    ClearStamps(TurtleId),
+    /// Undo the given number of the most recent commands of a turtle
+    ///
+    /// Movements, rotations, written text, and stamps can be undone. Undoing a command removes
+    /// everything it drew and moves the turtle back to the position and heading it had before the
+    /// command. Commands that happened before the turtle's drawings were last cleared cannot be
+    /// undone. If there are fewer commands than requested, every command is undone.
+    ///
+    /// Response: N/A
+    Undo(TurtleId, usize),
+    /// Save the position, heading, pen, and fill color of a turtle onto its stack of saved states
+    ///
+    /// Response: N/A
+    PushState(TurtleId),
+    /// Restore the most recently saved state of a turtle and remove it from the stack of saved
+    /// states
+    ///
+    /// The turtle jumps back to its saved position and heading instantly, without drawing
+    /// anything. If the turtle is filling, the fill color of the current fill is updated as well.
+    /// The response is `false` (and nothing changes) if there are no saved states.
+    ///
+    /// Response: `ServerResponse::StatePopped`
+    PopState(TurtleId),
+    /// Get the number of animations that have not completed yet for the given turtle (or for all
+    /// turtles if `None`)
+    ///
+    /// Response: `ServerResponse::PendingAnimations`
+    PendingAnimations(Option<TurtleId>),
 
     /// Creates a fill polygon from a turtle's current position
     ///
@@ -141,6 +461,23 @@ pub enum ClientRequest {
     ///
     /// Response: N/A
     ClearTurtle(TurtleId),
+    /// Clears all drawings for all turtles, gradually covering them using the given effect first
+    ///
+    /// Any animations that are currently playing are stopped right away, just like they would be
+    /// by a `ClearAll` request. Everything is cleared at once when the effect is done playing.
+    ///
+    /// Response: `ServerResponse::ClearComplete`
+    ClearAnimated(ClearEffect),
+    /// Clears every drawing that lies entirely inside of the given rectangle, leaving everything
+    /// else untouched
+    ///
+    /// The rectangle is in the coordinate system of the drawing. Drawings that are only partly
+    /// inside of it are kept, and so are the fill polygons of turtles that are still filling. Any
+    /// animations that are currently playing are stopped first, just like they would be by a
+    /// `ClearAll` request.
+    ///
+    /// Response: N/A
+    ClearRegion(Rect),
 
     /// Returns the entire current state of the given turtle in a format useful
     /// for printing only.
@@ -154,35 +491,497 @@ pub enum ClientRequest {
     DebugDrawing,
 }
 
+impl ClientRequest {
+    /// Returns the turtle that this request refers to, if any
+    pub fn turtle_id(&self) -> Option<TurtleId> {
+        use ClientRequest::*;
+        match *self {
+            TurtleProp(id, _) |
+            SetTurtleProp(id, _) |
+            ResetTurtleProp(id, _) |
+            ResetTurtle(id) |
+            MoveForward(id, _) |
+            TimedMoveForward(id, _, _) |
+            MoveTo(id, _) |
+            RotateInPlace(id, _, _) |
+            TimedRotateInPlace(id, _, _, _) |
+            AnimatePenColor(id, _, _) |
+            MoveArc(id, _, _, _) |
+            MoveCircle(id, _) |
+            CubicBezierTo(id, _, _, _) |
+            QuadBezierTo(id, _, _) |
+            FollowPath(id, _) |
+            WriteText(id, _, _, _) |
+            Stamp(id) |
+            StampImage(id, _) |
+            ClearStamp(id, _) |
+            ClearStamps(id) |
+            Undo(id, _) |
+            PushState(id) |
+            PopState(id) |
+            PendingAnimations(Some(id)) |
+            BeginFill(id) |
+            EndFill(id) |
+            ClearTurtle(id) |
+            TurtleDrawingBounds(id) |
+            DebugTurtle(id, _) => Some(id),
+
+            _ => None,
+        }
+    }
+
+    /// Returns true if this request is a turtle command that waits for a step in step mode
+    ///
+    /// Requests that only read the properties of a turtle never wait.
+    pub fn is_turtle_command(&self) -> bool {
+        use ClientRequest::*;
+        match self {
+            TurtleProp(..) |
+            PendingAnimations(_) |
+            TurtleDrawingBounds(_) |
+            DebugTurtle(..) => false,
+
+            _ => self.turtle_id().is_some(),
+        }
+    }
+
+    /// Returns the name of the kind of request, without any of its data
+    ///
+    /// Used to label requests in traces and error messages without formatting all of their data.
+    pub fn name(&self) -> &'static str {
+        use ClientRequest::*;
+        match self {
+            CreateTurtle => "CreateTurtle",
+            Export(..) => "Export",
+            Import(..) => "Import",
+            SaveSession(_) => "SaveSession",
+            LoadSession(_) => "LoadSession",
+            Print(_) => "Print",
+            ExportToolpath(..) => "ExportToolpath",
+            StartRecording(..) => "StartRecording",
+            StopRecording => "StopRecording",
+            RecordCommands(_) => "RecordCommands",
+            Share(_) => "Share",
+            ShareTcp(_) => "ShareTcp",
+            PollEvent => "PollEvent",
+            NextEvent => "NextEvent",
+            CancelNextEvent => "CancelNextEvent",
+            RequeueEvent(_) => "RequeueEvent",
+            DrawingProp(_) => "DrawingProp",
+            SetDrawingProp(_) => "SetDrawingProp",
+            ResetDrawingProp(_) => "ResetDrawingProp",
+            Flush => "Flush",
+            SetPaused(_) => "SetPaused",
+            SetStepMode(_) => "SetStepMode",
+            Step => "Step",
+            DrawCircle(..) => "DrawCircle",
+            AddLayer => "AddLayer",
+            LayerIsVisible(_) => "LayerIsVisible",
+            SetLayerVisible(..) => "SetLayerVisible",
+            MoveLayer(..) => "MoveLayer",
+            DrawingBounds => "DrawingBounds",
+            TurtleDrawingBounds(_) => "TurtleDrawingBounds",
+            ExportMesh => "ExportMesh",
+            ExportSvg(_) => "ExportSvg",
+            Primitives => "Primitives",
+            Primitive(_) => "Primitive",
+            HitTest(_) => "HitTest",
+            ColorAt(_) => "ColorAt",
+            MeasureText(..) => "MeasureText",
+            FontMetrics(_) => "FontMetrics",
+            LoadImage(_) => "LoadImage",
+            TurtleProp(..) => "TurtleProp",
+            SetTurtleProp(..) => "SetTurtleProp",
+            ResetTurtleProp(..) => "ResetTurtleProp",
+            ResetTurtle(_) => "ResetTurtle",
+            MoveForward(..) => "MoveForward",
+            TimedMoveForward(..) => "TimedMoveForward",
+            MoveTo(..) => "MoveTo",
+            RotateInPlace(..) => "RotateInPlace",
+            TimedRotateInPlace(..) => "TimedRotateInPlace",
+            AnimatePenColor(..) => "AnimatePenColor",
+            MoveArc(..) => "MoveArc",
+            MoveCircle(..) => "MoveCircle",
+            CubicBezierTo(..) => "CubicBezierTo",
+            QuadBezierTo(..) => "QuadBezierTo",
+            FollowPath(..) => "FollowPath",
+            WriteText(..) => "WriteText",
+            Stamp(_) => "Stamp",
+            StampImage(..) => "StampImage",
+            ClearStamp(..) => "ClearStamp",
+            ClearStamps(_) => "ClearStamps",
+            Undo(..) => "Undo",
+            PushState(_) => "PushState",
+            PopState(_) => "PopState",
+            PendingAnimations(_) => "PendingAnimations",
+            BeginFill(_) => "BeginFill",
+            EndFill(_) => "EndFill",
+            ClearAll => "ClearAll",
+            ClearTurtle(_) => "ClearTurtle",
+            ClearAnimated(_) => "ClearAnimated",
+            ClearRegion(_) => "ClearRegion",
+            DebugTurtle(..) => "DebugTurtle",
+            DebugDrawing => "DebugDrawing",
+        }
+    }
+
+    /// Returns true if the server will send a response to this request
+    pub fn has_response(&self) -> bool {
+        use ClientRequest::*;
+        match self {
+            CreateTurtle |
+            Export(..) |
+            Import(..) |
+            SaveSession(_) |
+            LoadSession(_) |
+            Print(_) |
+            ExportToolpath(..) |
+            StartRecording(..) |
+            StopRecording |
+            RecordCommands(_) |
+            Share(_) |
+            ShareTcp(_) |
+            PollEvent |
+            NextEvent |
+            DrawingProp(_) |
+            DrawingBounds |
+            TurtleDrawingBounds(_) |
+            ExportMesh |
+            ExportSvg(_) |
+            Primitives |
+            Primitive(_) |
+            HitTest(_) |
+            ColorAt(_) |
+            AddLayer |
+            LayerIsVisible(_) |
+            MeasureText(..) |
+            FontMetrics(_) |
+            LoadImage(_) |
+            TurtleProp(..) |
+            MoveForward(..) |
+            TimedMoveForward(..) |
+            MoveTo(..) |
+            RotateInPlace(..) |
+            TimedRotateInPlace(..) |
+            MoveArc(..) |
+            MoveCircle(..) |
+            CubicBezierTo(..) |
+            QuadBezierTo(..) |
+            FollowPath(..) |
+            WriteText(..) |
+            Stamp(_) |
+            StampImage(..) |
+            PopState(_) |
+            PendingAnimations(_) |
+            ClearAnimated(_) |
+            DebugTurtle(..) |
+            DebugDrawing => true,
+
+            SetDrawingProp(_) |
+            ResetDrawingProp(_) |
+            Flush |
+            SetPaused(_) |
+            SetStepMode(_) |
+            Step |
+            CancelNextEvent |
+            RequeueEvent(_) |
+            DrawCircle(..) |
+            SetLayerVisible(..) |
+            MoveLayer(..) |
+            SetTurtleProp(..) |
+            ResetTurtleProp(..) |
+            ResetTurtle(_) |
+            AnimatePenColor(..) |
+            ClearStamp(..) |
+            ClearStamps(_) |
+            Undo(..) |
+            PushState(_) |
+            BeginFill(_) |
+            EndFill(_) |
+            ClearAll |
+            ClearTurtle(_) |
+            ClearRegion(_) => false,
+        }
+    }
+
+    /// Returns true if this request may be sent by a client on another machine or a web page
+    ///
+    /// Anyone who can reach the port of a WebSocket or TCP bridge is able to connect to it, so
+    /// requests that can read or write files or interact with the rest of the system are not
+    /// allowed over those connections.
+    ///
+    /// New requests are not allowed remotely until they are added to this list.
+    pub fn is_allowed_remotely(&self) -> bool {
+        use ClientRequest::*;
+        match self {
+            MeasureText(_, TextOptions {font: Font::File(_), ..}) |
+            FontMetrics(TextOptions {font: Font::File(_), ..}) |
+            WriteText(_, _, TextOptions {font: Font::File(_), ..}, _) => false,
+
+            CreateTurtle |
+            PollEvent |
+            NextEvent |
+            CancelNextEvent |
+            DrawingProp(_) |
+            SetDrawingProp(_) |
+            ResetDrawingProp(_) |
+            Flush |
+            SetPaused(_) |
+            SetStepMode(_) |
+            Step |
+            DrawCircle(..) |
+            AddLayer |
+            LayerIsVisible(_) |
+            SetLayerVisible(..) |
+            MoveLayer(..) |
+            DrawingBounds |
+            TurtleDrawingBounds(_) |
+            ExportMesh |
+            ExportSvg(_) |
+            Primitives |
+            Primitive(_) |
+            HitTest(_) |
+            ColorAt(_) |
+            MeasureText(..) |
+            FontMetrics(_) |
+            LoadImage(_) |
+            TurtleProp(..) |
+            SetTurtleProp(..) |
+            ResetTurtleProp(..) |
+            ResetTurtle(_) |
+            MoveForward(..) |
+            TimedMoveForward(..) |
+            MoveTo(..) |
+            RotateInPlace(..) |
+            TimedRotateInPlace(..) |
+            AnimatePenColor(..) |
+            MoveArc(..) |
+            MoveCircle(..) |
+            CubicBezierTo(..) |
+            QuadBezierTo(..) |
+            FollowPath(..) |
+            WriteText(..) |
+            Stamp(_) |
+            StampImage(..) |
+            ClearStamp(..) |
+            ClearStamps(_) |
+            Undo(..) |
+            PushState(_) |
+            PopState(_) |
+            PendingAnimations(_) |
+            BeginFill(_) |
+            EndFill(_) |
+            ClearAll |
+            ClearTurtle(_) |
+            ClearAnimated(_) |
+            ClearRegion(_) |
+            DebugTurtle(..) |
+            DebugDrawing => true,
+
+            Export(..) |
+            Import(..) |
+            SaveSession(_) |
+            LoadSession(_) |
+            Print(_) |
+            ExportToolpath(..) |
+            StartRecording(..) |
+            StopRecording |
+            RecordCommands(_) |
+            Share(_) |
+            ShareTcp(_) |
+            RequeueEvent(_) => false,
+        }
+    }
+
+    /// Returns true if this request should be written into a command log
+    ///
+    /// This is also what decides which requests may be run again from a command log, since a
+    /// command log can come from anywhere. Only requests that change or read the drawing and its
+    /// turtles are allowed. Requests that read or write files, connect to other programs, pause
+    /// the drawing, or wait for input from the window are left out, since running them again
+    /// would not reproduce the drawing and could do something the person replaying it never
+    /// asked for.
+    ///
+    /// New requests are not logged until they are added to this list.
+    pub fn is_logged(&self) -> bool {
+        use ClientRequest::*;
+        match self {
+            MeasureText(_, TextOptions {font: Font::File(_), ..}) |
+            FontMetrics(TextOptions {font: Font::File(_), ..}) |
+            WriteText(_, _, TextOptions {font: Font::File(_), ..}, _) => false,
+
+            CreateTurtle |
+            DrawingProp(_) |
+            SetDrawingProp(_) |
+            ResetDrawingProp(_) |
+            Flush |
+            DrawCircle(..) |
+            AddLayer |
+            LayerIsVisible(_) |
+            SetLayerVisible(..) |
+            MoveLayer(..) |
+            DrawingBounds |
+            TurtleDrawingBounds(_) |
+            ExportMesh |
+            ExportSvg(_) |
+            Primitives |
+            Primitive(_) |
+            HitTest(_) |
+            ColorAt(_) |
+            MeasureText(..) |
+            FontMetrics(_) |
+            LoadImage(_) |
+            TurtleProp(..) |
+            SetTurtleProp(..) |
+            ResetTurtleProp(..) |
+            ResetTurtle(_) |
+            MoveForward(..) |
+            TimedMoveForward(..) |
+            MoveTo(..) |
+            RotateInPlace(..) |
+            TimedRotateInPlace(..) |
+            AnimatePenColor(..) |
+            MoveArc(..) |
+            MoveCircle(..) |
+            CubicBezierTo(..) |
+            QuadBezierTo(..) |
+            FollowPath(..) |
+            WriteText(..) |
+            Stamp(_) |
+            StampImage(..) |
+            ClearStamp(..) |
+            ClearStamps(_) |
+            Undo(..) |
+            PushState(_) |
+            PopState(_) |
+            PendingAnimations(_) |
+            BeginFill(_) |
+            EndFill(_) |
+            ClearAll |
+            ClearTurtle(_) |
+            ClearAnimated(_) |
+            ClearRegion(_) |
+            DebugTurtle(..) |
+            DebugDrawing => true,
+
+            Export(..) |
+            Import(..) |
+            SaveSession(_) |
+            LoadSession(_) |
+            Print(_) |
+            ExportToolpath(..) |
+            StartRecording(..) |
+            StopRecording |
+            RecordCommands(_) |
+            Share(_) |
+            ShareTcp(_) |
+            PollEvent |
+            NextEvent |
+            CancelNextEvent |
+            RequeueEvent(_) |
+            SetPaused(_) |
+            SetStepMode(_) |
+            Step => false,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ServerResponse {
     /// The ID of a newly created turtle, guaranteed to be unique
     NewTurtle(TurtleId),
 
-    /// The result of the export, possibly an error if something went wrong
+    /// The result of the export or print, possibly an error if something went wrong
     ExportComplete(Result<(), ExportError>),
 
+    /// The result of the import, possibly an error if something went wrong
+    ImportComplete(Result<(), ImportError>),
+
+    /// The result of saving or loading a session, possibly an error if something went wrong
+    SessionComplete(Result<(), SessionError>),
+
+    /// The result of sharing the drawing, possibly an error if something went wrong
+    ShareStarted(Result<(), ShareError>),
+
+    /// The address that the server is accepting TCP connections at, possibly an error if
+    /// something went wrong
+    TcpShareStarted(Result<SocketAddr, ShareError>),
+
     /// The next event, if any
     ///
-    /// A response of `None` only indicates that no events are *currently* available, NOT that no
-    /// events will *ever* be available.
+    /// In response to `PollEvent`, `None` only indicates that no events are *currently*
+    /// available, NOT that no events will *ever* be available. In response to `NextEvent`, `None`
+    /// indicates that the window has closed and no more events will be sent.
     Event(Option<Event>),
 
     /// The value of the given property of the drawing
     DrawingProp(DrawingPropValue),
 
+    /// The bounding box of everything that has been drawn, or `None` if nothing has been drawn
+    DrawingBounds(Option<Rect>),
+
+    /// The vertices and triangle indices of everything that has been drawn
+    Mesh(Vec<Vertex>, Vec<Index>),
+
+    /// The SVG document containing the drawing
+    Svg(String),
+
+    /// Every primitive that has been drawn, in the order they are drawn in
+    Primitives(Vec<Primitive>),
+
+    /// The requested primitive, or `None` if it is no longer part of the drawing
+    Primitive(Option<Primitive>),
+
+    /// The IDs of the primitives that contain the requested point, in the order they are drawn in
+    HitTest(Vec<PrimitiveId>),
+
+    /// The rendered color of the drawing at the requested point
+    ColorAt(Color),
+
+    /// The size of the measured or written text, possibly an error if the font could not be loaded
+    TextSize(Result<Size, FontError>),
+
+    /// The handle to a newly loaded image
+    ImageLoaded(ImageHandle),
+
+    /// The ID of a newly created stamp, guaranteed to be unique
+    NewStamp(StampId),
+
+    /// The ID of a newly created layer, guaranteed to be unique
+    NewLayer(LayerId),
+
+    /// Whether the layer is visible
+    LayerVisible(bool),
+
+    /// Whether a saved state was restored, `false` if there were no saved states
+    StatePopped(bool),
+
+    /// The measurements of a font, possibly an error if the font could not be loaded
+    FontMetrics(Result<FontMetrics, FontError>),
+
     /// The value of the given property of a turtle
     TurtleProp(TurtleId, TurtlePropValue),
 
     /// An animation was completed for a given turtle
     AnimationComplete(TurtleId),
 
+    /// The number of animations that have not completed yet
+    PendingAnimations(usize),
+
+    /// The drawing was cleared after a clear animation finished playing
+    ClearComplete,
+
     /// A representation of the entire state of a turtle, suitable for printing
     /// only
     DebugTurtle(TurtleId, debug::Turtle),
     /// A representation of the entire state of the drawing, suitable for
     /// printing only
     DebugDrawing(debug::Drawing),
+
+    /// The request could not be completed, so it was ignored
+    ///
+    /// Sent instead of the usual response to any request that has one.
+    Error(Error),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -194,49 +993,100 @@ pub enum RotationDirection {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ExportFormat {
     Svg,
+    Png,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToolpathFormat {
+    Gcode,
+    Hpgl,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DrawingProp {
     Title,
+    Icon,
     Background,
+    BackgroundImage,
     Center,
+    Zoom,
     Size,
     Width,
     Height,
     IsMaximized,
     IsFullscreen,
+    ResizeAnchor,
+    Minimap,
+    Filters,
+    Symmetry,
+    PressurePenWidth,
+    CoordinateSystem,
+    WorldCoordinates,
+    Dpi,
+    DisconnectBehavior,
+    Tracer,
+    IsAnimated,
+    PauseKey,
+    StepKey,
+    Navigation,
+    #[cfg(feature = "shaders")]
+    PostShader,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DrawingPropValue {
     Title(String),
+    Icon(Option<ImageHandle>),
     Background(Color),
+    BackgroundImage(Option<BackgroundImage>),
     Center(Point),
+    Zoom(f64),
     Size(Size),
     Width(u32),
     Height(u32),
     IsMaximized(bool),
     IsFullscreen(bool),
+    ResizeAnchor(ResizeAnchor),
+    Minimap(Option<Minimap>),
+    Filters(Vec<Filter>),
+    Symmetry(Option<Symmetry>),
+    PressurePenWidth(Option<f64>),
+    CoordinateSystem(CoordinateSystem),
+    WorldCoordinates(Option<WorldCoordinates>),
+    Dpi(f64),
+    DisconnectBehavior(DisconnectBehavior),
+    Tracer(u32),
+    IsAnimated(bool),
+    PauseKey(Option<Key>),
+    StepKey(Option<Key>),
+    Navigation(bool),
+    #[cfg(feature = "shaders")]
+    PostShader(Option<PostShader>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TurtleProp {
     Pen(PenProp),
     FillColor,
+    FillRule,
     IsFilling,
     Position,
     PositionX,
     PositionY,
     Heading,
     Speed,
+    Easing,
     IsVisible,
+    Shape,
+    ShapeSize,
+    Layer,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TurtlePropValue {
     Pen(PenPropValue),
     FillColor(Color),
+    FillRule(FillRule),
     /// NOTE: Instead of using this with `SetTurtleProp`, use `BeginFill` and `EndFill` instead.
     IsFilling(bool),
     /// NOTE: Instead of using this with `SetTurtleProp`, use `MoveTo` instead.
@@ -248,7 +1098,11 @@ pub enum TurtlePropValue {
     /// NOTE: Instead of using this with `SetTurtleProp`, use `RotateInPlace` instead.
     Heading(Radians),
     Speed(Speed),
+    Easing(Easing),
     IsVisible(bool),
+    Shape(Shape),
+    ShapeSize(f64),
+    Layer(LayerId),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -256,6 +1110,8 @@ pub enum PenProp {
     IsEnabled,
     Thickness,
     Color,
+    DashPattern,
+    BlendMode,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -263,4 +1119,6 @@ pub enum PenPropValue {
     IsEnabled(bool),
     Thickness(f64),
     Color(Color),
+    DashPattern(Vec<f64>),
+    BlendMode(BlendMode),
 }
@@ -1,15 +1,23 @@
+use std::io;
+use std::future::Future;
+use std::net::{SocketAddr, ToSocketAddrs};
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::renderer_client::RendererClient;
-use crate::renderer_server::{TurtleId, ExportError};
+use crate::renderer_server::{TurtleId, StampId, LayerId, is_valid_dash_pattern, ExportError, ImportError, SessionError, Minimap, Symmetry, Vertex, Index, Primitive, PrimitiveId, ClearEffect, ImageData, ImageHandle, ImageError, BackgroundImage};
+#[cfg(feature = "shaders")]
+use crate::renderer_server::PostShader;
 use crate::radians::Radians;
-use crate::{Distance, Point, Color, Speed, Event, Size, async_turtle::AngleUnit, debug};
+use crate::event::Key;
+use crate::{Error, Distance, Point, Color, Speed, Easing, Shape, FillRule, BlendMode, Event, Size, Rect, ResizeAnchor, CoordinateSystem, WorldCoordinates, DisconnectBehavior, ExportOptions, PrintOptions, PlotterOptions, ImportTransform, Filter, TextAlign, TextOptions, FontMetrics, FontError, async_turtle::AngleUnit, path::PathSegment, debug};
 
 use super::{
     ConnectionError,
     ClientRequest,
     ServerResponse,
     ExportFormat,
+    ToolpathFormat,
     DrawingProp,
     DrawingPropValue,
     TurtleProp,
@@ -17,6 +25,7 @@ use super::{
     PenProp,
     PenPropValue,
     RotationDirection,
+    ShareError,
 };
 
 /// A wrapper for `RendererClient` that encodes the the IPC protocol in a type-safe manner
@@ -37,45 +46,251 @@ impl ProtocolClient {
         Ok(client.into())
     }
 
+    /// Spawns a new server process that also accepts TCP connections at the first of the given
+    /// addresses that can be used, and creates a connection to it
+    pub async fn new_shared_tcp(addrs: Vec<SocketAddr>) -> Result<Self, ConnectionError> {
+        let client = RendererClient::new_shared_tcp(addrs).await?;
+        Ok(client.into())
+    }
+
+    /// Connects to a server that another process has shared using the given name
+    pub async fn join(name: &str) -> Result<Self, ConnectionError> {
+        let client = RendererClient::join(name).await?;
+        Ok(client.into())
+    }
+
+    /// Connects to a server on another machine through the TCP bridge at the given address
+    pub async fn join_tcp<A: ToSocketAddrs>(addr: A) -> Result<Self, ConnectionError> {
+        let client = RendererClient::join_tcp(addr).await?;
+        Ok(client.into())
+    }
+
+    /// Connects to a server that is already running in this process
+    ///
+    /// See `RendererClient::connect()` for more information.
+    pub async fn connect<S, F>(send_ipc_oneshot_name: S) -> Result<Self, ConnectionError>
+        where S: FnOnce(String) -> F,
+              F: Future<Output=io::Result<()>>,
+    {
+        let client = RendererClient::connect(send_ipc_oneshot_name).await?;
+        Ok(client.into())
+    }
+
     /// Creates a new renderer client that can also communicate to the same server
     pub async fn split(&self) -> Self {
         self.client.split().await.into()
     }
 
+    /// Sends the given request as-is, waiting for the response if the request has one
+    ///
+    /// This is only meant for passing along requests that come from outside of this crate. When
+    /// possible, prefer using the other methods of this type instead.
+    #[cfg_attr(not(feature = "websocket"), allow(dead_code))]
+    pub async fn forward(&self, req: ClientRequest) -> Option<ServerResponse> {
+        let has_response = req.has_response();
+        self.client.send(req);
+
+        if has_response {
+            Some(self.client.recv().await)
+        } else {
+            None
+        }
+    }
+
+    /// Receives the next response from the server
+    ///
+    /// This crate only sends requests that are valid, so an error from the server is always a bug.
+    async fn recv(&self) -> ServerResponse {
+        self.try_recv().await.unwrap_or_else(|err| match err {
+            // Not a bug since the window decides what is allowed over each connection
+            Error::NotAllowed(_) => panic!("The window refused the request: {}", err),
+            _ => panic!("The window was unable to complete the request: {}", err),
+        })
+    }
+
+    /// Waits for the response to the last request, or the error that the window sent instead
+    async fn try_recv(&self) -> Result<ServerResponse, Error> {
+        match self.client.recv().await {
+            ServerResponse::Error(err) => Err(err),
+            response => Ok(response),
+        }
+    }
+
+    /// Sends a request read from a command log and waits for its response, if it has one
+    ///
+    /// The response is not needed since the requests in the log already contain every ID that
+    /// the server gave out while the log was being written.
+    pub async fn replay_request(&self, request: ClientRequest) {
+        let has_response = request.has_response();
+        self.client.send(request);
+
+        if has_response {
+            self.client.recv().await;
+        }
+    }
+
     pub async fn create_turtle(&self) -> TurtleId {
         self.client.send(ClientRequest::CreateTurtle);
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::NewTurtle(id) => id,
             _ => unreachable!("bug: expected to receive `NewTurtle` in response to `CreateTurtle` request"),
         }
     }
 
-    pub async fn export_svg(&self, path: PathBuf) -> Result<(), ExportError> {
-        self.client.send(ClientRequest::Export(path, ExportFormat::Svg));
+    pub async fn export_svg(&self, path: PathBuf, options: ExportOptions) -> Result<(), ExportError> {
+        self.client.send(ClientRequest::Export(path, ExportFormat::Svg, options));
 
-        let response = self.client.recv().await;
+        let response = self.try_recv().await?;
         match response {
             ServerResponse::ExportComplete(res) => res,
             _ => unreachable!("bug: expected to receive `ExportComplete` in response to `Export` request"),
         }
     }
 
+    pub async fn export_png(&self, path: PathBuf, options: ExportOptions) -> Result<(), ExportError> {
+        self.client.send(ClientRequest::Export(path, ExportFormat::Png, options));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::ExportComplete(res) => res,
+            _ => unreachable!("bug: expected to receive `ExportComplete` in response to `Export` request"),
+        }
+    }
+
+    pub async fn start_recording(&self, path: PathBuf, fps: f64) -> Result<(), ExportError> {
+        self.client.send(ClientRequest::StartRecording(path, fps));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::ExportComplete(res) => res,
+            _ => unreachable!("bug: expected to receive `ExportComplete` in response to `StartRecording` request"),
+        }
+    }
+
+    pub async fn stop_recording(&self) -> Result<(), ExportError> {
+        self.client.send(ClientRequest::StopRecording);
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::ExportComplete(res) => res,
+            _ => unreachable!("bug: expected to receive `ExportComplete` in response to `StopRecording` request"),
+        }
+    }
+
+    pub async fn record_commands(&self, path: PathBuf) -> Result<(), ExportError> {
+        self.client.send(ClientRequest::RecordCommands(path));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::ExportComplete(res) => res,
+            _ => unreachable!("bug: expected to receive `ExportComplete` in response to `RecordCommands` request"),
+        }
+    }
+
+    pub async fn import_svg(&self, path: PathBuf, transform: ImportTransform) -> Result<(), ImportError> {
+        self.client.send(ClientRequest::Import(path, transform));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::ImportComplete(res) => res,
+            _ => unreachable!("bug: expected to receive `ImportComplete` in response to `Import` request"),
+        }
+    }
+
+    pub async fn save_session(&self, path: PathBuf) -> Result<(), SessionError> {
+        self.client.send(ClientRequest::SaveSession(path));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::SessionComplete(res) => res,
+            _ => unreachable!("bug: expected to receive `SessionComplete` in response to `SaveSession` request"),
+        }
+    }
+
+    pub async fn load_session(&self, path: PathBuf) -> Result<(), SessionError> {
+        self.client.send(ClientRequest::LoadSession(path));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::SessionComplete(res) => res,
+            _ => unreachable!("bug: expected to receive `SessionComplete` in response to `LoadSession` request"),
+        }
+    }
+
+    pub async fn print(&self, options: PrintOptions) -> Result<(), ExportError> {
+        self.client.send(ClientRequest::Print(options));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::ExportComplete(res) => res,
+            _ => unreachable!("bug: expected to receive `ExportComplete` in response to `Print` request"),
+        }
+    }
+
+    pub async fn export_toolpath(&self, path: PathBuf, format: ToolpathFormat, options: PlotterOptions) -> Result<(), ExportError> {
+        self.client.send(ClientRequest::ExportToolpath(path, format, options));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::ExportComplete(res) => res,
+            _ => unreachable!("bug: expected to receive `ExportComplete` in response to `ExportToolpath` request"),
+        }
+    }
+
+    pub async fn share(&self, name: String) -> Result<(), ShareError> {
+        self.client.send(ClientRequest::Share(name));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::ShareStarted(res) => res,
+            _ => unreachable!("bug: expected to receive `ShareStarted` in response to `Share` request"),
+        }
+    }
+
+    pub async fn share_tcp(&self, addrs: Vec<SocketAddr>) -> Result<SocketAddr, ShareError> {
+        self.client.send(ClientRequest::ShareTcp(addrs));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::TcpShareStarted(res) => res,
+            _ => unreachable!("bug: expected to receive `TcpShareStarted` in response to `ShareTcp` request"),
+        }
+    }
+
     pub async fn poll_event(&self) -> Option<Event> {
         self.client.send(ClientRequest::PollEvent);
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
+        match response {
+            ServerResponse::Event(event) => event,
+            _ => unreachable!("bug: expected to receive `Event` in response to `NextEvent` request"),
+        }
+    }
+
+    #[cfg_attr(not(feature = "unstable"), allow(dead_code))] // Only used by unstable methods
+    pub async fn next_event(&self) -> Option<Event> {
+        self.client.send(ClientRequest::NextEvent);
+
+        let response = self.recv().await;
         match response {
             ServerResponse::Event(event) => event,
             _ => unreachable!("bug: expected to receive `Event` in response to `NextEvent` request"),
         }
     }
 
+    /// Stops waiting for the response to `next_event()` after the future waiting for it was
+    /// dropped
+    #[cfg_attr(not(feature = "unstable"), allow(dead_code))] // Only used by unstable methods
+    pub fn cancel_next_event(&self) {
+        self.client.send_if_connected(ClientRequest::CancelNextEvent);
+    }
+
     pub async fn drawing_title(&self) -> String {
         self.client.send(ClientRequest::DrawingProp(DrawingProp::Title));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::DrawingProp(DrawingPropValue::Title(value)) => value,
             _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
@@ -85,7 +300,7 @@ impl ProtocolClient {
     pub async fn drawing_background(&self) -> Color {
         self.client.send(ClientRequest::DrawingProp(DrawingProp::Background));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::DrawingProp(DrawingPropValue::Background(value)) => value,
             _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
@@ -95,7 +310,7 @@ impl ProtocolClient {
     pub async fn drawing_center(&self) -> Point {
         self.client.send(ClientRequest::DrawingProp(DrawingProp::Center));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::DrawingProp(DrawingPropValue::Center(value)) => value,
             _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
@@ -105,7 +320,7 @@ impl ProtocolClient {
     pub async fn drawing_size(&self) -> Size {
         self.client.send(ClientRequest::DrawingProp(DrawingProp::Size));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::DrawingProp(DrawingPropValue::Size(value)) => value,
             _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
@@ -115,7 +330,7 @@ impl ProtocolClient {
     pub async fn drawing_is_maximized(&self) -> bool {
         self.client.send(ClientRequest::DrawingProp(DrawingProp::IsMaximized));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::DrawingProp(DrawingPropValue::IsMaximized(value)) => value,
             _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
@@ -125,22 +340,334 @@ impl ProtocolClient {
     pub async fn drawing_is_fullscreen(&self) -> bool {
         self.client.send(ClientRequest::DrawingProp(DrawingProp::IsFullscreen));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::DrawingProp(DrawingPropValue::IsFullscreen(value)) => value,
             _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
         }
     }
 
+    pub async fn drawing_resize_anchor(&self) -> ResizeAnchor {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::ResizeAnchor));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::ResizeAnchor(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_filters(&self) -> Vec<Filter> {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::Filters));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::Filters(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_pressure_pen_width(&self) -> Option<f64> {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::PressurePenWidth));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::PressurePenWidth(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_coordinate_system(&self) -> CoordinateSystem {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::CoordinateSystem));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::CoordinateSystem(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_world_coordinates(&self) -> Option<WorldCoordinates> {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::WorldCoordinates));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::WorldCoordinates(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_zoom(&self) -> f64 {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::Zoom));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::Zoom(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_dpi(&self) -> f64 {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::Dpi));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::Dpi(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_disconnect_behavior(&self) -> DisconnectBehavior {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::DisconnectBehavior));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::DisconnectBehavior(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_tracer(&self) -> u32 {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::Tracer));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::Tracer(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_is_animated(&self) -> bool {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::IsAnimated));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::IsAnimated(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_pause_key(&self) -> Option<Key> {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::PauseKey));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::PauseKey(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_step_key(&self) -> Option<Key> {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::StepKey));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::StepKey(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub async fn drawing_navigation(&self) -> bool {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::Navigation));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::Navigation(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub fn draw_circle(&self, center: Point, radius: Distance, fill_color: Color) {
+        debug_assert!(fill_color.is_valid(), "bug: colors should be validated before sending to renderer server");
+        self.client.send(ClientRequest::DrawCircle(center, radius, fill_color))
+    }
+
+    pub async fn add_layer(&self) -> LayerId {
+        self.client.send(ClientRequest::AddLayer);
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::NewLayer(id) => id,
+            _ => unreachable!("bug: expected to receive `NewLayer` in response to `AddLayer` request"),
+        }
+    }
+
+    pub async fn layer_is_visible(&self, layer: LayerId) -> Result<bool, Error> {
+        self.client.send(ClientRequest::LayerIsVisible(layer));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::LayerVisible(is_visible) => Ok(is_visible),
+            _ => unreachable!("bug: expected to receive `LayerVisible` in response to `LayerIsVisible` request"),
+        }
+    }
+
+    pub fn set_layer_visible(&self, layer: LayerId, is_visible: bool) {
+        self.client.send(ClientRequest::SetLayerVisible(layer, is_visible))
+    }
+
+    pub fn move_layer(&self, layer: LayerId, top: bool) {
+        self.client.send(ClientRequest::MoveLayer(layer, top))
+    }
+
+    pub async fn drawing_bounds(&self) -> Option<Rect> {
+        self.client.send(ClientRequest::DrawingBounds);
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingBounds(bounds) => bounds,
+            _ => unreachable!("bug: expected to receive `DrawingBounds` in response to `DrawingBounds` request"),
+        }
+    }
+
+    pub async fn turtle_drawing_bounds(&self, id: TurtleId) -> Option<Rect> {
+        self.client.send(ClientRequest::TurtleDrawingBounds(id));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingBounds(bounds) => bounds,
+            _ => unreachable!("bug: expected to receive `DrawingBounds` in response to `TurtleDrawingBounds` request"),
+        }
+    }
+
+    pub async fn drawing_pending_animations(&self) -> usize {
+        self.client.send(ClientRequest::PendingAnimations(None));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::PendingAnimations(count) => count,
+            _ => unreachable!("bug: expected to receive `PendingAnimations` in response to `PendingAnimations` request"),
+        }
+    }
+
+    pub async fn drawing_export_mesh(&self) -> (Vec<Vertex>, Vec<Index>) {
+        self.client.send(ClientRequest::ExportMesh);
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::Mesh(vertices, indices) => (vertices, indices),
+            _ => unreachable!("bug: expected to receive `Mesh` in response to `ExportMesh` request"),
+        }
+    }
+
+    pub async fn drawing_export_svg(&self, options: ExportOptions) -> String {
+        self.client.send(ClientRequest::ExportSvg(options));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::Svg(svg) => svg,
+            _ => unreachable!("bug: expected to receive `Svg` in response to `ExportSvg` request"),
+        }
+    }
+
+    pub async fn drawing_primitives(&self) -> Vec<Primitive> {
+        self.client.send(ClientRequest::Primitives);
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::Primitives(primitives) => primitives,
+            _ => unreachable!("bug: expected to receive `Primitives` in response to `Primitives` request"),
+        }
+    }
+
+    pub async fn drawing_primitive(&self, id: PrimitiveId) -> Option<Primitive> {
+        self.client.send(ClientRequest::Primitive(id));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::Primitive(primitive) => primitive,
+            _ => unreachable!("bug: expected to receive `Primitive` in response to `Primitive` request"),
+        }
+    }
+
+    pub async fn drawing_hit_test(&self, point: Point) -> Vec<PrimitiveId> {
+        self.client.send(ClientRequest::HitTest(point));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::HitTest(ids) => ids,
+            _ => unreachable!("bug: expected to receive `HitTest` in response to `HitTest` request"),
+        }
+    }
+
+    pub async fn drawing_color_at(&self, point: Point) -> Color {
+        self.client.send(ClientRequest::ColorAt(point));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::ColorAt(color) => color,
+            _ => unreachable!("bug: expected to receive `ColorAt` in response to `ColorAt` request"),
+        }
+    }
+
+    pub async fn measure_text(&self, text: String, options: TextOptions) -> Result<Size, FontError> {
+        debug_assert!(options.is_valid(), "bug: text options should be validated before sending to renderer server");
+        self.client.send(ClientRequest::MeasureText(text, options));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::TextSize(res) => res,
+            _ => unreachable!("bug: expected to receive `TextSize` in response to `MeasureText` request"),
+        }
+    }
+
+    pub async fn load_image(&self, image: ImageData) -> Result<ImageHandle, ImageError> {
+        debug_assert!(image.is_valid(), "bug: images should be validated before sending to renderer server");
+        self.client.send(ClientRequest::LoadImage(image));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::ImageLoaded(handle) => Ok(handle),
+            _ => unreachable!("bug: expected to receive `ImageLoaded` in response to `LoadImage` request"),
+        }
+    }
+
+    pub async fn font_metrics(&self, options: TextOptions) -> Result<FontMetrics, FontError> {
+        debug_assert!(options.is_valid(), "bug: text options should be validated before sending to renderer server");
+        self.client.send(ClientRequest::FontMetrics(options));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::FontMetrics(res) => res,
+            _ => unreachable!("bug: expected to receive `FontMetrics` in response to `FontMetrics` request"),
+        }
+    }
+
     pub fn drawing_set_title(&self, value: String) {
         self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::Title(value)))
     }
 
+    pub async fn drawing_icon(&self) -> Option<ImageHandle> {
+        self.client.send(ClientRequest::DrawingProp(DrawingProp::Icon));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::DrawingProp(DrawingPropValue::Icon(value)) => value,
+            _ => unreachable!("bug: expected to receive `DrawingProp` in response to `DrawingProp` request"),
+        }
+    }
+
+    pub fn drawing_set_icon(&self, value: ImageHandle) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::Icon(Some(value))))
+    }
+
+    pub fn drawing_reset_icon(&self) {
+        self.client.send(ClientRequest::ResetDrawingProp(DrawingProp::Icon))
+    }
+
     pub fn drawing_set_background(&self, value: Color) {
         debug_assert!(value.is_valid(), "bug: colors should be validated before sending to renderer server");
         self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::Background(value)))
     }
 
+    pub fn drawing_set_background_image(&self, value: BackgroundImage) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::BackgroundImage(Some(value))))
+    }
+
+    pub fn drawing_reset_background_image(&self) {
+        self.client.send(ClientRequest::ResetDrawingProp(DrawingProp::BackgroundImage))
+    }
+
     pub fn drawing_set_center(&self, value: Point) {
         debug_assert!(value.is_finite(), "bug: center should be validated before sending to renderer server");
         self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::Center(value)))
@@ -159,6 +686,118 @@ impl ProtocolClient {
         self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::IsFullscreen(value)))
     }
 
+    pub fn drawing_set_resize_anchor(&self, value: ResizeAnchor) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::ResizeAnchor(value)))
+    }
+
+    pub fn drawing_set_minimap(&self, value: Option<Minimap>) {
+        if let Some(minimap) = value {
+            debug_assert!(minimap.size.width > 0 && minimap.size.height > 0, "bug: minimap size should be validated before sending to renderer server");
+        }
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::Minimap(value)))
+    }
+
+    pub fn drawing_set_filters(&self, value: Vec<Filter>) {
+        debug_assert!(value.iter().all(|filter| filter.is_valid()), "bug: filters should be validated before sending to renderer server");
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::Filters(value)))
+    }
+
+    pub fn drawing_reset_filters(&self) {
+        self.client.send(ClientRequest::ResetDrawingProp(DrawingProp::Filters))
+    }
+
+    pub fn drawing_set_pressure_pen_width(&self, value: f64) {
+        debug_assert!(value.is_finite() && value > 0.0, "bug: pen width should be validated before sending to renderer server");
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::PressurePenWidth(Some(value))))
+    }
+
+    pub fn drawing_reset_pressure_pen_width(&self) {
+        self.client.send(ClientRequest::ResetDrawingProp(DrawingProp::PressurePenWidth))
+    }
+
+    pub fn drawing_set_coordinate_system(&self, value: CoordinateSystem) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::CoordinateSystem(value)))
+    }
+
+    pub fn drawing_set_world_coordinates(&self, value: WorldCoordinates) {
+        debug_assert!(value.is_valid(), "bug: world coordinates should be validated before sending to renderer server");
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::WorldCoordinates(Some(value))))
+    }
+
+    pub fn drawing_reset_world_coordinates(&self) {
+        self.client.send(ClientRequest::ResetDrawingProp(DrawingProp::WorldCoordinates))
+    }
+
+    pub fn drawing_set_zoom(&self, value: f64) {
+        debug_assert!(value.is_finite() && value > 0.0, "bug: zoom should be validated before sending to renderer server");
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::Zoom(value)))
+    }
+
+    pub fn drawing_reset_zoom(&self) {
+        self.client.send(ClientRequest::ResetDrawingProp(DrawingProp::Zoom))
+    }
+
+    pub fn drawing_set_dpi(&self, value: f64) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::Dpi(value)))
+    }
+
+    pub fn drawing_set_disconnect_behavior(&self, value: DisconnectBehavior) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::DisconnectBehavior(value)))
+    }
+
+    pub fn drawing_set_tracer(&self, value: u32) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::Tracer(value)))
+    }
+
+    pub fn drawing_set_is_animated(&self, value: bool) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::IsAnimated(value)))
+    }
+
+    pub fn drawing_flush(&self) {
+        self.client.send(ClientRequest::Flush)
+    }
+
+    pub fn drawing_set_paused(&self, value: bool) {
+        self.client.send(ClientRequest::SetPaused(value))
+    }
+
+    pub fn drawing_set_pause_key(&self, value: Option<Key>) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::PauseKey(value)))
+    }
+
+    pub fn drawing_set_step_mode(&self, value: bool) {
+        self.client.send(ClientRequest::SetStepMode(value))
+    }
+
+    pub fn drawing_step(&self) {
+        self.client.send(ClientRequest::Step)
+    }
+
+    pub fn drawing_set_step_key(&self, value: Option<Key>) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::StepKey(value)))
+    }
+
+    pub fn drawing_set_navigation(&self, value: bool) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::Navigation(value)))
+    }
+
+    pub fn drawing_set_symmetry(&self, value: Option<Symmetry>) {
+        if let Some(symmetry) = value {
+            debug_assert!(symmetry.axes > 0, "bug: symmetry axes should be validated before sending to renderer server");
+        }
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::Symmetry(value)))
+    }
+
+    #[cfg(feature = "shaders")]
+    pub fn drawing_set_post_shader(&self, value: PostShader) {
+        self.client.send(ClientRequest::SetDrawingProp(DrawingPropValue::PostShader(Some(value))))
+    }
+
+    #[cfg(feature = "shaders")]
+    pub fn drawing_reset_post_shader(&self) {
+        self.client.send(ClientRequest::ResetDrawingProp(DrawingProp::PostShader))
+    }
+
     pub fn drawing_reset_center(&self) {
         self.client.send(ClientRequest::ResetDrawingProp(DrawingProp::Center))
     }
@@ -170,7 +809,7 @@ impl ProtocolClient {
     pub async fn turtle_pen_is_enabled(&self, id: TurtleId) -> bool {
         self.client.send(ClientRequest::TurtleProp(id, TurtleProp::Pen(PenProp::IsEnabled)));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::TurtleProp(recv_id, TurtlePropValue::Pen(PenPropValue::IsEnabled(value))) => {
                 debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
@@ -183,7 +822,7 @@ impl ProtocolClient {
     pub async fn turtle_pen_thickness(&self, id: TurtleId) -> f64 {
         self.client.send(ClientRequest::TurtleProp(id, TurtleProp::Pen(PenProp::Thickness)));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::TurtleProp(recv_id, TurtlePropValue::Pen(PenPropValue::Thickness(value))) => {
                 debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
@@ -196,7 +835,7 @@ impl ProtocolClient {
     pub async fn turtle_pen_color(&self, id: TurtleId) -> Color {
         self.client.send(ClientRequest::TurtleProp(id, TurtleProp::Pen(PenProp::Color)));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::TurtleProp(recv_id, TurtlePropValue::Pen(PenPropValue::Color(value))) => {
                 debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
@@ -206,10 +845,36 @@ impl ProtocolClient {
         }
     }
 
+    pub async fn turtle_pen_dash_pattern(&self, id: TurtleId) -> Vec<f64> {
+        self.client.send(ClientRequest::TurtleProp(id, TurtleProp::Pen(PenProp::DashPattern)));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::TurtleProp(recv_id, TurtlePropValue::Pen(PenPropValue::DashPattern(value))) => {
+                debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
+                value
+            },
+            _ => unreachable!("bug: expected to receive `TurtleProp` in response to `TurtleProp` request"),
+        }
+    }
+
+    pub async fn turtle_pen_blend_mode(&self, id: TurtleId) -> BlendMode {
+        self.client.send(ClientRequest::TurtleProp(id, TurtleProp::Pen(PenProp::BlendMode)));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::TurtleProp(recv_id, TurtlePropValue::Pen(PenPropValue::BlendMode(value))) => {
+                debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
+                value
+            },
+            _ => unreachable!("bug: expected to receive `TurtleProp` in response to `TurtleProp` request"),
+        }
+    }
+
     pub async fn turtle_fill_color(&self, id: TurtleId) -> Color {
         self.client.send(ClientRequest::TurtleProp(id, TurtleProp::FillColor));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::TurtleProp(recv_id, TurtlePropValue::FillColor(value)) => {
                 debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
@@ -219,10 +884,23 @@ impl ProtocolClient {
         }
     }
 
+    pub async fn turtle_fill_rule(&self, id: TurtleId) -> FillRule {
+        self.client.send(ClientRequest::TurtleProp(id, TurtleProp::FillRule));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::TurtleProp(recv_id, TurtlePropValue::FillRule(value)) => {
+                debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
+                value
+            },
+            _ => unreachable!("bug: expected to receive `TurtleProp` in response to `TurtleProp` request"),
+        }
+    }
+
     pub async fn turtle_is_filling(&self, id: TurtleId) -> bool {
         self.client.send(ClientRequest::TurtleProp(id, TurtleProp::IsFilling));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::TurtleProp(recv_id, TurtlePropValue::IsFilling(value)) => {
                 debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
@@ -235,7 +913,7 @@ impl ProtocolClient {
     pub async fn turtle_position(&self, id: TurtleId) -> Point {
         self.client.send(ClientRequest::TurtleProp(id, TurtleProp::Position));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::TurtleProp(recv_id, TurtlePropValue::Position(value)) => {
                 debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
@@ -248,7 +926,7 @@ impl ProtocolClient {
     pub async fn turtle_heading(&self, id: TurtleId) -> Radians {
         self.client.send(ClientRequest::TurtleProp(id, TurtleProp::Heading));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::TurtleProp(recv_id, TurtlePropValue::Heading(value)) => {
                 debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
@@ -258,10 +936,23 @@ impl ProtocolClient {
         }
     }
 
+    pub async fn turtle_easing(&self, id: TurtleId) -> Easing {
+        self.client.send(ClientRequest::TurtleProp(id, TurtleProp::Easing));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::TurtleProp(recv_id, TurtlePropValue::Easing(value)) => {
+                debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
+                value
+            },
+            _ => unreachable!("bug: expected to receive `TurtleProp` in response to `TurtleProp` request"),
+        }
+    }
+
     pub async fn turtle_speed(&self, id: TurtleId) -> Speed {
         self.client.send(ClientRequest::TurtleProp(id, TurtleProp::Speed));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::TurtleProp(recv_id, TurtlePropValue::Speed(value)) => {
                 debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
@@ -274,7 +965,7 @@ impl ProtocolClient {
     pub async fn turtle_is_visible(&self, id: TurtleId) -> bool {
         self.client.send(ClientRequest::TurtleProp(id, TurtleProp::IsVisible));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::TurtleProp(recv_id, TurtlePropValue::IsVisible(value)) => {
                 debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
@@ -284,6 +975,45 @@ impl ProtocolClient {
         }
     }
 
+    pub async fn turtle_shape(&self, id: TurtleId) -> Shape {
+        self.client.send(ClientRequest::TurtleProp(id, TurtleProp::Shape));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::TurtleProp(recv_id, TurtlePropValue::Shape(value)) => {
+                debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
+                value
+            },
+            _ => unreachable!("bug: expected to receive `TurtleProp` in response to `TurtleProp` request"),
+        }
+    }
+
+    pub async fn turtle_shape_size(&self, id: TurtleId) -> f64 {
+        self.client.send(ClientRequest::TurtleProp(id, TurtleProp::ShapeSize));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::TurtleProp(recv_id, TurtlePropValue::ShapeSize(value)) => {
+                debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
+                value
+            },
+            _ => unreachable!("bug: expected to receive `TurtleProp` in response to `TurtleProp` request"),
+        }
+    }
+
+    pub async fn turtle_layer(&self, id: TurtleId) -> LayerId {
+        self.client.send(ClientRequest::TurtleProp(id, TurtleProp::Layer));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::TurtleProp(recv_id, TurtlePropValue::Layer(value)) => {
+                debug_assert_eq!(id, recv_id, "bug: received data for incorrect turtle");
+                value
+            },
+            _ => unreachable!("bug: expected to receive `TurtleProp` in response to `TurtleProp` request"),
+        }
+    }
+
     pub fn turtle_pen_set_is_enabled(&self, id: TurtleId, value: bool) {
         self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::Pen(PenPropValue::IsEnabled(value))))
     }
@@ -293,6 +1023,15 @@ impl ProtocolClient {
         self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::Pen(PenPropValue::Thickness(value))))
     }
 
+    pub fn turtle_pen_set_dash_pattern(&self, id: TurtleId, value: Vec<f64>) {
+        debug_assert!(is_valid_dash_pattern(&value), "bug: dash patterns should be validated before sending to renderer server");
+        self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::Pen(PenPropValue::DashPattern(value))))
+    }
+
+    pub fn turtle_pen_set_blend_mode(&self, id: TurtleId, value: BlendMode) {
+        self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::Pen(PenPropValue::BlendMode(value))))
+    }
+
     pub fn turtle_pen_set_color(&self, id: TurtleId, value: Color) {
         debug_assert!(value.is_valid(), "bug: colors should be validated before sending to renderer server");
         self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::Pen(PenPropValue::Color(value))))
@@ -303,14 +1042,37 @@ impl ProtocolClient {
         self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::FillColor(value)))
     }
 
+    pub fn turtle_set_fill_rule(&self, id: TurtleId, value: FillRule) {
+        self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::FillRule(value)))
+    }
+
     pub fn turtle_set_speed(&self, id: TurtleId, value: Speed) {
         self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::Speed(value)))
     }
 
+    pub fn turtle_set_easing(&self, id: TurtleId, value: Easing) {
+        debug_assert!(value.is_valid(), "bug: easings should be validated before sending to renderer server");
+        self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::Easing(value)))
+    }
+
     pub fn turtle_set_is_visible(&self, id: TurtleId, value: bool) {
         self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::IsVisible(value)))
     }
 
+    pub fn turtle_set_shape(&self, id: TurtleId, value: Shape) {
+        debug_assert!(value.is_valid(), "bug: shapes should be validated before sending to renderer server");
+        self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::Shape(value)))
+    }
+
+    pub fn turtle_set_shape_size(&self, id: TurtleId, value: f64) {
+        debug_assert!(value > 0.0 && value.is_finite(), "bug: shape size should be validated before sending to renderer server");
+        self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::ShapeSize(value)))
+    }
+
+    pub fn turtle_set_layer(&self, id: TurtleId, value: LayerId) {
+        self.client.send(ClientRequest::SetTurtleProp(id, TurtlePropValue::Layer(value)))
+    }
+
     pub fn turtle_reset_heading(&self, id: TurtleId) {
         self.client.send(ClientRequest::ResetTurtleProp(id, TurtleProp::Heading))
     }
@@ -326,7 +1088,7 @@ impl ProtocolClient {
 
         self.client.send(ClientRequest::MoveForward(id, distance));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::AnimationComplete(recv_id) => {
                 debug_assert_eq!(id, recv_id, "bug: notified of complete animation for incorrect turtle");
@@ -335,6 +1097,22 @@ impl ProtocolClient {
         }
     }
 
+    pub async fn timed_move_forward(&self, id: TurtleId, distance: Distance, duration: Duration) {
+        if !distance.is_normal() {
+            return;
+        }
+
+        self.client.send(ClientRequest::TimedMoveForward(id, distance, duration));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::AnimationComplete(recv_id) => {
+                debug_assert_eq!(id, recv_id, "bug: notified of complete animation for incorrect turtle");
+            },
+            _ => unreachable!("bug: expected to receive `AnimationComplete` in response to `TimedMoveForward` request"),
+        }
+    }
+
     pub async fn move_to(&self, id: TurtleId, target: Point) {
         if !target.is_finite() {
             return;
@@ -342,7 +1120,7 @@ impl ProtocolClient {
 
         self.client.send(ClientRequest::MoveTo(id, target));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::AnimationComplete(recv_id) => {
                 debug_assert_eq!(id, recv_id, "bug: notified of complete animation for incorrect turtle");
@@ -358,7 +1136,7 @@ impl ProtocolClient {
 
         self.client.send(ClientRequest::RotateInPlace(id, angle, direction));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::AnimationComplete(recv_id) => {
                 debug_assert_eq!(id, recv_id, "bug: notified of complete animation for incorrect turtle");
@@ -367,18 +1145,170 @@ impl ProtocolClient {
         }
     }
 
+    pub async fn timed_rotate_in_place(&self, id: TurtleId, angle: Radians, direction: RotationDirection, duration: Duration) {
+        if !angle.is_normal() {
+            return;
+        }
+
+        self.client.send(ClientRequest::TimedRotateInPlace(id, angle, direction, duration));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::AnimationComplete(recv_id) => {
+                debug_assert_eq!(id, recv_id, "bug: notified of complete animation for incorrect turtle");
+            },
+            _ => unreachable!("bug: expected to receive `AnimationComplete` in response to `TimedRotateInPlace` request"),
+        }
+    }
+
+    pub fn turtle_animate_pen_color(&self, id: TurtleId, color: Color, duration: Duration) {
+        self.client.send(ClientRequest::AnimatePenColor(id, color, duration));
+    }
+
+    pub async fn turtle_pending_animations(&self, id: TurtleId) -> usize {
+        self.client.send(ClientRequest::PendingAnimations(Some(id)));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::PendingAnimations(count) => count,
+            _ => unreachable!("bug: expected to receive `PendingAnimations` in response to `PendingAnimations` request"),
+        }
+    }
+
     pub async fn circular_arc(&self, id: TurtleId, radius: Distance, extent: Radians, direction: RotationDirection) {
         if !radius.is_normal() || !extent.is_normal() {
             return;
         }
 
-        let steps = 250;    // Arbitrary value for now.
-        let step = radius.abs() * extent.to_radians() / steps as f64;
-        let rotation = radius.signum() * extent / steps as f64;
+        self.client.send(ClientRequest::MoveArc(id, radius, extent, direction));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::AnimationComplete(recv_id) => {
+                debug_assert_eq!(id, recv_id, "bug: notified of complete animation for incorrect turtle");
+            },
+            _ => unreachable!("bug: expected to receive `AnimationComplete` in response to `MoveArc` request"),
+        }
+    }
+
+    pub async fn move_circle(&self, id: TurtleId, radius: Distance) {
+        if !radius.is_normal() {
+            return;
+        }
+
+        self.client.send(ClientRequest::MoveCircle(id, radius));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::AnimationComplete(recv_id) => {
+                debug_assert_eq!(id, recv_id, "bug: notified of complete animation for incorrect turtle");
+            },
+            _ => unreachable!("bug: expected to receive `AnimationComplete` in response to `MoveCircle` request"),
+        }
+    }
+
+    pub async fn write_text(&self, id: TurtleId, text: String, options: TextOptions, align: TextAlign) -> Result<Size, FontError> {
+        debug_assert!(options.is_valid(), "bug: text options should be validated before sending to renderer server");
+        self.client.send(ClientRequest::WriteText(id, text, options, align));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::TextSize(res) => res,
+            _ => unreachable!("bug: expected to receive `TextSize` in response to `WriteText` request"),
+        }
+    }
+
+    pub async fn stamp(&self, id: TurtleId) -> StampId {
+        self.client.send(ClientRequest::Stamp(id));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::NewStamp(stamp_id) => stamp_id,
+            _ => unreachable!("bug: expected to receive `NewStamp` in response to `Stamp` request"),
+        }
+    }
+
+    pub async fn stamp_image(&self, id: TurtleId, image: ImageHandle) -> Result<StampId, Error> {
+        self.client.send(ClientRequest::StampImage(id, image));
+
+        let response = self.try_recv().await?;
+        match response {
+            ServerResponse::NewStamp(stamp_id) => Ok(stamp_id),
+            _ => unreachable!("bug: expected to receive `NewStamp` in response to `StampImage` request"),
+        }
+    }
+
+    pub fn clear_stamp(&self, id: TurtleId, stamp_id: StampId) {
+        self.client.send(ClientRequest::ClearStamp(id, stamp_id))
+    }
+
+    pub fn clear_stamps(&self, id: TurtleId) {
+        self.client.send(ClientRequest::ClearStamps(id))
+    }
+
+    pub fn undo(&self, id: TurtleId, count: usize) {
+        self.client.send(ClientRequest::Undo(id, count))
+    }
+
+    pub fn push_state(&self, id: TurtleId) {
+        self.client.send(ClientRequest::PushState(id))
+    }
+
+    pub async fn pop_state(&self, id: TurtleId) -> bool {
+        self.client.send(ClientRequest::PopState(id));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::StatePopped(popped) => popped,
+            _ => unreachable!("bug: expected to receive `StatePopped` in response to `PopState` request"),
+        }
+    }
+
+    pub async fn cubic_bezier_to(&self, id: TurtleId, control1: Point, control2: Point, end: Point) {
+        if !control1.is_finite() || !control2.is_finite() || !end.is_finite() {
+            return;
+        }
+
+        self.client.send(ClientRequest::CubicBezierTo(id, control1, control2, end));
 
-        for _ in 0..steps {
-            self.move_forward(id, step).await;
-            self.rotate_in_place(id, rotation, direction).await;
+        let response = self.recv().await;
+        match response {
+            ServerResponse::AnimationComplete(recv_id) => {
+                debug_assert_eq!(id, recv_id, "bug: notified of complete animation for incorrect turtle");
+            },
+            _ => unreachable!("bug: expected to receive `AnimationComplete` in response to `CubicBezierTo` request"),
+        }
+    }
+
+    pub async fn follow_path(&self, id: TurtleId, path: Vec<PathSegment>) {
+        if path.is_empty() || !path.iter().all(|segment| segment.is_finite()) {
+            return;
+        }
+
+        self.client.send(ClientRequest::FollowPath(id, path));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::AnimationComplete(recv_id) => {
+                debug_assert_eq!(id, recv_id, "bug: notified of complete animation for incorrect turtle");
+            },
+            _ => unreachable!("bug: expected to receive `AnimationComplete` in response to `FollowPath` request"),
+        }
+    }
+
+    pub async fn quad_bezier_to(&self, id: TurtleId, control: Point, end: Point) {
+        if !control.is_finite() || !end.is_finite() {
+            return;
+        }
+
+        self.client.send(ClientRequest::QuadBezierTo(id, control, end));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::AnimationComplete(recv_id) => {
+                debug_assert_eq!(id, recv_id, "bug: notified of complete animation for incorrect turtle");
+            },
+            _ => unreachable!("bug: expected to receive `AnimationComplete` in response to `QuadBezierTo` request"),
         }
     }
 
@@ -398,10 +1328,24 @@ impl ProtocolClient {
         self.client.send(ClientRequest::ClearTurtle(id))
     }
 
+    pub fn clear_region(&self, area: Rect) {
+        self.client.send(ClientRequest::ClearRegion(area))
+    }
+
+    pub async fn clear_animated(&self, effect: ClearEffect) {
+        self.client.send(ClientRequest::ClearAnimated(effect));
+
+        let response = self.recv().await;
+        match response {
+            ServerResponse::ClearComplete => {},
+            _ => unreachable!("bug: expected to receive `ClearComplete` in response to `ClearAnimated` request"),
+        }
+    }
+
     pub async fn debug_turtle(&self, id: TurtleId, angle_unit: AngleUnit) -> debug::Turtle {
         self.client.send(ClientRequest::DebugTurtle(id, angle_unit));
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::DebugTurtle(recv_id, state) => {
                 debug_assert_eq!(id, recv_id, "bug: received debug turtle for incorrect turtle");
@@ -414,7 +1358,7 @@ impl ProtocolClient {
     pub async fn debug_drawing(&self) -> debug::Drawing {
         self.client.send(ClientRequest::DebugDrawing);
 
-        let response = self.client.recv().await;
+        let response = self.recv().await;
         match response {
             ServerResponse::DebugDrawing(state) => {
                 state
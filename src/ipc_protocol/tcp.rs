@@ -0,0 +1,387 @@
+//! Carries the protocol over TCP so that a program can draw in a window on another machine
+//!
+//! The server accepts TCP connections at an address chosen when the drawing is shared. Each TCP
+//! connection is added to the server as its own connection, just like a process that joined a
+//! shared drawing. Both sides use the TCP connection through the `TransportSender` and
+//! `TransportReceiver` traits, so the rest of the client and server work the same way no matter
+//! which transport is being used.
+//!
+//! Every message is encoded with bincode (the same encoding used by ipc-channel) and prefixed by
+//! its length as a little-endian `u32`. Both ends must be built with the same version of this
+//! crate since the encoding of the messages changes between versions. To check this, the
+//! connecting side first sends its `PROTOCOL_VERSION` and the server replies with its own before
+//! any requests are sent.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::convert::TryFrom;
+use std::sync::{Arc, mpsc as std_mpsc};
+use std::thread;
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+use serde::{Serialize, de::DeserializeOwned};
+use parking_lot::Mutex;
+use tokio::{runtime::Handle, sync::mpsc};
+use futures_util::future::{BoxFuture, FutureExt};
+use ipc_channel::ipc::IpcError;
+
+use crate::Error;
+use crate::renderer_client::ClientId;
+
+use super::{
+    ClientRequest,
+    ServerResponse,
+    ConnectionError,
+    ClientSender,
+    ClientReceiver,
+    ServerSender,
+    ClientMessage,
+    ClientResponse,
+    TransportSender,
+    TransportReceiver,
+    PROTOCOL_VERSION,
+};
+
+/// The largest message that will be accepted from a connection (in bytes)
+///
+/// This prevents a corrupted length from allocating an enormous buffer.
+const MAX_MESSAGE_LEN: u32 = 1 << 26;
+
+/// Sends messages over a TCP connection
+///
+/// The messages are written by a separate thread since writing to a `TcpStream` blocks until the
+/// other side reads what was sent. A slow connection must never hold up the task that sends the
+/// messages, since that task is shared by every other connection to the server. The connection is
+/// shut down once every message sent before this is dropped has been written, so that the other
+/// side finds out that no more messages will be sent.
+#[derive(Debug)]
+struct TcpSender {
+    outgoing: std_mpsc::Sender<Outgoing>,
+}
+
+/// Tells the thread writing to a TCP connection what to do next
+#[derive(Debug)]
+enum Outgoing {
+    /// Write an encoded message
+    Message(Vec<u8>),
+    /// Shut down the connection once everything before this has been written
+    Close,
+}
+
+impl TcpSender {
+    /// Starts writing the messages sent with the returned sender to the given connection
+    fn new(mut writer: TcpStream) -> Self {
+        let (outgoing, messages) = std_mpsc::channel();
+        thread::spawn(move || {
+            for message in messages {
+                let bytes = match message {
+                    Outgoing::Message(bytes) => bytes,
+                    Outgoing::Close => break,
+                };
+
+                // Dropping the receiver when this loop ends signals that the other side is gone
+                if writer.write_all(&bytes).and_then(|()| writer.flush()).is_err() {
+                    break;
+                }
+            }
+
+            // Ignoring the error since it just means that the other side is already gone
+            writer.shutdown(Shutdown::Both).unwrap_or(());
+        });
+
+        Self {outgoing}
+    }
+
+    /// Queues a message to be written without waiting for it to be sent
+    fn queue<T: Serialize>(outgoing: &std_mpsc::Sender<Outgoing>, message: &T) -> io::Result<()> {
+        let bytes = encode_message(message)?;
+        outgoing.send(Outgoing::Message(bytes)).map_err(|_| {
+            io::Error::new(io::ErrorKind::BrokenPipe, "the other side of the connection has disconnected")
+        })
+    }
+}
+
+impl<T: Serialize> TransportSender<T> for TcpSender {
+    fn send(&self, message: T) -> Result<(), ipc_channel::Error> {
+        Self::queue(&self.outgoing, &message)
+            .map_err(|err| Box::new(bincode::ErrorKind::Io(err)))
+    }
+}
+
+impl Drop for TcpSender {
+    fn drop(&mut self) {
+        // Ignoring the error since it just means that the connection is already closed
+        self.outgoing.send(Outgoing::Close).unwrap_or(());
+    }
+}
+
+/// Receives messages from a TCP connection
+///
+/// The messages are read by a separate thread since reading from a `TcpStream` blocks.
+struct TcpReceiver<T> {
+    messages: tokio::sync::Mutex<mpsc::UnboundedReceiver<T>>,
+}
+
+impl<T> fmt::Debug for TcpReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TcpReceiver").finish()
+    }
+}
+
+impl<T: DeserializeOwned + Send + 'static> TcpReceiver<T> {
+    /// Starts reading messages from the given connection until it is closed
+    ///
+    /// Only the messages that `accept` returns true for are received.
+    fn new<F>(mut reader: TcpStream, mut accept: F) -> Self
+        where F: FnMut(&T) -> bool + Send + 'static,
+    {
+        let (sender, messages) = mpsc::unbounded_channel();
+        thread::spawn(move || {
+            // Dropping the sender when this loop ends signals that the other side is gone
+            while let Ok(message) = read_message(&mut reader) {
+                if accept(&message) && sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {messages: tokio::sync::Mutex::new(messages)}
+    }
+}
+
+impl<T: Send> TransportReceiver<T> for TcpReceiver<T> {
+    fn recv(&self) -> BoxFuture<'_, Result<T, IpcError>> {
+        async move {
+            self.messages.lock().await.recv().await.ok_or(IpcError::Disconnected)
+        }.boxed()
+    }
+}
+
+/// Starts accepting TCP connections to the server at the given address in the background
+///
+/// Each connection can be used by a program on another machine to draw in the window. Returns the
+/// address that the server is listening on. Must be called from within the tokio runtime.
+pub fn serve_tcp(conn: ServerSender, addrs: &[SocketAddr]) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addrs)?;
+    let local_addr = listener.local_addr()?;
+
+    let handle = Handle::current();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            // A failed connection attempt should not stop anyone else from connecting
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+
+            // A slow connection should not keep anyone else waiting while its version is checked
+            let conn = conn.clone();
+            let handle = handle.clone();
+            thread::spawn(move || accept_tcp(conn, handle, stream).unwrap_or(()));
+        }
+    });
+
+    Ok(local_addr)
+}
+
+/// Checks the version of a new TCP connection and then adds it to the server
+fn accept_tcp(conn: ServerSender, handle: Handle, mut stream: TcpStream) -> io::Result<()> {
+    // Most responses are small and sent one at a time while the other side waits for them
+    stream.set_nodelay(true)?;
+
+    // Requests from another version cannot be read, but the other side is still told which version
+    // this is so that it can report the mismatch
+    let version = read_message::<u32, _>(&mut stream);
+    write_message(&mut stream, &PROTOCOL_VERSION)?;
+    if version? != PROTOCOL_VERSION {
+        return Ok(());
+    }
+
+    let sender = TcpSender::new(stream.try_clone()?);
+    // Refusals go through the same thread as every other response so that they are never written
+    // in the middle of another message
+    let outgoing = sender.outgoing.clone();
+    let receiver = TcpReceiver::new(stream, move |(id, req): &ClientMessage| {
+        if req.is_allowed_remotely() {
+            return true;
+        }
+
+        // The client is only told about the refusal if it is waiting for a response
+        if req.has_response() {
+            let err = Error::NotAllowed(format!("{:?}", req));
+            TcpSender::queue(&outgoing, &(*id, ServerResponse::Error(err))).unwrap_or(());
+        }
+        false
+    });
+
+    handle.enter(|| conn.add_transport(Box::new(sender), Box::new(receiver)));
+
+    Ok(())
+}
+
+/// Asks the server on the other side of a new connection to start accepting TCP connections at the
+/// first of the given addresses that can be used
+///
+/// The response is read directly from the receiver, so this must be done before any other request
+/// is sent over the connection.
+pub async fn request_tcp_share(
+    sender: &ClientSender,
+    receiver: &ClientReceiver,
+    addrs: Vec<SocketAddr>,
+) -> Result<SocketAddr, ConnectionError> {
+    let id = ClientId::new(0);
+    sender.send(id, ClientRequest::ShareTcp(addrs))?;
+
+    match receiver.recv().await {
+        Ok((_, ServerResponse::TcpShareStarted(res))) => res.map_err(|err| io::Error::other(err).into()),
+        Ok((_, ServerResponse::Error(err))) => Err(io::Error::other(err).into()),
+        Ok(_) => unreachable!("bug: expected to receive `TcpShareStarted` in response to `ShareTcp` request"),
+        Err(IpcError::Io(err)) => Err(err.into()),
+        Err(IpcError::Bincode(err)) => Err(err.into()),
+        Err(IpcError::Disconnected) => Err(io::Error::new(io::ErrorKind::ConnectionAborted, "the window closed before it could share the drawing").into()),
+    }
+}
+
+/// Connects to a server that was shared with `serve_tcp()` at the given address
+pub async fn connect_tcp<A: ToSocketAddrs>(addr: A) -> Result<(ClientSender, ClientReceiver), ConnectionError> {
+    let addrs: Vec<_> = addr.to_socket_addrs()?.collect();
+    let stream = tokio::task::spawn_blocking(move || {
+        let mut stream = TcpStream::connect(&addrs[..])?;
+        // Most requests are small and sent one at a time while waiting for the previous response
+        stream.set_nodelay(true)?;
+
+        write_message(&mut stream, &PROTOCOL_VERSION)?;
+        let version: u32 = read_message(&mut stream)?;
+        match version {
+            PROTOCOL_VERSION => Ok(stream),
+            server => Err(ConnectionError::VersionMismatch {client: PROTOCOL_VERSION, server}),
+        }
+    }).await??;
+
+    let sender = TcpSender::new(stream.try_clone()?);
+    let sender = ClientSender {sender: Arc::new(Mutex::new(Box::new(sender)))};
+    let receiver: TcpReceiver<ClientResponse> = TcpReceiver::new(stream, |_| true);
+    let receiver = ClientReceiver {receiver: Box::new(receiver)};
+
+    Ok((sender, receiver))
+}
+
+/// Writes a single length-prefixed message
+pub(super) fn write_message<T: Serialize, W: Write>(writer: &mut W, value: &T) -> io::Result<()> {
+    writer.write_all(&encode_message(value)?)?;
+    writer.flush()
+}
+
+/// Encodes a single message, prefixed by its length
+fn encode_message<T: Serialize>(value: &T) -> io::Result<Vec<u8>> {
+    let bytes = bincode::serialize(value).map_err(io::Error::other)?;
+    let len = u32::try_from(bytes.len()).ok()
+        .filter(|&len| len <= MAX_MESSAGE_LEN)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "message is too large to send"))?;
+
+    let mut message = Vec::with_capacity(4 + bytes.len());
+    message.extend_from_slice(&len.to_le_bytes());
+    message.extend_from_slice(&bytes);
+    Ok(message)
+}
+
+/// Reads a single length-prefixed message
+pub(super) fn read_message<T: DeserializeOwned, R: Read>(reader: &mut R) -> io::Result<T> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len);
+    if len > MAX_MESSAGE_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "message is too large to receive"));
+    }
+
+    let mut bytes = vec![0; len as usize];
+    reader.read_exact(&mut bytes)?;
+    bincode::deserialize(&bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Drawing;
+
+    #[test]
+    fn draws_over_tcp() {
+        let drawing = Drawing::new();
+        let addr = drawing.share_tcp("127.0.0.1:0").expect("unable to start TCP bridge");
+
+        let mut remote = Drawing::join_tcp(addr).expect("unable to connect over TCP");
+        remote.set_title("Hello from far away");
+        let mut turtle = remote.add_turtle();
+        turtle.forward(100.0);
+        assert_eq!(drawing.title(), "Hello from far away");
+        assert!((turtle.position() - [0.0, 100.0].into()).len() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_file_access_over_tcp() {
+        let drawing = Drawing::new();
+        let addr = drawing.share_tcp("127.0.0.1:0").expect("unable to start TCP bridge");
+
+        let remote = Drawing::join_tcp(addr).expect("unable to connect over TCP");
+        let err = remote.save_svg("never-saved.svg").expect_err("file access should be refused");
+        assert!(err.to_string().contains("not allowed"), "{}", err);
+    }
+
+    #[test]
+    fn shares_new_drawing_over_tcp() {
+        // Finds a port that is not being used so that it can be given to the new drawing
+        let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let drawing = Drawing::new_shared_tcp(addr).expect("unable to share new drawing");
+
+        let mut remote = Drawing::join_tcp(addr).expect("unable to connect over TCP");
+        remote.set_title("Shared from the start");
+        // Requests over one connection are handled in order, so once the remote drawing sees the
+        // new title, every other connection does as well
+        assert_eq!(remote.title(), "Shared from the start");
+        assert_eq!(drawing.title(), "Shared from the start");
+    }
+
+    #[test]
+    fn sends_without_waiting_for_a_slow_reader() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut reader, _) = listener.accept().unwrap();
+
+        // Far more than fits in the buffers of the connection, so a sender that writes the
+        // messages itself would wait until they are read
+        let message = vec![7u8; 1 << 20];
+        let count = 64;
+        let (done, finished) = std_mpsc::channel();
+        let messages = message.clone();
+        thread::spawn(move || {
+            let sender = TcpSender::new(stream);
+            for i in 0..count {
+                TransportSender::send(&sender, (i, messages.clone())).unwrap();
+            }
+            done.send(()).unwrap();
+        });
+        finished.recv_timeout(std::time::Duration::from_secs(10))
+            .expect("sending should not wait for the messages to be read");
+
+        // Every message still arrives, in order, before the connection is shut down
+        for i in 0..count {
+            let received: (u32, Vec<u8>) = read_message(&mut reader).unwrap();
+            assert_eq!(received, (i, message.clone()));
+        }
+        assert!(read_message::<(u32, Vec<u8>), _>(&mut reader).is_err());
+    }
+
+    #[test]
+    fn rejects_other_versions_over_tcp() {
+        let drawing = Drawing::new();
+        let addr = drawing.share_tcp("127.0.0.1:0").expect("unable to start TCP bridge");
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write_message(&mut stream, &(PROTOCOL_VERSION + 1)).unwrap();
+        let version: u32 = read_message(&mut stream).unwrap();
+        assert_eq!(version, PROTOCOL_VERSION);
+        // The bridge closes the connection instead of reading requests it does not understand
+        assert!(read_message::<u32, _>(&mut stream).is_err());
+    }
+}
@@ -0,0 +1,41 @@
+//! The transports that carry messages between the client and the server
+//!
+//! Each side of a connection only ever uses a transport through `TransportSender` and
+//! `TransportReceiver`, so the rest of the protocol works the same way no matter which transport
+//! is being used. IPC channels connect processes on the same machine, and TCP connects a program
+//! to a window on another machine (see the `tcp` module).
+
+use std::fmt;
+
+use serde::{Serialize, de::DeserializeOwned};
+use futures_util::future::{BoxFuture, FutureExt};
+use ipc_channel::ipc::{IpcSender, IpcError};
+
+use super::AsyncIpcReceiver;
+
+/// Sends messages to the other side of a connection
+pub trait TransportSender<T>: fmt::Debug + Send {
+    /// Sends a message, failing if the other side has disconnected
+    fn send(&self, message: T) -> Result<(), ipc_channel::Error>;
+}
+
+/// Receives messages from the other side of a connection
+pub trait TransportReceiver<T>: fmt::Debug + Send + Sync {
+    /// Waits for the next message
+    ///
+    /// Returns `IpcError::Disconnected` once the other side has disconnected and every message it
+    /// sent has been received.
+    fn recv(&self) -> BoxFuture<'_, Result<T, IpcError>>;
+}
+
+impl<T: Serialize + fmt::Debug + Send> TransportSender<T> for IpcSender<T> {
+    fn send(&self, message: T) -> Result<(), ipc_channel::Error> {
+        IpcSender::send(self, message)
+    }
+}
+
+impl<T: Serialize + DeserializeOwned + fmt::Debug + Send + 'static> TransportReceiver<T> for AsyncIpcReceiver<T> {
+    fn recv(&self) -> BoxFuture<'_, Result<T, IpcError>> {
+        AsyncIpcReceiver::recv(self).boxed()
+    }
+}
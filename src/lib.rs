@@ -57,6 +57,42 @@ broken unless you do so. See [Unstable features](#unstable-features) below for m
 //!
 //! For maximum overall performance, run your build with the `--release` flag.
 //!
+//! # Running without a window
+//!
+//! To run a turtle program somewhere without a display, like a CI server, enable the "headless"
+//! feature. No window is opened, but every method still works and every animation finishes
+//! instantly. Save the result with [`Drawing::save_png()`](struct.Drawing.html#method.save_png)
+//! or [`Drawing::save_svg()`](struct.Drawing.html#method.save_svg) to check what was drawn.
+//!
+//! ```toml
+//! [dev-dependencies]
+//! turtle = { version = "...", features = ["headless"] }
+//! ```
+//!
+//! The [`test`](test/index.html) module uses this to check drawings against reference images.
+//!
+//! # Running in a web browser
+//!
+//! Turtle programs can be compiled to `wasm32-unknown-unknown` and run in a web page with the
+//! "wasm" feature (e.g. using [wasm-bindgen]). The drawing is shown in the `<canvas>` element
+//! with the id `turtle`, which is added to the end of the page if there is none. A web page can't
+//! update while the program is running, so the drawing appears once the program finishes and
+//! every animation finishes instantly, just like with the "headless" feature.
+//!
+//! Your package also needs version 2 of Cargo's feature resolver (the default since the 2021
+//! edition) so that the dependencies used to open windows are left out of the build:
+//!
+//! ```toml
+//! [package]
+//! # ...
+//! resolver = "2"
+//!
+//! [dependencies]
+//! turtle = { version = "...", features = ["wasm"] }
+//! ```
+//!
+//! [wasm-bindgen]: https://rustwasm.github.io/docs/wasm-bindgen/
+//!
 //! # Unstable features
 //!
 //! Some parts of this crate are unstable and may be subject to change in the future. If you would
@@ -85,11 +121,25 @@ broken unless you do so. See [Unstable features](#unstable-features) below for m
 #[cfg(all(test, not(feature = "test")))]
 compile_error!("Make sure you run tests with `cargo test --features \"test unstable\"`");
 
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+compile_error!("Enable the \"wasm\" feature to run turtle programs in a web browser");
+
 mod radians;
 mod point;
+mod point3;
+mod rect;
 mod speed;
+mod easing;
+mod shape;
+mod fill_rule;
+mod blend_mode;
+mod path;
+mod snapshot;
 mod color;
+mod palette;
+mod error;
 pub mod rand;
+pub mod angle;
 
 mod ipc_protocol;
 mod renderer_server;
@@ -97,19 +147,92 @@ mod renderer_client;
 mod async_drawing;
 mod async_turtle;
 mod sync_runtime;
+mod time;
 mod debug;
 mod drawing;
 mod turtle;
+mod turtle3d;
+mod replay;
+#[cfg(feature = "websocket")]
+mod websocket_bridge;
+#[cfg(feature = "plotters")]
+mod plotters;
+#[cfg(feature = "unstable")]
+mod run;
+#[cfg(any(feature = "test", feature = "headless"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "headless")))]
+pub mod test;
 
 pub use crate::color::Color;
 pub use crate::color::colors;
-pub use crate::async_drawing::Size;
+pub use crate::palette::{Palette, PaletteError};
+pub use crate::error::Error;
+pub use crate::async_drawing::{Size, Corner};
 pub use crate::drawing::Drawing;
 pub use crate::point::Point;
+pub use crate::point3::Point3;
+pub use crate::rect::Rect;
 pub use crate::speed::Speed;
+pub use crate::easing::Easing;
+pub use crate::shape::Shape;
+pub use crate::fill_rule::FillRule;
+pub use crate::blend_mode::BlendMode;
+pub use crate::path::Path;
+pub use crate::snapshot::{TurtleState, PenState, DrawingState};
 pub use crate::async_turtle::{Angle, Distance};
 pub use crate::turtle::Turtle;
-pub use crate::renderer_server::{ExportError, start};
+pub use crate::turtle3d::{Turtle3D, Projection};
+pub use crate::ipc_protocol::ShareError;
+pub use crate::replay::{replay, replay_at_speed};
+#[cfg(feature = "websocket")]
+#[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+pub use crate::websocket_bridge::WebSocketOptions;
+#[cfg(feature = "plotters")]
+#[cfg_attr(docsrs, doc(cfg(feature = "plotters")))]
+pub use crate::plotters::PlottersBackend;
+#[cfg(feature = "shaders")]
+#[cfg_attr(docsrs, doc(cfg(feature = "shaders")))]
+pub use crate::renderer_server::ShaderError;
+pub use crate::renderer_server::{
+    ClearEffect,
+    WipeDirection,
+    ExportError,
+    ExportOptions,
+    ExportRegion,
+    Filter,
+    Font,
+    FontError,
+    FontMetrics,
+    ImageError,
+    ImageHandle,
+    BackgroundMode,
+    ImportError,
+    ImportTransform,
+    SessionError,
+    PrintOptions,
+    PaperSize,
+    PlotterOptions,
+    TextAlign,
+    TextOptions,
+    Vertex,
+    Index,
+    Primitive,
+    PrimitiveId,
+    StampId,
+    LayerId,
+    ResizeAnchor,
+    CoordinateSystem,
+    WorldCoordinates,
+    Origin,
+    DisconnectBehavior,
+    start,
+    start_with,
+    ProcessOptions,
+    run,
+    RendererBackend,
+    Frame,
+    set_renderer_backend,
+};
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "unstable")] {
@@ -117,6 +240,10 @@ cfg_if::cfg_if! {
         pub mod event;
         #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
         pub use crate::event::Event;
+        #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+        pub use crate::run::{run_with, Config, ControlFlow};
+        #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+        pub mod dev;
 
     } else {
         mod event;
@@ -0,0 +1,352 @@
+use std::fs;
+use std::iter::{Cycle, Copied};
+use std::path::Path;
+use std::slice;
+
+use thiserror::Error;
+
+use crate::Color;
+
+/// The first line of every GIMP palette file
+const GPL_HEADER: &str = "GIMP Palette";
+
+/// 10 evenly spaced colors from the viridis color map used by matplotlib
+const VIRIDIS: &[&str] = &[
+    "#440154", "#482878", "#3e4989", "#31688e", "#26828e",
+    "#1f9e89", "#35b779", "#6ece58", "#b5de2b", "#fde725",
+];
+
+/// Light, soft colors that go well together
+const PASTEL: &[&str] = &[
+    "#a1c9f4", "#ffb482", "#8de5a1", "#ff9f9b", "#d0bbff",
+    "#debb9b", "#fab0e4", "#cfcfcf", "#fffea3", "#b9f2f0",
+];
+
+/// The Okabe-Ito palette, which can be told apart by people with most kinds of color blindness
+const COLORBLIND: &[&str] = &[
+    "#000000", "#e69f00", "#56b4e9", "#009e73",
+    "#f0e442", "#0072b2", "#d55e00", "#cc79a7",
+];
+
+/// An error produced while loading a palette
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct PaletteError(String);
+
+/// An ordered list of colors that look good together
+///
+/// Palettes are useful for picking colors in a drawing that depends on some data, like a bar
+/// chart or a spiral that changes color as it goes. Colors can be picked by their index with
+/// [`get()`](#method.get), one after the other with [`cycle()`](#method.cycle), or anywhere
+/// between the first and last color with [`sample()`](#method.sample).
+///
+/// There are a few built-in palettes, and palettes can be loaded from the GIMP palette (`.gpl`)
+/// files used by many drawing programs with [`load_gpl()`](#method.load_gpl).
+///
+/// ```rust
+/// use turtle::{Turtle, Palette};
+///
+/// let mut turtle = Turtle::new();
+/// let palette = Palette::viridis();
+/// for (i, color) in palette.cycle().take(20).enumerate() {
+///     turtle.set_pen_color(color);
+///     turtle.forward(i as f64 * 10.0);
+///     turtle.right(90.0);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    name: String,
+    colors: Vec<Color>,
+}
+
+impl Palette {
+    /// Creates a palette with the given name and colors, in the given order
+    ///
+    /// Any type that can be converted into a color can be used.
+    ///
+    /// ```rust
+    /// use turtle::Palette;
+    ///
+    /// let palette = Palette::new("Traffic lights", ["red", "yellow", "green"]);
+    /// assert_eq!(palette.len(), 3);
+    /// assert_eq!(palette.get(1), "yellow".into());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no colors or if any of the colors is not valid.
+    pub fn new<S, I>(name: S, colors: I) -> Self
+        where S: Into<String>,
+              I: IntoIterator,
+              I::Item: Into<Color>,
+    {
+        let colors: Vec<Color> = colors.into_iter().map(Into::into).collect();
+        assert!(!colors.is_empty(), "A palette must have at least one color");
+        for color in &colors {
+            assert!(
+                color.is_valid(),
+                "Invalid color: {:?}. See the color module documentation for more information.",
+                color
+            );
+        }
+
+        Self {name: name.into(), colors}
+    }
+
+    /// Loads a palette from the GIMP palette (`.gpl`) file at the given path
+    ///
+    /// ```rust,no_run
+    /// use turtle::{Palette, PaletteError};
+    ///
+    /// fn main() -> Result<(), PaletteError> {
+    ///     let palette = Palette::load_gpl("sunset.gpl")?;
+    ///     println!("{} has {} colors", palette.name(), palette.len());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn load_gpl<P: AsRef<Path>>(path: P) -> Result<Self, PaletteError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .map_err(|err| PaletteError(format!("unable to open {}: {}", path.display(), err)))?;
+        Self::parse_gpl(&source)
+    }
+
+    /// Reads a palette from the contents of a GIMP palette (`.gpl`) file
+    ///
+    /// The file must start with a `GIMP Palette` line, followed by one color per line as red,
+    /// green, and blue values from 0 to 255 and an optional name for the color. The `Name:` line,
+    /// if any, becomes the name of the palette. Blank lines, comments starting with `#`, and any
+    /// other header lines (like `Columns:`) are ignored.
+    ///
+    /// ```rust
+    /// use turtle::{Color, Palette};
+    ///
+    /// let palette = Palette::parse_gpl("\
+    /// GIMP Palette
+    /// Name: Sunset
+    /// ## The colors of the sky at dusk
+    /// 255 94  77  Coral
+    /// 255 170 0   Amber
+    /// 97  41  130 Plum
+    /// ").unwrap();
+    /// assert_eq!(palette.name(), "Sunset");
+    /// assert_eq!(palette.get(2), Color::rgb(97.0, 41.0, 130.0));
+    /// ```
+    pub fn parse_gpl(source: &str) -> Result<Self, PaletteError> {
+        let mut lines = source.lines().enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        match lines.next() {
+            Some((_, GPL_HEADER)) => {},
+            _ => return Err(PaletteError(format!("a GIMP palette must start with `{}`", GPL_HEADER))),
+        }
+
+        let mut name = String::new();
+        let mut colors = Vec::new();
+        for (line_number, line) in lines {
+            if line.starts_with('#') {
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("Name:") {
+                name = value.trim().to_owned();
+                continue;
+            }
+            // Other header lines like `Columns: 4`
+            if line.starts_with(|c: char| c.is_ascii_alphabetic()) && line.contains(':') {
+                continue;
+            }
+
+            let mut values = line.split_whitespace().take(3).map(|value| value.parse::<u8>());
+            match (values.next(), values.next(), values.next()) {
+                (Some(Ok(red)), Some(Ok(green)), Some(Ok(blue))) => {
+                    colors.push(Color::rgb(red as f64, green as f64, blue as f64));
+                },
+                _ => return Err(PaletteError(format!(
+                    "line {}: expected red, green, and blue values from 0 to 255, found `{}`",
+                    line_number, line,
+                ))),
+            }
+        }
+
+        if colors.is_empty() {
+            return Err(PaletteError("the palette does not contain any colors".to_owned()));
+        }
+
+        Ok(Self {name, colors})
+    }
+
+    /// The viridis color map, which goes from dark purple through blue and green to yellow
+    ///
+    /// The colors get lighter at an even rate and can be told apart by people with most kinds of
+    /// color blindness, which makes this palette a good choice for showing values that go from
+    /// low to high with [`sample()`](#method.sample).
+    pub fn viridis() -> Self {
+        Self::from_hex("Viridis", VIRIDIS)
+    }
+
+    /// Light, soft colors that look good together
+    pub fn pastel() -> Self {
+        Self::from_hex("Pastel", PASTEL)
+    }
+
+    /// The Okabe-Ito palette, made up of 8 colors that can be told apart by people with most
+    /// kinds of color blindness
+    pub fn colorblind() -> Self {
+        Self::from_hex("Colorblind", COLORBLIND)
+    }
+
+    fn from_hex(name: &str, colors: &[&str]) -> Self {
+        Self {
+            name: name.to_owned(),
+            colors: colors.iter().map(|&color| Color::from(color)).collect(),
+        }
+    }
+
+    /// Returns the name of the palette, which is empty if a loaded palette did not have a name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of colors in the palette
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Always returns false since every palette has at least one color
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Returns all of the colors in the palette, in order
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Returns the color at the given index
+    ///
+    /// The index wraps around, so an index past the last color starts again from the first one.
+    /// That way every number can be given a color, even if there are more numbers than colors.
+    ///
+    /// ```rust
+    /// use turtle::Palette;
+    ///
+    /// let palette = Palette::new("Primary", ["red", "yellow", "blue"]);
+    /// assert_eq!(palette.get(0), "red".into());
+    /// assert_eq!(palette.get(2), "blue".into());
+    /// assert_eq!(palette.get(4), "yellow".into());
+    /// ```
+    pub fn get(&self, index: usize) -> Color {
+        self.colors[index % self.colors.len()]
+    }
+
+    /// Returns an iterator that goes through the colors of the palette in order, starting again
+    /// from the first color after the last one, forever
+    ///
+    /// ```rust
+    /// use turtle::Palette;
+    ///
+    /// let palette = Palette::new("Primary", ["red", "yellow", "blue"]);
+    /// let colors: Vec<_> = palette.cycle().take(4).collect();
+    /// assert_eq!(colors, ["red".into(), "yellow".into(), "blue".into(), "red".into()]);
+    /// ```
+    pub fn cycle(&self) -> Cycle<Copied<slice::Iter<'_, Color>>> {
+        self.colors.iter().copied().cycle()
+    }
+
+    /// Returns the color the given fraction of the way from the first color of the palette to
+    /// the last, blending the two closest colors together
+    ///
+    /// This treats the palette as a smooth gradient, which is useful for coloring a value that
+    /// can be anywhere in a range. The value of `t` must be between 0.0 and 1.0 (inclusive).
+    ///
+    /// ```rust
+    /// use turtle::{Color, Palette};
+    ///
+    /// let palette = Palette::new("Grey", [Color::rgb(0.0, 0.0, 0.0), Color::rgb(200.0, 200.0, 200.0)]);
+    /// assert_eq!(palette.sample(0.0), Color::rgb(0.0, 0.0, 0.0));
+    /// assert_eq!(palette.sample(0.25), Color::rgb(50.0, 50.0, 50.0));
+    /// assert_eq!(palette.sample(1.0), Color::rgb(200.0, 200.0, 200.0));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t` is not between 0.0 and 1.0.
+    pub fn sample(&self, t: f64) -> Color {
+        assert!((0.0..=1.0).contains(&t), "Invalid value for t: {}. It must be between 0.0 and 1.0", t);
+
+        let last = self.colors.len() - 1;
+        let position = t * last as f64;
+        let index = (position.floor() as usize).min(last);
+        if index == last {
+            return self.colors[last];
+        }
+        self.colors[index].lerp(self.colors[index + 1], position - index as f64)
+    }
+}
+
+impl<'a> IntoIterator for &'a Palette {
+    type Item = &'a Color;
+    type IntoIter = slice::Iter<'a, Color>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.colors.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_palettes_are_valid() {
+        for palette in &[Palette::viridis(), Palette::pastel(), Palette::colorblind()] {
+            assert!(!palette.is_empty());
+            assert!(palette.colors().iter().all(|color| color.is_valid()), "{:?}", palette);
+        }
+    }
+
+    #[test]
+    fn parses_gpl_headers_and_comments() {
+        let palette = Palette::parse_gpl("\
+GIMP Palette
+Name: Test
+Columns: 2
+#
+  0   0   0	Black
+255 255 255
+").unwrap();
+        assert_eq!(palette.name(), "Test");
+        assert_eq!(palette.colors(), &[Color::rgb(0.0, 0.0, 0.0), Color::rgb(255.0, 255.0, 255.0)]);
+    }
+
+    #[test]
+    fn rejects_invalid_gpl() {
+        assert!(Palette::parse_gpl("0 0 0").is_err());
+        assert!(Palette::parse_gpl("GIMP Palette\nName: Empty\n").is_err());
+        assert!(Palette::parse_gpl("GIMP Palette\n0 0 256\n").is_err());
+        assert!(Palette::parse_gpl("GIMP Palette\n0 0\n").is_err());
+    }
+
+    #[test]
+    fn samples_between_colors() {
+        let palette = Palette::new("Test", [
+            Color::rgb(0.0, 0.0, 0.0),
+            Color::rgb(100.0, 0.0, 0.0),
+            Color::rgb(100.0, 100.0, 0.0),
+        ]);
+        assert_eq!(palette.sample(0.5), Color::rgb(100.0, 0.0, 0.0));
+        assert_eq!(palette.sample(0.75), Color::rgb(100.0, 50.0, 0.0));
+        assert_eq!(palette.sample(1.0), Color::rgb(100.0, 100.0, 0.0));
+
+        let single = Palette::new("Single", ["red"]);
+        assert_eq!(single.sample(0.5), "red".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "A palette must have at least one color")]
+    fn rejects_empty_palette() {
+        Palette::new("Empty", Vec::<Color>::new());
+    }
+}
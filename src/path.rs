@@ -0,0 +1,211 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{Point, Angle};
+
+/// The largest angle covered by a single Bézier curve when approximating an arc (90 degrees)
+const MAX_ARC_SEGMENT_ANGLE: f64 = std::f64::consts::FRAC_PI_2;
+
+/// A path made of lines, arcs, and curves for a turtle to follow
+///
+/// Paths are used with [`Turtle::follow_path()`](struct.Turtle.html#method.follow_path), which
+/// moves the turtle along the entire path in a single smooth motion. The path starts wherever the
+/// turtle is at the time and each piece continues from where the previous one ended. Every
+/// position uses the same coordinates as [`Turtle::go_to()`](struct.Turtle.html#method.go_to).
+///
+/// ```rust
+/// use turtle::{Path, Point};
+///
+/// let path = Path::new()
+///     .line_to([100.0, 0.0])
+///     .arc_around([100.0, 50.0], 180.0)
+///     .curve_to([50.0, 150.0], [0.0, 50.0], [0.0, 0.0]);
+/// assert_eq!(path.len(), 3);
+///
+/// // A list of points can be used as a path made of straight lines
+/// let square = Path::from(vec![
+///     Point {x: 0.0, y: 100.0},
+///     Point {x: 100.0, y: 100.0},
+///     Point {x: 100.0, y: 0.0},
+///     Point {x: 0.0, y: 0.0},
+/// ]);
+/// assert_eq!(square.len(), 4);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+/// A single piece of a `Path`, starting from wherever the previous piece ended
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum PathSegment {
+    /// A straight line to the given point
+    Line(Point),
+    /// A quadratic Bézier curve with the given control point and end point
+    Quad(Point, Point),
+    /// A cubic Bézier curve with the given control points and end point
+    Cubic(Point, Point, Point),
+    /// A circular arc around the given center, counterclockwise if the angle is positive
+    ///
+    /// In a `Path`, the angle uses the unit of the turtle that follows it. Once the path is sent
+    /// to the renderer server, the angle is always in radians.
+    Arc(Point, Angle),
+}
+
+impl PathSegment {
+    /// Returns true if every point and angle in this segment is finite
+    pub(crate) fn is_finite(self) -> bool {
+        use PathSegment::*;
+        match self {
+            Line(end) => end.is_finite(),
+            Quad(control, end) => control.is_finite() && end.is_finite(),
+            Cubic(control1, control2, end) => control1.is_finite() && control2.is_finite() && end.is_finite(),
+            Arc(center, extent) => center.is_finite() && extent.is_finite(),
+        }
+    }
+}
+
+impl Path {
+    /// Creates an empty path
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a straight line to the given point
+    pub fn line_to<P: Into<Point>>(mut self, end: P) -> Self {
+        self.segments.push(PathSegment::Line(end.into()));
+        self
+    }
+
+    /// Adds a quadratic Bézier curve that bends towards the given control point and ends at the
+    /// given end point
+    ///
+    /// See [`Turtle::quad_bezier_to()`](struct.Turtle.html#method.quad_bezier_to) for more
+    /// information.
+    pub fn quad_to<P: Into<Point>>(mut self, control: P, end: P) -> Self {
+        self.segments.push(PathSegment::Quad(control.into(), end.into()));
+        self
+    }
+
+    /// Adds a cubic Bézier curve that bends towards the two given control points and ends at the
+    /// given end point
+    ///
+    /// See [`Turtle::bezier_to()`](struct.Turtle.html#method.bezier_to) for more information.
+    pub fn curve_to<P: Into<Point>>(mut self, control1: P, control2: P, end: P) -> Self {
+        self.segments.push(PathSegment::Cubic(control1.into(), control2.into(), end.into()));
+        self
+    }
+
+    /// Adds a circular arc that goes around the given center point by the given angle
+    ///
+    /// The radius of the arc is the distance from the end of the path so far to the center. The
+    /// arc goes counterclockwise if the angle is positive and clockwise if it is negative. The
+    /// angle uses the same unit (degrees or radians) as the turtle that follows the path.
+    pub fn arc_around<P: Into<Point>>(mut self, center: P, extent: Angle) -> Self {
+        self.segments.push(PathSegment::Arc(center.into(), extent));
+        self
+    }
+
+    /// Returns the number of lines, arcs, and curves in this path
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns true if nothing has been added to this path
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub(crate) fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+}
+
+impl From<Vec<Point>> for Path {
+    fn from(points: Vec<Point>) -> Self {
+        Self::from(&points[..])
+    }
+}
+
+impl From<&[Point]> for Path {
+    fn from(points: &[Point]) -> Self {
+        Self {segments: points.iter().map(|&point| PathSegment::Line(point)).collect()}
+    }
+}
+
+impl<const N: usize> From<&[Point; N]> for Path {
+    fn from(points: &[Point; N]) -> Self {
+        Self::from(&points[..])
+    }
+}
+
+/// Converts the given segments (with every angle in radians) into cubic Bézier curves, starting
+/// at the given point
+///
+/// Each curve is given as its two control points followed by its end point. Lines become
+/// straight curves and arcs are split into curves that each cover at most 90 degrees.
+pub(crate) fn to_curves(start: Point, segments: &[PathSegment]) -> Vec<[Point; 3]> {
+    let mut curves = Vec::new();
+    let mut current = start;
+    for &segment in segments {
+        match segment {
+            PathSegment::Line(end) => {
+                curves.push([current + (end - current) / 3.0, current + (end - current) * (2.0 / 3.0), end]);
+            },
+
+            PathSegment::Quad(control, end) => {
+                // Every quadratic curve is exactly the same as a cubic curve with these control points
+                let control1 = current + (control - current) * (2.0 / 3.0);
+                let control2 = end + (control - end) * (2.0 / 3.0);
+                curves.push([control1, control2, end]);
+            },
+
+            PathSegment::Cubic(control1, control2, end) => curves.push([control1, control2, end]),
+
+            PathSegment::Arc(center, extent) => {
+                let radius = (current - center).len();
+                let start_angle = (current - center).atan2();
+                let pieces = (extent.abs() / MAX_ARC_SEGMENT_ANGLE).ceil() as usize;
+                let angle = extent / pieces as f64;
+                // The distance to each control point that makes the curve closest to the arc
+                let handle = radius * 4.0 / 3.0 * (angle / 4.0).tan();
+
+                let at = |angle: f64| Point {x: angle.cos(), y: angle.sin()};
+                for i in 0..pieces {
+                    let (a0, a1) = (start_angle + angle * i as f64, start_angle + angle * (i + 1) as f64);
+                    let (p0, p1) = (center + at(a0) * radius, center + at(a1) * radius);
+                    let (tangent0, tangent1) = (Point {x: -a0.sin(), y: a0.cos()}, Point {x: -a1.sin(), y: a1.cos()});
+                    curves.push([p0 + tangent0 * handle, p1 - tangent1 * handle, p1]);
+                }
+            },
+        }
+
+        if let Some(&[_, _, end]) = curves.last() {
+            current = end;
+        }
+    }
+
+    curves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arcs_end_at_the_right_point() {
+        let path = Path::new().arc_around([0.0, 0.0], std::f64::consts::PI * 1.5);
+        let curves = to_curves(Point {x: 10.0, y: 0.0}, path.segments());
+        assert_eq!(curves.len(), 3);
+
+        let [_, _, end] = curves[2];
+        assert!((end - Point {x: 0.0, y: -10.0}).len() < 1e-9, "{:?}", end);
+    }
+
+    #[test]
+    fn empty_arcs_are_skipped() {
+        let path = Path::new().arc_around([0.0, 0.0], 0.0).line_to([5.0, 5.0]);
+        let curves = to_curves(Point {x: 10.0, y: 0.0}, path.segments());
+        assert_eq!(curves.len(), 1);
+        assert_eq!(curves[0][2], Point {x: 5.0, y: 5.0});
+    }
+}
@@ -0,0 +1,252 @@
+//! Allows charts from the [plotters] crate to be drawn using a turtle
+//!
+//! [plotters]: https://crates.io/crates/plotters
+
+use std::convert::Infallible;
+
+use plotters_backend::{
+    BackendColor,
+    BackendCoord,
+    BackendStyle,
+    DrawingBackend,
+    DrawingErrorKind,
+};
+
+use crate::{Drawing, Turtle, Color, Point, Size, Speed};
+
+/// The number of line segments used to draw each circle
+const CIRCLE_SEGMENTS: usize = 64;
+
+/// A [plotters] drawing backend that draws everything in a turtle window
+///
+/// The backend draws using its own turtle, so you can keep using all of your other turtles while a
+/// chart is being drawn. The area covered by the chart is the area of the drawing that was visible
+/// in the window when the backend was created. Changing the size or center of the drawing later
+/// will not move anything that the backend draws.
+///
+/// ```rust,ignore
+/// use plotters::prelude::*;
+/// use turtle::{Drawing, PlottersBackend};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut drawing = Drawing::new();
+///
+///     let root = PlottersBackend::new(&mut drawing).into_drawing_area();
+///     root.fill(&WHITE)?;
+///
+///     let mut chart = ChartBuilder::on(&root)
+///         .margin(20)
+///         .build_cartesian_2d(-3.14..3.14, -1.2..1.2)?;
+///     chart.draw_series(LineSeries::new(
+///         (-314..=314).map(|x| x as f64 / 100.0).map(|x| (x, x.sin())),
+///         &RED,
+///     ))?;
+///
+///     // Keep using turtles in the same window
+///     let mut turtle = drawing.add_turtle();
+///     turtle.forward(100.0);
+///
+///     Ok(())
+/// }
+/// ```
+///
+/// Text is drawn one pixel at a time using the fonts from plotters, so charts with a lot of text
+/// will take a while to draw.
+///
+/// [plotters]: https://crates.io/crates/plotters
+pub struct PlottersBackend {
+    turtle: Turtle,
+    size: Size,
+    /// The position of the top-left corner of the backend in the coordinate system of the turtle
+    top_left: Point,
+}
+
+impl PlottersBackend {
+    /// Creates a backend that draws on the given drawing
+    pub fn new(drawing: &mut Drawing) -> Self {
+        let size = drawing.size();
+        let center = drawing.center();
+        let top_left = center + Point {x: -(size.width as f64) / 2.0, y: size.height as f64 / 2.0};
+
+        let mut turtle = drawing.add_turtle();
+        turtle.hide();
+        turtle.set_speed(Speed::instant());
+        turtle.pen_up();
+
+        Self {turtle, size, top_left}
+    }
+
+    /// Converts a point in backend coordinates (y-axis pointing down) into a point in the
+    /// coordinate system of the turtle
+    fn to_point(&self, (x, y): (f64, f64)) -> Point {
+        self.top_left + Point {x, y: -y}
+    }
+
+    /// Draws a line through each of the given points in order
+    fn stroke<I>(&mut self, points: I, color: BackendColor, thickness: f64)
+        where I: IntoIterator<Item = (f64, f64)>,
+    {
+        let mut points = points.into_iter();
+        let first = match points.next() {
+            Some(first) => self.to_point(first),
+            None => return,
+        };
+
+        self.turtle.set_pen_color(convert_color(color));
+        self.turtle.set_pen_size(thickness);
+        self.turtle.go_to(first);
+
+        self.turtle.pen_down();
+        for point in points {
+            let point = self.to_point(point);
+            self.turtle.go_to(point);
+        }
+        self.turtle.pen_up();
+    }
+
+    /// Fills the polygon with the given points
+    fn fill<I>(&mut self, points: I, color: BackendColor)
+        where I: IntoIterator<Item = (f64, f64)>,
+    {
+        let mut points = points.into_iter();
+        let first = match points.next() {
+            Some(first) => self.to_point(first),
+            None => return,
+        };
+
+        self.turtle.set_fill_color(convert_color(color));
+        self.turtle.go_to(first);
+
+        self.turtle.begin_fill();
+        for point in points {
+            let point = self.to_point(point);
+            self.turtle.go_to(point);
+        }
+        self.turtle.end_fill();
+    }
+}
+
+impl DrawingBackend for PlottersBackend {
+    /// Drawing with a turtle never fails
+    type ErrorType = Infallible;
+
+    fn get_size(&self) -> (u32, u32) {
+        (self.size.width, self.size.height)
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<Infallible>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<Infallible>> {
+        // Everything is drawn in the window as soon as it is sent to the turtle
+        Ok(())
+    }
+
+    fn draw_pixel(&mut self, (x, y): BackendCoord, color: BackendColor) -> Result<(), DrawingErrorKind<Infallible>> {
+        let (x, y) = (x as f64, y as f64);
+        self.fill(vec![(x, y), (x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0)], color);
+        Ok(())
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Infallible>> {
+        self.draw_path(vec![from, to], style)
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Infallible>> {
+        let (left, top) = upper_left;
+        let (right, bottom) = bottom_right;
+
+        if fill {
+            // Filled rectangles cover every pixel up to and including the bottom-right corner
+            let (right, bottom) = (right + 1, bottom + 1);
+            self.fill_polygon(vec![(left, top), (right, top), (right, bottom), (left, bottom)], style)
+        } else {
+            self.draw_path(vec![(left, top), (right, top), (right, bottom), (left, bottom), (left, top)], style)
+        }
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Infallible>> {
+        // Lines go through the center of each pixel
+        let points = path.into_iter().map(|(x, y)| (x as f64 + 0.5, y as f64 + 0.5));
+        self.stroke(points, style.color(), style.stroke_width() as f64);
+        Ok(())
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        (x, y): BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Infallible>> {
+        let (x, y, radius) = (x as f64 + 0.5, y as f64 + 0.5, radius as f64);
+        let points = (0..=CIRCLE_SEGMENTS).map(|i| {
+            let angle = i as f64 / CIRCLE_SEGMENTS as f64 * 2.0 * std::f64::consts::PI;
+            (x + radius * angle.cos(), y + radius * angle.sin())
+        });
+
+        if fill {
+            self.fill(points, style.color());
+        } else {
+            self.stroke(points, style.color(), style.stroke_width() as f64);
+        }
+        Ok(())
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Infallible>> {
+        let points = vert.into_iter().map(|(x, y)| (x as f64, y as f64));
+        self.fill(points, style.color());
+        Ok(())
+    }
+}
+
+fn convert_color(color: BackendColor) -> Color {
+    let BackendColor {alpha, rgb: (red, green, blue)} = color;
+    Color::rgba(red as f64, green as f64, blue as f64, alpha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Rect;
+
+    /// Returns the area covered by everything that has been drawn
+    fn mesh_bounds(drawing: &Drawing) -> Option<Rect> {
+        let (vertices, _) = drawing.export_mesh();
+        Rect::bounding(vertices.iter().map(|v| [v.position[0] as f64, v.position[1] as f64]))
+    }
+
+    #[test]
+    fn uses_window_coordinates() {
+        let mut drawing = Drawing::new();
+        drawing.set_size([200, 100]);
+        drawing.set_center([1000.0, 0.0]);
+        let mut backend = PlottersBackend::new(&mut drawing);
+        assert_eq!(backend.get_size(), (200, 100));
+
+        let red = BackendColor {alpha: 1.0, rgb: (255, 0, 0)};
+        backend.draw_rect((0, 0), (9, 19), &red, true).unwrap();
+        assert_eq!(mesh_bounds(&drawing), Some(Rect::from_points([900.0, 50.0], [910.0, 30.0])));
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::{Add, Div, Index, IndexMut, Mul, Sub};
+use std::ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use serde::{Serialize, Deserialize};
 use interpolation::Lerp;
@@ -60,6 +60,28 @@ use crate::rand::{Random, RandomRange};
 /// assert_eq!(pt * 2.0, Point {x: a * 2.0, y: b * 2.0});
 /// assert_eq!(pt2 / 5.0, Point {x: c / 5.0, y: d / 5.0});
 /// assert_eq!(pt2 * 2.0 - pt, Point {x: c * 2.0 - a, y: d * 2.0 - b});
+/// assert_eq!(-pt, Point {x: -a, y: -b});
+///
+/// // Each of the operators can also be used to update a point in place
+/// let mut pt3 = pt;
+/// pt3 += pt2;
+/// pt3 *= 2.0;
+/// assert_eq!(pt3, (pt + pt2) * 2.0);
+/// ```
+///
+/// Points can also be used as vectors. Methods like [`dot()`], [`normalize()`], and
+/// [`rotate_about()`] make it easy to do the kind of math that often comes up when working
+/// with a turtle's [`position()`].
+///
+/// ```rust
+/// # use turtle::Point;
+/// let start = Point {x: 10.0, y: 10.0};
+/// let end = Point {x: 13.0, y: 14.0};
+/// assert_eq!(start.distance(end), 5.0);
+///
+/// // Find the point 20 steps from `start` in the direction of `end`
+/// let direction = (end - start).normalize();
+/// assert_eq!(start + direction * 20.0, Point {x: 22.0, y: 26.0});
 /// ```
 ///
 /// # Accessing Point Components
@@ -136,6 +158,10 @@ use crate::rand::{Random, RandomRange};
 /// [`Drawing::set_center()`]: struct.Drawing.html#method.set_center
 /// [`random()`]: rand/fn.random.html
 /// [`random_range()`]: rand/fn.random_range.html
+/// [`dot()`]: struct.Point.html#method.dot
+/// [`normalize()`]: struct.Point.html#method.normalize
+/// [`rotate_about()`]: struct.Point.html#method.rotate_about
+/// [`position()`]: struct.Turtle.html#method.position
 #[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Point {
     /// The x-coordinate of the Point
@@ -234,6 +260,129 @@ impl Point {
     pub fn atan2(self) -> f64 {
         self.y.atan2(self.x)
     }
+
+    /// Returns the distance between this point and the other point.
+    ///
+    /// ```rust
+    /// # use turtle::Point;
+    /// let p1 = Point {x: 1.0, y: 2.0};
+    /// let p2 = Point {x: 4.0, y: 6.0};
+    /// assert_eq!(p1.distance(p2), 5.0);
+    /// ```
+    pub fn distance(self, other: Self) -> f64 {
+        (other - self).len()
+    }
+
+    /// Returns the [dot product](https://en.wikipedia.org/wiki/Dot_product) of this point and the
+    /// other point, treating both as vectors.
+    ///
+    /// The dot product is `0.0` when the two vectors are perpendicular.
+    ///
+    /// ```rust
+    /// # use turtle::Point;
+    /// let p1 = Point {x: 1.0, y: 2.0};
+    /// let p2 = Point {x: 3.0, y: 4.0};
+    /// assert_eq!(p1.dot(p2), 11.0);
+    /// assert_eq!(Point {x: 1.0, y: 0.0}.dot(Point {x: 0.0, y: 1.0}), 0.0);
+    /// ```
+    pub fn dot(self, other: Self) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Returns the 2D [cross product](https://en.wikipedia.org/wiki/Cross_product) of this point
+    /// and the other point, treating both as vectors.
+    ///
+    /// The result is positive if `other` is counterclockwise from `self`, negative if it is
+    /// clockwise, and `0.0` if the two vectors are parallel.
+    ///
+    /// ```rust
+    /// # use turtle::Point;
+    /// let right = Point {x: 1.0, y: 0.0};
+    /// let up = Point {x: 0.0, y: 1.0};
+    /// assert_eq!(right.cross(up), 1.0);
+    /// assert_eq!(up.cross(right), -1.0);
+    /// ```
+    pub fn cross(self, other: Self) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Returns a point in the same direction as this one, but with a length of `1.0`.
+    ///
+    /// If this point has a length of zero, the origin is returned since it has no direction.
+    ///
+    /// ```rust
+    /// # use turtle::Point;
+    /// let p = Point {x: 3.0, y: 4.0};
+    /// assert_eq!(p.normalize(), Point {x: 0.6, y: 0.8});
+    /// assert_eq!(Point::origin().normalize(), Point::origin());
+    /// ```
+    pub fn normalize(self) -> Self {
+        let len = self.len();
+        if len == 0.0 {
+            Self::origin()
+        } else {
+            self / len
+        }
+    }
+
+    /// Returns this point rotated counterclockwise around the given center point by the given
+    /// angle in radians.
+    ///
+    /// Use [`f64::to_radians()`] if you have an angle in degrees.
+    ///
+    /// ```rust
+    /// # use turtle::Point;
+    /// let p = Point {x: 2.0, y: 1.0};
+    /// let rotated = p.rotate_about([1.0, 1.0], 90f64.to_radians());
+    /// assert!((rotated - Point {x: 1.0, y: 2.0}).len() < 1e-10);
+    /// ```
+    ///
+    /// [`f64::to_radians()`]: https://doc.rust-lang.org/std/primitive.f64.html#method.to_radians
+    pub fn rotate_about<P: Into<Point>>(self, center: P, angle: f64) -> Self {
+        let center = center.into();
+        let (sin, cos) = angle.sin_cos();
+        let Point {x, y} = self - center;
+
+        center + Point {
+            x: x * cos - y * sin,
+            y: x * sin + y * cos,
+        }
+    }
+
+    /// Linearly interpolates between this point and the other point.
+    ///
+    /// A value of `t = 0.0` returns this point and `t = 1.0` returns the other point. Values in
+    /// between return points on the line between the two points.
+    ///
+    /// ```rust
+    /// # use turtle::Point;
+    /// let p1 = Point {x: 0.0, y: 10.0};
+    /// let p2 = Point {x: 20.0, y: 30.0};
+    /// assert_eq!(p1.lerp(p2, 0.0), p1);
+    /// assert_eq!(p1.lerp(p2, 0.5), Point {x: 10.0, y: 20.0});
+    /// assert_eq!(p1.lerp(p2, 1.0), p2);
+    /// ```
+    pub fn lerp(self, other: Self, t: f64) -> Self {
+        Lerp::lerp(&self, &other, &t)
+    }
+
+    /// Returns the angle in radians of the direction from this point to the other point.
+    ///
+    /// The angle is measured counterclockwise from the positive x-axis, and will be between
+    /// `-PI` and `PI`.
+    ///
+    /// ```rust
+    /// # use turtle::Point;
+    /// use std::f64::consts::PI;
+    ///
+    /// let p = Point {x: 1.0, y: 1.0};
+    /// assert_eq!(p.angle_to([2.0, 1.0].into()), 0.0);
+    /// assert_eq!(p.angle_to([1.0, 2.0].into()), PI / 2.0);
+    /// assert_eq!(p.angle_to([0.0, 1.0].into()), PI);
+    /// ```
+    pub fn angle_to(self, other: Self) -> f64 {
+        (other - self).atan2()
+    }
 }
 
 impl From<(f64, f64)> for Point {
@@ -306,6 +455,41 @@ impl Div<f64> for Point {
     }
 }
 
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign<f64> for Point {
+    fn mul_assign(&mut self, other: f64) {
+        *self = *self * other;
+    }
+}
+
+impl DivAssign<f64> for Point {
+    fn div_assign(&mut self, other: f64) {
+        *self = *self / other;
+    }
+}
+
 impl Index<usize> for Point {
     type Output = f64;
 
@@ -0,0 +1,146 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use serde::{Serialize, Deserialize};
+
+/// A point (or direction) in 3D space, used by [`Turtle3D`](struct.Turtle3D.html)
+///
+/// The x-axis points right and the y-axis points up, just like they do for [`Point`]. The z-axis
+/// points out of the screen towards you.
+///
+/// Like `Point`, methods that accept a `Point3` also accept arrays and tuples:
+///
+/// ```rust
+/// # use turtle::Point3;
+/// assert_eq!(Point3 {x: 1.0, y: 2.0, z: 3.0}, [1.0, 2.0, 3.0].into());
+/// assert_eq!(Point3 {x: 1.0, y: 2.0, z: 3.0}, (1.0, 2.0, 3.0).into());
+/// ```
+///
+/// [`Point`]: struct.Point.html
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Point3 {
+    /// The x-coordinate of the point
+    pub x: f64,
+    /// The y-coordinate of the point
+    pub y: f64,
+    /// The z-coordinate of the point
+    pub z: f64,
+}
+
+impl Point3 {
+    /// Returns a Point3 that represents the origin of the coordinate system
+    pub const fn origin() -> Self {
+        Self {x: 0.0, y: 0.0, z: 0.0}
+    }
+
+    /// Returns true if x, y, and z are all finite (neither infinite nor `NaN`).
+    pub fn is_finite(self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+
+    /// Returns the length/magnitude of this point.
+    ///
+    /// The length of a point is defined as `sqrt(x^2 + y^2 + z^2)`
+    pub fn len(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns a point in the same direction with a length of 1.0
+    pub fn normalize(self) -> Self {
+        self * (1.0 / self.len())
+    }
+
+    /// Computes the dot product of the two points
+    pub fn dot(self, other: Self) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Computes the cross product of the two points
+    ///
+    /// ```rust
+    /// # use turtle::Point3;
+    /// let x = Point3 {x: 1.0, y: 0.0, z: 0.0};
+    /// let y = Point3 {x: 0.0, y: 1.0, z: 0.0};
+    /// assert_eq!(x.cross(y), Point3 {x: 0.0, y: 0.0, z: 1.0});
+    /// ```
+    pub fn cross(self, other: Self) -> Self {
+        Self {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+}
+
+impl From<(f64, f64, f64)> for Point3 {
+    fn from(pt: (f64, f64, f64)) -> Self {
+        Self {x: pt.0, y: pt.1, z: pt.2}
+    }
+}
+
+impl From<[f64; 3]> for Point3 {
+    fn from(pt: [f64; 3]) -> Self {
+        Self {x: pt[0], y: pt[1], z: pt[2]}
+    }
+}
+
+impl From<Point3> for [f64; 3] {
+    fn from(pt: Point3) -> Self {
+        [pt.x, pt.y, pt.z]
+    }
+}
+
+impl Add for Point3 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self {
+            x: self.x - other.x,
+            y: self.y - other.y,
+            z: self.z - other.z,
+        }
+    }
+}
+
+impl Mul<f64> for Point3 {
+    type Output = Self;
+
+    fn mul(self, other: f64) -> Self::Output {
+        Self {
+            x: self.x * other,
+            y: self.y * other,
+            z: self.z * other,
+        }
+    }
+}
+
+impl Mul<Point3> for f64 {
+    type Output = Point3;
+
+    fn mul(self, other: Point3) -> Self::Output {
+        other * self
+    }
+}
+
+impl Neg for Point3 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
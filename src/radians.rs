@@ -4,9 +4,9 @@ use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 use serde::{Serialize, Deserialize};
 use interpolation::Lerp;
 
-pub const PI: Radians = Radians(fPI);
+use crate::angle;
+
 pub const TWO_PI: Radians = Radians(2. * fPI);
-#[cfg_attr(any(feature = "test", test), allow(dead_code))]
 pub const ZERO: Radians = Radians(0.0);
 
 /// Represents an angle in radians.
@@ -74,6 +74,17 @@ impl Radians {
     pub fn floor(self) -> Self {
         Radians(self.0.floor())
     }
+
+    /// Returns an equivalent angle such that -PI < angle <= PI
+    pub fn normalized(self) -> Self {
+        Radians(angle::normalize(self.0, TWO_PI.0))
+    }
+
+    /// Returns the smallest angle to rotate counterclockwise to get from this angle to the other
+    /// angle. A negative value means that rotating clockwise is shorter.
+    pub fn shortest_difference(self, other: Self) -> Self {
+        (other - self).normalized()
+    }
 }
 
 impl Lerp for Radians {
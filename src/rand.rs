@@ -17,8 +17,16 @@
 //!
 //! * [`random()`] - for generating a single random value of a given type
 //! * [`random_range()`] - for generating a single random value of a given type in a certain range
+//! * [`random_range_step()`] - for generating a random value in a certain range that is a whole
+//!   number of steps away from the start of that range
 //! * [`shuffle()`] - for mixing up a slice of values (`Vec`, slices, etc.)
 //! * [`choose()`] - for choosing a single value from a slice of values (`Vec`, slices, etc.)
+//! * [`choose_weighted()`] - for choosing a single value from a slice of values where some values
+//!   should be chosen more often than others
+//! * [`noise1d()`] and [`noise2d()`] - for generating smoothly changing random values (see
+//!   [Smooth Randomness](#smooth-randomness))
+//! * [`SeededRng`] - for generating the same random values every time your program runs (see
+//!   [Reproducible Randomness](#reproducible-randomness))
 //!
 //! See the documentation for each of those functions for more on what you can use them for.
 //!
@@ -41,6 +49,58 @@
 //! * [`Point`] - a random point with two `f64` values greater than or equal to `0.0` and less than
 //!   or equal to `1.0`
 //!
+//! # Smooth Randomness
+//!
+//! Values from [`random()`] jump around a lot. Each value has nothing to do with the value that
+//! came before it. That is great for picking a random color, but if you use it to steer a turtle,
+//! you end up with a very jittery path.
+//!
+//! The [`noise1d()`] and [`noise2d()`] functions generate [Perlin noise]. Inputs that are close
+//! together produce outputs that are close together, so the values change smoothly as you move
+//! along. This is a great way to draw organic looking wandering paths, hills, clouds, etc.
+//!
+//! ```rust
+//! use turtle::{Turtle, rand::noise1d};
+//!
+//! let mut turtle = Turtle::new();
+//! for i in 0..100 {
+//!     // noise1d returns a value between 0.0 and 1.0, so this turns between -10 and 10 degrees
+//!     let turn = noise1d(i as f64 * 0.1) * 20.0 - 10.0;
+//!     turtle.right(turn);
+//!     turtle.forward(5.0);
+//! }
+//! ```
+//!
+//! For more detail, use [`noise1d_octaves()`] or [`noise2d_octaves()`]. These add together
+//! several layers ("octaves") of noise, each with finer detail than the last.
+//!
+//! The noise is random for each run of your program, but stays the same for the entire time
+//! your program is running. That means that calling `noise1d(1.5)` twice will give you the same
+//! value both times.
+//!
+//! # Reproducible Randomness
+//!
+//! Every time your program runs, [`random()`] and the other functions in this module generate
+//! different values. Sometimes you want a drawing that looks random, but is the same every time.
+//! For example, you may have found a random drawing that you really like, or you may want to
+//! check that your drawing hasn't changed in a test.
+//!
+//! A [`SeededRng`] generates values from a "seed" number that you choose. Using the same seed
+//! always results in the same values, in the same order.
+//!
+//! ```rust
+//! use turtle::{Color, Point, rand::SeededRng};
+//!
+//! let mut rng = SeededRng::new(2024);
+//! let color: Color = rng.random();
+//! let point: Point = rng.random_range([-200.0, -200.0], [200.0, 200.0]);
+//!
+//! // Starting again from the same seed generates the same values
+//! let mut rng = SeededRng::new(2024);
+//! assert_eq!(rng.random::<Color>(), color);
+//! assert_eq!(rng.random_range::<Point, _>([-200.0, -200.0], [200.0, 200.0]), point);
+//! ```
+//!
 //! # Random Custom Types
 //!
 //! To make types within your application capable of being used with [`random()`] or
@@ -52,6 +112,7 @@
 //! implement [`RandomSlice`] so it can be used with the [`shuffle()`] and [`choose()`] functions.
 //!
 //! ```rust
+//! # #![allow(dead_code)]
 //! use turtle::rand::RandomSlice;
 //!
 //! // This is a "newtype" wrapper around a Vec<T> which can be represented as a slice.
@@ -75,8 +136,16 @@
 //! [pseudo-random number generation]: https://en.wikipedia.org/wiki/Pseudorandom_number_generator
 //! [`random()`]: fn.random.html
 //! [`random_range()`]: fn.random_range.html
+//! [`random_range_step()`]: fn.random_range_step.html
 //! [`shuffle()`]: fn.shuffle.html
 //! [`choose()`]: fn.choose.html
+//! [`choose_weighted()`]: fn.choose_weighted.html
+//! [`noise1d()`]: fn.noise1d.html
+//! [`noise2d()`]: fn.noise2d.html
+//! [`noise1d_octaves()`]: fn.noise1d_octaves.html
+//! [`noise2d_octaves()`]: fn.noise2d_octaves.html
+//! [Perlin noise]: https://en.wikipedia.org/wiki/Perlin_noise
+//! [`SeededRng`]: struct.SeededRng.html
 //! [`Random`]: trait.Random.html
 //! [`RandomRange`]: trait.RandomRange.html
 //! [`RandomSlice`]: trait.RandomSlice.html
@@ -87,8 +156,28 @@
 //! [`Point`]: ../struct.Point.html
 //! [`opaque()`]: ../color/struct.Color.html#method.opaque
 
+use std::cell::RefCell;
 use std::num::Wrapping;
 
+use once_cell::sync::Lazy;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+thread_local! {
+    /// The generator used in place of the thread's random number generator while a
+    /// [`SeededRng`] is generating a value
+    static SEEDED_RNG: RefCell<Option<ChaCha8Rng>> = const { RefCell::new(None) };
+}
+
+/// Runs the given function with the random number generator that values should currently be
+/// generated from
+fn with_rng<T, F: FnOnce(&mut dyn RngCore) -> T>(f: F) -> T {
+    SEEDED_RNG.with(|seeded| match &mut *seeded.borrow_mut() {
+        Some(rng) => f(rng),
+        None => f(&mut rand::thread_rng()),
+    })
+}
+
 /// This trait represents any type that can have random values generated for it.
 ///
 /// **Tip:** There is a list later on this page that shows many of the types that implement this
@@ -104,6 +193,7 @@ use std::num::Wrapping;
 /// [`random_range()`]: fn.random_range.html
 ///
 /// ```rust,no_run
+/// # #![allow(dead_code)]
 /// use turtle::rand::{
 ///     random,
 ///     Random,
@@ -187,6 +277,7 @@ pub trait Random {
 /// value (e.g. [`Speed`], [`Color`], `f64`, `u32`, etc.). For example, if you had the type:
 ///
 /// ```rust,no_run
+/// # #![allow(dead_code)]
 /// #[derive(Debug, Clone)]
 /// struct Product {
 ///     price: f64,
@@ -276,7 +367,7 @@ macro_rules! impl_random {
             impl Random for $typ {
                 fn random() -> Self {
                     use rand::Rng;
-                    rand::thread_rng().gen()
+                    with_rng(|rng| rng.gen())
                 }
             }
 
@@ -284,7 +375,7 @@ macro_rules! impl_random {
                 fn random_range(low: Self, high: Self) -> Self {
                     use rand::{Rng, distributions::Uniform};
                     let uniform = Uniform::new_inclusive(low, high);
-                    rand::thread_rng().sample(&uniform)
+                    with_rng(|rng| rng.sample(&uniform))
                 }
             }
         )*
@@ -297,7 +388,7 @@ impl_random!(f32, f64, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128,
 impl Random for char {
     fn random() -> Self {
         use rand::Rng;
-        rand::thread_rng().gen()
+        with_rng(|rng| rng.gen())
     }
 }
 
@@ -305,7 +396,7 @@ impl Random for char {
 impl Random for bool {
     fn random() -> Self {
         use rand::Rng;
-        rand::thread_rng().gen()
+        with_rng(|rng| rng.gen())
     }
 }
 
@@ -464,6 +555,117 @@ pub fn random_range<T: RandomRange<B>, B>(low: B, high: B) -> T {
     RandomRange::random_range(low, high)
 }
 
+/// This trait represents any type that can have random values generated for it within a certain
+/// range, where the generated value must be a whole number of steps away from the low end of that
+/// range.
+///
+/// You will not typically use this trait directly. The [`random_range_step()`] function provides
+/// all the functionality of this trait without needing to have it in scope.
+///
+/// This trait is implemented for all of the numeric primitive types that implement
+/// [`RandomRange`]. Since [`Distance`] and [`Angle`] are both `f64`, you can use it with those
+/// too.
+///
+/// [`random_range_step()`]: fn.random_range_step.html
+/// [`RandomRange`]: trait.RandomRange.html
+/// [`Distance`]: ../type.Distance.html
+/// [`Angle`]: ../type.Angle.html
+pub trait RandomRangeStep: Sized {
+    /// Generate a single random value `x` in the given range such that low &le; x &le; high and
+    /// `x` is equal to `low + n * step` for some whole number `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low > high` or if `step` is not greater than zero.
+    fn random_range_step(low: Self, high: Self, step: Self) -> Self;
+}
+
+macro_rules! impl_random_range_step_int {
+    ($($typ:ty),*) => (
+        $(
+            impl RandomRangeStep for $typ {
+                fn random_range_step(low: Self, high: Self, step: Self) -> Self {
+                    assert!(low <= high, "low must be less than or equal to high");
+                    assert!(step > 0, "step must be greater than zero");
+
+                    // The distance between low and high always fits in an unsigned 128-bit
+                    // integer, even for the widest signed ranges
+                    let steps = high.abs_diff(low) as u128 / step as u128;
+                    let n: u128 = RandomRange::random_range(0, steps);
+                    // The result is between low and high, so the wrapping can only undo the
+                    // truncation of the offset to this type
+                    low.wrapping_add((n * step as u128) as $typ)
+                }
+            }
+        )*
+    );
+}
+
+impl_random_range_step_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_random_range_step_float {
+    ($($typ:ty),*) => (
+        $(
+            impl RandomRangeStep for $typ {
+                fn random_range_step(low: Self, high: Self, step: Self) -> Self {
+                    assert!(low <= high, "low must be less than or equal to high");
+                    assert!(step > 0.0, "step must be greater than zero");
+
+                    let steps = ((high - low) / step).floor() as u64;
+                    let n: u64 = RandomRange::random_range(0, steps);
+                    // Rounding can push the last step slightly past high
+                    (low + n as $typ * step).min(high)
+                }
+            }
+        )*
+    );
+}
+
+impl_random_range_step_float!(f32, f64);
+
+/// Generates a random value in the given range that is a whole number of steps away from `low`.
+///
+/// The value `x` that is returned will be such that low &le; x &le; high and `x` is equal to
+/// `low + n * step` for some whole number `n`. This is useful when you want random values that
+/// line up with a grid, or a random angle that is always a multiple of 45 degrees.
+///
+/// If `high` is not exactly a whole number of steps away from `low`, the largest value that can
+/// be returned is the last step before `high`.
+///
+/// See the [`RandomRangeStep`] trait for a list of types that can be used with this function.
+///
+/// [`RandomRangeStep`]: trait.RandomRangeStep.html
+///
+/// # Panics
+///
+/// Panics if `low > high` or if `step` is not greater than zero.
+///
+/// # Example
+///
+/// ```rust
+/// use turtle::rand::random_range_step;
+///
+/// // Generates one of 0, 45, 90, ..., 315, 360
+/// let angle: f64 = random_range_step(0.0, 360.0, 45.0);
+/// assert_eq!(angle % 45.0, 0.0);
+///
+/// // Generates one of 10, 13, 16, or 19
+/// let value: i32 = random_range_step(10, 20, 3);
+/// assert!(value >= 10 && value <= 19);
+/// assert_eq!((value - 10) % 3, 0);
+///
+/// // This will always return the same value because `low == high`
+/// assert_eq!(random_range_step(5u8, 5, 2), 5);
+///
+/// // Even the widest ranges work, and the result never goes past `high`
+/// let wide: i128 = random_range_step(i128::MIN, i128::MAX, 1 << 100);
+/// assert_eq!((wide - i128::MIN) % (1 << 100), 0);
+/// assert!(random_range_step(0.0, 0.3, 0.1) <= 0.3);
+/// ```
+pub fn random_range_step<T: RandomRangeStep>(low: T, high: T, step: T) -> T {
+    RandomRangeStep::random_range_step(low, high, step)
+}
+
 /// This trait represents useful random operations for slices.
 ///
 /// You will not typically use this trait directly or even import it.
@@ -491,14 +693,12 @@ impl<T> RandomSlice for [T] {
 
     fn shuffle(&mut self) {
         use rand::seq::SliceRandom;
-        let mut rng = rand::thread_rng();
-        <Self as SliceRandom>::shuffle(self, &mut rng);
+        with_rng(|rng| <Self as SliceRandom>::shuffle(self, rng));
     }
 
     fn choose(&self) -> Option<&Self::Item> {
         use rand::seq::SliceRandom;
-        let mut rng = rand::thread_rng();
-        <Self as SliceRandom>::choose(self, &mut rng)
+        with_rng(|rng| <Self as SliceRandom>::choose(self, rng))
     }
 }
 
@@ -594,3 +794,417 @@ pub fn shuffle<S: RandomSlice + ?Sized>(slice: &mut S) {
 pub fn choose<S: RandomSlice + ?Sized>(slice: &S) -> Option<&<S as RandomSlice>::Item> {
     slice.choose()
 }
+
+/// Chooses a random element from the slice, where the chance of each element being chosen is
+/// given by its weight, and returns a reference to it.
+///
+/// The `weights` slice must have the same length as `items`. Each weight is the relative chance of
+/// the item at the same index being chosen. For example, an item with a weight of `2.0` will be
+/// chosen twice as often as an item with a weight of `1.0`. Items with a weight of `0.0` will never
+/// be chosen.
+///
+/// If the slice is empty or all of the weights are zero, returns None.
+///
+/// # Panics
+///
+/// Panics if `items` and `weights` have different lengths or if any weight is negative, infinite,
+/// or NaN.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use turtle::{Turtle, rand::choose_weighted, colors::{RED, BLUE, GREEN}};
+///
+/// let mut turtle = Turtle::new();
+///
+/// let pen_colors = [RED, BLUE, GREEN];
+/// // Red will be chosen most of the time, green will almost never be chosen
+/// let weights = [10.0, 5.0, 1.0];
+/// let chosen_color = choose_weighted(&pen_colors, &weights).cloned().unwrap();
+/// turtle.set_pen_color(chosen_color);
+///
+/// // Even works with Vec
+/// let pen_colors = vec![RED, BLUE, GREEN];
+/// let chosen_color = choose_weighted(&pen_colors, &weights).cloned().unwrap();
+/// turtle.set_pen_color(chosen_color);
+/// ```
+pub fn choose_weighted<'a, T>(items: &'a [T], weights: &[f64]) -> Option<&'a T> {
+    use rand::{Rng, distributions::{WeightedIndex, WeightedError}};
+
+    assert_eq!(items.len(), weights.len(), "items and weights must have the same length");
+    for &weight in weights {
+        assert!(weight >= 0.0 && weight.is_finite(), "Invalid weight: {}. Weights must be finite and not negative", weight);
+    }
+
+    match WeightedIndex::new(weights) {
+        Ok(dist) => items.get(with_rng(|rng| rng.sample(&dist))),
+        Err(WeightedError::NoItem) | Err(WeightedError::AllWeightsZero) => None,
+        Err(err) => unreachable!("bug: weights should have been validated: {}", err),
+    }
+}
+
+/// A random number generator that always generates the same values when it is created with the
+/// same seed
+///
+/// This has methods that work just like [`random()`], [`random_range()`], [`shuffle()`], and
+/// the other functions in this module. Any type that can be used with those functions can be used
+/// with these methods too, including your own types that implement [`Random`] or [`RandomRange`].
+///
+/// Nothing about the turtle or the drawing affects the values that are generated, so you can
+/// use this to make generative art that looks exactly the same every time your program runs.
+/// The same seed produces the same values on every platform, and updating the dependencies of
+/// this crate will not change them either. They can only change if a new version of this crate
+/// changes how a particular type is generated.
+///
+/// [`random()`]: fn.random.html
+/// [`random_range()`]: fn.random_range.html
+/// [`shuffle()`]: fn.shuffle.html
+/// [`Random`]: trait.Random.html
+/// [`RandomRange`]: trait.RandomRange.html
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use turtle::{Turtle, Color, rand::SeededRng};
+///
+/// let mut turtle = Turtle::new();
+/// // Change the seed to get a different drawing
+/// let mut rng = SeededRng::new(7);
+///
+/// for _ in 0..50 {
+///     turtle.set_pen_color(rng.random::<Color>().opaque());
+///     turtle.forward(rng.random_range(10.0, 50.0));
+///     turtle.right(rng.random_range_step(0.0, 180.0, 30.0));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    rng: ChaCha8Rng,
+}
+
+impl SeededRng {
+    /// Creates a new random number generator from the given seed
+    ///
+    /// ```rust
+    /// use turtle::rand::SeededRng;
+    ///
+    /// let mut rng1 = SeededRng::new(42);
+    /// let mut rng2 = SeededRng::new(42);
+    /// for _ in 0..10 {
+    ///     assert_eq!(rng1.random::<u32>(), rng2.random::<u32>());
+    /// }
+    /// ```
+    pub fn new(seed: u64) -> Self {
+        Self {rng: ChaCha8Rng::seed_from_u64(seed)}
+    }
+
+    /// Generates a single random value of the type `T`
+    ///
+    /// See [`random()`](fn.random.html) for more information.
+    pub fn random<T: Random>(&mut self) -> T {
+        self.generate(random)
+    }
+
+    /// Generates a random value in the given range
+    ///
+    /// See [`random_range()`](fn.random_range.html) for more information.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low > high`
+    pub fn random_range<T: RandomRange<B>, B>(&mut self, low: B, high: B) -> T {
+        self.generate(|| random_range(low, high))
+    }
+
+    /// Generates a random value in the given range that is a whole number of steps away from
+    /// `low`
+    ///
+    /// See [`random_range_step()`](fn.random_range_step.html) for more information.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low > high` or if `step` is not greater than zero.
+    pub fn random_range_step<T: RandomRangeStep>(&mut self, low: T, high: T, step: T) -> T {
+        self.generate(|| random_range_step(low, high, step))
+    }
+
+    /// Shuffle the elements of the given slice in place
+    ///
+    /// See [`shuffle()`](fn.shuffle.html) for more information.
+    pub fn shuffle<S: RandomSlice + ?Sized>(&mut self, slice: &mut S) {
+        self.generate(|| shuffle(slice))
+    }
+
+    /// Chooses a random element from the slice and returns a reference to it
+    ///
+    /// See [`choose()`](fn.choose.html) for more information.
+    pub fn choose<'a, S: RandomSlice + ?Sized>(&mut self, slice: &'a S) -> Option<&'a <S as RandomSlice>::Item> {
+        self.generate(|| choose(slice))
+    }
+
+    /// Chooses a random element from the slice, where the chance of each element being chosen
+    /// is given by its weight
+    ///
+    /// See [`choose_weighted()`](fn.choose_weighted.html) for more information.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` and `weights` have different lengths or if any weight is negative,
+    /// infinite, or NaN.
+    pub fn choose_weighted<'a, T>(&mut self, items: &'a [T], weights: &[f64]) -> Option<&'a T> {
+        self.generate(|| choose_weighted(items, weights))
+    }
+
+    /// Runs the given function with this generator used in place of the thread's random number
+    /// generator
+    fn generate<T, F: FnOnce() -> T>(&mut self, f: F) -> T {
+        /// Puts the generator back, even if generating the value panicked
+        struct Restore<'a> {
+            rng: &'a mut ChaCha8Rng,
+            previous: Option<ChaCha8Rng>,
+        }
+
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                let used = SEEDED_RNG.with(|seeded| seeded.replace(self.previous.take()));
+                *self.rng = used.expect("bug: seeded random number generator was removed while in use");
+            }
+        }
+
+        let previous = SEEDED_RNG.with(|seeded| seeded.replace(Some(self.rng.clone())));
+        let _restore = Restore {rng: &mut self.rng, previous};
+        f()
+    }
+}
+
+/// The permutation table used to generate noise, repeated twice to avoid needing to wrap indexes
+///
+/// This is shuffled once per process so that the noise is different every time the program runs.
+static NOISE_PERMUTATION: Lazy<[u8; 512]> = Lazy::new(|| {
+    let mut values = [0u8; 256];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = i as u8;
+    }
+    shuffle(&mut values[..]);
+
+    let mut table = [0u8; 512];
+    for (i, value) in table.iter_mut().enumerate() {
+        *value = values[i % 256];
+    }
+    table
+});
+
+/// The amount that every position is moved by before generating noise
+///
+/// Gradient noise is always exactly in the middle of its range at the points of its lattice. This
+/// moves the lattice off of whole numbers (the positions that people are most likely to use) by an
+/// amount that does not divide evenly into anything.
+const NOISE_LATTICE_OFFSET: f64 = 0.618_033_988_749_894_9;
+
+/// The fade curve 6t^5 - 15t^4 + 10t^3 from Ken Perlin's "Improved Noise"
+fn noise_fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn noise_lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// Picks a gradient in [-1.0, 1.0] based on the hash value and returns its dot product with `x`
+fn noise_grad1(hash: u8, x: f64) -> f64 {
+    let gradient = (hash & 15) as f64 / 7.5 - 1.0;
+    gradient * x
+}
+
+/// Picks one of 8 gradient directions based on the hash value and returns its dot product with
+/// `(x, y)`
+fn noise_grad2(hash: u8, x: f64, y: f64) -> f64 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+/// Generates a smoothly changing random value between `0.0` and `1.0` (inclusive) for the given
+/// position.
+///
+/// Positions that are close together will result in values that are close together. The noise
+/// changes at roughly the scale of `1.0`, so if you want the value to change slowly, multiply
+/// the position by something small (e.g. `0.05`) before passing it in. Calling this function
+/// with the same position will always return the same value while the program is running.
+///
+/// The noise is built from random slopes placed at evenly spaced points that are `1.0` apart, and
+/// the value at each of those points is exactly `0.5`. Those points are offset so that they do not
+/// fall on whole numbers, but positions exactly `1.0` apart still always land on the same spot
+/// between those points. Step by a smaller amount to see the value change smoothly.
+///
+/// See [Smooth Randomness](index.html#smooth-randomness) for more information.
+///
+/// # Example
+///
+/// ```rust
+/// use turtle::rand::noise1d;
+///
+/// let value = noise1d(3.7);
+/// assert!(value >= 0.0 && value <= 1.0);
+/// // Always the same for the same position
+/// assert_eq!(value, noise1d(3.7));
+/// // Nearby positions result in similar values
+/// assert!((noise1d(3.7) - noise1d(3.71)).abs() < 0.1);
+/// // Whole numbers are not special
+/// assert!((0..10).any(|i| noise1d(i as f64) != 0.5));
+/// ```
+pub fn noise1d(x: f64) -> f64 {
+    let perm = &*NOISE_PERMUTATION;
+    let x = x + NOISE_LATTICE_OFFSET;
+
+    let x0 = x.floor();
+    let xi = (x0 as i64 & 255) as usize;
+    let xf = x - x0;
+
+    let u = noise_fade(xf);
+    let value = noise_lerp(u, noise_grad1(perm[xi], xf), noise_grad1(perm[xi + 1], xf - 1.0));
+
+    // The value is between -0.5 and 0.5, so this maps it to between 0.0 and 1.0
+    (value + 0.5).clamp(0.0, 1.0)
+}
+
+/// Generates a smoothly changing random value between `0.0` and `1.0` (inclusive) for the given
+/// position on a 2D plane.
+///
+/// This is the two dimensional version of [`noise1d()`]. It is useful for things like drawing
+/// terrain or textures, where each point needs a value that is similar to the values of the
+/// points around it.
+///
+/// See [Smooth Randomness](index.html#smooth-randomness) for more information.
+///
+/// [`noise1d()`]: fn.noise1d.html
+///
+/// # Example
+///
+/// ```rust
+/// use turtle::rand::noise2d;
+///
+/// let value = noise2d(3.7, -1.2);
+/// assert!(value >= 0.0 && value <= 1.0);
+/// assert_eq!(value, noise2d(3.7, -1.2));
+/// ```
+pub fn noise2d(x: f64, y: f64) -> f64 {
+    let perm = &*NOISE_PERMUTATION;
+    let x = x + NOISE_LATTICE_OFFSET;
+    let y = y + NOISE_LATTICE_OFFSET;
+
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let xi = (x0 as i64 & 255) as usize;
+    let yi = (y0 as i64 & 255) as usize;
+    let xf = x - x0;
+    let yf = y - y0;
+
+    let u = noise_fade(xf);
+    let v = noise_fade(yf);
+
+    let aa = perm[perm[xi] as usize + yi];
+    let ab = perm[perm[xi] as usize + yi + 1];
+    let ba = perm[perm[xi + 1] as usize + yi];
+    let bb = perm[perm[xi + 1] as usize + yi + 1];
+
+    let value = noise_lerp(
+        v,
+        noise_lerp(u, noise_grad2(aa, xf, yf), noise_grad2(ba, xf - 1.0, yf)),
+        noise_lerp(u, noise_grad2(ab, xf, yf - 1.0), noise_grad2(bb, xf - 1.0, yf - 1.0)),
+    );
+
+    // The value is between -1.0 and 1.0, so this maps it to between 0.0 and 1.0
+    ((value + 1.0) / 2.0).clamp(0.0, 1.0)
+}
+
+/// Generates a smoothly changing random value between `0.0` and `1.0` (inclusive) by adding
+/// together the given number of octaves of [`noise1d()`].
+///
+/// Each octave has twice the detail (frequency) and half the influence (amplitude) of the
+/// octave before it. Using a single octave is the same as calling [`noise1d()`]. More octaves
+/// result in a rougher, more natural looking result.
+///
+/// [`noise1d()`]: fn.noise1d.html
+///
+/// # Panics
+///
+/// Panics if `octaves` is zero.
+///
+/// # Example
+///
+/// ```rust
+/// use turtle::rand::{noise1d, noise1d_octaves};
+///
+/// let value = noise1d_octaves(3.7, 4);
+/// assert!(value >= 0.0 && value <= 1.0);
+///
+/// assert_eq!(noise1d_octaves(3.7, 1), noise1d(3.7));
+/// ```
+pub fn noise1d_octaves(x: f64, octaves: u32) -> f64 {
+    sum_octaves(octaves, |frequency| noise1d(x * frequency))
+}
+
+/// Generates a smoothly changing random value between `0.0` and `1.0` (inclusive) by adding
+/// together the given number of octaves of [`noise2d()`].
+///
+/// See [`noise1d_octaves()`] for more information about octaves.
+///
+/// [`noise2d()`]: fn.noise2d.html
+/// [`noise1d_octaves()`]: fn.noise1d_octaves.html
+///
+/// # Panics
+///
+/// Panics if `octaves` is zero.
+///
+/// # Example
+///
+/// ```rust
+/// use turtle::rand::noise2d_octaves;
+///
+/// let value = noise2d_octaves(3.7, -1.2, 6);
+/// assert!(value >= 0.0 && value <= 1.0);
+/// ```
+pub fn noise2d_octaves(x: f64, y: f64, octaves: u32) -> f64 {
+    sum_octaves(octaves, |frequency| noise2d(x * frequency, y * frequency))
+}
+
+/// Adds together octaves of noise and normalizes the result back into the range 0.0 to 1.0
+fn sum_octaves<F: Fn(f64) -> f64>(octaves: u32, noise: F) -> f64 {
+    assert!(octaves > 0, "octaves must be greater than zero");
+
+    let mut total = 0.0;
+    let mut max_total = 0.0;
+    let mut frequency = 1.0;
+    let mut amplitude = 1.0;
+    for _ in 0..octaves {
+        total += noise(frequency) * amplitude;
+        max_total += amplitude;
+
+        frequency *= 2.0;
+        amplitude *= 0.5;
+    }
+
+    total / max_total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_rng_sequence_is_stable() {
+        let mut rng = SeededRng::new(42);
+        let values: Vec<u32> = (0..4).map(|_| rng.random()).collect();
+        // These must never change, since that would change drawings made with a seed
+        assert_eq!(values, [962419617, 2928721845, 628724104, 4081401798]);
+        assert_eq!(rng.random_range::<i32, _>(-100, 100), 55);
+    }
+}
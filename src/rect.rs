@@ -0,0 +1,168 @@
+use serde::{Serialize, Deserialize};
+
+use crate::Point;
+
+/// An axis-aligned rectangle in 2D space
+///
+/// A `Rect` is represented by its bottom-left corner (`min`) and its top-right corner (`max`).
+/// Since the turtle coordinate system has the y-axis pointing up, `min.y` is the bottom of the
+/// rectangle and `max.y` is the top.
+///
+/// # Creating a Rect
+///
+/// You will usually create a `Rect` from two opposite corners or from a center point and a size.
+/// Both of these constructors work no matter what order the corners are given in.
+///
+/// ```rust
+/// # use turtle::{Rect, Point};
+/// let rect = Rect::from_points([100.0, 50.0], [-20.0, 10.0]);
+/// assert_eq!(rect.min, Point {x: -20.0, y: 10.0});
+/// assert_eq!(rect.max, Point {x: 100.0, y: 50.0});
+///
+/// let rect2 = Rect::from_center([40.0, 30.0], 120.0, 40.0);
+/// assert_eq!(rect, rect2);
+/// ```
+///
+/// # Working with Rects
+///
+/// ```rust
+/// # use turtle::Rect;
+/// let a = Rect::from_points([0.0, 0.0], [10.0, 10.0]);
+/// let b = Rect::from_points([5.0, 5.0], [20.0, 15.0]);
+///
+/// assert!(a.contains([2.0, 3.0]));
+/// assert!(!a.contains([12.0, 3.0]));
+///
+/// assert!(a.intersects(b));
+/// assert_eq!(a.intersection(b), Some(Rect::from_points([5.0, 5.0], [10.0, 10.0])));
+/// assert_eq!(a.union(b), Rect::from_points([0.0, 0.0], [20.0, 15.0]));
+///
+/// // Grow the rectangle by 5.0 in every direction
+/// assert_eq!(a.inflate(5.0), Rect::from_points([-5.0, -5.0], [15.0, 15.0]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    /// The bottom-left corner of the rectangle
+    pub min: Point,
+    /// The top-right corner of the rectangle
+    pub max: Point,
+}
+
+impl Rect {
+    /// Creates a rectangle with the two given points as opposite corners
+    pub fn from_points<P1: Into<Point>, P2: Into<Point>>(p1: P1, p2: P2) -> Self {
+        let p1 = p1.into();
+        let p2 = p2.into();
+
+        Self {
+            min: p1.min(p2),
+            max: p1.max(p2),
+        }
+    }
+
+    /// Creates a rectangle centered at the given point with the given width and height
+    ///
+    /// Negative values of `width` or `height` are treated as their absolute value.
+    pub fn from_center<P: Into<Point>>(center: P, width: f64, height: f64) -> Self {
+        let center = center.into();
+        let half_size = Point {x: width / 2.0, y: height / 2.0}.abs();
+
+        Self {
+            min: center - half_size,
+            max: center + half_size,
+        }
+    }
+
+    /// Returns the smallest rectangle that contains every one of the given points, or `None` if
+    /// no points were given
+    ///
+    /// ```rust
+    /// # use turtle::{Rect, Point};
+    /// let points = vec![Point {x: 1.0, y: 4.0}, Point {x: -3.0, y: 2.0}, Point {x: 2.0, y: -1.0}];
+    /// let rect = Rect::bounding(points).unwrap();
+    /// assert_eq!(rect, Rect::from_points([-3.0, -1.0], [2.0, 4.0]));
+    ///
+    /// assert_eq!(Rect::bounding(Vec::<Point>::new()), None);
+    /// ```
+    pub fn bounding<I, P>(points: I) -> Option<Self>
+        where I: IntoIterator<Item = P>,
+              P: Into<Point>,
+    {
+        let mut points = points.into_iter().map(Into::into);
+        let first = points.next()?;
+
+        Some(points.fold(Self {min: first, max: first}, |rect, point| Self {
+            min: rect.min.min(point),
+            max: rect.max.max(point),
+        }))
+    }
+
+    /// Returns the width of the rectangle
+    pub fn width(self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    /// Returns the height of the rectangle
+    pub fn height(self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    /// Returns the point at the center of the rectangle
+    pub fn center(self) -> Point {
+        self.min.lerp(self.max, 0.5)
+    }
+
+    /// Returns true if the rectangle has no area (its width or height is zero)
+    pub fn is_empty(self) -> bool {
+        self.width() <= 0.0 || self.height() <= 0.0
+    }
+
+    /// Returns true if the given point is inside the rectangle or on its edge
+    pub fn contains<P: Into<Point>>(self, point: P) -> bool {
+        let Point {x, y} = point.into();
+        x >= self.min.x && x <= self.max.x && y >= self.min.y && y <= self.max.y
+    }
+
+    /// Returns true if the other rectangle overlaps with this one
+    ///
+    /// Rectangles that only touch at their edges are considered to intersect.
+    pub fn intersects(self, other: Self) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x
+            && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+
+    /// Returns the area where this rectangle and the other rectangle overlap, or `None` if they do
+    /// not intersect
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        Some(Self {
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        })
+    }
+
+    /// Returns the smallest rectangle that contains both this rectangle and the other rectangle
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Returns a rectangle that is larger than this one by `amount` on every side
+    ///
+    /// A negative amount shrinks the rectangle instead. The rectangle will never shrink past its
+    /// center point.
+    pub fn inflate(self, amount: f64) -> Self {
+        let center = self.center();
+        let amount = Point {x: amount, y: amount};
+
+        Self {
+            min: (self.min - amount).min(center),
+            max: (self.max + amount).max(center),
+        }
+    }
+}
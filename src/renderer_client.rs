@@ -1,18 +1,42 @@
+use std::io;
+use std::fmt;
+use std::error::Error;
+use std::future::Future;
+use std::net::{SocketAddr, ToSocketAddrs};
 use std::sync::Arc;
+use std::time::Duration;
 
 use ipc_channel::ipc::IpcError;
 use serde::{Serialize, Deserialize};
 use tokio::sync::{mpsc, RwLock, Mutex};
-use thiserror::Error;
+use futures_util::future::{self, BoxFuture, FutureExt};
 
-use crate::ipc_protocol::{ClientSender, ConnectionError, ClientRequest, ServerResponse};
+use crate::ipc_protocol::{ClientSender, ClientReceiver, ConnectionError, ClientRequest, ServerResponse, connect_local_client, connect_tcp, join_invite};
 use crate::renderer_server::RendererServer;
+use crate::time;
+
+/// The longest time to wait for the crash report of the window after it disconnects
+///
+/// The window usually exits right after it disconnects, so this only matters if it gets stuck.
+const CRASH_REPORT_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// Signals that the IPC connection has been disconnected and therefore the window was probably
 /// closed
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
-#[error("Cannot continue to run turtle commands after window is closed. This panic stops the thread, but is not necessarily an error.")]
-struct Disconnected;
+///
+/// Contains a description of what went wrong if the window crashed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Disconnected(Option<Arc<str>>);
+
+impl fmt::Display for Disconnected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            None => write!(f, "Cannot continue to run turtle commands after window is closed. This panic stops the thread, but is not necessarily an error."),
+            Some(crash_report) => write!(f, "Cannot continue to run turtle commands because the window crashed.\n\n{}", crash_report),
+        }
+    }
+}
+
+impl Error for Disconnected {}
 
 /// A unique ID used to dispatch responses on the client side
 ///
@@ -21,6 +45,16 @@ struct Disconnected;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ClientId(usize);
 
+impl ClientId {
+    /// Creates the ID of the client with the given index
+    ///
+    /// Only needed to send requests before the dispatcher has started adding clients, since the
+    /// first client that it adds is always given the index `0`.
+    pub(crate) fn new(index: usize) -> Self {
+        ClientId(index)
+    }
+}
+
 /// Spawns the server, and dispatches the messages received from it
 ///
 /// Responses are dispatched back to the correct client based on the received client ID.
@@ -30,7 +64,11 @@ struct ClientDispatcher {
     ///
     /// When dropped, this will block until the server process has quit. This field is explicitly
     /// owned by this struct and not reference counted in order to guarantee that this happens.
-    server: RendererServer,
+    ///
+    /// This is `None` if the server was spawned by another process that shared it with this one,
+    /// or if it is being run directly by `turtle::run()`.
+    #[allow(dead_code)] // Only held so that it is dropped along with this struct
+    server: Option<RendererServer>,
 
     /// A channel for sending responses from the server to each client, indexed by `ClientId`
     ///
@@ -40,26 +78,54 @@ struct ClientDispatcher {
 }
 
 impl ClientDispatcher {
-    async fn new() -> Result<(Self, ClientSender), ConnectionError> {
-        let (server, sender, server_responses) = RendererServer::spawn().await?;
+    async fn new(tcp_addrs: Option<Vec<SocketAddr>>) -> Result<(Self, ClientSender), ConnectionError> {
+        let (server, sender, server_responses) = RendererServer::spawn(tcp_addrs).await?;
+        let crash_report = server.crash_report().boxed();
+        Ok(Self::with_connection(Some(server), crash_report, sender, server_responses))
+    }
+
+    async fn join(name: &str) -> Result<(Self, ClientSender), ConnectionError> {
+        let (sender, server_responses) = join_invite(name).await?;
+        // The process that shared the window is the one that finds out if it crashes
+        let crash_report = future::ready(None).boxed();
+        Ok(Self::with_connection(None, crash_report, sender, server_responses))
+    }
+
+    async fn join_tcp<A: ToSocketAddrs>(addr: A) -> Result<(Self, ClientSender), ConnectionError> {
+        let (sender, server_responses) = connect_tcp(addr).await?;
+        // A crash is reported on the machine running the window
+        let crash_report = future::ready(None).boxed();
+        Ok(Self::with_connection(None, crash_report, sender, server_responses))
+    }
+
+    async fn connect<S, F>(send_ipc_oneshot_name: S) -> Result<(Self, ClientSender), ConnectionError>
+        where S: FnOnce(String) -> F,
+              F: Future<Output=io::Result<()>>,
+    {
+        let (sender, server_responses) = connect_local_client(send_ipc_oneshot_name).await?;
+        // A panic that crashes the window also ends the thread running it, so there is nothing
+        // more to report
+        let crash_report = future::ready(None).boxed();
+        Ok(Self::with_connection(None, crash_report, sender, server_responses))
+    }
+
+    fn with_connection(
+        server: Option<RendererServer>,
+        crash_report: BoxFuture<'static, Option<String>>,
+        sender: ClientSender,
+        server_responses: ClientReceiver,
+    ) -> (Self, ClientSender) {
         let clients = Arc::new(RwLock::new(Vec::<mpsc::UnboundedSender<_>>::new()));
 
         let task_clients = clients.clone();
+        // The connection must be able to close while this task is still running
+        let task_sender = sender.downgrade();
         tokio::spawn(async move {
             loop {
                 let (id, response) = match server_responses.recv().await {
                     Ok((id, response)) => (id, Ok(response)),
 
-                    Err(IpcError::Disconnected) => {
-                        // Alert all the clients of the disconnection
-                        let clients = task_clients.read().await;
-                        for client in &*clients {
-                            // Ignoring the error since it just means that this particular client
-                            // connection must have gotten dropped
-                            client.send(Err(Disconnected)).unwrap_or(());
-                        }
-                        break;
-                    },
+                    Err(IpcError::Disconnected) => break,
 
                     Err(err) => panic!("Error while receiving IPC message: {:?}", err),
                 };
@@ -67,13 +133,31 @@ impl ClientDispatcher {
                 let clients = task_clients.read().await;
 
                 let ClientId(index) = id;
+                // An error just means that this particular client connection must have gotten
+                // dropped. Any event sent to it is put back so that another client can get it.
+                if let Err(mpsc::error::SendError(Ok(ServerResponse::Event(Some(event))))) = clients[index].send(response) {
+                    if let Some(sender) = task_sender.upgrade() {
+                        sender.send(id, ClientRequest::RequeueEvent(event)).unwrap_or(());
+                    }
+                }
+            }
+
+            // If the window crashed, its crash report is included so that the panics caused by
+            // the disconnection explain what happened
+            let crash_report = time::timeout(CRASH_REPORT_TIMEOUT, crash_report).await
+                .unwrap_or(None);
+            let disconnected = Disconnected(crash_report.map(Arc::from));
+
+            // Alert all the clients of the disconnection
+            let clients = task_clients.read().await;
+            for client in &*clients {
                 // Ignoring the error since it just means that this particular client
                 // connection must have gotten dropped
-                clients[index].send(response).unwrap_or(());
+                client.send(Err(disconnected.clone())).unwrap_or(());
             }
         });
 
-        Ok((Self {server, clients}, sender))
+        (Self {server, clients}, sender)
     }
 
     async fn add_client(&self) -> (ClientId, mpsc::UnboundedReceiver<Result<ServerResponse, Disconnected>>) {
@@ -90,16 +174,65 @@ impl ClientDispatcher {
 /// Represents a single connection to the server
 #[derive(Debug)]
 pub struct RendererClient {
-    dispatcher: Arc<ClientDispatcher>,
-    id: ClientId,
+    // Fields are dropped in declaration order, so the sender must come before the dispatcher.
+    // Dropping the dispatcher may block until the window is closed and the server can only tell
+    // that this client has finished once every sender has been dropped.
     sender: ClientSender,
+    id: ClientId,
     receiver: Mutex<mpsc::UnboundedReceiver<Result<ServerResponse, Disconnected>>>,
+    dispatcher: Arc<ClientDispatcher>,
 }
 
 impl RendererClient {
     /// Spawns a new server process and creates a connection to it
     pub async fn new() -> Result<Self, ConnectionError> {
-        let (dispatcher, sender) = ClientDispatcher::new().await?;
+        let (dispatcher, sender) = ClientDispatcher::new(None).await?;
+        let dispatcher = Arc::new(dispatcher);
+        let (id, receiver) = dispatcher.add_client().await;
+        let receiver = Mutex::new(receiver);
+
+        Ok(Self {dispatcher, id, sender, receiver})
+    }
+
+    /// Spawns a new server process that also accepts TCP connections at the first of the given
+    /// addresses that can be used, and creates a connection to it
+    pub async fn new_shared_tcp(addrs: Vec<SocketAddr>) -> Result<Self, ConnectionError> {
+        let (dispatcher, sender) = ClientDispatcher::new(Some(addrs)).await?;
+        let dispatcher = Arc::new(dispatcher);
+        let (id, receiver) = dispatcher.add_client().await;
+        let receiver = Mutex::new(receiver);
+
+        Ok(Self {dispatcher, id, sender, receiver})
+    }
+
+    /// Creates a connection to a server that another process has shared using the given name
+    pub async fn join(name: &str) -> Result<Self, ConnectionError> {
+        let (dispatcher, sender) = ClientDispatcher::join(name).await?;
+        let dispatcher = Arc::new(dispatcher);
+        let (id, receiver) = dispatcher.add_client().await;
+        let receiver = Mutex::new(receiver);
+
+        Ok(Self {dispatcher, id, sender, receiver})
+    }
+
+    /// Creates a connection to a server on another machine through the TCP bridge at the given
+    /// address
+    pub async fn join_tcp<A: ToSocketAddrs>(addr: A) -> Result<Self, ConnectionError> {
+        let (dispatcher, sender) = ClientDispatcher::join_tcp(addr).await?;
+        let dispatcher = Arc::new(dispatcher);
+        let (id, receiver) = dispatcher.add_client().await;
+        let receiver = Mutex::new(receiver);
+
+        Ok(Self {dispatcher, id, sender, receiver})
+    }
+
+    /// Connects to a server that is already running in this process, using the given function to
+    /// send it the name of the oneshot server that will establish the connection
+    pub async fn connect<S, F>(send_ipc_oneshot_name: S) -> Result<Self, ConnectionError>
+        where S: FnOnce(String) -> F,
+              F: Future<Output=io::Result<()>>,
+    {
+        let (dispatcher, sender) = ClientDispatcher::connect(send_ipc_oneshot_name).await?;
         let dispatcher = Arc::new(dispatcher);
         let (id, receiver) = dispatcher.add_client().await;
         let receiver = Mutex::new(receiver);
@@ -120,6 +253,7 @@ impl RendererClient {
     /// Sends a message to the server process
     ///
     /// When possible, prefer using methods from `ProtocolClient` instead of using this directly
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(client = ?self.id, request = req.name())))]
     pub fn send(&self, req: ClientRequest) {
         // The error produced by send is a serialization error, so it signals a bug in this code,
         // not something that should be propagated to be handled elsewhere.
@@ -127,6 +261,14 @@ impl RendererClient {
             .expect("bug: error while sending message through IPC")
     }
 
+    /// Sends a message to the server process, unless the server has already stopped
+    ///
+    /// Used for messages that no longer matter once the server is gone, which may be sent while
+    /// the window is closing.
+    pub fn send_if_connected(&self, req: ClientRequest) {
+        self.sender.send(self.id, req).unwrap_or(());
+    }
+
     /// Receives a response from the server process
     ///
     /// Note that if the same client sends multiple requests, there is no guarantee that the
@@ -134,14 +276,45 @@ impl RendererClient {
     /// ensure that ordering or otherwise prevent multiple requests from being sent simultaneously.
     ///
     /// When possible, prefer using methods from `ProtocolClient` instead of using this directly
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(client = ?self.id)))]
     pub async fn recv(&self) -> ServerResponse {
+        // Requests are always followed right away by waiting for their response, so the time spent
+        // waiting here is the latency of the request
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
         let mut receiver = self.receiver.lock().await;
-        receiver.recv().await
+        let response = receiver.recv().await
             // Since this struct keeps a ref-counted copy of the senders, they can't have possibly
             // been dropped at this point.
             .expect("bug: client senders should not be dropped yet")
             // This panic causes the program to exit if turtle commands continue after the window
             // closes
-            .unwrap_or_else(|err| panic!("IPC response not received: {}", err))
+            .unwrap_or_else(|err| panic!("IPC response not received: {}", err));
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(latency = ?start.elapsed(), "received response");
+
+        response
+    }
+}
+
+impl Drop for RendererClient {
+    fn drop(&mut self) {
+        // Any event that was sent to this client but never received is put back so that another
+        // client can get it. Closing first ensures that no more responses arrive after this.
+        // Nothing else can hold the lock while this is being dropped.
+        let mut receiver = match self.receiver.try_lock() {
+            Ok(receiver) => receiver,
+            Err(_) => return,
+        };
+        receiver.close();
+        while let Ok(response) = receiver.try_recv() {
+            if let Ok(ServerResponse::Event(Some(event))) = response {
+                // Sending only fails if the server has stopped, in which case the event no longer
+                // matters
+                self.sender.send(self.id, ClientRequest::RequeueEvent(event)).unwrap_or(());
+            }
+        }
     }
 }
@@ -6,88 +6,405 @@ mod backend;
 mod animation;
 mod handlers;
 mod start;
-
-cfg_if::cfg_if! {
-    if #[cfg(any(feature = "test", test))] {
-        mod test_event_loop_notifier;
-        use test_event_loop_notifier as event_loop_notifier;
-
-    } else {
-        mod event_loop_notifier;
-        mod main;
-    }
-}
+mod run;
+mod validate;
+mod event_loop_notifier;
+mod event_queue;
+mod navigation;
+mod session;
+#[cfg(not(any(feature = "test", feature = "headless", test)))]
+mod main;
+#[cfg(all(feature = "gamepad", not(any(feature = "test", feature = "headless", test))))]
+mod gamepad;
 
 pub(crate) use app::TurtleId;
+pub use app::{StampId, LayerId};
+pub(crate) use state::Minimap;
+pub use state::DisconnectBehavior;
+pub(crate) use renderer::symmetry::Symmetry;
 pub(crate) use backend::RendererServer;
-pub use renderer::export::ExportError;
-pub use start::start;
+pub use backend::{RendererBackend, Frame, set_renderer_backend};
+pub(crate) use coords::mm_to_px;
+pub(crate) use renderer::recorder::is_valid_recording_fps;
+pub(crate) use renderer::display_list::is_valid_dash_pattern;
+#[cfg(feature = "shaders")]
+pub(crate) use renderer::post_shader::PostShader;
+pub use renderer::export::{ExportError, ExportOptions, ExportRegion, PrintOptions, PaperSize};
+pub use renderer::plotter::PlotterOptions;
+pub use renderer::filter::Filter;
+pub use renderer::image::{ImageError, ImageHandle, BackgroundMode};
+pub(crate) use renderer::image::{ImageData, BackgroundImage};
+pub use renderer::import::{ImportError, ImportTransform};
+pub use session::SessionError;
+pub use renderer::mesh::{Vertex, Index};
+pub use renderer::primitive::{Primitive, PrimitiveId};
+pub use renderer::text::{Font, FontError, FontMetrics, TextAlign, TextOptions};
+#[cfg(feature = "shaders")]
+pub use renderer::post_shader::ShaderError;
+pub use coords::{ResizeAnchor, CoordinateSystem, WorldCoordinates, Origin};
+pub use animation::{ClearEffect, WipeDirection};
+pub use start::{start, start_with, ProcessOptions};
+pub use run::run;
+#[cfg(feature = "unstable")]
+pub(crate) use run::run_client;
+
+use std::collections::VecDeque;
 
 use ipc_channel::ipc::IpcError;
 use tokio::sync::mpsc;
 use parking_lot::{RwLock, Mutex};
 
-use crate::ipc_protocol::{ServerSender, ServerOneshotSender, ServerReceiver, ClientRequest};
+use crate::ipc_protocol::{CommandLog, Invite, ServerClientId, ServerSender, ServerOneshotSender, ServerReceiver, ClientRequest, ServerResponse};
 use crate::Event;
+use crate::time;
 
 use app::{SharedApp, App};
 use renderer::display_list::{SharedDisplayList, DisplayList};
 use event_loop_notifier::EventLoopNotifier;
+use event_queue::EventQueue;
 use animation::AnimationRunner;
+use renderer::recorder::GifRecorder;
+
+/// The channels through which the window (or whatever is standing in for it) talks to the server
+#[derive(Debug)]
+pub(crate) struct WindowChannels {
+    /// The events that happened in the window, sent to clients when they ask for them
+    pub events: mpsc::UnboundedReceiver<Event>,
+    /// The keyboard shortcuts pressed in the window
+    pub shortcuts: mpsc::UnboundedReceiver<Shortcut>,
+    /// Receives a message (or is closed) when the window shuts down
+    pub shutdown: mpsc::Receiver<()>,
+}
 
-/// Serves requests from the client forever
+/// Serves requests from the client until it disconnects or the window is closed
 async fn serve(
     conn: ServerSender,
-    client_requests: ServerReceiver,
+    mut client_requests: ServerReceiver,
     app: SharedApp,
     display_list: SharedDisplayList,
     event_loop: EventLoopNotifier,
-    mut events_receiver: mpsc::UnboundedReceiver<Event>,
-    mut server_shutdown_receiver: mpsc::Receiver<()>,
+    channels: WindowChannels,
 ) {
+    let WindowChannels {
+        events: events_receiver,
+        mut shortcuts,
+        shutdown: mut server_shutdown_receiver,
+    } = channels;
+
+    let mut events = EventQueue::new(events_receiver);
+
     let anim_runner = AnimationRunner::new(
         conn.clone(),
         app.clone(),
         display_list.clone(),
         event_loop.clone(),
     );
+    let recorder = GifRecorder::new(conn.clone(), app.clone(), display_list.clone());
+
+    // The requests held back while paused or waiting for a step, in the order they arrived
+    let mut held_requests = VecDeque::new();
+    let mut is_paused = false;
+    // The state of step mode, if it is enabled
+    let mut stepper: Option<Stepper> = None;
+    // The command log that every handled request is written into, if any
+    let mut command_log: Option<CommandLog> = None;
+    // The invitations published to share the drawing, which are removed when this task ends
+    let mut invites = Vec::new();
+    // Set once the window can no longer send shortcuts (e.g. because there is no window)
+    let mut shortcuts_closed = false;
+    // The clients waiting for the next event, in the order they started waiting
+    let mut event_waiters = VecDeque::new();
 
     loop {
-        // This will either receive the next request or end this task
-        let (client_id, request) = tokio::select! {
-            // If the main thread shuts down successfully, this will receive Some(()). If the main
-            // thread panics, this will return None. In either case, this loop needs to stop.
-            _ = server_shutdown_receiver.recv() => break,
-
-            req = client_requests.recv() => match req {
-                Ok(req) => req,
-                // Client has disconnected completely, no purpose in continuing this loop
-                Err(IpcError::Disconnected) => break,
-                Err(err) => panic!("unable to receive request from IPC client: {:?}", err),
+        // Requests that were held back are handled before any new ones
+        let is_waiting = is_paused || matches!(&stepper, Some(stepper) if stepper.is_waiting());
+        let next_request = match is_waiting {
+            false => held_requests.pop_front(),
+            true => None,
+        };
+
+        // This will either receive the next request, handle a shortcut, or end this task
+        let (client_id, request) = match next_request {
+            Some(req) => req,
+
+            None => tokio::select! {
+                // If the main thread shuts down successfully, this will receive Some(()). If the
+                // main thread panics, this will return None. In either case, this loop needs to
+                // stop.
+                _ = server_shutdown_receiver.recv() => return,
+
+                shortcut = shortcuts.recv(), if !shortcuts_closed => {
+                    match shortcut {
+                        Some(Shortcut::TogglePause) => {
+                            let paused = !is_paused;
+                            set_paused(&mut is_paused, paused, &anim_runner);
+                        },
+                        Some(Shortcut::Step) => if let Some(stepper) = &mut stepper {
+                            handle_handler_result(stepper.step(&app, &event_loop, &mut held_requests));
+                        },
+                        None => shortcuts_closed = true,
+                    }
+                    continue;
+                },
+
+                // Receiving `None` means no more events will be sent, which each waiting client is
+                // told about in turn
+                event = events.recv(), if !event_waiters.is_empty() => {
+                    let client_id = event_waiters.pop_front().expect("bug: a client should be waiting for an event");
+                    handle_handler_result(ServerOneshotSender::new(client_id, &conn)
+                        .send(ServerResponse::Event(event))
+                        .map_err(Into::into));
+                    continue;
+                },
+
+                req = client_requests.recv() => match req {
+                    Ok(req) => req,
+                    // Client has disconnected completely, no purpose in continuing this loop
+                    Err(IpcError::Disconnected) => break,
+                    Err(err) => panic!("unable to receive request from IPC client: {:?}", err),
+                },
             },
         };
 
+        let request = match request {
+            ClientRequest::SetPaused(paused) => {
+                set_paused(&mut is_paused, paused, &anim_runner);
+                continue;
+            },
+
+            ClientRequest::SetStepMode(enabled) => {
+                match (enabled, stepper.take()) {
+                    (true, None) => stepper = Some(Stepper::default()),
+                    (true, Some(prev)) => stepper = Some(prev),
+                    (false, Some(prev)) => handle_handler_result(prev.finish(&app, &event_loop, &mut held_requests)),
+                    (false, None) => {},
+                }
+                continue;
+            },
+
+            // Waiting for events is allowed even while paused or in step mode
+            ClientRequest::NextEvent => {
+                event_waiters.push_back(client_id);
+                continue;
+            },
+            // The client no longer exists, so there is no one to respond to
+            ClientRequest::CancelNextEvent => {
+                event_waiters.retain(|&waiter| waiter != client_id);
+                continue;
+            },
+            ClientRequest::RequeueEvent(event) => {
+                events.requeue(event);
+                continue;
+            },
+
+            ClientRequest::Step => {
+                if let Some(stepper) = &mut stepper {
+                    handle_handler_result(stepper.step(&app, &event_loop, &mut held_requests));
+                }
+                continue;
+            },
+
+            request if is_waiting => {
+                held_requests.push_back((client_id, request));
+                continue;
+            },
+
+            request if request.is_turtle_command() && stepper.is_some() => {
+                let stepper = stepper.as_mut().expect("bug: step mode should be enabled");
+                if !stepper.take_step() {
+                    handle_handler_result(stepper.wait(&app, &event_loop, client_id, request));
+                    continue;
+                }
+
+                request
+            },
+
+            request => request,
+        };
+
+        // Invalid requests are ignored so that they cannot crash the window. Only the clients
+        // waiting for a response can be told about the error.
+        let validation = validate::validate_request(&app.read(), &request);
+        if let Err(err) = validation {
+            if request.has_response() {
+                handle_handler_result(ServerOneshotSender::new(client_id, &conn)
+                    .send(ServerResponse::Error(err))
+                    .map_err(Into::into));
+            }
+            continue;
+        }
+
+        // The command log is dropped if it cannot be written to anymore, so that a full disk
+        // does not stop the drawing
+        let log_failed = match &mut command_log {
+            Some(log) if request.is_logged() => log.write(&request).is_err(),
+            _ => false,
+        };
+        if log_failed {
+            command_log = None;
+        }
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("handle_request", client = ?client_id, request = request.name()).entered();
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
         // Each request is executed immediately, in the order it arrives
         handle_handler_result(dispatch_request(
             ServerOneshotSender::new(client_id, &conn),
+            &conn,
             &app,
             &display_list,
             &event_loop,
-            &mut events_receiver,
+            &mut events,
             &anim_runner,
+            &recorder,
+            &mut command_log,
+            &mut invites,
             request,
         ));
 
+        // Animations continue after this, so this only measures the time taken to start them
+        #[cfg(feature = "tracing")]
+        tracing::debug!(latency = ?start.elapsed(), "handled request");
+    }
+
+    // No other process can join once requests are no longer being handled
+    drop(invites);
+
+    // The window stays open after the client disconnects, so it is up to the disconnect behavior
+    // of the drawing to decide what happens next
+    let behavior = app.read().drawing().disconnect_behavior;
+    handle_handler_result(finish(&app, &event_loop, &mut server_shutdown_receiver, behavior).await);
+}
+
+/// An action triggered by pressing one of the keyboard shortcuts of the drawing in the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))] // Only sent with a window
+pub(crate) enum Shortcut {
+    /// Pauses the drawing if it is running or resumes it if it is paused
+    TogglePause,
+    /// Runs the next turtle command while in step mode
+    Step,
+}
+
+/// Keeps track of the turtle commands allowed to run while in step mode
+#[derive(Debug, Default)]
+struct Stepper {
+    /// The turtle command waiting for the next step, if any
+    pending: Option<(ServerClientId, ClientRequest)>,
+    /// The number of steps taken before any turtle command was waiting for them
+    steps: usize,
+}
+
+impl Stepper {
+    /// Returns true if a turtle command is waiting for the next step
+    fn is_waiting(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Uses up one of the steps taken in advance, if there are any
+    fn take_step(&mut self) -> bool {
+        match self.steps {
+            0 => false,
+            _ => {
+                self.steps -= 1;
+                true
+            },
+        }
+    }
+
+    /// Holds back the given turtle command until the next step and shows it in the window title
+    fn wait(
+        &mut self,
+        app: &RwLock<App>,
+        event_loop: &EventLoopNotifier,
+        client_id: ServerClientId,
+        request: ClientRequest,
+    ) -> Result<(), handlers::HandlerError> {
+        let title = app.read().drawing().title.clone();
+        event_loop.set_title(format!("{} (next: {})", title, request.name()))?;
+        self.pending = Some((client_id, request));
+
+        Ok(())
+    }
+
+    /// Allows the next turtle command to run, including the one waiting for this step if any
+    fn step(
+        &mut self,
+        app: &RwLock<App>,
+        event_loop: &EventLoopNotifier,
+        held_requests: &mut VecDeque<(ServerClientId, ClientRequest)>,
+    ) -> Result<(), handlers::HandlerError> {
+        self.steps += 1;
+        if let Some(req) = self.pending.take() {
+            held_requests.push_front(req);
+            event_loop.set_title(app.read().drawing().title.clone())?;
+        }
+
+        Ok(())
+    }
+
+    /// Leaves step mode, letting the turtle command waiting for the next step run right away
+    fn finish(
+        mut self,
+        app: &RwLock<App>,
+        event_loop: &EventLoopNotifier,
+        held_requests: &mut VecDeque<(ServerClientId, ClientRequest)>,
+    ) -> Result<(), handlers::HandlerError> {
+        self.step(app, event_loop, held_requests)
+    }
+}
+
+/// Pauses or resumes the animations, if that changes whether they are paused
+fn set_paused(is_paused: &mut bool, paused: bool, anim_runner: &AnimationRunner) {
+    if *is_paused == paused {
+        return;
+    }
+
+    *is_paused = paused;
+    if paused {
+        anim_runner.pause();
+    } else {
+        anim_runner.resume();
+    }
+}
+
+/// Marks the window as finished and closes it if the given behavior asks for either of those
+async fn finish(
+    app: &RwLock<App>,
+    event_loop: &EventLoopNotifier,
+    server_shutdown_receiver: &mut mpsc::Receiver<()>,
+    behavior: DisconnectBehavior,
+) -> Result<(), handlers::HandlerError> {
+    if behavior.show_finished {
+        let title = app.read().drawing().title.clone();
+        event_loop.set_title(format!("{} (finished)", title))?;
+    }
+
+    if let Some(delay) = behavior.close_after {
+        tokio::select! {
+            // The window was closed before the delay ended
+            _ = server_shutdown_receiver.recv() => {},
+            _ = time::delay_for(delay) => event_loop.close()?,
+        }
     }
+
+    Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn dispatch_request(
     conn: ServerOneshotSender,
+    server_conn: &ServerSender,
     app: &RwLock<App>,
     display_list: &Mutex<DisplayList>,
     event_loop: &EventLoopNotifier,
-    events_receiver: &mut mpsc::UnboundedReceiver<Event>,
+    events: &mut EventQueue,
     anim_runner: &AnimationRunner,
+    recorder: &GifRecorder,
+    command_log: &mut Option<CommandLog>,
+    invites: &mut Vec<Invite>,
     request: ClientRequest,
 ) -> Result<(), handlers::HandlerError> {
     use ClientRequest::*;
@@ -96,12 +413,48 @@ fn dispatch_request(
             handlers::create_turtle(conn, &mut app.write(), event_loop)
         },
 
-        Export(path, format) => {
-            handlers::export_drawings(conn, &app.read(), &display_list.lock(), &path, format)
+        Export(path, format, options) => {
+            handlers::export_drawings(conn, &app.read(), &display_list.lock(), &path, format, &options)
+        },
+
+        Import(path, transform) => {
+            handlers::import_drawings(conn, &mut display_list.lock(), event_loop, &path, &transform)
+        },
+
+        SaveSession(path) => {
+            handlers::save_session(conn, &app.read(), &display_list.lock(), &path)
+        },
+        LoadSession(path) => {
+            handlers::load_session(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, &path)
+        },
+
+        Print(options) => {
+            handlers::print_drawings(conn, server_conn, &app.read(), &display_list.lock(), &options)
+        },
+
+        ExportToolpath(path, format, options) => {
+            handlers::export_toolpath(conn, &app.read(), &display_list.lock(), &path, format, &options)
+        },
+
+        StartRecording(path, fps) => {
+            handlers::start_recording(conn, recorder, &path, fps)
+        },
+        StopRecording => {
+            handlers::stop_recording(conn, recorder)
+        },
+        RecordCommands(path) => {
+            handlers::record_commands(conn, command_log, &path)
+        },
+
+        Share(name) => {
+            handlers::share_drawing(conn, server_conn, invites, &name)
+        },
+        ShareTcp(addrs) => {
+            handlers::share_tcp(conn, server_conn, &addrs)
         },
 
         PollEvent => {
-            handlers::poll_event(conn, events_receiver)
+            handlers::poll_event(conn, events)
         },
 
         DrawingProp(prop) => {
@@ -113,6 +466,62 @@ fn dispatch_request(
         ResetDrawingProp(prop) => {
             handlers::reset_drawing_prop(&mut app.write(), event_loop, prop)
         },
+        Flush => {
+            handlers::flush(event_loop)
+        },
+        // Handled by the server loop itself since it changes which requests are handled or waits
+        // for events from the window
+        SetPaused(_) | SetStepMode(_) | Step | NextEvent | CancelNextEvent | RequeueEvent(_) => {
+            unreachable!("bug: this request should have been handled by the server loop")
+        },
+        DrawCircle(center, radius, fill_color) => {
+            handlers::draw_circle(&app.read(), &mut display_list.lock(), event_loop, center, radius, fill_color)
+        },
+        AddLayer => {
+            handlers::add_layer(conn, &mut app.write(), &mut display_list.lock())
+        },
+        LayerIsVisible(layer) => {
+            handlers::layer_is_visible(conn, &display_list.lock(), layer)
+        },
+        SetLayerVisible(layer, is_visible) => {
+            handlers::set_layer_visible(&mut display_list.lock(), event_loop, layer, is_visible)
+        },
+        MoveLayer(layer, top) => {
+            handlers::move_layer(&mut display_list.lock(), event_loop, layer, top)
+        },
+        DrawingBounds => {
+            handlers::drawing_bounds(conn, &app.read(), &display_list.lock())
+        },
+        TurtleDrawingBounds(id) => {
+            handlers::turtle_drawing_bounds(conn, &app.read(), &display_list.lock(), id)
+        },
+        ExportMesh => {
+            handlers::export_mesh(conn, &display_list.lock())
+        },
+        ExportSvg(options) => {
+            handlers::export_svg(conn, &app.read(), &display_list.lock(), &options)
+        },
+        Primitives => {
+            handlers::primitives(conn, &display_list.lock())
+        },
+        Primitive(id) => {
+            handlers::primitive(conn, &display_list.lock(), id)
+        },
+        HitTest(point) => {
+            handlers::hit_test(conn, &display_list.lock(), point)
+        },
+        ColorAt(point) => {
+            handlers::color_at(conn, &app.read(), &display_list.lock(), point)
+        },
+        MeasureText(text, options) => {
+            handlers::measure_text(conn, &text, &options)
+        },
+        FontMetrics(options) => {
+            handlers::font_metrics(conn, &options)
+        },
+        LoadImage(image) => {
+            handlers::load_image(conn, &mut app.write(), image)
+        },
 
         TurtleProp(id, prop) => {
             handlers::turtle_prop(conn, &app.read(), id, prop)
@@ -128,13 +537,64 @@ fn dispatch_request(
         },
 
         MoveForward(id, distance) => {
-            handlers::move_forward(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, id, distance)
+            handlers::move_forward(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, id, distance, None)
+        },
+        TimedMoveForward(id, distance, duration) => {
+            handlers::move_forward(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, id, distance, Some(duration))
         },
         MoveTo(id, target_pos) => {
             handlers::move_to(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, id, target_pos)
         },
         RotateInPlace(id, angle, direction) => {
-            handlers::rotate_in_place(conn, &mut app.write(), event_loop, anim_runner, id, angle, direction)
+            handlers::rotate_in_place(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, id, angle, direction, None)
+        },
+        TimedRotateInPlace(id, angle, direction, duration) => {
+            handlers::rotate_in_place(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, id, angle, direction, Some(duration))
+        },
+        AnimatePenColor(id, color, duration) => {
+            handlers::animate_pen_color(&mut app.write(), event_loop, id, color, duration)
+        },
+        MoveArc(id, radius, extent, direction) => {
+            handlers::move_arc(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, id, radius, extent, direction)
+        },
+        MoveCircle(id, radius) => {
+            handlers::move_circle(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, id, radius)
+        },
+        CubicBezierTo(id, control1, control2, end) => {
+            handlers::cubic_bezier_to(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, id, control1, control2, end)
+        },
+        QuadBezierTo(id, control, end) => {
+            handlers::quad_bezier_to(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, id, control, end)
+        },
+        FollowPath(id, path) => {
+            handlers::follow_path(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, id, &path)
+        },
+        WriteText(id, text, options, align) => {
+            handlers::write_text(conn, &mut app.write(), &mut display_list.lock(), event_loop, id, &text, &options, align)
+        },
+        Stamp(id) => {
+            handlers::stamp(conn, &mut app.write(), &mut display_list.lock(), event_loop, id)
+        },
+        StampImage(id, image) => {
+            handlers::stamp_image(conn, &mut app.write(), &mut display_list.lock(), event_loop, id, image)
+        },
+        ClearStamp(id, stamp_id) => {
+            handlers::clear_stamp(&mut app.write(), &mut display_list.lock(), event_loop, id, stamp_id)
+        },
+        ClearStamps(id) => {
+            handlers::clear_stamps(&mut app.write(), &mut display_list.lock(), event_loop, id)
+        },
+        Undo(id, count) => {
+            handlers::undo(&mut app.write(), &mut display_list.lock(), event_loop, id, count)
+        },
+        PushState(id) => {
+            handlers::push_state(&mut app.write(), id)
+        },
+        PopState(id) => {
+            handlers::pop_state(conn, &mut app.write(), &mut display_list.lock(), event_loop, id)
+        },
+        PendingAnimations(id) => {
+            handlers::pending_animations(conn, anim_runner, id)
         },
 
         BeginFill(id) => {
@@ -150,6 +610,12 @@ fn dispatch_request(
         ClearTurtle(id) => {
             handlers::clear_turtle(&mut app.write(), &mut display_list.lock(), event_loop, id)
         },
+        ClearAnimated(effect) => {
+            handlers::clear_animated(conn, &mut app.write(), &mut display_list.lock(), event_loop, anim_runner, effect)
+        },
+        ClearRegion(area) => {
+            handlers::clear_region(&mut app.write(), &mut display_list.lock(), event_loop, anim_runner, area)
+        },
 
         DebugTurtle(id, angle_unit) => {
             handlers::debug_turtle(conn, &app.read(), id, angle_unit)
@@ -1,20 +1,23 @@
 use std::cmp::min;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-use tokio::{sync::mpsc, time};
+use tokio::sync::mpsc;
 use interpolation::lerp;
 use parking_lot::{RwLock, Mutex};
+use serde::{Serialize, Deserialize};
 
-use crate::renderer_client::ClientId;
-use crate::ipc_protocol::{ServerSender, RotationDirection, ServerResponse};
+use crate::ipc_protocol::{ServerSender, ServerClientId, RotationDirection, ServerResponse};
+use crate::time;
 use crate::radians::{self, Radians};
-use crate::Point;
+use crate::{Color, Distance, Point, Rect, Speed, Easing, BlendMode, colors};
 
 use super::{
     handle_handler_result,
     app::{SharedApp, App, TurtleDrawings, TurtleId},
     state::TurtleState,
-    renderer::display_list::{DisplayList, SharedDisplayList, PrimHandle},
+    coords::AxisScale,
+    renderer::display_list::{DisplayList, SharedDisplayList, PrimHandle, Curve},
     event_loop_notifier::EventLoopNotifier,
     handlers::HandlerError,
 };
@@ -28,10 +31,83 @@ const MICROS_PER_SEC: u64 = 1_000_000;
 /// The maximum length of an animation frame
 const FRAME_DURATION: time::Duration = time::Duration::from_micros(MICROS_PER_SEC / FPS);
 
+/// The largest distance between a curve and the line segments used to fill it or to move along it
+const CURVE_TOLERANCE: f64 = 0.1;
+
+/// The largest angle that the turtle turns by during a single line segment of an arc (1 degree)
+const ARC_SEGMENT_ANGLE: Radians = Radians::from_radians_value(std::f64::consts::PI / 180.0);
+
+/// A change of the pen color of a turtle that happens gradually while the turtle keeps drawing
+#[derive(Debug, Clone)]
+pub struct ColorTransition {
+    from: Color,
+    to: Color,
+    start: time::Instant,
+    duration: time::Duration,
+}
+
+impl ColorTransition {
+    pub fn new(from: Color, to: Color, duration: time::Duration) -> Self {
+        Self {from, to, start: time::Instant::now(), duration}
+    }
+
+    /// Returns the color of the pen at the given time
+    pub fn color_at(&self, now: time::Instant) -> Color {
+        if self.is_finished(now) {
+            return self.to;
+        }
+
+        let elapsed = (now - self.start).as_micros() as f64;
+        let t = elapsed / self.duration.as_micros() as f64;
+        self.from.lerp(self.to, t.min(1.0))
+    }
+
+    /// Returns true if the pen has reached its final color by the given time
+    pub fn is_finished(&self, now: time::Instant) -> bool {
+        now >= self.start + self.duration
+    }
+}
+
+/// Decides how long a movement or rotation takes
+#[derive(Debug, Clone, Copy)]
+pub enum Timing {
+    /// As long as it takes to move or turn at the given speed
+    Speed(Speed),
+    /// Exactly the given amount of time, no matter how far the turtle moves or turns
+    Duration(time::Duration),
+}
+
+impl From<Speed> for Timing {
+    fn from(speed: Speed) -> Self {
+        Timing::Speed(speed)
+    }
+}
+
+impl Timing {
+    /// Returns true if the animation should complete right away
+    fn is_instant(self) -> bool {
+        match self {
+            Timing::Speed(speed) => speed.is_instant(),
+            Timing::Duration(duration) => duration.as_micros() == 0,
+        }
+    }
+
+    /// Returns the total duration of the animation, using the given function to compute it from
+    /// the speed if there is no fixed duration
+    fn total_duration(self, from_speed: impl FnOnce(Speed) -> time::Duration) -> time::Duration {
+        match self {
+            Timing::Speed(speed) => from_speed(speed),
+            Timing::Duration(duration) => duration,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum AnimationKind {
     Move(MoveAnimation),
     Rotate(RotateAnimation),
+    Arc(ArcAnimation),
+    Curve(CurveAnimation),
 }
 
 impl From<MoveAnimation> for AnimationKind {
@@ -46,6 +122,18 @@ impl From<RotateAnimation> for AnimationKind {
     }
 }
 
+impl From<ArcAnimation> for AnimationKind {
+    fn from(anim: ArcAnimation) -> Self {
+        AnimationKind::Arc(anim)
+    }
+}
+
+impl From<CurveAnimation> for AnimationKind {
+    fn from(anim: CurveAnimation) -> Self {
+        AnimationKind::Curve(anim)
+    }
+}
+
 #[derive(Debug)]
 struct Animation {
     /// The ID of the turtle associated with this animation
@@ -53,11 +141,11 @@ struct Animation {
     /// The animation that will be played
     kind: AnimationKind,
     /// The client that will be notified when that animation is completed
-    client_id: ClientId,
+    client_id: ServerClientId,
 }
 
 impl Animation {
-    pub fn new(turtle_id: TurtleId, kind: impl Into<AnimationKind>, client_id: ClientId) -> Self {
+    pub fn new(turtle_id: TurtleId, kind: impl Into<AnimationKind>, client_id: ServerClientId) -> Self {
         let kind = kind.into();
         Self {turtle_id, kind, client_id}
     }
@@ -67,6 +155,8 @@ impl Animation {
         match &self.kind {
             Move(anim) => anim.is_running(),
             Rotate(anim) => anim.is_running(),
+            Arc(anim) => anim.is_running(),
+            Curve(anim) => anim.is_running(),
         }
     }
 
@@ -75,6 +165,18 @@ impl Animation {
         match &self.kind {
             Move(anim) => anim.next_update(),
             Rotate(anim) => anim.next_update(),
+            Arc(anim) => anim.next_update(),
+            Curve(anim) => anim.next_update(),
+        }
+    }
+
+    pub fn delay(&mut self, duration: time::Duration) {
+        use AnimationKind::*;
+        match &mut self.kind {
+            Move(anim) => anim.delay(duration),
+            Rotate(anim) => anim.delay(duration),
+            Arc(anim) => anim.delay(duration),
+            Curve(anim) => anim.delay(duration),
         }
     }
 }
@@ -99,6 +201,8 @@ pub struct MoveAnimation {
     current_pos: Point,
     /// The total duration of the animation
     total_duration: time::Duration,
+    /// Maps the fraction of the duration that has passed to the progress of the animation
+    easing: Easing,
     /// A handle to the line that is manipulated by this animation (if any)
     prim: Option<PrimHandle>,
     /// The index of this point in the fill polygon (if any)
@@ -110,16 +214,17 @@ impl MoveAnimation {
         turtle: &mut TurtleDrawings,
         display_list: &mut DisplayList,
         target_pos: Point,
+        timing: impl Into<Timing>,
     ) -> Self {
-        let TurtleState {position, speed, ref pen, ..} = turtle.state;
+        let TurtleState {position, easing, ..} = turtle.state;
+        let timing = timing.into();
 
         let start = time::Instant::now();
 
-        if cfg!(any(feature = "test", test)) || speed.is_instant() {
+        if cfg!(any(feature = "test", feature = "headless", test)) || timing.is_instant() {
             // Set to the final position and draw a line with no animation
             turtle.state.position = target_pos;
-            let prim = display_list.push_line(position, target_pos, pen);
-            turtle.drawings.extend(prim);
+            let prim = turtle.push_line(display_list, position, target_pos);
 
             // Append to the current fill polygon, if any
             let fill_poly_index = turtle.current_fill_polygon.map(|poly_handle| {
@@ -135,16 +240,19 @@ impl MoveAnimation {
                 target_pos,
                 current_pos: position,
                 total_duration: time::Duration::from_micros(0),
+                easing,
                 prim,
                 fill_poly_index,
             }
 
         } else {
-            let px_per_sec = speed.to_px_per_sec();
-            let abs_distance = (target_pos - position).len();
-            // Use microseconds instead of ms for greater precision
-            let total_micros = abs_distance * MICROS_PER_SEC as f64 / px_per_sec;
-            let total_duration = time::Duration::from_micros(total_micros as u64);
+            let total_duration = timing.total_duration(|speed| {
+                let px_per_sec = speed.to_px_per_sec();
+                let abs_distance = (target_pos - position).len();
+                // Use microseconds instead of ms for greater precision
+                let total_micros = abs_distance * MICROS_PER_SEC as f64 / px_per_sec;
+                time::Duration::from_micros(total_micros as u64)
+            });
 
             // If the duration of the animation is less than a frame, don't wait the entire frame
             // to complete it
@@ -154,8 +262,7 @@ impl MoveAnimation {
             // No need to update position since the turtle hasn't move anywhere yet
 
             // Start with a zero-length line since the animation hasn't started yet
-            let prim = display_list.push_line(position, position, pen);
-            turtle.drawings.extend(prim);
+            let prim = turtle.push_line(display_list, position, position);
 
             // Append to the current fill polygon, if any
             let fill_poly_index = turtle.current_fill_polygon.map(|poly_handle| {
@@ -170,6 +277,7 @@ impl MoveAnimation {
                 target_pos,
                 current_pos: position,
                 total_duration,
+                easing,
                 prim,
                 fill_poly_index,
             }
@@ -184,6 +292,12 @@ impl MoveAnimation {
         self.next_update
     }
 
+    /// Moves the animation later by the given amount of time, as if none of that time had passed
+    pub fn delay(&mut self, duration: time::Duration) {
+        self.start += duration;
+        self.next_update += duration;
+    }
+
     /// Advances the animation based on the amount of time that has elapsed since it started
     pub fn step(&mut self, now: time::Instant) {
         let &mut Self {
@@ -194,6 +308,7 @@ impl MoveAnimation {
             target_pos,
             ref mut current_pos,
             total_duration,
+            easing,
             prim: _,
             fill_poly_index: _,
         } = self;
@@ -207,7 +322,7 @@ impl MoveAnimation {
 
         } else {
             // t is the total progress made in the animation so far
-            let t = elapsed.as_micros() as f64 / total_duration.as_micros() as f64;
+            let t = easing.apply(elapsed.as_micros() as f64 / total_duration.as_micros() as f64);
             let current_pos = lerp(&start_pos, &target_pos, &t);
 
             // If the time remaining is less than a frame, don't wait the entire frame
@@ -264,6 +379,8 @@ pub struct RotateAnimation {
     direction: RotationDirection,
     /// The total duration of the animation
     total_duration: time::Duration,
+    /// Maps the fraction of the duration that has passed to the progress of the animation
+    easing: Easing,
 }
 
 impl RotateAnimation {
@@ -271,12 +388,14 @@ impl RotateAnimation {
         turtle: &mut TurtleDrawings,
         delta_angle: Radians,
         direction: RotationDirection,
+        timing: impl Into<Timing>,
     ) -> Self {
-        let TurtleState {heading, speed, ..} = turtle.state;
+        let TurtleState {heading, easing, ..} = turtle.state;
+        let timing = timing.into();
 
         let start = time::Instant::now();
 
-        if cfg!(any(feature = "test", test)) || speed.is_instant() {
+        if cfg!(any(feature = "test", feature = "headless", test)) || timing.is_instant() {
             // Set to the final heading with no animation
             turtle.state.heading = rotate(heading, delta_angle, direction);
 
@@ -290,14 +409,17 @@ impl RotateAnimation {
                 delta_angle,
                 direction,
                 total_duration: time::Duration::from_micros(0),
+                easing,
             }
 
         } else {
-            let rad_per_sec = speed.to_rad_per_sec();
-            // Use microseconds instead of ms for greater precision
-            let total_micros = (delta_angle * MICROS_PER_SEC as f64 / rad_per_sec).to_radians();
-            // abs() because time is always positive, even if angle is negative
-            let total_duration = time::Duration::from_micros(total_micros.abs() as u64);
+            let total_duration = timing.total_duration(|speed| {
+                let rad_per_sec = speed.to_rad_per_sec();
+                // Use microseconds instead of ms for greater precision
+                let total_micros = (delta_angle * MICROS_PER_SEC as f64 / rad_per_sec).to_radians();
+                // abs() because time is always positive, even if angle is negative
+                time::Duration::from_micros(total_micros.abs() as u64)
+            });
 
             // If the duration of the animation is less than a frame, don't wait the entire frame
             // to complete it
@@ -315,6 +437,7 @@ impl RotateAnimation {
                 delta_angle,
                 direction,
                 total_duration,
+                easing,
             }
         }
     }
@@ -327,6 +450,12 @@ impl RotateAnimation {
         self.next_update
     }
 
+    /// Moves the animation later by the given amount of time, as if none of that time had passed
+    pub fn delay(&mut self, duration: time::Duration) {
+        self.start += duration;
+        self.next_update += duration;
+    }
+
     /// Advances the animation based on the amount of time that has elapsed since it started
     pub fn step(&mut self, now: time::Instant) {
         let &mut Self {
@@ -338,6 +467,7 @@ impl RotateAnimation {
             delta_angle,
             direction,
             total_duration,
+            easing,
         } = self;
 
         let elapsed = start.elapsed();
@@ -350,7 +480,7 @@ impl RotateAnimation {
 
         } else {
             // t is the total progress made in the animation so far
-            let t = elapsed.as_micros() as f64 / total_duration.as_micros() as f64;
+            let t = easing.apply(elapsed.as_micros() as f64 / total_duration.as_micros() as f64);
             let current_delta = lerp(&radians::ZERO, &delta_angle, &t);
 
             // If the time remaining is less than a frame, don't wait the entire frame
@@ -367,6 +497,502 @@ impl RotateAnimation {
     }
 }
 
+#[derive(Debug)]
+pub struct ArcAnimation {
+    /// true if the animation should continue, false if it should stop
+    running: bool,
+    /// The next instant at which the animation loop should step this animation, up to
+    /// `FRAME_DURATION` from now
+    ///
+    /// Updated with every call to `step`
+    next_update: time::Instant,
+    /// The instant that the animation started, used to precisely determine how long the animation
+    /// has been running
+    start: time::Instant,
+    /// The scale of the world coordinates of the drawing, since the arc is circular in world
+    /// coordinates even if that makes it elliptical on the screen
+    scale: AxisScale,
+    /// The start angle of the turtle, in world coordinates
+    start_heading: Radians,
+    /// The center of the circle that the turtle moves around, in logical coordinates divided by
+    /// `scale` so that the circle is not stretched
+    center: Point,
+    /// The signed radius of the circle, positive if the center is to the left of the turtle
+    radius: Distance,
+    /// The angle that the turtle turns by the end of the animation, positive if counterclockwise
+    turn: Radians,
+    /// The number of line segments used to draw the whole arc
+    segments: usize,
+    /// The amount of progress from 0.0 to 1.0 (updated by step)
+    progress: f64,
+    /// The total duration of the animation
+    total_duration: time::Duration,
+    /// Maps the fraction of the duration that has passed to the progress of the animation
+    easing: Easing,
+    /// The index of the segment that is currently being drawn
+    current_segment: usize,
+    /// A handle to the line of the current segment (if any)
+    prim: Option<PrimHandle>,
+    /// The index of the end of the current segment in the fill polygon (if any)
+    fill_poly_index: Option<usize>,
+    /// If true, the lines drawn for each segment are replaced by a single circle at the end
+    circle: bool,
+    /// The lines drawn for each segment so far, only kept if `circle` is true
+    lines: Vec<PrimHandle>,
+}
+
+impl ArcAnimation {
+    pub fn new(
+        turtle: &mut TurtleDrawings,
+        display_list: &mut DisplayList,
+        scale: AxisScale,
+        radius: Distance,
+        extent: Radians,
+        direction: RotationDirection,
+        speed: Speed,
+    ) -> Self {
+        Self::start(turtle, display_list, scale, radius, extent, direction, false, speed)
+    }
+
+    /// Moves the turtle once around a circle with its center `radius` units to the left of the
+    /// turtle (to its right if the radius is negative)
+    ///
+    /// The circle is drawn as a single circle primitive once the animation is complete, unless
+    /// the world coordinates stretch it into an ellipse.
+    pub fn circle(
+        turtle: &mut TurtleDrawings,
+        display_list: &mut DisplayList,
+        scale: AxisScale,
+        radius: Distance,
+        speed: Speed,
+    ) -> Self {
+        Self::start(turtle, display_list, scale, radius, radians::TWO_PI, RotationDirection::Counterclockwise, true, speed)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn start(
+        turtle: &mut TurtleDrawings,
+        display_list: &mut DisplayList,
+        scale: AxisScale,
+        radius: Distance,
+        extent: Radians,
+        direction: RotationDirection,
+        circle: bool,
+        speed: Speed,
+    ) -> Self {
+        let TurtleState {position, heading, ref pen, easing, ..} = turtle.state;
+
+        // Mirrored world coordinates turn the other way, so that the direction stays the same on
+        // the screen
+        let direction = match direction {
+            _ if !scale.is_mirrored() => direction,
+            RotationDirection::Counterclockwise => RotationDirection::Clockwise,
+            RotationDirection::Clockwise => RotationDirection::Counterclockwise,
+        };
+        // Going around a center to the right is the same as going clockwise around a center to the
+        // left, so everything is computed as if the turtle was turning counterclockwise
+        let radius = match direction {
+            RotationDirection::Counterclockwise => radius,
+            RotationDirection::Clockwise => -radius,
+        };
+        // A negative extent moves backwards, which turns the other way
+        let turn = radius.signum() * extent;
+        let heading = scale.invert_heading(heading);
+        let center = scale.invert(position) + radius * left_normal(heading);
+        let segments = (turn.to_radians().abs() / ARC_SEGMENT_ANGLE.to_radians()).ceil().max(1.0) as usize;
+        // A circle that is stretched into an ellipse, dashed, or blended is left as the lines that
+        // were drawn, since the exact circle could not be drawn the same way
+        let circle = circle && scale.is_uniform() && pen.dash_pattern.is_empty()
+            && pen.blend_mode == BlendMode::Alpha;
+
+        let start = time::Instant::now();
+        let instant = cfg!(any(feature = "test", feature = "headless", test)) || speed.is_instant();
+        let total_duration = if instant {
+            time::Duration::from_micros(0)
+        } else {
+            let px_per_sec = speed.to_px_per_sec();
+            // The length of a stretched arc is approximated by the average scale of both axes
+            let average_scale = (scale.x * scale.y).abs().sqrt();
+            let abs_distance = (radius * turn.to_radians() * average_scale).abs();
+            // Use microseconds instead of ms for greater precision
+            let total_micros = abs_distance * MICROS_PER_SEC as f64 / px_per_sec;
+            time::Duration::from_micros(total_micros as u64)
+        };
+
+        // Start with a zero-length line since the animation hasn't started yet
+        let prim = turtle.push_line(display_list, position, position);
+
+        // Append to the current fill polygon, if any
+        let fill_poly_index = turtle.current_fill_polygon.map(|poly_handle| {
+            display_list.polygon_push(poly_handle, position)
+        });
+
+        let mut anim = Self {
+            running: !instant,
+            // If the duration of the animation is less than a frame, don't wait the entire frame
+            // to complete it
+            next_update: start + min(total_duration, FRAME_DURATION),
+            start,
+            scale,
+            start_heading: heading,
+            center,
+            radius,
+            turn,
+            segments,
+            progress: 0.0,
+            total_duration,
+            easing,
+            current_segment: 0,
+            prim,
+            fill_poly_index,
+            circle,
+            lines: if circle { prim.into_iter().collect() } else { Vec::new() },
+        };
+
+        if instant {
+            // Draw the entire arc and set to the final position and heading with no animation
+            anim.progress = 1.0;
+            anim.write_current_state(turtle, display_list);
+        }
+
+        anim
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn next_update(&self) -> time::Instant {
+        self.next_update
+    }
+
+    /// Moves the animation later by the given amount of time, as if none of that time had passed
+    pub fn delay(&mut self, duration: time::Duration) {
+        self.start += duration;
+        self.next_update += duration;
+    }
+
+    /// Advances the animation based on the amount of time that has elapsed since it started
+    pub fn step(&mut self, now: time::Instant) {
+        let elapsed = self.start.elapsed();
+        self.progress = if elapsed >= self.total_duration {
+            self.running = false;
+            self.next_update = now;
+
+            1.0
+
+        } else {
+            // If the time remaining is less than a frame, don't wait the entire frame
+            let remaining = self.total_duration - elapsed;
+            self.next_update = now + min(remaining, FRAME_DURATION);
+
+            self.easing.apply(elapsed.as_micros() as f64 / self.total_duration.as_micros() as f64)
+        };
+    }
+
+    pub fn write_current_state(&mut self, turtle: &mut TurtleDrawings, display_list: &mut DisplayList) {
+        let current_fill_polygon = turtle.current_fill_polygon;
+
+        // Finish every segment that the turtle has moved past and start the one after it
+        let reached_segment = min((self.progress * self.segments as f64) as usize, self.segments);
+        while self.current_segment < reached_segment {
+            self.current_segment += 1;
+            let end = self.point_at(self.current_segment as f64 / self.segments as f64);
+            self.update_segment_end(current_fill_polygon, display_list, end);
+
+            if self.current_segment < self.segments {
+                self.prim = turtle.push_line(display_list, end, end);
+                if self.circle {
+                    self.lines.extend(self.prim);
+                }
+
+                self.fill_poly_index = current_fill_polygon.map(|poly_handle| {
+                    display_list.polygon_push(poly_handle, end)
+                });
+            }
+        }
+
+        let pos = self.point_at(self.progress);
+        if self.current_segment < self.segments {
+            self.update_segment_end(current_fill_polygon, display_list, pos);
+
+        } else if self.circle && !self.lines.is_empty() {
+            // The whole circle has been drawn, so its lines can be replaced by the exact circle
+            let TurtleDrawings {state, drawings, ..} = &mut *turtle;
+            let lines = std::mem::take(&mut self.lines);
+            drawings.retain(|handle| lines.binary_search(handle).is_err());
+            display_list.remove(lines.into_iter());
+
+            let center = self.scale.apply(self.center);
+            let radius = self.radius * self.scale.x.abs();
+            let prim = display_list.push_circle(center, radius, &state.pen, colors::TRANSPARENT);
+            if let Some(prim) = prim {
+                display_list.set_layer(prim, state.layer);
+            }
+            drawings.extend(prim);
+        }
+
+        let state = &mut turtle.state;
+        state.position = pos;
+        let heading = rotate(self.start_heading, self.turn * self.progress, RotationDirection::Counterclockwise);
+        state.heading = self.scale.apply_heading(heading);
+        debug_assert!(!state.heading.is_nan(), "bug: heading became NaN");
+    }
+
+    /// Returns the position of the turtle once the given fraction of the arc has been drawn
+    fn point_at(&self, t: f64) -> Point {
+        let heading = self.start_heading + self.turn * t;
+        self.scale.apply(self.center - self.radius * left_normal(heading))
+    }
+
+    /// Moves the end of the line being drawn and its point in the fill polygon (if any)
+    fn update_segment_end(
+        &self,
+        current_fill_polygon: Option<PrimHandle>,
+        display_list: &mut DisplayList,
+        end: Point,
+    ) {
+        if let Some(prim) = self.prim {
+            display_list.line_update_end(prim, end);
+        }
+
+        if let Some(poly_handle) = current_fill_polygon {
+            // This unwrap is safe because `current_fill_polygon` is `Some`
+            display_list.polygon_update(poly_handle, self.fill_poly_index.unwrap(), end);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CurveAnimation {
+    /// true if the animation should continue, false if it should stop
+    running: bool,
+    /// The next instant at which the animation loop should step this animation, up to
+    /// `FRAME_DURATION` from now
+    ///
+    /// Updated with every call to `step`
+    next_update: time::Instant,
+    /// The instant that the animation started, used to precisely determine how long the animation
+    /// has been running
+    start: time::Instant,
+    /// The curves that the turtle moves along, one after the other
+    curves: Vec<Curve>,
+    /// Points along each curve, evenly spaced by the fraction of the way along that curve
+    ///
+    /// The last point of each curve is repeated as the first point of the next one.
+    samples: Vec<Point>,
+    /// The index of the curve that each sample is on and how far along that curve it is
+    sample_params: Vec<(usize, f64)>,
+    /// The distance along the whole path to each of the samples
+    distances: Vec<f64>,
+    /// The amount of progress from 0.0 to 1.0 (updated by step)
+    progress: f64,
+    /// The total duration of the animation
+    total_duration: time::Duration,
+    /// Maps the fraction of the duration that has passed to the progress of the animation
+    easing: Easing,
+    /// The index of the last sample that the turtle has moved past
+    current_sample: usize,
+    /// Handles to the curves drawn so far by this animation, one for each curve that the turtle
+    /// has reached (`None` if the pen is up)
+    prims: Vec<Option<PrimHandle>>,
+    /// The index of the current point in the fill polygon (if any)
+    fill_poly_index: Option<usize>,
+}
+
+impl CurveAnimation {
+    /// Creates an animation that moves the turtle along each of the given curves in order
+    ///
+    /// Each curve is given as its two control points followed by its end point. The first curve
+    /// starts at the turtle's current position and every other curve starts where the previous one
+    /// ended. There must be at least one curve.
+    pub fn new(
+        turtle: &mut TurtleDrawings,
+        display_list: &mut DisplayList,
+        path: &[[Point; 3]],
+        speed: Speed,
+    ) -> Self {
+        assert!(!path.is_empty(), "bug: curve animations should have at least one curve");
+
+        let TurtleState {position, ref pen, easing, ..} = turtle.state;
+        let curves: Vec<_> = path.iter().scan(position, |start, &[control1, control2, end]| {
+            let curve = Curve {
+                start: *start,
+                control1,
+                control2,
+                end,
+                thickness: pen.thickness,
+                color: pen.color,
+                // Only the shape of the curve is needed here
+                dash: None,
+                blend_mode: pen.blend_mode,
+            };
+            *start = end;
+            Some(curve)
+        }).collect();
+
+        // The samples are used to move at a constant speed along the curves and to fill them
+        let mut samples = Vec::new();
+        let mut sample_params = Vec::new();
+        for (index, curve) in curves.iter().enumerate() {
+            let points = curve.flatten(CURVE_TOLERANCE);
+            let last = (points.len() - 1) as f64;
+            sample_params.extend((0..points.len()).map(|i| (index, i as f64 / last)));
+            samples.extend(points);
+        }
+        let distances: Vec<f64> = samples.iter().scan((0.0, position), |(total, prev), &point| {
+            *total += (point - *prev).len();
+            *prev = point;
+            Some(*total)
+        }).collect();
+        let length = *distances.last().expect("bug: curves always have samples");
+
+        let start = time::Instant::now();
+        let instant = cfg!(any(feature = "test", feature = "headless", test)) || speed.is_instant();
+        let total_duration = if instant {
+            time::Duration::from_micros(0)
+        } else {
+            let px_per_sec = speed.to_px_per_sec();
+            // Use microseconds instead of ms for greater precision
+            let total_micros = length * MICROS_PER_SEC as f64 / px_per_sec;
+            time::Duration::from_micros(total_micros as u64)
+        };
+
+        // Start with a curve that has no length since the animation hasn't started yet
+        let prims = vec![turtle.push_curve(display_list, [position; 4])];
+
+        // Append to the current fill polygon, if any
+        let fill_poly_index = turtle.current_fill_polygon.map(|poly_handle| {
+            display_list.polygon_push(poly_handle, position)
+        });
+
+        let mut anim = Self {
+            running: !instant,
+            // If the duration of the animation is less than a frame, don't wait the entire frame
+            // to complete it
+            next_update: start + min(total_duration, FRAME_DURATION),
+            start,
+            curves,
+            samples,
+            sample_params,
+            distances,
+            progress: 0.0,
+            total_duration,
+            easing,
+            current_sample: 0,
+            prims,
+            fill_poly_index,
+        };
+
+        if instant {
+            // Draw every curve and set to the final position and heading with no animation
+            anim.progress = 1.0;
+            anim.write_current_state(turtle, display_list);
+        }
+
+        anim
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn next_update(&self) -> time::Instant {
+        self.next_update
+    }
+
+    /// Moves the animation later by the given amount of time, as if none of that time had passed
+    pub fn delay(&mut self, duration: time::Duration) {
+        self.start += duration;
+        self.next_update += duration;
+    }
+
+    /// Advances the animation based on the amount of time that has elapsed since it started
+    pub fn step(&mut self, now: time::Instant) {
+        let elapsed = self.start.elapsed();
+        self.progress = if elapsed >= self.total_duration {
+            self.running = false;
+            self.next_update = now;
+
+            1.0
+
+        } else {
+            // If the time remaining is less than a frame, don't wait the entire frame
+            let remaining = self.total_duration - elapsed;
+            self.next_update = now + min(remaining, FRAME_DURATION);
+
+            self.easing.apply(elapsed.as_micros() as f64 / self.total_duration.as_micros() as f64)
+        };
+    }
+
+    pub fn write_current_state(&mut self, turtle: &mut TurtleDrawings, display_list: &mut DisplayList) {
+        let last_sample = self.samples.len() - 1;
+        let last_curve = self.curves.len() - 1;
+
+        // Find how far along the path the turtle is based on the distance it has moved
+        let length = self.distances[last_sample];
+        let distance = self.progress * length;
+        let reached_sample = self.distances.iter().rposition(|&d| d <= distance).unwrap_or(0);
+        let (index, t) = if reached_sample >= last_sample || length == 0.0 {
+            (last_curve, self.progress)
+        } else {
+            let (d0, d1) = (self.distances[reached_sample], self.distances[reached_sample + 1]);
+            let between = if d1 > d0 { (distance - d0) / (d1 - d0) } else { 0.0 };
+            let (index, t0) = self.sample_params[reached_sample];
+            let (_, t1) = self.sample_params[reached_sample + 1];
+            (index, lerp(&t0, &t1, &between))
+        };
+
+        // Finish the curves that the turtle has moved past and start the one it is on now
+        while self.prims.len() <= index {
+            let finished = &self.curves[self.prims.len() - 1];
+            if let Some(prim) = self.prims[self.prims.len() - 1] {
+                display_list.curve_update(prim, finished.points());
+            }
+            self.prims.push(turtle.push_curve(display_list, [finished.end; 4]));
+        }
+
+        let TurtleDrawings {state, current_fill_polygon, ..} = turtle;
+        let curve = &self.curves[index];
+
+        // Each sample the turtle has moved past becomes a point in the fill polygon
+        if let Some(poly_handle) = *current_fill_polygon {
+            while self.current_sample < reached_sample {
+                self.current_sample += 1;
+                let point = self.samples[self.current_sample];
+                // This unwrap is safe because `current_fill_polygon` is `Some`
+                display_list.polygon_update(poly_handle, self.fill_poly_index.unwrap(), point);
+                if self.current_sample < last_sample {
+                    self.fill_poly_index = Some(display_list.polygon_push(poly_handle, point));
+                }
+            }
+        }
+
+        let pos = if self.progress >= 1.0 { curve.end } else { curve.point_at(t) };
+        if let Some(poly_handle) = *current_fill_polygon {
+            if self.current_sample < last_sample {
+                display_list.polygon_update(poly_handle, self.fill_poly_index.unwrap(), pos);
+            }
+        }
+
+        // Update the curve we have been drawing, if any
+        if let Some(prim) = self.prims[index] {
+            display_list.curve_update(prim, curve.split_at(t).points());
+        }
+
+        state.position = pos;
+        // The turtle keeps its heading if the curve does not go anywhere
+        if let Some(direction) = curve.tangent_at(t) {
+            state.heading = rotate(radians::ZERO, Radians::from_radians_value(direction.atan2()), RotationDirection::Counterclockwise);
+        }
+    }
+}
+
+/// Returns the unit vector that points to the left of a turtle with the given heading
+fn left_normal(heading: Radians) -> Point {
+    Point {x: -heading.sin(), y: heading.cos()}
+}
+
 /// Rotates the given `angle` by the given `rotation` in the given `direction`
 ///
 /// Let's say you have a starting angle X. Standard angles go counterclockwise, so
@@ -385,20 +1011,203 @@ fn rotate(angle: Radians, rotation: Radians, direction: RotationDirection) -> Ra
     angle - radians::TWO_PI * (angle / radians::TWO_PI).floor()
 }
 
+/// A transition used by [`Drawing::clear_animated()`] to go from the current drawing to an empty
+/// one
+///
+/// Each effect covers the visible part of the drawing with the background color over the given
+/// number of seconds. Once it is completely covered, everything is cleared.
+///
+/// [`Drawing::clear_animated()`]: struct.Drawing.html#method.clear_animated
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ClearEffect {
+    /// Fades the drawing out over the given number of seconds
+    Fade(f64),
+    /// Sweeps across the drawing in the given direction over the given number of seconds
+    Wipe(WipeDirection, f64),
+}
+
+impl ClearEffect {
+    /// Returns true if the duration of the effect is finite and not negative
+    pub fn is_valid(self) -> bool {
+        let duration = match self {
+            ClearEffect::Fade(duration) | ClearEffect::Wipe(_, duration) => duration,
+        };
+        duration.is_finite() && duration >= 0.0
+    }
+
+    fn duration(self) -> time::Duration {
+        match self {
+            ClearEffect::Fade(duration) | ClearEffect::Wipe(_, duration) => {
+                time::Duration::from_micros((duration * MICROS_PER_SEC as f64) as u64)
+            },
+        }
+    }
+}
+
+/// The direction that a [`ClearEffect::Wipe`] moves in
+///
+/// [`ClearEffect::Wipe`]: enum.ClearEffect.html#variant.Wipe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WipeDirection {
+    /// Starts from the right edge of the window and moves left
+    Left,
+    /// Starts from the left edge of the window and moves right
+    Right,
+    /// Starts from the bottom edge of the window and moves up
+    Up,
+    /// Starts from the top edge of the window and moves down
+    Down,
+}
+
+/// Gradually covers the drawing before clearing it
+#[derive(Debug)]
+pub struct ClearAnimation {
+    /// true if the animation should continue, false if it should stop
+    running: bool,
+    /// The next instant at which the animation loop should step this animation
+    next_update: time::Instant,
+    /// The instant that the animation started
+    start: time::Instant,
+    /// The effect being played
+    effect: ClearEffect,
+    /// The area of the drawing that was visible when the animation started
+    area: Rect,
+    /// The color that the area will be covered with
+    color: Color,
+    /// A handle to the polygon covering the drawing
+    cover: PrimHandle,
+    /// The amount of progress from 0.0 to 1.0 (updated by step)
+    progress: f64,
+}
+
+impl ClearAnimation {
+    /// Starts covering the given area of the drawing
+    ///
+    /// Returns `None` (without changing anything) if the animation should complete right away,
+    /// in which case the drawing should be cleared immediately instead.
+    pub fn new(display_list: &mut DisplayList, effect: ClearEffect, area: Rect, color: Color) -> Option<Self> {
+        let total_duration = effect.duration();
+        if cfg!(any(feature = "test", feature = "headless", test)) || total_duration.as_micros() == 0 {
+            return None;
+        }
+
+        // The cover is drawn above everything that is visible
+        let cover = display_list.push_polygon(vec![area.min; 4], color);
+        display_list.set_layer(cover, display_list.top_visible_layer());
+
+        let start = time::Instant::now();
+        let anim = Self {
+            running: true,
+            next_update: start + min(total_duration, FRAME_DURATION),
+            start,
+            effect,
+            area,
+            color,
+            // The points are set right away below
+            cover,
+            progress: 0.0,
+        };
+        anim.write_current_state(display_list);
+
+        Some(anim)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn next_update(&self) -> time::Instant {
+        self.next_update
+    }
+
+    /// Moves the animation later by the given amount of time, as if none of that time had passed
+    pub fn delay(&mut self, duration: time::Duration) {
+        self.start += duration;
+        self.next_update += duration;
+    }
+
+    /// Advances the animation based on the amount of time that has elapsed since it started
+    pub fn step(&mut self, now: time::Instant) {
+        let total_duration = self.effect.duration();
+        let elapsed = self.start.elapsed();
+        self.progress = if elapsed >= total_duration {
+            self.running = false;
+            self.next_update = now;
+
+            1.0
+
+        } else {
+            let remaining = total_duration - elapsed;
+            self.next_update = now + min(remaining, FRAME_DURATION);
+
+            elapsed.as_micros() as f64 / total_duration.as_micros() as f64
+        };
+    }
+
+    pub fn write_current_state(&self, display_list: &mut DisplayList) {
+        let Rect {min, max} = self.area;
+        let t = self.progress;
+
+        let (min, max, alpha) = match self.effect {
+            ClearEffect::Fade(_) => (min, max, self.color.alpha * t),
+            ClearEffect::Wipe(direction, _) => {
+                let (min, max) = match direction {
+                    WipeDirection::Left => (Point {x: lerp(&max.x, &min.x, &t), y: min.y}, max),
+                    WipeDirection::Right => (min, Point {x: lerp(&min.x, &max.x, &t), y: max.y}),
+                    WipeDirection::Up => (min, Point {x: max.x, y: lerp(&min.y, &max.y, &t)}),
+                    WipeDirection::Down => (Point {x: min.x, y: lerp(&max.y, &min.y, &t)}, max),
+                };
+                (min, max, self.color.alpha)
+            },
+        };
+
+        let corners = [min, Point {x: max.x, y: min.y}, max, Point {x: min.x, y: max.y}];
+        for (i, &corner) in corners.iter().enumerate() {
+            display_list.polygon_update(self.cover, i, corner);
+        }
+        display_list.polygon_set_fill_color(self.cover, Color {alpha, ..self.color});
+    }
+}
+
+/// Removes every drawing from the display list and from every turtle
+pub fn clear_drawings(app: &mut App, display_list: &mut DisplayList) {
+    display_list.clear();
+
+    for (_, turtle) in app.turtles_mut() {
+        let TurtleDrawings {state: _, drawings, current_fill_polygon, stamps, undo_steps, saved_states: _, last_stroke, pen_color_transition: _} = turtle;
+
+        drawings.clear();
+        *current_fill_polygon = None;
+        stamps.clear();
+        undo_steps.clear();
+        *last_stroke = None;
+    }
+}
+
 #[derive(Debug)]
 enum Message {
     /// Run the given animation
     Play(Animation),
+    /// Run the given clear animation, notifying the given client once it is complete
+    Clear(ClearAnimation, ServerClientId),
     /// Stop all animations that are currently playing
     ///
     /// Animations stop at wherever they were last updated.
     StopAll,
+    /// Freeze all animations where they are until they are resumed
+    Pause,
+    /// Continue all animations from where they were paused
+    Resume,
 }
 
+/// The IDs of the turtles that currently have an animation playing
+type PlayingAnimations = Arc<Mutex<HashSet<TurtleId>>>;
+
 /// Spawns a task to manage running animations and drive them to completion
 #[derive(Debug)]
 pub struct AnimationRunner {
     sender: mpsc::UnboundedSender<Message>,
+    playing: PlayingAnimations,
 }
 
 impl AnimationRunner {
@@ -409,6 +1218,7 @@ impl AnimationRunner {
         event_loop: EventLoopNotifier,
     ) -> Self {
         let (sender, receiver) = mpsc::unbounded_channel();
+        let playing = PlayingAnimations::default();
 
         tokio::spawn(animation_loop(
             conn,
@@ -416,19 +1226,45 @@ impl AnimationRunner {
             display_list,
             event_loop,
             receiver,
+            playing.clone(),
         ));
 
-        Self {sender}
+        Self {sender, playing}
     }
 
-    pub fn play(&self, turtle_id: TurtleId, kind: impl Into<AnimationKind>, client_id: ClientId) {
+    pub fn play(&self, turtle_id: TurtleId, kind: impl Into<AnimationKind>, client_id: ServerClientId) {
+        // Marked as playing right away so that requests handled before the animation task
+        // receives this message still see the animation
+        self.playing.lock().insert(turtle_id);
         self.send(Message::Play(Animation::new(turtle_id, kind, client_id)));
     }
 
+    /// Returns the number of animations that have not completed yet, either for the given turtle
+    /// or for all turtles
+    pub fn pending(&self, turtle_id: Option<TurtleId>) -> usize {
+        let playing = self.playing.lock();
+        match turtle_id {
+            Some(id) => playing.contains(&id) as usize,
+            None => playing.len(),
+        }
+    }
+
+    pub fn clear(&self, anim: ClearAnimation, client_id: ServerClientId) {
+        self.send(Message::Clear(anim, client_id));
+    }
+
     pub fn stop_all(&self) {
         self.send(Message::StopAll);
     }
 
+    pub fn pause(&self) {
+        self.send(Message::Pause);
+    }
+
+    pub fn resume(&self) {
+        self.send(Message::Resume);
+    }
+
     fn send(&self, mess: Message) {
         self.sender.send(mess)
             .expect("bug: animation runner task should run as long as server task");
@@ -442,9 +1278,14 @@ async fn animation_loop(
     display_list: SharedDisplayList,
     event_loop: EventLoopNotifier,
     mut receiver: mpsc::UnboundedReceiver<Message>,
+    playing: PlayingAnimations,
 ) {
     // Map of turtle ID to the current animation playing for it (if any)
     let mut animations: HashMap<TurtleId, Animation> = HashMap::new();
+    // The clear animation currently playing (if any) and the client waiting for it
+    let mut clearing: Option<(ClearAnimation, ServerClientId)> = None;
+    // The instant that the animations were paused at, if they are paused
+    let mut paused_at: Option<time::Instant> = None;
 
     let mut next_frame = time::Instant::now() + FRAME_DURATION;
     // It's important to update as soon as an animation is ready to be updated because otherwise we
@@ -452,7 +1293,7 @@ async fn animation_loop(
     // many small lines that take less than a frame duration, it may have to wait too long in
     // between lines. That would make Speed stop mattering under a certain line length and would
     // impose an undesirable minimum amount of time on each animation.
-    let mut next_update = compute_next_update(next_frame, &animations);
+    let mut next_update = compute_next_update(next_frame, &animations, &clearing);
 
     loop {
         tokio::select! {
@@ -466,8 +1307,15 @@ async fn animation_loop(
                     animations.insert(anim.turtle_id, anim);
                 },
 
+                Some(Message::Clear(anim, client_id)) => {
+                    debug_assert!(clearing.is_none(),
+                        "bug: cannot start clearing while another clear animation is playing");
+                    clearing = Some((anim, client_id));
+                },
+
                 Some(Message::StopAll) => {
                     // Complete all pending animations at their last update
+                    playing.lock().clear();
                     for anim in animations.values() {
                         handle_handler_result(conn.send(
                             anim.client_id,
@@ -476,14 +1324,41 @@ async fn animation_loop(
                     }
 
                     animations.clear();
+
+                    // Clearing stops immediately too, but the drawing still needs to end up empty
+                    if let Some((_, client_id)) = clearing.take() {
+                        handle_handler_result(complete_clear(
+                            &conn,
+                            &app,
+                            &display_list,
+                            &event_loop,
+                            client_id,
+                        ));
+                    }
+                },
+
+                Some(Message::Pause) => if paused_at.is_none() {
+                    paused_at = Some(time::Instant::now());
+                },
+
+                Some(Message::Resume) => if let Some(paused_at) = paused_at.take() {
+                    // Each animation continues from where it was, as if no time passed while it
+                    // was paused
+                    let paused_for = paused_at.elapsed();
+                    for anim in animations.values_mut() {
+                        anim.delay(paused_for);
+                    }
+                    if let Some((anim, _)) = &mut clearing {
+                        anim.delay(paused_for);
+                    }
                 },
 
                 // Sender has been dropped, so renderer server has stopped running
                 None => break,
             },
 
-            // Trigger an update once the next update time has elapsed
-            _ = time::delay_until(next_update) => {
+            // Trigger an update once the next update time has elapsed, unless paused
+            _ = time::delay_until(next_update), if paused_at.is_none() => {
                 let now = time::Instant::now();
 
                 handle_handler_result(update_animations(
@@ -493,6 +1368,15 @@ async fn animation_loop(
                     &display_list,
                     &event_loop,
                     &mut animations,
+                    &playing,
+                ));
+                handle_handler_result(update_clear_animation(
+                    now,
+                    &conn,
+                    &app,
+                    &display_list,
+                    &event_loop,
+                    &mut clearing,
                 ));
 
                 // Only advance if the frame has elapsed
@@ -508,7 +1392,7 @@ async fn animation_loop(
         }
 
         // Set the time at which we should schedule the next update
-        next_update = compute_next_update(next_frame, &animations);
+        next_update = compute_next_update(next_frame, &animations, &clearing);
     }
 }
 
@@ -516,9 +1400,11 @@ async fn animation_loop(
 fn compute_next_update(
     next_frame: time::Instant,
     animations: &HashMap<TurtleId, Animation>,
+    clearing: &Option<(ClearAnimation, ServerClientId)>,
 ) -> time::Instant {
     let next_update = animations.values()
         .map(|anim| anim.next_update())
+        .chain(clearing.iter().map(|(anim, _)| anim.next_update()))
         .min()
         .unwrap_or(next_frame);
 
@@ -534,6 +1420,7 @@ fn update_animations(
     display_list: &Mutex<DisplayList>,
     event_loop: &EventLoopNotifier,
     animations: &mut HashMap<TurtleId, Animation>,
+    playing: &Mutex<HashSet<TurtleId>>,
 ) -> Result<(), HandlerError> {
     // true if even one animation was updated
     let mut animation_updated = false;
@@ -549,10 +1436,14 @@ fn update_animations(
         match &mut anim.kind {
             Move(anim) => anim.step(now),
             Rotate(anim) => anim.step(now),
+            Arc(anim) => anim.step(now),
+            Curve(anim) => anim.step(now),
         }
 
         // Check if the animation has completed
         if !anim.is_running() {
+            // No longer pending as soon as the client can find out that it has completed
+            playing.lock().remove(&anim.turtle_id);
             conn.send(anim.client_id, ServerResponse::AnimationComplete(anim.turtle_id))?;
 
             completed_animations.push(anim.turtle_id);
@@ -567,16 +1458,24 @@ fn update_animations(
         let mut app = app.write();
         let mut display_list = display_list.lock();
         for anim in animations.values_mut() {
-            let TurtleDrawings {state, current_fill_polygon, ..} = app.turtle_mut(anim.turtle_id);
+            let turtle = app.turtle_mut(anim.turtle_id);
 
             use AnimationKind::*;
-            match &anim.kind {
+            match &mut anim.kind {
                 Move(anim) => {
-                    anim.write_current_state(state, *current_fill_polygon, &mut display_list);
+                    anim.write_current_state(&mut turtle.state, turtle.current_fill_polygon, &mut display_list);
                 },
 
                 Rotate(anim) => {
-                    anim.write_current_state(state);
+                    anim.write_current_state(&mut turtle.state);
+                },
+
+                Arc(anim) => {
+                    anim.write_current_state(turtle, &mut display_list);
+                },
+
+                Curve(anim) => {
+                    anim.write_current_state(turtle, &mut display_list);
                 },
             }
         }
@@ -593,3 +1492,46 @@ fn update_animations(
 
     Ok(())
 }
+
+/// Updates the clear animation (if any) if it is ready to be updated, clearing the drawing once
+/// the animation is complete
+fn update_clear_animation(
+    now: time::Instant,
+    conn: &ServerSender,
+    app: &RwLock<App>,
+    display_list: &Mutex<DisplayList>,
+    event_loop: &EventLoopNotifier,
+    clearing: &mut Option<(ClearAnimation, ServerClientId)>,
+) -> Result<(), HandlerError> {
+    let anim = match clearing {
+        Some((anim, _)) if now >= anim.next_update() => anim,
+        _ => return Ok(()),
+    };
+
+    anim.step(now);
+    if anim.is_running() {
+        anim.write_current_state(&mut display_list.lock());
+        event_loop.request_redraw()?;
+
+    } else if let Some((_, client_id)) = clearing.take() {
+        complete_clear(conn, app, display_list, event_loop, client_id)?;
+    }
+
+    Ok(())
+}
+
+/// Clears the drawing (including the cover drawn by the clear animation) and notifies the client
+fn complete_clear(
+    conn: &ServerSender,
+    app: &RwLock<App>,
+    display_list: &Mutex<DisplayList>,
+    event_loop: &EventLoopNotifier,
+    client_id: ServerClientId,
+) -> Result<(), HandlerError> {
+    clear_drawings(&mut app.write(), &mut display_list.lock());
+    conn.send(client_id, ServerResponse::ClearComplete)?;
+
+    event_loop.request_redraw()?;
+
+    Ok(())
+}
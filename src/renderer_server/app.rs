@@ -1,15 +1,74 @@
 use std::sync::Arc;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
 
 use serde::{Serialize, Deserialize};
 use parking_lot::RwLock;
+use crate::time::Instant;
 
-use super::state::{TurtleState, DrawingState};
-use super::renderer::display_list::PrimHandle;
+use crate::{Color, Point, Speed};
+use crate::radians::Radians;
+
+use super::state::{TurtleState, DrawingState, SavedState};
+use super::animation::{Timing, ColorTransition};
+use super::renderer::display_list::{DisplayList, PrimHandle};
+use super::renderer::image::{ImageData, BackgroundMode};
+use super::renderer::text::GlyphImageKey;
 
 /// The unique ID of a particular turtle
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TurtleId(usize);
 
+impl TurtleId {
+    /// Returns the index of this turtle, in the order that the turtles were created
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// The unique ID of an image that has been loaded into the drawing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ImageId(usize);
+
+impl ImageId {
+    /// Returns the index of this image, in the order that the images were loaded
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// The largest number of commands that can be undone for each turtle
+///
+/// Once this many commands have been saved, the oldest command can no longer be undone.
+const MAX_UNDO_STEPS: usize = 1000;
+
+/// Identifies a stamp left on the canvas by a turtle
+///
+/// Stamps are created using [`Turtle::stamp()`](struct.Turtle.html#method.stamp) or
+/// [`Turtle::stamp_image()`](struct.Turtle.html#method.stamp_image) and can be removed again
+/// using [`Turtle::clear_stamp()`](struct.Turtle.html#method.clear_stamp). Every stamp in a
+/// drawing has a different ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StampId(usize);
+
+/// Identifies a layer of a drawing
+///
+/// Every drawing starts with a single layer, [`LayerId::BASE`](#associatedconstant.BASE). More
+/// layers can be added with [`Drawing::add_layer()`](struct.Drawing.html#method.add_layer), and
+/// turtles can be moved between them using
+/// [`Turtle::set_layer()`](struct.Turtle.html#method.set_layer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LayerId(usize);
+
+impl LayerId {
+    /// The layer that every drawing starts with and every turtle draws in by default
+    pub const BASE: LayerId = LayerId(0);
+
+    pub(crate) fn index(self) -> usize {
+        self.0
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct TurtleDrawings {
     /// The current state of this turtle: position, heading, etc.
@@ -24,6 +83,164 @@ pub struct TurtleDrawings {
     /// This handle will already be present in `drawings`, so it does not need to be added after
     /// the fill has begun.
     pub current_fill_polygon: Option<PrimHandle>,
+
+    /// The stamps created by this turtle that have not been cleared, in the order they were
+    /// created, along with the drawings that make up each stamp
+    ///
+    /// The handles for each stamp will already be present in `drawings`.
+    pub stamps: Vec<(StampId, Vec<PrimHandle>)>,
+
+    /// The state of the turtle before each of the commands that can be undone, with the most
+    /// recent command last
+    pub undo_steps: VecDeque<UndoStep>,
+
+    /// The states saved by pushing the state of the turtle, with the most recently pushed state
+    /// last
+    pub saved_states: Vec<SavedState>,
+
+    /// The line or curve that was most recently drawn by this turtle, which the dash pattern of
+    /// the next line or curve continues from
+    ///
+    /// The handle may no longer be in the display list if it has been removed.
+    pub last_stroke: Option<PrimHandle>,
+
+    /// The gradual change of the pen color that is in progress, if any
+    ///
+    /// The pen color in `state` is only updated to follow the transition when something is drawn
+    /// with it, so use `pen_color()` to find out what the pen color currently is.
+    pub pen_color_transition: Option<ColorTransition>,
+}
+
+/// What is needed to undo one command (e.g. a movement) of a turtle
+#[derive(Debug, Clone)]
+pub struct UndoStep {
+    /// The position of the turtle before the command
+    position: Point,
+    /// The heading of the turtle before the command
+    heading: Radians,
+    /// Every drawing created by the command has a handle greater than or equal to this one
+    first_handle: PrimHandle,
+    /// The fill polygon of the turtle before the command (if any) and the number of points it had
+    fill: Option<(PrimHandle, usize)>,
+}
+
+impl TurtleDrawings {
+    /// Returns the current color of the pen, taking any transition in progress into account
+    pub fn pen_color(&self) -> Color {
+        match &self.pen_color_transition {
+            Some(transition) => transition.color_at(Instant::now()),
+            None => self.state.pen.color,
+        }
+    }
+
+    /// Updates the pen color of the turtle to follow the transition in progress (if any)
+    pub fn update_pen_color(&mut self) {
+        if let Some(transition) = &self.pen_color_transition {
+            let now = Instant::now();
+            self.state.pen.color = transition.color_at(now);
+            if transition.is_finished(now) {
+                self.pen_color_transition = None;
+            }
+        }
+    }
+
+    /// Pushes a line drawn with the pen of the turtle into the display list
+    ///
+    /// Returns `None` if the pen is up. Otherwise, the line is added to the drawings of the
+    /// turtle, placed in its layer, and continues the dash pattern of the line or curve drawn
+    /// before it.
+    pub fn push_line(&mut self, display_list: &mut DisplayList, start: Point, end: Point) -> Option<PrimHandle> {
+        self.update_pen_color();
+        let handle = display_list.push_line(start, end, &self.state.pen)?;
+        self.add_stroke(display_list, handle);
+        Some(handle)
+    }
+
+    /// Pushes a curve drawn with the pen of the turtle into the display list
+    ///
+    /// Returns `None` if the pen is up. Otherwise, the curve is added just like a line would be
+    /// by `push_line()`.
+    pub fn push_curve(&mut self, display_list: &mut DisplayList, points: [Point; 4]) -> Option<PrimHandle> {
+        self.update_pen_color();
+        let handle = display_list.push_curve(points, &self.state.pen)?;
+        self.add_stroke(display_list, handle);
+        Some(handle)
+    }
+
+    fn add_stroke(&mut self, display_list: &mut DisplayList, handle: PrimHandle) {
+        display_list.set_layer(handle, self.state.layer);
+        if let Some(previous) = self.last_stroke {
+            display_list.continue_dash(handle, previous);
+        }
+
+        self.drawings.push(handle);
+        self.last_stroke = Some(handle);
+    }
+
+    /// Forgets about the given drawings after they have been removed from the display list
+    ///
+    /// The current fill polygon must not be one of them. Stamps are forgotten once every drawing
+    /// that makes them up has been removed.
+    pub fn forget_drawings(&mut self, removed: &HashSet<PrimHandle>) {
+        self.drawings.retain(|handle| !removed.contains(handle));
+        for (_, prims) in &mut self.stamps {
+            prims.retain(|handle| !removed.contains(handle));
+        }
+        self.stamps.retain(|(_, prims)| !prims.is_empty());
+
+        // Undoing a command no longer needs to remove points from a fill that is gone
+        for step in &mut self.undo_steps {
+            if step.fill.is_some_and(|(handle, _)| removed.contains(&handle)) {
+                step.fill = None;
+            }
+        }
+    }
+
+    /// Records the current state of the turtle so that the command it is about to run can be
+    /// undone
+    pub fn save_undo_step(&mut self, display_list: &DisplayList) {
+        if self.undo_steps.len() == MAX_UNDO_STEPS {
+            self.undo_steps.pop_front();
+        }
+
+        self.undo_steps.push_back(UndoStep {
+            position: self.state.position,
+            heading: self.state.heading,
+            first_handle: display_list.next_handle(),
+            fill: self.current_fill_polygon
+                .map(|handle| (handle, display_list.polygon_len(handle))),
+        });
+    }
+
+    /// Undoes the most recent command of the turtle, removing everything it drew and moving the
+    /// turtle back to where it was
+    ///
+    /// Returns false if there was nothing to undo.
+    pub fn undo(&mut self, display_list: &mut DisplayList) -> bool {
+        let step = match self.undo_steps.pop_back() {
+            Some(step) => step,
+            None => return false,
+        };
+        let is_new = |&handle: &PrimHandle| handle >= step.first_handle;
+
+        display_list.remove(self.drawings.iter().copied().filter(is_new));
+        self.drawings.retain(|handle| !is_new(handle));
+        self.stamps.retain(|(_, prims)| !prims.iter().any(is_new));
+        // A fill started by the command was removed along with everything else it drew
+        if self.current_fill_polygon.as_ref().is_some_and(is_new) {
+            self.current_fill_polygon = None;
+        }
+        // A fill polygon that was removed from the display list is also removed from the undo
+        // steps, so it must still exist
+        if let Some((handle, len)) = step.fill {
+            display_list.polygon_truncate(handle, len);
+        }
+
+        self.state.position = step.position;
+        self.state.heading = step.heading;
+
+        true
+    }
 }
 
 /// The entire state of the application, shared between threads in the server
@@ -36,6 +253,19 @@ pub struct App {
     /// Need to be very careful deleting from this field because the `TurtleId` returned from
     /// `add_turtle()` must remain unique and thus can never be repeated.
     turtles: Vec<TurtleDrawings>,
+    /// Each `ImageId` indexes into this field
+    ///
+    /// Images are never removed so that every `ImageId` stays valid for as long as the drawing
+    /// exists.
+    images: Vec<Arc<ImageData>>,
+    /// The image added for each character image used in text, so that each is only stored once
+    glyph_images: HashMap<GlyphImageKey, ImageId>,
+    /// The number of stamps that have been created, used to generate a unique `StampId`
+    stamps_created: usize,
+    /// The number of layers that have been added on top of the base layer
+    ///
+    /// Layers are never removed, so every `LayerId` up to this number is valid.
+    layers_added: usize,
 }
 
 impl App {
@@ -62,6 +292,36 @@ impl App {
         &self.turtles[index]
     }
 
+    /// Returns the speed that the animations of the given turtle should play at
+    ///
+    /// Nothing is animated if animation is disabled for the whole drawing. Nothing is animated
+    /// while the tracer of the drawing is skipping updates either, since most of the frames of
+    /// each animation would never be shown.
+    pub fn animation_speed(&self, id: TurtleId) -> Speed {
+        if self.drawing.is_animated && self.drawing.tracer == 1 {
+            self.turtle(id).state.speed
+        } else {
+            Speed::instant()
+        }
+    }
+
+    /// Returns how long an animation of the given turtle should take, taking exactly the given
+    /// duration (if any) instead of using the speed of the turtle
+    ///
+    /// Animations are always instant if the drawing is not animated, even with a duration.
+    pub fn animation_timing(&self, id: TurtleId, duration: Option<Duration>) -> Timing {
+        match duration {
+            Some(duration) if self.drawing.is_animated && self.drawing.tracer == 1 => Timing::Duration(duration),
+            _ => Timing::Speed(self.animation_speed(id)),
+        }
+    }
+
+    /// Returns true if the given turtle exists
+    pub fn has_turtle(&self, id: TurtleId) -> bool {
+        let TurtleId(index) = id;
+        index < self.turtles.len()
+    }
+
     /// Returns a mutable handle to the given turtle
     pub fn turtle_mut(&mut self, id: TurtleId) -> &mut TurtleDrawings {
         let TurtleId(index) = id;
@@ -69,7 +329,7 @@ impl App {
     }
 
     /// Returns an iterator over all of the turtles
-    #[cfg_attr(feature = "test", allow(dead_code))] // Used in renderer, but not for tests
+    #[cfg_attr(any(feature = "test", feature = "headless"), allow(dead_code))] // Used in renderer, but not for tests
     pub fn turtles(&self) -> impl Iterator<Item=(TurtleId, &TurtleDrawings)> {
         (0..).zip(self.turtles.iter()).map(|(id, turtle)| (TurtleId(id), turtle))
     }
@@ -78,6 +338,83 @@ impl App {
     pub fn turtles_mut(&mut self) -> impl Iterator<Item=(TurtleId, &mut TurtleDrawings)> {
         (0..).zip(self.turtles.iter_mut()).map(|(id, turtle)| (TurtleId(id), turtle))
     }
+
+    /// Returns a `StampId` that has never been returned before
+    pub fn next_stamp_id(&mut self) -> StampId {
+        let id = StampId(self.stamps_created);
+        self.stamps_created += 1;
+        id
+    }
+
+    /// Returns a `LayerId` for a new layer that has never been returned before
+    pub fn next_layer_id(&mut self) -> LayerId {
+        self.layers_added += 1;
+        LayerId(self.layers_added)
+    }
+
+    /// Returns true if the given layer exists
+    pub fn has_layer(&self, id: LayerId) -> bool {
+        let LayerId(index) = id;
+        index <= self.layers_added
+    }
+
+    /// Adds a new image to the application state, returning its `ImageId`
+    pub fn add_image(&mut self, image: ImageData) -> ImageId {
+        let id = ImageId(self.images.len());
+        self.images.push(Arc::new(image));
+        id
+    }
+
+    /// Adds the image of a character in a font, returning the `ImageId` it was given the first
+    /// time it was added if it was already added before
+    pub fn add_glyph_image(&mut self, key: GlyphImageKey, image: ImageData) -> ImageId {
+        if let Some(&id) = self.glyph_images.get(&key) {
+            return id;
+        }
+
+        let id = self.add_image(image);
+        self.glyph_images.insert(key, id);
+        id
+    }
+
+    /// Returns the given image
+    pub fn image(&self, id: ImageId) -> &Arc<ImageData> {
+        let ImageId(index) = id;
+        &self.images[index]
+    }
+
+    /// Returns true if the given image exists
+    pub fn has_image(&self, id: ImageId) -> bool {
+        let ImageId(index) = id;
+        index < self.images.len()
+    }
+
+    /// Returns the background image of the drawing and how it is arranged, if there is one
+    pub fn background_image(&self) -> Option<(&ImageData, BackgroundMode)> {
+        let background_image = self.drawing.background_image?;
+        Some((self.image(background_image.image.id), background_image.mode))
+    }
+
+    /// Returns every image, indexed by `ImageId`
+    #[cfg_attr(any(feature = "test", feature = "headless"), allow(dead_code))] // Used in renderer, but not for tests
+    pub fn images(&self) -> &[Arc<ImageData>] {
+        &self.images
+    }
+
+    /// Sets the pen thickness of every turtle based on the given touch pressure (from 0.0 to 1.0)
+    /// if the drawing maps pressure to pen width
+    #[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))] // Only used with a window
+    pub fn apply_touch_pressure(&mut self, pressure: f64) {
+        let max_width = match self.drawing.pressure_pen_width {
+            Some(max_width) => max_width,
+            None => return,
+        };
+
+        let thickness = max_width * pressure.clamp(0.0, 1.0);
+        for turtle in &mut self.turtles {
+            turtle.state.pen.thickness = thickness;
+        }
+    }
 }
 
 // Using `RwLock` so that requests that only need to read from the state can run concurrently with
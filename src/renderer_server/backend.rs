@@ -1,14 +1,105 @@
+use std::future::Future;
+use std::net::SocketAddr;
+
+use futures_util::future::Either;
+
+use crate::ipc_protocol::{ClientSender, ClientReceiver, ConnectionError, request_tcp_share};
+
+use super::start::ProcessOptions;
+
 cfg_if::cfg_if! {
-    if #[cfg(any(feature = "test", test))] {
+    // There are no windows in a web browser, so the drawing is shown in the web page instead
+    if #[cfg(all(feature = "wasm", target_arch = "wasm32"))] {
         mod test;
-        pub use test::*;
+        mod wasm;
+        use self::wasm as native;
+        pub use self::test::run_main;
+
+    // The test backend never opens a window, so it is also used for headless programs
+    } else if #[cfg(any(feature = "test", feature = "headless", test))] {
+        mod test;
+        use self::test as native;
+        pub use self::test::run_main;
+
+    } else if #[cfg(feature = "single-process")] {
+        // The window always runs in this process, so the executable is never run again
+        mod multithreaded;
+        mod single_process;
+        use single_process as native;
 
     } else if #[cfg(target_os = "macos")] {
         mod multiprocessed;
-        pub use multiprocessed::*;
+        use multiprocessed as native;
+
+    } else if #[cfg(unix)] {
+        // The first window runs in a thread and any others run in separate processes
+        mod multiprocessed;
+        mod multithreaded;
+        mod mixed;
+        use mixed as native;
 
     } else {
         mod multithreaded;
-        pub use multithreaded::*;
+        use multithreaded as native;
+    }
+}
+
+// Custom backends are available on every platform, with or without windows
+mod custom;
+
+pub use custom::{RendererBackend, Frame, set_renderer_backend};
+
+/// Spawns the task, thread, or process responsible for handling and responding to client requests
+#[derive(Debug)]
+pub enum RendererServer {
+    Native(native::RendererServer),
+    Custom(custom::RendererServer),
+}
+
+impl RendererServer {
+    /// Runs any initialization logic required at the beginning of the program
+    pub fn start() {
+        native::RendererServer::start();
+    }
+
+    /// Sets the options used to spawn every renderer process after this is called
+    pub fn set_process_options(options: ProcessOptions) {
+        native::RendererServer::set_process_options(options);
+    }
+
+    /// Spawns the backend and returns the struct that will be used to interface with it.
+    ///
+    /// A custom backend is used if one has been registered with `set_renderer_backend()`. If any
+    /// TCP addresses are given, the server also accepts connections from other machines at the
+    /// first of those addresses that can be used (see `serve_tcp()`).
+    pub async fn spawn(
+        tcp_addrs: Option<Vec<SocketAddr>>,
+    ) -> Result<(Self, ClientSender, ClientReceiver), ConnectionError> {
+        let (server, sender, receiver) = match custom::backend_factory() {
+            Some(create_backend) => {
+                let (server, sender, receiver) = custom::RendererServer::spawn(create_backend).await?;
+                (RendererServer::Custom(server), sender, receiver)
+            },
+
+            None => {
+                let (server, sender, receiver) = native::RendererServer::spawn().await?;
+                (RendererServer::Native(server), sender, receiver)
+            },
+        };
+
+        if let Some(addrs) = tcp_addrs {
+            request_tcp_share(&sender, &receiver, addrs).await?;
+        }
+
+        Ok((server, sender, receiver))
+    }
+
+    /// Waits for the renderer to exit and returns a description of what went wrong if it was a
+    /// process that exited unsuccessfully
+    pub fn crash_report(&self) -> impl Future<Output=Option<String>> + Send + 'static {
+        match self {
+            RendererServer::Native(server) => Either::Left(server.crash_report()),
+            RendererServer::Custom(server) => Either::Right(server.crash_report()),
+        }
     }
 }
@@ -0,0 +1,431 @@
+use std::thread;
+use std::sync::{Arc, mpsc as std_mpsc};
+use std::future::{self, Future};
+use std::time::Duration;
+
+use parking_lot::{Mutex, const_mutex};
+use tokio::sync::{mpsc, oneshot};
+use futures_util::future::{FutureExt, RemoteHandle};
+
+use crate::{Color, Rect, Size, Event, Vertex, Index};
+use crate::ipc_protocol::{
+    ClientSender,
+    ClientReceiver,
+    ConnectionError,
+    connect_server,
+    connect_local_client,
+};
+
+use super::super::{
+    serve,
+    WindowChannels,
+    app::SharedApp,
+    renderer::{mesh, display_list::SharedDisplayList},
+    event_loop_notifier::{EventLoopNotifier, MainThreadAction},
+};
+
+/// How often a custom backend is checked for events when the drawing is not changing
+const POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Creates the backend used for each new drawing
+type BackendFactory = dyn Fn() -> Box<dyn RendererBackend> + Send + Sync;
+
+/// The function registered with `set_renderer_backend()`, if any
+static BACKEND_FACTORY: Mutex<Option<Arc<BackendFactory>>> = const_mutex(None);
+
+/// Everything that a [`RendererBackend`] needs to show the drawing
+///
+/// Like [`Drawing::export_mesh()`], the drawing is given as a mesh of triangles. Every three
+/// indices form one triangle, and the triangles should be drawn in order on top of the background
+/// color. The turtles themselves and any images are not included.
+///
+/// [`RendererBackend`]: trait.RendererBackend.html
+/// [`Drawing::export_mesh()`]: struct.Drawing.html#method.export_mesh
+#[derive(Debug, Clone, Copy)]
+pub struct Frame<'a> {
+    /// The size of the drawing in logical pixels
+    pub size: Size,
+    /// The background color of the drawing
+    pub background: Color,
+    /// The area of the drawing that would be visible in the window, in the same coordinate
+    /// system as the vertices
+    pub visible_area: Rect,
+    /// The vertices of every triangle in the drawing
+    pub vertices: &'a [Vertex],
+    /// Indices into `vertices`, where every three indices form one triangle
+    pub indices: &'a [Index],
+}
+
+/// A custom way to show drawings, used instead of opening a window
+///
+/// Implement this trait to show drawings with another graphics library or game engine (e.g. SDL
+/// or Bevy), then register it with [`set_renderer_backend()`] before creating any drawings. Each
+/// drawing gets its own backend, which runs on a thread of its own. Only
+/// [`present()`](#tymethod.present) is required. Every other method has a default that does
+/// nothing.
+///
+/// ```rust,no_run
+/// use turtle::{Drawing, Frame, RendererBackend};
+///
+/// struct PrintBackend;
+///
+/// impl RendererBackend for PrintBackend {
+///     fn present(&mut self, frame: &Frame) {
+///         println!("{} triangles", frame.indices.len() / 3);
+///     }
+/// }
+///
+/// turtle::set_renderer_backend(|| PrintBackend);
+///
+/// let mut drawing = Drawing::new();
+/// let mut turtle = drawing.add_turtle();
+/// turtle.forward(100.0);
+/// ```
+///
+/// [`set_renderer_backend()`]: fn.set_renderer_backend.html
+pub trait RendererBackend: 'static {
+    /// Shows the given frame
+    ///
+    /// This is called whenever the drawing changes, following the
+    /// [tracer](struct.Drawing.html#method.set_tracer) of the drawing.
+    fn present(&mut self, frame: &Frame<'_>);
+
+    /// Changes the title shown for the drawing
+    ///
+    /// This is called once when the backend starts and again whenever the title changes.
+    fn set_title(&mut self, _title: &str) {}
+
+    /// Changes the size of the drawing (in logical pixels)
+    ///
+    /// This is called once when the backend starts and again whenever the size changes.
+    fn set_size(&mut self, _size: Size) {}
+
+    /// Returns every event that happened since the last time this was called
+    ///
+    /// Each event is sent to the program through
+    /// [`Drawing::poll_event()`](struct.Drawing.html#method.poll_event).
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    fn poll_events(&mut self) -> Vec<Event> {
+        Vec::new()
+    }
+
+    /// Returns false once the drawing should stop being shown, e.g. because its window has been
+    /// closed
+    ///
+    /// The program is disconnected from the drawing once this returns false, just like when a
+    /// window is closed.
+    fn is_open(&mut self) -> bool {
+        true
+    }
+
+    /// Stops showing the drawing, e.g. because the disconnect behavior of the drawing closes it
+    fn close(&mut self) {}
+}
+
+/// Uses the given function to create the backend of every drawing created after this is called
+///
+/// The function is called once for each drawing, on the thread that will run its backend. Since
+/// each backend stays on its own thread, the backend itself does not need to be `Send`. See
+/// [`RendererBackend`] for an example.
+///
+/// [`RendererBackend`]: trait.RendererBackend.html
+pub fn set_renderer_backend<B, F>(create_backend: F)
+    where B: RendererBackend,
+          F: Fn() -> B + Send + Sync + 'static,
+{
+    let factory: Arc<BackendFactory> = Arc::new(move || Box::new(create_backend()));
+    *BACKEND_FACTORY.lock() = Some(factory);
+}
+
+/// Returns the function registered to create custom backends, if any
+pub(super) fn backend_factory() -> Option<Arc<BackendFactory>> {
+    BACKEND_FACTORY.lock().clone()
+}
+
+/// Spawns the task responsible for handling and responding to client requests, along with a
+/// thread that runs a custom backend
+#[derive(Debug)]
+pub struct RendererServer {
+    /// A remote handle drops its running task when it is dropped, which also ends the thread
+    /// running the backend
+    #[allow(dead_code)] // Only held so that the task is dropped along with this struct
+    task_handle: RemoteHandle<()>,
+}
+
+impl RendererServer {
+    /// Spawns the server in a new task and returns the struct that will be used to interface
+    /// with it.
+    pub async fn spawn(
+        create_backend: Arc<BackendFactory>,
+    ) -> Result<(Self, ClientSender, ClientReceiver), ConnectionError> {
+        let (server_name_sender, server_name_receiver) = oneshot::channel();
+        let (child, task_handle) = async move {
+            let server_name = server_name_receiver.await
+                .expect("bug: unable to receive server name");
+            run_main(create_backend, server_name).await;
+        }.remote_handle();
+
+        tokio::spawn(child);
+
+        let (conn_sender, conn_receiver) = connect_local_client(move |name| async {
+            server_name_sender.send(name)
+                .expect("bug: unable to send server name to renderer server");
+            Ok(())
+        }).await?;
+
+        Ok((Self {task_handle}, conn_sender, conn_receiver))
+    }
+
+    /// Always returns `None` because a panic in the backend only ends the thread running it
+    pub fn crash_report(&self) -> impl Future<Output=Option<String>> + Send + 'static {
+        future::ready(None)
+    }
+}
+
+async fn run_main(create_backend: Arc<BackendFactory>, server_name: String) {
+    let app = SharedApp::default();
+    let display_list = SharedDisplayList::default();
+
+    // The actions that would normally be performed by the window go to the backend thread instead
+    let (actions_sender, actions) = std_mpsc::channel();
+    let event_loop_notifier = EventLoopNotifier::with_backend(actions_sender);
+    // A channel for transferring events
+    let (events_sender, events_receiver) = mpsc::unbounded_channel();
+    // A channel for the keyboard shortcuts of the drawing, which are only supported in windows
+    let (_shortcuts_sender, shortcuts) = mpsc::unbounded_channel();
+    // The server shuts down once the backend thread drops this
+    let (server_shutdown, server_shutdown_receiver) = mpsc::channel(1);
+
+    let (conn_sender, conn_receiver) = connect_server(server_name)
+        .expect("unable to establish turtle server connection");
+
+    let thread_app = app.clone();
+    let thread_display_list = display_list.clone();
+    thread::Builder::new()
+        .name("turtle renderer backend".to_string())
+        .spawn(move || {
+            let backend = create_backend();
+            run_backend(backend, thread_app, thread_display_list, actions, events_sender);
+            drop(server_shutdown);
+        })
+        .expect("unable to spawn thread for renderer backend");
+
+    serve(
+        conn_sender,
+        conn_receiver,
+        app,
+        display_list,
+        event_loop_notifier,
+        WindowChannels {events: events_receiver, shortcuts, shutdown: server_shutdown_receiver},
+    ).await;
+}
+
+/// Runs the backend until it is closed or the server stops
+fn run_backend(
+    mut backend: Box<dyn RendererBackend>,
+    app: SharedApp,
+    display_list: SharedDisplayList,
+    actions: std_mpsc::Receiver<MainThreadAction>,
+    #[cfg_attr(not(feature = "unstable"), allow(unused_variables))]
+    events_sender: mpsc::UnboundedSender<Event>,
+) {
+    {
+        let app = app.read();
+        let drawing = app.drawing();
+        backend.set_title(&drawing.title);
+        backend.set_size(Size {width: drawing.width, height: drawing.height});
+    }
+
+    let mut needs_redraw = true;
+    // The number of updates since the last frame was presented, used to apply the tracer
+    let mut skipped_updates = 0;
+
+    loop {
+        let action = match actions.recv_timeout(POLL_INTERVAL) {
+            Ok(action) => Some(action),
+            Err(std_mpsc::RecvTimeoutError::Timeout) => None,
+            // The server has stopped, so there is nothing left to show
+            Err(std_mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+
+        for action in action.into_iter().chain(actions.try_iter()) {
+            match action {
+                MainThreadAction::Redraw => {
+                    let tracer = app.read().drawing().tracer;
+                    skipped_updates += 1;
+                    // A tracer of zero only redraws when the drawing is flushed
+                    if tracer != 0 && skipped_updates >= tracer {
+                        skipped_updates = 0;
+                        needs_redraw = true;
+                    }
+                },
+
+                MainThreadAction::Flush => {
+                    skipped_updates = 0;
+                    needs_redraw = true;
+                },
+
+                MainThreadAction::SetTitle(title) => backend.set_title(&title),
+
+                MainThreadAction::SetSize(size) => {
+                    backend.set_size(size);
+                    needs_redraw = true;
+                },
+
+                MainThreadAction::Close => {
+                    backend.close();
+                    return;
+                },
+
+                // Icons and the maximized and fullscreen states only apply to windows
+                MainThreadAction::SetIcon(_) |
+                MainThreadAction::SetIsMaximized(_) |
+                MainThreadAction::SetIsFullscreen(_) => {},
+            }
+        }
+
+        #[cfg(feature = "unstable")]
+        for event in backend.poll_events() {
+            // Sending only fails if the server has stopped, which ends this loop anyway
+            events_sender.send(event).unwrap_or(());
+        }
+
+        if !backend.is_open() {
+            break;
+        }
+
+        if needs_redraw {
+            needs_redraw = false;
+            present(&mut *backend, &app, &display_list);
+        }
+    }
+}
+
+/// Tessellates the drawing and shows it with the given backend
+fn present(backend: &mut dyn RendererBackend, app: &SharedApp, display_list: &SharedDisplayList) {
+    let (size, background, visible_area) = {
+        let app = app.read();
+        let drawing = app.drawing();
+        let size = Size {width: drawing.width, height: drawing.height};
+        let visible_area = Rect::from_center(drawing.center, drawing.width as f64, drawing.height as f64);
+        (size, drawing.background, visible_area)
+    };
+    let (vertices, indices) = mesh::tessellate(&display_list.lock());
+
+    backend.present(&Frame {
+        size,
+        background,
+        visible_area,
+        vertices: &vertices,
+        indices: &indices,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Instant;
+    #[cfg(feature = "unstable")]
+    use std::mem;
+
+    #[cfg(feature = "unstable")]
+    use futures_util::StreamExt;
+
+    use crate::Drawing;
+
+    /// Held while a backend is registered, since tests that run at the same time would otherwise
+    /// create their drawings with each other's backends
+    static REGISTER_LOCK: Mutex<()> = const_mutex(());
+
+    /// Records the title of the drawing along with the number of triangles in each frame
+    struct RecordingBackend {
+        title: String,
+        frames: Arc<Mutex<Vec<(String, usize)>>>,
+    }
+
+    impl RendererBackend for RecordingBackend {
+        fn present(&mut self, frame: &Frame<'_>) {
+            self.frames.lock().push((self.title.clone(), frame.indices.len() / 3));
+        }
+
+        fn set_title(&mut self, title: &str) {
+            self.title = title.to_string();
+        }
+    }
+
+    /// Sends the events pushed into it to the drawing
+    #[cfg(feature = "unstable")]
+    struct EventBackend {
+        events: Arc<Mutex<Vec<Event>>>,
+    }
+
+    #[cfg(feature = "unstable")]
+    impl RendererBackend for EventBackend {
+        fn present(&mut self, _frame: &Frame<'_>) {}
+
+        fn poll_events(&mut self) -> Vec<Event> {
+            mem::take(&mut *self.events.lock())
+        }
+    }
+
+    #[test]
+    fn presents_with_custom_backend() {
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        let backend_frames = frames.clone();
+        let registered = REGISTER_LOCK.lock();
+        set_renderer_backend(move || RecordingBackend {
+            title: String::new(),
+            frames: backend_frames.clone(),
+        });
+
+        let mut drawing = Drawing::new();
+        // Other tests may create drawings with this backend while it is registered
+        *BACKEND_FACTORY.lock() = None;
+        drop(registered);
+
+        drawing.set_title("custom backend test");
+        let mut turtle = drawing.add_turtle();
+        turtle.forward(100.0);
+        drawing.flush();
+
+        let timeout = Instant::now() + Duration::from_secs(10);
+        let presented = || frames.lock().iter()
+            .any(|(title, triangles)| title == "custom backend test" && *triangles > 0);
+        while !presented() {
+            assert!(Instant::now() < timeout, "the drawing was never presented by the custom backend");
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "unstable")]
+    fn keeps_next_event_after_dropping_stream() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let backend_events = events.clone();
+        let registered = REGISTER_LOCK.lock();
+        set_renderer_backend(move || EventBackend {events: backend_events.clone()});
+
+        let mut drawing = Drawing::new();
+        *BACKEND_FACTORY.lock() = None;
+        drop(registered);
+
+        // Start waiting for an event, then stop listening before one arrives
+        let mut stream = drawing.event_stream();
+        assert_eq!(stream.next().now_or_never(), None);
+        drop(stream);
+
+        events.lock().push(Event::WindowFocused(true));
+
+        let timeout = Instant::now() + Duration::from_secs(10);
+        let event = loop {
+            if let Some(event) = drawing.poll_event() {
+                break event;
+            }
+            assert!(Instant::now() < timeout, "the event was lost after the stream was dropped");
+            thread::sleep(POLL_INTERVAL);
+        };
+        assert_eq!(event, Event::WindowFocused(true));
+    }
+}
@@ -0,0 +1,59 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use futures_util::future::Either;
+
+use crate::ipc_protocol::{ClientSender, ClientReceiver, ConnectionError};
+
+use super::{multiprocessed, multithreaded};
+use super::super::start::ProcessOptions;
+
+/// Set once a window has been opened in a thread of this process
+///
+/// The window event loop uses a single X11 connection for the entire process, so two event loops
+/// running at the same time would receive each other's events. Only the first window runs in a
+/// thread. Every window opened after that runs in its own process.
+static WINDOW_THREAD_USED: AtomicBool = AtomicBool::new(false);
+
+/// Spawns the task or process responsible for handling and responding to client requests
+#[derive(Debug)]
+pub enum RendererServer {
+    Thread(multithreaded::RendererServer),
+    Process(multiprocessed::RendererServer),
+}
+
+impl RendererServer {
+    /// Runs any initialization logic required at the beginning of the program
+    pub fn start() {
+        multithreaded::RendererServer::start();
+        multiprocessed::RendererServer::start();
+    }
+
+    /// Sets the options used to spawn every renderer process after this is called
+    pub fn set_process_options(options: ProcessOptions) {
+        multithreaded::RendererServer::set_process_options(options.clone());
+        multiprocessed::RendererServer::set_process_options(options);
+    }
+
+    /// Spawns the backend in a new task or process and returns the struct that will be used to
+    /// interface with it.
+    pub async fn spawn() -> Result<(Self, ClientSender, ClientReceiver), ConnectionError> {
+        if !WINDOW_THREAD_USED.swap(true, Ordering::SeqCst) {
+            let (server, sender, receiver) = multithreaded::RendererServer::spawn().await?;
+            Ok((RendererServer::Thread(server), sender, receiver))
+
+        } else {
+            let (server, sender, receiver) = multiprocessed::RendererServer::spawn().await?;
+            Ok((RendererServer::Process(server), sender, receiver))
+        }
+    }
+
+    /// Waits for the renderer to exit and returns a description of what went wrong if it was a
+    /// process that exited unsuccessfully
+    pub fn crash_report(&self) -> impl Future<Output=Option<String>> + Send + 'static {
+        match self {
+            RendererServer::Thread(server) => Either::Left(server.crash_report()),
+            RendererServer::Process(server) => Either::Right(server.crash_report()),
+        }
+    }
+}
@@ -1,11 +1,19 @@
 use std::io;
 use std::env;
-use std::process::{self, Stdio, ExitStatus};
+use std::fs;
+use std::future::Future;
+use std::collections::VecDeque;
+use std::os::unix::process::CommandExt;
+use std::process::{self, Stdio, ExitStatus, Command as StdCommand};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 
 use tokio::{
     runtime::{Runtime, Handle},
-    io::AsyncWriteExt,
-    process::{Command, ChildStdin},
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::{Command, ChildStdin, ChildStderr},
+    sync::watch,
 };
 use futures_util::future::{FutureExt, RemoteHandle};
 
@@ -19,11 +27,29 @@ use crate::ipc_protocol::{
     connect_client,
 };
 
-use super::super::main::run_main;
+use super::super::{main::run_main, start::ProcessOptions};
 
 /// The environment variable that is set to indicate that the current process is a server process
 const RENDERER_PROCESS_ENV_VAR: &str = "RUN_TURTLE_CANVAS";
 
+/// If this environment variable is set, a crash report is written to the file at the path it
+/// contains whenever the renderer process exits unsuccessfully
+const CRASH_REPORT_ENV_VAR: &str = "TURTLE_CRASH_REPORT";
+
+/// The number of lines at the end of the renderer's stderr that are included in crash reports
+const CRASH_REPORT_LINES: usize = 100;
+
+/// The options used to spawn every renderer process
+static PROCESS_OPTIONS: Lazy<Mutex<ProcessOptions>> = Lazy::new(Default::default);
+
+/// The state of the renderer process, as seen by the client
+#[derive(Debug, Clone)]
+enum RendererStatus {
+    Running,
+    /// The process has exited, with a crash report if it was unsuccessful
+    Exited(Option<String>),
+}
+
 /// Spawns the task/process responsible for handling and responding to client requests
 #[derive(Debug)]
 pub struct RendererServer {
@@ -39,6 +65,8 @@ pub struct RendererServer {
     /// dropped. (unlike a `JoinHandle` which will detach instead.) This is important to make sure
     /// the window closes when the thread holding this struct panics.
     task_handle: Option<RemoteHandle<io::Result<ExitStatus>>>,
+    /// Updated once the process has exited
+    status: watch::Receiver<RendererStatus>,
 }
 
 impl RendererServer {
@@ -64,24 +92,67 @@ impl RendererServer {
         }
     }
 
+    /// Sets the options used to spawn every renderer process after this is called
+    pub fn set_process_options(options: ProcessOptions) {
+        *PROCESS_OPTIONS.lock() = options;
+    }
+
     /// Spawns the backend in a new task and returns the struct that will be used to
     /// interface with it.
     pub async fn spawn() -> Result<(Self, ClientSender, ClientReceiver), ConnectionError> {
-        let current_exe = env::current_exe()?;
+        let options = PROCESS_OPTIONS.lock().clone();
+        let executable = match options.executable {
+            Some(executable) => executable,
+            None => env::current_exe()?,
+        };
+
+        // tokio's Command does not support setting argv[0], so the std Command is configured first
+        let mut command = StdCommand::new(executable);
+        command.args(options.args);
+        command.envs(options.envs);
+        if let Some(current_dir) = options.current_dir {
+            command.current_dir(current_dir);
+        }
+        if let Some(process_title) = options.process_title {
+            command.arg0(process_title);
+        }
 
         // The new process is the same executable as this process but with a special environment
         // variable passed in
-        let mut child = Command::new(current_exe)
+        let mut child = Command::from(command)
             .env(RENDERER_PROCESS_ENV_VAR, "true")
             // Pipe input so we can communicate with the spawned process
-            //
-            // stdout/stderr will be inherited from the current process
             .stdin(Stdio::piped())
+            // Pipe stderr so that it can be included in crash reports. It is still forwarded to
+            // the stderr of this process as it is received.
+            //
+            // stdout will be inherited from the current process
+            .stderr(Stdio::piped())
             .kill_on_drop(true)
             .spawn()?;
 
         let child_stdin = child.stdin.take()
             .expect("bug: renderer process was not spawned with a handle to stdin");
+        let child_stderr = child.stderr.take()
+            .expect("bug: renderer process was not spawned with a handle to stderr");
+
+        let (status_sender, status) = watch::channel(RendererStatus::Running);
+        let child = async move {
+            let output = forward_stderr(child_stderr).await;
+            let exit_status = child.await?;
+
+            let crash_report = if exit_status.success() {
+                None
+            } else {
+                let report = crash_report(exit_status, &output);
+                save_crash_report(&report);
+                Some(report)
+            };
+            // Ignoring the error since it just means that nothing is waiting for the report
+            status_sender.broadcast(RendererStatus::Exited(crash_report)).unwrap_or(());
+
+            Ok(exit_status)
+        };
 
         // Spawn a separate task for the child process so this task can continue to make progress
         // while that runs. The remote handle will drop that future when it is dropped.
@@ -97,7 +168,75 @@ impl RendererServer {
             send_ipc_oneshot_name(child_stdin, name)
         }).await?;
 
-        Ok((Self {runtime_handle, task_handle}, conn_sender, conn_receiver))
+        Ok((Self {runtime_handle, task_handle, status}, conn_sender, conn_receiver))
+    }
+
+    /// Waits for the renderer process to exit and returns a description of what went wrong if it
+    /// exited unsuccessfully
+    pub fn crash_report(&self) -> impl Future<Output=Option<String>> + Send + 'static {
+        let mut status = self.status.clone();
+        async move {
+            while let Some(status) = status.recv().await {
+                if let RendererStatus::Exited(crash_report) = status {
+                    return crash_report;
+                }
+            }
+
+            // The process was killed before it could exit on its own
+            None
+        }
+    }
+}
+
+/// Copies each line of the renderer's stderr to the stderr of this process until the renderer
+/// closes it, returning the last lines that were received
+async fn forward_stderr(child_stderr: ChildStderr) -> VecDeque<String> {
+    let mut lines = BufReader::new(child_stderr).lines();
+    let mut output = VecDeque::with_capacity(CRASH_REPORT_LINES);
+
+    // Reading stops at the first error since the output is only needed for diagnostics
+    while let Ok(Some(line)) = lines.next_line().await {
+        eprintln!("{}", line);
+
+        if output.len() == CRASH_REPORT_LINES {
+            output.pop_front();
+        }
+        output.push_back(line);
+    }
+
+    output
+}
+
+/// Describes why the renderer process exited using the given status and the last lines of output
+/// that it wrote to stderr
+fn crash_report(exit_status: ExitStatus, output: &VecDeque<String>) -> String {
+    let mut report = format!("The turtle window process exited unexpectedly ({})\n", exit_status);
+
+    if output.is_empty() {
+        report.push_str("\nThe window process did not write anything to stderr.\n");
+    } else {
+        report.push_str("\nLast output of the window process:\n");
+        for line in output {
+            report.push_str(line);
+            report.push('\n');
+        }
+    }
+
+    report
+}
+
+/// Writes the given crash report to the file requested by the user, if any
+fn save_crash_report(report: &str) {
+    let path = match env::var_os(CRASH_REPORT_ENV_VAR) {
+        Some(path) => path,
+        None => return,
+    };
+
+    match fs::write(&path, report) {
+        Ok(()) => eprintln!("turtle: crash report saved to '{}'", path.to_string_lossy()),
+        // Not worth panicking over since the process has already crashed and its output was
+        // already forwarded to stderr
+        Err(err) => eprintln!("turtle: unable to save crash report to '{}': {}", path.to_string_lossy(), err),
     }
 }
 
@@ -1,4 +1,5 @@
 use std::panic;
+use std::future::{self, Future};
 
 use tokio::{
     task,
@@ -12,10 +13,10 @@ use crate::ipc_protocol::{
     ClientReceiver,
     ConnectionError,
     connect_server,
-    connect_client,
+    connect_local_client,
 };
 
-use super::super::main::run_main;
+use super::super::{main::run_main, start::ProcessOptions};
 
 /// Spawns the task responsible for handling and responding to client requests
 #[derive(Debug)]
@@ -38,6 +39,9 @@ impl RendererServer {
     /// Runs any initialization logic required at the beginning of the program
     pub fn start() {}
 
+    /// Ignores the given options since no process is spawned by this backend
+    pub fn set_process_options(_options: ProcessOptions) {}
+
     /// Spawns the backend in a new task and returns the struct that will be used to
     /// interface with it.
     pub async fn spawn() -> Result<(Self, ClientSender, ClientReceiver), ConnectionError> {
@@ -60,7 +64,7 @@ impl RendererServer {
         let runtime_handle = Handle::current();
         let task_handle = Some(task_handle);
 
-        let (conn_sender, conn_receiver) = connect_client(move |name| async {
+        let (conn_sender, conn_receiver) = connect_local_client(move |name| async {
             server_name_sender.send(name)
                 .expect("bug: unable to send server name to renderer server");
             Ok(())
@@ -68,6 +72,12 @@ impl RendererServer {
 
         Ok((Self {runtime_handle, task_handle}, conn_sender, conn_receiver))
     }
+
+    /// Always returns `None` because the window runs in this process, so any panic that crashes
+    /// it has already been reported and is propagated when this struct is dropped
+    pub fn crash_report(&self) -> impl Future<Output=Option<String>> + Send + 'static {
+        future::ready(None)
+    }
 }
 
 impl Drop for RendererServer {
@@ -0,0 +1,61 @@
+use std::future::Future;
+#[cfg(all(unix, not(target_os = "macos")))]
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::ipc_protocol::{ClientSender, ClientReceiver, ConnectionError};
+
+use super::multithreaded;
+use super::super::start::ProcessOptions;
+
+/// Set once a window has been opened in a thread of this process
+///
+/// The window event loop uses a single X11 connection for the entire process, so two event loops
+/// running at the same time would receive each other's events. Without another process to run it
+/// in, a second window cannot be opened.
+#[cfg(all(unix, not(target_os = "macos")))]
+static WINDOW_THREAD_USED: AtomicBool = AtomicBool::new(false);
+
+/// Spawns the task responsible for handling and responding to client requests, without ever
+/// starting another process (the `single-process` feature)
+///
+/// The window runs in a thread of this process and the connection to it uses channels within
+/// this process. Use `turtle::run()` to run the window on the main thread instead.
+#[derive(Debug)]
+pub struct RendererServer {
+    server: multithreaded::RendererServer,
+}
+
+impl RendererServer {
+    /// Runs any initialization logic required at the beginning of the program
+    pub fn start() {
+        multithreaded::RendererServer::start();
+    }
+
+    /// Ignores the given options since no process is spawned by this backend
+    pub fn set_process_options(options: ProcessOptions) {
+        multithreaded::RendererServer::set_process_options(options);
+    }
+
+    /// Spawns the backend in a new task and returns the struct that will be used to
+    /// interface with it.
+    pub async fn spawn() -> Result<(Self, ClientSender, ClientReceiver), ConnectionError> {
+        // The window can only be run on the main thread, which is running the program
+        #[cfg(target_os = "macos")]
+        panic!("windows must be opened with `turtle::run()` on macOS when the `single-process` feature is enabled");
+
+        #[cfg(all(unix, not(target_os = "macos")))]
+        if WINDOW_THREAD_USED.swap(true, Ordering::SeqCst) {
+            panic!("only one window can be opened on this platform when the `single-process` feature is enabled");
+        }
+
+        #[allow(unreachable_code)]
+        let (server, sender, receiver) = multithreaded::RendererServer::spawn().await?;
+        Ok((Self {server}, sender, receiver))
+    }
+
+    /// Always returns `None` because the window runs in this process, so any panic that crashes
+    /// it has already been reported and is propagated when this struct is dropped
+    pub fn crash_report(&self) -> impl Future<Output=Option<String>> + Send + 'static {
+        self.server.crash_report()
+    }
+}
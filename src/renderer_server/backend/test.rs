@@ -1,3 +1,5 @@
+use std::future::{self, Future};
+
 use tokio::sync::{mpsc, oneshot};
 use futures_util::future::{FutureExt, RemoteHandle};
 
@@ -6,14 +8,16 @@ use crate::ipc_protocol::{
     ClientReceiver,
     ConnectionError,
     connect_server,
-    connect_client,
+    connect_local_client,
 };
 
 use super::super::{
     serve,
+    WindowChannels,
     app::SharedApp,
     renderer::display_list::SharedDisplayList,
-    test_event_loop_notifier::EventLoopNotifier
+    start::ProcessOptions,
+    event_loop_notifier::EventLoopNotifier,
 };
 
 /// Spawns the task responsible for handling and responding to client requests
@@ -21,28 +25,43 @@ use super::super::{
 pub struct RendererServer {
     /// A remote handle is like a `JoinHandle` that drops its running task when it is dropped. A
     /// normal `JoinHandle` would detach the task, and that is not desirable for tests.
-    task_handle: RemoteHandle<()>,
+    #[allow(dead_code)] // Only held so that the task is dropped along with this struct
+    pub(super) task_handle: RemoteHandle<()>,
 }
 
 impl RendererServer {
     /// Runs any initialization logic required at the beginning of the program
     pub fn start() {}
 
+    /// Ignores the given options since no process is spawned by this backend
+    pub fn set_process_options(_options: ProcessOptions) {}
+
     /// Spawns the backend in a new task and returns the struct that will be used to
     /// interface with it.
+    #[cfg_attr(all(feature = "wasm", target_arch = "wasm32"), allow(dead_code))] // The wasm backend runs its own server
     pub async fn spawn() -> Result<(Self, ClientSender, ClientReceiver), ConnectionError> {
+        Self::spawn_with(run_main).await
+    }
+
+    /// Spawns a new task that runs the given server with the name that it should connect to
+    pub(super) async fn spawn_with<F, R>(
+        run_server: F,
+    ) -> Result<(Self, ClientSender, ClientReceiver), ConnectionError>
+        where F: FnOnce(String) -> R + Send + 'static,
+              R: Future<Output=()> + Send + 'static,
+    {
         let (server_name_sender, server_name_receiver) = oneshot::channel();
         // Spawn a separate task for the server so this task can continue to make progress
         // while that runs. The remote handle will drop that future when it is dropped.
         let (child, task_handle) = async move {
             let server_name = server_name_receiver.await
                 .expect("bug: unable to receive server name");
-            run_main(server_name).await;
+            run_server(server_name).await;
         }.remote_handle();
 
         tokio::spawn(child);
 
-        let (conn_sender, conn_receiver) = connect_client(move |name| async {
+        let (conn_sender, conn_receiver) = connect_local_client(move |name| async {
             server_name_sender.send(name)
                 .expect("bug: unable to send server name to test renderer server");
             Ok(())
@@ -50,6 +69,11 @@ impl RendererServer {
 
         Ok((Self {task_handle}, conn_sender, conn_receiver))
     }
+
+    /// Always returns `None` because the test server runs as a task in this process
+    pub fn crash_report(&self) -> impl Future<Output=Option<String>> + Send + 'static {
+        future::ready(None)
+    }
 }
 
 pub async fn run_main(server_name: String) {
@@ -62,10 +86,17 @@ pub async fn run_main(server_name: String) {
     // as short as possible.
     let display_list = SharedDisplayList::default();
 
+    serve_without_window(server_name, app, display_list).await;
+}
+
+/// Serves requests until the client disconnects, without ever opening a window
+pub async fn serve_without_window(server_name: String, app: SharedApp, display_list: SharedDisplayList) {
     // Create the proxy that will be given to the thread managing IPC
     let event_loop_notifier = EventLoopNotifier::new();
     // A channel for transferring events
     let (_events_sender, events_receiver) = mpsc::unbounded_channel();
+    // A channel for the keyboard shortcuts of the drawing, which are never pressed
+    let (_shortcuts_sender, shortcuts) = mpsc::unbounded_channel();
     // A channel for notifying on shutdown
     let (_server_shutdown, server_shutdown_receiver) = mpsc::channel(1);
 
@@ -78,7 +109,6 @@ pub async fn run_main(server_name: String) {
         app,
         display_list,
         event_loop_notifier,
-        events_receiver,
-        server_shutdown_receiver,
+        WindowChannels {events: events_receiver, shortcuts, shutdown: server_shutdown_receiver},
     ).await;
 }
@@ -0,0 +1,124 @@
+use std::future::Future;
+use std::thread;
+
+use wasm_bindgen::{Clamped, JsCast};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageData};
+
+use crate::Rect;
+use crate::ipc_protocol::{ClientSender, ClientReceiver, ConnectionError};
+use crate::sync_runtime;
+
+use super::test::{self, serve_without_window};
+use super::super::{
+    app::SharedApp,
+    renderer::{raster, display_list::SharedDisplayList},
+    start::ProcessOptions,
+};
+
+/// The id of the `<canvas>` element that the drawing is shown in
+const CANVAS_ID: &str = "turtle";
+
+/// Spawns the task responsible for handling and responding to client requests, and shows the
+/// drawing in the web page once the program is done with it (the `wasm` feature)
+#[derive(Debug)]
+pub struct RendererServer {
+    /// The server task, which is run to completion when this struct is dropped
+    server: Option<test::RendererServer>,
+}
+
+impl RendererServer {
+    /// Runs any initialization logic required at the beginning of the program
+    pub fn start() {
+        test::RendererServer::start();
+    }
+
+    /// Ignores the given options since no process is spawned by this backend
+    pub fn set_process_options(options: ProcessOptions) {
+        test::RendererServer::set_process_options(options);
+    }
+
+    /// Spawns the backend in a new task and returns the struct that will be used to
+    /// interface with it.
+    pub async fn spawn() -> Result<(Self, ClientSender, ClientReceiver), ConnectionError> {
+        let (server, sender, receiver) = test::RendererServer::spawn_with(|server_name| async move {
+            let app = SharedApp::default();
+            let display_list = SharedDisplayList::default();
+            serve_without_window(server_name, app.clone(), display_list.clone()).await;
+
+            show_drawing(&app, &display_list);
+        }).await?;
+
+        Ok((Self {server: Some(server)}, sender, receiver))
+    }
+
+    /// Always returns `None` because the server runs as a task in this process
+    pub fn crash_report(&self) -> impl Future<Output=Option<String>> + Send + 'static {
+        // This unwrap is safe because the server is only taken when this struct is dropped
+        self.server.as_ref().unwrap().crash_report()
+    }
+}
+
+impl Drop for RendererServer {
+    fn drop(&mut self) {
+        // Nothing is shown for a program that panicked, just like a window that crashed
+        if thread::panicking() {
+            return;
+        }
+
+        // Tasks only run while the program is waiting for the runtime, so the server has to be
+        // driven until it notices that the client is gone and shows the drawing
+        //
+        // This unwrap is safe because no struct gets dropped twice
+        let server = self.server.take().unwrap();
+        sync_runtime::block_on(server.task_handle);
+    }
+}
+
+/// Draws the area of the drawing that would be visible in a window into the canvas
+fn show_drawing(app: &SharedApp, display_list: &SharedDisplayList) {
+    let app = app.read();
+    let drawing = app.drawing();
+    let area = Rect::from_center(drawing.center, drawing.width as f64, drawing.height as f64);
+
+    let display_list = display_list.lock();
+    let pixmap = raster::rasterize(&display_list, area, 1.0, drawing.background, app.background_image(), drawing.symmetry)
+        .expect("the drawing is too large to show in the web page");
+
+    let canvas = canvas();
+    canvas.set_width(pixmap.width());
+    canvas.set_height(pixmap.height());
+
+    let context = canvas.get_context("2d").ok().flatten()
+        .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok())
+        .expect("unable to draw into the canvas of the web page");
+    let pixels = pixmap.to_rgba8();
+    let image = ImageData::new_with_u8_clamped_array_and_sh(Clamped(&pixels), pixmap.width(), pixmap.height())
+        .expect("bug: the drawing should always fit in the image data");
+    context.put_image_data(&image, 0.0, 0.0)
+        .expect("unable to draw into the canvas of the web page");
+}
+
+/// Returns the canvas with the id `CANVAS_ID`, adding one to the end of the web page if there
+/// isn't one yet
+fn canvas() -> HtmlCanvasElement {
+    let document = web_sys::window().and_then(|window| window.document())
+        .expect("turtle programs compiled to wasm can only be run in a web page");
+
+    let element = match document.get_element_by_id(CANVAS_ID) {
+        Some(element) => element,
+        None => {
+            let element = document.create_element("canvas")
+                .expect("unable to create a canvas in the web page");
+            element.set_id(CANVAS_ID);
+            document.body()
+                .expect("the web page must have a body to show the drawing in")
+                .append_child(&element)
+                .expect("unable to add a canvas to the web page");
+            element
+        },
+    };
+
+    element.dyn_into().unwrap_or_else(|_| {
+        panic!("the element with the id \"{}\" in the web page must be a <canvas>", CANVAS_ID)
+    })
+}
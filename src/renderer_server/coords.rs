@@ -11,11 +11,332 @@
 //!   * origin is the top-left corner of the framebuffer
 //!   * x is left (-) to right (+)
 //!   * y is top (-) to bottom (+)
+//!
+//! Since the origin of the logical coordinate system is relative to the center of the
+//! framebuffer, resizing the window keeps the drawing centered by default. The `ResizeAnchor`
+//! selects whether this happens or whether the drawing should stay in place relative to the
+//! top-left corner of the window instead.
+//!
+//! user coordinates
+//!   * the coordinates used in the public API (turtle positions, headings, events, etc.)
+//!   * the same as logical coordinates unless configured otherwise by a `CoordinateSystem`
+//!   * origin and direction of the y axis are set by the `CoordinateSystem`
+//!   * if `WorldCoordinates` are set, they replace the `CoordinateSystem` and can also scale
+//!     each axis by a different amount
 
+#[cfg(not(target_arch = "wasm32"))]
 use glutin::dpi::PhysicalPosition;
 use pathfinder_geometry::vector::{Vector2F, vec2f};
+use serde::{Serialize, Deserialize};
 
 use crate::Point;
+use crate::ipc_protocol::RotationDirection;
+use crate::radians::{self, Radians};
+
+/// The number of millimeters in one inch
+pub(crate) const MM_PER_INCH: f64 = 25.4;
+
+/// Converts a length in millimeters to logical pixels at the given DPI (dots per inch)
+pub(crate) fn mm_to_px(mm: f64, dpi: f64) -> f64 {
+    mm / MM_PER_INCH * dpi
+}
+
+/// Converts a length in logical pixels to millimeters at the given DPI (dots per inch)
+pub(crate) fn px_to_mm(px: f64, dpi: f64) -> f64 {
+    px / dpi * MM_PER_INCH
+}
+
+/// Controls which part of the window the drawing stays fixed to when the window is resized
+///
+/// See [`Drawing::set_resize_anchor()`] for more information.
+///
+/// [`Drawing::set_resize_anchor()`]: struct.Drawing.html#method.set_resize_anchor
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResizeAnchor {
+    /// The center of the drawing stays at the center of the window, so the drawing remains
+    /// centered as the window grows or shrinks (default)
+    #[default]
+    Center,
+    /// The drawing stays in the same place relative to the top-left corner of the window, so
+    /// growing the window reveals more of the drawing to the right and bottom
+    TopLeft,
+}
+
+impl ResizeAnchor {
+    /// Computes the drawing `center` that keeps the drawing anchored correctly after the window
+    /// changes from `old_size` to `new_size` (both in logical pixels)
+    pub(crate) fn center_after_resize(
+        self,
+        center: Point,
+        old_size: (u32, u32),
+        new_size: (u32, u32),
+    ) -> Point {
+        match self {
+            ResizeAnchor::Center => center,
+            ResizeAnchor::TopLeft => {
+                // The center of the framebuffer moves right and down by half of the change in
+                // size, so moving the center by the same amount keeps everything else in place
+                let dx = (new_size.0 as f64 - old_size.0 as f64) / 2.0;
+                let dy = (new_size.1 as f64 - old_size.1 as f64) / 2.0;
+                Point {x: center.x + dx, y: center.y - dy}
+            },
+        }
+    }
+}
+
+/// The point that the coordinates of a drawing are measured from
+///
+/// See [`Drawing::set_coordinate_system()`] for more information.
+///
+/// [`Drawing::set_coordinate_system()`]: struct.Drawing.html#method.set_coordinate_system
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Origin {
+    /// The origin is at the center of the window (default)
+    #[default]
+    Center,
+    /// The origin is at the top-left corner of the window
+    TopLeft,
+    /// The origin is at the bottom-left corner of the window
+    BottomLeft,
+}
+
+/// The coordinate system used for positions and headings throughout the drawing
+///
+/// See [`Drawing::set_coordinate_system()`] for more information.
+///
+/// ```rust
+/// use turtle::{CoordinateSystem, Origin};
+///
+/// let coords = CoordinateSystem::default();
+/// assert_eq!(coords.origin, Origin::Center);
+/// assert!(coords.y_up);
+/// ```
+///
+/// [`Drawing::set_coordinate_system()`]: struct.Drawing.html#method.set_coordinate_system
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CoordinateSystem {
+    /// The point that all coordinates are measured from
+    pub origin: Origin,
+    /// If true, y increases towards the top of the window. Otherwise, y increases towards the
+    /// bottom of the window like in most screen coordinate systems.
+    pub y_up: bool,
+}
+
+impl Default for CoordinateSystem {
+    fn default() -> Self {
+        Self {
+            origin: Origin::Center,
+            y_up: true,
+        }
+    }
+}
+
+impl CoordinateSystem {
+    /// Returns the position of the origin in logical coordinates for a drawing of the given size
+    ///
+    /// The corners of the window are computed as if the drawing `center` was never moved, so
+    /// that panning the drawing moves the origin along with everything else.
+    fn origin_point(self, size: (u32, u32)) -> Point {
+        let half_width = size.0 as f64 / 2.0;
+        let half_height = size.1 as f64 / 2.0;
+
+        match self.origin {
+            Origin::Center => Point::origin(),
+            Origin::TopLeft => Point {x: -half_width, y: half_height},
+            Origin::BottomLeft => Point {x: -half_width, y: -half_height},
+        }
+    }
+
+    /// Converts a point in logical coordinates to user coordinates for a drawing of the given
+    /// size (in logical pixels)
+    pub(crate) fn to_user(self, point: Point, size: (u32, u32)) -> Point {
+        let offset = point - self.origin_point(size);
+        Point {
+            x: offset.x,
+            y: if self.y_up { offset.y } else { -offset.y },
+        }
+    }
+
+    /// Converts a point in user coordinates to logical coordinates for a drawing of the given
+    /// size (in logical pixels)
+    pub(crate) fn to_logical(self, point: Point, size: (u32, u32)) -> Point {
+        let origin = self.origin_point(size);
+        Point {
+            x: origin.x + point.x,
+            y: origin.y + if self.y_up { point.y } else { -point.y },
+        }
+    }
+
+    /// Converts a heading between logical and user coordinates (in either direction)
+    ///
+    /// Flipping the y axis reflects every heading across the x axis, so 90 degrees points
+    /// towards the bottom of the window instead of the top. Headings between 0 and 2*pi stay in
+    /// that range.
+    pub(crate) fn convert_heading(self, heading: Radians) -> Radians {
+        if self.y_up {
+            heading
+        } else {
+            (radians::TWO_PI - heading) % radians::TWO_PI
+        }
+    }
+}
+
+/// The area of user coordinates that fills the window, set with
+/// [`Drawing::set_world_coordinates()`]
+///
+/// The corners do not need to be in any particular order. If `lower_left` is to the right of
+/// `upper_right`, x increases towards the left of the window. Similarly, y increases towards the
+/// bottom of the window if `lower_left` is above `upper_right`.
+///
+/// [`Drawing::set_world_coordinates()`]: struct.Drawing.html#method.set_world_coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorldCoordinates {
+    /// The coordinates of the bottom-left corner of the window
+    pub lower_left: Point,
+    /// The coordinates of the top-right corner of the window
+    pub upper_right: Point,
+}
+
+impl WorldCoordinates {
+    /// Returns true if the coordinates are finite and cover an area that is not empty
+    pub(crate) fn is_valid(self) -> bool {
+        self.lower_left.is_finite() && self.upper_right.is_finite()
+            && self.lower_left.x != self.upper_right.x
+            && self.lower_left.y != self.upper_right.y
+    }
+
+    /// Returns the number of logical pixels in one unit along each axis for a drawing of the
+    /// given size (in logical pixels)
+    pub(crate) fn scale(self, size: (u32, u32)) -> AxisScale {
+        AxisScale {
+            x: size.0 as f64 / (self.upper_right.x - self.lower_left.x),
+            y: size.1 as f64 / (self.upper_right.y - self.lower_left.y),
+        }
+    }
+
+    /// Converts a point in logical coordinates to world coordinates for a drawing of the given
+    /// size (in logical pixels)
+    pub(crate) fn to_user(self, point: Point, size: (u32, u32)) -> Point {
+        let corner = bottom_left_corner(size);
+        self.lower_left + self.scale(size).invert(point - corner)
+    }
+
+    /// Converts a point in world coordinates to logical coordinates for a drawing of the given
+    /// size (in logical pixels)
+    pub(crate) fn to_logical(self, point: Point, size: (u32, u32)) -> Point {
+        let corner = bottom_left_corner(size);
+        corner + self.scale(size).apply(point - self.lower_left)
+    }
+}
+
+/// Returns the bottom-left corner of the window in logical coordinates, as if the drawing
+/// `center` was never moved
+fn bottom_left_corner(size: (u32, u32)) -> Point {
+    Point {x: -(size.0 as f64) / 2.0, y: -(size.1 as f64) / 2.0}
+}
+
+/// A scale factor for each axis, used to convert lengths and directions between world
+/// coordinates and logical coordinates
+///
+/// Either factor can be negative if that axis is flipped. Since the scale can be different for
+/// each axis, angles are not preserved: a heading of 45 degrees in world coordinates only points
+/// diagonally on the screen if both axes have the same scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct AxisScale {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl AxisScale {
+    /// The scale used when world coordinates are not set
+    pub const IDENTITY: Self = Self {x: 1.0, y: 1.0};
+
+    /// Returns true if both axes are scaled by the same amount, ignoring whether they are flipped
+    pub fn is_uniform(self) -> bool {
+        self.x.abs() == self.y.abs()
+    }
+
+    /// Returns true if exactly one of the axes is flipped, which reverses the direction of every
+    /// rotation
+    pub fn is_mirrored(self) -> bool {
+        (self.x < 0.0) != (self.y < 0.0)
+    }
+
+    /// Converts a vector in world units to logical pixels
+    pub fn apply(self, vector: Point) -> Point {
+        Point {x: vector.x * self.x, y: vector.y * self.y}
+    }
+
+    /// Converts a vector in logical pixels to world units
+    pub fn invert(self, vector: Point) -> Point {
+        Point {x: vector.x / self.x, y: vector.y / self.y}
+    }
+
+    /// Converts a heading in world coordinates to the heading that faces the same way on the
+    /// screen in logical coordinates
+    pub fn apply_heading(self, heading: Radians) -> Radians {
+        if self == Self::IDENTITY {
+            return heading;
+        }
+
+        let direction = self.apply(Point {x: heading.cos(), y: heading.sin()});
+        normalize_heading(direction.atan2())
+    }
+
+    /// Converts a heading in logical coordinates to the heading that faces the same way on the
+    /// screen in world coordinates
+    pub fn invert_heading(self, heading: Radians) -> Radians {
+        if self == Self::IDENTITY {
+            return heading;
+        }
+
+        let direction = self.invert(Point {x: heading.cos(), y: heading.sin()});
+        normalize_heading(direction.atan2())
+    }
+
+    /// Returns the amount that a turtle with the given logical heading needs to turn on the
+    /// screen in the given direction to turn by `angle` in world coordinates
+    ///
+    /// Every full turn in world coordinates is still a full turn on the screen, so only the
+    /// remainder of the angle is stretched by the scale.
+    pub fn rotation(self, heading: Radians, angle: Radians, direction: RotationDirection) -> Radians {
+        if self == Self::IDENTITY {
+            return angle;
+        }
+
+        // A negative angle turns the other way
+        let (sign, direction) = match direction {
+            _ if angle.to_radians() >= 0.0 => (1.0, direction),
+            RotationDirection::Clockwise => (-1.0, RotationDirection::Counterclockwise),
+            RotationDirection::Counterclockwise => (-1.0, RotationDirection::Clockwise),
+        };
+        let angle = angle.to_radians().abs();
+
+        // Turning counterclockwise on the screen turns clockwise in world coordinates if the axes
+        // are mirrored
+        let world_ccw = (direction == RotationDirection::Counterclockwise) != self.is_mirrored();
+        let world_heading = self.invert_heading(heading).to_radians();
+        let target = if world_ccw { world_heading + angle } else { world_heading - angle };
+        let target = self.apply_heading(Radians::from_radians_value(target)).to_radians();
+
+        let turn = radians::TWO_PI.to_radians();
+        let full_turns = (angle / turn).floor() * turn;
+        let partial = match direction {
+            RotationDirection::Counterclockwise => target - heading.to_radians(),
+            RotationDirection::Clockwise => heading.to_radians() - target,
+        }.rem_euclid(turn);
+        // Rounding can make a turn that is very close to a full turn wrap around to zero (or the
+        // other way around), so the remainder of the original angle decides which it is
+        let partial = if angle - full_turns < 1e-9 && partial > turn / 2.0 { 0.0 } else { partial };
+
+        Radians::from_radians_value(sign * (full_turns + partial))
+    }
+}
+
+/// Returns the equivalent heading between 0 and 2*pi
+fn normalize_heading(heading: f64) -> Radians {
+    Radians::from_radians_value(heading.rem_euclid(radians::TWO_PI.to_radians()))
+}
 
 /// A point in the screen corodinate system
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -25,6 +346,7 @@ pub struct ScreenPoint {
 }
 
 /// A `PhysicalPosition<f64>` is considered to be in screen coordinates in glutin
+#[cfg(not(target_arch = "wasm32"))]
 impl From<PhysicalPosition<f64>> for ScreenPoint {
     fn from(pos: PhysicalPosition<f64>) -> Self {
         Self {x: pos.x, y: pos.y}
@@ -32,6 +354,7 @@ impl From<PhysicalPosition<f64>> for ScreenPoint {
 }
 
 /// A `PhysicalPosition<f64>` is considered to be in screen coordinates in glutin
+#[cfg(not(target_arch = "wasm32"))]
 impl From<ScreenPoint> for PhysicalPosition<f64> {
     fn from(pt: ScreenPoint) -> Self {
         let ScreenPoint {x, y} = pt;
@@ -137,6 +460,90 @@ mod tests {
         assert_eq!(screen_coord, ScreenPoint {x: 160.0, y: 270.0});
     }
 
+    #[test]
+    fn resize_anchor() {
+        let center = Point {x: 10.0, y: 20.0};
+
+        // Center anchor never moves the center
+        let new_center = ResizeAnchor::Center.center_after_resize(center, (800, 600), (1000, 700));
+        assert_eq!(new_center, center);
+
+        // Top-left anchor keeps the top-left corner of the window at the same logical point
+        let old_size = (800, 600);
+        let new_size = (1000, 700);
+        let top_left = |center, (width, height): (u32, u32)| {
+            let fb_center = ScreenPoint {x: width as f64 / 2.0, y: height as f64 / 2.0};
+            ScreenPoint {x: 0.0, y: 0.0}.to_logical(1.0, center, fb_center)
+        };
+        let new_center = ResizeAnchor::TopLeft.center_after_resize(center, old_size, new_size);
+        assert_eq!(new_center, Point {x: 110.0, y: -30.0});
+        assert_eq!(top_left(center, old_size), top_left(new_center, new_size));
+
+        // Shrinking works the same way
+        let new_center = ResizeAnchor::TopLeft.center_after_resize(center, new_size, old_size);
+        assert_eq!(top_left(center, new_size), top_left(new_center, old_size));
+    }
+
+    #[test]
+    fn coordinate_systems() {
+        let size = (800, 600);
+
+        // The default coordinate system is the same as logical coordinates
+        let coords = CoordinateSystem::default();
+        assert_eq!(coords.to_user(Point {x: 10.0, y: 20.0}, size), Point {x: 10.0, y: 20.0});
+
+        // Screen-style coordinates: the top-left corner is the origin and y increases downwards
+        let coords = CoordinateSystem {origin: Origin::TopLeft, y_up: false};
+        assert_eq!(coords.to_logical(Point::origin(), size), Point {x: -400.0, y: 300.0});
+        assert_eq!(coords.to_logical(Point {x: 400.0, y: 300.0}, size), Point::origin());
+        assert_eq!(coords.to_user(Point {x: 10.0, y: -20.0}, size), Point {x: 410.0, y: 320.0});
+
+        let coords = CoordinateSystem {origin: Origin::BottomLeft, y_up: true};
+        let point = Point {x: 12.0, y: 34.0};
+        assert_eq!(coords.to_user(coords.to_logical(point, size), size), point);
+        assert_eq!(coords.to_logical(Point {x: 800.0, y: 600.0}, size), Point {x: 400.0, y: 300.0});
+
+        // Flipping the y axis reflects headings across the x axis
+        let coords = CoordinateSystem {origin: Origin::Center, y_up: false};
+        let north = Radians::from_degrees_value(90.0);
+        assert!((coords.convert_heading(north).to_degrees() - 270.0).abs() < 1e-9);
+        assert_eq!(coords.convert_heading(radians::ZERO), radians::ZERO);
+    }
+
+    #[test]
+    fn world_coordinates() {
+        let size = (400, 300);
+        let world = WorldCoordinates {
+            lower_left: Point {x: 0.0, y: 10.0},
+            upper_right: Point {x: 4.0, y: 4.0},
+        };
+        assert!(world.is_valid());
+        assert_eq!(world.to_logical(Point {x: 0.0, y: 10.0}, size), Point {x: -200.0, y: -150.0});
+        assert_eq!(world.to_logical(Point {x: 4.0, y: 4.0}, size), Point {x: 200.0, y: 150.0});
+        assert_eq!(world.to_user(Point::origin(), size), Point {x: 2.0, y: 7.0});
+
+        // Only the y axis is flipped, so every heading is mirrored
+        let scale = world.scale(size);
+        assert_eq!(scale, AxisScale {x: 100.0, y: -50.0});
+        assert!(scale.is_mirrored());
+        assert!(!scale.is_uniform());
+        let north = Radians::from_degrees_value(90.0);
+        assert!((scale.apply_heading(north).to_degrees() - 270.0).abs() < 1e-9);
+
+        // Turning by 45 degrees in world coordinates turns less than that on the screen since
+        // the y axis is squashed
+        let east = radians::ZERO;
+        let turn = scale.rotation(east, Radians::from_degrees_value(45.0), RotationDirection::Clockwise);
+        assert!((turn.to_radians() - 0.5f64.atan()).abs() < 1e-9);
+        let turn = scale.rotation(east, Radians::from_degrees_value(-405.0), RotationDirection::Clockwise);
+        assert!((turn.to_radians() + 2.0 * std::f64::consts::PI + 0.5f64.atan()).abs() < 1e-9);
+        let turn = scale.rotation(east, radians::TWO_PI, RotationDirection::Counterclockwise);
+        assert!((turn - radians::TWO_PI).to_radians().abs() < 1e-9);
+
+        let empty = WorldCoordinates {lower_left: Point::origin(), upper_right: Point {x: 1.0, y: 0.0}};
+        assert!(!empty.is_valid());
+    }
+
     #[test]
     fn logical_coords() {
         // The origin is always at fb_center as long as center is also the origin
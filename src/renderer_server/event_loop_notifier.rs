@@ -1,54 +1,106 @@
-use glutin::{
-    dpi::LogicalSize,
-    event_loop::{self, EventLoopProxy},
-};
+use std::sync::mpsc;
+
+#[cfg(not(any(feature = "test", feature = "headless", test)))]
+use glutin::event_loop::{self, EventLoopProxy};
 use thiserror::Error;
 
+use crate::Size;
+
+use super::renderer::image::ImageHandle;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
 #[error("event loop closed while messages were still being sent to it")]
 pub struct EventLoopClosed;
 
+#[cfg(not(any(feature = "test", feature = "headless", test)))]
 impl<T> From<event_loop::EventLoopClosed<T>> for EventLoopClosed {
     fn from(_: event_loop::EventLoopClosed<T>) -> Self {
         EventLoopClosed
     }
 }
 
+impl<T> From<mpsc::SendError<T>> for EventLoopClosed {
+    fn from(_: mpsc::SendError<T>) -> Self {
+        EventLoopClosed
+    }
+}
+
 /// A custom event used to perform actions within the glutin event loop on the main thread
+///
+/// Custom renderer backends receive these actions too, on the thread running the backend.
 #[derive(Debug, Clone, PartialEq)]
 pub enum MainThreadAction {
-    /// Redraw the window
+    /// Redraw the window, unless the tracer of the drawing is skipping this update
     Redraw,
+    /// Redraw the window, even if the tracer of the drawing is skipping updates
+    Flush,
     /// Update the window title
     SetTitle(String),
+    /// Update the window icon to the given image, or remove the icon if there is none
+    SetIcon(Option<ImageHandle>),
     /// Update the window size (in logical coordinates)
-    SetSize(LogicalSize<u32>),
+    SetSize(Size),
     /// Change the maximized state of the window
     SetIsMaximized(bool),
     /// Change the fullscreen state of the window
     SetIsFullscreen(bool),
+    /// Close the window and end the event loop
+    Close,
+}
+
+/// Where the actions sent by an `EventLoopNotifier` go
+#[derive(Debug, Clone)]
+enum Target {
+    /// The glutin event loop running the window
+    #[cfg(not(any(feature = "test", feature = "headless", test)))]
+    EventLoop(EventLoopProxy<MainThreadAction>),
+    /// The thread running a custom renderer backend
+    Backend(mpsc::Sender<MainThreadAction>),
+    /// Nowhere, since there is no window (e.g. in tests)
+    #[cfg(any(feature = "test", feature = "headless", test))]
+    Nowhere,
 }
 
 /// Notifies the main loop when actions need to take place
 #[derive(Debug, Clone)]
 pub struct EventLoopNotifier {
-    event_loop: EventLoopProxy<MainThreadAction>,
+    target: Target,
 }
 
 impl EventLoopNotifier {
+    #[cfg(not(any(feature = "test", feature = "headless", test)))]
     pub fn new(event_loop: EventLoopProxy<MainThreadAction>) -> Self {
-        Self {event_loop}
+        Self {target: Target::EventLoop(event_loop)}
+    }
+
+    /// Creates a notifier that ignores every action, since there is no window to perform them
+    #[cfg(any(feature = "test", feature = "headless", test))]
+    pub fn new() -> Self {
+        Self {target: Target::Nowhere}
+    }
+
+    /// Creates a notifier that sends every action to the thread running a custom backend
+    pub fn with_backend(backend: mpsc::Sender<MainThreadAction>) -> Self {
+        Self {target: Target::Backend(backend)}
     }
 
     pub fn request_redraw(&self) -> Result<(), EventLoopClosed> {
         self.send_action(MainThreadAction::Redraw)
     }
 
+    pub fn flush(&self) -> Result<(), EventLoopClosed> {
+        self.send_action(MainThreadAction::Flush)
+    }
+
     pub fn set_title(&self, title: String) -> Result<(), EventLoopClosed> {
         self.send_action(MainThreadAction::SetTitle(title))
     }
 
-    pub fn set_size<S: Into<LogicalSize<u32>>>(&self, size: S) -> Result<(), EventLoopClosed> {
+    pub fn set_icon(&self, icon: Option<ImageHandle>) -> Result<(), EventLoopClosed> {
+        self.send_action(MainThreadAction::SetIcon(icon))
+    }
+
+    pub fn set_size<S: Into<Size>>(&self, size: S) -> Result<(), EventLoopClosed> {
         self.send_action(MainThreadAction::SetSize(size.into()))
     }
 
@@ -60,7 +112,17 @@ impl EventLoopNotifier {
         self.send_action(MainThreadAction::SetIsFullscreen(is_fullscreen))
     }
 
+    pub fn close(&self) -> Result<(), EventLoopClosed> {
+        self.send_action(MainThreadAction::Close)
+    }
+
     fn send_action(&self, action: MainThreadAction) -> Result<(), EventLoopClosed> {
-        Ok(self.event_loop.send_event(action)?)
+        match &self.target {
+            #[cfg(not(any(feature = "test", feature = "headless", test)))]
+            Target::EventLoop(event_loop) => Ok(event_loop.send_event(action)?),
+            Target::Backend(backend) => Ok(backend.send(action)?),
+            #[cfg(any(feature = "test", feature = "headless", test))]
+            Target::Nowhere => Ok(()),
+        }
     }
 }
@@ -0,0 +1,48 @@
+use std::collections::VecDeque;
+
+use tokio::sync::mpsc::{self, error::TryRecvError};
+
+use crate::Event;
+
+/// The events from the window that have not been sent to a client yet
+///
+/// Events that could not be delivered are put back at the front of the queue so that they are
+/// still sent before any events that occurred after them.
+#[derive(Debug)]
+pub(crate) struct EventQueue {
+    receiver: mpsc::UnboundedReceiver<Event>,
+    requeued: VecDeque<Event>,
+}
+
+impl EventQueue {
+    pub fn new(receiver: mpsc::UnboundedReceiver<Event>) -> Self {
+        Self {receiver, requeued: VecDeque::new()}
+    }
+
+    /// Puts back an event that was sent to a client that no longer exists
+    ///
+    /// Events put back one after the other keep the order they were put back in.
+    pub fn requeue(&mut self, event: Event) {
+        self.requeued.push_back(event);
+    }
+
+    /// Returns the next event if one is available right away
+    ///
+    /// Returns `Err(TryRecvError::Closed)` once the window will not send any more events.
+    pub fn try_recv(&mut self) -> Result<Event, TryRecvError> {
+        match self.requeued.pop_front() {
+            Some(event) => Ok(event),
+            None => self.receiver.try_recv(),
+        }
+    }
+
+    /// Waits for the next event, returning `None` once the window will not send any more events
+    ///
+    /// Stopping the returned future before it completes never loses an event.
+    pub async fn recv(&mut self) -> Option<Event> {
+        match self.requeued.pop_front() {
+            Some(event) => Some(event),
+            None => self.receiver.recv().await,
+        }
+    }
+}
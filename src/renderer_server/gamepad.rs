@@ -0,0 +1,40 @@
+use std::thread;
+
+use gilrs::Gilrs;
+use tokio::sync::mpsc;
+
+use crate::Event;
+use crate::event::GamepadEvent;
+
+/// Spawns a thread that sends an event whenever a gamepad is connected, disconnected, or used
+///
+/// The thread ends once events can no longer be sent. If gamepads are not supported on this
+/// platform, the thread ends right away and no gamepad events are ever sent.
+pub fn spawn_gamepad_thread(events_sender: mpsc::UnboundedSender<Event>) {
+    // Gilrs is created on the thread that uses it since it cannot be sent between threads on
+    // every platform
+    thread::spawn(move || {
+        let mut gilrs = match Gilrs::new() {
+            Ok(gilrs) => gilrs,
+            // Gamepads are optional, so the drawing keeps working without them
+            Err(_) => return,
+        };
+
+        loop {
+            let event = match gilrs.next_event_blocking(None) {
+                Some(event) => event,
+                None => continue,
+            };
+
+            let gamepad_event = match GamepadEvent::from_gilrs(event.event) {
+                Some(gamepad_event) => gamepad_event,
+                None => continue,
+            };
+
+            // Sending fails once the IPC thread has ended, so there is no one left to send events to
+            if events_sender.send(Event::Gamepad(event.id.into(), gamepad_event)).is_err() {
+                break;
+            }
+        }
+    });
+}
@@ -1,7 +1,25 @@
 mod create_turtle;
 mod export_drawings;
+mod import_drawings;
+mod session;
+mod print_drawings;
+mod share_drawing;
+mod record_gif;
+mod record_commands;
 mod poll_event;
 mod drawing_prop;
+mod drawing_bounds;
+mod export_mesh;
+mod primitives;
+mod color_at;
+mod draw_shape;
+mod layer;
+mod measure_text;
+mod write_text;
+mod image;
+mod stamp;
+mod undo;
+mod saved_state;
 mod turtle_prop;
 mod animation;
 mod fill;
@@ -10,8 +28,26 @@ mod debug;
 
 pub(crate) use create_turtle::*;
 pub(crate) use export_drawings::*;
+pub(crate) use import_drawings::*;
+pub(crate) use session::*;
+pub(crate) use print_drawings::*;
+pub(crate) use share_drawing::*;
+pub(crate) use record_gif::*;
+pub(crate) use record_commands::*;
 pub(crate) use poll_event::*;
 pub(crate) use drawing_prop::*;
+pub(crate) use drawing_bounds::*;
+pub(crate) use export_mesh::*;
+pub(crate) use primitives::*;
+pub(crate) use color_at::*;
+pub(crate) use draw_shape::*;
+pub(crate) use layer::*;
+pub(crate) use measure_text::*;
+pub(crate) use write_text::*;
+pub(crate) use image::*;
+pub(crate) use stamp::*;
+pub(crate) use undo::*;
+pub(crate) use saved_state::*;
 pub(crate) use turtle_prop::*;
 pub(crate) use animation::*;
 pub(crate) use fill::*;
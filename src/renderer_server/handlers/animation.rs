@@ -1,16 +1,19 @@
+use std::time::Duration;
+
 use crate::ipc_protocol::{ServerOneshotSender, ServerResponse, RotationDirection};
 use crate::radians::Radians;
-use crate::{Distance, Point};
+use crate::{Color, Distance, Point};
+use crate::path::{self, PathSegment};
 
 use super::HandlerError;
 use super::super::{
     event_loop_notifier::EventLoopNotifier,
-    state::TurtleState,
     app::{TurtleId, App},
-    animation::{MoveAnimation, RotateAnimation, AnimationRunner},
+    animation::{MoveAnimation, RotateAnimation, ArcAnimation, CurveAnimation, AnimationRunner, ColorTransition, Timing},
     renderer::display_list::DisplayList,
 };
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn move_forward(
     conn: ServerOneshotSender,
     app: &mut App,
@@ -19,19 +22,19 @@ pub(crate) fn move_forward(
     anim_runner: &AnimationRunner,
     id: TurtleId,
     distance: Distance,
+    duration: Option<Duration>,
 ) -> Result<(), HandlerError> {
-    let turtle = app.turtle_mut(id);
+    let heading = app.turtle(id).state.heading;
+    // The total amount we'll move in the x and y directions
+    let movement = app.drawing().movement(heading, distance);
+    let timing = app.animation_timing(id, duration);
 
-    let TurtleState {position, heading, ..} = turtle.state;
+    let turtle = app.turtle_mut(id);
+    turtle.save_undo_step(display_list);
 
-    // The total amount we'll move in the x and y directions
-    let movement = Point {
-        x: distance * heading.cos(),
-        y: distance * heading.sin(),
-    };
-    let target_pos = position + movement;
+    let target_pos = turtle.state.position + movement;
 
-    let anim = MoveAnimation::new(turtle, display_list, target_pos);
+    let anim = MoveAnimation::new(turtle, display_list, target_pos, timing);
 
     if anim.is_running() {
         anim_runner.play(id, anim, conn.client_id());
@@ -56,9 +59,12 @@ pub(crate) fn move_to(
     id: TurtleId,
     target_pos: Point,
 ) -> Result<(), HandlerError> {
+    let target_pos = app.drawing().to_logical(target_pos);
+    let speed = app.animation_speed(id);
     let turtle = app.turtle_mut(id);
+    turtle.save_undo_step(display_list);
 
-    let anim = MoveAnimation::new(turtle, display_list, target_pos);
+    let anim = MoveAnimation::new(turtle, display_list, target_pos, speed);
 
     if anim.is_running() {
         anim_runner.play(id, anim, conn.client_id());
@@ -74,18 +80,185 @@ pub(crate) fn move_to(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn rotate_in_place(
     conn: ServerOneshotSender,
     app: &mut App,
+    display_list: &mut DisplayList,
     event_loop: &EventLoopNotifier,
     anim_runner: &AnimationRunner,
     id: TurtleId,
     angle: Radians,
     direction: RotationDirection,
+    duration: Option<Duration>,
+) -> Result<(), HandlerError> {
+    // The angle is in the coordinate system of the drawing, which may not match the screen
+    let heading = app.turtle(id).state.heading;
+    let angle = app.drawing().axis_scale().rotation(heading, angle, direction);
+    let timing = app.animation_timing(id, duration);
+
+    let turtle = app.turtle_mut(id);
+    turtle.save_undo_step(display_list);
+
+    let anim = RotateAnimation::new(turtle, angle, direction, timing);
+
+    if anim.is_running() {
+        anim_runner.play(id, anim, conn.client_id());
+
+    } else {
+        // Instant animations complete right away and don't need to be queued
+        // Signal the main thread that the image has changed
+        event_loop.request_redraw()?;
+
+        conn.send(ServerResponse::AnimationComplete(id))?;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn move_arc(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    anim_runner: &AnimationRunner,
+    id: TurtleId,
+    radius: Distance,
+    extent: Radians,
+    direction: RotationDirection,
+) -> Result<(), HandlerError> {
+    let scale = app.drawing().axis_scale();
+    let speed = app.animation_speed(id);
+    let turtle = app.turtle_mut(id);
+    turtle.save_undo_step(display_list);
+
+    let anim = ArcAnimation::new(turtle, display_list, scale, radius, extent, direction, speed);
+
+    if anim.is_running() {
+        anim_runner.play(id, anim, conn.client_id());
+
+    } else {
+        // Instant animations complete right away and don't need to be queued
+        // Signal the main thread that the image has changed
+        event_loop.request_redraw()?;
+
+        conn.send(ServerResponse::AnimationComplete(id))?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn move_circle(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    anim_runner: &AnimationRunner,
+    id: TurtleId,
+    radius: Distance,
+) -> Result<(), HandlerError> {
+    let scale = app.drawing().axis_scale();
+    let speed = app.animation_speed(id);
+    let turtle = app.turtle_mut(id);
+    turtle.save_undo_step(display_list);
+
+    let anim = ArcAnimation::circle(turtle, display_list, scale, radius, speed);
+
+    if anim.is_running() {
+        anim_runner.play(id, anim, conn.client_id());
+
+    } else {
+        // Instant animations complete right away and don't need to be queued
+        // Signal the main thread that the image has changed
+        event_loop.request_redraw()?;
+
+        conn.send(ServerResponse::AnimationComplete(id))?;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn cubic_bezier_to(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    anim_runner: &AnimationRunner,
+    id: TurtleId,
+    control1: Point,
+    control2: Point,
+    end: Point,
+) -> Result<(), HandlerError> {
+    let drawing = app.drawing();
+    let control1 = drawing.to_logical(control1);
+    let control2 = drawing.to_logical(control2);
+    let end = drawing.to_logical(end);
+    let speed = app.animation_speed(id);
+    let turtle = app.turtle_mut(id);
+    turtle.save_undo_step(display_list);
+
+    let anim = CurveAnimation::new(turtle, display_list, &[[control1, control2, end]], speed);
+
+    if anim.is_running() {
+        anim_runner.play(id, anim, conn.client_id());
+
+    } else {
+        // Instant animations complete right away and don't need to be queued
+        // Signal the main thread that the image has changed
+        event_loop.request_redraw()?;
+
+        conn.send(ServerResponse::AnimationComplete(id))?;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn quad_bezier_to(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    anim_runner: &AnimationRunner,
+    id: TurtleId,
+    control: Point,
+    end: Point,
 ) -> Result<(), HandlerError> {
+    // Every quadratic curve is exactly the same as a cubic curve with these control points
+    let start = app.drawing().to_user(app.turtle(id).state.position);
+    let control1 = start + (control - start) * (2.0 / 3.0);
+    let control2 = end + (control - end) * (2.0 / 3.0);
+
+    cubic_bezier_to(conn, app, display_list, event_loop, anim_runner, id, control1, control2, end)
+}
+
+pub(crate) fn follow_path(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    anim_runner: &AnimationRunner,
+    id: TurtleId,
+    path: &[PathSegment],
+) -> Result<(), HandlerError> {
+    let drawing = app.drawing();
+    let start = drawing.to_user(app.turtle(id).state.position);
+    let curves: Vec<_> = path::to_curves(start, path).into_iter()
+        .map(|points| points.map(|point| drawing.to_logical(point)))
+        .collect();
+    // A path made of empty arcs does not go anywhere
+    if curves.is_empty() {
+        conn.send(ServerResponse::AnimationComplete(id))?;
+        return Ok(());
+    }
+
+    let speed = app.animation_speed(id);
     let turtle = app.turtle_mut(id);
+    turtle.save_undo_step(display_list);
 
-    let anim = RotateAnimation::new(turtle, angle, direction);
+    let anim = CurveAnimation::new(turtle, display_list, &curves, speed);
 
     if anim.is_running() {
         anim_runner.play(id, anim, conn.client_id());
@@ -100,3 +273,41 @@ pub(crate) fn rotate_in_place(
 
     Ok(())
 }
+
+pub(crate) fn animate_pen_color(
+    app: &mut App,
+    event_loop: &EventLoopNotifier,
+    id: TurtleId,
+    color: Color,
+    duration: Duration,
+) -> Result<(), HandlerError> {
+    let timing = app.animation_timing(id, Some(duration));
+    let turtle = app.turtle_mut(id);
+
+    match timing {
+        // The color only changes as lines are drawn, so there is nothing to redraw yet
+        Timing::Duration(duration) if duration.as_micros() > 0 && !cfg!(any(feature = "test", feature = "headless", test)) => {
+            let transition = ColorTransition::new(turtle.pen_color(), color, duration);
+            turtle.pen_color_transition = Some(transition);
+        },
+
+        _ => {
+            turtle.pen_color_transition = None;
+            turtle.state.pen.color = color;
+            // Signal the main thread that the turtle may have changed
+            event_loop.request_redraw()?;
+        },
+    }
+
+    Ok(())
+}
+
+pub(crate) fn pending_animations(
+    conn: ServerOneshotSender,
+    anim_runner: &AnimationRunner,
+    id: Option<TurtleId>,
+) -> Result<(), HandlerError> {
+    conn.send(ServerResponse::PendingAnimations(anim_runner.pending(id)))?;
+
+    Ok(())
+}
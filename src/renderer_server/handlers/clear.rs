@@ -1,8 +1,13 @@
+use std::collections::HashSet;
+
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+use crate::Rect;
+
 use super::HandlerError;
 use super::super::{
     event_loop_notifier::EventLoopNotifier,
     app::{App, TurtleId, TurtleDrawings},
-    animation::AnimationRunner,
+    animation::{self, AnimationRunner, ClearAnimation, ClearEffect},
     renderer::display_list::DisplayList,
 };
 
@@ -12,18 +17,79 @@ pub(crate) fn clear_all(
     event_loop: &EventLoopNotifier,
     anim_runner: &AnimationRunner,
 ) -> Result<(), HandlerError> {
-    display_list.clear();
+    animation::clear_drawings(app, display_list);
 
-    for (_, turtle) in app.turtles_mut() {
-        let TurtleDrawings {state: _, drawings, current_fill_polygon} = turtle;
+    // Stop all animations that may have been running
+    anim_runner.stop_all();
+
+    // Signal the main thread that the image has changed
+    event_loop.request_redraw()?;
+
+    Ok(())
+}
+
+pub(crate) fn clear_animated(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    anim_runner: &AnimationRunner,
+    effect: ClearEffect,
+) -> Result<(), HandlerError> {
+    // Stop all animations that may have been running so nothing is drawn over the cover
+    anim_runner.stop_all();
+
+    let drawing = app.drawing();
+    let area = Rect::from_center(drawing.center, drawing.width as f64, drawing.height as f64);
+    let color = drawing.background;
+
+    // The effect is skipped entirely if animation is disabled
+    let anim = match drawing.is_animated {
+        true => ClearAnimation::new(display_list, effect, area, color),
+        false => None,
+    };
 
-        drawings.clear();
-        *current_fill_polygon = None;
+    match anim {
+        Some(anim) => anim_runner.clear(anim, conn.client_id()),
+
+        None => {
+            animation::clear_drawings(app, display_list);
+            conn.send(ServerResponse::ClearComplete)?;
+        },
     }
 
-    // Stop all animations that may have been running
+    // Signal the main thread that the image has changed
+    event_loop.request_redraw()?;
+
+    Ok(())
+}
+
+pub(crate) fn clear_region(
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    anim_runner: &AnimationRunner,
+    area: Rect,
+) -> Result<(), HandlerError> {
+    // Stop all animations so that none of them go on to update a drawing that gets removed
     anim_runner.stop_all();
 
+    let area = app.drawing().rect_to_logical(area);
+    // Fills that are in progress must stay in the display list so their turtles can keep adding
+    // points to them
+    let fills: HashSet<_> = app.turtles()
+        .filter_map(|(_, turtle)| turtle.current_fill_polygon)
+        .collect();
+
+    let removed = display_list.remove_within(area, |handle| fills.contains(&handle));
+    if removed.is_empty() {
+        return Ok(());
+    }
+
+    for (_, turtle) in app.turtles_mut() {
+        turtle.forget_drawings(&removed);
+    }
+
     // Signal the main thread that the image has changed
     event_loop.request_redraw()?;
 
@@ -38,11 +104,14 @@ pub(crate) fn clear_turtle(
 ) -> Result<(), HandlerError> {
     let turtle = app.turtle_mut(id);
 
-    let TurtleDrawings {state: _, drawings, current_fill_polygon} = turtle;
+    let TurtleDrawings {state: _, drawings, current_fill_polygon, stamps, undo_steps, saved_states: _, last_stroke, pen_color_transition: _} = turtle;
 
     display_list.remove(drawings.iter().copied());
     drawings.clear();
     *current_fill_polygon = None;
+    stamps.clear();
+    undo_steps.clear();
+    *last_stroke = None;
 
     // Signal the main thread that the image has changed
     event_loop.request_redraw()?;
@@ -0,0 +1,27 @@
+use crate::{Point, Rect};
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+
+use super::HandlerError;
+use super::super::{
+    app::App,
+    renderer::{raster, display_list::DisplayList},
+};
+
+pub(crate) fn color_at(
+    conn: ServerOneshotSender,
+    app: &App,
+    display_list: &DisplayList,
+    point: Point,
+) -> Result<(), HandlerError> {
+    let drawing = app.drawing();
+
+    // Rendering a single pixel centered on the point gives the same color that the point would
+    // have in an exported image
+    let area = Rect::from_center(point, 1.0, 1.0);
+    let pixmap = raster::rasterize(display_list, area, 1.0, drawing.background, app.background_image(), drawing.symmetry)
+        .expect("bug: a single pixel should never be too large to render");
+
+    conn.send(ServerResponse::ColorAt(pixmap.color_at(0, 0)))?;
+
+    Ok(())
+}
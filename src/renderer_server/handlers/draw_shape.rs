@@ -0,0 +1,29 @@
+use crate::{Color, Distance, Point};
+
+use super::HandlerError;
+use super::super::{
+    event_loop_notifier::EventLoopNotifier,
+    state::Pen,
+    app::App,
+    renderer::display_list::DisplayList,
+};
+
+pub(crate) fn draw_circle(
+    app: &App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    center: Point,
+    radius: Distance,
+    fill_color: Color,
+) -> Result<(), HandlerError> {
+    let center = app.drawing().to_logical(center);
+    // Only the inside of the circle is drawn
+    let pen = Pen {is_enabled: false, ..Pen::default()};
+
+    if display_list.push_circle(center, radius, &pen, fill_color).is_some() {
+        // Signal the main thread that the image has changed
+        event_loop.request_redraw()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+
+use super::HandlerError;
+use super::super::{
+    app::{App, TurtleId},
+    renderer::display_list::DisplayList,
+};
+
+pub(crate) fn drawing_bounds(
+    conn: ServerOneshotSender,
+    app: &App,
+    display_list: &DisplayList,
+) -> Result<(), HandlerError> {
+    let drawing = app.drawing();
+    let bounds = display_list.bounds()
+        .map(|bounds| drawing.rect_to_user(bounds));
+
+    conn.send(ServerResponse::DrawingBounds(bounds))?;
+
+    Ok(())
+}
+
+pub(crate) fn turtle_drawing_bounds(
+    conn: ServerOneshotSender,
+    app: &App,
+    display_list: &DisplayList,
+    id: TurtleId,
+) -> Result<(), HandlerError> {
+    let drawing = app.drawing();
+    let bounds = display_list.bounds_of(&app.turtle(id).drawings)
+        .map(|bounds| drawing.rect_to_user(bounds));
+
+    conn.send(ServerResponse::DrawingBounds(bounds))?;
+
+    Ok(())
+}
@@ -17,13 +17,32 @@ pub(crate) fn drawing_prop(
     use DrawingProp::*;
     let value = match prop {
         Title => DrawingPropValue::Title(drawing.title.clone()),
+        Icon => DrawingPropValue::Icon(drawing.icon),
         Background => DrawingPropValue::Background(drawing.background),
-        Center => DrawingPropValue::Center(drawing.center),
+        BackgroundImage => DrawingPropValue::BackgroundImage(drawing.background_image),
+        Center => DrawingPropValue::Center(drawing.to_user(drawing.center)),
+        Zoom => DrawingPropValue::Zoom(drawing.zoom),
         Size => DrawingPropValue::Size(crate::Size {width: drawing.width, height: drawing.height}),
         Width => DrawingPropValue::Width(drawing.width),
         Height => DrawingPropValue::Height(drawing.height),
         IsMaximized => DrawingPropValue::IsMaximized(drawing.is_maximized),
         IsFullscreen => DrawingPropValue::IsFullscreen(drawing.is_fullscreen),
+        ResizeAnchor => DrawingPropValue::ResizeAnchor(drawing.resize_anchor),
+        Minimap => DrawingPropValue::Minimap(drawing.minimap),
+        Filters => DrawingPropValue::Filters(drawing.filters.clone()),
+        Symmetry => DrawingPropValue::Symmetry(drawing.symmetry),
+        PressurePenWidth => DrawingPropValue::PressurePenWidth(drawing.pressure_pen_width),
+        CoordinateSystem => DrawingPropValue::CoordinateSystem(drawing.coordinate_system),
+        WorldCoordinates => DrawingPropValue::WorldCoordinates(drawing.world_coordinates),
+        Dpi => DrawingPropValue::Dpi(drawing.dpi),
+        DisconnectBehavior => DrawingPropValue::DisconnectBehavior(drawing.disconnect_behavior),
+        Tracer => DrawingPropValue::Tracer(drawing.tracer),
+        IsAnimated => DrawingPropValue::IsAnimated(drawing.is_animated),
+        PauseKey => DrawingPropValue::PauseKey(drawing.pause_key),
+        StepKey => DrawingPropValue::StepKey(drawing.step_key),
+        Navigation => DrawingPropValue::Navigation(drawing.navigation),
+        #[cfg(feature = "shaders")]
+        PostShader => DrawingPropValue::PostShader(drawing.post_shader.clone()),
     };
 
     conn.send(ServerResponse::DrawingProp(value))?;
@@ -38,6 +57,13 @@ pub(crate) fn set_drawing_prop(
 ) -> Result<(), HandlerError> {
     let mut drawing = app.drawing_mut();
 
+    // The center is given in the coordinate system of the drawing, but stored in logical
+    // coordinates so that rendering does not depend on the coordinate system
+    let prop_value = match prop_value {
+        DrawingPropValue::Center(center) => DrawingPropValue::Center(drawing.to_logical(center)),
+        prop_value => prop_value,
+    };
+
     modify_drawing(&mut drawing, event_loop, prop_value)
 }
 
@@ -51,8 +77,11 @@ pub(crate) fn reset_drawing_prop(
     use DrawingProp::*;
     modify_drawing(&mut drawing, event_loop, match prop {
         Title => DrawingPropValue::Title(DrawingState::DEFAULT_TITLE.to_string()),
+        Icon => DrawingPropValue::Icon(DrawingState::DEFAULT_ICON),
         Background => DrawingPropValue::Background(DrawingState::DEFAULT_BACKGROUND),
+        BackgroundImage => DrawingPropValue::BackgroundImage(DrawingState::DEFAULT_BACKGROUND_IMAGE),
         Center => DrawingPropValue::Center(DrawingState::DEFAULT_CENTER),
+        Zoom => DrawingPropValue::Zoom(DrawingState::DEFAULT_ZOOM),
         Size => DrawingPropValue::Size(crate::Size {
             width: DrawingState::DEFAULT_WIDTH,
             height: DrawingState::DEFAULT_HEIGHT,
@@ -61,10 +90,26 @@ pub(crate) fn reset_drawing_prop(
         Height => DrawingPropValue::Height(DrawingState::DEFAULT_HEIGHT),
         IsMaximized => DrawingPropValue::IsMaximized(DrawingState::DEFAULT_IS_MAXIMIZED),
         IsFullscreen => DrawingPropValue::IsFullscreen(DrawingState::DEFAULT_IS_FULLSCREEN),
+        ResizeAnchor => DrawingPropValue::ResizeAnchor(DrawingState::DEFAULT_RESIZE_ANCHOR),
+        Minimap => DrawingPropValue::Minimap(DrawingState::DEFAULT_MINIMAP),
+        Filters => DrawingPropValue::Filters(DrawingState::DEFAULT_FILTERS),
+        Symmetry => DrawingPropValue::Symmetry(DrawingState::DEFAULT_SYMMETRY),
+        PressurePenWidth => DrawingPropValue::PressurePenWidth(DrawingState::DEFAULT_PRESSURE_PEN_WIDTH),
+        CoordinateSystem => DrawingPropValue::CoordinateSystem(DrawingState::DEFAULT_COORDINATE_SYSTEM),
+        WorldCoordinates => DrawingPropValue::WorldCoordinates(DrawingState::DEFAULT_WORLD_COORDINATES),
+        Dpi => DrawingPropValue::Dpi(DrawingState::DEFAULT_DPI),
+        DisconnectBehavior => DrawingPropValue::DisconnectBehavior(DrawingState::DEFAULT_DISCONNECT_BEHAVIOR),
+        Tracer => DrawingPropValue::Tracer(DrawingState::DEFAULT_TRACER),
+        IsAnimated => DrawingPropValue::IsAnimated(DrawingState::DEFAULT_IS_ANIMATED),
+        PauseKey => DrawingPropValue::PauseKey(DrawingState::DEFAULT_PAUSE_KEY),
+        StepKey => DrawingPropValue::StepKey(DrawingState::DEFAULT_STEP_KEY),
+        Navigation => DrawingPropValue::Navigation(DrawingState::DEFAULT_NAVIGATION),
+        #[cfg(feature = "shaders")]
+        PostShader => DrawingPropValue::PostShader(DrawingState::DEFAULT_POST_SHADER),
     })
 }
 
-fn modify_drawing(
+pub(super) fn modify_drawing(
     drawing: &mut DrawingState,
     event_loop: &EventLoopNotifier,
     prop_value: DrawingPropValue,
@@ -78,6 +123,13 @@ fn modify_drawing(
             event_loop.set_title(title)?;
         },
 
+        Icon(icon) => {
+            drawing.icon = icon;
+
+            // Signal the main thread to change this property on the window
+            event_loop.set_icon(icon)?;
+        },
+
         Background(background) => {
             drawing.background = background;
 
@@ -85,6 +137,13 @@ fn modify_drawing(
             event_loop.request_redraw()?;
         },
 
+        BackgroundImage(background_image) => {
+            drawing.background_image = background_image;
+
+            // Signal the main thread that the image has changed
+            event_loop.request_redraw()?;
+        },
+
         Center(center) => {
             drawing.center = center;
 
@@ -92,23 +151,29 @@ fn modify_drawing(
             event_loop.request_redraw()?;
         },
 
+        Zoom(zoom) => {
+            drawing.zoom = zoom;
+
+            // Signal the main thread that the image has changed
+            event_loop.request_redraw()?;
+        },
+
         Size(crate::Size {width, height}) => {
-            drawing.width = width;
-            drawing.height = height;
+            drawing.resize(width, height);
 
             // Signal the main thread to change this property on the window
             event_loop.set_size((width, height))?;
         },
 
         Width(width) => {
-            drawing.width = width;
+            drawing.resize(width, drawing.height);
 
             // Signal the main thread to change this property on the window
             event_loop.set_size((width, drawing.height))?;
         },
 
         Height(height) => {
-            drawing.height = height;
+            drawing.resize(drawing.width, height);
 
             // Signal the main thread to change this property on the window
             event_loop.set_size((drawing.width, height))?;
@@ -127,7 +192,93 @@ fn modify_drawing(
             // Signal the main thread to change this property on the window
             event_loop.set_is_fullscreen(is_fullscreen)?;
         },
+
+        ResizeAnchor(resize_anchor) => {
+            drawing.resize_anchor = resize_anchor;
+        },
+
+        Minimap(minimap) => {
+            drawing.minimap = minimap;
+
+            // Signal the main thread that the image has changed
+            event_loop.request_redraw()?;
+        },
+
+        Filters(filters) => {
+            drawing.filters = filters;
+
+            // Signal the main thread that the image has changed
+            event_loop.request_redraw()?;
+        },
+
+        Symmetry(symmetry) => {
+            drawing.symmetry = symmetry;
+
+            // Signal the main thread that the image has changed
+            event_loop.request_redraw()?;
+        },
+
+        PressurePenWidth(pressure_pen_width) => {
+            drawing.pressure_pen_width = pressure_pen_width;
+        },
+
+        CoordinateSystem(coordinate_system) => {
+            drawing.coordinate_system = coordinate_system;
+            // Choosing a coordinate system replaces the world coordinates
+            drawing.world_coordinates = None;
+        },
+
+        WorldCoordinates(world_coordinates) => {
+            drawing.world_coordinates = world_coordinates;
+        },
+
+        Dpi(dpi) => {
+            drawing.dpi = dpi;
+        },
+
+        DisconnectBehavior(disconnect_behavior) => {
+            drawing.disconnect_behavior = disconnect_behavior;
+        },
+
+        Tracer(tracer) => {
+            drawing.tracer = tracer;
+
+            // Anything that was skipped is shown once every update is shown again
+            if tracer == 1 {
+                event_loop.flush()?;
+            }
+        },
+
+        IsAnimated(is_animated) => {
+            drawing.is_animated = is_animated;
+        },
+
+        PauseKey(pause_key) => {
+            drawing.pause_key = pause_key;
+        },
+
+        StepKey(step_key) => {
+            drawing.step_key = step_key;
+        },
+
+        Navigation(navigation) => {
+            drawing.navigation = navigation;
+        },
+
+        #[cfg(feature = "shaders")]
+        PostShader(post_shader) => {
+            drawing.post_shader = post_shader;
+
+            // Signal the main thread that the image has changed
+            event_loop.request_redraw()?;
+        },
     }
 
     Ok(())
 }
+
+pub(crate) fn flush(event_loop: &EventLoopNotifier) -> Result<(), HandlerError> {
+    event_loop.flush()?;
+
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use std::path::Path;
 
-use crate::ipc_protocol::{ServerOneshotSender, ServerResponse, ExportFormat};
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse, ExportFormat, ToolpathFormat};
+use crate::{ExportOptions, PlotterOptions};
 
 use super::HandlerError;
 use super::super::{
     app::App,
-    renderer::{export, display_list::DisplayList},
+    renderer::{export, plotter, display_list::DisplayList},
 };
 
 pub(crate) fn export_drawings(
@@ -14,12 +15,47 @@ pub(crate) fn export_drawings(
     display_list: &DisplayList,
     path: &Path,
     format: ExportFormat,
+    options: &ExportOptions,
 ) -> Result<(), HandlerError> {
     let drawing = app.drawing();
 
     use ExportFormat::*;
     let res = match format {
-        Svg => export::save_svg(display_list, drawing, path),
+        Svg => export::save_svg(display_list, drawing, app.background_image(), options, path),
+        Png => export::save_png(display_list, drawing, app.background_image(), options, path),
+    };
+
+    conn.send(ServerResponse::ExportComplete(res))?;
+
+    Ok(())
+}
+
+pub(crate) fn export_svg(
+    conn: ServerOneshotSender,
+    app: &App,
+    display_list: &DisplayList,
+    options: &ExportOptions,
+) -> Result<(), HandlerError> {
+    let svg = export::svg_string(display_list, app.drawing(), app.background_image(), options);
+    conn.send(ServerResponse::Svg(svg))?;
+
+    Ok(())
+}
+
+pub(crate) fn export_toolpath(
+    conn: ServerOneshotSender,
+    app: &App,
+    display_list: &DisplayList,
+    path: &Path,
+    format: ToolpathFormat,
+    options: &PlotterOptions,
+) -> Result<(), HandlerError> {
+    let drawing = app.drawing();
+
+    use ToolpathFormat::*;
+    let res = match format {
+        Gcode => plotter::save_gcode(display_list, drawing, options, path),
+        Hpgl => plotter::save_hpgl(display_list, drawing, options, path),
     };
 
     conn.send(ServerResponse::ExportComplete(res))?;
@@ -0,0 +1,14 @@
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+
+use super::HandlerError;
+use super::super::renderer::{mesh, display_list::DisplayList};
+
+pub(crate) fn export_mesh(
+    conn: ServerOneshotSender,
+    display_list: &DisplayList,
+) -> Result<(), HandlerError> {
+    let (vertices, indices) = mesh::tessellate(display_list);
+    conn.send(ServerResponse::Mesh(vertices, indices))?;
+
+    Ok(())
+}
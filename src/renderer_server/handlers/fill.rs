@@ -13,14 +13,15 @@ pub(crate) fn begin_fill(
 ) -> Result<(), HandlerError> {
     let turtle = app.turtle_mut(id);
 
-    let TurtleDrawings {state: turtle, drawings, current_fill_polygon} = turtle;
+    let TurtleDrawings {state: turtle, drawings, current_fill_polygon, ..} = turtle;
 
     // Ignore the request if we are already filling
     if current_fill_polygon.is_some() {
         return Ok(());
     }
 
-    let poly_handle = display_list.push_polygon_start(turtle.position, turtle.fill_color);
+    let poly_handle = display_list.push_polygon_start(turtle.position, turtle.fill_color, turtle.fill_rule);
+    display_list.set_layer(poly_handle, turtle.layer);
     drawings.push(poly_handle);
     *current_fill_polygon = Some(poly_handle);
 
@@ -0,0 +1,20 @@
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+
+use super::HandlerError;
+use super::super::{
+    app::App,
+    renderer::image::{ImageData, ImageHandle},
+};
+
+pub(crate) fn load_image(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    image: ImageData,
+) -> Result<(), HandlerError> {
+    let (width, height) = (image.width, image.height);
+    let id = app.add_image(image);
+
+    conn.send(ServerResponse::ImageLoaded(ImageHandle::new(id, width, height)))?;
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+use crate::ImportTransform;
+
+use super::HandlerError;
+use super::super::{
+    event_loop_notifier::EventLoopNotifier,
+    renderer::{import, display_list::DisplayList},
+};
+
+pub(crate) fn import_drawings(
+    conn: ServerOneshotSender,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    path: &Path,
+    transform: &ImportTransform,
+) -> Result<(), HandlerError> {
+    let res = import::import_svg(display_list, path, transform);
+    let is_ok = res.is_ok();
+
+    conn.send(ServerResponse::ImportComplete(res))?;
+
+    if is_ok {
+        event_loop.request_redraw()?;
+    }
+
+    Ok(())
+}
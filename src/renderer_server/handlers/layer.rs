@@ -0,0 +1,61 @@
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+
+use super::HandlerError;
+use super::super::{
+    event_loop_notifier::EventLoopNotifier,
+    app::{App, LayerId},
+    renderer::display_list::DisplayList,
+};
+
+pub(crate) fn add_layer(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+) -> Result<(), HandlerError> {
+    let id = app.next_layer_id();
+    display_list.add_layer(id);
+
+    conn.send(ServerResponse::NewLayer(id))?;
+
+    Ok(())
+}
+
+pub(crate) fn layer_is_visible(
+    conn: ServerOneshotSender,
+    display_list: &DisplayList,
+    layer: LayerId,
+) -> Result<(), HandlerError> {
+    conn.send(ServerResponse::LayerVisible(display_list.is_layer_visible(layer)))?;
+
+    Ok(())
+}
+
+pub(crate) fn set_layer_visible(
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    layer: LayerId,
+    is_visible: bool,
+) -> Result<(), HandlerError> {
+    if display_list.is_layer_visible(layer) != is_visible {
+        display_list.set_layer_visible(layer, is_visible);
+
+        // Signal the main thread that the image has changed
+        event_loop.request_redraw()?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn move_layer(
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    layer: LayerId,
+    top: bool,
+) -> Result<(), HandlerError> {
+    display_list.move_layer(layer, top);
+
+    // Signal the main thread that the image has changed
+    event_loop.request_redraw()?;
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+use crate::TextOptions;
+
+use super::HandlerError;
+use super::super::renderer::text;
+
+pub(crate) fn measure_text(
+    conn: ServerOneshotSender,
+    content: &str,
+    options: &TextOptions,
+) -> Result<(), HandlerError> {
+    conn.send(ServerResponse::TextSize(text::measure_text(content, options)))?;
+
+    Ok(())
+}
+
+pub(crate) fn font_metrics(
+    conn: ServerOneshotSender,
+    options: &TextOptions,
+) -> Result<(), HandlerError> {
+    conn.send(ServerResponse::FontMetrics(text::font_metrics(options)))?;
+
+    Ok(())
+}
@@ -1,18 +1,17 @@
-use tokio::sync::mpsc::{self, error::TryRecvError};
+use tokio::sync::mpsc::error::TryRecvError;
 
 use crate::ipc_protocol::{
     ServerOneshotSender,
     ServerResponse,
 };
-use crate::Event;
-
 use super::HandlerError;
+use super::super::event_queue::EventQueue;
 
 pub(crate) fn poll_event(
     conn: ServerOneshotSender,
-    events_receiver: &mut mpsc::UnboundedReceiver<Event>,
+    events: &mut EventQueue,
 ) -> Result<(), HandlerError> {
-    let event = match events_receiver.try_recv() {
+    let event = match events.try_recv() {
         Ok(event) => Some(event),
         Err(TryRecvError::Empty) => None,
         // The main thread must have ended so no more events will be sent ever
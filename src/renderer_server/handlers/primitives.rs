@@ -0,0 +1,34 @@
+use crate::{Point, PrimitiveId};
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+
+use super::HandlerError;
+use super::super::renderer::{primitive, display_list::DisplayList};
+
+pub(crate) fn primitives(
+    conn: ServerOneshotSender,
+    display_list: &DisplayList,
+) -> Result<(), HandlerError> {
+    conn.send(ServerResponse::Primitives(primitive::snapshot(display_list)))?;
+
+    Ok(())
+}
+
+pub(crate) fn primitive(
+    conn: ServerOneshotSender,
+    display_list: &DisplayList,
+    id: PrimitiveId,
+) -> Result<(), HandlerError> {
+    conn.send(ServerResponse::Primitive(primitive::get(display_list, id)))?;
+
+    Ok(())
+}
+
+pub(crate) fn hit_test(
+    conn: ServerOneshotSender,
+    display_list: &DisplayList,
+    point: Point,
+) -> Result<(), HandlerError> {
+    conn.send(ServerResponse::HitTest(primitive::hit_test(display_list, point)))?;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::ipc_protocol::{ServerOneshotSender, ServerSender, ServerResponse};
+use crate::PrintOptions;
+
+use super::HandlerError;
+use super::super::{
+    handle_handler_result,
+    app::App,
+    renderer::{export, display_list::DisplayList},
+};
+
+pub(crate) fn print_drawings(
+    conn: ServerOneshotSender,
+    server_conn: &ServerSender,
+    app: &App,
+    display_list: &DisplayList,
+    options: &PrintOptions,
+) -> Result<(), HandlerError> {
+    let drawing = app.drawing();
+
+    let page = match export::print_page(display_list, drawing, app.background_image(), options) {
+        Ok(page) => page,
+        Err(err) => {
+            conn.send(ServerResponse::ExportComplete(Err(err)))?;
+            return Ok(());
+        },
+    };
+
+    // Printing waits for the operating system (and possibly a print dialog), so requests from
+    // other clients continue to be handled in the meantime
+    let client_id = conn.client_id();
+    let server_conn = server_conn.clone();
+    tokio::spawn(async move {
+        let res = page.print_in_background().await;
+
+        handle_handler_result(server_conn.send(client_id, ServerResponse::ExportComplete(res))
+            .map_err(HandlerError::IpcChannelError));
+    });
+
+    Ok(())
+}
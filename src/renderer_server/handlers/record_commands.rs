@@ -0,0 +1,21 @@
+use std::path::Path;
+
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse, CommandLog};
+
+use super::HandlerError;
+
+pub(crate) fn record_commands(
+    conn: ServerOneshotSender,
+    command_log: &mut Option<CommandLog>,
+    path: &Path,
+) -> Result<(), HandlerError> {
+    // Any log that was already being written is finished, even if the new one cannot be created
+    *command_log = None;
+    let res = CommandLog::create(path).map(|log| {
+        *command_log = Some(log);
+    }).map_err(Into::into);
+
+    conn.send(ServerResponse::ExportComplete(res))?;
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+
+use super::HandlerError;
+use super::super::renderer::recorder::GifRecorder;
+
+pub(crate) fn start_recording(
+    conn: ServerOneshotSender,
+    recorder: &GifRecorder,
+    path: &Path,
+    fps: f64,
+) -> Result<(), HandlerError> {
+    let res = recorder.start(path, fps);
+
+    conn.send(ServerResponse::ExportComplete(res))?;
+
+    Ok(())
+}
+
+pub(crate) fn stop_recording(
+    conn: ServerOneshotSender,
+    recorder: &GifRecorder,
+) -> Result<(), HandlerError> {
+    // The recorder responds once the GIF has been written
+    recorder.stop(conn.client_id());
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+
+use super::HandlerError;
+use super::super::{
+    event_loop_notifier::EventLoopNotifier,
+    app::{TurtleId, App},
+    state::SavedState,
+    renderer::display_list::DisplayList,
+};
+
+pub(crate) fn push_state(
+    app: &mut App,
+    id: TurtleId,
+) -> Result<(), HandlerError> {
+    let turtle = app.turtle_mut(id);
+
+    let saved = SavedState::new(&turtle.state);
+    turtle.saved_states.push(saved);
+
+    Ok(())
+}
+
+pub(crate) fn pop_state(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    id: TurtleId,
+) -> Result<(), HandlerError> {
+    let turtle = app.turtle_mut(id);
+
+    let saved = turtle.saved_states.pop();
+    let popped = saved.is_some();
+
+    if let Some(SavedState {position, heading, pen, fill_color}) = saved {
+        // Jumping back is a movement, so it can be undone like one
+        turtle.save_undo_step(display_list);
+
+        turtle.pen_color_transition = None;
+        let state = &mut turtle.state;
+        state.position = position;
+        state.heading = heading;
+        state.pen = pen;
+        state.fill_color = fill_color;
+
+        // Update the current fill polygon to the restored color
+        if let Some(poly_handle) = turtle.current_fill_polygon {
+            display_list.polygon_set_fill_color(poly_handle, fill_color);
+        }
+
+        // Signal the main thread that the image has changed
+        event_loop.request_redraw()?;
+    }
+
+    conn.send(ServerResponse::StatePopped(popped))?;
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse, DrawingPropValue};
+use crate::{Size, Shape};
+
+use super::HandlerError;
+use super::drawing_prop::modify_drawing;
+use super::super::{
+    event_loop_notifier::EventLoopNotifier,
+    app::{App, LayerId},
+    animation::{self, AnimationRunner},
+    session::{Session, SavedDrawing},
+    renderer::display_list::DisplayList,
+};
+
+pub(crate) fn save_session(
+    conn: ServerOneshotSender,
+    app: &App,
+    display_list: &DisplayList,
+    path: &Path,
+) -> Result<(), HandlerError> {
+    let res = Session::capture(app, display_list).save(path);
+
+    conn.send(ServerResponse::SessionComplete(res))?;
+
+    Ok(())
+}
+
+pub(crate) fn load_session(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    anim_runner: &AnimationRunner,
+    path: &Path,
+) -> Result<(), HandlerError> {
+    let session = match Session::load(path) {
+        Ok(session) => session,
+        Err(err) => {
+            conn.send(ServerResponse::SessionComplete(Err(err)))?;
+            return Ok(());
+        },
+    };
+    let Session {drawing, turtles, primitives, ..} = session;
+
+    // Stop all animations so that none of them go on to update what is about to be replaced
+    anim_runner.stop_all();
+    animation::clear_drawings(app, display_list);
+
+    for prim in primitives.iter().filter_map(|prim| prim.to_draw_prim()) {
+        display_list.push_prim(prim);
+    }
+
+    // Turtles are matched up in the order that they were created. Any extra saved turtles are
+    // ignored since there is no turtle in this program to give their state to.
+    for ((_, turtle), mut state) in app.turtles_mut().zip(turtles) {
+        // Layers and images only exist while the program that created them is running, and
+        // everything that was drawn is loaded into the base layer
+        state.layer = LayerId::BASE;
+        if let Shape::Image(_) = state.shape {
+            state.shape = Shape::default();
+        }
+
+        turtle.state = state;
+        turtle.saved_states.clear();
+        turtle.pen_color_transition = None;
+    }
+
+    let SavedDrawing {title, background, center, zoom, width, height} = drawing;
+    let drawing = app.drawing_mut();
+    for prop_value in [
+        DrawingPropValue::Title(title),
+        DrawingPropValue::Background(background),
+        DrawingPropValue::Center(center),
+        DrawingPropValue::Zoom(zoom),
+        DrawingPropValue::Size(Size {width, height}),
+    ] {
+        modify_drawing(drawing, event_loop, prop_value)?;
+    }
+
+    conn.send(ServerResponse::SessionComplete(Ok(())))?;
+
+    // Signal the main thread that the image has changed
+    event_loop.request_redraw()?;
+
+    Ok(())
+}
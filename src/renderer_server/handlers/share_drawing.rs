@@ -0,0 +1,33 @@
+use std::net::SocketAddr;
+
+use crate::ipc_protocol::{ServerOneshotSender, ServerSender, ServerResponse, Invite, publish_invite, serve_tcp};
+
+use super::HandlerError;
+
+pub(crate) fn share_drawing(
+    conn: ServerOneshotSender,
+    server_conn: &ServerSender,
+    invites: &mut Vec<Invite>,
+    name: &str,
+) -> Result<(), HandlerError> {
+    // Each invitation is kept until the server stops so that it is removed at that point
+    let res = publish_invite(server_conn.clone(), name).map(|invite| {
+        invites.push(invite);
+    }).map_err(Into::into);
+
+    conn.send(ServerResponse::ShareStarted(res))?;
+
+    Ok(())
+}
+
+pub(crate) fn share_tcp(
+    conn: ServerOneshotSender,
+    server_conn: &ServerSender,
+    addrs: &[SocketAddr],
+) -> Result<(), HandlerError> {
+    let res = serve_tcp(server_conn.clone(), addrs).map_err(Into::into);
+
+    conn.send(ServerResponse::TcpShareStarted(res))?;
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+use crate::{Shape, BlendMode, colors};
+
+use super::HandlerError;
+use super::super::{
+    event_loop_notifier::EventLoopNotifier,
+    app::{TurtleId, StampId, App},
+    state::Pen,
+    renderer::{
+        display_list::{DisplayList, Image, PrimHandle},
+        image::ImageHandle,
+    },
+};
+
+/// The pen used to outline stamps, matching the outline of the turtle in the window
+const STAMP_OUTLINE: Pen = Pen {
+    is_enabled: true,
+    thickness: 1.0,
+    color: colors::BLACK,
+    dash_pattern: Vec::new(),
+    blend_mode: BlendMode::Alpha,
+};
+
+pub(crate) fn stamp(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    id: TurtleId,
+) -> Result<(), HandlerError> {
+    app.turtle_mut(id).save_undo_step(display_list);
+
+    let turtle = &app.turtle(id).state;
+    let prims = match turtle.shape {
+        Shape::Image(image) => vec![push_image(app, display_list, id, image)],
+
+        ref shape => {
+            // The stamp looks just like the turtle: a white shape with a black outline
+            let outline = shape.outline_at(turtle.position, turtle.heading, turtle.shape_size);
+            let mut prims = vec![display_list.push_polygon(outline.clone(), colors::WHITE)];
+            for (&start, &end) in outline.iter().zip(outline.iter().cycle().skip(1)) {
+                prims.extend(display_list.push_line(start, end, &STAMP_OUTLINE));
+            }
+            prims
+        },
+    };
+
+    let stamp_id = add_stamp(app, display_list, id, prims);
+
+    // Signal the main thread that the image has changed
+    event_loop.request_redraw()?;
+
+    conn.send(ServerResponse::NewStamp(stamp_id))?;
+
+    Ok(())
+}
+
+pub(crate) fn stamp_image(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    id: TurtleId,
+    image: ImageHandle,
+) -> Result<(), HandlerError> {
+    app.turtle_mut(id).save_undo_step(display_list);
+
+    let prim = push_image(app, display_list, id, image);
+    let stamp_id = add_stamp(app, display_list, id, vec![prim]);
+
+    // Signal the main thread that the image has changed
+    event_loop.request_redraw()?;
+
+    conn.send(ServerResponse::NewStamp(stamp_id))?;
+
+    Ok(())
+}
+
+pub(crate) fn clear_stamp(
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    id: TurtleId,
+    stamp_id: StampId,
+) -> Result<(), HandlerError> {
+    let turtle = app.turtle_mut(id);
+
+    // Ignore stamps that were already cleared (e.g. by clearing the turtle's drawings)
+    let index = match turtle.stamps.iter().position(|&(other_id, _)| other_id == stamp_id) {
+        Some(index) => index,
+        None => return Ok(()),
+    };
+    let (_, prims) = turtle.stamps.remove(index);
+
+    turtle.drawings.retain(|handle| !prims.contains(handle));
+    display_list.remove(prims.into_iter());
+
+    // Signal the main thread that the image has changed
+    event_loop.request_redraw()?;
+
+    Ok(())
+}
+
+pub(crate) fn clear_stamps(
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    id: TurtleId,
+) -> Result<(), HandlerError> {
+    let turtle = app.turtle_mut(id);
+
+    let prims: Vec<_> = turtle.stamps.drain(..).flat_map(|(_, prims)| prims).collect();
+    turtle.drawings.retain(|handle| !prims.contains(handle));
+    display_list.remove(prims.into_iter());
+
+    // Signal the main thread that the image has changed
+    event_loop.request_redraw()?;
+
+    Ok(())
+}
+
+/// Draws the given image where the turtle would be if the image was its shape
+fn push_image(app: &App, display_list: &mut DisplayList, id: TurtleId, image: ImageHandle) -> PrimHandle {
+    let data = app.image(image.id).clone();
+    let turtle = &app.turtle(id).state;
+
+    let image = Image::placed(image.id, data, turtle.position, turtle.heading, turtle.shape_size);
+    display_list.push_image(image)
+}
+
+/// Records that the given drawings make up a new stamp of the given turtle
+fn add_stamp(app: &mut App, display_list: &mut DisplayList, id: TurtleId, prims: Vec<PrimHandle>) -> StampId {
+    let stamp_id = app.next_stamp_id();
+
+    let turtle = app.turtle_mut(id);
+    for &prim in &prims {
+        display_list.set_layer(prim, turtle.state.layer);
+    }
+    turtle.drawings.extend(prims.iter().copied());
+    turtle.stamps.push((stamp_id, prims));
+
+    stamp_id
+}
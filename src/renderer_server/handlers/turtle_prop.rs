@@ -11,7 +11,7 @@ use super::HandlerError;
 use super::super::{
     event_loop_notifier::EventLoopNotifier,
     state::{self, TurtleState},
-    app::{TurtleId, TurtleDrawings, App},
+    app::{TurtleId, TurtleDrawings, LayerId, App},
     renderer::display_list::DisplayList,
 };
 
@@ -21,24 +21,37 @@ pub(crate) fn turtle_prop(
     id: TurtleId,
     prop: TurtleProp,
 ) -> Result<(), HandlerError> {
+    let drawing = app.drawing();
     let turtle = app.turtle(id);
+    let pen_color = turtle.pen_color();
 
     let TurtleDrawings {state: turtle, current_fill_polygon, ..} = turtle;
 
+    // Positions and headings are reported in the coordinate system of the drawing
+    let position = drawing.to_user(turtle.position);
+    let heading = drawing.heading_to_user(turtle.heading);
+
     use TurtleProp::*;
     use PenProp::*;
     let value = match prop {
         Pen(IsEnabled) => TurtlePropValue::Pen(PenPropValue::IsEnabled(turtle.pen.is_enabled)),
         Pen(Thickness) => TurtlePropValue::Pen(PenPropValue::Thickness(turtle.pen.thickness)),
-        Pen(Color) => TurtlePropValue::Pen(PenPropValue::Color(turtle.pen.color)),
+        Pen(Color) => TurtlePropValue::Pen(PenPropValue::Color(pen_color)),
+        Pen(DashPattern) => TurtlePropValue::Pen(PenPropValue::DashPattern(turtle.pen.dash_pattern.clone())),
+        Pen(BlendMode) => TurtlePropValue::Pen(PenPropValue::BlendMode(turtle.pen.blend_mode)),
         FillColor => TurtlePropValue::FillColor(turtle.fill_color),
+        FillRule => TurtlePropValue::FillRule(turtle.fill_rule),
         IsFilling => TurtlePropValue::IsFilling(current_fill_polygon.is_some()),
-        Position => TurtlePropValue::Position(turtle.position),
-        PositionX => TurtlePropValue::PositionX(turtle.position.x),
-        PositionY => TurtlePropValue::PositionY(turtle.position.y),
-        Heading => TurtlePropValue::Heading(turtle.heading),
+        Position => TurtlePropValue::Position(position),
+        PositionX => TurtlePropValue::PositionX(position.x),
+        PositionY => TurtlePropValue::PositionY(position.y),
+        Heading => TurtlePropValue::Heading(heading),
         Speed => TurtlePropValue::Speed(turtle.speed),
+        Easing => TurtlePropValue::Easing(turtle.easing),
         IsVisible => TurtlePropValue::IsVisible(turtle.is_visible),
+        Shape => TurtlePropValue::Shape(turtle.shape.clone()),
+        ShapeSize => TurtlePropValue::ShapeSize(turtle.shape_size),
+        Layer => TurtlePropValue::Layer(turtle.layer),
     };
 
     conn.send(ServerResponse::TurtleProp(id, value))?;
@@ -55,14 +68,20 @@ pub(crate) fn set_turtle_prop(
 ) -> Result<(), HandlerError> {
     let turtle = app.turtle_mut(id);
 
-    let TurtleDrawings {state: turtle, current_fill_polygon, ..} = turtle;
+    let TurtleDrawings {state: turtle, current_fill_polygon, pen_color_transition, ..} = turtle;
 
     use TurtlePropValue::*;
     use PenPropValue::*;
     match prop_value {
         Pen(IsEnabled(is_enabled)) => turtle.pen.is_enabled = is_enabled,
         Pen(Thickness(thickness)) => turtle.pen.thickness = thickness,
-        Pen(Color(color)) => turtle.pen.color = color,
+        Pen(Color(color)) => {
+            // Setting the color stops any transition to another color
+            *pen_color_transition = None;
+            turtle.pen.color = color;
+        },
+        Pen(DashPattern(pattern)) => turtle.pen.dash_pattern = pattern,
+        Pen(BlendMode(blend_mode)) => turtle.pen.blend_mode = blend_mode,
 
         FillColor(fill_color) => {
             turtle.fill_color = fill_color;
@@ -75,6 +94,17 @@ pub(crate) fn set_turtle_prop(
                 event_loop.request_redraw()?;
             }
         },
+        FillRule(fill_rule) => {
+            turtle.fill_rule = fill_rule;
+
+            // Update the current fill polygon to the new rule
+            if let Some(poly_handle) = *current_fill_polygon {
+                display_list.polygon_set_fill_rule(poly_handle, fill_rule);
+
+                // Signal the main thread that the image has changed
+                event_loop.request_redraw()?;
+            }
+        },
 
         IsFilling(_) => unreachable!("bug: should have used `BeginFill` and `EndFill` instead"),
         Position(_) |
@@ -83,6 +113,7 @@ pub(crate) fn set_turtle_prop(
         Heading(_) => unreachable!("bug: should have used `RotateInPlace` instead"),
 
         Speed(speed) => turtle.speed = speed,
+        Easing(easing) => turtle.easing = easing,
 
         IsVisible(is_visible) => {
             turtle.is_visible = is_visible;
@@ -90,6 +121,27 @@ pub(crate) fn set_turtle_prop(
             // Signal the main thread that the image has changed
             event_loop.request_redraw()?;
         },
+
+        Shape(shape) => {
+            turtle.shape = shape;
+
+            // Signal the main thread that the image has changed
+            event_loop.request_redraw()?;
+        },
+
+        ShapeSize(size) => {
+            turtle.shape_size = size;
+
+            // Signal the main thread that the image has changed
+            event_loop.request_redraw()?;
+        },
+
+        Layer(layer) => {
+            turtle.layer = layer;
+
+            // The turtle is hidden if its new layer is hidden
+            event_loop.request_redraw()?;
+        },
     }
 
     Ok(())
@@ -104,7 +156,7 @@ pub(crate) fn reset_turtle_prop(
 ) -> Result<(), HandlerError> {
     let turtle = app.turtle_mut(id);
 
-    let TurtleDrawings {state: turtle, current_fill_polygon, ..} = turtle;
+    let TurtleDrawings {state: turtle, current_fill_polygon, pen_color_transition, ..} = turtle;
 
     let mut drawing_changed = false;
 
@@ -113,7 +165,12 @@ pub(crate) fn reset_turtle_prop(
     match prop {
         Pen(IsEnabled) => turtle.pen.is_enabled = state::Pen::DEFAULT_IS_ENABLED,
         Pen(Thickness) => turtle.pen.thickness = state::Pen::DEFAULT_THICKNESS,
-        Pen(Color) => turtle.pen.color = state::Pen::DEFAULT_COLOR,
+        Pen(Color) => {
+            *pen_color_transition = None;
+            turtle.pen.color = state::Pen::DEFAULT_COLOR;
+        },
+        Pen(DashPattern) => turtle.pen.dash_pattern = Vec::new(),
+        Pen(BlendMode) => turtle.pen.blend_mode = crate::BlendMode::default(),
 
         FillColor => {
             turtle.fill_color = TurtleState::DEFAULT_FILL_COLOR;
@@ -125,6 +182,16 @@ pub(crate) fn reset_turtle_prop(
                 drawing_changed = true;
             }
         },
+        FillRule => {
+            turtle.fill_rule = crate::FillRule::default();
+
+            // Update the current fill polygon to the new rule
+            if let Some(poly_handle) = *current_fill_polygon {
+                display_list.polygon_set_fill_rule(poly_handle, crate::FillRule::default());
+
+                drawing_changed = true;
+            }
+        },
 
         IsFilling => unreachable!("bug: should have used `BeginFill` and `EndFill` instead"),
 
@@ -147,11 +214,25 @@ pub(crate) fn reset_turtle_prop(
         },
 
         Speed => turtle.speed = crate::Speed::default(),
+        Easing => turtle.easing = crate::Easing::default(),
 
         IsVisible => {
             turtle.is_visible = TurtleState::DEFAULT_IS_VISIBLE;
             drawing_changed = true;
         },
+
+        Shape => {
+            turtle.shape = crate::Shape::default();
+            drawing_changed = true;
+        },
+        ShapeSize => {
+            turtle.shape_size = TurtleState::DEFAULT_SHAPE_SIZE;
+            drawing_changed = true;
+        },
+        Layer => {
+            turtle.layer = LayerId::BASE;
+            drawing_changed = true;
+        },
     }
 
     if drawing_changed {
@@ -170,13 +251,15 @@ pub(crate) fn reset_turtle(
 ) -> Result<(), HandlerError> {
     let turtle = app.turtle_mut(id);
 
-    let TurtleDrawings {state: turtle, current_fill_polygon, ..} = turtle;
+    let TurtleDrawings {state: turtle, current_fill_polygon, pen_color_transition, ..} = turtle;
 
     *turtle = TurtleState::default();
+    *pen_color_transition = None;
 
-    // Update the current fill polygon to the new color
+    // Update the current fill polygon to the new color and rule
     if let Some(poly_handle) = *current_fill_polygon {
         display_list.polygon_set_fill_color(poly_handle, TurtleState::DEFAULT_FILL_COLOR);
+        display_list.polygon_set_fill_rule(poly_handle, crate::FillRule::default());
     }
 
     // Signal the main thread that the image has changed
@@ -0,0 +1,25 @@
+use super::HandlerError;
+use super::super::{
+    event_loop_notifier::EventLoopNotifier,
+    app::{TurtleId, App},
+    renderer::display_list::DisplayList,
+};
+
+pub(crate) fn undo(
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    id: TurtleId,
+    count: usize,
+) -> Result<(), HandlerError> {
+    let turtle = app.turtle_mut(id);
+
+    let undone = (0..count).take_while(|_| turtle.undo(display_list)).count();
+
+    if undone > 0 {
+        // Signal the main thread that the image has changed
+        event_loop.request_redraw()?;
+    }
+
+    Ok(())
+}
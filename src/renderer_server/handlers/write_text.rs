@@ -0,0 +1,64 @@
+use crate::ipc_protocol::{ServerOneshotSender, ServerResponse};
+use crate::radians::Radians;
+use crate::{TextAlign, TextOptions};
+
+use super::HandlerError;
+use super::super::{
+    event_loop_notifier::EventLoopNotifier,
+    app::{TurtleId, App},
+    renderer::{display_list::{DisplayList, Image}, text::{self, TextPart}},
+};
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn write_text(
+    conn: ServerOneshotSender,
+    app: &mut App,
+    display_list: &mut DisplayList,
+    event_loop: &EventLoopNotifier,
+    id: TurtleId,
+    content: &str,
+    options: &TextOptions,
+    align: TextAlign,
+) -> Result<(), HandlerError> {
+    let turtle = app.turtle_mut(id);
+    turtle.save_undo_step(display_list);
+    turtle.update_pen_color();
+    let position = turtle.state.position;
+    let pen_color = turtle.state.pen.color;
+    let layer = turtle.state.layer;
+
+    let written = text::layout_text(content, options, align, position).and_then(|parts| {
+        // Text is written even if the pen is up, just like in Python's turtle module
+        for part in parts {
+            let handle = match part {
+                TextPart::Outlines {outlines, color} => {
+                    display_list.push_text(outlines, color.unwrap_or(pen_color))
+                },
+
+                TextPart::Bitmap {key, image, center, scale} => {
+                    let image_id = app.add_glyph_image(key, image);
+                    let data = app.image(image_id).clone();
+                    // Images are placed the way a turtle facing up would be drawn
+                    let up = Radians::from_degrees_value(90.0);
+                    Some(display_list.push_image(Image::placed(image_id, data, center, up, scale)))
+                },
+            };
+
+            if let Some(handle) = handle {
+                display_list.set_layer(handle, layer);
+                app.turtle_mut(id).drawings.push(handle);
+            }
+        }
+
+        text::measure_text(content, options)
+    });
+
+    if written.is_ok() {
+        // Signal the main thread that the image has changed
+        event_loop.request_redraw()?;
+    }
+
+    conn.send(ServerResponse::TextSize(written))?;
+
+    Ok(())
+}
@@ -7,8 +7,8 @@ use glutin::{
     ContextBuilder,
     WindowedContext,
     PossiblyCurrent,
-    dpi::{LogicalSize, PhysicalPosition},
-    window::{WindowBuilder, Fullscreen},
+    dpi::{LogicalSize, PhysicalSize, PhysicalPosition},
+    window::{WindowBuilder, Fullscreen, Icon},
     event::{
         Event as GlutinEvent,
         StartCause,
@@ -16,16 +16,21 @@ use glutin::{
         KeyboardInput,
         VirtualKeyCode,
         ElementState,
+        Touch,
+        MouseButton,
+        MouseScrollDelta,
     },
     event_loop::{ControlFlow, EventLoop},
     platform::run_return::EventLoopExtRunReturn,
 };
+use pathfinder_geometry::vector::{Vector2I, vec2i};
 use tokio::{
     sync::mpsc,
     runtime::Handle,
 };
 
 use crate::Event;
+use crate::event::{MouseTracker, PressedState};
 use crate::ipc_protocol::{ServerSender, ServerReceiver, ConnectionError};
 
 use super::{
@@ -36,6 +41,9 @@ use super::{
         display_list::{SharedDisplayList, DisplayList},
     },
     event_loop_notifier::{EventLoopNotifier, MainThreadAction},
+    navigation::Navigator,
+    Shortcut,
+    WindowChannels,
 };
 
 /// The maximum rendering FPS allowed
@@ -48,6 +56,9 @@ const MAX_RENDERING_FPS: u64 = 60;
 // 1,000,000 us in 1 s
 const MICROS_PER_SEC: u64 = 1_000_000;
 
+/// The number of pixels of smooth scrolling (e.g. on a touchpad) that count as one line scrolled
+const PIXELS_PER_SCROLL_LINE: f64 = 20.0;
+
 fn new_event_loop<T>() -> EventLoop<T> {
     cfg_if::cfg_if! {
         if #[cfg(target_os = "macos")] {
@@ -93,9 +104,14 @@ pub fn run_main(
 
     // A channel for transferring events
     let (events_sender, events_receiver) = mpsc::unbounded_channel();
+    #[cfg(feature = "gamepad")]
+    super::gamepad::spawn_gamepad_thread(events_sender.clone());
+    // A channel for the keyboard shortcuts of the drawing, like the pause key
+    let (shortcuts_sender, shortcuts) = mpsc::unbounded_channel();
     // Put these variables in an Option so we can call `take()` in the event loop. Required
     // because borrow checker cannot verify which events only fire once.
     let mut events_receiver = Some(events_receiver);
+    let mut shortcuts = Some(shortcuts);
     let mut establish_connection = Some(establish_connection);
     // Using a bounded (size = 1) channel because a oneshot consumes self when awaited and this
     // needs to be polled multiple times
@@ -125,12 +141,20 @@ pub fn run_main(
 
     // Need to draw using the physical size in pixels, not the logical size
     let draw_size = gl_context.window().inner_size();
-    let mut renderer = Renderer::new(draw_size, gl_context.window().scale_factor());
+    let mut renderer = Renderer::new(physical_size(draw_size), gl_context.window().scale_factor());
 
     // For rate limiting rendering
     let min_render_delay = Duration::from_micros(MICROS_PER_SEC / MAX_RENDERING_FPS);
     // Subtracting the delay so we do an initial render right away
     let mut last_render = Instant::now() - min_render_delay;
+    // For adding the position of the mouse to clicks and drags
+    let mut mouse = MouseTracker::default();
+    // The number of updates since the window was last redrawn, used to apply the tracer
+    let mut skipped_updates = 0;
+    // The shortcut key held down, if any, so that holding a key only triggers its shortcut once
+    let mut shortcut_key_down = None;
+    // For zooming and moving the view with the mouse while navigation is enabled
+    let mut navigator = Navigator::default();
     // Very important to use `run_return` here instead of `run` because `run` calls process::exit()
     // and that is not appropriate for the multithreaded backend as that would cause the entire
     // process to end when the window is closed.
@@ -148,9 +172,12 @@ pub fn run_main(
                 app.clone(),
                 display_list.clone(),
                 event_loop_notifier.clone(),
-                events_receiver.take().expect("bug: init event should only occur once"),
+                WindowChannels {
+                    events: events_receiver.take().expect("bug: init event should only occur once"),
+                    shortcuts: shortcuts.take().expect("bug: init event should only occur once"),
+                    shutdown: server_shutdown_receiver.take().expect("bug: init event should only occur once"),
+                },
                 establish_connection.take().expect("bug: init event should only occur once"),
-                server_shutdown_receiver.take().expect("bug: init event should only occur once"),
             );
         },
 
@@ -194,9 +221,41 @@ pub fn run_main(
                 WindowEvent::Resized(size) => {
                     let size = size.to_logical(scale_factor);
                     let mut app = app.write();
-                    let mut drawing = app.drawing_mut();
-                    drawing.width = size.width;
-                    drawing.height = size.height;
+                    let drawing = app.drawing_mut();
+                    drawing.resize(size.width, size.height);
+                },
+
+                // Update the pens before the event is sent so that anything drawn in response to
+                // it uses the new width
+                WindowEvent::Touch(Touch {force: Some(force), ..}) => {
+                    app.write().apply_touch_pressure(force.normalized());
+                },
+
+                // Navigation is handled here so that it works no matter what the program is doing
+                WindowEvent::MouseInput {state, button: MouseButton::Middle, ..} => {
+                    navigator.set_panning(state == ElementState::Pressed);
+                },
+                WindowEvent::CursorMoved {position, ..} => {
+                    let mut app = app.write();
+                    if navigator.cursor_moved(app.drawing_mut(), position.into(), scale_factor) {
+                        gl_context.window().request_redraw();
+                    }
+                },
+                WindowEvent::MouseWheel {delta, ..} => {
+                    let lines = match delta {
+                        MouseScrollDelta::LineDelta(_, y) => y as f64,
+                        MouseScrollDelta::PixelDelta(position) => position.y / PIXELS_PER_SCROLL_LINE,
+                    };
+                    let draw_size = gl_context.window().inner_size();
+                    let fb_center = ScreenPoint {
+                        x: draw_size.width as f64 / 2.0,
+                        y: draw_size.height as f64 / 2.0,
+                    };
+
+                    let mut app = app.write();
+                    if navigator.scroll(app.drawing_mut(), lines, scale_factor, fb_center) {
+                        gl_context.window().request_redraw();
+                    }
                 },
 
                 //TODO: There are currently no events for updating is_maximized, so that property
@@ -205,7 +264,8 @@ pub fn run_main(
                 _ => {},
             }
 
-            // Converts to logical coordinates, only locking the drawing if this is actually called
+            // Converts to the coordinate system of the drawing, only locking the drawing if this is
+            // actually called
             let to_logical = |pos: PhysicalPosition<f64>| {
                 let app = app.read();
                 let drawing = app.drawing();
@@ -216,17 +276,51 @@ pub fn run_main(
                     y: draw_size.height as f64 / 2.0,
                 };
 
+                // Zooming in makes each logical pixel cover more of the screen
+                let scale = scale_factor * drawing.zoom;
                 let pos: ScreenPoint = pos.into();
-                pos.to_logical(scale_factor, center, fb_center)
+                drawing.to_user(pos.to_logical(scale, center, fb_center))
             };
 
             //TODO: There is no guarantee that sending this event here will actually allow a client
             // to receive it. After all, if the window closes and this process exits, there will be
             // no way to handle subsequent `NextEvent` requests.
             if let Some(event) = Event::from_window_event(event, scale_factor, to_logical) {
+                // Shortcut keys control the drawing instead of being sent to the program
+                if let Event::Key(key, state) = event {
+                    let shortcut = {
+                        let app = app.read();
+                        let drawing = app.drawing();
+                        if Some(key) == drawing.pause_key {
+                            Some(Shortcut::TogglePause)
+                        } else if Some(key) == drawing.step_key {
+                            Some(Shortcut::Step)
+                        } else {
+                            None
+                        }
+                    };
+
+                    if let Some(shortcut) = shortcut {
+                        match state {
+                            PressedState::Pressed if shortcut_key_down != Some(key) => {
+                                shortcut_key_down = Some(key);
+                                // Ignoring the error since it just means that the server has ended
+                                shortcuts_sender.send(shortcut).unwrap_or(());
+                            },
+                            PressedState::Pressed => {},
+                            PressedState::Released => shortcut_key_down = None,
+                        }
+                        return;
+                    }
+                }
+
+                let follow_up = mouse.update(&event);
                 // Sending may fail if the IPC thread has ended due to a disconnection when the
                 // main process ends. This is not a fatal error though so we just ignore it.
                 events_sender.send(event).unwrap_or(());
+                if let Some(follow_up) = follow_up {
+                    events_sender.send(follow_up).unwrap_or(());
+                }
             }
         },
 
@@ -234,6 +328,17 @@ pub fn run_main(
         GlutinEvent::DeviceEvent {..} => {},
 
         GlutinEvent::UserEvent(MainThreadAction::Redraw) => {
+            let tracer = app.read().drawing().tracer;
+            skipped_updates += 1;
+            // A tracer of zero only redraws when the drawing is flushed
+            if tracer != 0 && skipped_updates >= tracer {
+                skipped_updates = 0;
+                gl_context.window().request_redraw();
+            }
+        },
+
+        GlutinEvent::UserEvent(MainThreadAction::Flush) => {
+            skipped_updates = 0;
             gl_context.window().request_redraw();
         },
 
@@ -241,8 +346,21 @@ pub fn run_main(
             gl_context.window().set_title(&title);
         },
 
+        GlutinEvent::UserEvent(MainThreadAction::SetIcon(icon)) => {
+            let icon = icon.and_then(|handle| {
+                let app = app.read();
+                let image = app.image(handle.id);
+                // The image has already been validated, so this should only fail if the
+                // platform does not support icons of this size
+                Icon::from_rgba(image.pixels.clone(), image.width, image.height)
+                    .map_err(|err| eprintln!("unable to set window icon: {}", err))
+                    .ok()
+            });
+            gl_context.window().set_window_icon(icon);
+        },
+
         GlutinEvent::UserEvent(MainThreadAction::SetSize(size)) => {
-            gl_context.window().set_inner_size(size);
+            gl_context.window().set_inner_size(LogicalSize {width: size.width, height: size.height});
         },
 
         GlutinEvent::UserEvent(MainThreadAction::SetIsMaximized(is_maximized)) => {
@@ -255,6 +373,10 @@ pub fn run_main(
             } else { None });
         },
 
+        GlutinEvent::UserEvent(MainThreadAction::Close) => {
+            *control_flow = ControlFlow::Exit;
+        },
+
         GlutinEvent::RedrawRequested(_) => {
             // Check if we just rendered
             let last_render_delay = last_render.elapsed();
@@ -295,7 +417,7 @@ fn redraw(
     let drawing = app.drawing();
     let turtle_states = app.turtles().map(|(_, turtle)| &turtle.state);
 
-    renderer.render(draw_size, display_list, drawing, turtle_states);
+    renderer.render(physical_size(draw_size), display_list, drawing, app.images(), turtle_states);
     gl_context.swap_buffers().expect("unable to swap the buffer (for double buffering)");
 }
 
@@ -304,9 +426,8 @@ fn spawn_async_server(
     app: SharedApp,
     display_list: SharedDisplayList,
     event_loop: EventLoopNotifier,
-    events_receiver: mpsc::UnboundedReceiver<Event>,
+    channels: WindowChannels,
     establish_connection: impl Future<Output=Result<(ServerSender, ServerReceiver), ConnectionError>> + Send + 'static,
-    server_shutdown_receiver: mpsc::Receiver<()>,
 ) {
     handle.spawn(async {
         let (conn_sender, conn_receiver) = establish_connection.await
@@ -318,8 +439,12 @@ fn spawn_async_server(
             app,
             display_list,
             event_loop,
-            events_receiver,
-            server_shutdown_receiver,
+            channels,
         ).await;
     });
 }
+
+/// Converts the size of the window into the size used by the renderer
+fn physical_size(size: PhysicalSize<u32>) -> Vector2I {
+    vec2i(size.width as i32, size.height as i32)
+}
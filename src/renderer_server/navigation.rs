@@ -0,0 +1,124 @@
+use super::coords::ScreenPoint;
+use super::state::DrawingState;
+
+/// How much the view zooms in (or out) for each line scrolled
+const ZOOM_PER_LINE: f64 = 1.1;
+/// The smallest zoom that can be reached by scrolling, so that the drawing cannot get lost
+const MIN_ZOOM: f64 = 0.01;
+/// The largest zoom that can be reached by scrolling
+const MAX_ZOOM: f64 = 100.0;
+
+/// Zooms and moves the view into the drawing with the mouse while navigation is enabled
+#[derive(Debug, Default)]
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+pub struct Navigator {
+    /// The last known position of the cursor in screen coordinates
+    cursor: Option<ScreenPoint>,
+    /// True while the middle mouse button is held down
+    is_panning: bool,
+}
+
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+impl Navigator {
+    /// Starts or stops moving the view as the cursor moves
+    pub fn set_panning(&mut self, is_panning: bool) {
+        self.is_panning = is_panning;
+    }
+
+    /// Updates the position of the cursor, moving the view along with it while panning
+    ///
+    /// Returns true if the view changed.
+    pub fn cursor_moved(&mut self, drawing: &mut DrawingState, cursor: ScreenPoint, dpi_scale: f64) -> bool {
+        let previous = self.cursor.replace(cursor);
+        match previous {
+            Some(previous) if self.is_panning && drawing.navigation => {
+                let scale = dpi_scale * drawing.zoom;
+                // The view moves in the opposite direction of the cursor so that the drawing
+                // follows it. The y-axis of the screen points down.
+                drawing.center.x -= (cursor.x - previous.x) / scale;
+                drawing.center.y += (cursor.y - previous.y) / scale;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Zooms in by the given number of lines scrolled (or out if it is negative), keeping the
+    /// point under the cursor in the same place on the screen
+    ///
+    /// Returns true if the view changed.
+    pub fn scroll(&mut self, drawing: &mut DrawingState, lines: f64, dpi_scale: f64, fb_center: ScreenPoint) -> bool {
+        if !drawing.navigation || !lines.is_finite() {
+            return false;
+        }
+
+        let zoom = (drawing.zoom * ZOOM_PER_LINE.powf(lines)).clamp(MIN_ZOOM, MAX_ZOOM);
+        if (zoom - drawing.zoom).abs() < f64::EPSILON {
+            return false;
+        }
+
+        if let Some(cursor) = self.cursor {
+            let anchor = cursor.to_logical(dpi_scale * drawing.zoom, drawing.center, fb_center);
+            drawing.center = anchor + (drawing.center - anchor) * (drawing.zoom / zoom);
+        }
+        drawing.zoom = zoom;
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Point;
+
+    const FB_CENTER: ScreenPoint = ScreenPoint {x: 400.0, y: 300.0};
+
+    fn navigable_drawing() -> DrawingState {
+        DrawingState {navigation: true, ..DrawingState::default()}
+    }
+
+    #[test]
+    fn zooming_keeps_the_point_under_the_cursor() {
+        let mut drawing = navigable_drawing();
+        let mut navigator = Navigator::default();
+        let cursor = ScreenPoint {x: 500.0, y: 200.0};
+        navigator.cursor_moved(&mut drawing, cursor, 2.0);
+
+        let before = cursor.to_logical(2.0 * drawing.zoom, drawing.center, FB_CENTER);
+        assert!(navigator.scroll(&mut drawing, 3.0, 2.0, FB_CENTER));
+        assert!(drawing.zoom > 1.0);
+        let after = cursor.to_logical(2.0 * drawing.zoom, drawing.center, FB_CENTER);
+        assert!((after - before).len() < 1e-9, "{:?} != {:?}", after, before);
+    }
+
+    #[test]
+    fn panning_follows_the_cursor() {
+        let mut drawing = navigable_drawing();
+        drawing.zoom = 2.0;
+        let mut navigator = Navigator::default();
+        navigator.cursor_moved(&mut drawing, ScreenPoint {x: 100.0, y: 100.0}, 1.0);
+
+        // Moving without the middle button held down only updates the cursor
+        assert!(!navigator.cursor_moved(&mut drawing, ScreenPoint {x: 120.0, y: 100.0}, 1.0));
+        assert_eq!(drawing.center, Point::origin());
+
+        navigator.set_panning(true);
+        assert!(navigator.cursor_moved(&mut drawing, ScreenPoint {x: 140.0, y: 120.0}, 1.0));
+        assert_eq!(drawing.center, Point {x: -10.0, y: 10.0});
+    }
+
+    #[test]
+    fn does_nothing_unless_enabled() {
+        let mut drawing = DrawingState::default();
+        let mut navigator = Navigator::default();
+        navigator.set_panning(true);
+        navigator.cursor_moved(&mut drawing, ScreenPoint {x: 0.0, y: 0.0}, 1.0);
+
+        assert!(!navigator.cursor_moved(&mut drawing, ScreenPoint {x: 50.0, y: 50.0}, 1.0));
+        assert!(!navigator.scroll(&mut drawing, 1.0, 1.0, FB_CENTER));
+        assert_eq!(drawing.center, Point::origin());
+        assert_eq!(drawing.zoom, 1.0);
+    }
+}
@@ -1,31 +1,59 @@
+pub mod color_glyph;
 pub mod display_list;
 pub mod export;
+pub mod filter;
+pub mod gif;
+pub mod image;
+pub mod import;
+pub mod mesh;
+pub mod plotter;
+pub mod primitive;
+#[cfg(feature = "shaders")]
+pub mod post_shader;
+pub mod raster;
+pub mod recorder;
+pub mod symmetry;
+pub mod text;
 
-use glutin::dpi::PhysicalSize;
-use pathfinder_canvas::{Canvas, CanvasFontContext, Path2D, LineCap, LineJoin, FillRule};
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use pathfinder_canvas::{Canvas, CanvasFontContext, CanvasRenderingContext2D, CompositeOperation, Path2D, LineCap, LineJoin, FillRule};
 use pathfinder_color::ColorU;
-use pathfinder_geometry::vector::{vec2f, vec2i};
+use pathfinder_content::{
+    effects::{BlurDirection, PatternFilter},
+    outline::Outline,
+    pattern::{Image as PatternImage, Pattern},
+};
+use pathfinder_geometry::{rect::RectF, transform2d::Transform2F, vector::{Vector2F, Vector2I, vec2f, vec2i}};
 use pathfinder_gl::{GLDevice, GLVersion};
 use pathfinder_resources::embedded::EmbeddedResourceLoader;
 use pathfinder_renderer::{
     concurrent::rayon::RayonExecutor,
     concurrent::scene_proxy::SceneProxy,
     options::BuildOptions,
+    paint::Paint,
+    scene::{DrawPath, RenderTarget, Scene},
     gpu::{
         renderer::Renderer as PathfinderRenderer,
         options::{DestFramebuffer, RendererOptions},
     },
 };
 
-use crate::{Point, Color};
+use crate::{Point, Color, Rect, Corner, Shape, BlendMode};
 
+use super::app::ImageId;
 use super::coords::ScreenPoint;
-use super::state::{DrawingState, TurtleState};
+use super::state::{DrawingState, TurtleState, Minimap};
 
-use display_list::{DisplayList, DrawPrim, Line, Polygon};
+use display_list::{DisplayList, DrawPrim, Line, Polygon, Circle, Curve, Text, Path, Image, Dash};
+use image::{ImageData, BackgroundImage, BackgroundMode};
+use filter::{Filter, filter_color, blur_radius};
+#[cfg(feature = "shaders")]
+use post_shader::{PostShader, PostProcessor};
 
 /// Converts a color from the representation in this crate to the one used in the renderer
-#[cfg_attr(any(feature = "test", test), allow(dead_code))]
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
 fn convert_color(color: Color) -> ColorU {
     let Color {red, green, blue, alpha} = color;
     ColorU {
@@ -36,24 +64,95 @@ fn convert_color(color: Color) -> ColorU {
     }
 }
 
+/// Sets the dash pattern used for the next lines drawn on the canvas, or makes them solid if
+/// there is no dash pattern
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+fn set_line_dash(canvas: &mut CanvasRenderingContext2D, dash: Option<&Dash>, scale: f64) {
+    match dash {
+        Some(dash) => {
+            canvas.set_line_dash(dash.pattern.iter().map(|&length| (length * scale) as f32).collect());
+            canvas.set_line_dash_offset((dash.offset * scale) as f32);
+        },
+        None => canvas.set_line_dash(Vec::new()),
+    }
+}
+
+/// Returns the operation that the canvas uses to draw with the given blend mode
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+fn composite_operation(blend_mode: BlendMode) -> CompositeOperation {
+    match blend_mode {
+        BlendMode::Alpha => CompositeOperation::SourceOver,
+        BlendMode::Additive => CompositeOperation::Lighter,
+        BlendMode::Multiply => CompositeOperation::Multiply,
+        BlendMode::Erase => CompositeOperation::DestinationOut,
+    }
+}
+
+/// The textures used to draw images, so that each image only needs to be converted once
+#[derive(Default)]
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+struct TextureCache {
+    /// The filters that were applied to the filtered textures
+    filters: Vec<Filter>,
+    /// The texture for each image, with (`true`) or without (`false`) the filters applied
+    textures: HashMap<(ImageId, bool), Pattern>,
+}
+
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+impl TextureCache {
+    /// Removes the filtered textures if the filters of the drawing have changed
+    fn update_filters(&mut self, filters: &[Filter]) {
+        if self.filters != filters {
+            self.filters = filters.to_vec();
+            self.textures.retain(|&(_, filtered), _| !filtered);
+        }
+    }
+
+    /// Returns the texture for the given image with the given filters applied
+    ///
+    /// The filters must either be empty or the filters last passed to `update_filters`.
+    fn texture(&mut self, id: ImageId, data: &ImageData, filters: &[Filter]) -> Pattern {
+        self.textures.entry((id, !filters.is_empty())).or_insert_with(|| {
+            let pixels = data.pixels.chunks(4).map(|p| {
+                let color = Color {
+                    red: p[0] as f64,
+                    green: p[1] as f64,
+                    blue: p[2] as f64,
+                    alpha: p[3] as f64 / 255.0,
+                };
+                convert_color(filter_color(color, filters))
+            }).collect();
+            let size = vec2i(data.width as i32, data.height as i32);
+            Pattern::from_image(PatternImage::new(size, Arc::new(pixels)))
+        }).clone()
+    }
+}
+
 /// A renderer that draws on the current OpenGL context
-#[cfg_attr(any(feature = "test", test), allow(dead_code))]
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
 pub struct Renderer {
     renderer: PathfinderRenderer<GLDevice>,
     font_context: CanvasFontContext,
     scene: SceneProxy,
+    textures: TextureCache,
     /// Information about DPI scaling: https://docs.rs/glutin/0.24.0/glutin/dpi/index.html
     dpi_scale: f64,
+    /// The post-processing shader that was last requested by the drawing
+    #[cfg(feature = "shaders")]
+    post_shader: Option<PostShader>,
+    /// The compiled version of `post_shader`, or `None` if it failed to compile
+    #[cfg(feature = "shaders")]
+    post_processor: Option<PostProcessor>,
 }
 
-#[cfg_attr(any(feature = "test", test), allow(dead_code))]
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
 impl Renderer {
     /// Creates a new renderer with the given physical size in pixels
-    pub fn new(draw_size: PhysicalSize<u32>, dpi_scale: f64) -> Self {
+    pub fn new(draw_size: Vector2I, dpi_scale: f64) -> Self {
         let renderer = PathfinderRenderer::new(
             GLDevice::new(GLVersion::GL3, 0),
             &EmbeddedResourceLoader::new(),
-            DestFramebuffer::full_window(vec2i(draw_size.width as i32, draw_size.height as i32)),
+            DestFramebuffer::full_window(draw_size),
             // This background color will be overwritten during the first render
             RendererOptions {
                 background_color: Some(ColorU::white().to_f32()),
@@ -65,7 +164,12 @@ impl Renderer {
             renderer,
             font_context: CanvasFontContext::from_system_source(),
             scene: SceneProxy::new(RayonExecutor),
+            textures: TextureCache::default(),
             dpi_scale,
+            #[cfg(feature = "shaders")]
+            post_shader: None,
+            #[cfg(feature = "shaders")]
+            post_processor: None,
         }
     }
 
@@ -79,29 +183,43 @@ impl Renderer {
         self.dpi_scale = dpi_scale;
     }
 
-    /// Draw the given primitives onto a canvas of the given size
+    /// Draw the given primitives onto a canvas of the given physical size in pixels
     ///
     /// Size is passed in to ensure that it is up-to-date
     pub fn render<'a>(
         &mut self,
-        draw_size: PhysicalSize<u32>,
+        draw_size: Vector2I,
         display_list: &DisplayList,
         drawing: &DrawingState,
+        images: &[Arc<ImageData>],
         turtles: impl Iterator<Item=&'a TurtleState>
     ) {
         // Set the current draw size
         self.renderer.replace_dest_framebuffer(
-            DestFramebuffer::full_window(vec2i(draw_size.width as i32, draw_size.height as i32))
+            DestFramebuffer::full_window(draw_size)
         );
 
         // Clear to background color
+        let filters = &drawing.filters;
+        let textures = &mut self.textures;
+        textures.update_filters(filters);
         self.renderer.set_options(RendererOptions {
-            background_color: Some(convert_color(drawing.background).to_f32()),
+            background_color: Some(convert_color(filter_color(drawing.background, filters)).to_f32()),
             ..RendererOptions::default()
         });
 
         // The size of the framebuffer
-        let fb_size = vec2f(draw_size.width as f32, draw_size.height as f32);
+        let fb_size = draw_size.to_f32();
+
+        // The background image is drawn separately so that it is not blurred with the drawing
+        let mut canvas = Canvas::new(fb_size)
+            .get_context_2d(self.font_context.clone());
+        if let Some(background_image) = drawing.background_image {
+            let data = &images[background_image.image.id.index()];
+            draw_background_image(&mut canvas, textures, background_image, data, filters, self.dpi_scale, fb_size);
+        }
+        let mut scene = canvas.into_canvas().into_scene();
+
         let mut canvas = Canvas::new(fb_size)
             .get_context_2d(self.font_context.clone());
 
@@ -112,64 +230,54 @@ impl Renderer {
         //TODO: Remove this line once servo/pathfinder#318 is fixed.
         //  Link: https://github.com/servo/pathfinder/issues/318
         // Need to render *something* every time to get pathfinder to even render a background
-        canvas.stroke_rect(pathfinder_geometry::rect::RectF::new(vec2f(0.0, 0.0), vec2f(1.0, 1.0)));
+        canvas.stroke_rect(RectF::new(vec2f(0.0, 0.0), vec2f(1.0, 1.0)));
 
-        // Draw each primitive
+        // Draw each primitive that may be visible in the window. The zoom is applied here so
+        // that the primitives themselves never need to change.
         let dpi_scale = self.dpi_scale;
+        let scale = dpi_scale * drawing.zoom;
         let center = drawing.center;
         let fb_center = (fb_size / 2.0).into();
-        for prim in display_list.iter() {
-            match prim {
-                &DrawPrim::Line(Line {start, end, thickness, color}) => {
-                    let mut path = Path2D::new();
-
-                    path.move_to(ScreenPoint::from_logical(start, dpi_scale, center, fb_center).into());
-                    path.line_to(ScreenPoint::from_logical(end, dpi_scale, center, fb_center).into());
+        let viewport = visible_area(drawing, scale, fb_size);
+        let prims = symmetry::visible_prims(display_list, viewport, drawing.symmetry);
+        draw_prims(&mut canvas, textures, prims.iter().map(AsRef::as_ref), filters, scale, center, fb_center);
 
-                    canvas.set_line_width((thickness * dpi_scale) as f32);
-                    canvas.set_stroke_style(convert_color(color));
-                    canvas.stroke_path(path);
-                },
-
-                &DrawPrim::Polygon(Polygon {ref points, fill_color}) => {
-                    // Skip obviously degenerate polygons
-                    if points.len() <= 2 {
-                        continue;
-                    }
-
-                    let mut path = Path2D::new();
-
-                    path.move_to(ScreenPoint::from_logical(points[0], dpi_scale, center, fb_center).into());
-                    for &point in &points[1..] {
-                        path.line_to(ScreenPoint::from_logical(point, dpi_scale, center, fb_center).into());
-                    }
+        let mut drawing_scene = canvas.into_canvas().into_scene();
+        let blur = blur_radius(filters);
+        if blur > 0.0 {
+            drawing_scene = blur_scene(drawing_scene, fb_size, (blur * scale) as f32);
+        } else if prims.iter().any(|prim| prim.blend_mode() == BlendMode::Erase) {
+            // Erasing needs to leave the drawing transparent so that the background shows through
+            // instead of the framebuffer being cleared
+            drawing_scene = isolate_scene(drawing_scene, fb_size);
+        }
+        scene.append_scene(drawing_scene);
 
-                    path.close_path();
+        // The turtles and the minimap are drawn on top of the drawing so that filters do not
+        // apply to them
+        let mut canvas = Canvas::new(fb_size)
+            .get_context_2d(self.font_context.clone());
+        canvas.set_line_cap(LineCap::Round);
+        canvas.set_line_join(LineJoin::Round);
 
-                    canvas.set_fill_style(convert_color(fill_color));
-                    canvas.fill_path(path, FillRule::Winding);
-                },
+        for turtle in turtles {
+            let &TurtleState {position, heading, is_visible, ref shape, shape_size, layer, ..} = turtle;
+            // Turtles are hidden along with their layer
+            if !is_visible || !display_list.is_layer_visible(layer) {
+                continue;
             }
-        }
 
-        // The turtle shell specified in logical coordinates relative to the turtle position
-        let shell = &[Point {x: 0.0, y: 15.0}, Point {x: 10.0, y: 0.0}, Point {x: 0.0, y: -15.0}];
-        for turtle in turtles {
-            let &TurtleState {position, heading, is_visible, ..} = turtle;
-            if !is_visible {
+            if let Shape::Image(handle) = *shape {
+                let data = images[handle.id.index()].clone();
+                let image = Image::placed(handle.id, data, position, heading, shape_size);
+                draw_image(&mut canvas, textures, &image, &[], scale, center, fb_center);
                 continue;
             }
 
-            let Point {x: turtle_x, y: turtle_y} = position;
-            let cos = heading.cos();
-            let sin = heading.sin();
-            let shell_screen_coord = |Point {x, y}| {
-                // Rotate each point by the heading and add the current turtle position
-                let point = Point {
-                    x: cos * x - sin * y + turtle_x,
-                    y: sin * x + cos * y + turtle_y,
-                };
-                ScreenPoint::from_logical(point, dpi_scale, center, fb_center).into()
+            // The turtle shell in logical coordinates, scaled and rotated around the turtle
+            let shell = shape.outline_at(position, heading, shape_size);
+            let shell_screen_coord = |point| {
+                ScreenPoint::from_logical(point, scale, center, fb_center).into()
             };
 
             let mut path = Path2D::new();
@@ -185,8 +293,373 @@ impl Renderer {
             canvas.stroke_path(path);
         }
 
+        if let Some(minimap) = drawing.minimap {
+            draw_minimap(&mut canvas, textures, display_list, drawing, minimap, dpi_scale, fb_size);
+        }
+
         // Build and render scene
-        self.scene.replace_scene(canvas.into_canvas().into_scene());
+        scene.append_scene(canvas.into_canvas().into_scene());
+        self.scene.replace_scene(scene);
         self.scene.build_and_render(&mut self.renderer, BuildOptions::default());
+
+        #[cfg(feature = "shaders")]
+        self.apply_post_shader(draw_size, drawing);
+    }
+
+    /// Applies the post-processing shader of the drawing (if any) to the frame that was just
+    /// rendered, compiling it first if it has changed
+    #[cfg(feature = "shaders")]
+    fn apply_post_shader(&mut self, draw_size: Vector2I, drawing: &DrawingState) {
+        if self.post_shader != drawing.post_shader {
+            self.post_shader = drawing.post_shader.clone();
+            self.post_processor = self.post_shader.as_ref().and_then(|shader| {
+                // Shaders are validated by the client, so this can only fail if the driver
+                // rejects the translated shader. Frames are shown without the shader instead.
+                PostProcessor::new(shader)
+                    .map_err(|err| eprintln!("unable to compile post-processing shader: {}", err))
+                    .ok()
+            });
+        }
+
+        if let Some(processor) = &mut self.post_processor {
+            processor.apply(draw_size.x(), draw_size.y());
+        }
     }
 }
+
+/// Draws each primitive in the display list
+///
+/// The `scale` is the number of screen pixels per logical pixel. It is applied to both the
+/// positions of the primitives and the thickness of lines.
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+fn draw_prims<'a>(
+    canvas: &mut CanvasRenderingContext2D,
+    textures: &mut TextureCache,
+    prims: impl Iterator<Item=&'a DrawPrim>,
+    filters: &[Filter],
+    scale: f64,
+    center: Point,
+    fb_center: ScreenPoint,
+) {
+    for prim in prims {
+        match prim {
+            &DrawPrim::Line(Line {start, end, thickness, color, ref dash, blend_mode}) => {
+                let mut path = Path2D::new();
+
+                path.move_to(ScreenPoint::from_logical(start, scale, center, fb_center).into());
+                path.line_to(ScreenPoint::from_logical(end, scale, center, fb_center).into());
+
+                set_line_dash(canvas, dash.as_ref(), scale);
+                canvas.set_line_width((thickness * scale) as f32);
+                canvas.set_stroke_style(convert_color(filter_color(color, filters)));
+                canvas.set_global_composite_operation(composite_operation(blend_mode));
+                canvas.stroke_path(path);
+                canvas.set_global_composite_operation(CompositeOperation::SourceOver);
+            },
+
+            &DrawPrim::Polygon(Polygon {ref points, fill_color, fill_rule}) => {
+                // Skip obviously degenerate polygons
+                if points.len() <= 2 {
+                    continue;
+                }
+
+                let mut path = Path2D::new();
+
+                path.move_to(ScreenPoint::from_logical(points[0], scale, center, fb_center).into());
+                for &point in &points[1..] {
+                    path.line_to(ScreenPoint::from_logical(point, scale, center, fb_center).into());
+                }
+
+                path.close_path();
+
+                canvas.set_fill_style(convert_color(filter_color(fill_color, filters)));
+                canvas.fill_path(path, match fill_rule {
+                    crate::FillRule::NonZero => FillRule::Winding,
+                    crate::FillRule::EvenOdd => FillRule::EvenOdd,
+                });
+            },
+
+            &DrawPrim::Circle(Circle {center: circle_center, radius, thickness, color, fill_color}) => {
+                let mut path = Path2D::new();
+
+                let circle_center = ScreenPoint::from_logical(circle_center, scale, center, fb_center);
+                path.ellipse(circle_center.into(), (radius * scale) as f32, 0.0, 0.0, 2.0 * std::f32::consts::PI);
+
+                if fill_color.alpha > 0.0 {
+                    canvas.set_fill_style(convert_color(filter_color(fill_color, filters)));
+                    canvas.fill_path(path.clone(), FillRule::Winding);
+                }
+
+                if thickness > 0.0 {
+                    set_line_dash(canvas, None, scale);
+                    canvas.set_line_width((thickness * scale) as f32);
+                    canvas.set_stroke_style(convert_color(filter_color(color, filters)));
+                    canvas.stroke_path(path);
+                }
+            },
+
+            &DrawPrim::Curve(Curve {start, control1, control2, end, thickness, color, ref dash, blend_mode}) => {
+                let to_screen = |point| ScreenPoint::from_logical(point, scale, center, fb_center).into();
+                let mut path = Path2D::new();
+
+                path.move_to(to_screen(start));
+                path.bezier_curve_to(to_screen(control1), to_screen(control2), to_screen(end));
+
+                set_line_dash(canvas, dash.as_ref(), scale);
+                canvas.set_line_width((thickness * scale) as f32);
+                canvas.set_stroke_style(convert_color(filter_color(color, filters)));
+                canvas.set_global_composite_operation(composite_operation(blend_mode));
+                canvas.stroke_path(path);
+                canvas.set_global_composite_operation(CompositeOperation::SourceOver);
+            },
+
+            &DrawPrim::Text(Text {ref outlines, color}) => {
+                // Every character is part of the same path so that the holes in characters are
+                // left empty by the fill rule
+                let mut path = Path2D::new();
+                for outline in outlines {
+                    path.move_to(ScreenPoint::from_logical(outline[0], scale, center, fb_center).into());
+                    for &point in &outline[1..] {
+                        path.line_to(ScreenPoint::from_logical(point, scale, center, fb_center).into());
+                    }
+                    path.close_path();
+                }
+
+                canvas.set_fill_style(convert_color(filter_color(color, filters)));
+                canvas.fill_path(path, FillRule::Winding);
+            },
+
+            &DrawPrim::Path(Path {ref subpaths, fill_color, fill_rule}) => {
+                // Every subpath is part of the same path so that the fill rule can leave holes
+                let mut path = Path2D::new();
+                for subpath in subpaths {
+                    path.move_to(ScreenPoint::from_logical(subpath[0], scale, center, fb_center).into());
+                    for &point in &subpath[1..] {
+                        path.line_to(ScreenPoint::from_logical(point, scale, center, fb_center).into());
+                    }
+                    path.close_path();
+                }
+
+                canvas.set_fill_style(convert_color(filter_color(fill_color, filters)));
+                canvas.fill_path(path, match fill_rule {
+                    crate::FillRule::NonZero => FillRule::Winding,
+                    crate::FillRule::EvenOdd => FillRule::EvenOdd,
+                });
+            },
+
+            DrawPrim::Image(image) => {
+                draw_image(canvas, textures, image, filters, scale, center, fb_center);
+            },
+        }
+    }
+}
+
+/// Draws an image with its pixels stretched and rotated to fit between its corners
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+fn draw_image(
+    canvas: &mut CanvasRenderingContext2D,
+    textures: &mut TextureCache,
+    image: &Image,
+    filters: &[Filter],
+    scale: f64,
+    center: Point,
+    fb_center: ScreenPoint,
+) {
+    let data = &image.data;
+    let [top_left, top_right, bottom_left] = image.corners()
+        .map(|point| ScreenPoint::from_logical(point, scale, center, fb_center));
+
+    // Maps the pixels of the image onto its corners on the screen
+    let (width, height) = (data.width as f64, data.height as f64);
+    let transform = Transform2F::row_major(
+        ((top_right.x - top_left.x) / width) as f32,
+        ((bottom_left.x - top_left.x) / height) as f32,
+        ((top_right.y - top_left.y) / width) as f32,
+        ((bottom_left.y - top_left.y) / height) as f32,
+        top_left.x as f32,
+        top_left.y as f32,
+    );
+
+    let texture = textures.texture(image.id, data, filters);
+    canvas.set_transform(&transform);
+    // Images are usually small (e.g. pixel art sprites), so they are scaled up without smoothing
+    canvas.set_image_smoothing_enabled(false);
+    canvas.draw_image(texture, RectF::new(Vector2F::zero(), vec2f(width as f32, height as f32)));
+    canvas.set_image_smoothing_enabled(true);
+    canvas.reset_transform();
+}
+
+/// Draws the given background image across the entire framebuffer, arranged with the given mode
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+fn draw_background_image(
+    canvas: &mut CanvasRenderingContext2D,
+    textures: &mut TextureCache,
+    background_image: BackgroundImage,
+    data: &ImageData,
+    filters: &[Filter],
+    dpi_scale: f64,
+    fb_size: Vector2F,
+) {
+    let BackgroundImage {image, mode} = background_image;
+    let placement = mode.placement(data, fb_size.x() as f64, fb_size.y() as f64, dpi_scale);
+    let origin = vec2f(placement.min.x as f32, placement.min.y as f32);
+    let scale = vec2f(
+        (placement.width() / data.width as f64) as f32,
+        (placement.height() / data.height as f64) as f32,
+    );
+
+    // Like other images, the background is scaled up without smoothing
+    let mut texture = textures.texture(image.id, data, filters);
+    match mode {
+        BackgroundMode::Tile => {
+            texture.set_repeat_x(true);
+            texture.set_repeat_y(true);
+            texture.set_smoothing_enabled(false);
+            texture.apply_transform(Transform2F::from_translation(origin) * Transform2F::from_scale(scale));
+            canvas.set_fill_style(texture);
+            canvas.fill_rect(RectF::new(Vector2F::zero(), fb_size));
+        },
+
+        BackgroundMode::Stretch | BackgroundMode::Center => {
+            canvas.set_transform(&(Transform2F::from_translation(origin) * Transform2F::from_scale(scale)));
+            let size = vec2f(data.width as f32, data.height as f32);
+            canvas.set_image_smoothing_enabled(false);
+            canvas.draw_image(texture, RectF::new(Vector2F::zero(), size));
+            canvas.set_image_smoothing_enabled(true);
+            canvas.reset_transform();
+        },
+    }
+}
+
+/// Draws a scaled-down view of the entire drawing in a corner of the framebuffer, along with a
+/// rectangle showing the area that is currently visible
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+fn draw_minimap(
+    canvas: &mut CanvasRenderingContext2D,
+    textures: &mut TextureCache,
+    display_list: &DisplayList,
+    drawing: &DrawingState,
+    minimap: Minimap,
+    dpi_scale: f64,
+    fb_size: Vector2F,
+) {
+    // Distance between the minimap and the edges of the window in logical pixels
+    const MARGIN: f64 = 10.0;
+
+    let Minimap {corner, size} = minimap;
+    let map_size = vec2f(size.width as f32, size.height as f32) * dpi_scale as f32;
+    let margin = (MARGIN * dpi_scale) as f32;
+    let map_origin = match corner {
+        Corner::TopLeft => vec2f(margin, margin),
+        Corner::TopRight => vec2f(fb_size.x() - map_size.x() - margin, margin),
+        Corner::BottomLeft => vec2f(margin, fb_size.y() - map_size.y() - margin),
+        Corner::BottomRight => fb_size - map_size - vec2f(margin, margin),
+    };
+    let map_rect = RectF::new(map_origin, map_size);
+
+    // The area of the drawing currently visible in the window
+    let viewport = visible_area(drawing, dpi_scale * drawing.zoom, fb_size);
+    // Always show the viewport, even if nothing has been drawn in it
+    let content = match symmetry::bounds(display_list, drawing.symmetry) {
+        Some(bounds) => bounds.union(viewport),
+        None => viewport,
+    };
+
+    // Fit the content inside the minimap, preserving its aspect ratio
+    let map_scale = (map_size.x() as f64 / content.width())
+        .min(map_size.y() as f64 / content.height());
+    let map_center = ScreenPoint::from(map_rect.center());
+
+    // The minimap is too small for blurring to be useful, but the colors should still match
+    let filters = &drawing.filters;
+    let background = filter_color(drawing.background, filters);
+    canvas.set_fill_style(convert_color(background));
+    canvas.fill_rect(map_rect);
+
+    let prims = symmetry::visible_prims(display_list, content, drawing.symmetry);
+    draw_prims(canvas, textures, prims.iter().map(AsRef::as_ref), filters, map_scale, content.center(), map_center);
+
+    // Outline the viewport
+    let viewport_min: Vector2F = ScreenPoint::from_logical(viewport.min, map_scale, content.center(), map_center).into();
+    let viewport_max: Vector2F = ScreenPoint::from_logical(viewport.max, map_scale, content.center(), map_center).into();
+    // In screen coordinates, the y-axis is flipped so min and max need to be swapped
+    let viewport_rect = RectF::from_points(
+        vec2f(viewport_min.x(), viewport_max.y()),
+        vec2f(viewport_max.x(), viewport_min.y()),
+    );
+    canvas.set_line_width((1.0 * dpi_scale) as f32);
+    canvas.set_stroke_style(ColorU::new(220, 20, 60, 255));
+    canvas.stroke_rect(viewport_rect);
+
+    // Border around the entire minimap
+    canvas.set_stroke_style(ColorU::black());
+    canvas.stroke_rect(map_rect);
+}
+
+/// Returns the area of the drawing (in logical coordinates) that is visible in a framebuffer of
+/// the given size when drawn with the given number of screen pixels per logical pixel
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+fn visible_area(drawing: &DrawingState, scale: f64, fb_size: Vector2F) -> Rect {
+    Rect::from_center(
+        drawing.center,
+        fb_size.x() as f64 / scale,
+        fb_size.y() as f64 / scale,
+    )
+}
+
+/// Returns a scene that draws the given scene into an offscreen render target before drawing it
+/// onto the framebuffer
+///
+/// Anything erased from the given scene becomes transparent in the render target, rather than
+/// erasing the background that is already in the framebuffer.
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+fn isolate_scene(content: Scene, fb_size: Vector2F) -> Scene {
+    let size = fb_size.to_i32();
+    let area = RectF::new(Vector2F::zero(), fb_size);
+
+    let mut scene = Scene::new();
+    scene.set_view_box(content.view_box());
+
+    let target = scene.push_render_target(RenderTarget::new(size, String::new()));
+    scene.append_scene(content);
+    scene.pop_render_target();
+
+    let paint = scene.push_paint(&Paint::from_pattern(Pattern::from_render_target(target, size)));
+    scene.push_path(DrawPath::new(Outline::from_rect(area), paint));
+
+    scene
+}
+
+/// Returns a scene that draws the given scene blurred by the given amount (in screen pixels)
+///
+/// The scene is drawn into an offscreen render target, which is then blurred horizontally into a
+/// second render target, and finally blurred vertically onto the framebuffer. This is the same
+/// approach that pathfinder uses to blur shadows.
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+fn blur_scene(content: Scene, fb_size: Vector2F, radius: f32) -> Scene {
+    let size = fb_size.to_i32();
+    let area = RectF::new(Vector2F::zero(), fb_size);
+
+    let mut scene = Scene::new();
+    scene.set_view_box(content.view_box());
+
+    let target_y = scene.push_render_target(RenderTarget::new(size, String::new()));
+    let target_x = scene.push_render_target(RenderTarget::new(size, String::new()));
+    scene.append_scene(content);
+    scene.pop_render_target();
+
+    // The radius is treated as the standard deviation of the blur
+    let sigma = radius;
+    for (target, direction) in [(target_x, BlurDirection::X), (target_y, BlurDirection::Y)].iter().copied() {
+        let mut pattern = Pattern::from_render_target(target, size);
+        pattern.set_filter(Some(PatternFilter::Blur {direction, sigma}));
+        let paint = scene.push_paint(&Paint::from_pattern(pattern));
+        scene.push_path(DrawPath::new(Outline::from_rect(area), paint));
+
+        if direction == BlurDirection::X {
+            scene.pop_render_target();
+        }
+    }
+
+    scene
+}
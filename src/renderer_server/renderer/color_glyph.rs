@@ -0,0 +1,173 @@
+//! Reads color glyphs from the `COLR` and `CPAL` tables of a font (version 0)
+//!
+//! A color glyph is drawn by filling the outlines of several other glyphs (its layers) one on top
+//! of the other, each with a color from the palette of the font. Only the first palette is used.
+//!
+//! See: <https://learn.microsoft.com/en-us/typography/opentype/spec/colr>
+
+use std::cmp::Ordering;
+
+use ttf_parser::GlyphId;
+
+use crate::Color;
+
+/// The palette index that means "use the color of the text" instead of a color from the palette
+const FOREGROUND_PALETTE_INDEX: u16 = 0xFFFF;
+
+/// The size (in bytes) of each record in the base glyph array of the `COLR` table
+const BASE_GLYPH_RECORD_SIZE: usize = 6;
+/// The size (in bytes) of each record in the layer array of the `COLR` table
+const LAYER_RECORD_SIZE: usize = 4;
+/// The size (in bytes) of each color record in the `CPAL` table
+const COLOR_RECORD_SIZE: usize = 4;
+
+/// One layer of a color glyph
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorLayer {
+    /// The glyph whose outline is filled to draw this layer
+    pub glyph: GlyphId,
+    /// The color of this layer, or `None` if it should be drawn in the color of the text
+    pub color: Option<Color>,
+}
+
+/// Returns the layers of the given glyph (from bottom to top), or `None` if the glyph does not
+/// have any color layers
+///
+/// `colr` and `cpal` are the contents of the `COLR` and `CPAL` tables of the font. Tables that are
+/// invalid or too short are treated as if the glyph had no color layers.
+pub fn color_layers(colr: &[u8], cpal: Option<&[u8]>, glyph: GlyphId) -> Option<Vec<ColorLayer>> {
+    let num_base_glyphs = read_u16(colr, 2)? as usize;
+    let base_glyphs_offset = read_u32(colr, 4)? as usize;
+    let layers_offset = read_u32(colr, 8)? as usize;
+    let num_layers = read_u16(colr, 12)? as usize;
+
+    // Base glyph records are sorted by glyph ID
+    let base_glyph = |index: usize| read_u16(colr, base_glyphs_offset + index * BASE_GLYPH_RECORD_SIZE);
+    let (mut low, mut high) = (0, num_base_glyphs);
+    let record = loop {
+        if low >= high {
+            return None;
+        }
+
+        let mid = (low + high) / 2;
+        let GlyphId(id) = glyph;
+        match base_glyph(mid)?.cmp(&id) {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => break base_glyphs_offset + mid * BASE_GLYPH_RECORD_SIZE,
+        }
+    };
+
+    let first_layer = read_u16(colr, record + 2)? as usize;
+    let layer_count = read_u16(colr, record + 4)? as usize;
+    if layer_count == 0 || first_layer + layer_count > num_layers {
+        return None;
+    }
+
+    (first_layer..first_layer + layer_count).map(|index| {
+        let layer = layers_offset + index * LAYER_RECORD_SIZE;
+        let glyph = GlyphId(read_u16(colr, layer)?);
+        let palette_index = read_u16(colr, layer + 2)?;
+
+        let color = match palette_index {
+            FOREGROUND_PALETTE_INDEX => None,
+            index => cpal.and_then(|cpal| palette_color(cpal, index)),
+        };
+        Some(ColorLayer {glyph, color})
+    }).collect()
+}
+
+/// Returns the color at the given index of the first palette in the `CPAL` table
+fn palette_color(cpal: &[u8], index: u16) -> Option<Color> {
+    let num_palette_entries = read_u16(cpal, 2)?;
+    if index >= num_palette_entries {
+        return None;
+    }
+
+    let color_records_offset = read_u32(cpal, 8)? as usize;
+    let first_color = read_u16(cpal, 12)? as usize;
+    let record = color_records_offset + (first_color + index as usize) * COLOR_RECORD_SIZE;
+
+    // Colors are stored as BGRA, without premultiplied alpha
+    let bgra = cpal.get(record..record + COLOR_RECORD_SIZE)?;
+    Some(Color {
+        red: bgra[2] as f64,
+        green: bgra[1] as f64,
+        blue: bgra[0] as f64,
+        alpha: bgra[3] as f64 / 255.0,
+    })
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `COLR` table with two color glyphs: glyph 3 has two layers and glyph 7 has one layer in
+    /// the color of the text
+    fn colr() -> Vec<u8> {
+        let mut table = Vec::new();
+        // version, numBaseGlyphRecords, baseGlyphRecordsOffset, layerRecordsOffset, numLayerRecords
+        table.extend_from_slice(&[0, 0, 0, 2, 0, 0, 0, 14, 0, 0, 0, 26, 0, 3]);
+        // glyphID, firstLayerIndex, numLayers
+        table.extend_from_slice(&[0, 3, 0, 0, 0, 2]);
+        table.extend_from_slice(&[0, 7, 0, 2, 0, 1]);
+        // glyphID, paletteIndex
+        table.extend_from_slice(&[0, 10, 0, 1]);
+        table.extend_from_slice(&[0, 11, 0, 0]);
+        table.extend_from_slice(&[0, 12, 0xFF, 0xFF]);
+        table
+    }
+
+    /// A `CPAL` table with one palette of two colors
+    fn cpal() -> Vec<u8> {
+        let mut table = Vec::new();
+        // version, numPaletteEntries, numPalettes, numColorRecords, colorRecordsArrayOffset,
+        // colorRecordIndices[0]
+        table.extend_from_slice(&[0, 0, 0, 2, 0, 1, 0, 2, 0, 0, 0, 14, 0, 0]);
+        // blue, green, red, alpha
+        table.extend_from_slice(&[0, 0, 255, 255]);
+        table.extend_from_slice(&[255, 128, 0, 51]);
+        table
+    }
+
+    #[test]
+    fn reads_layers_with_palette_colors() {
+        let layers = color_layers(&colr(), Some(&cpal()), GlyphId(3)).unwrap();
+        assert_eq!(layers, vec![
+            ColorLayer {
+                glyph: GlyphId(10),
+                color: Some(Color {red: 0.0, green: 128.0, blue: 255.0, alpha: 0.2}),
+            },
+            ColorLayer {
+                glyph: GlyphId(11),
+                color: Some(Color {red: 255.0, green: 0.0, blue: 0.0, alpha: 1.0}),
+            },
+        ]);
+
+        let layers = color_layers(&colr(), Some(&cpal()), GlyphId(7)).unwrap();
+        assert_eq!(layers, vec![ColorLayer {glyph: GlyphId(12), color: None}]);
+    }
+
+    #[test]
+    fn glyphs_without_layers() {
+        for &id in &[0, 4, 8, 0xFFFF] {
+            assert_eq!(color_layers(&colr(), Some(&cpal()), GlyphId(id)), None);
+        }
+
+        // A truncated table never reads past its end
+        let colr = colr();
+        for len in 0..colr.len() {
+            assert_eq!(color_layers(&colr[..len], Some(&cpal()), GlyphId(7)), None, "{}", len);
+        }
+    }
+}
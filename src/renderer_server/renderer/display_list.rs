@@ -1,10 +1,137 @@
-use std::{sync::Arc, collections::BTreeMap};
+mod chunk_index;
+
+use std::{sync::Arc, collections::{BTreeMap, HashMap, HashSet}, f64::consts::PI};
 
 use parking_lot::Mutex;
+use serde::{Serialize, Deserialize};
+
+use crate::{Point, Color, Rect, FillRule, BlendMode};
+use crate::radians::Radians;
+
+use super::image::ImageData;
+use super::super::{app::{ImageId, LayerId}, state::Pen};
+
+use chunk_index::ChunkIndex;
+
+/// The largest number of line segments used by `Circle::flatten()`, no matter how large the circle
+const MAX_CIRCLE_SEGMENTS: f64 = 4096.0;
+
+/// The largest number of line segments used by `Curve::flatten()`, no matter how large the curve
+const MAX_CURVE_SEGMENTS: f64 = 4096.0;
+
+/// The largest distance between a curve and the line segments used to measure its length
+const CURVE_LENGTH_TOLERANCE: f64 = 0.01;
+
+/// The largest distance between a curve and the line segments used to check if a point is on it
+const HIT_TEST_TOLERANCE: f64 = 0.1;
+
+/// Returns true if the given lengths of dashes and gaps can be used as the dash pattern of a pen
+///
+/// An empty pattern draws solid lines. Otherwise, every length must be finite and not negative,
+/// and the pattern must not have a total length of zero.
+pub fn is_valid_dash_pattern(pattern: &[f64]) -> bool {
+    pattern.is_empty() || (
+        pattern.iter().all(|length| length.is_finite() && *length >= 0.0)
+            && pattern.iter().sum::<f64>() > 0.0
+    )
+}
+
+/// A repeating pattern of dashes and gaps that a line or curve is drawn with
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dash {
+    /// The length of each dash followed by the length of the gap after it, in (logical) pixels
+    ///
+    /// Always has an even number of lengths, none of them negative, with a total greater than
+    /// zero.
+    pub pattern: Arc<[f64]>,
+    /// The distance into the pattern at which the line or curve starts
+    pub offset: f64,
+}
+
+impl Dash {
+    /// Returns the dash pattern of the given pen, starting at the beginning of the pattern, or
+    /// `None` if the pen draws solid lines
+    pub fn from_pen(pen: &Pen) -> Option<Self> {
+        let pattern = &pen.dash_pattern;
+        if pattern.is_empty() {
+            return None;
+        }
 
-use crate::{Point, Color};
+        // Odd patterns are repeated to make them even, just like in SVG
+        let pattern: Arc<[f64]> = if pattern.len().is_multiple_of(2) {
+            pattern.as_slice().into()
+        } else {
+            pattern.iter().chain(pattern).copied().collect()
+        };
+        Some(Self {pattern, offset: 0.0})
+    }
+
+    /// Returns the offset of a dash with the same pattern that continues after a path of the
+    /// given length drawn with this dash
+    pub fn offset_after(&self, length: f64) -> f64 {
+        let period: f64 = self.pattern.iter().sum();
+        (self.offset + length) % period
+    }
+
+    /// Splits the path through the given points into the parts that are covered by dashes
+    ///
+    /// Each part is a path with at least two points. Dashes with no length have two points in the
+    /// same place.
+    pub fn split(&self, points: &[Point]) -> Vec<Vec<Point>> {
+        let period: f64 = self.pattern.iter().sum();
+        let is_dash = |index: usize| index.is_multiple_of(2);
+
+        // Find the part of the pattern that the path starts in
+        let mut index = 0;
+        let mut into_pattern = self.offset.rem_euclid(period);
+        while into_pattern > self.pattern[index] {
+            into_pattern -= self.pattern[index];
+            index = (index + 1) % self.pattern.len();
+        }
+        // The distance until the current dash or gap ends
+        let mut remaining = self.pattern[index] - into_pattern;
+
+        let mut parts = Vec::new();
+        let mut part: Vec<Point> = if is_dash(index) { points.iter().copied().take(1).collect() } else { Vec::new() };
+        for segment in points.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            let length = (end - start).len();
+
+            let mut travelled = 0.0;
+            while length - travelled > remaining {
+                travelled += remaining;
+                // A dash ends here or starts here after a gap
+                part.push(start + (end - start) * (travelled / length));
+                if is_dash(index) {
+                    parts.push(std::mem::take(&mut part));
+                }
 
-use super::super::state::Pen;
+                index = (index + 1) % self.pattern.len();
+                remaining = self.pattern[index];
+            }
+
+            remaining -= length - travelled;
+            if is_dash(index) {
+                part.push(end);
+            }
+        }
+        if is_dash(index) {
+            parts.push(part);
+        }
+
+        parts.retain(|part| part.len() >= 2);
+        parts
+    }
+}
+
+/// Returns the parts of the path through the given points that are covered by the given dash
+/// pattern, or the whole path if there is no dash pattern
+pub fn dashed(points: &[Point], dash: Option<&Dash>) -> Vec<Vec<Point>> {
+    match dash {
+        Some(dash) => dash.split(points),
+        None => vec![points.to_vec()],
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Line {
@@ -17,6 +144,22 @@ pub struct Line {
     pub thickness: f64,
     /// The stroke color of the line
     pub color: Color,
+    /// The dash pattern of the line, or `None` if the line is solid
+    pub dash: Option<Dash>,
+    /// How the line combines with what was drawn before it
+    pub blend_mode: BlendMode,
+}
+
+impl Line {
+    /// Returns the area covered by this line, including its thickness
+    pub fn bounds(&self) -> Rect {
+        Rect::from_points(self.start, self.end).inflate(self.thickness / 2.0)
+    }
+
+    /// Returns the length of the line, not including its thickness
+    pub fn length(&self) -> f64 {
+        (self.end - self.start).len()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +171,216 @@ pub struct Polygon {
 
     /// The fill color of the polygon
     pub fill_color: Color,
+    /// The rule used to decide which parts of the polygon are filled if its outline crosses over
+    /// itself
+    pub fill_rule: FillRule,
+}
+
+#[derive(Debug, Clone)]
+pub struct Circle {
+    /// The center of the circle
+    pub center: Point,
+    /// The radius of the circle, never negative
+    pub radius: f64,
+
+    /// The thickness of the outline in (logical) pixels, zero if there is no outline
+    pub thickness: f64,
+    /// The stroke color of the outline
+    pub color: Color,
+    /// The fill color of the circle, transparent if it is not filled
+    pub fill_color: Color,
+}
+
+impl Circle {
+    /// Returns the area covered by this circle, including the thickness of its outline
+    pub fn bounds(&self) -> Rect {
+        Rect::from_points(self.center, self.center).inflate(self.radius + self.thickness / 2.0)
+    }
+
+    /// Returns points around a circle with the given center and radius, starting and ending at
+    /// the rightmost point
+    ///
+    /// The points are close enough together that the line segments between them are never more
+    /// than `tolerance` away from the circle itself.
+    pub fn flatten(center: Point, radius: f64, tolerance: f64) -> Vec<Point> {
+        // The largest angle for which the middle of a chord is within the tolerance of the circle
+        let max_angle = 2.0 * (1.0 - (tolerance / radius).min(1.0)).acos();
+        let segments = (2.0 * PI / max_angle).ceil().clamp(8.0, MAX_CIRCLE_SEGMENTS) as usize;
+
+        (0..=segments).map(|i| {
+            let angle = 2.0 * PI * i as f64 / segments as f64;
+            center + Point {x: radius * angle.cos(), y: radius * angle.sin()}
+        }).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Curve {
+    /// The point where the curve will begin when drawn
+    pub start: Point,
+    /// The control point that the curve moves towards as it leaves `start`
+    pub control1: Point,
+    /// The control point that the curve comes from as it arrives at `end`
+    pub control2: Point,
+    /// The point where the curve will end when drawn
+    pub end: Point,
+
+    /// The thickness of the curve in (logical) pixels
+    pub thickness: f64,
+    /// The stroke color of the curve
+    pub color: Color,
+    /// The dash pattern of the curve, or `None` if the curve is solid
+    pub dash: Option<Dash>,
+    /// How the curve combines with what was drawn before it
+    pub blend_mode: BlendMode,
+}
+
+impl Curve {
+    /// Returns the area covered by this curve, including its thickness
+    ///
+    /// A cubic Bézier curve is always inside of the area covered by its control points.
+    pub fn bounds(&self) -> Rect {
+        Rect::bounding(self.points().iter().copied())
+            .expect("bug: curves always have points")
+            .inflate(self.thickness / 2.0)
+    }
+
+    /// Returns the start, both control points, and the end of the curve, in that order
+    pub fn points(&self) -> [Point; 4] {
+        [self.start, self.control1, self.control2, self.end]
+    }
+
+    /// Returns the point at the given fraction `t` (from 0.0 to 1.0) of the way along the curve
+    pub fn point_at(&self, t: f64) -> Point {
+        let u = 1.0 - t;
+        self.start * (u * u * u)
+            + self.control1 * (3.0 * u * u * t)
+            + self.control2 * (3.0 * u * t * t)
+            + self.end * (t * t * t)
+    }
+
+    /// Returns the direction that the curve is going in at the given fraction `t` of the way
+    /// along it, or `None` if the curve does not go anywhere
+    pub fn tangent_at(&self, t: f64) -> Option<Point> {
+        let [p0, p1, p2, p3] = self.points();
+        let u = 1.0 - t;
+        let derivative = (p1 - p0) * (u * u) + (p2 - p1) * (2.0 * u * t) + (p3 - p2) * (t * t);
+
+        // The derivative is zero at an end of the curve if a control point is on top of it, in
+        // which case the curve still leaves towards the next distinct point
+        let fallbacks = if t < 0.5 { [p2 - p0, p3 - p0] } else { [p3 - p1, p3 - p0] };
+        std::iter::once(derivative).chain(fallbacks.iter().copied())
+            .find(|dir| dir.len() > 1e-12)
+    }
+
+    /// Returns the part of the curve from its start to the given fraction `t` of the way along it
+    pub fn split_at(&self, t: f64) -> Curve {
+        let [p0, p1, p2, p3] = self.points();
+        let lerp = |a: Point, b: Point| a + (b - a) * t;
+
+        let p01 = lerp(p0, p1);
+        let p12 = lerp(p1, p2);
+        let p23 = lerp(p2, p3);
+        let p012 = lerp(p01, p12);
+        let p123 = lerp(p12, p23);
+
+        Curve {
+            start: p0,
+            control1: p01,
+            control2: p012,
+            end: lerp(p012, p123),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the length of the curve, not including its thickness
+    pub fn length(&self) -> f64 {
+        self.flatten(CURVE_LENGTH_TOLERANCE).windows(2)
+            .map(|segment| (segment[1] - segment[0]).len())
+            .sum()
+    }
+
+    /// Returns points along the curve from its start to its end, evenly spaced by the fraction of
+    /// the way along the curve
+    ///
+    /// The points are close enough together that the line segments between them are never more
+    /// than `tolerance` away from the curve itself.
+    pub fn flatten(&self, tolerance: f64) -> Vec<Point> {
+        let [p0, p1, p2, p3] = self.points();
+        // Wang's formula for the number of segments needed to stay within the tolerance
+        let max_bend = (p0 - p1 * 2.0 + p2).len().max((p1 - p2 * 2.0 + p3).len());
+        let segments = (0.75 * max_bend / tolerance).sqrt().ceil().clamp(1.0, MAX_CURVE_SEGMENTS) as usize;
+
+        (0..=segments).map(|i| self.point_at(i as f64 / segments as f64)).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Text {
+    /// The outline of each character, each one a closed loop of points
+    ///
+    /// Guaranteed to be non-empty. Every outline is filled together using the non-zero fill rule,
+    /// which leaves the holes in characters like "o" or "A" empty.
+    pub outlines: Vec<Vec<Point>>,
+
+    /// The fill color of the text
+    pub color: Color,
+}
+
+/// A filled shape made up of several closed loops of points, e.g. imported from an SVG file
+#[derive(Debug, Clone)]
+pub struct Path {
+    /// Each closed loop of points in the shape
+    ///
+    /// Guaranteed to be non-empty. Every sub-path is filled together, so sub-paths inside of
+    /// other sub-paths can leave holes (depending on the fill rule).
+    pub subpaths: Vec<Vec<Point>>,
+
+    pub fill_color: Color,
+    pub fill_rule: FillRule,
+}
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    /// The ID of the image, used to cache the image in the renderer
+    pub id: ImageId,
+    /// The pixels of the image
+    pub data: Arc<ImageData>,
+
+    /// The position of the center of the image
+    pub center: Point,
+    /// The vector from the center of the image to the middle of its right edge
+    pub right: Point,
+    /// The vector from the center of the image to the middle of its top edge
+    pub up: Point,
+}
+
+impl Image {
+    /// Places an image the way a turtle with the image as its shape would be drawn
+    ///
+    /// The center of the image is at `position`, the top of the image faces in the direction of
+    /// `heading`, and each pixel of the image is `scale` logical pixels wide.
+    pub fn placed(id: ImageId, data: Arc<ImageData>, position: Point, heading: Radians, scale: f64) -> Self {
+        let forward = Point {x: heading.cos(), y: heading.sin()};
+        let right = Point {x: forward.y, y: -forward.x} * (data.width as f64 * scale / 2.0);
+        let up = forward * (data.height as f64 * scale / 2.0);
+
+        Self {id, data, center: position, right, up}
+    }
+
+    /// Returns the top left, top right, and bottom left corners of the image
+    pub fn corners(&self) -> [Point; 3] {
+        let top_left = self.center - self.right + self.up;
+        [top_left, top_left + self.right * 2.0, top_left - self.up * 2.0]
+    }
+
+    /// Returns the area covered by this image
+    pub fn bounds(&self) -> Rect {
+        let [top_left, top_right, bottom_left] = self.corners();
+        let bottom_right = top_right + bottom_left - top_left;
+        Rect::bounding([top_left, top_right, bottom_left, bottom_right].iter().copied())
+            .expect("bug: images always have corners")
+    }
 }
 
 /// A drawing primitive
@@ -35,9 +388,79 @@ pub struct Polygon {
 pub enum DrawPrim {
     Line(Line),
     Polygon(Polygon),
+    Circle(Circle),
+    Curve(Curve),
+    Text(Text),
+    Path(Path),
+    Image(Image),
 }
 
 impl DrawPrim {
+    /// Returns the area covered by this primitive, including the thickness of lines
+    pub fn bounds(&self) -> Rect {
+        use DrawPrim::*;
+        match self {
+            Line(line) => line.bounds(),
+            Polygon(polygon) => Rect::bounding(polygon.points.iter().copied())
+                .expect("bug: polygons should be non-empty"),
+            Circle(circle) => circle.bounds(),
+            Curve(curve) => curve.bounds(),
+            Text(text) => Rect::bounding(text.outlines.iter().flatten().copied())
+                .expect("bug: text should be non-empty"),
+            Path(path) => Rect::bounding(path.subpaths.iter().flatten().copied())
+                .expect("bug: paths should be non-empty"),
+            Image(image) => image.bounds(),
+        }
+    }
+
+    /// Returns true if the given point is on a part of this primitive that gets drawn
+    ///
+    /// Points within half the thickness of a line or curve are on it, except in the gaps of a
+    /// dashed line. Polygons, paths, and text use their fill rule to decide which points are
+    /// inside.
+    pub fn contains(&self, point: Point) -> bool {
+        use DrawPrim::*;
+        match self {
+            Line(line) => dashed(&[line.start, line.end], line.dash.as_ref()).iter()
+                .any(|points| polyline_distance(points, point) <= line.thickness / 2.0),
+            Polygon(polygon) => polygon.points.len() >= 3 &&
+                polygon.fill_rule.is_inside(winding_number(&polygon.points, point)),
+            Circle(circle) => {
+                let distance = circle.center.distance(point);
+                (circle.fill_color.alpha > 0.0 && distance <= circle.radius) ||
+                    (circle.thickness > 0.0 && (distance - circle.radius).abs() <= circle.thickness / 2.0)
+            },
+            Curve(curve) => dashed(&curve.flatten(HIT_TEST_TOLERANCE), curve.dash.as_ref()).iter()
+                .any(|points| polyline_distance(points, point) <= curve.thickness / 2.0),
+            Text(text) => {
+                let winding = text.outlines.iter().map(|outline| winding_number(outline, point)).sum();
+                FillRule::NonZero.is_inside(winding)
+            },
+            Path(path) => {
+                let winding = path.subpaths.iter().map(|subpath| winding_number(subpath, point)).sum();
+                path.fill_rule.is_inside(winding)
+            },
+            Image(image) => {
+                let offset = point - image.center;
+                offset.dot(image.right).abs() <= image.right.square_len() &&
+                    offset.dot(image.up).abs() <= image.up.square_len()
+            },
+        }
+    }
+
+    /// Returns how this primitive combines with what was drawn before it
+    ///
+    /// Only lines and curves are drawn with the blend mode of a pen. Everything else is drawn
+    /// over what is underneath it.
+    pub fn blend_mode(&self) -> BlendMode {
+        use DrawPrim::*;
+        match self {
+            Line(line) => line.blend_mode,
+            Curve(curve) => curve.blend_mode,
+            Polygon(_) | Circle(_) | Text(_) | Path(_) | Image(_) => BlendMode::Alpha,
+        }
+    }
+
     pub fn as_line_mut(&mut self) -> Option<&mut Line> {
         use DrawPrim::*;
         match self {
@@ -46,6 +469,14 @@ impl DrawPrim {
         }
     }
 
+    pub fn as_curve_mut(&mut self) -> Option<&mut Curve> {
+        use DrawPrim::*;
+        match self {
+            Curve(curve) => Some(curve),
+            _ => None,
+        }
+    }
+
     pub fn as_polygon_mut(&mut self) -> Option<&mut Polygon> {
         use DrawPrim::*;
         match self {
@@ -55,23 +486,100 @@ impl DrawPrim {
     }
 }
 
+/// Returns the shortest distance from the given point to the line segments between the points
+fn polyline_distance(points: &[Point], point: Point) -> f64 {
+    if let [only] = points {
+        return only.distance(point);
+    }
+
+    points.windows(2).map(|segment| {
+        let (start, end) = (segment[0], segment[1]);
+        let direction = end - start;
+        let length = direction.square_len();
+        if length == 0.0 {
+            return start.distance(point);
+        }
+
+        // Find the closest point on the segment by projecting onto it
+        let t = ((point - start).dot(direction) / length).clamp(0.0, 1.0);
+        (start + direction * t).distance(point)
+    }).fold(f64::INFINITY, f64::min)
+}
+
+/// Returns the number of times that the closed loop through the given points winds around the
+/// given point, counting counterclockwise loops as positive
+fn winding_number(points: &[Point], point: Point) -> i32 {
+    let mut winding = 0;
+    for (i, &start) in points.iter().enumerate() {
+        let end = points[(i + 1) % points.len()];
+        // Which side of the edge the point is on (positive if it is on the left)
+        let side = (end.x - start.x) * (point.y - start.y) - (point.x - start.x) * (end.y - start.y);
+        if start.y <= point.y {
+            if end.y > point.y && side > 0.0 {
+                winding += 1;
+            }
+        } else if end.y <= point.y && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
 /// A unique handle to one of the items in a display list
 ///
 /// Handles are guaranteed to be unique, even across removals from the display list.
-#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PrimHandle(usize);
 
 pub type SharedDisplayList = Arc<Mutex<DisplayList>>;
 
+/// A layer of the display list and whether it is currently drawn
+#[derive(Debug, Clone, Copy)]
+struct Layer {
+    id: LayerId,
+    is_visible: bool,
+}
+
 /// A list of drawing primitives in the order that they are rendered
-#[derive(Default, Debug)]
+///
+/// In addition to the draw order, primitives are also indexed by the area of the canvas that they
+/// cover. The canvas is divided into chunks and only the chunks that overlap with the visible
+/// area need to be considered during rendering. This allows the canvas to be effectively
+/// unbounded without slowing down rendering of the part of the drawing that is visible.
+///
+/// Every primitive belongs to a layer. Layers are drawn from the bottom to the top, and the
+/// primitives within each layer are drawn in the order that they were added. Primitives in
+/// hidden layers are skipped entirely.
+///
+/// Every chunk is kept in memory, even when it has not been visible for a long time, so the size
+/// of a drawing is still limited by the memory that is available. Paging chunks that are out of
+/// view to disk has not been implemented yet.
+#[derive(Debug)]
 pub struct DisplayList {
     /// Using a b-tree because it provides sorted access/iteration *and* removal of individual
     /// elements. The ID within `PrimHandle` is always monotonic, so this should provide the
-    /// correct draw order.
+    /// correct draw order within each layer.
     items: BTreeMap<PrimHandle, DrawPrim>,
+    /// The spatial index of every item in `items`
+    chunks: ChunkIndex,
     /// The next ID inside `PrimHandle`, must be monotonic (even across removals/deletions)
     next_id: usize,
+    /// Every layer in the order that they are drawn, from the bottom to the top
+    layers: Vec<Layer>,
+    /// The layer of every item that is not in the base layer
+    item_layers: HashMap<PrimHandle, LayerId>,
+}
+
+impl Default for DisplayList {
+    fn default() -> Self {
+        Self {
+            items: BTreeMap::default(),
+            chunks: ChunkIndex::default(),
+            next_id: 0,
+            layers: vec![Layer {id: LayerId::BASE, is_visible: true}],
+            item_layers: HashMap::default(),
+        }
+    }
 }
 
 impl DisplayList {
@@ -80,14 +588,15 @@ impl DisplayList {
     /// If a new line would not need to be drawn based on the pen configuration, `None` is
     /// returned. Otherwise, a handle to the line that will be drawn is returned.
     pub fn push_line(&mut self, start: Point, end: Point, pen: &Pen) -> Option<PrimHandle> {
-        let &Pen {is_enabled, thickness, color} = pen;
+        let &Pen {is_enabled, thickness, color, blend_mode, ..} = pen;
 
         // Do not draw lines for which the pen is disabled
         if !is_enabled {
             return None;
         }
 
-        let handle = self.insert(DrawPrim::Line(Line {start, end, thickness, color}));
+        let dash = Dash::from_pen(pen);
+        let handle = self.insert(DrawPrim::Line(Line {start, end, thickness, color, dash, blend_mode}));
         Some(handle)
     }
 
@@ -99,17 +608,124 @@ impl DisplayList {
         let line = prim.as_line_mut()
             .expect("bug: attempt to update the end of a draw primitive that was not a line");
         line.end = end;
+
+        // Lines only ever grow from their start point during animation, so growing the bounds
+        // is enough to keep the index up to date
+        let bounds = line.bounds();
+        self.chunks.grow(handle, bounds);
+    }
+
+    /// Pushes a new cubic Bézier curve into the display list
+    ///
+    /// If a new curve would not need to be drawn based on the pen configuration, `None` is
+    /// returned. Otherwise, a handle to the curve that will be drawn is returned.
+    pub fn push_curve(&mut self, points: [Point; 4], pen: &Pen) -> Option<PrimHandle> {
+        let &Pen {is_enabled, thickness, color, blend_mode, ..} = pen;
+
+        // Do not draw curves for which the pen is disabled
+        if !is_enabled {
+            return None;
+        }
+
+        let [start, control1, control2, end] = points;
+        let dash = Dash::from_pen(pen);
+        let handle = self.insert(DrawPrim::Curve(Curve {start, control1, control2, end, thickness, color, dash, blend_mode}));
+        Some(handle)
+    }
+
+    /// Replaces the start, control points, and end of a curve
+    ///
+    /// Panics if the given handle does not refer to a curve primitive.
+    pub fn curve_update(&mut self, handle: PrimHandle, points: [Point; 4]) {
+        let prim = self.items.get_mut(&handle).expect("bug: invalid handle");
+        let curve = prim.as_curve_mut()
+            .expect("bug: attempt to update the points of a draw primitive that was not a curve");
+        let [start, control1, control2, end] = points;
+        curve.start = start;
+        curve.control1 = control1;
+        curve.control2 = control2;
+        curve.end = end;
+
+        // Curves are only ever replaced by a longer part of the same curve during animation, and
+        // each part is inside the area covered by the points of the whole curve
+        let bounds = curve.bounds();
+        self.chunks.grow(handle, bounds);
+    }
+
+    /// Pushes a new circle into the display list
+    ///
+    /// The outline is drawn with the given pen and the inside is filled with the given color. If
+    /// neither would be visible, `None` is returned. Otherwise, a handle to the circle that will be
+    /// drawn is returned.
+    pub fn push_circle(&mut self, center: Point, radius: f64, pen: &Pen, fill_color: Color) -> Option<PrimHandle> {
+        let &Pen {is_enabled, thickness, color, ..} = pen;
+        let thickness = if is_enabled { thickness } else { 0.0 };
+
+        if thickness <= 0.0 && fill_color.alpha <= 0.0 {
+            return None;
+        }
+
+        let radius = radius.abs();
+        let handle = self.insert(DrawPrim::Circle(Circle {center, radius, thickness, color, fill_color}));
+        Some(handle)
+    }
+
+    /// Pushes text with the given character outlines into the display list
+    ///
+    /// If the text would not be visible (e.g. because it only contains spaces), `None` is
+    /// returned. Otherwise, a handle to the text that will be drawn is returned.
+    pub fn push_text(&mut self, outlines: Vec<Vec<Point>>, color: Color) -> Option<PrimHandle> {
+        if outlines.is_empty() || color.alpha <= 0.0 {
+            return None;
+        }
+
+        let handle = self.insert(DrawPrim::Text(Text {outlines, color}));
+        Some(handle)
+    }
+
+    /// Pushes a filled shape made up of the given sub-paths into the display list
+    ///
+    /// Sub-paths with fewer than three points are left out since they cannot contain anything. If
+    /// none are left, or the shape would not be visible, `None` is returned. Otherwise, a handle to
+    /// the shape that will be drawn is returned.
+    pub fn push_path(&mut self, mut subpaths: Vec<Vec<Point>>, fill_color: Color, fill_rule: FillRule) -> Option<PrimHandle> {
+        subpaths.retain(|subpath| subpath.len() >= 3);
+        if subpaths.is_empty() || fill_color.alpha <= 0.0 {
+            return None;
+        }
+
+        let handle = self.insert(DrawPrim::Path(Path {subpaths, fill_color, fill_rule}));
+        Some(handle)
+    }
+
+    /// Pushes an image into the display list, returning a handle to the image that will be drawn
+    pub fn push_image(&mut self, image: Image) -> PrimHandle {
+        self.insert(DrawPrim::Image(image))
     }
 
     /// Creates a polygon with one point, and pushes it into the display list
-    pub fn push_polygon_start(&mut self, start: Point, fill_color: Color) -> PrimHandle {
-        self.insert(DrawPrim::Polygon(Polygon {points: vec![start], fill_color}))
+    pub fn push_polygon_start(&mut self, start: Point, fill_color: Color, fill_rule: FillRule) -> PrimHandle {
+        self.insert(DrawPrim::Polygon(Polygon {points: vec![start], fill_color, fill_rule}))
+    }
+
+    /// Pushes a polygon with all of the given points into the display list
+    ///
+    /// Panics if no points are given.
+    pub fn push_polygon(&mut self, points: Vec<Point>, fill_color: Color) -> PrimHandle {
+        assert!(!points.is_empty(), "bug: polygons should be non-empty");
+        self.insert(DrawPrim::Polygon(Polygon {points, fill_color, fill_rule: FillRule::NonZero}))
+    }
+
+    /// Pushes a primitive that was created elsewhere (e.g. loaded from a file) into the display
+    /// list, returning a handle to the primitive that will be drawn
+    pub fn push_prim(&mut self, prim: DrawPrim) -> PrimHandle {
+        self.insert(prim)
     }
 
     /// Pushes a point into a polygon with the given handle
     ///
     /// Returns the index of that point in the polygon (guaranteed to be valid because points
-    /// cannot be removed from polygons).
+    /// are only removed from polygons by undoing, which never happens during an animation).
     ///
     /// Panics if the given handle does not refer to a polygon primitive.
     pub fn polygon_push(&mut self, handle: PrimHandle, point: Point) -> usize {
@@ -119,6 +735,8 @@ impl DisplayList {
 
         let index = polygon.points.len();
         polygon.points.push(point);
+
+        self.chunks.grow(handle, Rect::from_points(point, point));
         index
     }
 
@@ -136,6 +754,31 @@ impl DisplayList {
 
         // This will panic if the index is out of bounds
         polygon.points[index] = point;
+
+        // The bounds are allowed to be larger than necessary, so there is no need to shrink them
+        self.chunks.grow(handle, Rect::from_points(point, point));
+    }
+
+    /// Removes every point after the first `len` points of the polygon with the given handle
+    ///
+    /// Panics if the given handle does not refer to a polygon primitive.
+    pub fn polygon_truncate(&mut self, handle: PrimHandle, len: usize) {
+        let prim = self.items.get_mut(&handle).expect("bug: invalid handle");
+        let polygon = prim.as_polygon_mut()
+            .expect("bug: attempt to truncate a draw primitive that was not a polygon");
+
+        // The bounds are allowed to be larger than necessary, so there is no need to shrink them
+        polygon.points.truncate(len);
+    }
+
+    /// Returns the number of points in the polygon with the given handle
+    ///
+    /// Panics if the given handle does not refer to a polygon primitive.
+    pub fn polygon_len(&self, handle: PrimHandle) -> usize {
+        match self.items.get(&handle).expect("bug: invalid handle") {
+            DrawPrim::Polygon(polygon) => polygon.points.len(),
+            _ => panic!("bug: attempt to get the length of a draw primitive that was not a polygon"),
+        }
     }
 
     /// Sets the fill color of a polygon to the given color
@@ -148,30 +791,231 @@ impl DisplayList {
         polygon.fill_color = fill_color;
     }
 
+    /// Sets the fill rule of a polygon to the given rule
+    ///
+    /// Panics if the given handle does not refer to a polygon primitive.
+    pub fn polygon_set_fill_rule(&mut self, handle: PrimHandle, fill_rule: FillRule) {
+        let prim = self.items.get_mut(&handle).expect("bug: invalid handle");
+        let polygon = prim.as_polygon_mut()
+            .expect("bug: attempt to set the fill rule of a draw primitive that was not a polygon");
+        polygon.fill_rule = fill_rule;
+    }
+
+    /// Returns the handle that the next item pushed into the display list will have
+    ///
+    /// Every item pushed from now on will have a handle greater than or equal to this handle.
+    pub fn next_handle(&self) -> PrimHandle {
+        PrimHandle(self.next_id)
+    }
+
     /// Removes the given items from the display list
     pub fn remove<I: Iterator<Item=PrimHandle>>(&mut self, items: I) {
         for handle in items {
             self.items.remove(&handle);
+            self.chunks.remove(handle);
+            self.item_layers.remove(&handle);
         }
     }
 
+    /// Removes every item that lies entirely inside of the given area, except for the items that
+    /// `keep` returns true for
+    ///
+    /// Items in hidden layers are removed too. Returns the handles of the items that were removed.
+    pub fn remove_within(&mut self, area: Rect, keep: impl Fn(PrimHandle) -> bool) -> HashSet<PrimHandle> {
+        let removed: HashSet<_> = self.chunks.query(area)
+            .filter(|&handle| !keep(handle))
+            .filter(|handle| {
+                let bounds = self.items.get(handle)
+                    .expect("bug: index out of sync with display list")
+                    .bounds();
+                area.contains(bounds.min) && area.contains(bounds.max)
+            })
+            .collect();
+
+        self.remove(removed.iter().copied());
+
+        removed
+    }
+
     /// Removes all items from the display list
     ///
     /// This invalidates all handles that have been returned so far. Continuing to use them will
-    /// cause a panic. New handles created after this will still be unique.
+    /// cause a panic. New handles created after this will still be unique. The layers themselves
+    /// are kept.
     pub fn clear(&mut self) {
         self.items.clear();
+        self.chunks.clear();
+        self.item_layers.clear();
+    }
+
+    /// Continues the dash pattern of the given line or curve from where the previous line or
+    /// curve left off
+    ///
+    /// The pattern only continues if the previous line or curve uses the same pattern and ends
+    /// where the given one starts. Otherwise, the pattern starts from the beginning.
+    pub fn continue_dash(&mut self, handle: PrimHandle, previous: PrimHandle) {
+        let previous = match self.items.get(&previous) {
+            Some(DrawPrim::Line(line)) => line.dash.as_ref()
+                .map(|dash| (line.end, dash.pattern.clone(), dash.offset_after(line.length()))),
+            Some(DrawPrim::Curve(curve)) => curve.dash.as_ref()
+                .map(|dash| (curve.end, dash.pattern.clone(), dash.offset_after(curve.length()))),
+            _ => None,
+        };
+        let (end, pattern, offset) = match previous {
+            Some(previous) => previous,
+            None => return,
+        };
+
+        let (start, dash) = match self.items.get_mut(&handle) {
+            Some(DrawPrim::Line(line)) => (line.start, &mut line.dash),
+            Some(DrawPrim::Curve(curve)) => (curve.start, &mut curve.dash),
+            _ => return,
+        };
+        if let Some(dash) = dash {
+            if start == end && dash.pattern == pattern {
+                dash.offset = offset;
+            }
+        }
+    }
+
+    /// Adds a new layer on top of every other layer
+    pub fn add_layer(&mut self, id: LayerId) {
+        self.layers.push(Layer {id, is_visible: true});
+    }
+
+    /// Moves the given item into the given layer
+    ///
+    /// Items are added to the base layer when they are pushed.
+    pub fn set_layer(&mut self, handle: PrimHandle, layer: LayerId) {
+        if layer == LayerId::BASE {
+            self.item_layers.remove(&handle);
+        } else {
+            self.item_layers.insert(handle, layer);
+        }
+    }
+
+    /// Returns true if the given layer is drawn
+    pub fn is_layer_visible(&self, layer: LayerId) -> bool {
+        self.layers.iter().any(|entry| entry.id == layer && entry.is_visible)
+    }
+
+    /// Returns the layer that is drawn on top of every other visible layer
+    ///
+    /// The base layer is returned if every layer is hidden.
+    pub fn top_visible_layer(&self) -> LayerId {
+        self.layers.iter().rev()
+            .find(|entry| entry.is_visible)
+            .map(|entry| entry.id)
+            .unwrap_or(LayerId::BASE)
+    }
+
+    /// Shows or hides the given layer
+    pub fn set_layer_visible(&mut self, layer: LayerId, is_visible: bool) {
+        for entry in &mut self.layers {
+            if entry.id == layer {
+                entry.is_visible = is_visible;
+            }
+        }
+    }
+
+    /// Moves the given layer so that it is drawn on top of every other layer (if `top` is true)
+    /// or below every other layer (if `top` is false)
+    pub fn move_layer(&mut self, layer: LayerId, top: bool) {
+        if let Some(index) = self.layers.iter().position(|entry| entry.id == layer) {
+            let entry = self.layers.remove(index);
+            if top {
+                self.layers.push(entry);
+            } else {
+                self.layers.insert(0, entry);
+            }
+        }
+    }
+
+    /// Computes the smallest rectangle that contains every visible primitive in the display list
+    ///
+    /// Lines include their thickness. Degenerate polygons (fewer than 3 points) are not drawn, so
+    /// they are not included. Returns `None` if there is nothing to draw.
+    pub fn bounds(&self) -> Option<Rect> {
+        Self::bounds_of_prims(self.iter())
+    }
+
+    /// Computes the smallest rectangle that contains every visible primitive with one of the
+    /// given handles, in the same way as `bounds()`
+    ///
+    /// Handles of primitives that have been removed are ignored.
+    pub fn bounds_of(&self, handles: &[PrimHandle]) -> Option<Rect> {
+        Self::bounds_of_prims(self.handles_in_draw_order(handles.iter().copied())
+            .filter_map(|handle| self.items.get(&handle)))
+    }
+
+    fn bounds_of_prims<'a>(prims: impl Iterator<Item=&'a DrawPrim>) -> Option<Rect> {
+        prims.filter_map(|prim| match prim {
+            DrawPrim::Polygon(Polygon {points, ..}) if points.len() < 3 => None,
+            prim => Some(prim.bounds()),
+        }).fold(None, |bounds: Option<Rect>, rect| match bounds {
+            Some(bounds) => Some(bounds.union(rect)),
+            None => Some(rect),
+        })
     }
 
     /// Iterates over the items in the display list in the order in which they should be rendered
+    ///
+    /// Items in hidden layers are skipped.
     pub fn iter(&self) -> impl Iterator<Item=&DrawPrim> {
-        self.items.values()
+        self.in_draw_order(self.items.keys().copied())
+    }
+
+    /// Returns the item with the given handle, if it is still in the display list
+    pub fn get(&self, handle: PrimHandle) -> Option<&DrawPrim> {
+        self.items.get(&handle)
+    }
+
+    /// Returns the handle of every item that contains the given point, in the order in which they
+    /// should be rendered
+    ///
+    /// Items in hidden layers are skipped.
+    pub fn hit_test(&self, point: Point) -> Vec<PrimHandle> {
+        let area = Rect::from_center(point, 1.0, 1.0);
+        self.handles_in_draw_order(self.chunks.query(area))
+            .filter(|handle| self.items[handle].contains(point))
+            .collect()
+    }
+
+    /// Iterates over the items in the display list that may be visible in the given area, in the
+    /// order in which they should be rendered
+    ///
+    /// Some of the items may not actually be visible, but every item that is visible will be
+    /// included. Items in hidden layers are skipped.
+    pub fn iter_visible(&self, area: Rect) -> impl Iterator<Item=&DrawPrim> {
+        self.in_draw_order(self.chunks.query(area))
+    }
+
+    /// Returns the items with the given handles in the order in which they should be rendered,
+    /// skipping every item in a hidden layer
+    fn in_draw_order<I: Iterator<Item=PrimHandle>>(&self, handles: I) -> impl Iterator<Item=&DrawPrim> {
+        self.handles_in_draw_order(handles)
+            .map(move |handle| self.items.get(&handle).expect("bug: index out of sync with display list"))
+    }
+
+    /// Sorts the given handles by layer and then by the order they were added in, skipping every
+    /// handle in a hidden layer
+    fn handles_in_draw_order<I: Iterator<Item=PrimHandle>>(&self, handles: I) -> impl Iterator<Item=PrimHandle> {
+        let mut ordered: Vec<_> = handles.filter_map(|handle| {
+            let layer = self.item_layers.get(&handle).copied().unwrap_or(LayerId::BASE);
+            let position = self.layers.iter().position(|entry| entry.id == layer && entry.is_visible)?;
+            Some((position, handle))
+        }).collect();
+        // Handles are monotonic, so sorting by them keeps items in the order they were added
+        ordered.sort_unstable();
+
+        ordered.into_iter().map(|(_, handle)| handle)
     }
 
     /// Inserts a new primitive into the display list, returning its handle
     fn insert(&mut self, prim: DrawPrim) -> PrimHandle {
         let handle = PrimHandle(self.next_id);
         self.next_id += 1;
+        self.chunks.insert(handle, prim.bounds());
         assert!(self.items.insert(handle, prim).is_none(), "bug: handles should be unique");
         handle
     }
@@ -0,0 +1,258 @@
+use std::collections::{HashMap, BTreeSet};
+
+use crate::{Point, Rect};
+
+use super::PrimHandle;
+
+/// The width and height of each chunk in logical pixels
+const CHUNK_SIZE: f64 = 256.0;
+
+/// Items that would cover more than this many chunks are not stored in chunks at all
+///
+/// This prevents extremely long lines from creating a huge number of chunks that each only
+/// contain that one line.
+const MAX_CHUNKS_PER_ITEM: i64 = 1024;
+
+/// The position of a chunk in the grid of chunks that covers the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ChunkCoord {
+    x: i64,
+    y: i64,
+}
+
+/// An inclusive range of chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ChunkRange {
+    min: ChunkCoord,
+    max: ChunkCoord,
+}
+
+impl ChunkRange {
+    /// Returns the range of chunks that overlap with the given rectangle
+    fn covering(rect: Rect) -> Self {
+        let to_chunk = |Point {x, y}| ChunkCoord {
+            x: (x / CHUNK_SIZE).floor() as i64,
+            y: (y / CHUNK_SIZE).floor() as i64,
+        };
+
+        Self {
+            min: to_chunk(rect.min),
+            max: to_chunk(rect.max),
+        }
+    }
+
+    /// The number of chunks in this range
+    fn len(self) -> i64 {
+        let width = self.max.x.saturating_sub(self.min.x).saturating_add(1);
+        let height = self.max.y.saturating_sub(self.min.y).saturating_add(1);
+        width.saturating_mul(height)
+    }
+
+    fn contains(self, coord: ChunkCoord) -> bool {
+        coord.x >= self.min.x && coord.x <= self.max.x && coord.y >= self.min.y && coord.y <= self.max.y
+    }
+
+    /// Returns the first chunk that is in both this range and the given range, assuming that the
+    /// ranges overlap
+    fn first_shared(self, other: Self) -> ChunkCoord {
+        ChunkCoord {
+            x: self.min.x.max(other.min.x),
+            y: self.min.y.max(other.min.y),
+        }
+    }
+
+    fn coords(self) -> impl Iterator<Item=ChunkCoord> {
+        let ChunkRange {min, max} = self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| ChunkCoord {x, y}))
+    }
+}
+
+/// Where an item is stored in the index
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    /// The (possibly conservative) bounding box of the item
+    bounds: Rect,
+    /// The chunks that the item is stored in, or `None` if the item is oversized
+    chunks: Option<ChunkRange>,
+}
+
+/// A spatial index that divides the canvas into fixed size chunks
+///
+/// Each item is stored in every chunk that its bounding box overlaps. The bounding box only needs
+/// to be conservative: an item that is stored in extra chunks will still be found, it just might
+/// be drawn even though it isn't visible.
+#[derive(Debug, Default)]
+pub struct ChunkIndex {
+    chunks: HashMap<ChunkCoord, BTreeSet<PrimHandle>>,
+    /// Items that cover too many chunks to be worth storing in chunks
+    oversized: BTreeSet<PrimHandle>,
+    placements: HashMap<PrimHandle, Placement>,
+}
+
+impl ChunkIndex {
+    /// Stores the given item in the index with the given bounding box, replacing any previous
+    /// bounding box for that item
+    pub fn insert(&mut self, handle: PrimHandle, bounds: Rect) {
+        self.remove(handle);
+
+        let range = ChunkRange::covering(bounds);
+        let chunks = if range.len() > MAX_CHUNKS_PER_ITEM {
+            self.oversized.insert(handle);
+            None
+        } else {
+            for coord in range.coords() {
+                self.chunks.entry(coord).or_default().insert(handle);
+            }
+            Some(range)
+        };
+
+        self.placements.insert(handle, Placement {bounds, chunks});
+    }
+
+    /// Grows the bounding box of an item that is already in the index to also contain the given
+    /// rectangle
+    ///
+    /// Panics if the item is not in the index.
+    pub fn grow(&mut self, handle: PrimHandle, rect: Rect) {
+        let placement = self.placements.get(&handle).expect("bug: invalid handle");
+        let bounds = placement.bounds.union(rect);
+        // Avoid re-inserting into every chunk if nothing has changed
+        if bounds == placement.bounds {
+            return;
+        }
+
+        let range = ChunkRange::covering(bounds);
+        match placement.chunks {
+            // Growing within the same set of chunks only requires updating the bounds
+            Some(old_range) if old_range == range => {
+                self.placements.insert(handle, Placement {bounds, chunks: Some(range)});
+            },
+            _ => self.insert(handle, bounds),
+        }
+    }
+
+    /// Removes the given item from the index (if it is present)
+    pub fn remove(&mut self, handle: PrimHandle) {
+        let placement = match self.placements.remove(&handle) {
+            Some(placement) => placement,
+            None => return,
+        };
+
+        match placement.chunks {
+            Some(range) => for coord in range.coords() {
+                if let Some(chunk) = self.chunks.get_mut(&coord) {
+                    chunk.remove(&handle);
+                    if chunk.is_empty() {
+                        self.chunks.remove(&coord);
+                    }
+                }
+            },
+            None => {
+                self.oversized.remove(&handle);
+            },
+        }
+    }
+
+    /// Removes all items from the index
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.oversized.clear();
+        self.placements.clear();
+    }
+
+    /// Iterates over the handles of every item that may overlap with the given area
+    ///
+    /// Each handle is returned exactly once, in no particular order. The query itself does not
+    /// allocate, but callers that need the draw order still collect and sort the handles.
+    pub fn query(&self, area: Rect) -> impl Iterator<Item=PrimHandle> + '_ {
+        let range = ChunkRange::covering(area);
+
+        // If the area is very large, it is faster to go through the chunks that actually exist
+        let (existing, covered) = if range.len() > self.chunks.len() as i64 {
+            let existing = self.chunks.iter()
+                .filter(move |&(&coord, _)| range.contains(coord))
+                .map(|(&coord, chunk)| (coord, chunk));
+            (Some(existing), None)
+        } else {
+            let covered = range.coords()
+                .filter_map(move |coord| Some((coord, self.chunks.get(&coord)?)));
+            (None, Some(covered))
+        };
+        let chunks = existing.into_iter().flatten().chain(covered.into_iter().flatten());
+
+        // An item stored in several of the chunks is only returned from the first one
+        let chunked = chunks.flat_map(move |(coord, chunk)| chunk.iter().copied().filter(move |handle| {
+            let placement = self.placements.get(handle).expect("bug: index out of sync with placements");
+            let item_range = placement.chunks.expect("bug: oversized items should not be stored in chunks");
+            item_range.first_shared(range) == coord
+        }));
+
+        self.oversized.iter().copied().chain(chunked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x1: f64, y1: f64, x2: f64, y2: f64) -> Rect {
+        Rect::from_points([x1, y1], [x2, y2])
+    }
+
+    /// Returns the handles that the index finds in the given area, in sorted order
+    fn query(index: &ChunkIndex, area: Rect) -> Vec<PrimHandle> {
+        let mut handles: Vec<_> = index.query(area).collect();
+        handles.sort_unstable();
+        handles
+    }
+
+    #[test]
+    fn query_skips_far_away_items() {
+        let mut index = ChunkIndex::default();
+        index.insert(PrimHandle(0), rect(0.0, 0.0, 10.0, 10.0));
+        index.insert(PrimHandle(1), rect(5000.0, 5000.0, 5010.0, 5010.0));
+        index.insert(PrimHandle(2), rect(-300.0, -20.0, 20.0, 30.0));
+
+        assert_eq!(query(&index, rect(-400.0, -300.0, 400.0, 300.0)), vec![PrimHandle(0), PrimHandle(2)]);
+        assert_eq!(query(&index, rect(4900.0, 4900.0, 5100.0, 5100.0)), vec![PrimHandle(1)]);
+    }
+
+    #[test]
+    fn query_returns_each_item_once() {
+        let mut index = ChunkIndex::default();
+        // Covers a 4x4 square of chunks
+        index.insert(PrimHandle(0), rect(-300.0, -300.0, 300.0, 300.0));
+        index.insert(PrimHandle(1), rect(0.0, 0.0, 10.0, 10.0));
+
+        assert_eq!(query(&index, rect(-1000.0, -1000.0, 1000.0, 1000.0)), vec![PrimHandle(0), PrimHandle(1)]);
+        assert_eq!(query(&index, rect(-100.0, -100.0, 100.0, 100.0)), vec![PrimHandle(0), PrimHandle(1)]);
+        // Larger than the number of chunks that exist
+        assert_eq!(query(&index, rect(-1e6, -1e6, 1e6, 1e6)), vec![PrimHandle(0), PrimHandle(1)]);
+        assert_eq!(query(&index, rect(200.0, -280.0, 250.0, -270.0)), vec![PrimHandle(0)]);
+    }
+
+    #[test]
+    fn grow_and_remove() {
+        let mut index = ChunkIndex::default();
+        index.insert(PrimHandle(0), rect(0.0, 0.0, 10.0, 10.0));
+        assert!(query(&index, rect(1000.0, 0.0, 1010.0, 10.0)).is_empty());
+
+        index.grow(PrimHandle(0), rect(1000.0, 0.0, 1000.0, 0.0));
+        assert_eq!(query(&index, rect(1000.0, 0.0, 1010.0, 10.0)), vec![PrimHandle(0)]);
+
+        index.remove(PrimHandle(0));
+        assert!(query(&index, rect(-1e6, -1e6, 1e6, 1e6)).is_empty());
+        assert!(index.chunks.is_empty());
+    }
+
+    #[test]
+    fn oversized_items_always_found() {
+        let mut index = ChunkIndex::default();
+        index.insert(PrimHandle(0), rect(-1e9, 0.0, 1e9, 1.0));
+        assert!(index.chunks.is_empty());
+        assert_eq!(query(&index, rect(40.0, 40.0, 50.0, 50.0)), vec![PrimHandle(0)]);
+
+        index.remove(PrimHandle(0));
+        assert!(query(&index, rect(40.0, 40.0, 50.0, 50.0)).is_empty());
+    }
+}
@@ -1,15 +1,26 @@
+use std::env;
+use std::process;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter};
 use std::fmt::Write;
-use std::path::Path as FilePath;
+use std::path::{Path as FilePath, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use thiserror::Error;
+use tokio::task;
 use serde::{Serialize, Deserialize};
-use svg::node::element::{Line, Polygon, Rectangle};
+use svg::Document;
+use svg::node::Node;
+use svg::node::element::{Circle, Definitions, Group, Image, Line, Mask, Path, Pattern, Polygon, Rectangle};
 
-use crate::Color;
+use crate::{Color, Rect, FillRule, BlendMode};
 
-use super::display_list::{DisplayList, DrawPrim, Line as DrawLine, Polygon as DrawPolygon};
+use super::display_list::{DisplayList, DrawPrim, Line as DrawLine, Polygon as DrawPolygon, Circle as DrawCircle, Curve as DrawCurve, Text as DrawText, Path as DrawPath, Image as DrawImage, Dash};
+use super::image::{ImageData, BackgroundMode};
+use super::symmetry;
+use super::raster::{self, Pixmap};
 use super::super::{
-    coords::ScreenPoint,
+    coords::{self, ScreenPoint},
     state::DrawingState,
 };
 
@@ -24,6 +35,107 @@ fn px(value: f64) -> String {
     format!("{}px", value)
 }
 
+/// Converts the lengths of a dash pattern into a comma-separated list, scaling each one by the
+/// given amount
+fn dash_array(dash: &Dash, scale: f64) -> String {
+    let lengths: Vec<_> = dash.pattern.iter().map(|length| (length * scale).to_string()).collect();
+    lengths.join(",")
+}
+
+/// Returns the CSS property that blends a line or curve with what is underneath it, or `None` if
+/// it is drawn normally
+///
+/// Erasing is done with a mask instead, since SVG has no blend mode for it.
+fn blend_style(blend_mode: BlendMode) -> Option<&'static str> {
+    match blend_mode {
+        BlendMode::Alpha | BlendMode::Erase => None,
+        BlendMode::Additive => Some("mix-blend-mode: plus-lighter"),
+        BlendMode::Multiply => Some("mix-blend-mode: multiply"),
+    }
+}
+
+/// The color that a line or curve is drawn with, which for erasing is the color that it is drawn
+/// with in the mask
+fn stroke_color(color: Color, blend_mode: BlendMode) -> String {
+    match blend_mode {
+        // Black hides everything under it in a mask, so only the alpha of the color matters
+        BlendMode::Erase => rgba(Color {red: 0.0, green: 0.0, blue: 0.0, ..color}),
+        _ => rgba(color),
+    }
+}
+
+/// Collects what is drawn into a document, using masks for lines and curves that erase part of it
+struct Layers {
+    /// Everything drawn so far that has not been erased, except by the pending eraser
+    content: Group,
+    /// The masks used to erase parts of the content
+    masks: Definitions,
+    /// The number of masks used so far
+    mask_count: usize,
+    /// The lines and curves that erase the content, which are collected until something is drawn
+    /// normally again
+    eraser: Option<Mask>,
+}
+
+impl Layers {
+    fn new() -> Self {
+        Self {content: Group::new(), masks: Definitions::new(), mask_count: 0, eraser: None}
+    }
+
+    /// Adds the given element over everything drawn so far
+    fn add<T: Node>(&mut self, node: T) {
+        self.apply_eraser();
+        self.content.append(node);
+    }
+
+    /// Adds the given line or curve with the given blend mode
+    fn add_stroke<T: Node>(&mut self, node: T, blend_mode: BlendMode) {
+        if blend_mode != BlendMode::Erase {
+            self.add(node);
+            return;
+        }
+
+        let mask_count = &mut self.mask_count;
+        let eraser = self.eraser.take().unwrap_or_else(|| {
+            *mask_count += 1;
+            // White keeps everything that is not erased
+            Mask::new()
+                .set("id", format!("erase-{}", mask_count))
+                .set("maskUnits", "userSpaceOnUse")
+                .set("x", 0)
+                .set("y", 0)
+                .set("width", "100%")
+                .set("height", "100%")
+                .add(Rectangle::new()
+                    .set("width", "100%")
+                    .set("height", "100%")
+                    .set("fill", "white"))
+        });
+        self.eraser = Some(eraser.add(node));
+    }
+
+    /// Erases the content with the pending eraser (if any)
+    fn apply_eraser(&mut self) {
+        if let Some(eraser) = self.eraser.take() {
+            let content = std::mem::replace(&mut self.content, Group::new());
+            self.content = Group::new()
+                .set("mask", format!("url(#erase-{})", self.mask_count))
+                .add(content);
+            self.masks.append(eraser);
+        }
+    }
+
+    /// Adds everything that was drawn to the document
+    fn finish(mut self, document: Document) -> Document {
+        self.apply_eraser();
+        if self.mask_count > 0 {
+            document.add(self.masks).add(self.content)
+        } else {
+            document.add(self.content)
+        }
+    }
+}
+
 /// Converts a list of pairs into a space-separated list of comma-separated pairs
 ///
 /// The list must be non-empty
@@ -38,18 +150,269 @@ fn pairs(mut items: impl Iterator<Item=ScreenPoint>) -> String {
     out
 }
 
+/// Encodes the given bytes in base64 (with padding), for use in a data URL
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate()
+            .fold(0u32, |group, (i, &byte)| group | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
 /// An error produced while exporting the drawing
 #[derive(Debug, Error, Serialize, Deserialize)]
 #[error("{0}")]
-pub struct ExportError(String);
+pub struct ExportError(pub(super) String);
+
+impl From<crate::Error> for ExportError {
+    fn from(err: crate::Error) -> Self {
+        ExportError(err.to_string())
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(err: io::Error) -> Self {
+        ExportError(err.to_string())
+    }
+}
+
+/// The area of the drawing that should be exported
+///
+/// See [`ExportOptions`](struct.ExportOptions.html) for more information.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ExportRegion {
+    /// Export the area of the drawing that is currently visible in the window
+    Window,
+    /// Export everything that has been drawn, plus the given margin on every side
+    ///
+    /// If nothing has been drawn yet, the area visible in the window is exported instead.
+    Content {
+        /// The amount of extra space to leave around the drawing (in logical pixels)
+        margin: f64,
+    },
+    /// Export the given area of the drawing (in the coordinate system of the drawing)
+    Rect(Rect),
+}
+
+/// Options for controlling which part of the drawing gets exported and at what resolution
+///
+/// The default options export the area visible in the window at its current size.
+///
+/// ```rust
+/// use turtle::{ExportOptions, ExportRegion, Rect};
+///
+/// let options = ExportOptions::default();
+/// assert_eq!(options.region, ExportRegion::Window);
+/// assert_eq!(options.scale, 1.0);
+///
+/// // Export a 200x100 area around the origin at twice the resolution
+/// let options = ExportOptions {
+///     region: ExportRegion::Rect(Rect::from_center([0.0, 0.0], 200.0, 100.0)),
+///     scale: 2.0,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExportOptions {
+    /// The area of the drawing to export
+    pub region: ExportRegion,
+    /// The number of pixels in the exported image for each logical pixel in the drawing
+    ///
+    /// A scale of `2.0` will produce an image with twice the width and height of the region. The
+    /// scale must be positive.
+    pub scale: f64,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            region: ExportRegion::Window,
+            scale: 1.0,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Returns true if these options describe a non-empty area at a valid scale
+    pub(crate) fn is_valid(&self) -> bool {
+        self.region.is_valid() && self.scale.is_finite() && self.scale > 0.0
+    }
+}
+
+impl ExportRegion {
+    pub(super) fn is_valid(self) -> bool {
+        match self {
+            ExportRegion::Window => true,
+            ExportRegion::Content {margin} => margin.is_finite(),
+            ExportRegion::Rect(rect) => rect.min.is_finite() && rect.max.is_finite() && !rect.is_empty(),
+        }
+    }
+
+    /// Determines the area of the drawing that this region refers to
+    pub(super) fn area(self, display_list: &DisplayList, drawing: &DrawingState) -> Rect {
+        let window = Rect::from_center(drawing.center, drawing.width as f64, drawing.height as f64);
+
+        match self {
+            ExportRegion::Window => window,
+            ExportRegion::Content {margin} => symmetry::bounds(display_list, drawing.symmetry)
+                .map(|bounds| bounds.inflate(margin))
+                .unwrap_or(window),
+            ExportRegion::Rect(rect) => drawing.rect_to_logical(rect),
+        }
+    }
+}
+
+/// The size of the paper used when printing
+///
+/// See [`PrintOptions`](struct.PrintOptions.html) for more information.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PaperSize {
+    /// ISO A4 paper (210mm by 297mm)
+    A4,
+    /// US Letter paper (8.5in by 11in)
+    Letter,
+    /// A custom paper size, specified in millimeters
+    Custom {
+        /// The width of the paper in millimeters
+        width: f64,
+        /// The height of the paper in millimeters
+        height: f64,
+    },
+}
+
+impl PaperSize {
+    /// Returns the width and height of the paper in millimeters, in portrait orientation
+    pub(super) fn dimensions(self) -> (f64, f64) {
+        match self {
+            PaperSize::A4 => (210.0, 297.0),
+            PaperSize::Letter => (215.9, 279.4),
+            PaperSize::Custom {width, height} => (width, height),
+        }
+    }
+}
+
+/// Options for controlling how the drawing is printed
+///
+/// The selected region of the drawing is scaled to fit on the page inside of the margins, keeping
+/// its aspect ratio. To print the drawing at its true size instead, set `true_scale` and use
+/// [`Drawing::set_dpi()`] to choose how many logical pixels are in an inch. The default options
+/// print the area visible in the window on a portrait A4 page with a 10mm margin.
+///
+/// ```rust
+/// use turtle::{PrintOptions, PaperSize, ExportRegion};
+///
+/// let options = PrintOptions::default();
+/// assert_eq!(options.region, ExportRegion::Window);
+/// assert_eq!(options.paper, PaperSize::A4);
+///
+/// // Print everything that has been drawn on sideways letter paper
+/// let options = PrintOptions {
+///     region: ExportRegion::Content {margin: 0.0},
+///     paper: PaperSize::Letter,
+///     landscape: true,
+///     ..PrintOptions::default()
+/// };
+/// ```
+///
+/// [`Drawing::set_dpi()`]: struct.Drawing.html#method.set_dpi
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PrintOptions {
+    /// The area of the drawing to print
+    pub region: ExportRegion,
+    /// The size of the paper to print on
+    pub paper: PaperSize,
+    /// If true, the paper is turned sideways so that it is wider than it is tall
+    pub landscape: bool,
+    /// The amount of empty space to leave at each edge of the paper (in millimeters)
+    pub margin: f64,
+    /// If true, the region is printed at the physical size given by the DPI of the drawing and
+    /// centered on the page instead of being scaled to fit inside of the margins
+    ///
+    /// Anything that does not fit on the page is cut off.
+    pub true_scale: bool,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            region: ExportRegion::Window,
+            paper: PaperSize::A4,
+            landscape: false,
+            margin: 10.0,
+            true_scale: false,
+        }
+    }
+}
+
+impl PrintOptions {
+    /// Returns true if these options describe a non-empty area and leave some space on the paper
+    /// for it to be printed
+    pub(crate) fn is_valid(&self) -> bool {
+        let (width, height) = self.paper.dimensions();
+        let paper_valid = width.is_finite() && height.is_finite();
+        let margin_valid = self.margin.is_finite() && self.margin >= 0.0
+            && self.margin * 2.0 < width && self.margin * 2.0 < height;
+
+        self.region.is_valid() && paper_valid && margin_valid
+    }
+
+    /// Returns the width and height of the page in millimeters, taking the orientation into account
+    fn page_size(&self) -> (f64, f64) {
+        let (width, height) = self.paper.dimensions();
+        if self.landscape {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+}
 
 pub fn save_svg(
     display_list: &DisplayList,
     drawing: &DrawingState,
+    background_image: Option<(&ImageData, BackgroundMode)>,
+    options: &ExportOptions,
     path: &FilePath,
 ) -> Result<(), ExportError> {
-    let mut document = svg::Document::new()
-        .set("viewBox", (0, 0, drawing.width, drawing.height));
+    let document = svg_document(display_list, drawing, background_image, options);
+    svg::save(path, &document).map_err(|err| ExportError(err.to_string()))
+}
+
+/// Returns the contents of the SVG file that `save_svg()` would save
+pub fn svg_string(
+    display_list: &DisplayList,
+    drawing: &DrawingState,
+    background_image: Option<(&ImageData, BackgroundMode)>,
+    options: &ExportOptions,
+) -> String {
+    svg_document(display_list, drawing, background_image, options).to_string()
+}
+
+fn svg_document(
+    display_list: &DisplayList,
+    drawing: &DrawingState,
+    background_image: Option<(&ImageData, BackgroundMode)>,
+    options: &ExportOptions,
+) -> Document {
+    let area = options.region.area(display_list, drawing);
+    let scale = options.scale;
+    let width = (area.width() * scale).ceil().max(1.0);
+    let height = (area.height() * scale).ceil().max(1.0);
+
+    let mut document = Document::new()
+        .set("width", width)
+        .set("height", height)
+        .set("viewBox", (0, 0, width, height));
 
     // set background color - https://stackoverflow.com/a/11293812/9276882
     let background = Rectangle::new()
@@ -57,48 +420,494 @@ pub fn save_svg(
         .set("height", "100%")
         .set("fill", rgba(drawing.background));
     document = document.add(background);
+    let frame = Rect::from_points([0.0, 0.0], [width, height]);
+    document = add_background_image(document, background_image, frame, scale);
 
-    let center = drawing.center;
     let image_center = ScreenPoint {
-        x: drawing.width as f64 / 2.0,
-        y: drawing.height as f64 / 2.0,
+        x: width / 2.0,
+        y: height / 2.0,
+    };
+    add_prims(document, display_list, drawing, area, scale, image_center)
+}
+
+pub fn save_png(
+    display_list: &DisplayList,
+    drawing: &DrawingState,
+    background_image: Option<(&ImageData, BackgroundMode)>,
+    options: &ExportOptions,
+    path: &FilePath,
+) -> Result<(), ExportError> {
+    let area = options.region.area(display_list, drawing);
+    let pixmap = raster::rasterize(display_list, area, options.scale, drawing.background, background_image, drawing.symmetry)
+        .ok_or_else(|| ExportError("the exported image would be too large, try a smaller scale or region".to_string()))?;
+
+    let file = File::create(path).map_err(|err| ExportError(err.to_string()))?;
+    write_png(&pixmap, file, None)
+}
+
+/// Writes the image to the given file as a PNG, recording its resolution (in dots per inch) if
+/// there is one so that it is shown at the right physical size
+fn write_png(pixmap: &Pixmap, file: File, dpi: Option<f64>) -> Result<(), ExportError> {
+    let mut encoder = png::Encoder::new(BufWriter::new(file), pixmap.width(), pixmap.height());
+    encoder.set_color(png::ColorType::RGBA);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(|err| ExportError(err.to_string()))?;
+    if let Some(dpi) = dpi {
+        // The pHYs chunk is in pixels per meter, with a unit of 1 meaning meters
+        let pixels_per_meter = (coords::mm_to_px(1000.0, dpi).round() as u32).to_be_bytes();
+        let mut phys = Vec::with_capacity(9);
+        phys.extend_from_slice(&pixels_per_meter);
+        phys.extend_from_slice(&pixels_per_meter);
+        phys.push(1);
+        writer.write_chunk(*b"pHYs", &phys).map_err(|err| ExportError(err.to_string()))?;
+    }
+    writer.write_image_data(&pixmap.to_rgba8()).map_err(|err| ExportError(err.to_string()))
+}
+
+/// The resolution that pages are rendered at for printing (in dots per inch)
+const PRINT_DPI: f64 = 300.0;
+
+/// A page showing the drawing, rendered and ready to be printed
+#[derive(Debug)]
+pub struct PrintPage {
+    pixmap: Pixmap,
+}
+
+/// Renders the page that `options` describes, sized in physical units
+///
+/// Only the printed area is filled with the background color so that the margins stay blank.
+pub fn print_page(
+    display_list: &DisplayList,
+    drawing: &DrawingState,
+    background_image: Option<(&ImageData, BackgroundMode)>,
+    options: &PrintOptions,
+) -> Result<PrintPage, ExportError> {
+    let area = options.region.area(display_list, drawing);
+    let (page_width, page_height) = options.page_size();
+    let scale = page_scale(
+        area,
+        (page_width, page_height),
+        options.margin,
+        options.true_scale,
+        drawing.dpi,
+    );
+
+    // Anything that would not fit on the page is never rendered
+    let area = Rect::from_center(
+        area.center(),
+        area.width().min(page_width / scale),
+        area.height().min(page_height / scale),
+    );
+
+    let too_large = || ExportError("the printed page would be too large, try a smaller paper size".to_string());
+    let dots_per_mm = coords::mm_to_px(1.0, PRINT_DPI);
+    let white = Color {red: 255.0, green: 255.0, blue: 255.0, alpha: 1.0};
+    let mut pixmap = Pixmap::new(
+        (page_width * dots_per_mm).round() as u32,
+        (page_height * dots_per_mm).round() as u32,
+        white,
+    ).ok_or_else(too_large)?;
+    let frame = raster::rasterize(display_list, area, scale * dots_per_mm, drawing.background, background_image, drawing.symmetry)
+        .ok_or_else(too_large)?;
+
+    let x = (pixmap.width() as i64 - frame.width() as i64) / 2;
+    let y = (pixmap.height() as i64 - frame.height() as i64) / 2;
+    pixmap.copy_from(&frame, x, y);
+
+    Ok(PrintPage {pixmap})
+}
+
+impl PrintPage {
+    /// Prints the page on a thread where blocking is allowed, since handing it to the operating
+    /// system can take a long time (e.g. while a print dialog is open)
+    pub async fn print_in_background(self) -> Result<(), ExportError> {
+        task::spawn_blocking(move || self.print()).await.unwrap_or_else(|err| {
+            Err(ExportError(format!("printing stopped unexpectedly: {}", err)))
+        })
+    }
+
+    /// Hands the page to the operating system to be printed, returning once it has been handed off
+    pub fn print(self) -> Result<(), ExportError> {
+        let (file, path) = PrintFile::create().map_err(|err| ExportError(err.to_string()))?;
+        write_png(&self.pixmap, file, Some(PRINT_DPI))?;
+
+        send_to_printer(&path.0)
+    }
+}
+
+/// The temporary file that a page is saved to while it is being printed, removed when dropped
+#[derive(Debug)]
+struct PrintFile(PathBuf);
+
+impl PrintFile {
+    /// Creates a new file that no other page is using
+    fn create() -> io::Result<(File, Self)> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        loop {
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            let path = env::temp_dir().join(format!("turtle-print-{}-{}.png", process::id(), id));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => return Ok((file, PrintFile(path))),
+                // Left behind by another process that had the same ID
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl Drop for PrintFile {
+    fn drop(&mut self) {
+        // Ignoring the error since there is nothing left to do if the file cannot be removed
+        fs::remove_file(&self.0).unwrap_or(());
+    }
+}
+
+/// Adds the background image (if any) to the document, arranged within the given frame
+///
+/// The frame is in the coordinates of the document, with y increasing downwards. Each pixel of
+/// the image is `scale` units wide unless it is stretched.
+fn add_background_image(
+    document: Document,
+    background_image: Option<(&ImageData, BackgroundMode)>,
+    frame: Rect,
+    scale: f64,
+) -> Document {
+    let (image, mode) = match background_image {
+        Some(background_image) => background_image,
+        None => return document,
     };
-    for prim in display_list.iter() {
-        match prim {
-            &DrawPrim::Line(DrawLine {start, end, thickness, color}) => {
-                let start = ScreenPoint::from_logical(start, 1.0, center, image_center);
-                let end = ScreenPoint::from_logical(end, 1.0, center, image_center);
 
-                let line = Line::new()
+    let placement = mode.placement(image, frame.width(), frame.height(), scale);
+    let href = format!("data:image/png;base64,{}", base64(&image.encode_png()));
+    let image = Image::new()
+        .set("width", placement.width())
+        .set("height", placement.height())
+        .set("preserveAspectRatio", "none")
+        .set("image-rendering", "pixelated")
+        .set("href", href);
+
+    match mode {
+        BackgroundMode::Tile => {
+            // The pattern repeats the image across the entire frame, starting from its top left
+            let pattern = Pattern::new()
+                .set("id", "background-image")
+                .set("patternUnits", "userSpaceOnUse")
+                .set("x", frame.min.x)
+                .set("y", frame.min.y)
+                .set("width", placement.width())
+                .set("height", placement.height())
+                .add(image);
+            let tiles = Rectangle::new()
+                .set("x", frame.min.x)
+                .set("y", frame.min.y)
+                .set("width", frame.width())
+                .set("height", frame.height())
+                .set("fill", "url(#background-image)");
+
+            document.add(Definitions::new().add(pattern)).add(tiles)
+        },
+
+        BackgroundMode::Stretch | BackgroundMode::Center => {
+            document.add(image
+                .set("x", frame.min.x + placement.min.x)
+                .set("y", frame.min.y + placement.min.y))
+        },
+    }
+}
+
+/// Returns the number of millimeters on the page for each logical pixel in the given area
+///
+/// With `true_scale`, this is the physical size of a pixel at the given DPI. Otherwise, the area
+/// is fit within the margins of the page, preserving its aspect ratio.
+pub(super) fn page_scale(area: Rect, page_size: (f64, f64), margin: f64, true_scale: bool, dpi: f64) -> f64 {
+    if true_scale {
+        return coords::px_to_mm(1.0, dpi);
+    }
+
+    let (page_width, page_height) = page_size;
+    ((page_width - margin * 2.0) / area.width().max(1.0))
+        .min((page_height - margin * 2.0) / area.height().max(1.0))
+}
+
+/// Asks the operating system to print the image at the given path
+///
+/// A print dialog is shown on Windows and macOS. Other platforms send the image straight to the
+/// default printer with the `lp` command, at the resolution the page was rendered at.
+fn send_to_printer(path: &FilePath) -> Result<(), ExportError> {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "test")] {
+            // Never actually print anything during tests
+            let _ = path;
+            Ok(())
+
+        } else {
+            use std::process::Command;
+
+            // Each command only exits once the image has been handed off, so the file can be
+            // removed right after
+            #[cfg(target_os = "windows")]
+            let status = Command::new("powershell")
+                .args(&["-NoProfile", "-Command"])
+                .arg(format!("Start-Process -Verb Print -Wait -FilePath {}", powershell_literal(path)))
+                .status();
+            #[cfg(target_os = "macos")]
+            let status = Command::new("osascript")
+                .args(&[
+                    "-e", "on run argv",
+                    "-e", "tell application \"Preview\" to print POSIX file (item 1 of argv) with print dialog",
+                    "-e", "end run",
+                ])
+                .arg(path)
+                .status();
+            #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+            let status = Command::new("lp")
+                .arg("-o")
+                .arg(format!("ppi={}", PRINT_DPI))
+                .arg(path)
+                .status();
+
+            match status {
+                Ok(status) if status.success() => Ok(()),
+                Ok(status) => Err(ExportError(format!("printing failed ({})", status))),
+                Err(err) => Err(ExportError(format!("unable to start printing: {}", err))),
+            }
+        }
+    }
+}
+
+/// Quotes the given path as a PowerShell string literal
+///
+/// PowerShell joins every argument after `-Command` into a single script, so an unquoted path
+/// would be split at its spaces. Nothing is expanded within single quotes, and a quote is written
+/// by doubling it (PowerShell also treats the curly single quotes as quotes).
+#[cfg(any(target_os = "windows", test))]
+#[cfg_attr(feature = "test", allow(dead_code))]
+fn powershell_literal(path: &FilePath) -> String {
+    let mut literal = String::from("'");
+    for c in path.to_string_lossy().chars() {
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+            literal.push(c);
+        }
+        literal.push(c);
+    }
+    literal.push('\'');
+    literal
+}
+
+/// Adds every primitive in the given area of the drawing to the document
+///
+/// The area is scaled by `scale` and centered at `image_center` in the document.
+fn add_prims(
+    document: Document,
+    display_list: &DisplayList,
+    drawing: &DrawingState,
+    area: Rect,
+    scale: f64,
+    image_center: ScreenPoint,
+) -> Document {
+    let center = area.center();
+    let mut layers = Layers::new();
+    for prim in symmetry::visible_prims(display_list, area, drawing.symmetry) {
+        match prim.as_ref() {
+            &DrawPrim::Line(DrawLine {start, end, thickness, color, ref dash, blend_mode}) => {
+                let start = ScreenPoint::from_logical(start, scale, center, image_center);
+                let end = ScreenPoint::from_logical(end, scale, center, image_center);
+
+                let mut line = Line::new()
                     .set("x1", start.x)
                     .set("y1", start.y)
                     .set("x2", end.x)
                     .set("y2", end.y)
                     .set("stroke-linecap", "round")
                     .set("stroke-linejoin", "round")
-                    .set("stroke", rgba(color))
-                    .set("stroke-width", px(thickness));
+                    .set("stroke", stroke_color(color, blend_mode))
+                    .set("stroke-width", px(thickness * scale));
+                if let Some(dash) = dash {
+                    line = line
+                        .set("stroke-dasharray", dash_array(dash, scale))
+                        .set("stroke-dashoffset", dash.offset * scale);
+                }
+                if let Some(style) = blend_style(blend_mode) {
+                    line = line.set("style", style);
+                }
 
-                document = document.add(line);
+                layers.add_stroke(line, blend_mode);
             },
 
-            &DrawPrim::Polygon(DrawPolygon {ref points, fill_color}) => {
+            &DrawPrim::Polygon(DrawPolygon {ref points, fill_color, fill_rule}) => {
                 // Skip obviously degenerate polygons
                 if points.len() <= 2 {
                     continue;
                 }
 
                 let points = points.iter()
-                    .map(|&p| ScreenPoint::from_logical(p, 1.0, center, image_center));
+                    .map(|&p| ScreenPoint::from_logical(p, scale, center, image_center));
                 let polygon = Polygon::new()
                     .set("points", pairs(points))
+                    .set("fill-rule", match fill_rule {
+                        FillRule::NonZero => "nonzero",
+                        FillRule::EvenOdd => "evenodd",
+                    })
+                    .set("fill", rgba(fill_color));
+
+                layers.add(polygon);
+            },
+
+            &DrawPrim::Circle(DrawCircle {center: circle_center, radius, thickness, color, fill_color}) => {
+                let circle_center = ScreenPoint::from_logical(circle_center, scale, center, image_center);
+
+                let mut circle = Circle::new()
+                    .set("cx", circle_center.x)
+                    .set("cy", circle_center.y)
+                    .set("r", radius * scale)
+                    .set("fill", rgba(fill_color));
+                if thickness > 0.0 {
+                    circle = circle
+                        .set("stroke", rgba(color))
+                        .set("stroke-width", px(thickness * scale));
+                }
+
+                layers.add(circle);
+            },
+
+            &DrawPrim::Curve(DrawCurve {start, control1, control2, end, thickness, color, ref dash, blend_mode}) => {
+                let [start, control1, control2, end] = [start, control1, control2, end]
+                    .map(|p| ScreenPoint::from_logical(p, scale, center, image_center));
+                let data = format!(
+                    "M {},{} C {},{} {},{} {},{}",
+                    start.x, start.y,
+                    control1.x, control1.y,
+                    control2.x, control2.y,
+                    end.x, end.y,
+                );
+
+                let mut path = Path::new()
+                    .set("d", data)
+                    .set("fill", "none")
+                    .set("stroke-linecap", "round")
+                    .set("stroke", stroke_color(color, blend_mode))
+                    .set("stroke-width", px(thickness * scale));
+                if let Some(dash) = dash {
+                    path = path
+                        .set("stroke-dasharray", dash_array(dash, scale))
+                        .set("stroke-dashoffset", dash.offset * scale);
+                }
+                if let Some(style) = blend_style(blend_mode) {
+                    path = path.set("style", style);
+                }
+
+                layers.add_stroke(path, blend_mode);
+            },
+
+            &DrawPrim::Text(DrawText {ref outlines, color}) => {
+                // Exporting the outlines (instead of a <text> element) makes sure that the text
+                // looks exactly the same no matter which fonts are installed where it is viewed
+                let data: Vec<_> = outlines.iter().map(|outline| {
+                    let points = outline.iter()
+                        .map(|&p| ScreenPoint::from_logical(p, scale, center, image_center));
+                    format!("M {} Z", pairs(points))
+                }).collect();
+
+                let path = Path::new()
+                    .set("d", data.join(" "))
                     .set("fill-rule", "nonzero")
+                    .set("fill", rgba(color));
+
+                layers.add(path);
+            },
+
+            &DrawPrim::Path(DrawPath {ref subpaths, fill_color, fill_rule}) => {
+                let data: Vec<_> = subpaths.iter().map(|subpath| {
+                    let points = subpath.iter()
+                        .map(|&p| ScreenPoint::from_logical(p, scale, center, image_center));
+                    format!("M {} Z", pairs(points))
+                }).collect();
+
+                let path = Path::new()
+                    .set("d", data.join(" "))
+                    .set("fill-rule", match fill_rule {
+                        FillRule::NonZero => "nonzero",
+                        FillRule::EvenOdd => "evenodd",
+                    })
                     .set("fill", rgba(fill_color));
 
-                document = document.add(polygon);
+                layers.add(path);
+            },
+
+            DrawPrim::Image(image) => {
+                let DrawImage {ref data, ..} = *image;
+                let [top_left, top_right, bottom_left] = image.corners()
+                    .map(|p| ScreenPoint::from_logical(p, scale, center, image_center));
+
+                // Maps the pixels of the image onto its corners, which also rotates the image
+                let (width, height) = (data.width as f64, data.height as f64);
+                let transform = format!(
+                    "matrix({} {} {} {} {} {})",
+                    (top_right.x - top_left.x) / width,
+                    (top_right.y - top_left.y) / width,
+                    (bottom_left.x - top_left.x) / height,
+                    (bottom_left.y - top_left.y) / height,
+                    top_left.x,
+                    top_left.y,
+                );
+
+                let image = Image::new()
+                    .set("width", width)
+                    .set("height", height)
+                    .set("preserveAspectRatio", "none")
+                    .set("image-rendering", "pixelated")
+                    .set("transform", transform)
+                    .set("href", format!("data:image/png;base64,{}", base64(&data.encode_png())));
+
+                layers.add(image);
             },
         }
     }
 
-    svg::save(path, &document).map_err(|err| ExportError(err.to_string()))
+    layers.finish(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::colors::BLACK;
+    use crate::Point;
+
+    #[test]
+    fn quotes_paths_for_powershell() {
+        let path = FilePath::new(r"C:\Users\Jane Doe\AppData\Local\Temp\turtle.png");
+        assert_eq!(powershell_literal(path), r"'C:\Users\Jane Doe\AppData\Local\Temp\turtle.png'");
+
+        let path = FilePath::new("C:\\O'Brien\u{2019}s $HOME\\turtle.png");
+        assert_eq!(powershell_literal(path), "'C:\\O''Brien\u{2019}\u{2019}s $HOME\\turtle.png'");
+    }
+
+    #[test]
+    fn prints_page_at_paper_size_and_removes_file() {
+        let mut display_list = DisplayList::default();
+        display_list.push_polygon(vec![
+            Point {x: -50.0, y: 50.0},
+            Point {x: 50.0, y: 50.0},
+            Point {x: 50.0, y: -50.0},
+            Point {x: -50.0, y: -50.0},
+        ], BLACK);
+
+        let page = print_page(&display_list, &DrawingState::default(), None, &PrintOptions::default()).unwrap();
+        // A4 paper at 300 DPI
+        assert_eq!((page.pixmap.width(), page.pixmap.height()), (2480, 3508));
+        // The margins are left blank and the drawing is centered
+        assert_eq!(page.pixmap.color_at(0, 0), "white".into());
+        assert_eq!(page.pixmap.color_at(1240, 1754), BLACK);
+
+        let prefix = format!("turtle-print-{}-", process::id());
+        let print_files = || fs::read_dir(env::temp_dir()).unwrap()
+            .filter(|entry| entry.as_ref().unwrap().file_name().to_string_lossy().starts_with(&prefix))
+            .count();
+        page.print().unwrap();
+        assert_eq!(print_files(), 0);
+    }
 }
@@ -0,0 +1,103 @@
+use serde::{Serialize, Deserialize};
+
+use crate::Color;
+
+/// An effect applied to the drawing after it is drawn in the window
+///
+/// Filters are added using [`Drawing::apply_filter()`]. They only change how the drawing looks in
+/// the window, not what is saved by methods like [`Drawing::save_svg()`].
+///
+/// ```rust
+/// use turtle::{Drawing, Filter};
+///
+/// let mut drawing = Drawing::new();
+/// drawing.apply_filter(Filter::Grayscale);
+/// drawing.apply_filter(Filter::Blur(2.0));
+/// assert_eq!(drawing.filters(), vec![Filter::Grayscale, Filter::Blur(2.0)]);
+///
+/// drawing.clear_filters();
+/// assert_eq!(drawing.filters(), vec![]);
+/// ```
+///
+/// [`Drawing::apply_filter()`]: struct.Drawing.html#method.apply_filter
+/// [`Drawing::save_svg()`]: struct.Drawing.html#method.save_svg
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Filter {
+    /// Blurs the drawing using the given radius (in logical pixels)
+    ///
+    /// The radius must be zero or greater. A radius of zero has no effect.
+    Blur(f64),
+    /// Replaces every color with its opposite (e.g. white becomes black)
+    Invert,
+    /// Replaces every color with a shade of gray that has the same brightness
+    Grayscale,
+}
+
+impl Filter {
+    /// Returns true if the filter can be applied
+    pub fn is_valid(self) -> bool {
+        match self {
+            Filter::Blur(radius) => radius.is_finite() && radius >= 0.0,
+            Filter::Invert | Filter::Grayscale => true,
+        }
+    }
+}
+
+/// Returns the color that should be drawn in place of the given color after applying every one of
+/// the given filters in order
+///
+/// Changing colors before they are drawn gives exactly the same result as changing the colors of
+/// the final image since every pixel is a weighted sum of the colors drawn on it.
+pub fn filter_color(color: Color, filters: &[Filter]) -> Color {
+    filters.iter().fold(color, |color, filter| {
+        let Color {red, green, blue, alpha} = color;
+        match filter {
+            Filter::Blur(_) => color,
+            Filter::Invert => Color {red: 255.0 - red, green: 255.0 - green, blue: 255.0 - blue, alpha},
+            Filter::Grayscale => {
+                // Relative luminance (ITU-R BT.709)
+                let gray = 0.2126 * red + 0.7152 * green + 0.0722 * blue;
+                Color {red: gray, green: gray, blue: gray, alpha}
+            },
+        }
+    })
+}
+
+/// Returns the total blur radius of the given filters
+///
+/// Applying one blur after another is the same as applying a single blur with a radius equal to
+/// the square root of the sum of the squares of each radius.
+pub fn blur_radius(filters: &[Filter]) -> f64 {
+    filters.iter().map(|filter| match filter {
+        Filter::Blur(radius) => radius * radius,
+        _ => 0.0,
+    }).sum::<f64>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_colors_in_order() {
+        let color = Color::rgba(255.0, 0.0, 0.0, 0.5);
+
+        assert_eq!(filter_color(color, &[]), color);
+        assert_eq!(filter_color(color, &[Filter::Invert]), Color::rgba(0.0, 255.0, 255.0, 0.5));
+
+        let gray = 0.2126 * 255.0;
+        assert_eq!(filter_color(color, &[Filter::Grayscale]), Color::rgba(gray, gray, gray, 0.5));
+        let inverted_gray = 255.0 - gray;
+        assert_eq!(
+            filter_color(color, &[Filter::Grayscale, Filter::Blur(3.0), Filter::Invert]),
+            Color::rgba(inverted_gray, inverted_gray, inverted_gray, 0.5),
+        );
+    }
+
+    #[test]
+    fn combines_blur_radius() {
+        assert_eq!(blur_radius(&[]), 0.0);
+        assert_eq!(blur_radius(&[Filter::Invert]), 0.0);
+        assert_eq!(blur_radius(&[Filter::Blur(3.0), Filter::Invert, Filter::Blur(4.0)]), 5.0);
+    }
+}
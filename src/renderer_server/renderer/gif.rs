@@ -0,0 +1,267 @@
+use std::io::{self, Write};
+use std::collections::HashMap;
+
+use super::raster::Pixmap;
+
+/// The number of bits used for each palette index in the compressed image data
+const MIN_CODE_SIZE: u8 = 8;
+/// The LZW code that resets the table of codes
+const CLEAR_CODE: u16 = 1 << MIN_CODE_SIZE;
+/// The LZW code that marks the end of the image data
+const END_CODE: u16 = CLEAR_CODE + 1;
+/// The largest number of codes supported by GIF (i.e. 12 bits)
+const MAX_CODES: u16 = 4096;
+
+/// The number of levels of each color channel in the palette used when a frame has too many
+/// colors to get a palette of its own (6 * 7 * 6 = 252 colors, with more shades of green since
+/// the eye is most sensitive to it)
+const UNIFORM_LEVELS: [u32; 3] = [6, 7, 6];
+
+/// Writes an animated GIF that loops forever, one frame at a time
+#[derive(Debug)]
+pub struct GifEncoder<W: Write> {
+    writer: W,
+    width: u16,
+    height: u16,
+}
+
+impl<W: Write> GifEncoder<W> {
+    /// Writes the start of a GIF with the given size (in pixels) to the given writer
+    pub fn new(mut writer: W, width: u16, height: u16) -> io::Result<Self> {
+        writer.write_all(b"GIF89a")?;
+        // Logical screen descriptor: no global color table, so each frame has its own palette
+        writer.write_all(&width.to_le_bytes())?;
+        writer.write_all(&height.to_le_bytes())?;
+        writer.write_all(&[0, 0, 0])?;
+
+        // Application extension that makes the animation loop forever
+        writer.write_all(&[0x21, 0xFF, 11])?;
+        writer.write_all(b"NETSCAPE2.0")?;
+        writer.write_all(&[3, 1, 0, 0, 0])?;
+
+        Ok(Self {writer, width, height})
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Adds a frame that is shown for the given delay (in hundredths of a second)
+    ///
+    /// The frame must have the same size as the GIF. Frames with more than 256 colors are
+    /// reduced to a fixed palette of 252 colors.
+    pub fn add_frame(&mut self, frame: &Pixmap, delay: u16) -> io::Result<()> {
+        debug_assert_eq!((frame.width(), frame.height()), (self.width as u32, self.height as u32),
+            "bug: every frame must have the same size as the GIF");
+
+        let (palette, indexes) = quantize(frame);
+
+        // Graphic control extension: keep the previous frame in place and then wait for the delay
+        self.writer.write_all(&[0x21, 0xF9, 4, 0b0000_0100])?;
+        self.writer.write_all(&delay.to_le_bytes())?;
+        self.writer.write_all(&[0, 0])?;
+
+        // Image descriptor covering the whole image with a local color table of 256 colors
+        self.writer.write_all(&[0x2C, 0, 0, 0, 0])?;
+        self.writer.write_all(&self.width.to_le_bytes())?;
+        self.writer.write_all(&self.height.to_le_bytes())?;
+        self.writer.write_all(&[0b1000_0111])?;
+        for i in 0..256 {
+            self.writer.write_all(&palette.get(i).copied().unwrap_or([0, 0, 0]))?;
+        }
+
+        self.writer.write_all(&[MIN_CODE_SIZE])?;
+        for block in lzw_compress(&indexes).chunks(255) {
+            self.writer.write_all(&[block.len() as u8])?;
+            self.writer.write_all(block)?;
+        }
+        self.writer.write_all(&[0])
+    }
+
+    /// Writes the end of the GIF and returns the writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.write_all(&[0x3B])?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Returns the palette of the given image and the index of each of its pixels in that palette
+///
+/// Any transparency in the image is ignored since GIF frames cannot be partially transparent.
+fn quantize(frame: &Pixmap) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let pixels: Vec<[u8; 3]> = frame.to_rgba8().chunks(4).map(|p| [p[0], p[1], p[2]]).collect();
+
+    let mut palette = Vec::new();
+    let mut palette_indexes = HashMap::new();
+    let mut indexes = Vec::with_capacity(pixels.len());
+    for &pixel in &pixels {
+        let index = *palette_indexes.entry(pixel).or_insert_with(|| {
+            palette.push(pixel);
+            palette.len() - 1
+        });
+        if index > 255 {
+            return uniform_quantize(&pixels);
+        }
+        indexes.push(index as u8);
+    }
+
+    (palette, indexes)
+}
+
+/// Maps every pixel to the closest color in a palette where each channel has evenly spaced levels
+fn uniform_quantize(pixels: &[[u8; 3]]) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let [r_levels, g_levels, b_levels] = UNIFORM_LEVELS;
+    let level = |value: u8, levels: u32| (value as u32 * (levels - 1) + 127) / 255;
+    let value = |level: u32, levels: u32| (level * 255 / (levels - 1)) as u8;
+
+    let mut palette = Vec::new();
+    for r in 0..r_levels {
+        for g in 0..g_levels {
+            for b in 0..b_levels {
+                palette.push([value(r, r_levels), value(g, g_levels), value(b, b_levels)]);
+            }
+        }
+    }
+
+    let indexes = pixels.iter().map(|&[r, g, b]| {
+        let index = (level(r, r_levels) * g_levels + level(g, g_levels)) * b_levels + level(b, b_levels);
+        index as u8
+    }).collect();
+
+    (palette, indexes)
+}
+
+/// Compresses the given palette indexes using the variable-length LZW codes used by GIF
+fn lzw_compress(indexes: &[u8]) -> Vec<u8> {
+    let mut output = BitWriter::default();
+    let mut codes: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut code_size = MIN_CODE_SIZE + 1;
+    let mut next_code = END_CODE + 1;
+
+    output.write(CLEAR_CODE, code_size);
+
+    let mut pixels = indexes.iter().copied();
+    let mut current = match pixels.next() {
+        Some(first) => first as u16,
+        None => {
+            output.write(END_CODE, code_size);
+            return output.finish();
+        },
+    };
+
+    for pixel in pixels {
+        if let Some(&code) = codes.get(&(current, pixel)) {
+            current = code;
+            continue;
+        }
+
+        output.write(current, code_size);
+        if next_code == MAX_CODES {
+            output.write(CLEAR_CODE, code_size);
+            codes.clear();
+            code_size = MIN_CODE_SIZE + 1;
+            next_code = END_CODE + 1;
+        } else {
+            if next_code >= 1 << code_size {
+                code_size += 1;
+            }
+            codes.insert((current, pixel), next_code);
+            next_code += 1;
+        }
+        current = pixel as u16;
+    }
+
+    output.write(current, code_size);
+    output.write(END_CODE, code_size);
+    output.finish()
+}
+
+/// Packs codes of varying sizes into bytes, starting from the least significant bit
+#[derive(Debug, Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A straightforward GIF LZW decoder, used to check that the encoder round-trips
+    fn lzw_decompress(data: &[u8]) -> Vec<u8> {
+        let mut bits = data.iter().flat_map(|&byte| (0..8).map(move |i| (byte >> i) & 1));
+        let mut read = |size: u8| (0..size).try_fold(0u16, |code, i| bits.next().map(|bit| code | (bit as u16) << i));
+
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut code_size = MIN_CODE_SIZE + 1;
+        let mut output = Vec::new();
+        let mut previous: Option<Vec<u8>> = None;
+        while let Some(code) = read(code_size) {
+            if code == CLEAR_CODE {
+                table = (0..=255).map(|i| vec![i as u8]).chain(vec![vec![], vec![]]).collect();
+                code_size = MIN_CODE_SIZE + 1;
+                previous = None;
+                continue;
+            }
+            if code == END_CODE {
+                break;
+            }
+
+            let entry = match table.get(code as usize) {
+                Some(entry) => entry.clone(),
+                None => {
+                    let previous = previous.clone().unwrap();
+                    let mut entry = previous.clone();
+                    entry.push(previous[0]);
+                    entry
+                },
+            };
+            if let Some(mut previous) = previous.take() {
+                previous.push(entry[0]);
+                table.push(previous);
+                if table.len() == 1 << code_size && code_size < 12 {
+                    code_size += 1;
+                }
+            }
+            output.extend_from_slice(&entry);
+            previous = Some(entry);
+        }
+
+        output
+    }
+
+    #[test]
+    fn compression_round_trips() {
+        let repetitive: Vec<u8> = (0..20_000).map(|i| (i / 7 % 5) as u8).collect();
+        let varied: Vec<u8> = (0..20_000u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8).collect();
+
+        for indexes in &[repetitive, varied, vec![42], Vec::new()] {
+            assert_eq!(&lzw_decompress(&lzw_compress(indexes)), indexes);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use thiserror::Error;
+use serde::{Serialize, Deserialize};
+
+use crate::Rect;
+
+use super::super::app::ImageId;
+
+/// The largest number of pixels that a loaded image can have
+///
+/// Each image is sent to the window all at once, so this keeps every image small enough to fit
+/// in a single message.
+const MAX_PIXELS: u64 = 1 << 22;
+
+/// An error produced while loading an image
+#[derive(Debug, Error, Serialize, Deserialize)]
+#[error("{0}")]
+pub struct ImageError(String);
+
+impl From<crate::Error> for ImageError {
+    fn from(err: crate::Error) -> Self {
+        ImageError(err.to_string())
+    }
+}
+
+/// An image that has been loaded into a drawing
+///
+/// Images are loaded using [`Drawing::load_image()`](struct.Drawing.html#method.load_image). The
+/// same image can be used any number of times by any turtle in the drawing, either as its
+/// [shape](enum.Shape.html#variant.Image) or with
+/// [`Turtle::stamp_image()`](struct.Turtle.html#method.stamp_image). A handle can only be used
+/// with the drawing that the image was loaded into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ImageHandle {
+    pub(crate) id: ImageId,
+    width: u32,
+    height: u32,
+}
+
+impl ImageHandle {
+    pub(crate) fn new(id: ImageId, width: u32, height: u32) -> Self {
+        Self {id, width, height}
+    }
+
+    /// Returns the width of the image in pixels
+    pub fn width(self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the image in pixels
+    pub fn height(self) -> u32 {
+        self.height
+    }
+}
+
+/// How a background image set with
+/// [`Drawing::set_background_image()`](struct.Drawing.html#method.set_background_image) is
+/// arranged within the window
+///
+/// The background image stays in place when the view of the drawing is moved or zoomed. In
+/// exported images, the background is arranged within the exported area instead of the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BackgroundMode {
+    /// Stretch the image to cover the entire window, ignoring its aspect ratio
+    Stretch,
+    /// Repeat the image across the window, starting from the top left corner
+    Tile,
+    /// Draw the image once at its actual size in the middle of the window
+    Center,
+}
+
+impl BackgroundMode {
+    /// Returns the area covered by the image (or by its top left tile) within a frame of the
+    /// given size, in the same units as the frame size
+    ///
+    /// The area is relative to the top left corner of the frame, with y increasing downwards.
+    /// The image is drawn with `scale` units for each of its pixels unless it is stretched.
+    pub(crate) fn placement(self, image: &ImageData, frame_width: f64, frame_height: f64, scale: f64) -> Rect {
+        let width = image.width as f64 * scale;
+        let height = image.height as f64 * scale;
+        match self {
+            BackgroundMode::Stretch => Rect::from_points([0.0, 0.0], [frame_width, frame_height]),
+            BackgroundMode::Tile => Rect::from_points([0.0, 0.0], [width, height]),
+            BackgroundMode::Center => Rect::from_center([frame_width / 2.0, frame_height / 2.0], width, height),
+        }
+    }
+}
+
+/// An image drawn behind everything else in the drawing
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BackgroundImage {
+    pub image: ImageHandle,
+    pub mode: BackgroundMode,
+}
+
+/// The pixels of an image that is sent to the window when the image is loaded
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageData {
+    pub width: u32,
+    pub height: u32,
+    /// Each pixel as (non-premultiplied) RGBA, row by row from the top
+    pub pixels: Vec<u8>,
+}
+
+// Printing every pixel would make requests containing images impossible to read
+impl fmt::Debug for ImageData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageData")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ImageData {
+    /// Reads and decodes the PNG image at the given path
+    pub fn open(path: &Path) -> Result<Self, ImageError> {
+        let file = File::open(path)
+            .map_err(|err| ImageError(format!("unable to open {}: {}", path.display(), err)))?;
+        Self::decode_png(BufReader::new(file))
+    }
+
+    /// Decodes a PNG image, converting it to 8-bit RGBA
+    pub fn decode_png<R: Read>(reader: R) -> Result<Self, ImageError> {
+        let mut decoder = png::Decoder::new(reader);
+        // Expands palettes and grayscale images with fewer than 8 bits per pixel
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+
+        let (info, mut reader) = decoder.read_info()
+            .map_err(|err| ImageError(format!("unable to read PNG image: {}", err)))?;
+        let (width, height) = (info.width, info.height);
+        if !is_valid_size(width, height) {
+            return Err(ImageError(format!(
+                "a {}x{} image is too large, images can have at most {} pixels",
+                width, height, MAX_PIXELS,
+            )));
+        }
+
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut buffer)
+            .map_err(|err| ImageError(format!("unable to read PNG image: {}", err)))?;
+
+        let (color_type, _) = reader.output_color_type();
+        let pixels = match color_type {
+            png::ColorType::RGBA => buffer,
+            png::ColorType::RGB => buffer.chunks(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+            png::ColorType::GrayscaleAlpha => buffer.chunks(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            png::ColorType::Indexed => unreachable!("bug: palettes should have been expanded by the decoder"),
+        };
+
+        Ok(Self {width, height, pixels})
+    }
+
+    /// Returns true if the size of the image is allowed and matches the number of pixels
+    pub fn is_valid(&self) -> bool {
+        is_valid_size(self.width, self.height)
+            && self.pixels.len() as u64 == self.width as u64 * self.height as u64 * 4
+    }
+
+    /// Returns the RGBA color of the given pixel, where (0, 0) is the top left corner
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        let p = &self.pixels[index..index + 4];
+        [p[0], p[1], p[2], p[3]]
+    }
+
+    /// Encodes the image in PNG format
+    pub fn encode_png(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        // Writing to memory cannot fail and the size of the image has already been checked
+        let mut writer = encoder.write_header().expect("bug: unable to encode image");
+        writer.write_image_data(&self.pixels).expect("bug: unable to encode image");
+        drop(writer);
+
+        bytes
+    }
+}
+
+fn is_valid_size(width: u32, height: u32) -> bool {
+    width > 0 && height > 0 && width as u64 * height as u64 <= MAX_PIXELS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png_round_trip() {
+        let image = ImageData {
+            width: 2,
+            height: 1,
+            pixels: vec![255, 0, 0, 255, 0, 0, 255, 128],
+        };
+        assert!(image.is_valid());
+
+        let decoded = ImageData::decode_png(&image.encode_png()[..]).unwrap();
+        assert_eq!(decoded, image);
+        assert_eq!(decoded.pixel(1, 0), [0, 0, 255, 128]);
+    }
+
+    #[test]
+    fn rejects_invalid_png() {
+        let err = ImageData::decode_png(&b"not a png"[..]).unwrap_err();
+        assert!(err.to_string().contains("unable to read PNG image"), "{}", err);
+    }
+}
@@ -0,0 +1,234 @@
+use std::fs;
+use std::path::Path as FilePath;
+
+use thiserror::Error;
+use serde::{Serialize, Deserialize};
+use usvg::{Tree, Group, Node, Paint, Transform, tiny_skia_path::{PathSegment, Point as SvgPoint}};
+
+use crate::{Color, Point, FillRule, BlendMode};
+
+use super::display_list::DisplayList;
+use super::super::state::Pen;
+
+/// The number of line segments used to approximate each curve
+const CURVE_SEGMENTS: usize = 16;
+
+/// An error produced while importing an SVG file
+#[derive(Debug, Error, Serialize, Deserialize)]
+#[error("{0}")]
+pub struct ImportError(String);
+
+impl From<crate::Error> for ImportError {
+    fn from(err: crate::Error) -> Self {
+        ImportError(err.to_string())
+    }
+}
+
+/// Controls where an imported image is placed in the drawing and how large it is
+///
+/// The default transform places the center of the image at the origin at its original size.
+///
+/// ```rust
+/// use turtle::{ImportTransform, Point};
+///
+/// let transform = ImportTransform::default();
+/// assert_eq!(transform.center, Point {x: 0.0, y: 0.0});
+/// assert_eq!(transform.scale, 1.0);
+///
+/// // Place the image at half of its original size with its center at (100, 50)
+/// let transform = ImportTransform {
+///     center: [100.0, 50.0].into(),
+///     scale: 0.5,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImportTransform {
+    /// The point in the drawing where the center of the image will be placed
+    pub center: Point,
+    /// The amount that the image will be scaled by, must be greater than zero
+    pub scale: f64,
+}
+
+impl Default for ImportTransform {
+    fn default() -> Self {
+        Self {
+            center: Point {x: 0.0, y: 0.0},
+            scale: 1.0,
+        }
+    }
+}
+
+impl ImportTransform {
+    /// Returns true if the center is finite and the scale is positive and finite
+    pub fn is_valid(&self) -> bool {
+        self.center.is_finite() && self.scale.is_finite() && self.scale > 0.0
+    }
+}
+
+/// Adds everything in the SVG file at the given path to the display list
+///
+/// Each filled shape becomes a single path that keeps its fill rule, so holes (e.g. the inside of
+/// the letter "O") are left empty. Strokes become lines. Curves are approximated with straight
+/// line segments. Gradients and patterns are drawn using the color of their first stop (or black if
+/// there is none). Images and text are not imported.
+pub fn import_svg(
+    display_list: &mut DisplayList,
+    path: &FilePath,
+    transform: &ImportTransform,
+) -> Result<(), ImportError> {
+    let data = fs::read(path).map_err(|err| ImportError(err.to_string()))?;
+    let tree = Tree::from_data(&data, &usvg::Options::default())
+        .map_err(|err| ImportError(err.to_string()))?;
+
+    // Maps SVG coordinates (y-axis pointing down) into the coordinate system of the turtle so
+    // that the center of the image ends up at the requested point
+    let size = tree.size();
+    let ImportTransform {center, scale} = *transform;
+    let to_drawing = |pt: SvgPoint| Point {
+        x: center.x + (pt.x as f64 - size.width() as f64 / 2.0) * scale,
+        y: center.y - (pt.y as f64 - size.height() as f64 / 2.0) * scale,
+    };
+
+    import_group(display_list, tree.root(), 1.0, scale, &to_drawing);
+
+    Ok(())
+}
+
+fn import_group(
+    display_list: &mut DisplayList,
+    group: &Group,
+    opacity: f64,
+    scale: f64,
+    to_drawing: &dyn Fn(SvgPoint) -> Point,
+) {
+    // Nested opacity is approximated by applying it to every shape in the group
+    let opacity = opacity * group.opacity().get() as f64;
+
+    for node in group.children() {
+        match node {
+            Node::Group(group) => import_group(display_list, group, opacity, scale, to_drawing),
+
+            Node::Path(path) if path.is_visible() => {
+                let transform = path.abs_transform();
+                let subpaths = flatten(path.data().segments(), transform, to_drawing);
+
+                if let Some(fill) = path.fill() {
+                    let fill_color = paint_color(fill.paint(), opacity * fill.opacity().get() as f64);
+                    let fill_rule = match fill.rule() {
+                        usvg::FillRule::NonZero => FillRule::NonZero,
+                        usvg::FillRule::EvenOdd => FillRule::EvenOdd,
+                    };
+                    // Every sub-path is filled as if it were closed
+                    let points = subpaths.iter().map(|(points, _)| points.clone()).collect();
+                    display_list.push_path(points, fill_color, fill_rule);
+                }
+
+                if let Some(stroke) = path.stroke() {
+                    // Non-uniform scaling is approximated using the average of both directions
+                    let (sx, sy) = transform.get_scale();
+                    let pen = Pen {
+                        is_enabled: true,
+                        thickness: stroke.width().get() as f64 * (sx + sy) as f64 / 2.0 * scale,
+                        color: paint_color(stroke.paint(), opacity * stroke.opacity().get() as f64),
+                        dash_pattern: Vec::new(),
+                        blend_mode: BlendMode::Alpha,
+                    };
+
+                    for (points, is_closed) in &subpaths {
+                        for pair in points.windows(2) {
+                            display_list.push_line(pair[0], pair[1], &pen);
+                        }
+
+                        // Sub-paths always have at least two points
+                        let (first, last) = (points[0], points[points.len() - 1]);
+                        if *is_closed && first != last {
+                            display_list.push_line(last, first, &pen);
+                        }
+                    }
+                }
+            },
+
+            // Images and text are not supported
+            _ => {},
+        }
+    }
+}
+
+/// Converts path segments into lists of points in the coordinate system of the drawing, one for
+/// each sub-path, along with whether that sub-path was closed
+fn flatten(
+    segments: impl Iterator<Item=PathSegment>,
+    transform: Transform,
+    to_drawing: &dyn Fn(SvgPoint) -> Point,
+) -> Vec<(Vec<Point>, bool)> {
+    // Both transforms are affine, so applying them to the control points of a curve gives the
+    // same result as applying them to every point on the curve
+    let convert = |mut pt: SvgPoint| {
+        transform.map_point(&mut pt);
+        to_drawing(pt)
+    };
+
+    let mut subpaths = Vec::new();
+    let mut current: Vec<Point> = Vec::new();
+    // The start point of the current sub-path, which is where a closed sub-path ends
+    let mut start = convert(SvgPoint::zero());
+
+    let mut finish = |points: &mut Vec<Point>, is_closed: bool| {
+        if points.len() > 1 {
+            subpaths.push((points.split_off(0), is_closed));
+        }
+        points.clear();
+    };
+
+    for segment in segments {
+        let last = current.last().copied().unwrap_or(start);
+        match segment {
+            PathSegment::MoveTo(pt) => {
+                finish(&mut current, false);
+                start = convert(pt);
+                current.push(start);
+            },
+            PathSegment::LineTo(pt) => current.push(convert(pt)),
+            PathSegment::QuadTo(ctrl, pt) => {
+                let (ctrl, pt) = (convert(ctrl), convert(pt));
+                current.extend((1..=CURVE_SEGMENTS).map(|i| {
+                    let t = i as f64 / CURVE_SEGMENTS as f64;
+                    let mt = 1.0 - t;
+                    last * (mt * mt) + ctrl * (2.0 * mt * t) + pt * (t * t)
+                }));
+            },
+            PathSegment::CubicTo(ctrl1, ctrl2, pt) => {
+                let (ctrl1, ctrl2, pt) = (convert(ctrl1), convert(ctrl2), convert(pt));
+                current.extend((1..=CURVE_SEGMENTS).map(|i| {
+                    let t = i as f64 / CURVE_SEGMENTS as f64;
+                    let mt = 1.0 - t;
+                    last * (mt * mt * mt) + ctrl1 * (3.0 * mt * mt * t) + ctrl2 * (3.0 * mt * t * t) + pt * (t * t * t)
+                }));
+            },
+            PathSegment::Close => {
+                finish(&mut current, true);
+                // Anything drawn after closing starts back at the start of the sub-path
+                current.push(start);
+            },
+        }
+    }
+    finish(&mut current, false);
+
+    subpaths
+}
+
+/// Returns the color that will be used to draw the given paint with the given opacity
+fn paint_color(paint: &Paint, opacity: f64) -> Color {
+    let color = match paint {
+        Paint::Color(color) => *color,
+        Paint::LinearGradient(gradient) => first_stop(gradient.stops()),
+        Paint::RadialGradient(gradient) => first_stop(gradient.stops()),
+        Paint::Pattern(_) => usvg::Color::black(),
+    };
+
+    Color::rgba(color.red as f64, color.green as f64, color.blue as f64, opacity.clamp(0.0, 1.0))
+}
+
+fn first_stop(stops: &[usvg::Stop]) -> usvg::Color {
+    stops.first().map(|stop| stop.color()).unwrap_or_else(usvg::Color::black)
+}
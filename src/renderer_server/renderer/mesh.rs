@@ -0,0 +1,549 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{Color, Point, FillRule, BlendMode};
+
+use super::display_list::{DisplayList, DrawPrim, Line, Polygon, Circle, Curve, Text, Path, dashed};
+
+/// The largest distance between a circle or curve and the triangles used to draw it
+const CIRCLE_TOLERANCE: f64 = 0.1;
+
+/// The type of each index into the list of vertices returned by
+/// [`Drawing::export_mesh()`](struct.Drawing.html#method.export_mesh)
+pub type Index = u32;
+
+/// A single vertex of the triangle mesh returned by
+/// [`Drawing::export_mesh()`](struct.Drawing.html#method.export_mesh)
+///
+/// Both fields use `f32` since that is what graphics APIs and game engines usually expect.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Vertex {
+    /// The position of the vertex in the same coordinate system as the turtle
+    ///
+    /// The origin is at the center of the drawing and the y-axis points up.
+    pub position: [f32; 2],
+    /// The color of the vertex as red, green, blue, and alpha, each between 0.0 and 1.0
+    pub color: [f32; 4],
+}
+
+impl Vertex {
+    fn new(position: Point, color: Color) -> Self {
+        let Color {red, green, blue, alpha} = color;
+        Self {
+            position: [position.x as f32, position.y as f32],
+            color: [(red / 255.0) as f32, (green / 255.0) as f32, (blue / 255.0) as f32, alpha as f32],
+        }
+    }
+}
+
+/// Tessellates every primitive in the display list into triangles
+///
+/// Every three indices form one triangle. Triangles are listed in the same order that the
+/// primitives are drawn in, so drawing them in order reproduces the drawing.
+pub fn tessellate(display_list: &DisplayList) -> (Vec<Vertex>, Vec<Index>) {
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+
+    for prim in display_list.iter() {
+        match prim {
+            // Vertex colors can only be drawn over each other, so lines and curves that erase
+            // cannot be included
+            DrawPrim::Line(Line {blend_mode: BlendMode::Erase, ..}) |
+            DrawPrim::Curve(Curve {blend_mode: BlendMode::Erase, ..}) => {},
+            DrawPrim::Line(line) => tessellate_line(line, &mut vertices, &mut indices),
+            DrawPrim::Polygon(polygon) => tessellate_polygon(polygon, &mut vertices, &mut indices),
+            DrawPrim::Circle(circle) => tessellate_circle(circle, &mut vertices, &mut indices),
+            DrawPrim::Curve(curve) => tessellate_curve(curve, &mut vertices, &mut indices),
+            DrawPrim::Text(text) => tessellate_text(text, &mut vertices, &mut indices),
+            DrawPrim::Path(path) => tessellate_path(path, &mut vertices, &mut indices),
+            // Meshes only have a color for each vertex, so images cannot be included
+            DrawPrim::Image(_) => {},
+        }
+    }
+
+    (vertices, indices)
+}
+
+/// Adds a rectangle covering the given line, including its thickness
+///
+/// The renderer draws lines with round caps. Those are approximated with square caps so that
+/// consecutive lines still meet without any gaps.
+fn tessellate_line(line: &Line, vertices: &mut Vec<Vertex>, indices: &mut Vec<Index>) {
+    let &Line {start, end, thickness, color, ref dash, ..} = line;
+
+    for segment in dashed(&[start, end], dash.as_ref()).iter().flat_map(|points| points.windows(2)) {
+        tessellate_segment(segment[0], segment[1], thickness, color, vertices, indices);
+    }
+}
+
+/// Adds a rectangle covering a single solid segment of a line
+fn tessellate_segment(start: Point, end: Point, thickness: f64, color: Color, vertices: &mut Vec<Vertex>, indices: &mut Vec<Index>) {
+    let dir = end - start;
+    // Lines with no length or thickness do not cover any area
+    if dir.len() == 0.0 || thickness <= 0.0 {
+        return;
+    }
+    let along = dir.normalize() * (thickness / 2.0);
+    let across = Point {x: -along.y, y: along.x};
+
+    let base = vertices.len() as Index;
+    vertices.extend([
+        start - along + across,
+        start - along - across,
+        end + along - across,
+        end + along + across,
+    ].iter().map(|&pt| Vertex::new(pt, color)));
+    indices.extend([0, 1, 2, 0, 2, 3].iter().map(|i| base + i));
+}
+
+/// Adds triangles that fill the given polygon
+///
+/// Polygons that cross over themselves are cut into bands like text so that the fill rule can be
+/// applied. Both fill rules give the same result for every other polygon.
+fn tessellate_polygon(polygon: &Polygon, vertices: &mut Vec<Vertex>, indices: &mut Vec<Index>) {
+    let Polygon {points, fill_color, fill_rule} = polygon;
+
+    // Filling usually ends where it began, so the last point is often a duplicate of the first
+    let mut points = points.clone();
+    points.dedup();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    let edges: Vec<_> = outline_edges(&points).collect();
+    if !crossing_heights(&edges).is_empty() {
+        tessellate_bands(edges, *fill_color, *fill_rule, vertices, indices);
+        return;
+    }
+
+    let base = vertices.len() as Index;
+    let start = indices.len();
+    triangulate(&points, indices);
+    for index in &mut indices[start..] {
+        *index += base;
+    }
+
+    vertices.extend(points.into_iter().map(|pt| Vertex::new(pt, *fill_color)));
+}
+
+/// Adds a triangle fan that fills the given circle and a ring of triangles for its outline
+fn tessellate_circle(circle: &Circle, vertices: &mut Vec<Vertex>, indices: &mut Vec<Index>) {
+    let &Circle {center, radius, thickness, color, fill_color} = circle;
+
+    if fill_color.alpha > 0.0 && radius > 0.0 {
+        let points = Circle::flatten(center, radius, CIRCLE_TOLERANCE);
+        let base = vertices.len() as Index;
+        vertices.push(Vertex::new(center, fill_color));
+        vertices.extend(points.iter().map(|&pt| Vertex::new(pt, fill_color)));
+        for i in 1..points.len() as Index {
+            indices.extend([base, base + i, base + i + 1].iter());
+        }
+    }
+
+    if thickness > 0.0 {
+        let outer_radius = radius + thickness / 2.0;
+        let inner_radius = (radius - thickness / 2.0).max(0.0);
+        let outer = Circle::flatten(center, outer_radius, CIRCLE_TOLERANCE);
+        let base = vertices.len() as Index;
+        for &pt in &outer {
+            // Each inner point is at the same angle as an outer point so they can be joined into quads
+            let inner = center + (pt - center) * (inner_radius / outer_radius);
+            vertices.push(Vertex::new(pt, color));
+            vertices.push(Vertex::new(inner, color));
+        }
+        for i in 0..outer.len() as Index - 1 {
+            let (outer0, inner0, outer1, inner1) = (base + 2 * i, base + 2 * i + 1, base + 2 * i + 2, base + 2 * i + 3);
+            indices.extend([outer0, inner0, outer1, outer1, inner0, inner1].iter());
+        }
+    }
+}
+
+/// Adds a rectangle covering each of the line segments that approximate the given curve
+fn tessellate_curve(curve: &Curve, vertices: &mut Vec<Vertex>, indices: &mut Vec<Index>) {
+    let &Curve {thickness, color, ref dash, ..} = curve;
+
+    let points = curve.flatten(CIRCLE_TOLERANCE);
+    for segment in dashed(&points, dash.as_ref()).iter().flat_map(|points| points.windows(2)) {
+        tessellate_segment(segment[0], segment[1], thickness, color, vertices, indices);
+    }
+}
+
+/// Adds triangles that fill the inside of the given text
+///
+/// Ear clipping cannot leave holes in characters like "o", so the text is instead cut into bands
+/// that are filled using the non-zero fill rule.
+fn tessellate_text(text: &Text, vertices: &mut Vec<Vertex>, indices: &mut Vec<Index>) {
+    let Text {outlines, color} = text;
+
+    let edges = outlines.iter().flat_map(|outline| outline_edges(outline)).collect();
+    tessellate_bands(edges, *color, FillRule::NonZero, vertices, indices);
+}
+
+/// Adds triangles that fill the inside of the given path, leaving holes using its fill rule
+fn tessellate_path(path: &Path, vertices: &mut Vec<Vertex>, indices: &mut Vec<Index>) {
+    let Path {subpaths, fill_color, fill_rule} = path;
+
+    let edges = subpaths.iter().flat_map(|subpath| outline_edges(subpath)).collect();
+    tessellate_bands(edges, *fill_color, *fill_rule, vertices, indices);
+}
+
+/// Returns each edge of the given closed outline, including the one from the last point to the
+/// first point
+fn outline_edges(outline: &[Point]) -> impl Iterator<Item=(Point, Point)> + '_ {
+    outline.iter().copied().zip(outline.iter().copied().cycle().skip(1))
+}
+
+/// Returns the height of every point where two of the given edges cross through each other
+///
+/// Edges that only touch (like consecutive edges of an outline) or that overlap along the same
+/// line are not counted.
+fn crossing_heights(edges: &[(Point, Point)]) -> Vec<f64> {
+    let mut heights = Vec::new();
+    for (i, &(a0, a1)) in edges.iter().enumerate() {
+        for &(b0, b1) in &edges[i + 1..] {
+            // Edges that are not at any of the same heights cannot cross
+            if a0.y.max(a1.y) < b0.y.min(b1.y) || b0.y.max(b1.y) < a0.y.min(a1.y) {
+                continue;
+            }
+
+            let (dir_a, dir_b) = (a1 - a0, b1 - b0);
+            let denom = dir_a.cross(dir_b);
+            if denom == 0.0 {
+                continue;
+            }
+            let t = (b0 - a0).cross(dir_b) / denom;
+            let u = (b0 - a0).cross(dir_a) / denom;
+            if t > 0.0 && t < 1.0 && u > 0.0 && u < 1.0 {
+                heights.push(a0.y + t * dir_a.y);
+            }
+        }
+    }
+    heights
+}
+
+/// Adds triangles that fill the area inside of the given edges of one or more closed outlines,
+/// using the given fill rule
+///
+/// The area is cut into horizontal bands at the height of every point and of every place where
+/// two edges cross. Edges never cross in the middle of a band, so the part of each band that is
+/// inside of the outlines is a set of trapezoids.
+fn tessellate_bands(mut edges: Vec<(Point, Point)>, color: Color, fill_rule: FillRule, vertices: &mut Vec<Vertex>, indices: &mut Vec<Index>) {
+    let mut heights = crossing_heights(&edges);
+    heights.extend(edges.iter().flat_map(|&(p0, p1)| [p0.y, p1.y]));
+    heights.sort_by(f64::total_cmp);
+    heights.dedup();
+
+    // Horizontal edges never cross the middle of a band
+    edges.retain(|(p0, p1)| p0.y != p1.y);
+
+    // Each crossing is the x position where an edge crosses the bottom, middle, and top of the
+    // band, along with the direction that it crosses in
+    let mut crossings = Vec::new();
+    for band in heights.windows(2) {
+        let (bottom, top) = (band[0], band[1]);
+        let middle = (bottom + top) / 2.0;
+
+        crossings.clear();
+        for &(p0, p1) in &edges {
+            if (p0.y <= middle) == (p1.y <= middle) {
+                continue;
+            }
+            let x_at = |y: f64| p0.x + (y - p0.y) * (p1.x - p0.x) / (p1.y - p0.y);
+            crossings.push((x_at(bottom), x_at(middle), x_at(top), if p1.y > p0.y { 1 } else { -1 }));
+        }
+        crossings.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let mut winding = 0;
+        for pair in crossings.windows(2) {
+            winding += pair[0].3;
+            if !fill_rule.is_inside(winding) {
+                continue;
+            }
+
+            let (left, right) = (pair[0], pair[1]);
+            let base = vertices.len() as Index;
+            vertices.extend([
+                Point {x: left.0, y: bottom},
+                Point {x: right.0, y: bottom},
+                Point {x: right.2, y: top},
+                Point {x: left.2, y: top},
+            ].iter().map(|&pt| Vertex::new(pt, color)));
+            indices.extend([0, 1, 2, 0, 2, 3].iter().map(|i| base + i));
+        }
+    }
+}
+
+/// Triangulates a simple polygon using ear clipping, adding the indices (relative to the start of
+/// `points`) of each triangle
+///
+/// The points may be given in either clockwise or counterclockwise order. Polygons that intersect
+/// themselves cannot be triangulated exactly. As much as possible of those is clipped before
+/// falling back to a triangle fan for whatever is left.
+fn triangulate(points: &[Point], indices: &mut Vec<Index>) {
+    // Twice the signed area, positive if the points are in counterclockwise order
+    let area: f64 = (0..points.len())
+        .map(|i| points[i].cross(points[(i + 1) % points.len()]))
+        .sum();
+    // Also catches polygons with fewer than 3 points
+    if area == 0.0 {
+        return;
+    }
+    let orientation = area.signum();
+
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    while remaining.len() > 3 {
+        let len = remaining.len();
+        // Positive for convex vertices, negative for reflex vertices, and zero if collinear
+        let turn = |i: usize| {
+            let prev = points[remaining[(i + len - 1) % len]];
+            let curr = points[remaining[i]];
+            let next = points[remaining[(i + 1) % len]];
+            (curr - prev).cross(next - curr) * orientation
+        };
+
+        let ear = (0..len).find_map(|i| {
+            let prev = remaining[(i + len - 1) % len];
+            let curr = remaining[i];
+            let next = remaining[(i + 1) % len];
+
+            let turn_curr = turn(i);
+            if turn_curr < 0.0 {
+                // A reflex vertex can never be an ear
+                return None;
+            }
+            if turn_curr == 0.0 {
+                // Collinear points can be removed without adding a triangle
+                return Some((i, None));
+            }
+
+            // Only a reflex vertex can be inside of (or on the edge of) an ear
+            let triangle = [points[prev], points[curr], points[next]];
+            let is_empty = (0..len)
+                .filter(|&j| turn(j) < 0.0)
+                .map(|j| remaining[j])
+                .filter(|&other| other != prev && other != next)
+                .all(|other| !in_triangle(points[other], triangle));
+
+            if is_empty {
+                Some((i, Some([prev, curr, next])))
+            } else {
+                None
+            }
+        });
+
+        match ear {
+            Some((i, triangle)) => {
+                if let Some(triangle) = triangle {
+                    indices.extend(triangle.iter().map(|&p| p as Index));
+                }
+                remaining.remove(i);
+            },
+
+            // Only happens if the polygon intersects itself
+            None => break,
+        }
+    }
+
+    let first = remaining[0];
+    for pair in remaining[1..].windows(2) {
+        indices.extend([first, pair[0], pair[1]].iter().map(|&p| p as Index));
+    }
+}
+
+/// Returns true if the point is inside the given triangle or on one of its edges
+fn in_triangle(point: Point, [a, b, c]: [Point; 3]) -> bool {
+    let d1 = (b - a).cross(point - a);
+    let d2 = (c - b).cross(point - b);
+    let d3 = (a - c).cross(point - c);
+
+    (d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0) || (d1 <= 0.0 && d2 <= 0.0 && d3 <= 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::f64::consts::PI;
+
+    use crate::Rect;
+
+    /// Returns the total area covered by the given triangles
+    fn triangles_area(points: &[Point], indices: &[Index]) -> f64 {
+        indices.chunks(3).map(|tri| {
+            let [a, b, c] = [points[tri[0] as usize], points[tri[1] as usize], points[tri[2] as usize]];
+            (b - a).cross(c - a).abs() / 2.0
+        }).sum()
+    }
+
+    #[test]
+    fn tessellate_circle_area() {
+        let circle = Circle {
+            center: [3.0, 4.0].into(),
+            radius: 10.0,
+            thickness: 2.0,
+            color: Color::rgb(0.0, 0.0, 0.0),
+            fill_color: Color::rgb(255.0, 255.0, 255.0),
+        };
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        tessellate_circle(&circle, &mut vertices, &mut indices);
+        let points: Vec<Point> = vertices.iter()
+            .map(|v| [v.position[0] as f64, v.position[1] as f64].into())
+            .collect();
+
+        // The fill covers the circle and the outline covers the ring between radius 9 and 11
+        let expected = PI * 10.0 * 10.0 + PI * (11.0 * 11.0 - 9.0 * 9.0);
+        let area = triangles_area(&points, &indices);
+        assert!((area - expected).abs() / expected < 2e-2, "{} vs {}", area, expected);
+    }
+
+    #[test]
+    fn triangulate_concave() {
+        // An L-shape with an area of 3, in both orientations
+        let mut points: Vec<Point> = vec![
+            [0.0, 0.0].into(), [2.0, 0.0].into(), [2.0, 1.0].into(),
+            [1.0, 1.0].into(), [1.0, 2.0].into(), [0.0, 2.0].into(),
+        ];
+
+        for _ in 0..2 {
+            let mut indices = Vec::new();
+            triangulate(&points, &mut indices);
+            assert_eq!(indices.len(), 4 * 3);
+            assert_eq!(triangles_area(&points, &indices), 3.0);
+
+            points.reverse();
+        }
+    }
+
+    #[test]
+    fn triangulate_degenerate() {
+        let mut indices = Vec::new();
+        triangulate(&[[0.0, 0.0].into(), [1.0, 1.0].into()], &mut indices);
+        triangulate(&[[0.0, 0.0].into(), [1.0, 1.0].into(), [2.0, 2.0].into()], &mut indices);
+        assert!(indices.is_empty());
+    }
+
+    #[test]
+    fn tessellate_line_covers_thickness() {
+        let line = Line {
+            start: [0.0, 0.0].into(),
+            end: [10.0, 0.0].into(),
+            thickness: 2.0,
+            color: Color::rgb(255.0, 0.0, 0.0),
+            dash: None,
+            blend_mode: BlendMode::Alpha,
+        };
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        tessellate_line(&line, &mut vertices, &mut indices);
+
+        let points: Vec<Point> = vertices.iter()
+            .map(|v| [v.position[0] as f64, v.position[1] as f64].into())
+            .collect();
+        assert_eq!(Rect::bounding(points.iter().copied()), Some(Rect::from_points([-1.0, -1.0], [11.0, 1.0])));
+        assert_eq!(triangles_area(&points, &indices), 24.0);
+        assert!(vertices.iter().all(|v| v.color == [1.0, 0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn tessellate_dashed_line() {
+        use super::super::display_list::Dash;
+
+        let line = Line {
+            start: [0.0, 0.0].into(),
+            end: [10.0, 0.0].into(),
+            thickness: 2.0,
+            color: Color::rgb(255.0, 0.0, 0.0),
+            // Starts part of the way into the first dash
+            dash: Some(Dash {pattern: vec![2.0, 3.0].into(), offset: 1.0}),
+            blend_mode: BlendMode::Alpha,
+        };
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        tessellate_line(&line, &mut vertices, &mut indices);
+
+        let points: Vec<Point> = vertices.iter()
+            .map(|v| [v.position[0] as f64, v.position[1] as f64].into())
+            .collect();
+        // Dashes from 0 to 1, 4 to 6, and 9 to 10, each covering its thickness at both ends
+        assert_eq!(indices.len() / 3, 3 * 2);
+        assert_eq!(Rect::bounding(points.iter().copied()), Some(Rect::from_points([-1.0, -1.0], [11.0, 1.0])));
+        assert_eq!(triangles_area(&points, &indices), (3.0 + 4.0 + 3.0) * 2.0);
+    }
+
+    #[test]
+    fn tessellate_star_with_fill_rules() {
+        // A five-pointed star drawn in one stroke, going around its middle twice
+        let radius = 10.0;
+        let points: Vec<Point> = (0..5).map(|i| {
+            let angle = PI / 2.0 + i as f64 * 4.0 * PI / 5.0;
+            Point {x: radius * angle.cos(), y: radius * angle.sin()}
+        }).collect();
+
+        let area = |fill_rule| {
+            let polygon = Polygon {points: points.clone(), fill_color: Color::rgb(255.0, 255.0, 255.0), fill_rule};
+            let mut vertices = Vec::new();
+            let mut indices = Vec::new();
+            tessellate_polygon(&polygon, &mut vertices, &mut indices);
+            assert!(vertices.iter().all(|v| v.color == [1.0, 1.0, 1.0, 1.0]));
+
+            let points: Vec<Point> = vertices.iter()
+                .map(|v| [v.position[0] as f64, v.position[1] as f64].into())
+                .collect();
+            triangles_area(&points, &indices)
+        };
+
+        // The even-odd rule leaves out the pentagon in the middle of the star
+        let pentagon_radius = radius * (2.0 * PI / 5.0).cos() / (PI / 5.0).cos();
+        let pentagon_area = 5.0 / 2.0 * pentagon_radius * pentagon_radius * (2.0 * PI / 5.0).sin();
+        let (non_zero, even_odd) = (area(FillRule::NonZero), area(FillRule::EvenOdd));
+        assert!(even_odd > 0.0);
+        assert!((non_zero - even_odd - pentagon_area).abs() < 1e-3, "{} - {} vs {}", non_zero, even_odd, pentagon_area);
+    }
+
+    #[test]
+    fn tessellate_text_leaves_holes() {
+        // A square with a square hole, going around the other way inside of it
+        let text = Text {
+            outlines: vec![
+                vec![[0.0, 0.0].into(), [4.0, 0.0].into(), [4.0, 4.0].into(), [0.0, 4.0].into()],
+                vec![[1.0, 1.0].into(), [1.0, 3.0].into(), [3.0, 3.0].into(), [3.0, 1.0].into()],
+            ],
+            color: Color::rgb(0.0, 0.0, 255.0),
+        };
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        tessellate_text(&text, &mut vertices, &mut indices);
+        let points: Vec<Point> = vertices.iter()
+            .map(|v| [v.position[0] as f64, v.position[1] as f64].into())
+            .collect();
+
+        assert_eq!(triangles_area(&points, &indices), 12.0);
+        assert!(vertices.iter().all(|v| v.color == [0.0, 0.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn tessellate_path_with_fill_rules() {
+        // Two squares going around in the same direction, one inside of the other
+        let subpaths: Vec<Vec<Point>> = vec![
+            vec![[0.0, 0.0].into(), [4.0, 0.0].into(), [4.0, 4.0].into(), [0.0, 4.0].into()],
+            vec![[1.0, 1.0].into(), [3.0, 1.0].into(), [3.0, 3.0].into(), [1.0, 3.0].into()],
+        ];
+
+        for &(fill_rule, area) in &[(FillRule::NonZero, 16.0), (FillRule::EvenOdd, 12.0)] {
+            let path = Path {subpaths: subpaths.clone(), fill_color: Color::rgb(255.0, 0.0, 0.0), fill_rule};
+
+            let mut vertices = Vec::new();
+            let mut indices = Vec::new();
+            tessellate_path(&path, &mut vertices, &mut indices);
+            let points: Vec<Point> = vertices.iter()
+                .map(|v| [v.position[0] as f64, v.position[1] as f64].into())
+                .collect();
+
+            assert_eq!(triangles_area(&points, &indices), area, "{:?}", fill_rule);
+        }
+    }
+}
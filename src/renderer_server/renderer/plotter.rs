@@ -0,0 +1,355 @@
+use std::fs;
+use std::fmt::Write;
+use std::path::Path as FilePath;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{Point, Rect, BlendMode};
+
+use super::display_list::{DisplayList, DrawPrim, Line, Circle, Curve, Text, dashed};
+use super::export::{self, ExportError, ExportRegion, PaperSize};
+use super::symmetry;
+use super::super::state::DrawingState;
+
+/// The number of HPGL plotter units in one millimeter
+const HPGL_UNITS_PER_MM: f64 = 40.0;
+
+/// The largest distance (in millimeters) between the end of one line and the start of the next
+/// that still allows them to be drawn without lifting the pen
+const JOIN_TOLERANCE: f64 = 1e-3;
+
+/// The largest distance (in millimeters) between a circle or curve and the lines used to plot it
+const CIRCLE_TOLERANCE: f64 = 0.02;
+
+/// Options for controlling how the drawing is converted into instructions for a pen plotter
+///
+/// Only the lines drawn by the turtles are plotted. Fills are skipped since a plotter can only
+/// draw with a pen. Like with [`PrintOptions`], the selected region of the drawing is scaled to
+/// fit inside the margins of the paper, keeping its aspect ratio, unless `true_scale` is set. All
+/// coordinates are measured in millimeters from the bottom-left corner of the paper.
+///
+/// The default options plot the area visible in the window on a portrait A4 page with a 10mm
+/// margin.
+///
+/// ```rust
+/// use turtle::{PlotterOptions, PaperSize, ExportRegion};
+///
+/// let options = PlotterOptions::default();
+/// assert_eq!(options.region, ExportRegion::Window);
+/// assert_eq!(options.paper, PaperSize::A4);
+///
+/// // Plot everything that has been drawn slowly on sideways letter paper
+/// let options = PlotterOptions {
+///     region: ExportRegion::Content {margin: 0.0},
+///     paper: PaperSize::Letter,
+///     landscape: true,
+///     feed_rate: 600.0,
+///     ..PlotterOptions::default()
+/// };
+/// ```
+///
+/// [`PrintOptions`]: struct.PrintOptions.html
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlotterOptions {
+    /// The area of the drawing to plot
+    pub region: ExportRegion,
+    /// The size of the paper to plot on
+    pub paper: PaperSize,
+    /// If true, the paper is turned sideways so that it is wider than it is tall
+    pub landscape: bool,
+    /// The amount of empty space to leave at each edge of the paper (in millimeters)
+    pub margin: f64,
+    /// The speed at which the pen moves while it is drawing (in millimeters per minute)
+    ///
+    /// Must be positive.
+    pub feed_rate: f64,
+    /// The speed at which the pen moves while it is lifted (in millimeters per minute)
+    ///
+    /// Must be positive. HPGL has no separate speed for these moves, so this is only used for
+    /// G-code.
+    pub travel_rate: f64,
+    /// The height of the Z axis (in millimeters) when the pen is lifted off of the paper
+    ///
+    /// Only used for G-code. Must be greater than `pen_down_height`.
+    pub pen_up_height: f64,
+    /// The height of the Z axis (in millimeters) when the pen is touching the paper
+    ///
+    /// Only used for G-code.
+    pub pen_down_height: f64,
+    /// If true, the region is plotted at the physical size given by the DPI of the drawing and
+    /// centered on the paper instead of being scaled to fit inside of the margins
+    ///
+    /// Lines are still cut off at the edges of the region, but not at the edges of the paper, so
+    /// make sure that the region fits on the paper.
+    pub true_scale: bool,
+}
+
+impl Default for PlotterOptions {
+    fn default() -> Self {
+        Self {
+            region: ExportRegion::Window,
+            paper: PaperSize::A4,
+            landscape: false,
+            margin: 10.0,
+            feed_rate: 1500.0,
+            travel_rate: 3000.0,
+            pen_up_height: 5.0,
+            pen_down_height: 0.0,
+            true_scale: false,
+        }
+    }
+}
+
+impl PlotterOptions {
+    /// Returns true if these options describe a non-empty area that fits on the paper and can be
+    /// plotted at the given speeds and heights
+    pub(crate) fn is_valid(&self) -> bool {
+        let (width, height) = self.paper.dimensions();
+        let paper_valid = width.is_finite() && height.is_finite();
+        let margin_valid = self.margin.is_finite() && self.margin >= 0.0
+            && self.margin * 2.0 < width && self.margin * 2.0 < height;
+        let rates_valid = self.feed_rate.is_finite() && self.feed_rate > 0.0
+            && self.travel_rate.is_finite() && self.travel_rate > 0.0;
+        let heights_valid = self.pen_up_height.is_finite() && self.pen_down_height.is_finite()
+            && self.pen_up_height > self.pen_down_height;
+
+        self.region.is_valid() && paper_valid && margin_valid && rates_valid && heights_valid
+    }
+
+    /// Returns the width and height of the page in millimeters, taking the orientation into account
+    fn page_size(&self) -> (f64, f64) {
+        let (width, height) = self.paper.dimensions();
+        if self.landscape {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+}
+
+/// Saves the lines in the drawing as G-code that draws them with a pen plotter
+pub fn save_gcode(
+    display_list: &DisplayList,
+    drawing: &DrawingState,
+    options: &PlotterOptions,
+    path: &FilePath,
+) -> Result<(), ExportError> {
+    let paths = toolpaths(display_list, drawing, options);
+    fs::write(path, gcode(&paths, options)).map_err(|err| ExportError(err.to_string()))
+}
+
+/// Saves the lines in the drawing as HPGL that draws them with a pen plotter
+pub fn save_hpgl(
+    display_list: &DisplayList,
+    drawing: &DrawingState,
+    options: &PlotterOptions,
+    path: &FilePath,
+) -> Result<(), ExportError> {
+    let paths = toolpaths(display_list, drawing, options);
+    fs::write(path, hpgl(&paths, options)).map_err(|err| ExportError(err.to_string()))
+}
+
+/// Converts the visible lines in the drawing into a list of paths on the paper (in millimeters)
+///
+/// Each path is drawn from start to end without lifting the pen. Consecutive lines that meet end
+/// to start (like the lines drawn by a turtle that never lifts its pen) are combined into a
+/// single path.
+fn toolpaths(display_list: &DisplayList, drawing: &DrawingState, options: &PlotterOptions) -> Vec<Vec<Point>> {
+    let area = options.region.area(display_list, drawing);
+    let (page_width, page_height) = options.page_size();
+    let scale = export::page_scale(
+        area,
+        (page_width, page_height),
+        options.margin,
+        options.true_scale,
+        drawing.dpi,
+    );
+    let center = area.center();
+    let to_paper = |point: Point| Point {
+        x: page_width / 2.0 + (point.x - center.x) * scale,
+        y: page_height / 2.0 + (point.y - center.y) * scale,
+    };
+
+    let mut paths: Vec<Vec<Point>> = Vec::new();
+    for prim in symmetry::visible_prims(display_list, area, drawing.symmetry) {
+        let outlines = match *prim {
+            // A plotter cannot take ink off the paper, so lines and curves that erase are skipped
+            DrawPrim::Line(Line {blend_mode: BlendMode::Erase, ..}) |
+            DrawPrim::Curve(Curve {blend_mode: BlendMode::Erase, ..}) => continue,
+            // The pen is lifted between the dashes of dashed lines and curves
+            DrawPrim::Line(Line {start, end, ref dash, ..}) => dashed(&[start, end], dash.as_ref()),
+            // Only the outline of a circle is drawn, just like with the other filled shapes
+            DrawPrim::Circle(Circle {center, radius, thickness, ..}) if thickness > 0.0 => {
+                vec![Circle::flatten(center, radius, CIRCLE_TOLERANCE / scale)]
+            },
+            DrawPrim::Curve(ref curve) => dashed(&curve.flatten(CIRCLE_TOLERANCE / scale), curve.dash.as_ref()),
+            // Text is drawn as the outline of each character
+            DrawPrim::Text(Text {ref outlines, ..}) => outlines.iter().map(|outline| {
+                let mut outline = outline.clone();
+                outline.push(outline[0]);
+                outline
+            }).collect(),
+            DrawPrim::Circle(_) | DrawPrim::Polygon(_) | DrawPrim::Path(_) | DrawPrim::Image(_) => continue,
+        };
+
+        for segment in outlines.iter().flat_map(|points| points.windows(2)) {
+            let (start, end) = match clip(segment[0], segment[1], area) {
+                Some(line) => line,
+                None => continue,
+            };
+            let (start, end) = (to_paper(start), to_paper(end));
+
+            match paths.last_mut() {
+                Some(path) if path.last().is_some_and(|&last| (last - start).len() <= JOIN_TOLERANCE) => {
+                    path.push(end);
+                },
+                _ => paths.push(vec![start, end]),
+            }
+        }
+    }
+
+    paths
+}
+
+/// Clips the line from `start` to `end` to the given area using the Liang-Barsky algorithm
+///
+/// Returns `None` if no part of the line is inside of the area.
+fn clip(start: Point, end: Point, area: Rect) -> Option<(Point, Point)> {
+    let delta = end - start;
+    let mut t_min = 0.0f64;
+    let mut t_max = 1.0f64;
+
+    let edges = [
+        (-delta.x, start.x - area.min.x),
+        (delta.x, area.max.x - start.x),
+        (-delta.y, start.y - area.min.y),
+        (delta.y, area.max.y - start.y),
+    ];
+    for &(p, q) in &edges {
+        if p == 0.0 {
+            // Parallel to this edge, so the line is either entirely inside or outside of it
+            if q < 0.0 {
+                return None;
+            }
+            continue;
+        }
+
+        let t = q / p;
+        if p < 0.0 {
+            t_min = t_min.max(t);
+        } else {
+            t_max = t_max.min(t);
+        }
+    }
+
+    if t_min > t_max {
+        return None;
+    }
+
+    let at = |t: f64| Point {x: start.x + delta.x * t, y: start.y + delta.y * t};
+    Some((at(t_min), at(t_max)))
+}
+
+/// Generates G-code that draws the given paths
+fn gcode(paths: &[Vec<Point>], options: &PlotterOptions) -> String {
+    let pen_up = format!("G0 Z{:.3}", options.pen_up_height);
+    let pen_down = format!("G1 Z{:.3} F{:.0}", options.pen_down_height, options.feed_rate);
+
+    let mut lines = vec![
+        "G21 ; millimeters".to_string(),
+        "G90 ; absolute positioning".to_string(),
+        pen_up.clone(),
+    ];
+    for path in paths {
+        let (first, rest) = path.split_first().expect("bug: paths are never empty");
+        lines.push(format!("G0 X{:.3} Y{:.3} F{:.0}", first.x, first.y, options.travel_rate));
+        lines.push(pen_down.clone());
+        lines.extend(rest.iter()
+            .map(|point| format!("G1 X{:.3} Y{:.3} F{:.0}", point.x, point.y, options.feed_rate)));
+        lines.push(pen_up.clone());
+    }
+    lines.push(format!("G0 X0 Y0 F{:.0}", options.travel_rate));
+
+    lines.join("\n") + "\n"
+}
+
+/// Generates HPGL that draws the given paths
+fn hpgl(paths: &[Vec<Point>], options: &PlotterOptions) -> String {
+    let units = |value: f64| (value * HPGL_UNITS_PER_MM).round() as i64;
+    // HPGL velocities are in centimeters per second
+    let velocity = options.feed_rate / 600.0;
+
+    let mut out = format!("IN;SP1;VS{:.1};\n", velocity);
+    for path in paths {
+        let (first, rest) = path.split_first().expect("bug: paths are never empty");
+        write!(out, "PU{},{};PD", units(first.x), units(first.y)).expect("write to string cannot fail");
+
+        let points: Vec<_> = rest.iter()
+            .map(|point| format!("{},{}", units(point.x), units(point.y)))
+            .collect();
+        out.push_str(&points.join(","));
+        out.push_str(";\n");
+    }
+    out.push_str("PU;SP0;\n");
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::colors::BLACK;
+    use super::super::super::state::Pen;
+
+    fn line_options() -> PlotterOptions {
+        // A 100x100 area mapped onto a 120x120mm page with a 10mm margin, so 1px = 1mm and the
+        // origin of the drawing is at (60, 60) on the paper
+        PlotterOptions {
+            region: ExportRegion::Rect(Rect::from_center(Point::origin(), 100.0, 100.0)),
+            paper: PaperSize::Custom {width: 120.0, height: 120.0},
+            ..PlotterOptions::default()
+        }
+    }
+
+    #[test]
+    fn joins_connected_lines() {
+        let pen = Pen {is_enabled: true, thickness: 1.0, color: BLACK, dash_pattern: Vec::new(), blend_mode: BlendMode::Alpha};
+        let mut display_list = DisplayList::default();
+        display_list.push_line(Point {x: 0.0, y: 0.0}, Point {x: 10.0, y: 0.0}, &pen);
+        display_list.push_line(Point {x: 10.0, y: 0.0}, Point {x: 10.0, y: 10.0}, &pen);
+        display_list.push_line(Point {x: -10.0, y: 0.0}, Point {x: -20.0, y: 0.0}, &pen);
+        display_list.push_polygon(vec![
+            Point {x: 0.0, y: 0.0},
+            Point {x: 5.0, y: 0.0},
+            Point {x: 5.0, y: 5.0},
+        ], BLACK);
+
+        let paths = toolpaths(&display_list, &DrawingState::default(), &line_options());
+        assert_eq!(paths, vec![
+            vec![Point {x: 60.0, y: 60.0}, Point {x: 70.0, y: 60.0}, Point {x: 70.0, y: 70.0}],
+            vec![Point {x: 50.0, y: 60.0}, Point {x: 40.0, y: 60.0}],
+        ]);
+
+        let gcode = gcode(&paths, &line_options());
+        assert_eq!(gcode.matches("G0 Z5.000").count(), 3);
+        assert!(gcode.contains("G1 X70.000 Y70.000 F1500"), "{}", gcode);
+
+        let hpgl = hpgl(&paths, &line_options());
+        assert!(hpgl.contains("PU2400,2400;PD2800,2400,2800,2800;"), "{}", hpgl);
+        assert!(hpgl.ends_with("PU;SP0;\n"));
+    }
+
+    #[test]
+    fn clips_lines_to_area() {
+        let area = Rect::from_center(Point::origin(), 100.0, 100.0);
+
+        let clipped = clip(Point {x: 0.0, y: 0.0}, Point {x: 100.0, y: 0.0}, area);
+        assert_eq!(clipped, Some((Point {x: 0.0, y: 0.0}, Point {x: 50.0, y: 0.0})));
+
+        let clipped = clip(Point {x: -100.0, y: -100.0}, Point {x: 100.0, y: 100.0}, area);
+        assert_eq!(clipped, Some((Point {x: -50.0, y: -50.0}, Point {x: 50.0, y: 50.0})));
+
+        assert_eq!(clip(Point {x: 60.0, y: 0.0}, Point {x: 60.0, y: 10.0}, area), None);
+    }
+}
@@ -0,0 +1,373 @@
+//! Applies a user-provided fragment shader to the final image shown in the window
+//!
+//! Shaders are written in WGSL and translated to GLSL so that they can run on the OpenGL context
+//! used by the renderer. The translation happens in the client so that any errors can be reported
+//! right away. Only the WGSL source is ever sent to the window, which translates it again, so that
+//! a connection from another machine (or a command log) can't hand the renderer GLSL that was not
+//! produced and validated by naga. The renderer copies each frame into a texture and then draws
+//! that texture back onto the window using the shader.
+
+use std::ffi::CString;
+use std::ptr;
+use std::convert::TryFrom;
+
+use gl::types::{GLchar, GLenum, GLint, GLuint};
+use thiserror::Error;
+use serde::{Serialize, Deserialize};
+use naga::{
+    ShaderStage,
+    back::glsl,
+    front::wgsl,
+    proc::BoundsCheckPolicies,
+    valid::{Capabilities, ValidationFlags, Validator},
+};
+
+/// The GLSL version that shaders are translated to, the same version required by the renderer
+const GLSL_VERSION: u16 = 330;
+
+/// Draws a single triangle that covers the entire window and passes along the position of each
+/// fragment with (0, 0) at the top-left corner and (1, 1) at the bottom-right corner
+///
+/// The name of the output must match the name that naga uses for the input at location 0.
+const VERTEX_SHADER: &str = "#version 330 core
+out vec2 _vs2fs_location0;
+void main() {
+    vec2 pos = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+    _vs2fs_location0 = vec2(pos.x, 1.0 - pos.y);
+    gl_Position = vec4(pos * 2.0 - 1.0, 0.0, 1.0);
+}
+";
+
+/// An error produced while compiling a post-processing shader
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[error("{0}")]
+pub struct ShaderError(String);
+
+/// A post-processing shader that has been translated so that it can be used by the renderer
+///
+/// Serialized as its WGSL source and translated again when deserialized.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct PostShader {
+    /// The WGSL source that the shader was translated from
+    source: String,
+    /// The GLSL source of the fragment shader
+    glsl: String,
+    /// The name of the sampler uniform that should be bound to the frame, if the shader uses it
+    frame_sampler: Option<String>,
+}
+
+impl PostShader {
+    /// Translates the given WGSL source into a shader that can be used by the renderer
+    ///
+    /// The source must contain a single fragment entry point. The frame is available as a
+    /// `texture_2d<f32>` at `@group(0) @binding(0)` along with a sampler at `@group(0)
+    /// @binding(1)`. The position of the current fragment (from `(0, 0)` at the top-left to `(1,
+    /// 1)` at the bottom-right) is passed in at `@location(0)`.
+    pub fn from_wgsl(source: &str) -> Result<Self, ShaderError> {
+        let module = wgsl::parse_str(source)
+            .map_err(|err| ShaderError(err.emit_to_string(source)))?;
+        let info = Validator::new(ValidationFlags::all(), Capabilities::empty())
+            .validate(&module)
+            .map_err(|err| ShaderError(err.emit_to_string(source)))?;
+
+        let mut entry_points = module.entry_points.iter()
+            .filter(|entry_point| entry_point.stage == ShaderStage::Fragment);
+        let entry_point = match (entry_points.next(), entry_points.next()) {
+            (Some(entry_point), None) => entry_point,
+            (None, _) => return Err(ShaderError("shader must have a `@fragment` entry point".to_string())),
+            (Some(_), Some(_)) => return Err(ShaderError("shader must only have one `@fragment` entry point".to_string())),
+        };
+
+        let options = glsl::Options {
+            version: glsl::Version::Desktop(GLSL_VERSION),
+            ..glsl::Options::default()
+        };
+        let pipeline_options = glsl::PipelineOptions {
+            shader_stage: ShaderStage::Fragment,
+            entry_point: entry_point.name.clone(),
+            multiview: None,
+        };
+
+        let mut output = String::new();
+        let mut writer = glsl::Writer::new(
+            &mut output,
+            &module,
+            &info,
+            &options,
+            &pipeline_options,
+            BoundsCheckPolicies::default(),
+        ).map_err(|err| ShaderError(err.to_string()))?;
+        let reflection = writer.write().map_err(|err| ShaderError(err.to_string()))?;
+
+        // Only the frame at binding 0 is provided, so any other textures can't be used
+        let mut frame_sampler = None;
+        for (name, mapping) in reflection.texture_mapping {
+            let binding = module.global_variables[mapping.texture].binding.as_ref();
+            match binding {
+                Some(naga::ResourceBinding {group: 0, binding: 0}) => frame_sampler = Some(name),
+                _ => return Err(ShaderError("the only texture available is the frame at `@group(0) @binding(0)`".to_string())),
+            }
+        }
+        if !reflection.uniforms.is_empty() {
+            return Err(ShaderError("uniform buffers are not supported".to_string()));
+        }
+
+        Ok(Self {source: source.to_string(), glsl: output, frame_sampler})
+    }
+}
+
+impl From<PostShader> for String {
+    fn from(shader: PostShader) -> Self {
+        shader.source
+    }
+}
+
+impl TryFrom<String> for PostShader {
+    type Error = ShaderError;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Self::from_wgsl(&source)
+    }
+}
+
+/// The OpenGL objects needed to apply a post-processing shader
+///
+/// These must only be created and dropped while the OpenGL context is current.
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+pub struct PostProcessor {
+    program: GLuint,
+    /// The location of the sampler uniform in the program, if any
+    frame_location: Option<GLint>,
+    /// An empty vertex array, required by OpenGL even though there are no vertex attributes
+    vertex_array: GLuint,
+    /// The texture that each frame is copied into and the framebuffer used to copy into it
+    texture: GLuint,
+    framebuffer: GLuint,
+    /// The size of `texture`, or (0, 0) if it has not been allocated yet
+    size: (i32, i32),
+}
+
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+impl PostProcessor {
+    /// Compiles the given shader
+    ///
+    /// The GLSL should always be valid since it was produced by naga, but that is not guaranteed
+    /// for every driver, so any compilation errors are still returned.
+    pub fn new(shader: &PostShader) -> Result<Self, ShaderError> {
+        // Safety: these functions are only called on the thread where the context is current
+        unsafe {
+            let vertex = compile_shader(gl::VERTEX_SHADER, VERTEX_SHADER)?;
+            let fragment = match compile_shader(gl::FRAGMENT_SHADER, &shader.glsl) {
+                Ok(fragment) => fragment,
+                Err(err) => {
+                    gl::DeleteShader(vertex);
+                    return Err(err);
+                },
+            };
+
+            let program = gl::CreateProgram();
+            gl::AttachShader(program, vertex);
+            gl::AttachShader(program, fragment);
+            gl::LinkProgram(program);
+            // The shaders are no longer needed once the program has been linked
+            gl::DeleteShader(vertex);
+            gl::DeleteShader(fragment);
+
+            let mut status = 0;
+            gl::GetProgramiv(program, gl::LINK_STATUS, &mut status);
+            if status == 0 {
+                let err = info_log(program, gl::GetProgramiv, gl::GetProgramInfoLog);
+                gl::DeleteProgram(program);
+                return Err(ShaderError(err));
+            }
+
+            let frame_location = match &shader.frame_sampler {
+                Some(name) => {
+                    let name = CString::new(name.as_str()).expect("bug: uniform names cannot contain NUL");
+                    Some(gl::GetUniformLocation(program, name.as_ptr()))
+                },
+                None => None,
+            };
+
+            let mut vertex_array = 0;
+            gl::GenVertexArrays(1, &mut vertex_array);
+            let mut texture = 0;
+            gl::GenTextures(1, &mut texture);
+            let mut framebuffer = 0;
+            gl::GenFramebuffers(1, &mut framebuffer);
+
+            Ok(Self {
+                program,
+                frame_location,
+                vertex_array,
+                texture,
+                framebuffer,
+                size: (0, 0),
+            })
+        }
+    }
+
+    /// Applies the shader to whatever is currently drawn in the default framebuffer, which must
+    /// have the given size in pixels
+    pub fn apply(&mut self, width: i32, height: i32) {
+        // Safety: this is only called on the thread where the context is current
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            if self.size != (width, height) {
+                gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA8 as GLint, width, height, 0,
+                    gl::RGBA, gl::UNSIGNED_BYTE, ptr::null());
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
+
+                gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.framebuffer);
+                gl::FramebufferTexture2D(gl::DRAW_FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, self.texture, 0);
+                self.size = (width, height);
+            }
+
+            // Copy the frame into the texture, upside down so that the first row of the texture
+            // is the top of the window like in WGSL
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.framebuffer);
+            gl::BlitFramebuffer(0, 0, width, height, 0, height, width, 0, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+
+            // Draw the texture back onto the window using the shader
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(0, 0, width, height);
+            gl::Disable(gl::BLEND);
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Disable(gl::STENCIL_TEST);
+            gl::Disable(gl::SCISSOR_TEST);
+
+            gl::UseProgram(self.program);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            if let Some(location) = self.frame_location {
+                gl::Uniform1i(location, 0);
+            }
+            gl::BindVertexArray(self.vertex_array);
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+
+            gl::BindVertexArray(0);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::UseProgram(0);
+        }
+    }
+}
+
+impl Drop for PostProcessor {
+    fn drop(&mut self) {
+        // Safety: this is only dropped on the thread where the context is current
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.framebuffer);
+            gl::DeleteTextures(1, &self.texture);
+            gl::DeleteVertexArrays(1, &self.vertex_array);
+            gl::DeleteProgram(self.program);
+        }
+    }
+}
+
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+unsafe fn compile_shader(kind: GLenum, source: &str) -> Result<GLuint, ShaderError> {
+    let source = CString::new(source).map_err(|_| ShaderError("shader cannot contain NUL".to_string()))?;
+
+    let shader = gl::CreateShader(kind);
+    gl::ShaderSource(shader, 1, &source.as_ptr(), ptr::null());
+    gl::CompileShader(shader);
+
+    let mut status = 0;
+    gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut status);
+    if status == 0 {
+        let err = info_log(shader, gl::GetShaderiv, gl::GetShaderInfoLog);
+        gl::DeleteShader(shader);
+        return Err(ShaderError(err));
+    }
+
+    Ok(shader)
+}
+
+/// Reads the info log of a shader or program
+#[cfg_attr(any(feature = "test", feature = "headless", test), allow(dead_code))]
+unsafe fn info_log(
+    object: GLuint,
+    get_iv: unsafe fn(GLuint, GLenum, *mut GLint),
+    get_log: unsafe fn(GLuint, GLint, *mut GLint, *mut GLchar),
+) -> String {
+    let mut len = 0;
+    get_iv(object, gl::INFO_LOG_LENGTH, &mut len);
+
+    let mut log = vec![0u8; len.max(1) as usize];
+    let mut written = 0;
+    get_log(object, len, &mut written, log.as_mut_ptr() as *mut GLchar);
+    log.truncate(written.max(0) as usize);
+
+    String::from_utf8_lossy(&log).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_passthrough_shader() {
+        let shader = PostShader::from_wgsl("
+            @group(0) @binding(0) var frame: texture_2d<f32>;
+            @group(0) @binding(1) var frame_sampler: sampler;
+
+            @fragment
+            fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+                return textureSample(frame, frame_sampler, uv);
+            }
+        ").unwrap();
+
+        assert!(shader.glsl.starts_with("#version 330 core"), "{}", shader.glsl);
+        assert!(shader.glsl.contains("_vs2fs_location0"), "{}", shader.glsl);
+        assert!(shader.frame_sampler.is_some());
+    }
+
+    #[test]
+    fn only_sends_wgsl_source() {
+        let source = "
+            @fragment
+            fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+                return vec4<f32>(uv, 0.0, 1.0);
+            }
+        ";
+        let shader = PostShader::from_wgsl(source).unwrap();
+
+        let json = serde_json::to_string(&shader).unwrap();
+        assert_eq!(json, serde_json::to_string(source).unwrap());
+        assert_eq!(serde_json::from_str::<PostShader>(&json).unwrap(), shader);
+
+        // GLSL that was not produced by naga can never be received
+        let glsl = serde_json::json!({"glsl": shader.glsl, "frame_sampler": null});
+        assert!(serde_json::from_value::<PostShader>(glsl).is_err());
+        assert!(serde_json::from_str::<PostShader>(&serde_json::to_string(&shader.glsl).unwrap()).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_shaders() {
+        assert!(PostShader::from_wgsl("not a shader").is_err());
+
+        // No fragment entry point
+        assert!(PostShader::from_wgsl("
+            @vertex
+            fn main() -> @builtin(position) vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+        ").is_err());
+
+        // A texture other than the frame
+        assert!(PostShader::from_wgsl("
+            @group(0) @binding(3) var other: texture_2d<f32>;
+            @group(0) @binding(1) var frame_sampler: sampler;
+
+            @fragment
+            fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+                return textureSample(other, frame_sampler, uv);
+            }
+        ").is_err());
+    }
+}
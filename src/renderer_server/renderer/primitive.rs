@@ -0,0 +1,292 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{Color, Point, FillRule, BlendMode};
+
+use super::display_list::{self, DisplayList, DrawPrim, PrimHandle};
+
+/// Identifies a single shape in the drawing
+///
+/// IDs are returned by [`Drawing::hit_test()`](struct.Drawing.html#method.hit_test) and can be
+/// used to look up the shape with [`Drawing::primitive()`](struct.Drawing.html#method.primitive).
+/// Every shape ever drawn has a different ID, even after it is undone or cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PrimitiveId(PrimHandle);
+
+/// A single shape in the drawing, as returned by
+/// [`Drawing::primitives()`](struct.Drawing.html#method.primitives)
+///
+/// Every position uses the same coordinate system as the turtle: the origin is at the center of
+/// the drawing and the y-axis points up. Thicknesses and dash lengths are in pixels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Primitive {
+    /// A straight line, drawn with rounded ends
+    Line {
+        start: Point,
+        end: Point,
+        thickness: f64,
+        color: Color,
+        /// The lengths of each dash followed by the gap after it, or `None` if the line is solid
+        dash: Option<Vec<f64>>,
+        /// The distance into the dash pattern at which the line starts
+        dash_offset: f64,
+        /// How the line combines with what was drawn before it
+        blend_mode: BlendMode,
+    },
+
+    /// A filled polygon, e.g. from [`Turtle::begin_fill()`](struct.Turtle.html#method.begin_fill)
+    Polygon {
+        points: Vec<Point>,
+        fill_color: Color,
+        fill_rule: FillRule,
+    },
+
+    /// A circle, with an outline if `thickness` is greater than zero
+    ///
+    /// The fill color is transparent if the circle is not filled.
+    Circle {
+        center: Point,
+        radius: f64,
+        thickness: f64,
+        color: Color,
+        fill_color: Color,
+    },
+
+    /// A cubic Bézier curve, drawn with rounded ends
+    Curve {
+        start: Point,
+        control1: Point,
+        control2: Point,
+        end: Point,
+        thickness: f64,
+        color: Color,
+        /// The lengths of each dash followed by the gap after it, or `None` if the curve is solid
+        dash: Option<Vec<f64>>,
+        /// The distance into the dash pattern at which the curve starts
+        dash_offset: f64,
+        /// How the curve combines with what was drawn before it
+        blend_mode: BlendMode,
+    },
+
+    /// Written text, given as the outline of each character
+    ///
+    /// Every outline is a closed loop of points. All of the outlines are filled together using
+    /// the non-zero fill rule, which leaves the holes in characters like "o" or "A" empty.
+    Text {
+        outlines: Vec<Vec<Point>>,
+        color: Color,
+    },
+
+    /// A filled shape made up of several closed loops of points, e.g. from
+    /// [`Drawing::import_svg()`](struct.Drawing.html#method.import_svg)
+    ///
+    /// All of the loops are filled together, so the fill rule decides which areas (like the hole
+    /// in a ring) are left empty.
+    Path {
+        subpaths: Vec<Vec<Point>>,
+        fill_color: Color,
+        fill_rule: FillRule,
+    },
+
+    /// A stamped image
+    ///
+    /// The pixels of the image are not included. `right` is the vector from the center of the
+    /// image to the middle of its right edge and `up` is the vector from the center to the middle
+    /// of its top edge, so together they give the size and rotation of the image.
+    Image {
+        center: Point,
+        right: Point,
+        up: Point,
+    },
+}
+
+impl From<&DrawPrim> for Primitive {
+    fn from(prim: &DrawPrim) -> Self {
+        let dash = |dash: &Option<display_list::Dash>| match dash {
+            Some(dash) => (Some(dash.pattern.to_vec()), dash.offset),
+            None => (None, 0.0),
+        };
+
+        match prim {
+            DrawPrim::Line(line) => {
+                let (dash, dash_offset) = dash(&line.dash);
+                Primitive::Line {
+                    start: line.start,
+                    end: line.end,
+                    thickness: line.thickness,
+                    color: line.color,
+                    dash,
+                    dash_offset,
+                    blend_mode: line.blend_mode,
+                }
+            },
+
+            DrawPrim::Polygon(polygon) => Primitive::Polygon {
+                points: polygon.points.clone(),
+                fill_color: polygon.fill_color,
+                fill_rule: polygon.fill_rule,
+            },
+
+            DrawPrim::Circle(circle) => Primitive::Circle {
+                center: circle.center,
+                radius: circle.radius,
+                thickness: circle.thickness,
+                color: circle.color,
+                fill_color: circle.fill_color,
+            },
+
+            DrawPrim::Curve(curve) => {
+                let (dash, dash_offset) = dash(&curve.dash);
+                Primitive::Curve {
+                    start: curve.start,
+                    control1: curve.control1,
+                    control2: curve.control2,
+                    end: curve.end,
+                    thickness: curve.thickness,
+                    color: curve.color,
+                    dash,
+                    dash_offset,
+                    blend_mode: curve.blend_mode,
+                }
+            },
+
+            DrawPrim::Text(text) => Primitive::Text {
+                outlines: text.outlines.clone(),
+                color: text.color,
+            },
+
+            DrawPrim::Path(path) => Primitive::Path {
+                subpaths: path.subpaths.clone(),
+                fill_color: path.fill_color,
+                fill_rule: path.fill_rule,
+            },
+
+            DrawPrim::Image(image) => Primitive::Image {
+                center: image.center,
+                right: image.right,
+                up: image.up,
+            },
+        }
+    }
+}
+
+impl Primitive {
+    /// Returns true if every color in this primitive is valid and every number is finite
+    pub(crate) fn is_valid(&self) -> bool {
+        let is_finite = |points: &[Point]| points.iter().all(|pt| pt.is_finite());
+        let is_valid_dash = |dash: &Option<Vec<f64>>, offset: f64| offset.is_finite() && match dash {
+            // Dash patterns are always made even before they are drawn
+            Some(pattern) => !pattern.is_empty() && pattern.len().is_multiple_of(2) && display_list::is_valid_dash_pattern(pattern),
+            None => true,
+        };
+
+        match self {
+            Primitive::Line {start, end, thickness, color, dash, dash_offset, ..} => {
+                is_finite(&[*start, *end]) && thickness.is_finite() && *thickness >= 0.0
+                    && color.is_valid() && is_valid_dash(dash, *dash_offset)
+            },
+            Primitive::Polygon {points, fill_color, ..} => {
+                !points.is_empty() && is_finite(points) && fill_color.is_valid()
+            },
+            Primitive::Circle {center, radius, thickness, color, fill_color} => {
+                center.is_finite() && radius.is_finite() && *radius >= 0.0
+                    && thickness.is_finite() && *thickness >= 0.0
+                    && color.is_valid() && fill_color.is_valid()
+            },
+            Primitive::Curve {start, control1, control2, end, thickness, color, dash, dash_offset, ..} => {
+                is_finite(&[*start, *control1, *control2, *end]) && thickness.is_finite()
+                    && *thickness >= 0.0 && color.is_valid() && is_valid_dash(dash, *dash_offset)
+            },
+            Primitive::Text {outlines, color} => {
+                !outlines.is_empty() && outlines.iter().all(|outline| is_finite(outline)) && color.is_valid()
+            },
+            Primitive::Path {subpaths, fill_color, ..} => {
+                !subpaths.is_empty() && subpaths.iter().all(|subpath| is_finite(subpath)) && fill_color.is_valid()
+            },
+            Primitive::Image {center, right, up} => is_finite(&[*center, *right, *up]),
+        }
+    }
+
+    /// Converts this primitive back into a primitive that can be added to a display list
+    ///
+    /// Returns `None` for images since the pixels of the image are not available.
+    pub(crate) fn to_draw_prim(&self) -> Option<DrawPrim> {
+        let dash = |dash: &Option<Vec<f64>>, offset: f64| dash.as_ref().map(|pattern| display_list::Dash {
+            pattern: pattern.as_slice().into(),
+            offset,
+        });
+
+        let prim = match self {
+            Primitive::Line {start, end, thickness, color, dash: line_dash, dash_offset, blend_mode} => {
+                DrawPrim::Line(display_list::Line {
+                    start: *start,
+                    end: *end,
+                    thickness: *thickness,
+                    color: *color,
+                    dash: dash(line_dash, *dash_offset),
+                    blend_mode: *blend_mode,
+                })
+            },
+
+            Primitive::Polygon {points, fill_color, fill_rule} => DrawPrim::Polygon(display_list::Polygon {
+                points: points.clone(),
+                fill_color: *fill_color,
+                fill_rule: *fill_rule,
+            }),
+
+            Primitive::Circle {center, radius, thickness, color, fill_color} => DrawPrim::Circle(display_list::Circle {
+                center: *center,
+                radius: *radius,
+                thickness: *thickness,
+                color: *color,
+                fill_color: *fill_color,
+            }),
+
+            Primitive::Curve {start, control1, control2, end, thickness, color, dash: curve_dash, dash_offset, blend_mode} => {
+                DrawPrim::Curve(display_list::Curve {
+                    start: *start,
+                    control1: *control1,
+                    control2: *control2,
+                    end: *end,
+                    thickness: *thickness,
+                    color: *color,
+                    dash: dash(curve_dash, *dash_offset),
+                    blend_mode: *blend_mode,
+                })
+            },
+
+            Primitive::Text {outlines, color} => DrawPrim::Text(display_list::Text {
+                outlines: outlines.clone(),
+                color: *color,
+            }),
+
+            Primitive::Path {subpaths, fill_color, fill_rule} => DrawPrim::Path(display_list::Path {
+                subpaths: subpaths.clone(),
+                fill_color: *fill_color,
+                fill_rule: *fill_rule,
+            }),
+
+            Primitive::Image {..} => return None,
+        };
+
+        Some(prim)
+    }
+}
+
+/// Returns the ID of every primitive that contains the given point, in the order they are drawn in
+///
+/// Primitives in hidden layers are skipped.
+pub fn hit_test(display_list: &DisplayList, point: Point) -> Vec<PrimitiveId> {
+    display_list.hit_test(point).into_iter().map(PrimitiveId).collect()
+}
+
+/// Returns a copy of the primitive with the given ID, if it is still in the display list
+pub fn get(display_list: &DisplayList, id: PrimitiveId) -> Option<Primitive> {
+    display_list.get(id.0).map(Primitive::from)
+}
+
+/// Returns a copy of every primitive in the display list, in the order they are drawn in
+///
+/// Primitives in hidden layers are skipped.
+pub fn snapshot(display_list: &DisplayList) -> Vec<Primitive> {
+    display_list.iter().map(Primitive::from).collect()
+}
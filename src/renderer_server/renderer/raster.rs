@@ -0,0 +1,590 @@
+use std::f64::consts::PI;
+
+use crate::{Color, Point, Rect, FillRule, BlendMode};
+
+use super::display_list::{DisplayList, DrawPrim, Line, Polygon, Circle, Text, Path, dashed};
+use super::image::{ImageData, BackgroundMode};
+use super::symmetry::{self, Symmetry};
+use super::super::coords::ScreenPoint;
+
+/// The number of rows sampled within each pixel to anti-alias the edges of shapes
+///
+/// The horizontal coverage of each row is computed exactly, so only vertical sampling is needed.
+const SUBSAMPLES: usize = 4;
+
+/// The number of line segments used to approximate each rounded end of a line
+const CAP_SEGMENTS: usize = 12;
+
+/// The largest distance (in pixels) between a circle or curve and the line segments used to draw it
+const CIRCLE_TOLERANCE: f64 = 0.1;
+
+/// The largest number of pixels that a rasterized image can have
+const MAX_PIXELS: u64 = 1 << 26;
+
+/// An image rendered in software, without a window or a GPU
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pixmap {
+    width: u32,
+    height: u32,
+    /// Each pixel as premultiplied RGBA, row by row from the top
+    pixels: Vec<[u8; 4]>,
+    /// The pixels that are uncovered by erasing, in the same order as `pixels`
+    ///
+    /// Empty if erasing makes pixels transparent.
+    erased: Vec<[u8; 4]>,
+}
+
+impl Pixmap {
+    /// Creates an image with the given size in pixels where every pixel is the given color
+    ///
+    /// Returns `None` if the image would be too large to allocate.
+    pub fn new(width: u32, height: u32, color: Color) -> Option<Self> {
+        if width as u64 * height as u64 > MAX_PIXELS {
+            return None;
+        }
+
+        let pixels = vec![premultiply(color, 1.0); width as usize * height as usize];
+        Some(Self {width, height, pixels, erased: Vec::new()})
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the pixels of the image as (non-premultiplied) RGBA bytes, row by row from the top
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.pixels.iter().flat_map(|&[r, g, b, a]| {
+            let unpremultiply = |c: u8| match a {
+                0 => 0,
+                _ => ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8,
+            };
+            [unpremultiply(r), unpremultiply(g), unpremultiply(b), a]
+        }).collect()
+    }
+
+    /// Returns the (non-premultiplied) color of the pixel at the given position, counting from
+    /// the top left corner of the image
+    pub fn color_at(&self, x: u32, y: u32) -> Color {
+        let [r, g, b, a] = self.pixels[(y * self.width + x) as usize];
+        if a == 0 {
+            return Color {red: 0.0, green: 0.0, blue: 0.0, alpha: 0.0};
+        }
+
+        let alpha = a as f64 / 255.0;
+        Color {
+            red: (r as f64 / alpha).min(255.0),
+            green: (g as f64 / alpha).min(255.0),
+            blue: (b as f64 / alpha).min(255.0),
+            alpha,
+        }
+    }
+
+    /// Replaces the pixels of this image with the given image, placing its top left corner at the
+    /// given position
+    ///
+    /// Any part of the given image that is outside of this image is cut off.
+    pub fn copy_from(&mut self, image: &Pixmap, x: i64, y: i64) {
+        let first_col = x.max(0);
+        let end_col = (x + image.width as i64).min(self.width as i64);
+        if first_col >= end_col {
+            return;
+        }
+
+        for row in y.max(0)..(y + image.height as i64).min(self.height as i64) {
+            let dest = (row * self.width as i64) as usize;
+            let src = ((row - y) * image.width as i64) as usize;
+            self.pixels[dest + first_col as usize..dest + end_col as usize]
+                .copy_from_slice(&image.pixels[src + (first_col - x) as usize..src + (end_col - x) as usize]);
+        }
+    }
+
+    /// Makes everything drawn so far the pixels that erasing uncovers
+    fn save_erased(&mut self) {
+        self.erased = self.pixels.clone();
+    }
+
+    /// Fills the given polygon (in pixel coordinates) using the non-zero fill rule
+    fn fill(&mut self, points: &[ScreenPoint], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+
+        self.fill_outlines(&[points], color, FillRule::NonZero, BlendMode::Alpha);
+    }
+
+    /// Fills the area inside all of the given closed outlines (in pixel coordinates) together
+    /// using the given fill rule, combining the color with each pixel using the given blend mode
+    ///
+    /// Filling the outlines together (rather than one at a time) leaves holes wherever an outline
+    /// goes around the other way inside of another outline.
+    fn fill_outlines(&mut self, outlines: &[&[ScreenPoint]], color: Color, fill_rule: FillRule, blend_mode: BlendMode) {
+        if color.alpha <= 0.0 {
+            return;
+        }
+
+        let (min_y, max_y) = outlines.iter().flat_map(|points| points.iter())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), p| (min.min(p.y), max.max(p.y)));
+        let first_row = min_y.floor().max(0.0) as u32;
+        let end_row = (max_y.ceil().max(0.0) as u32).min(self.height);
+
+        let width = self.width as usize;
+        let mut coverage = vec![0.0f32; width];
+        let mut crossings = Vec::new();
+
+        for row in first_row..end_row {
+            for sample in 0..SUBSAMPLES {
+                let y = row as f64 + (sample as f64 + 0.5) / SUBSAMPLES as f64;
+
+                // Each crossing is the x position where an edge crosses this row and the
+                // direction that it crosses in
+                crossings.clear();
+                let edges = outlines.iter()
+                    .flat_map(|points| points.iter().zip(points.iter().cycle().skip(1)));
+                for (&p0, &p1) in edges {
+                    if (p0.y <= y) == (p1.y <= y) {
+                        continue;
+                    }
+                    let x = p0.x + (y - p0.y) * (p1.x - p0.x) / (p1.y - p0.y);
+                    crossings.push((x, if p1.y > p0.y { 1 } else { -1 }));
+                }
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                let mut winding = 0;
+                for pair in crossings.windows(2) {
+                    winding += pair[0].1;
+                    if fill_rule.is_inside(winding) {
+                        add_span(&mut coverage, pair[0].0, pair[1].0);
+                    }
+                }
+            }
+
+            let row_start = row as usize * width;
+            for (x, cover) in coverage.iter_mut().enumerate() {
+                if *cover > 0.0 {
+                    let coverage = (*cover as f64 / SUBSAMPLES as f64).min(1.0);
+                    let index = row_start + x;
+                    match blend_mode {
+                        BlendMode::Alpha => blend(&mut self.pixels[index], color, coverage),
+                        BlendMode::Additive => add(&mut self.pixels[index], color, coverage),
+                        BlendMode::Multiply => multiply(&mut self.pixels[index], color, coverage),
+                        BlendMode::Erase => {
+                            let uncovered = self.erased.get(index).copied().unwrap_or([0; 4]);
+                            erase(&mut self.pixels[index], uncovered, color.alpha * coverage);
+                        },
+                    }
+                    *cover = 0.0;
+                }
+            }
+        }
+    }
+
+    /// Draws the given background image over the entire image, arranged with the given mode
+    ///
+    /// Each pixel of the background image covers `scale` pixels unless it is stretched.
+    fn draw_background(&mut self, image: &ImageData, mode: BackgroundMode, scale: f64) {
+        let (width, height) = (self.width as f64, self.height as f64);
+        let tile = mode.placement(image, width, height, scale);
+        let (columns, rows) = match mode {
+            BackgroundMode::Tile => ((width / tile.width()).ceil() as u32, (height / tile.height()).ceil() as u32),
+            BackgroundMode::Stretch | BackgroundMode::Center => (1, 1),
+        };
+
+        for row in 0..rows {
+            for col in 0..columns {
+                let left = tile.min.x + col as f64 * tile.width();
+                let top = tile.min.y + row as f64 * tile.height();
+                self.draw_image([
+                    ScreenPoint {x: left, y: top},
+                    ScreenPoint {x: left + tile.width(), y: top},
+                    ScreenPoint {x: left, y: top + tile.height()},
+                ], image);
+            }
+        }
+    }
+
+    /// Draws an image into the parallelogram with the given corners (in pixel coordinates)
+    ///
+    /// Each pixel is drawn with the color of the nearest pixel of the image, without smoothing.
+    fn draw_image(&mut self, [top_left, top_right, bottom_left]: [ScreenPoint; 3], image: &ImageData) {
+        let across = (top_right.x - top_left.x, top_right.y - top_left.y);
+        let down = (bottom_left.x - top_left.x, bottom_left.y - top_left.y);
+        let determinant = across.0 * down.1 - across.1 * down.0;
+        if determinant.abs() < f64::EPSILON {
+            return;
+        }
+
+        let bottom_right = (top_right.x + down.0, top_right.y + down.1);
+        let xs = [top_left.x, top_right.x, bottom_left.x, bottom_right.0];
+        let ys = [top_left.y, top_right.y, bottom_left.y, bottom_right.1];
+        let clamp_to = |value: f64, max: u32| value.max(0.0).min(max as f64) as u32;
+        let first_col = clamp_to(xs.iter().copied().fold(f64::INFINITY, f64::min).floor(), self.width);
+        let end_col = clamp_to(xs.iter().copied().fold(f64::NEG_INFINITY, f64::max).ceil(), self.width);
+        let first_row = clamp_to(ys.iter().copied().fold(f64::INFINITY, f64::min).floor(), self.height);
+        let end_row = clamp_to(ys.iter().copied().fold(f64::NEG_INFINITY, f64::max).ceil(), self.height);
+
+        for row in first_row..end_row {
+            for col in first_col..end_col {
+                // Find where the center of this pixel is within the image, from 0 to 1 across and
+                // down the image
+                let dx = col as f64 + 0.5 - top_left.x;
+                let dy = row as f64 + 0.5 - top_left.y;
+                let u = (dx * down.1 - dy * down.0) / determinant;
+                let v = (across.0 * dy - across.1 * dx) / determinant;
+                if !(0.0..1.0).contains(&u) || !(0.0..1.0).contains(&v) {
+                    continue;
+                }
+
+                let [red, green, blue, alpha] = image.pixel(
+                    (u * image.width as f64) as u32,
+                    (v * image.height as f64) as u32,
+                );
+                let color = Color {
+                    red: red as f64,
+                    green: green as f64,
+                    blue: blue as f64,
+                    alpha: alpha as f64 / 255.0,
+                };
+                let index = (row * self.width + col) as usize;
+                blend(&mut self.pixels[index], color, 1.0);
+            }
+        }
+    }
+}
+
+/// Adds the horizontal coverage of the span from `x0` to `x1` to each pixel that it overlaps
+fn add_span(coverage: &mut [f32], x0: f64, x1: f64) {
+    let x0 = x0.max(0.0);
+    let x1 = x1.min(coverage.len() as f64);
+    if x1 <= x0 {
+        return;
+    }
+
+    let first = x0.floor() as usize;
+    let last = (x1.ceil() as usize - 1).min(coverage.len() - 1);
+    for (i, cover) in coverage.iter_mut().enumerate().take(last + 1).skip(first) {
+        let start = x0.max(i as f64);
+        let end = x1.min(i as f64 + 1.0);
+        *cover += (end - start) as f32;
+    }
+}
+
+/// Returns the given color as premultiplied RGBA, with its alpha scaled by `coverage`
+fn premultiply(color: Color, coverage: f64) -> [u8; 4] {
+    let alpha = (color.alpha * coverage).clamp(0.0, 1.0);
+    let channel = |c: f64| (c.clamp(0.0, 255.0) * alpha).round() as u8;
+    [channel(color.red), channel(color.green), channel(color.blue), (alpha * 255.0).round() as u8]
+}
+
+/// Draws the given color over a premultiplied pixel, with its alpha scaled by `coverage`
+fn blend(pixel: &mut [u8; 4], color: Color, coverage: f64) {
+    let src = premultiply(color, coverage);
+    let inverse_alpha = 255 - src[3] as u32;
+    for (dst, src) in pixel.iter_mut().zip(src.iter()) {
+        *dst = (*src as u32 + (*dst as u32 * inverse_alpha + 127) / 255).min(255) as u8;
+    }
+}
+
+/// Adds the given color to a premultiplied pixel, with its alpha scaled by `coverage`
+fn add(pixel: &mut [u8; 4], color: Color, coverage: f64) {
+    let src = premultiply(color, coverage);
+    for (dst, src) in pixel.iter_mut().zip(src.iter()) {
+        *dst = dst.saturating_add(*src);
+    }
+}
+
+/// Multiplies a premultiplied pixel by the given color, with its alpha scaled by `coverage`
+///
+/// This is the same as the `multiply` blend mode in CSS, so parts of the color that are over
+/// transparent pixels are drawn as if they were blended normally.
+fn multiply(pixel: &mut [u8; 4], color: Color, coverage: f64) {
+    let src = premultiply(color, coverage).map(|c| c as f64 / 255.0);
+    let dst = pixel.map(|c| c as f64 / 255.0);
+    let (src_alpha, dst_alpha) = (src[3], dst[3]);
+    for i in 0..3 {
+        let value = src[i] * (1.0 - dst_alpha) + dst[i] * (1.0 - src_alpha) + src[i] * dst[i];
+        pixel[i] = (value * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+    pixel[3] = ((src_alpha + dst_alpha - src_alpha * dst_alpha) * 255.0).round().clamp(0.0, 255.0) as u8;
+}
+
+/// Replaces the given fraction of a premultiplied pixel with the pixel that erasing uncovers
+fn erase(pixel: &mut [u8; 4], uncovered: [u8; 4], amount: f64) {
+    let amount = amount.clamp(0.0, 1.0);
+    for (dst, uncovered) in pixel.iter_mut().zip(uncovered.iter()) {
+        let value = *dst as f64 + (*uncovered as f64 - *dst as f64) * amount;
+        *dst = value.round() as u8;
+    }
+}
+
+/// Returns the outline of a line of the given thickness with rounded ends
+///
+/// This matches the round line caps used when the line is drawn in the window.
+fn line_outline(start: ScreenPoint, end: ScreenPoint, thickness: f64) -> Vec<ScreenPoint> {
+    let radius = thickness / 2.0;
+    let angle = (end.y - start.y).atan2(end.x - start.x);
+
+    // Half a circle around each end, going around the outline in one direction
+    let cap = |center: ScreenPoint, start_angle: f64| (0..=CAP_SEGMENTS).map(move |i| {
+        let theta = start_angle + PI * i as f64 / CAP_SEGMENTS as f64;
+        ScreenPoint {
+            x: center.x + radius * theta.cos(),
+            y: center.y + radius * theta.sin(),
+        }
+    });
+
+    cap(end, angle - PI / 2.0).chain(cap(start, angle + PI / 2.0)).collect()
+}
+
+/// Renders the given area of the drawing into an image, scaling it by `scale`
+///
+/// The background image (if any) is arranged within the rendered area. Returns `None` if the
+/// image would be too large.
+pub fn rasterize(
+    display_list: &DisplayList,
+    area: Rect,
+    scale: f64,
+    background: Color,
+    background_image: Option<(&ImageData, BackgroundMode)>,
+    symmetry: Option<Symmetry>,
+) -> Option<Pixmap> {
+    let width = (area.width() * scale).ceil().max(1.0);
+    let height = (area.height() * scale).ceil().max(1.0);
+    let mut pixmap = Pixmap::new(width as u32, height as u32, background)?;
+    if let Some((image, mode)) = background_image {
+        pixmap.draw_background(image, mode, scale);
+    }
+
+    let prims = symmetry::visible_prims(display_list, area, symmetry);
+    // Erasing uncovers the background, so it needs to be kept around
+    if prims.iter().any(|prim| prim.blend_mode() == BlendMode::Erase) {
+        pixmap.save_erased();
+    }
+
+    let center = area.center();
+    let image_center = ScreenPoint {
+        x: width / 2.0,
+        y: height / 2.0,
+    };
+    let to_screen = |point: Point| ScreenPoint::from_logical(point, scale, center, image_center);
+
+    // The outlines of every segment of a line or curve are filled together, so that the places
+    // where the segments overlap are not drawn twice
+    let stroke = |pixmap: &mut Pixmap, parts: Vec<Vec<Point>>, thickness: f64, color: Color, blend_mode: BlendMode| {
+        let outlines: Vec<_> = parts.iter().flat_map(|points| points.windows(2))
+            .map(|segment| line_outline(to_screen(segment[0]), to_screen(segment[1]), thickness * scale))
+            .collect();
+        let outlines: Vec<_> = outlines.iter().map(Vec::as_slice).collect();
+        pixmap.fill_outlines(&outlines, color, FillRule::NonZero, blend_mode);
+    };
+    // Every outline of text or a path is filled together, so that the fill rule can leave holes
+    let fill = |pixmap: &mut Pixmap, outlines: &[Vec<Point>], color: Color, fill_rule: FillRule| {
+        let outlines: Vec<Vec<_>> = outlines.iter()
+            .map(|outline| outline.iter().map(|&point| to_screen(point)).collect())
+            .collect();
+        let outlines: Vec<_> = outlines.iter().map(Vec::as_slice).collect();
+        pixmap.fill_outlines(&outlines, color, fill_rule, BlendMode::Alpha);
+    };
+
+    for prim in prims {
+        match prim.as_ref() {
+            &DrawPrim::Line(Line {start, end, thickness, color, ref dash, blend_mode}) => {
+                stroke(&mut pixmap, dashed(&[start, end], dash.as_ref()), thickness, color, blend_mode);
+            },
+
+            DrawPrim::Polygon(Polygon {points, fill_color, fill_rule}) => {
+                if points.len() < 3 {
+                    continue;
+                }
+
+                let points: Vec<_> = points.iter().map(|&point| to_screen(point)).collect();
+                pixmap.fill_outlines(&[&points], *fill_color, *fill_rule, BlendMode::Alpha);
+            },
+
+            &DrawPrim::Circle(Circle {center, radius, thickness, color, fill_color}) => {
+                let flatten = |radius: f64| -> Vec<_> {
+                    Circle::flatten(center, radius, CIRCLE_TOLERANCE / scale).into_iter()
+                        .map(to_screen)
+                        .collect()
+                };
+
+                pixmap.fill(&flatten(radius), fill_color);
+
+                if thickness > 0.0 {
+                    // The outline is the area between two circles, so the inner circle goes around
+                    // the other way to cancel out the inside of the outer circle
+                    let mut outline = flatten(radius + thickness / 2.0);
+                    let inner_radius = (radius - thickness / 2.0).max(0.0);
+                    outline.extend(flatten(inner_radius).into_iter().rev());
+                    pixmap.fill(&outline, color);
+                }
+            },
+
+            DrawPrim::Curve(curve) => {
+                // Each segment has round ends, so the segments join up without any gaps
+                let points = curve.flatten(CIRCLE_TOLERANCE / scale);
+                stroke(&mut pixmap, dashed(&points, curve.dash.as_ref()), curve.thickness, curve.color, curve.blend_mode);
+            },
+
+            DrawPrim::Text(Text {outlines, color}) => fill(&mut pixmap, outlines, *color, FillRule::NonZero),
+
+            DrawPrim::Path(Path {subpaths, fill_color, fill_rule}) => fill(&mut pixmap, subpaths, *fill_color, *fill_rule),
+
+            DrawPrim::Image(image) => {
+                pixmap.draw_image(image.corners().map(to_screen), &image.data);
+            },
+        }
+    }
+
+    Some(pixmap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::colors::{BLACK, WHITE, TRANSPARENT};
+    use crate::renderer_server::state::Pen;
+
+    fn pixel(pixmap: &Pixmap, x: u32, y: u32) -> [u8; 4] {
+        pixmap.pixels[(y * pixmap.width + x) as usize]
+    }
+
+    #[test]
+    fn fills_polygons_with_anti_aliased_edges() {
+        let mut display_list = DisplayList::default();
+        // A square covering the left half of a 10x10 image, with its right edge halfway through
+        // a column of pixels
+        display_list.push_polygon(vec![
+            Point {x: -5.0, y: 5.0},
+            Point {x: -0.5, y: 5.0},
+            Point {x: -0.5, y: -5.0},
+            Point {x: -5.0, y: -5.0},
+        ], BLACK);
+
+        let area = Rect::from_center(Point::origin(), 10.0, 10.0);
+        let pixmap = rasterize(&display_list, area, 1.0, WHITE, None, None).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (10, 10));
+
+        assert_eq!(pixel(&pixmap, 0, 5), [0, 0, 0, 255]);
+        assert_eq!(pixel(&pixmap, 9, 5), [255, 255, 255, 255]);
+        let [r, _, _, a] = pixel(&pixmap, 4, 5);
+        assert!((120..=135).contains(&r), "{}", r);
+        assert_eq!(a, 255);
+    }
+
+    #[test]
+    fn draws_circles_with_hollow_outlines() {
+        let mut display_list = DisplayList::default();
+        let pen = Pen {is_enabled: true, thickness: 2.0, color: BLACK, dash_pattern: Vec::new(), blend_mode: BlendMode::Alpha};
+        display_list.push_circle(Point::origin(), 8.0, &pen, TRANSPARENT);
+
+        let area = Rect::from_center(Point::origin(), 20.0, 20.0);
+        let pixmap = rasterize(&display_list, area, 1.0, WHITE, None, None).unwrap();
+
+        // The center of the image is inside the circle, which is not filled
+        assert_eq!(pixel(&pixmap, 10, 10), [255, 255, 255, 255]);
+        // The outline is between a radius of 7 and 9
+        assert_eq!(pixel(&pixmap, 2, 10), [0, 0, 0, 255]);
+        assert_eq!(pixel(&pixmap, 10, 17), [0, 0, 0, 255]);
+        // The corners are outside of the circle
+        assert_eq!(pixel(&pixmap, 0, 0), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn draws_rotated_images() {
+        use std::sync::Arc;
+
+        use crate::radians::Radians;
+        use crate::renderer_server::app::App;
+        use super::super::display_list::Image;
+
+        // A 2x1 image with a red pixel on the left and a transparent pixel on the right
+        let data = ImageData {
+            width: 2,
+            height: 1,
+            pixels: vec![255, 0, 0, 255, 0, 0, 0, 0],
+        };
+        let id = App::default().add_image(data.clone());
+
+        // Facing right, so the left edge of the image is at the top
+        let mut display_list = DisplayList::default();
+        let heading = Radians::from_degrees_value(0.0);
+        display_list.push_image(Image::placed(id, Arc::new(data), Point::origin(), heading, 4.0));
+
+        let area = Rect::from_center(Point::origin(), 8.0, 8.0);
+        let pixmap = rasterize(&display_list, area, 1.0, WHITE, None, None).unwrap();
+
+        assert_eq!(pixel(&pixmap, 4, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(&pixmap, 4, 6), [255, 255, 255, 255]);
+        // The sides of the rotated image are only 4 pixels wide
+        assert_eq!(pixel(&pixmap, 0, 1), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn draws_background_images() {
+        // A 2x1 image with a red pixel on the left and a blue pixel on the right
+        let data = ImageData {
+            width: 2,
+            height: 1,
+            pixels: vec![255, 0, 0, 255, 0, 0, 255, 255],
+        };
+        let background = |mode, area, scale| {
+            rasterize(&DisplayList::default(), area, scale, WHITE, Some((&data, mode)), None).unwrap()
+        };
+        let (red, blue, white) = ([255, 0, 0, 255], [0, 0, 255, 255], [255, 255, 255, 255]);
+
+        let pixmap = background(BackgroundMode::Stretch, Rect::from_center(Point::origin(), 10.0, 4.0), 1.0);
+        assert_eq!(pixel(&pixmap, 4, 0), red);
+        assert_eq!(pixel(&pixmap, 5, 3), blue);
+
+        let pixmap = background(BackgroundMode::Tile, Rect::from_center(Point::origin(), 5.0, 2.0), 1.0);
+        assert_eq!(pixel(&pixmap, 0, 0), red);
+        assert_eq!(pixel(&pixmap, 1, 0), blue);
+        assert_eq!(pixel(&pixmap, 3, 1), blue);
+        assert_eq!(pixel(&pixmap, 4, 1), red);
+
+        // Each pixel of the image covers 2x2 pixels in the middle of the exported area
+        let pixmap = background(BackgroundMode::Center, Rect::from_center(Point::origin(), 3.0, 3.0), 2.0);
+        assert_eq!(pixel(&pixmap, 1, 2), red);
+        assert_eq!(pixel(&pixmap, 4, 3), blue);
+        assert_eq!(pixel(&pixmap, 0, 0), white);
+        assert_eq!(pixel(&pixmap, 5, 4), white);
+    }
+
+    #[test]
+    fn blends_lines_with_pen_blend_mode() {
+        let pen = |color, blend_mode| Pen {is_enabled: true, thickness: 4.0, color, dash_pattern: Vec::new(), blend_mode};
+        let area = Rect::from_center(Point::origin(), 20.0, 20.0);
+        let (start, end) = (Point {x: -10.0, y: 0.0}, Point {x: 10.0, y: 0.0});
+        let draw = |first: &Pen, second: &Pen, background| {
+            let mut display_list = DisplayList::default();
+            display_list.push_line(start, end, first);
+            display_list.push_line(start, end, second);
+            rasterize(&display_list, area, 1.0, background, None, None).unwrap()
+        };
+
+        let red = Color::rgb(200.0, 0.0, 0.0);
+        let green = Color::rgb(100.0, 100.0, 0.0);
+        let pixmap = draw(&pen(red, BlendMode::Alpha), &pen(green, BlendMode::Additive), BLACK);
+        assert_eq!(pixel(&pixmap, 10, 10), [255, 100, 0, 255]);
+
+        let grey = Color::rgb(128.0, 128.0, 128.0);
+        let pixmap = draw(&pen(WHITE, BlendMode::Alpha), &pen(grey, BlendMode::Multiply), WHITE);
+        assert_eq!(pixel(&pixmap, 10, 10), [128, 128, 128, 255]);
+
+        // Erasing uncovers the background, even where a line was drawn over it
+        let pixmap = draw(&pen(BLACK, BlendMode::Alpha), &pen(BLACK, BlendMode::Erase), red);
+        assert_eq!(pixel(&pixmap, 10, 10), [200, 0, 0, 255]);
+        assert_eq!(pixel(&pixmap, 10, 0), [200, 0, 0, 255]);
+    }
+
+    #[test]
+    fn rejects_huge_images() {
+        let area = Rect::from_center(Point::origin(), 100_000.0, 100_000.0);
+        assert_eq!(rasterize(&DisplayList::default(), area, 1.0, WHITE, None, None), None);
+    }
+}
@@ -0,0 +1,176 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::convert::TryFrom;
+
+use tokio::{sync::oneshot, task::JoinHandle};
+use parking_lot::Mutex;
+
+use crate::ipc_protocol::{ServerSender, ServerClientId, ServerResponse};
+use crate::time;
+use crate::Rect;
+
+use super::display_list::SharedDisplayList;
+use super::export::ExportError;
+use super::gif::GifEncoder;
+use super::raster::{self, Pixmap};
+use super::super::{handle_handler_result, app::SharedApp, handlers::HandlerError};
+
+/// The largest delay that a single GIF frame can have (in hundredths of a second)
+const MAX_FRAME_DELAY: u16 = u16::MAX;
+
+/// The highest frame rate that can be recorded
+///
+/// Many GIF viewers slow down frames shorter than 2 hundredths of a second, so faster frame rates
+/// would not play back at the right speed.
+pub const MAX_RECORDING_FPS: f64 = 50.0;
+
+/// Returns true if a GIF can be recorded at the given number of frames per second
+pub fn is_valid_recording_fps(fps: f64) -> bool {
+    fps.is_finite() && fps > 0.0 && fps <= MAX_RECORDING_FPS
+}
+
+/// A recording of the drawing into an animated GIF that is in progress
+#[derive(Debug)]
+struct Recording {
+    /// Stops the recording when sent (or dropped)
+    stop: oneshot::Sender<()>,
+    /// The task capturing frames, which finishes writing the file once the recording stops
+    task: JoinHandle<Result<(), ExportError>>,
+}
+
+/// Captures frames of the drawing in the background and encodes them into an animated GIF
+///
+/// Only one recording can be in progress at a time.
+#[derive(Debug)]
+pub struct GifRecorder {
+    conn: ServerSender,
+    app: SharedApp,
+    display_list: SharedDisplayList,
+    recording: Mutex<Option<Recording>>,
+}
+
+impl GifRecorder {
+    pub fn new(conn: ServerSender, app: SharedApp, display_list: SharedDisplayList) -> Self {
+        Self {conn, app, display_list, recording: Mutex::default()}
+    }
+
+    /// Starts recording the area visible in the window to the GIF file at the given path
+    ///
+    /// The size of the GIF is the size of the window when the recording starts.
+    pub fn start(&self, path: &Path, fps: f64) -> Result<(), ExportError> {
+        let mut recording = self.recording.lock();
+        if recording.is_some() {
+            return Err(ExportError("a GIF is already being recorded".to_string()));
+        }
+
+        let (width, height) = self.app.read().drawing().size();
+        let (width, height) = match (u16::try_from(width), u16::try_from(height)) {
+            (Ok(width), Ok(height)) => (width, height),
+            _ => return Err(ExportError(format!("the window is too large to record as a GIF ({}x{})", width, height))),
+        };
+
+        let file = File::create(path).map_err(|err| ExportError(err.to_string()))?;
+        let encoder = GifEncoder::new(BufWriter::new(file), width, height)
+            .map_err(|err| ExportError(err.to_string()))?;
+
+        let (stop, stop_receiver) = oneshot::channel();
+        let task = tokio::spawn(record(
+            self.app.clone(),
+            self.display_list.clone(),
+            encoder,
+            fps,
+            stop_receiver,
+        ));
+        *recording = Some(Recording {stop, task});
+
+        Ok(())
+    }
+
+    /// Stops the current recording and responds to the given client once the GIF has been written
+    ///
+    /// The response is an error if nothing is being recorded.
+    pub fn stop(&self, client_id: ServerClientId) {
+        let recording = self.recording.lock().take();
+        let conn = self.conn.clone();
+
+        // Encoding the last frames may take a while, so requests from other clients continue to
+        // be handled in the meantime
+        tokio::spawn(async move {
+            let res = match recording {
+                Some(Recording {stop, task}) => {
+                    // Ignoring the error since it just means that the task has already stopped
+                    stop.send(()).unwrap_or(());
+                    task.await.unwrap_or_else(|err| {
+                        Err(ExportError(format!("GIF recording stopped unexpectedly: {}", err)))
+                    })
+                },
+                None => Err(ExportError("no GIF is being recorded".to_string())),
+            };
+
+            handle_handler_result(conn.send(client_id, ServerResponse::ExportComplete(res))
+                .map_err(HandlerError::IpcChannelError));
+        });
+    }
+}
+
+/// Captures a frame of the drawing at the given rate until told to stop, then finishes the GIF
+async fn record(
+    app: SharedApp,
+    display_list: SharedDisplayList,
+    mut encoder: GifEncoder<BufWriter<File>>,
+    fps: f64,
+    mut stop: oneshot::Receiver<()>,
+) -> Result<(), ExportError> {
+    // GIF frame delays are in hundredths of a second
+    let delay = (100.0 / fps).round().clamp(1.0, MAX_FRAME_DELAY as f64) as u16;
+    let mut interval = time::interval(time::Duration::from_secs_f64(1.0 / fps));
+
+    // Each frame is only written once the next frame is different, so that frames where nothing
+    // changed can be merged into a single frame with a longer delay
+    let mut pending: Option<(Pixmap, u16)> = None;
+    loop {
+        tokio::select! {
+            // Stopping also happens if the recorder is dropped because the server is shutting down
+            _ = &mut stop => break,
+
+            _ = interval.tick() => {
+                let frame = capture(&app, &display_list, encoder.width(), encoder.height())?;
+
+                pending = match pending.take() {
+                    Some((previous, previous_delay)) if previous == frame && previous_delay < MAX_FRAME_DELAY - delay => {
+                        Some((previous, previous_delay + delay))
+                    },
+                    Some((previous, previous_delay)) => {
+                        encoder.add_frame(&previous, previous_delay).map_err(|err| ExportError(err.to_string()))?;
+                        Some((frame, delay))
+                    },
+                    None => Some((frame, delay)),
+                };
+            },
+        }
+    }
+
+    if let Some((frame, delay)) = pending {
+        encoder.add_frame(&frame, delay).map_err(|err| ExportError(err.to_string()))?;
+    }
+    encoder.finish().map_err(|err| ExportError(err.to_string()))?;
+
+    Ok(())
+}
+
+/// Renders the area of the drawing visible in the window at the given size
+fn capture(
+    app: &SharedApp,
+    display_list: &SharedDisplayList,
+    width: u16,
+    height: u16,
+) -> Result<Pixmap, ExportError> {
+    let app = app.read();
+    let drawing = app.drawing();
+    let area = Rect::from_center(drawing.center, width as f64, height as f64);
+
+    let display_list = display_list.lock();
+    raster::rasterize(&display_list, area, 1.0, drawing.background, app.background_image(), drawing.symmetry)
+        .ok_or_else(|| ExportError("the window is too large to record".to_string()))
+}
@@ -0,0 +1,200 @@
+use std::borrow::Cow;
+use std::f64::consts::PI;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{Point, Rect};
+
+use super::display_list::{DisplayList, DrawPrim, Line, Polygon, Circle, Curve, Text, Path, Image};
+
+/// Replicates everything in the drawing around the origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Symmetry {
+    /// The number of rotated copies of the drawing (including the drawing itself)
+    ///
+    /// Guaranteed to be at least 1.
+    pub axes: u32,
+    /// If true, each of the rotated copies is also reflected across the y-axis
+    pub mirror: bool,
+}
+
+impl Symmetry {
+    /// Returns the number of copies of each primitive, including the primitive itself
+    fn copies(self) -> u32 {
+        if self.mirror { self.axes * 2 } else { self.axes }
+    }
+
+    /// Returns the position of the given point in the copy with the given index
+    ///
+    /// The copy with index 0 is the original drawing.
+    fn transform(self, index: u32, point: Point) -> Point {
+        let Point {x, y} = point;
+        let x = if index >= self.axes { -x } else { x };
+
+        let angle = 2.0 * PI * (index % self.axes) as f64 / self.axes as f64;
+        let (sin, cos) = angle.sin_cos();
+        Point {
+            x: cos * x - sin * y,
+            y: sin * x + cos * y,
+        }
+    }
+
+    /// Returns the given primitive as it appears in the copy with the given index
+    fn transform_prim(self, index: u32, prim: &DrawPrim) -> DrawPrim {
+        match *prim {
+            DrawPrim::Line(Line {start, end, thickness, color, ref dash, blend_mode}) => DrawPrim::Line(Line {
+                start: self.transform(index, start),
+                end: self.transform(index, end),
+                thickness,
+                color,
+                dash: dash.clone(),
+                blend_mode,
+            }),
+
+            DrawPrim::Polygon(Polygon {ref points, fill_color, fill_rule}) => DrawPrim::Polygon(Polygon {
+                points: points.iter().map(|&point| self.transform(index, point)).collect(),
+                fill_color,
+                fill_rule,
+            }),
+
+            DrawPrim::Path(Path {ref subpaths, fill_color, fill_rule}) => DrawPrim::Path(Path {
+                subpaths: subpaths.iter()
+                    .map(|subpath| subpath.iter().map(|&point| self.transform(index, point)).collect())
+                    .collect(),
+                fill_color,
+                fill_rule,
+            }),
+
+            DrawPrim::Circle(Circle {center, radius, thickness, color, fill_color}) => DrawPrim::Circle(Circle {
+                center: self.transform(index, center),
+                radius,
+                thickness,
+                color,
+                fill_color,
+            }),
+
+            DrawPrim::Curve(Curve {start, control1, control2, end, thickness, color, ref dash, blend_mode}) => DrawPrim::Curve(Curve {
+                start: self.transform(index, start),
+                control1: self.transform(index, control1),
+                control2: self.transform(index, control2),
+                end: self.transform(index, end),
+                thickness,
+                color,
+                dash: dash.clone(),
+                blend_mode,
+            }),
+
+            DrawPrim::Text(Text {ref outlines, color}) => DrawPrim::Text(Text {
+                outlines: outlines.iter()
+                    .map(|outline| outline.iter().map(|&point| self.transform(index, point)).collect())
+                    .collect(),
+                color,
+            }),
+
+            // The transform has no translation, so it also applies to the sides of the image
+            DrawPrim::Image(Image {id, ref data, center, right, up}) => DrawPrim::Image(Image {
+                id,
+                data: data.clone(),
+                center: self.transform(index, center),
+                right: self.transform(index, right),
+                up: self.transform(index, up),
+            }),
+        }
+    }
+
+    /// Iterates over every copy of the given primitive, starting with the primitive itself
+    fn prim_copies(self, prim: &DrawPrim) -> impl Iterator<Item=Cow<'_, DrawPrim>> {
+        (0..self.copies()).map(move |index| match index {
+            0 => Cow::Borrowed(prim),
+            _ => Cow::Owned(self.transform_prim(index, prim)),
+        })
+    }
+}
+
+/// Returns the primitives that may be visible in the given area, in the order in which they
+/// should be rendered
+///
+/// If the drawing has a symmetry, the copies of each primitive are included right after it.
+pub fn visible_prims(
+    display_list: &DisplayList,
+    area: Rect,
+    symmetry: Option<Symmetry>,
+) -> Vec<Cow<'_, DrawPrim>> {
+    match symmetry {
+        // The copies of a primitive may be visible even if the primitive itself is not, so every
+        // primitive needs to be checked
+        Some(symmetry) => display_list.iter()
+            .flat_map(|prim| symmetry.prim_copies(prim))
+            .filter(|prim| prim.bounds().intersects(area))
+            .collect(),
+
+        None => display_list.iter_visible(area).map(Cow::Borrowed).collect(),
+    }
+}
+
+/// Computes the smallest rectangle that contains every visible primitive in the display list,
+/// including the copies made by the symmetry of the drawing (if any)
+///
+/// Returns `None` if there is nothing to draw.
+pub fn bounds(display_list: &DisplayList, symmetry: Option<Symmetry>) -> Option<Rect> {
+    let bounds = display_list.bounds()?;
+    let symmetry = match symmetry {
+        Some(symmetry) => symmetry,
+        None => return Some(bounds),
+    };
+
+    // Each copy fits inside the same copy of the original bounds
+    let Rect {min, max} = bounds;
+    let corners = [min, Point {x: max.x, y: min.y}, max, Point {x: min.x, y: max.y}];
+    let points = (0..symmetry.copies())
+        .flat_map(|index| corners.iter().map(move |&corner| symmetry.transform(index, corner)));
+
+    Rect::bounding(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::colors::BLACK;
+
+    fn assert_close(actual: Point, expected: Point) {
+        assert!((actual - expected).len() < 1e-9, "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn rotations_and_reflections() {
+        let symmetry = Symmetry {axes: 4, mirror: true};
+        let point = Point {x: 1.0, y: 2.0};
+
+        assert_eq!(symmetry.copies(), 8);
+        assert_close(symmetry.transform(0, point), point);
+        assert_close(symmetry.transform(1, point), Point {x: -2.0, y: 1.0});
+        assert_close(symmetry.transform(2, point), Point {x: -1.0, y: -2.0});
+        assert_close(symmetry.transform(4, point), Point {x: -1.0, y: 2.0});
+        assert_close(symmetry.transform(5, point), Point {x: -2.0, y: -1.0});
+    }
+
+    #[test]
+    fn copies_every_primitive() {
+        let mut display_list = DisplayList::default();
+        display_list.push_polygon(vec![
+            Point {x: 10.0, y: 10.0},
+            Point {x: 20.0, y: 10.0},
+            Point {x: 20.0, y: 20.0},
+        ], BLACK);
+
+        let everywhere = Rect::from_center(Point::origin(), 100.0, 100.0);
+        let symmetry = Some(Symmetry {axes: 4, mirror: false});
+        assert_eq!(visible_prims(&display_list, everywhere, None).len(), 1);
+        assert_eq!(visible_prims(&display_list, everywhere, symmetry).len(), 4);
+
+        // Only the copy rotated by 180 degrees is in this area
+        let corner = Rect::from_points(Point {x: -50.0, y: -50.0}, Point {x: 0.0, y: 0.0});
+        assert_eq!(visible_prims(&display_list, corner, symmetry).len(), 1);
+
+        let bounds = bounds(&display_list, Some(Symmetry {axes: 2, mirror: false})).unwrap();
+        assert_close(bounds.min, Point {x: -20.0, y: -20.0});
+        assert_close(bounds.max, Point {x: 20.0, y: 20.0});
+    }
+}
@@ -0,0 +1,794 @@
+use std::env;
+use std::fs;
+use std::sync::Arc;
+use std::ops::Range;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rustybuzz::{Face, UnicodeBuffer, Direction};
+use ttf_parser::{GlyphId, RasterImageFormat, Tag};
+use unicode_bidi::BidiInfo;
+use thiserror::Error;
+use serde::{Serialize, Deserialize};
+
+use crate::{Point, Size, Color};
+
+use super::color_glyph;
+use super::image::ImageData;
+
+/// The font files that are tried (in order) when looking for each kind of system font
+const SANS_SERIF_FILES: &[&str] = &[
+    "DejaVuSans.ttf", "LiberationSans-Regular.ttf", "NotoSans-Regular.ttf", "FreeSans.ttf",
+    "Arial.ttf", "arial.ttf", "Helvetica.ttc", "segoeui.ttf",
+];
+const SERIF_FILES: &[&str] = &[
+    "DejaVuSerif.ttf", "LiberationSerif-Regular.ttf", "NotoSerif-Regular.ttf", "FreeSerif.ttf",
+    "Times New Roman.ttf", "times.ttf", "Times.ttc",
+];
+const MONOSPACE_FILES: &[&str] = &[
+    "DejaVuSansMono.ttf", "LiberationMono-Regular.ttf", "NotoSansMono-Regular.ttf", "FreeMono.ttf",
+    "Courier New.ttf", "cour.ttf", "Menlo.ttc", "consola.ttf",
+];
+/// The emoji font files that are tried (in order) for characters that are missing from the font
+/// used to write the text
+const EMOJI_FILES: &[&str] = &[
+    "NotoColorEmoji.ttf", "Apple Color Emoji.ttc", "seguiemj.ttf", "TwemojiMozilla.ttf",
+    "JoyPixels.ttf", "NotoEmoji-Regular.ttf",
+];
+
+/// The maximum number of nested directories searched in each font directory
+const MAX_SEARCH_DEPTH: usize = 4;
+
+/// The largest distance (in pixels) between the curves in a character and the lines used to draw
+/// them
+///
+/// This is small enough that the text still looks smooth when the drawing is zoomed in.
+const OUTLINE_TOLERANCE: f64 = 0.02;
+
+/// The largest number of line segments used for a single curve in a character
+const MAX_OUTLINE_SEGMENTS: f64 = 64.0;
+
+/// The contents of every font file that has been loaded so far
+static FONTS: Lazy<Mutex<HashMap<Font, Arc<Vec<u8>>>>> = Lazy::new(Default::default);
+
+/// The contents of the emoji font file, or `None` if no emoji font could be loaded
+static EMOJI_FONT: Lazy<Option<Arc<Vec<u8>>>> = Lazy::new(|| {
+    let data = fs::read(find_system_font(EMOJI_FILES)?).ok()?;
+    Face::from_slice(&data, 0)?;
+    Some(Arc::new(data))
+});
+
+/// An error produced while loading a font
+#[derive(Debug, Error, Serialize, Deserialize)]
+#[error("{0}")]
+pub struct FontError(String);
+
+impl From<crate::Error> for FontError {
+    fn from(err: crate::Error) -> Self {
+        FontError(err.to_string())
+    }
+}
+
+/// The font used to draw or measure text
+///
+/// The generic font families are looked up in the fonts installed on the system. Characters that
+/// are missing from the font (like most emoji) are written using an emoji font installed on the
+/// system if it has them, and using the font's "missing glyph" character otherwise. Color fonts
+/// are drawn in color, with the parts of each character that the font leaves uncolored drawn in
+/// the color of the pen.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Font {
+    /// A font without serifs, like DejaVu Sans or Arial (default)
+    #[default]
+    SansSerif,
+    /// A font with serifs, like DejaVu Serif or Times New Roman
+    Serif,
+    /// A font where every character has the same width, like DejaVu Sans Mono or Courier New
+    Monospace,
+    /// The TrueType or OpenType font file at the given path
+    File(PathBuf),
+}
+
+/// Controls how text is measured
+///
+/// ```rust
+/// use turtle::{TextOptions, Font};
+///
+/// let options = TextOptions::default();
+/// assert_eq!(options.font_size, 16.0);
+/// assert_eq!(options.font, Font::SansSerif);
+///
+/// let heading = TextOptions {
+///     font_size: 32.0,
+///     font: Font::Serif,
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextOptions {
+    /// The size of the font in pixels, must be greater than zero
+    ///
+    /// This is the size of the "em square" of the font, so some characters may be slightly larger.
+    pub font_size: f64,
+    /// The font that the text is written in
+    pub font: Font,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            font_size: 16.0,
+            font: Font::default(),
+        }
+    }
+}
+
+impl TextOptions {
+    /// Returns true if the font size is positive and finite
+    pub fn is_valid(&self) -> bool {
+        self.font_size.is_finite() && self.font_size > 0.0
+    }
+}
+
+/// The vertical measurements of a font at a particular size, in pixels
+///
+/// See [`Drawing::font_metrics()`](struct.Drawing.html#method.font_metrics) for more information.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FontMetrics {
+    /// The distance from the baseline to the top of the tallest characters
+    pub ascent: f64,
+    /// The distance from the baseline to the bottom of the lowest characters
+    ///
+    /// This is usually negative since most characters that go below the baseline (like "g" or "y")
+    /// only go a little below it.
+    pub descent: f64,
+    /// The extra space that should be left between the descent of one line and the ascent of the
+    /// next
+    pub line_gap: f64,
+}
+
+/// Where text is placed relative to the turtle when it is written
+///
+/// See [`Turtle::write_with()`](struct.Turtle.html#method.write_with) for more information.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TextAlign {
+    /// Each line of text starts at the turtle (default)
+    #[default]
+    Left,
+    /// Each line of text is centered on the turtle
+    Center,
+    /// Each line of text ends at the turtle
+    Right,
+}
+
+impl FontMetrics {
+    /// Returns the distance between the baselines of two consecutive lines of text
+    pub fn line_height(self) -> f64 {
+        self.ascent - self.descent + self.line_gap
+    }
+}
+
+/// Returns the vertical measurements of the font described by the given options
+pub fn font_metrics(options: &TextOptions) -> Result<FontMetrics, FontError> {
+    let data = load_font(&options.font)?;
+    let face = parse_font(&data);
+
+    Ok(metrics(&face, options.font_size))
+}
+
+/// Measures the size of the box that the given text would take up, rounded up to the nearest
+/// pixel
+///
+/// Each line of the text (separated by `\n`) is placed below the previous one.
+pub fn measure_text(text: &str, options: &TextOptions) -> Result<Size, FontError> {
+    let fonts = load_fonts(&options.font)?;
+    let faces: Vec<_> = fonts.iter().map(|(_, data)| parse_font(data)).collect();
+    let metrics = metrics(&faces[0], options.font_size);
+
+    let width = text.lines()
+        .map(|line| shape_line(&faces, options.font_size, line).1)
+        .fold(0.0, f64::max);
+    let lines = text.lines().count().max(1);
+    let height = (metrics.ascent - metrics.descent) + (lines - 1) as f64 * metrics.line_height();
+
+    Ok(Size {
+        width: width.ceil() as u32,
+        height: height.ceil() as u32,
+    })
+}
+
+/// A part of some laid out text
+///
+/// The parts of the text should be drawn in the order they were returned, since the layers of
+/// color characters are drawn on top of each other.
+#[derive(Debug)]
+pub enum TextPart {
+    /// The outlines of one or more characters (or layers of characters), each a closed loop of
+    /// points in logical coordinates
+    Outlines {
+        outlines: Vec<Vec<Point>>,
+        /// The color to fill the outlines with, or `None` to use the color of the pen
+        color: Option<Color>,
+    },
+    /// A character drawn using an image stored in the font, like most color emoji
+    Bitmap {
+        /// Identifies this image so that it only needs to be stored once
+        key: GlyphImageKey,
+        image: ImageData,
+        /// The position of the center of the image, in logical coordinates
+        center: Point,
+        /// The width of each pixel of the image, in logical pixels
+        scale: f64,
+    },
+}
+
+/// Identifies the image of a character in a font
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GlyphImageKey {
+    /// The font the image is from, or `None` for the emoji font
+    font: Option<Font>,
+    glyph: u16,
+    pixels_per_em: u16,
+}
+
+/// Lays out the given text, with the baseline of the first line passing through `origin`
+///
+/// Each line of the text (separated by `\n`) is placed below the previous one and aligned with
+/// `origin` according to `align`. Characters without an outline or image (like spaces) do not
+/// produce any parts. The outlines of characters that are drawn in the color of the pen are
+/// collected into as few parts as possible.
+pub fn layout_text(
+    text: &str,
+    options: &TextOptions,
+    align: TextAlign,
+    origin: Point,
+) -> Result<Vec<TextPart>, FontError> {
+    let fonts = load_fonts(&options.font)?;
+    let faces: Vec<_> = fonts.iter().map(|(_, data)| parse_font(data)).collect();
+    let metrics = metrics(&faces[0], options.font_size);
+
+    let mut parts = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let (glyphs, width) = shape_line(&faces, options.font_size, line);
+        let start = match align {
+            TextAlign::Left => origin.x,
+            TextAlign::Center => origin.x - width / 2.0,
+            TextAlign::Right => origin.x - width,
+        };
+        let baseline = origin.y - i as f64 * metrics.line_height();
+
+        for glyph in glyphs {
+            let (font, face) = (&fonts[glyph.font].0, &faces[glyph.font]);
+            let position = Point {x: start + glyph.x, y: baseline + glyph.y};
+            draw_glyph(&mut parts, font, face, options.font_size, glyph.id, position);
+        }
+    }
+
+    Ok(parts)
+}
+
+/// Adds the parts needed to draw the given glyph with its origin at `position`
+///
+/// `font` is the font that the face was loaded from, or `None` for the emoji font.
+fn draw_glyph(
+    parts: &mut Vec<TextPart>,
+    font: &Option<Font>,
+    face: &Face<'_>,
+    font_size: f64,
+    glyph: GlyphId,
+    position: Point,
+) {
+    let scale = font_size / face.units_per_em() as f64;
+    let outline = |glyph| {
+        let mut outliner = Outliner {scale, origin: position, outlines: Vec::new()};
+        face.outline_glyph(glyph, &mut outliner);
+        outliner.outlines
+    };
+
+    let colr = face.table_data(Tag::from_bytes(b"COLR"));
+    let cpal = face.table_data(Tag::from_bytes(b"CPAL"));
+    if let Some(layers) = colr.and_then(|colr| color_glyph::color_layers(colr, cpal, glyph)) {
+        for layer in layers {
+            push_outlines(parts, outline(layer.glyph), layer.color);
+        }
+        return;
+    }
+
+    // Use an image with at least one pixel for each pixel of the text, if the font has one
+    let pixels_per_em = font_size.ceil().clamp(1.0, u16::MAX as f64) as u16;
+    if let Some(raster) = face.glyph_raster_image(glyph, pixels_per_em) {
+        let image = match raster.format {
+            RasterImageFormat::PNG => ImageData::decode_png(raster.data).ok(),
+        };
+        if let Some(image) = image {
+            // The offset of the image is measured in the pixels of the image
+            let pixel_size = font_size / raster.pixels_per_em as f64;
+            let bottom_left = position + Point {x: raster.x as f64, y: raster.y as f64} * pixel_size;
+            let size = Point {x: image.width as f64, y: image.height as f64} * pixel_size;
+
+            let GlyphId(id) = glyph;
+            let key = GlyphImageKey {font: font.clone(), glyph: id, pixels_per_em: raster.pixels_per_em};
+            let center = bottom_left + size / 2.0;
+            parts.push(TextPart::Bitmap {key, image, center, scale: pixel_size});
+            return;
+        }
+    }
+
+    push_outlines(parts, outline(glyph), None);
+}
+
+/// Adds outlines to the last part if it is filled with the same color, or as a new part otherwise
+fn push_outlines(parts: &mut Vec<TextPart>, mut outlines: Vec<Vec<Point>>, color: Option<Color>) {
+    // Some fonts include empty or degenerate contours, which would never be drawn anyway
+    outlines.retain(|outline| outline.len() >= 3);
+    if outlines.is_empty() {
+        return;
+    }
+
+    match parts.last_mut() {
+        Some(TextPart::Outlines {outlines: last, color: last_color}) if *last_color == color => {
+            last.extend(outlines);
+        },
+        _ => parts.push(TextPart::Outlines {outlines, color}),
+    }
+}
+
+/// A glyph placed by shaping a line of text
+#[derive(Debug, Clone, Copy)]
+struct PlacedGlyph {
+    /// The index of the font that the glyph is from
+    font: usize,
+    id: GlyphId,
+    /// The horizontal position of the glyph (in pixels) from the start of the line
+    x: f64,
+    /// The vertical position of the glyph (in pixels) from the baseline, with the y-axis pointing
+    /// up
+    y: f64,
+}
+
+/// Shapes a single line of text, returning its glyphs from left to right along with the distance
+/// from the start of the line to the end of its last glyph
+///
+/// Shaping picks the form of each character that fits with the characters around it (e.g. in
+/// Arabic or Devanagari), positions combining marks, and applies kerning. The parts of the line
+/// that are written right-to-left (e.g. Arabic or Hebrew) are put in order using the Unicode
+/// bidirectional algorithm.
+///
+/// Each character is shaped using the first of the given fonts that has it (see `font_runs()`).
+fn shape_line(faces: &[Face<'_>], font_size: f64, line: &str) -> (Vec<PlacedGlyph>, f64) {
+    let mut glyphs = Vec::new();
+    let mut x = 0.0;
+    let bidi = BidiInfo::new(line, None);
+    for paragraph in &bidi.paragraphs {
+        let (levels, runs) = bidi.visual_runs(paragraph, paragraph.range.clone());
+        for run in runs {
+            let is_rtl = levels[run.start].is_rtl();
+            let direction = match is_rtl {
+                true => Direction::RightToLeft,
+                false => Direction::LeftToRight,
+            };
+
+            let has_glyph = |font: usize, c| faces[font].glyph_index(c).is_some();
+            let mut font_runs = font_runs(&line[run.clone()], faces.len(), has_glyph);
+            // The parts of a right-to-left run are placed from the end of the run to its start
+            if is_rtl {
+                font_runs.reverse();
+            }
+
+            for (range, font) in font_runs {
+                let face = &faces[font];
+                let scale = font_size / face.units_per_em() as f64;
+
+                let mut buffer = UnicodeBuffer::new();
+                buffer.push_str(&line[run.start + range.start..run.start + range.end]);
+                buffer.set_direction(direction);
+
+                // Right-to-left runs come out of the shaper already in order from left to right
+                let shaped = rustybuzz::shape(face, &[], buffer);
+                for (info, position) in shaped.glyph_infos().iter().zip(shaped.glyph_positions()) {
+                    glyphs.push(PlacedGlyph {
+                        font,
+                        id: GlyphId(info.glyph_id as u16),
+                        x: x + position.x_offset as f64 * scale,
+                        y: position.y_offset as f64 * scale,
+                    });
+                    x += position.x_advance as f64 * scale;
+                }
+            }
+        }
+    }
+
+    (glyphs, x)
+}
+
+/// Splits text into the parts that should be shaped with each font, returning the byte range of
+/// each part along with the index of its font
+///
+/// Each character uses the first font that has it, or the first font if none of them do.
+/// Characters that modify the character before them (like combining marks, variation selectors,
+/// and the parts of emoji sequences) stay with that character. A character followed by the emoji
+/// variation selector (U+FE0F) prefers the last font, which is the emoji font if one is loaded.
+fn font_runs(text: &str, fonts: usize, has_glyph: impl Fn(usize, char) -> bool) -> Vec<(Range<usize>, usize)> {
+    let mut runs: Vec<(Range<usize>, usize)> = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    let mut prev = None;
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, next)| next);
+        let end = i + c.len_utf8();
+
+        let continues_prev = is_modifier(c) || prev == Some(ZERO_WIDTH_JOINER);
+        let font = match runs.last() {
+            Some(&(_, font)) if continues_prev => font,
+            _ if next == Some(EMOJI_PRESENTATION) && has_glyph(fonts - 1, c) => fonts - 1,
+            _ => (0..fonts).find(|&font| has_glyph(font, c)).unwrap_or(0),
+        };
+
+        match runs.last_mut() {
+            Some((range, last_font)) if *last_font == font => range.end = end,
+            _ => runs.push((i..end, font)),
+        }
+        prev = Some(c);
+    }
+
+    runs
+}
+
+/// Joins two emoji into a single emoji (e.g. in family emoji)
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+/// Asks for a character to be displayed as a color emoji
+const EMOJI_PRESENTATION: char = '\u{FE0F}';
+
+/// Returns true if the given character modifies the character before it
+fn is_modifier(c: char) -> bool {
+    matches!(c,
+        // Combining diacritical marks
+        '\u{0300}'..='\u{036F}' |
+        // Zero width non-joiner and joiner
+        '\u{200C}'..='\u{200D}' |
+        // Combining marks for symbols (including the keycap in emoji like 1\u{FE0F}\u{20E3})
+        '\u{20D0}'..='\u{20FF}' |
+        // Variation selectors
+        '\u{FE00}'..='\u{FE0F}' |
+        // Emoji skin tone modifiers
+        '\u{1F3FB}'..='\u{1F3FF}' |
+        // Tags (used in subdivision flags)
+        '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+/// Returns the vertical measurements of the given font at the given size
+fn metrics(face: &Face<'_>, font_size: f64) -> FontMetrics {
+    let scale = font_size / face.units_per_em() as f64;
+
+    FontMetrics {
+        ascent: face.ascender() as f64 * scale,
+        descent: face.descender() as f64 * scale,
+        line_gap: face.line_gap() as f64 * scale,
+    }
+}
+
+/// Collects the outlines of glyphs, replacing each curve with line segments
+#[derive(Debug, Default)]
+struct Outliner {
+    /// The number of pixels in each unit of the font
+    scale: f64,
+    /// The position of the glyph currently being outlined, in logical coordinates
+    origin: Point,
+    outlines: Vec<Vec<Point>>,
+}
+
+impl Outliner {
+    /// Converts a point relative to the current glyph (in font units) into logical coordinates
+    fn point(&self, x: f32, y: f32) -> Point {
+        Point {
+            x: self.origin.x + x as f64 * self.scale,
+            y: self.origin.y + y as f64 * self.scale,
+        }
+    }
+
+    /// Adds a point to the end of the current outline
+    fn push(&mut self, point: Point) {
+        match self.outlines.last_mut() {
+            Some(outline) => outline.push(point),
+            None => self.outlines.push(vec![point]),
+        }
+    }
+
+    /// Returns the last point in the current outline
+    fn current(&self) -> Point {
+        self.outlines.last().and_then(|outline| outline.last().copied()).unwrap_or(self.origin)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for Outliner {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        self.outlines.push(vec![point]);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        self.push(point);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (p0, p1, p2) = (self.current(), self.point(x1, y1), self.point(x, y));
+
+        // Wang's formula for the number of segments needed to stay within the tolerance
+        let bend = (p0 - p1 * 2.0 + p2).len();
+        let segments = (0.25 * bend / OUTLINE_TOLERANCE).sqrt().ceil().clamp(1.0, MAX_OUTLINE_SEGMENTS) as usize;
+        for i in 1..=segments {
+            let t = i as f64 / segments as f64;
+            let mt = 1.0 - t;
+            self.push(p0 * (mt * mt) + p1 * (2.0 * mt * t) + p2 * (t * t));
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (p0, p1, p2, p3) = (self.current(), self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+
+        let bend = (p0 - p1 * 2.0 + p2).len().max((p1 - p2 * 2.0 + p3).len());
+        let segments = (0.75 * bend / OUTLINE_TOLERANCE).sqrt().ceil().clamp(1.0, MAX_OUTLINE_SEGMENTS) as usize;
+        for i in 1..=segments {
+            let t = i as f64 / segments as f64;
+            let mt = 1.0 - t;
+            self.push(p0 * (mt * mt * mt) + p1 * (3.0 * mt * mt * t) + p2 * (3.0 * mt * t * t) + p3 * (t * t * t));
+        }
+    }
+
+    fn close(&mut self) {
+        // Outlines are always treated as closed, so the point that returns to the start of the
+        // outline is not needed
+        if let Some(outline) = self.outlines.last_mut() {
+            if outline.len() > 1 && outline.first() == outline.last() {
+                outline.pop();
+            }
+        }
+    }
+}
+
+/// Parses a font that was already checked to be valid by `load_font()`
+fn parse_font(data: &[u8]) -> Face<'_> {
+    Face::from_slice(data, 0).expect("bug: font should have been checked when it was loaded")
+}
+
+/// The contents of a font file, along with the font it was loaded from (`None` for the emoji font)
+type LoadedFont = (Option<Font>, Arc<Vec<u8>>);
+
+/// Loads the given font followed by the emoji font (if one can be found)
+fn load_fonts(font: &Font) -> Result<Vec<LoadedFont>, FontError> {
+    let mut fonts = vec![(Some(font.clone()), load_font(font)?)];
+    if let Some(emoji) = &*EMOJI_FONT {
+        fonts.push((None, emoji.clone()));
+    }
+    Ok(fonts)
+}
+
+/// Loads the given font, reusing it if it has already been loaded
+fn load_font(font: &Font) -> Result<Arc<Vec<u8>>, FontError> {
+    if let Some(loaded) = FONTS.lock().get(font) {
+        return Ok(loaded.clone());
+    }
+
+    let path = match font {
+        Font::SansSerif => find_system_font(SANS_SERIF_FILES),
+        Font::Serif => find_system_font(SERIF_FILES),
+        Font::Monospace => find_system_font(MONOSPACE_FILES),
+        Font::File(path) => Some(path.clone()),
+    }.ok_or_else(|| FontError(format!("unable to find a {:?} font on this system", font)))?;
+
+    let data = fs::read(&path)
+        .map_err(|err| FontError(format!("unable to read font file '{}': {}", path.display(), err)))?;
+    if Face::from_slice(&data, 0).is_none() {
+        return Err(FontError(format!("'{}' is not a valid font file", path.display())));
+    }
+
+    let loaded = Arc::new(data);
+    FONTS.lock().insert(font.clone(), loaded.clone());
+    Ok(loaded)
+}
+
+/// Searches the font directories of the system for the first of the given file names that exists
+fn find_system_font(file_names: &[&str]) -> Option<PathBuf> {
+    let dirs = font_dirs();
+    file_names.iter()
+        .find_map(|name| dirs.iter().find_map(|dir| find_file(dir, name, MAX_SEARCH_DEPTH)))
+}
+
+/// Returns the directories where fonts are usually installed
+fn font_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if cfg!(target_os = "windows") {
+        let windir = env::var_os("WINDIR").unwrap_or_else(|| "C:\\Windows".into());
+        dirs.push(Path::new(&windir).join("Fonts"));
+
+    } else if cfg!(target_os = "macos") {
+        dirs.push(PathBuf::from("/System/Library/Fonts"));
+        dirs.push(PathBuf::from("/Library/Fonts"));
+        if let Some(home) = env::var_os("HOME") {
+            dirs.push(Path::new(&home).join("Library/Fonts"));
+        }
+
+    } else {
+        if let Some(home) = env::var_os("HOME") {
+            dirs.push(Path::new(&home).join(".local/share/fonts"));
+            dirs.push(Path::new(&home).join(".fonts"));
+        }
+        dirs.push(PathBuf::from("/usr/local/share/fonts"));
+        dirs.push(PathBuf::from("/usr/share/fonts"));
+    }
+
+    dirs
+}
+
+/// Looks for a file with the given name in the given directory or any directory nested within it
+/// (up to the given depth)
+fn find_file(dir: &Path, name: &str, depth: usize) -> Option<PathBuf> {
+    let path = dir.join(name);
+    if path.is_file() {
+        return Some(path);
+    }
+
+    if depth == 0 {
+        return None;
+    }
+
+    fs::read_dir(dir).ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .find_map(|path| find_file(&path, name, depth - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lays out text that only uses the color of the pen, returning the outline of every character
+    fn outline_text(text: &str, options: &TextOptions, align: TextAlign, origin: Point) -> Result<Vec<Vec<Point>>, FontError> {
+        let parts = layout_text(text, options, align, origin)?;
+        assert!(parts.len() <= 1, "{:?}", parts);
+        Ok(parts.into_iter().flat_map(|part| match part {
+            TextPart::Outlines {outlines, color: None} => outlines,
+            part => panic!("expected text in the color of the pen, got {:?}", part),
+        }).collect())
+    }
+
+    #[test]
+    fn longer_text_is_wider() {
+        let options = TextOptions::default();
+        let short = measure_text("turtle", &options).unwrap();
+        let long = measure_text("turtle turtle", &options).unwrap();
+        assert!(long.width > short.width);
+        assert_eq!(long.height, short.height);
+
+        let two_lines = measure_text("turtle\nturtle", &options).unwrap();
+        assert_eq!(two_lines.width, short.width);
+        assert!(two_lines.height > short.height);
+
+        assert_eq!(measure_text("", &options).unwrap().width, 0);
+    }
+
+    #[test]
+    fn metrics_scale_with_font_size() {
+        let small = font_metrics(&TextOptions {font_size: 10.0, font: Font::Monospace}).unwrap();
+        let large = font_metrics(&TextOptions {font_size: 20.0, font: Font::Monospace}).unwrap();
+        assert!(small.ascent > 0.0 && small.descent < 0.0);
+        assert!((large.ascent - small.ascent * 2.0).abs() < 1e-3);
+        assert!((large.line_height() - small.line_height() * 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn outlines_follow_alignment() {
+        let options = TextOptions::default();
+        let bounds = |align| {
+            let outlines = outline_text("turtle", &options, align, Point::origin()).unwrap();
+            outlines.iter().flatten()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), p| (min.min(p.x), max.max(p.x)))
+        };
+
+        let (left_min, left_max) = bounds(TextAlign::Left);
+        assert!(left_min >= 0.0 && left_max > 0.0);
+        let (right_min, right_max) = bounds(TextAlign::Right);
+        assert!(right_min < 0.0 && right_max <= 0.0);
+        let (center_min, center_max) = bounds(TextAlign::Center);
+        assert!((center_min + center_max).abs() < 2.0, "{} {}", center_min, center_max);
+
+        assert!(outline_text("  \n ", &options, TextAlign::Left, Point::origin()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn outlines_have_holes() {
+        let options = TextOptions {font_size: 20.0, font: Font::SansSerif};
+        let origin = Point {x: 10.0, y: 10.0};
+        let outlines = outline_text("o", &options, TextAlign::Left, origin).unwrap();
+        assert_eq!(outlines.len(), 2);
+
+        // The hole goes around the other way so it is left empty when filled
+        let area = |outline: &Vec<Point>| -> f64 {
+            (0..outline.len()).map(|i| outline[i].cross(outline[(i + 1) % outline.len()])).sum()
+        };
+        assert!(area(&outlines[0]) * area(&outlines[1]) < 0.0);
+
+        // The character is above the baseline, with the y-axis pointing up
+        let metrics = font_metrics(&options).unwrap();
+        for point in outlines.iter().flatten() {
+            assert!(point.y > origin.y - 1.0 && point.y < origin.y + metrics.ascent);
+        }
+    }
+
+    /// Shapes a single line of text with the default font, returning its glyphs and width
+    fn shape(line: &str) -> (Vec<GlyphId>, f64) {
+        let data = load_font(&Font::SansSerif).unwrap();
+        let (glyphs, width) = shape_line(&[parse_font(&data)], 20.0, line);
+        (glyphs.iter().map(|glyph| glyph.id).collect(), width)
+    }
+
+    #[test]
+    fn joins_arabic_characters() {
+        // Each beh takes a different form when it is joined to the characters around it
+        let (isolated, _) = shape("\u{628}");
+        let (joined, _) = shape("\u{628}\u{628}\u{628}");
+        assert_eq!(joined.len(), 3);
+        assert!(joined.iter().all(|&glyph| glyph != isolated[0]), "{:?} {:?}", isolated, joined);
+    }
+
+    #[test]
+    fn reverses_right_to_left_text() {
+        let (alef, _) = shape("\u{5d0}");
+        let (bet, _) = shape("\u{5d1}");
+        let (word, _) = shape("\u{5d0}\u{5d1}");
+        assert_eq!(word, vec![bet[0], alef[0]]);
+
+        // Left-to-right text around the right-to-left text stays where it is
+        let (a, _) = shape("a");
+        let (mixed, _) = shape("a \u{5d0}\u{5d1} a");
+        assert_eq!(mixed.first(), Some(&a[0]));
+        assert_eq!(mixed.last(), Some(&a[0]));
+        assert_eq!(mixed[2..4], [bet[0], alef[0]]);
+    }
+
+    #[test]
+    fn combining_marks_take_no_space() {
+        let (plain, plain_width) = shape("e");
+        let (accented, accented_width) = shape("e\u{301}");
+        assert_eq!(plain.len(), 1);
+        assert!(!accented.is_empty());
+        assert!((accented_width - plain_width).abs() < 1e-6, "{} {}", plain_width, accented_width);
+    }
+
+    #[test]
+    fn missing_characters_use_later_fonts() {
+        // The first font has every ASCII character, and the second font has everything else and
+        // digits
+        let has_glyph = |font, c: char| match font {
+            0 => c.is_ascii() || c == '\u{301}',
+            _ => !c.is_ascii() || c.is_ascii_digit(),
+        };
+        let runs = |text| font_runs(text, 2, has_glyph);
+
+        assert_eq!(runs("turtle"), vec![(0..6, 0)]);
+        assert_eq!(runs(""), vec![]);
+        assert_eq!(runs("a\u{1F422}b"), vec![(0..1, 0), (1..5, 1), (5..6, 0)]);
+        // Combining marks stay with the character before them, even in another font
+        assert_eq!(runs("\u{1F422}\u{301}e\u{301}"), vec![(0..6, 1), (6..9, 0)]);
+        // Skin tones and sequences joined with a zero width joiner stay together
+        assert_eq!(runs("\u{1F44B}\u{1F3FD}!"), vec![(0..8, 1), (8..9, 0)]);
+        assert_eq!(runs("\u{1F469}\u{200D}\u{1F4BB}"), vec![(0..11, 1)]);
+        // The emoji variation selector asks for the emoji font, even for characters that are in
+        // the first font
+        assert_eq!(runs("1\u{FE0F}\u{20E3}2"), vec![(0..7, 1), (7..8, 0)]);
+        // Characters that are not in any font use the first font
+        let runs = font_runs("a\u{1F422}", 2, |font, c| font == 0 && c == 'a');
+        assert_eq!(runs, vec![(0..5, 0)]);
+    }
+
+    #[test]
+    fn missing_font_file() {
+        let font = Font::File(PathBuf::from("this/font/does/not/exist.ttf"));
+        let err = font_metrics(&TextOptions {font_size: 12.0, font}).unwrap_err();
+        assert!(err.to_string().contains("unable to read font file"), "{}", err);
+    }
+}
@@ -0,0 +1,137 @@
+use std::thread;
+use std::panic;
+
+use tokio::sync::oneshot;
+
+use crate::ipc_protocol::ProtocolClient;
+use crate::async_turtle::AsyncTurtle;
+use crate::sync_runtime::block_on;
+use crate::Turtle;
+
+/// Runs the turtle window in the current thread and the given function in a new thread
+///
+/// Most programs create a [`Turtle`] with [`Turtle::new()`]. On platforms where the window must be
+/// run on the main thread (like macOS), that opens the window in a separate process so that the
+/// main thread stays free for the rest of the program. `run()` does the opposite: the window is run
+/// on the main thread of this process and your code is moved to another thread. No other process
+/// is ever started.
+///
+/// With the `single-process` feature, the program and the window talk to each other over channels
+/// within this process instead of IPC channels. That feature also makes sure that no other process
+/// is started for any window, which matters in environments like process supervisors and test
+/// harnesses that do not allow the executable to be run again.
+///
+/// The function is given a turtle in a new drawing. This function returns only after the window
+/// has been closed. Just like with [`Turtle::new()`], the window stays open after the function
+/// returns so that you can see what was drawn.
+///
+/// ```rust,no_run
+/// // Call this at the start of main()
+/// turtle::run(|turtle| {
+///     for _ in 0..4 {
+///         turtle.forward(100.0);
+///         turtle.right(90.0);
+///     }
+/// });
+/// ```
+///
+/// # Panics
+///
+/// Panics if this is not called on the main thread. If the function panics, that panic is
+/// propagated once the window has been closed.
+///
+/// [`Turtle`]: struct.Turtle.html
+/// [`Turtle::new()`]: struct.Turtle.html#method.new
+pub fn run<F>(f: F)
+    where F: FnOnce(&mut Turtle) + Send + 'static,
+{
+    run_client(|client| {
+        let turtle = block_on(AsyncTurtle::with_client(client));
+        let mut turtle = Turtle::from(turtle);
+        f(&mut turtle);
+    });
+}
+
+/// Runs the turtle window in the current thread and the given function in a new thread, passing
+/// the function a client connected to the window
+///
+/// See [`run()`] for more details.
+pub(crate) fn run_client<F>(f: F)
+    where F: FnOnce(ProtocolClient) + Send + 'static,
+{
+    // Checks that the current thread is the main thread
+    crate::start();
+
+    let (server_name_sender, server_name_receiver) = oneshot::channel();
+
+    let program = thread::Builder::new()
+        .name("turtle program".to_string())
+        .spawn(move || {
+            let client = block_on(ProtocolClient::connect(|name| async {
+                server_name_sender.send(name)
+                    .expect("bug: unable to send server name to renderer server");
+                Ok(())
+            })).expect("unable to connect to turtle window");
+
+            f(client);
+        })
+        .expect("unable to spawn thread to run turtle program");
+
+    run_window(server_name_receiver);
+
+    if let Err(panic_err) = program.join() {
+        panic::resume_unwind(panic_err);
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(any(feature = "test", feature = "headless", test))] {
+        /// Runs the test server until the program disconnects, since there is no window to close
+        fn run_window(server_name: oneshot::Receiver<String>) {
+            block_on(async {
+                let server_name = server_name.await
+                    .expect("bug: unable to receive server name");
+                super::backend::run_main(server_name).await;
+            });
+        }
+
+    } else {
+        /// Runs the window until it is closed
+        fn run_window(server_name: oneshot::Receiver<String>) {
+            use tokio::runtime::Runtime;
+
+            use crate::ipc_protocol::connect_server;
+
+            let runtime = Runtime::new()
+                .expect("unable to spawn tokio runtime to run turtle window");
+
+            super::main::run_main(runtime.handle().clone(), async {
+                let server_name = server_name.await
+                    .expect("bug: unable to receive server name");
+                connect_server(server_name)
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Point;
+
+    #[test]
+    fn runs_program_in_new_thread() {
+        run(|turtle| {
+            turtle.forward(100.0);
+            assert!((turtle.position() - Point {x: 0.0, y: 100.0}).len() < 1e-9);
+            assert_ne!(thread::current().name(), Some("main"));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "program panicked")]
+    fn propagates_panics() {
+        run(|_| panic!("program panicked"));
+    }
+}
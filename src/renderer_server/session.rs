@@ -0,0 +1,198 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+use crate::{Color, Point, Primitive};
+
+use super::app::App;
+use super::state::TurtleState;
+use super::renderer::{primitive, display_list::{DisplayList, is_valid_dash_pattern}};
+
+/// The version of the session file format
+///
+/// This must be increased whenever the format changes so that files in the old format are
+/// rejected with a clear error instead of being loaded incorrectly.
+const SESSION_VERSION: u32 = 1;
+
+/// An error produced while saving or loading a drawing session
+///
+/// See [`Drawing::save()`](struct.Drawing.html#method.save) for more information.
+#[derive(Debug, Error, Serialize, Deserialize)]
+#[error("{0}")]
+pub struct SessionError(String);
+
+impl From<crate::Error> for SessionError {
+    fn from(err: crate::Error) -> Self {
+        SessionError(err.to_string())
+    }
+}
+
+/// The configuration of the drawing that is saved in a session file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedDrawing {
+    pub title: String,
+    pub background: Color,
+    /// The center of the view in logical coordinates
+    pub center: Point,
+    pub zoom: f64,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Everything that is saved in a session file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Session {
+    version: u32,
+    pub drawing: SavedDrawing,
+    /// The state of every turtle, in the order that the turtles were created
+    pub turtles: Vec<TurtleState>,
+    /// Every visible primitive in the drawing, in the order that they are drawn
+    pub primitives: Vec<Primitive>,
+}
+
+/// The start of every session file, read before the rest of the file so that a file in another
+/// version of the format can be rejected before trying to read it
+#[derive(Debug, Deserialize)]
+struct SessionVersion {
+    version: u32,
+}
+
+impl Session {
+    /// Captures the configuration of the drawing, the state of every turtle, and everything that
+    /// has been drawn
+    ///
+    /// Images are left out since their pixels are not saved.
+    pub fn capture(app: &App, display_list: &DisplayList) -> Self {
+        let drawing = app.drawing();
+
+        Self {
+            version: SESSION_VERSION,
+            drawing: SavedDrawing {
+                title: drawing.title.clone(),
+                background: drawing.background,
+                center: drawing.center,
+                zoom: drawing.zoom,
+                width: drawing.width,
+                height: drawing.height,
+            },
+            turtles: app.turtles().map(|(_, turtle)| turtle.state.clone()).collect(),
+            primitives: primitive::snapshot(display_list).into_iter()
+                .filter(|prim| !matches!(prim, Primitive::Image {..}))
+                .collect(),
+        }
+    }
+
+    /// Writes the session to the given path as JSON
+    pub fn save(&self, path: &Path) -> Result<(), SessionError> {
+        let data = serde_json::to_vec(self).map_err(|err| SessionError(err.to_string()))?;
+        fs::write(path, data).map_err(|err| SessionError(err.to_string()))
+    }
+
+    /// Reads a session that was written with `save()` from the given path
+    pub fn load(path: &Path) -> Result<Self, SessionError> {
+        let data = fs::read(path).map_err(|err| SessionError(err.to_string()))?;
+
+        let SessionVersion {version} = serde_json::from_slice(&data)
+            .map_err(|err| SessionError(format!("not a turtle session file: {}", err)))?;
+        if version != SESSION_VERSION {
+            return Err(SessionError(format!(
+                "unsupported session file version {} (expected version {})",
+                version,
+                SESSION_VERSION,
+            )));
+        }
+
+        let session: Self = serde_json::from_slice(&data)
+            .map_err(|err| SessionError(format!("invalid session file: {}", err)))?;
+        if !session.is_valid() {
+            return Err(SessionError("invalid session file: contains an invalid color, size, or position".to_string()));
+        }
+
+        Ok(session)
+    }
+
+    fn is_valid(&self) -> bool {
+        let SavedDrawing {background, center, zoom, width, height, ..} = self.drawing;
+        let is_valid_drawing = background.is_valid() && center.is_finite()
+            && zoom.is_finite() && zoom > 0.0 && width > 0 && height > 0;
+
+        let is_valid_turtle = |turtle: &TurtleState| {
+            let TurtleState {ref pen, fill_color, position, heading, ref shape, shape_size, ..} = *turtle;
+            pen.color.is_valid() && pen.thickness.is_finite() && pen.thickness >= 0.0
+                && is_valid_dash_pattern(&pen.dash_pattern) && fill_color.is_valid()
+                && position.is_finite() && heading.to_radians().is_finite() && shape.is_valid()
+                && shape_size.is_finite() && shape_size > 0.0
+        };
+
+        is_valid_drawing
+            && self.turtles.iter().all(is_valid_turtle)
+            && self.primitives.iter().all(|prim| prim.is_valid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::colors::RED;
+
+    fn session_with(drawing: SavedDrawing) -> Session {
+        Session {
+            version: SESSION_VERSION,
+            drawing,
+            turtles: vec![TurtleState::default()],
+            primitives: vec![Primitive::Polygon {
+                points: vec![Point::origin(), Point {x: 10.0, y: 0.0}, Point {x: 0.0, y: 10.0}],
+                fill_color: RED,
+                fill_rule: Default::default(),
+            }],
+        }
+    }
+
+    fn drawing() -> SavedDrawing {
+        SavedDrawing {
+            title: "Session".to_string(),
+            background: RED,
+            center: Point {x: 20.0, y: -5.0},
+            zoom: 2.0,
+            width: 400,
+            height: 300,
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session-save-and-load.json");
+        session_with(drawing()).save(&path).unwrap();
+
+        let session = Session::load(&path).unwrap();
+        assert_eq!(session.drawing.title, "Session");
+        assert_eq!(session.drawing.center, Point {x: 20.0, y: -5.0});
+        assert_eq!(session.turtles.len(), 1);
+        assert_eq!(session.primitives.len(), 1);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session-rejects-other-versions.json");
+        let mut session = session_with(drawing());
+        session.version = SESSION_VERSION + 1;
+        session.save(&path).unwrap();
+
+        let err = Session::load(&path).unwrap_err();
+        assert!(err.to_string().contains("unsupported session file version"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session-rejects-invalid-values.json");
+        session_with(SavedDrawing {zoom: -1.0, ..drawing()}).save(&path).unwrap();
+
+        assert!(Session::load(&path).is_err());
+    }
+}
@@ -1,11 +1,14 @@
-#[cfg(not(any(feature = "test", test)))]
+#[cfg(not(any(feature = "test", feature = "headless", test)))]
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use std::ffi::OsString;
+use std::path::PathBuf;
+
 use super::backend::RendererServer;
 
 /// `start()` must be called once from the main thread, but it can be called after that any number
 /// of times. This flag helps ensure that the main thread check only executes the first time.
-#[cfg(not(any(feature = "test", test)))]
+#[cfg(not(any(feature = "test", feature = "headless", test)))]
 static START_RAN_ONCE: AtomicBool = AtomicBool::new(false);
 
 /// Start the turtle window in advance
@@ -22,6 +25,14 @@ static START_RAN_ONCE: AtomicBool = AtomicBool::new(false);
 /// The [`Turtle::new()`] method will call this function for you so that you don't need to worry
 /// about this unless you are doing something before that.
 ///
+/// # Crash Reports
+///
+/// Whenever the window runs in a separate process (always on macOS, and for every window after the
+/// first on other Unix platforms), anything that process writes to stderr is forwarded to the
+/// stderr of your program. If the window crashes, the last lines of that output are included in
+/// the panic message of the next turtle command. Set the `TURTLE_CRASH_REPORT` environment
+/// variable to a file path to also save that report to a file.
+///
 /// # Example
 /// ```rust,no_run
 /// # #![allow(unused_variables, unused_mut)]
@@ -50,7 +61,7 @@ pub fn start() {
     // accidentally make it harder to run a turtle program on a different platform. The check is
     // not foolproof and there is no way to verify that start() is called at the beginning of
     // main() in all cases. This is just to help in the cases where we can detect something.
-    #[cfg(not(any(feature = "test", test)))]
+    #[cfg(not(any(feature = "test", feature = "headless", test)))]
     if !START_RAN_ONCE.swap(true, Ordering::SeqCst) {
         assert_main_thread();
     }
@@ -58,7 +69,84 @@ pub fn start() {
     RendererServer::start();
 }
 
-#[cfg(not(any(feature = "test", test)))]
+/// Controls how the window process is spawned whenever the window runs in a separate process
+///
+/// Every window runs in a separate process on macOS. On other Unix platforms, the first window
+/// runs in a thread of the current process and every window after that runs in its own process.
+/// On Windows, every window runs in a thread and these options are ignored. See [`start_with()`]
+/// for more information.
+///
+/// ```rust
+/// use turtle::ProcessOptions;
+///
+/// let options = ProcessOptions {
+///     envs: vec![("RUST_BACKTRACE".into(), "1".into())],
+///     process_title: Some("turtle-window".into()),
+///     ..ProcessOptions::default()
+/// };
+/// assert_eq!(options.executable, None);
+/// ```
+///
+/// [`start_with()`]: fn.start_with.html
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProcessOptions {
+    /// Extra environment variables to set in the window process
+    ///
+    /// The window process inherits the environment of the current process, so this is only needed
+    /// for variables that should be different in the window process.
+    pub envs: Vec<(OsString, OsString)>,
+    /// The executable to run as the window process, or `None` to use the path returned by
+    /// [`std::env::current_exe()`]
+    ///
+    /// The window process is created by running your program again and having [`start()`] take it
+    /// over, so this must be a copy of the current program. This is useful when `current_exe()`
+    /// cannot be relied on, for example because the program was run from a temporary file that
+    /// has since been deleted or from inside an AppImage.
+    ///
+    /// [`start()`]: fn.start.html
+    pub executable: Option<PathBuf>,
+    /// The arguments passed to the window process
+    ///
+    /// No arguments are passed by default. This is needed when the executable is an interpreter
+    /// that must be told which program to run again (e.g. the path of a Python script).
+    pub args: Vec<OsString>,
+    /// The working directory of the window process, or `None` to use the working directory of the
+    /// current process
+    pub current_dir: Option<PathBuf>,
+    /// The name that the window process is listed under by tools like `ps` (its `argv[0]`), or
+    /// `None` to use the path of the executable
+    pub process_title: Option<OsString>,
+}
+
+/// Start the turtle window in advance, using the given options to spawn the window process
+///
+/// This works just like [`start()`] and must also be called at the start of `main()`. The options
+/// apply to every window opened after this is called, including the one opened by
+/// [`Turtle::new()`].
+///
+/// ```rust,no_run
+/// use std::path::PathBuf;
+/// use turtle::{Turtle, ProcessOptions};
+///
+/// fn main() {
+///     turtle::start_with(ProcessOptions {
+///         current_dir: Some(PathBuf::from("/tmp")),
+///         ..ProcessOptions::default()
+///     });
+///
+///     let mut turtle = Turtle::new();
+///     turtle.forward(100.0);
+/// }
+/// ```
+///
+/// [`start()`]: fn.start.html
+/// [`Turtle::new()`]: struct.Turtle.html#method.new
+pub fn start_with(options: ProcessOptions) {
+    RendererServer::set_process_options(options);
+    start();
+}
+
+#[cfg(not(any(feature = "test", feature = "headless", test)))]
 fn assert_main_thread() {
     // This check isn't foolproof. Someone can always create a thread named "main".
     if std::thread::current().name().unwrap_or("") != "main" {
@@ -1,13 +1,34 @@
 use std::f64::consts::PI;
+use std::time::Duration;
 
 use serde::{Serialize, Deserialize};
 
+use super::coords::AxisScale;
+use super::renderer::symmetry::Symmetry;
+use super::renderer::image::{ImageHandle, BackgroundImage};
+#[cfg(feature = "shaders")]
+use super::renderer::post_shader::PostShader;
+
+use crate::event::Key;
 use crate::{
     Color,
     Point,
     Speed,
+    Easing,
+    Shape,
+    FillRule,
+    BlendMode,
+    Size,
+    Corner,
+    ResizeAnchor,
+    CoordinateSystem,
+    WorldCoordinates,
+    Origin,
+    Rect,
+    LayerId,
     debug,
     radians::Radians,
+    Filter,
     colors::{WHITE, BLACK},
     async_turtle::AngleUnit,
 };
@@ -15,72 +36,302 @@ use crate::{
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DrawingState {
     pub title: String,
+    /// The image shown as the icon of the window, or `None` to use the default icon
+    pub icon: Option<ImageHandle>,
     pub background: Color,
+    pub background_image: Option<BackgroundImage>,
     pub center: Point,
+    pub zoom: f64,
     pub width: u32,
     pub height: u32,
     pub is_maximized: bool,
     pub is_fullscreen: bool,
+    pub resize_anchor: ResizeAnchor,
+    pub minimap: Option<Minimap>,
+    pub filters: Vec<Filter>,
+    pub symmetry: Option<Symmetry>,
+    pub pressure_pen_width: Option<f64>,
+    pub coordinate_system: CoordinateSystem,
+    pub world_coordinates: Option<WorldCoordinates>,
+    pub dpi: f64,
+    pub disconnect_behavior: DisconnectBehavior,
+    /// The window is only redrawn after this many updates, or only when it is flushed if this is
+    /// zero
+    pub tracer: u32,
+    /// If false, every animation completes right away, no matter what the speed of each turtle is
+    pub is_animated: bool,
+    /// The key that pauses and resumes the drawing when it is pressed in the window, if any
+    pub pause_key: Option<Key>,
+    /// The key that runs the next turtle command in step mode when it is pressed, if any
+    pub step_key: Option<Key>,
+    /// If true, scrolling zooms the view and dragging with the middle mouse button moves it
+    pub navigation: bool,
+    #[cfg(feature = "shaders")]
+    pub post_shader: Option<PostShader>,
+}
+
+/// The configuration of the minimap overlay
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Minimap {
+    /// The corner of the window that the minimap is drawn in
+    pub corner: Corner,
+    /// The size of the minimap in logical pixels
+    pub size: Size,
+}
+
+/// Controls what happens to the window once the program that controls it has finished
+///
+/// See [`Drawing::set_disconnect_behavior()`] for more information.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use turtle::DisconnectBehavior;
+///
+/// // Keep the window open until it is closed (default)
+/// let behavior = DisconnectBehavior::default();
+/// assert_eq!(behavior.close_after, None);
+/// assert!(!behavior.show_finished);
+///
+/// // Show that the program has finished, then close the window 10 seconds later
+/// let behavior = DisconnectBehavior {
+///     close_after: Some(Duration::from_secs(10)),
+///     show_finished: true,
+/// };
+/// ```
+///
+/// [`Drawing::set_disconnect_behavior()`]: struct.Drawing.html#method.set_disconnect_behavior
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisconnectBehavior {
+    /// How long to keep the window open after the program finishes, or `None` to keep it open
+    /// until it is closed
+    pub close_after: Option<Duration>,
+    /// If true, " (finished)" is added to the end of the window title once the program finishes
+    pub show_finished: bool,
 }
 
 impl DrawingState {
     pub const DEFAULT_TITLE: &'static str = "Turtle";
+    pub const DEFAULT_ICON: Option<ImageHandle> = None;
     pub const DEFAULT_BACKGROUND: Color = WHITE;
+    pub const DEFAULT_BACKGROUND_IMAGE: Option<BackgroundImage> = None;
     pub const DEFAULT_CENTER: Point = Point::origin();
+    pub const DEFAULT_ZOOM: f64 = 1.0;
     pub const DEFAULT_WIDTH: u32 = 800;
     pub const DEFAULT_HEIGHT: u32 = 600;
     pub const DEFAULT_IS_MAXIMIZED: bool = false;
     pub const DEFAULT_IS_FULLSCREEN: bool = false;
+    pub const DEFAULT_RESIZE_ANCHOR: ResizeAnchor = ResizeAnchor::Center;
+    pub const DEFAULT_MINIMAP: Option<Minimap> = None;
+    pub const DEFAULT_FILTERS: Vec<Filter> = Vec::new();
+    pub const DEFAULT_SYMMETRY: Option<Symmetry> = None;
+    pub const DEFAULT_PRESSURE_PEN_WIDTH: Option<f64> = None;
+    pub const DEFAULT_COORDINATE_SYSTEM: CoordinateSystem = CoordinateSystem {
+        origin: Origin::Center,
+        y_up: true,
+    };
+    pub const DEFAULT_WORLD_COORDINATES: Option<WorldCoordinates> = None;
+    /// The resolution used by CSS and most browsers, where one inch is 96 logical pixels
+    pub const DEFAULT_DPI: f64 = 96.0;
+    pub const DEFAULT_DISCONNECT_BEHAVIOR: DisconnectBehavior = DisconnectBehavior {
+        close_after: None,
+        show_finished: false,
+    };
+    pub const DEFAULT_TRACER: u32 = 1;
+    pub const DEFAULT_IS_ANIMATED: bool = true;
+    pub const DEFAULT_PAUSE_KEY: Option<Key> = None;
+    pub const DEFAULT_STEP_KEY: Option<Key> = None;
+    pub const DEFAULT_NAVIGATION: bool = false;
+    #[cfg(feature = "shaders")]
+    pub const DEFAULT_POST_SHADER: Option<PostShader> = None;
 }
 
 impl Default for DrawingState {
     fn default() -> Self {
         Self {
             title: Self::DEFAULT_TITLE.to_owned(),
+            icon: Self::DEFAULT_ICON,
             background: Self::DEFAULT_BACKGROUND,
+            background_image: Self::DEFAULT_BACKGROUND_IMAGE,
             center: Self::DEFAULT_CENTER,
+            zoom: Self::DEFAULT_ZOOM,
             width: Self::DEFAULT_WIDTH,
             height: Self::DEFAULT_HEIGHT,
             is_maximized: Self::DEFAULT_IS_MAXIMIZED,
             is_fullscreen: Self::DEFAULT_IS_FULLSCREEN,
+            resize_anchor: Self::DEFAULT_RESIZE_ANCHOR,
+            minimap: Self::DEFAULT_MINIMAP,
+            filters: Self::DEFAULT_FILTERS,
+            symmetry: Self::DEFAULT_SYMMETRY,
+            pressure_pen_width: Self::DEFAULT_PRESSURE_PEN_WIDTH,
+            coordinate_system: Self::DEFAULT_COORDINATE_SYSTEM,
+            world_coordinates: Self::DEFAULT_WORLD_COORDINATES,
+            dpi: Self::DEFAULT_DPI,
+            disconnect_behavior: Self::DEFAULT_DISCONNECT_BEHAVIOR,
+            tracer: Self::DEFAULT_TRACER,
+            is_animated: Self::DEFAULT_IS_ANIMATED,
+            pause_key: Self::DEFAULT_PAUSE_KEY,
+            step_key: Self::DEFAULT_STEP_KEY,
+            navigation: Self::DEFAULT_NAVIGATION,
+            #[cfg(feature = "shaders")]
+            post_shader: Self::DEFAULT_POST_SHADER,
         }
     }
 }
 
 impl DrawingState {
+    /// Returns the size of the drawing in logical pixels
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Converts a point in logical coordinates to the coordinate system of the drawing
+    pub fn to_user(&self, point: Point) -> Point {
+        match self.world_coordinates {
+            Some(world) => world.to_user(point, self.size()),
+            None => self.coordinate_system.to_user(point, self.size()),
+        }
+    }
+
+    /// Converts a point in the coordinate system of the drawing to logical coordinates
+    pub fn to_logical(&self, point: Point) -> Point {
+        match self.world_coordinates {
+            Some(world) => world.to_logical(point, self.size()),
+            None => self.coordinate_system.to_logical(point, self.size()),
+        }
+    }
+
+    /// Converts a rectangle in logical coordinates to the coordinate system of the drawing
+    pub fn rect_to_user(&self, rect: Rect) -> Rect {
+        Rect::from_points(self.to_user(rect.min), self.to_user(rect.max))
+    }
+
+    /// Converts a rectangle in the coordinate system of the drawing to logical coordinates
+    pub fn rect_to_logical(&self, rect: Rect) -> Rect {
+        Rect::from_points(self.to_logical(rect.min), self.to_logical(rect.max))
+    }
+
+    /// Converts a heading in logical coordinates to the coordinate system of the drawing
+    pub fn heading_to_user(&self, heading: Radians) -> Radians {
+        match self.world_coordinates {
+            Some(_) => self.axis_scale().invert_heading(heading),
+            None => self.coordinate_system.convert_heading(heading),
+        }
+    }
+
+    /// Returns the number of logical pixels in one unit of the world coordinates along each axis
+    ///
+    /// Only world coordinates can change the length of anything, so this is the identity if they
+    /// are not set. Flipping the y axis with the coordinate system does not change which way
+    /// turtles turn on the screen, so that is not included either.
+    pub fn axis_scale(&self) -> AxisScale {
+        match self.world_coordinates {
+            Some(world) => world.scale(self.size()),
+            None => AxisScale::IDENTITY,
+        }
+    }
+
+    /// Returns how far a turtle facing the given logical heading moves in logical coordinates
+    /// when it moves forward by the given distance in the coordinate system of the drawing
+    pub fn movement(&self, heading: Radians, distance: f64) -> Point {
+        let scale = self.axis_scale();
+        let heading = scale.invert_heading(heading);
+        scale.apply(Point {
+            x: distance * heading.cos(),
+            y: distance * heading.sin(),
+        })
+    }
+
+    /// Updates the size of the drawing, moving the center if needed to respect the resize anchor
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.center = self.resize_anchor.center_after_resize(
+            self.center,
+            (self.width, self.height),
+            (width, height),
+        );
+        self.width = width;
+        self.height = height;
+    }
+
     pub(crate) fn to_debug(&self) -> debug::Drawing {
         let &Self {
             ref title,
+            icon,
             background,
+            background_image,
             center,
+            zoom,
             width,
             height,
             is_maximized,
             is_fullscreen,
+            resize_anchor,
+            minimap,
+            ref filters,
+            symmetry,
+            pressure_pen_width,
+            coordinate_system,
+            world_coordinates,
+            dpi,
+            disconnect_behavior,
+            tracer,
+            is_animated,
+            pause_key,
+            step_key,
+            navigation,
+            // The translated shader is not useful for debugging
+            #[cfg(feature = "shaders")]
+            post_shader: _,
         } = self;
 
         let title = title.clone();
+        let filters = filters.clone();
 
         debug::Drawing {
             title,
+            icon,
             background,
+            background_image,
             center,
+            zoom,
             width,
             height,
             is_maximized,
             is_fullscreen,
+            resize_anchor,
+            minimap,
+            filters,
+            symmetry,
+            pressure_pen_width,
+            coordinate_system,
+            world_coordinates,
+            dpi,
+            disconnect_behavior,
+            tracer,
+            is_animated,
+            pause_key,
+            step_key,
+            navigation,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TurtleState {
     pub pen: Pen,
     pub fill_color: Color,
+    /// The rule used to fill the shapes drawn between `BeginFill` and `EndFill`
+    pub fill_rule: FillRule,
     pub position: Point,
     pub heading: Radians,
     pub speed: Speed,
+    /// The easing applied to every movement and rotation of the turtle
+    pub easing: Easing,
     pub is_visible: bool,
+    pub shape: Shape,
+    /// The amount that the shape of the turtle is scaled by, always positive and finite
+    pub shape_size: f64,
+    /// The layer that the turtle is drawn in and that everything it draws is added to
+    pub layer: LayerId,
 }
 
 impl TurtleState {
@@ -88,6 +339,7 @@ impl TurtleState {
     pub const DEFAULT_POSITION: Point = Point::origin();
     pub const DEFAULT_HEADING: Radians = Radians::from_radians_value(PI / 2.0);
     pub const DEFAULT_IS_VISIBLE: bool = true;
+    pub const DEFAULT_SHAPE_SIZE: f64 = 1.0;
 }
 
 impl Default for TurtleState {
@@ -95,10 +347,15 @@ impl Default for TurtleState {
         Self {
             pen: Pen::default(),
             fill_color: Self::DEFAULT_FILL_COLOR,
+            fill_rule: FillRule::default(),
             position: Self::DEFAULT_POSITION,
             heading: Self::DEFAULT_HEADING,
             speed: Speed::default(),
+            easing: Easing::default(),
             is_visible: Self::DEFAULT_IS_VISIBLE,
+            shape: Shape::default(),
+            shape_size: Self::DEFAULT_SHAPE_SIZE,
+            layer: LayerId::BASE,
         }
     }
 }
@@ -109,9 +366,14 @@ impl TurtleState {
             position,
             heading,
             speed,
+            easing,
             ref pen,
             fill_color,
+            fill_rule,
             is_visible,
+            ref shape,
+            shape_size,
+            layer,
         } = self;
 
         let heading = match angle_unit {
@@ -124,18 +386,45 @@ impl TurtleState {
             position,
             heading,
             speed,
+            easing,
             pen,
             fill_color,
+            fill_rule,
             is_visible,
+            shape: shape.clone(),
+            shape_size,
+            layer,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// The parts of a turtle's state that are saved when the state is pushed onto its stack of saved
+/// states
+#[derive(Debug, Clone)]
+pub struct SavedState {
+    pub position: Point,
+    pub heading: Radians,
+    pub pen: Pen,
+    pub fill_color: Color,
+}
+
+impl SavedState {
+    /// Saves the given state of a turtle
+    pub fn new(state: &TurtleState) -> Self {
+        let TurtleState {position, heading, ref pen, fill_color, ..} = *state;
+        Self {position, heading, pen: pen.clone(), fill_color}
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pen {
     pub is_enabled: bool,
     pub thickness: f64,
     pub color: Color,
+    /// The lengths of the dashes and the gaps between them, empty if lines are solid
+    pub dash_pattern: Vec<f64>,
+    /// How the lines drawn with the pen combine with what is underneath them
+    pub blend_mode: BlendMode,
 }
 
 impl Pen {
@@ -150,6 +439,8 @@ impl Default for Pen {
             is_enabled: Self::DEFAULT_IS_ENABLED,
             thickness: Self::DEFAULT_THICKNESS,
             color: Self::DEFAULT_COLOR,
+            dash_pattern: Vec::new(),
+            blend_mode: BlendMode::default(),
         }
     }
 }
@@ -160,12 +451,16 @@ impl Pen {
             is_enabled,
             thickness,
             color,
+            ref dash_pattern,
+            blend_mode,
         } = self;
 
         debug::Pen {
             is_enabled,
             thickness,
             color,
+            dash_pattern: dash_pattern.clone(),
+            blend_mode,
         }
     }
 }
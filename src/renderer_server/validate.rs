@@ -0,0 +1,263 @@
+//! Checks that requests can be handled before running them
+//!
+//! The handlers assume that every request they are given is valid and panic otherwise. The
+//! client library checks its arguments before sending anything, but other clients (e.g. programs
+//! that joined a shared drawing or WebSocket connections) may send anything at all, so requests
+//! are checked here first.
+
+use crate::Error;
+use crate::ipc_protocol::{
+    ClientRequest,
+    DrawingPropValue,
+    TurtlePropValue,
+    PenPropValue,
+    is_valid_share_name,
+};
+
+use super::app::{App, LayerId};
+use super::renderer::image::ImageHandle;
+use super::renderer::recorder::is_valid_recording_fps;
+use super::renderer::display_list::is_valid_dash_pattern;
+
+/// Returns an error if the given request cannot be handled with the current state of the app
+pub(crate) fn validate_request(app: &App, request: &ClientRequest) -> Result<(), Error> {
+    if let Some(id) = request.turtle_id() {
+        if !app.has_turtle(id) {
+            return Err(Error::UnknownTurtle(id.index()));
+        }
+    }
+
+    use ClientRequest::*;
+    let valid = match request {
+        Export(_, _, options) => options.is_valid(),
+        Import(_, transform) => transform.is_valid(),
+        Print(options) => options.is_valid(),
+        ExportToolpath(_, _, options) => options.is_valid(),
+        &StartRecording(_, fps) => is_valid_recording_fps(fps),
+        Share(name) => is_valid_share_name(name),
+        SetDrawingProp(value) => return validate_drawing_prop(app, value),
+        MeasureText(_, options) |
+        FontMetrics(options) |
+        WriteText(_, _, options, _) => options.is_valid(),
+        LoadImage(image) => image.is_valid(),
+        &StampImage(_, image) => return validate_image(app, image),
+        SetTurtleProp(_, value) => return validate_turtle_prop(app, value),
+        &MoveForward(_, distance) |
+        &TimedMoveForward(_, distance, _) => distance.is_finite(),
+        &MoveTo(_, target) => target.is_finite(),
+        &RotateInPlace(_, angle, _) |
+        &TimedRotateInPlace(_, angle, _, _) => angle.to_radians().is_finite(),
+        &AnimatePenColor(_, color, _) => color.is_valid(),
+        &MoveArc(_, radius, extent, _) => radius.is_finite() && extent.to_radians().is_finite(),
+        &MoveCircle(_, radius) => radius.is_finite(),
+        &CubicBezierTo(_, control1, control2, end) => control1.is_finite() && control2.is_finite() && end.is_finite(),
+        &QuadBezierTo(_, control, end) => control.is_finite() && end.is_finite(),
+        FollowPath(_, path) => !path.is_empty() && path.iter().all(|segment| segment.is_finite()),
+        &ColorAt(point) => point.is_finite(),
+        &HitTest(point) => point.is_finite(),
+        &DrawCircle(center, radius, color) => center.is_finite() && radius.is_finite() && radius >= 0.0 && color.is_valid(),
+        &ClearAnimated(effect) => effect.is_valid(),
+        &ClearRegion(area) => area.min.is_finite() && area.max.is_finite(),
+        &LayerIsVisible(layer) |
+        &SetLayerVisible(layer, _) |
+        &MoveLayer(layer, _) => return validate_layer(app, layer),
+        _ => true,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidRequest(format!("{:?}", request)))
+    }
+}
+
+fn validate_drawing_prop(app: &App, value: &DrawingPropValue) -> Result<(), Error> {
+    use DrawingPropValue::*;
+    let valid = match value {
+        &Icon(Some(icon)) => return validate_image(app, icon),
+        Background(color) => color.is_valid(),
+        &BackgroundImage(Some(background_image)) => return validate_image(app, background_image.image),
+        Center(center) => center.is_finite(),
+        &Zoom(zoom) => zoom.is_finite() && zoom > 0.0,
+        Size(size) => size.width > 0 && size.height > 0,
+        &Width(width) => width > 0,
+        &Height(height) => height > 0,
+        Minimap(Some(minimap)) => minimap.size.width > 0 && minimap.size.height > 0,
+        Filters(filters) => filters.iter().all(|filter| filter.is_valid()),
+        Symmetry(Some(symmetry)) => symmetry.axes > 0,
+        &PressurePenWidth(Some(width)) => width.is_finite() && width > 0.0,
+        &WorldCoordinates(Some(world)) => world.is_valid(),
+        &Dpi(dpi) => dpi.is_finite() && dpi > 0.0,
+        _ => true,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidRequest(format!("invalid drawing property: {:?}", value)))
+    }
+}
+
+fn validate_turtle_prop(app: &App, value: &TurtlePropValue) -> Result<(), Error> {
+    use TurtlePropValue::*;
+    let valid = match value {
+        &Shape(crate::Shape::Image(image)) => return validate_image(app, image),
+        &Layer(layer) => return validate_layer(app, layer),
+        &Pen(PenPropValue::Thickness(thickness)) => thickness.is_finite() && thickness >= 0.0,
+        Pen(PenPropValue::DashPattern(pattern)) => is_valid_dash_pattern(pattern),
+        Pen(PenPropValue::Color(color)) |
+        FillColor(color) => color.is_valid(),
+        FillRule(_) => true,
+        Shape(shape) => shape.is_valid(),
+        &ShapeSize(size) => size.is_finite() && size > 0.0,
+        &Easing(easing) => easing.is_valid(),
+
+        IsFilling(_) => return Err(Error::InvalidRequest(
+            "use the `BeginFill` and `EndFill` requests to start and stop filling".to_string()
+        )),
+        Position(_) |
+        PositionX(_) |
+        PositionY(_) => return Err(Error::InvalidRequest(
+            "use the `MoveTo` request to change the position of a turtle".to_string()
+        )),
+        Heading(_) => return Err(Error::InvalidRequest(
+            "use the `RotateInPlace` request to change the heading of a turtle".to_string()
+        )),
+
+        _ => true,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidRequest(format!("invalid turtle property: {:?}", value)))
+    }
+}
+
+/// Returns an error if the image was not loaded into this drawing
+///
+/// The size is checked as well since a handle with the wrong size would make the turtle's shape
+/// different from the image that is drawn.
+fn validate_image(app: &App, image: ImageHandle) -> Result<(), Error> {
+    let id = image.id;
+    if !app.has_image(id) {
+        return Err(Error::UnknownImage(id.index()));
+    }
+
+    let data = app.image(id);
+    if (data.width, data.height) != (image.width(), image.height()) {
+        return Err(Error::InvalidRequest(format!("{:?} does not match the size of the image", image)));
+    }
+
+    Ok(())
+}
+
+/// Returns an error if the layer was not added to this drawing
+fn validate_layer(app: &App, layer: LayerId) -> Result<(), Error> {
+    if app.has_layer(layer) {
+        Ok(())
+    } else {
+        Err(Error::UnknownLayer(layer.index()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Point;
+    use crate::ipc_protocol::TurtleProp;
+
+    #[test]
+    fn rejects_invalid_requests() {
+        let mut app = App::default();
+        let id = app.add_turtle();
+
+        assert_eq!(validate_request(&app, &ClientRequest::MoveTo(id, Point::origin())), Ok(()));
+        assert!(matches!(
+            validate_request(&app, &ClientRequest::MoveTo(id, Point {x: f64::NAN, y: 0.0})),
+            Err(Error::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            validate_request(&app, &ClientRequest::SetTurtleProp(id, TurtlePropValue::PositionX(1.0))),
+            Err(Error::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            validate_request(&app, &ClientRequest::SetDrawingProp(DrawingPropValue::Width(0))),
+            Err(Error::InvalidRequest(_))
+        ));
+
+        // A turtle that only exists in a different drawing
+        let mut other_app = App::default();
+        other_app.add_turtle();
+        let other_id = other_app.add_turtle();
+        let missing = ClientRequest::TurtleProp(other_id, TurtleProp::Position);
+        assert_eq!(validate_request(&app, &missing), Err(Error::UnknownTurtle(1)));
+    }
+
+    #[test]
+    fn rejects_unknown_images() {
+        use super::super::renderer::image::{ImageData, BackgroundImage, BackgroundMode};
+
+        let mut app = App::default();
+        let id = app.add_turtle();
+        let data = ImageData {width: 1, height: 1, pixels: vec![0; 4]};
+        let image = ImageHandle::new(app.add_image(data.clone()), 1, 1);
+        assert_eq!(validate_request(&app, &ClientRequest::StampImage(id, image)), Ok(()));
+
+        // An image that was only loaded in a different drawing
+        let mut other_app = App::default();
+        other_app.add_image(data.clone());
+        let other_image = ImageHandle::new(other_app.add_image(data), 1, 1);
+        assert_eq!(
+            validate_request(&app, &ClientRequest::StampImage(id, other_image)),
+            Err(Error::UnknownImage(1))
+        );
+        let shape = TurtlePropValue::Shape(crate::Shape::Image(other_image));
+        assert_eq!(
+            validate_request(&app, &ClientRequest::SetTurtleProp(id, shape)),
+            Err(Error::UnknownImage(1))
+        );
+        let background = DrawingPropValue::BackgroundImage(Some(BackgroundImage {
+            image: other_image,
+            mode: BackgroundMode::Tile,
+        }));
+        assert_eq!(
+            validate_request(&app, &ClientRequest::SetDrawingProp(background)),
+            Err(Error::UnknownImage(1))
+        );
+        assert_eq!(
+            validate_request(&app, &ClientRequest::SetDrawingProp(DrawingPropValue::Icon(Some(other_image)))),
+            Err(Error::UnknownImage(1))
+        );
+
+        // The size of the handle must match the image
+        let resized = ImageHandle::new(image.id, 2, 1);
+        assert!(matches!(
+            validate_request(&app, &ClientRequest::StampImage(id, resized)),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_layers() {
+        let mut app = App::default();
+        let id = app.add_turtle();
+        let layer = app.next_layer_id();
+        assert_eq!(validate_request(&app, &ClientRequest::SetLayerVisible(LayerId::BASE, false)), Ok(()));
+        assert_eq!(validate_request(&app, &ClientRequest::MoveLayer(layer, false)), Ok(()));
+
+        // A layer that was only added in a different drawing
+        let mut other_app = App::default();
+        other_app.next_layer_id();
+        let other_layer = other_app.next_layer_id();
+        assert_eq!(
+            validate_request(&app, &ClientRequest::LayerIsVisible(other_layer)),
+            Err(Error::UnknownLayer(2))
+        );
+        assert_eq!(
+            validate_request(&app, &ClientRequest::SetTurtleProp(id, TurtlePropValue::Layer(other_layer))),
+            Err(Error::UnknownLayer(2))
+        );
+    }
+}
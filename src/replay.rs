@@ -0,0 +1,125 @@
+//! Draws a picture again from a command log written by `Drawing::record_to()`
+
+use std::io;
+use std::path::Path;
+
+use crate::Drawing;
+use crate::async_drawing::AsyncDrawing;
+use crate::ipc_protocol::CommandLogReader;
+use crate::sync_runtime::block_on;
+
+/// Opens a new window and runs every command in the command log at the given path, at the same
+/// pace that they originally ran.
+///
+/// Command logs are written by [`Drawing::record_to()`]. This makes it possible to share a drawing
+/// as a file that shows it being drawn, without sharing the program that drew it. Only commands
+/// sent after recording started are in the log, so start recording before adding any turtles.
+///
+/// This returns once every command has run. The window then stays open until it is closed, just
+/// like at the end of any other program. Returns an error if the file cannot be read or if it is
+/// not a command log written by this version of the crate. Command logs never contain commands
+/// that read or write files, pause the drawing, or wait for input, so a log that does is rejected
+/// before any of its commands run.
+///
+/// ```rust,no_run
+/// fn main() -> std::io::Result<()> {
+///     turtle::replay("square.turtlelog")
+/// }
+/// ```
+///
+/// [`Drawing::record_to()`]: struct.Drawing.html#method.record_to
+pub fn replay<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    replay_at_speed(path, 1.0)
+}
+
+/// Same as [`replay()`], but the commands run `speed` times as fast as they originally did.
+///
+/// For example, a `speed` of 2.0 makes the pauses between commands half as long. The speed of
+/// each turtle is still taken from the log, so this does not make the turtles themselves move any
+/// faster. Commands run one at a time, in the order they were recorded.
+///
+/// ```rust,no_run
+/// fn main() -> std::io::Result<()> {
+///     // Skip through the pauses in the original drawing
+///     turtle::replay_at_speed("square.turtlelog", 10.0)
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Panics if `speed` is not a finite number greater than zero.
+///
+/// [`replay()`]: fn.replay.html
+pub fn replay_at_speed<P: AsRef<Path>>(path: P, speed: f64) -> io::Result<()> {
+    assert!(speed.is_finite() && speed > 0.0, "Invalid playback speed: {}", speed);
+
+    let log = CommandLogReader::open(path.as_ref())?;
+    let mut drawing: AsyncDrawing = Drawing::new().into();
+    block_on(drawing.replay(log, speed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ExportOptions;
+    use crate::ipc_protocol::{ClientRequest, CommandLog, ExportFormat};
+
+    #[test]
+    fn replays_recorded_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("replay.turtlelog");
+
+        let mut drawing = Drawing::new();
+        drawing.record_to(&path).unwrap();
+        drawing.set_title("Replayed");
+        let mut turtle = drawing.add_turtle();
+        turtle.forward(100.0);
+        turtle.right(90.0);
+        turtle.forward(50.0);
+        let mesh = drawing.export_mesh();
+
+        let log = CommandLogReader::open(&path).unwrap();
+        let mut replayed: AsyncDrawing = Drawing::new().into();
+        block_on(replayed.replay(log, 100.0)).unwrap();
+
+        assert_eq!(block_on(replayed.title()), "Replayed");
+        assert_eq!(block_on(replayed.export_mesh()).0.len(), mesh.0.len());
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("replay-invalid.txt");
+        std::fs::write(&path, "forward 100").unwrap();
+        let err = replay(&path).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_requests_that_are_never_logged() {
+        let dir = tempfile::tempdir().unwrap();
+        let stolen = dir.path().join("stolen.svg");
+        let hostile_requests = [
+            ClientRequest::Export(stolen.clone(), ExportFormat::Svg, ExportOptions::default()),
+            ClientRequest::SaveSession(stolen.clone()),
+            // These would wait forever
+            ClientRequest::NextEvent,
+            ClientRequest::SetStepMode(true),
+        ];
+
+        for request in hostile_requests {
+            let path = dir.path().join("hostile.turtlelog");
+            let mut log = CommandLog::create(&path).unwrap();
+            log.write(&ClientRequest::CreateTurtle).unwrap();
+            log.write(&request).unwrap();
+            drop(log);
+
+            let err = replay(&path).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(err.to_string().contains("cannot be replayed"), "{}", err);
+        }
+        assert!(!stolen.exists());
+    }
+}
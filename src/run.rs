@@ -0,0 +1,199 @@
+//! An alternative way to structure programs around a callback that runs once per event and once
+//! per frame
+
+use std::time::Duration;
+
+use futures_util::stream::StreamExt;
+use crate::time::{self, Instant};
+
+use crate::{Drawing, Event, Color, Size, DisconnectBehavior};
+use crate::async_drawing::AsyncDrawing;
+use crate::event::EventStream;
+use crate::renderer_server::run_client;
+use crate::sync_runtime::block_on;
+
+/// The settings used to create the drawing passed to [`run_with()`]
+///
+/// ```rust
+/// use turtle::{Config, Size};
+///
+/// let config = Config {
+///     title: "My Game".to_string(),
+///     size: Size {width: 400, height: 400},
+///     ..Config::default()
+/// };
+/// assert_eq!(config.fps, 60.0);
+/// ```
+///
+/// [`run_with()`]: fn.run_with.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// The title of the window
+    pub title: String,
+    /// The size of the drawing
+    pub size: Size,
+    /// The background color of the drawing
+    pub background: Color,
+    /// The number of times per second that the callback is run for a new frame
+    ///
+    /// Must be greater than zero.
+    pub fps: f64,
+}
+
+impl Default for Config {
+    /// Uses the same title, size, and background as [`Drawing::new()`] at 60 frames per second
+    ///
+    /// [`Drawing::new()`]: struct.Drawing.html#method.new
+    fn default() -> Self {
+        Self {
+            title: "Turtle".to_string(),
+            size: Size {width: 800, height: 600},
+            background: "white".into(),
+            fps: 60.0,
+        }
+    }
+}
+
+/// Returned from the callback passed to [`run_with()`] to decide whether it should keep running
+///
+/// [`run_with()`]: fn.run_with.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlFlow {
+    /// Keep calling the callback
+    Continue,
+    /// Stop calling the callback and return from `run_with()`
+    Exit,
+}
+
+/// Creates a drawing and runs the given callback for every event and once per frame until the
+/// callback returns [`ControlFlow::Exit`]
+///
+/// The callback is given `Some(event)` as soon as each event occurs. It is also given `None`
+/// [`fps`] times per second, which is a good time to update and draw the next frame of an
+/// animation or game. If the callback takes longer than a frame, the next frame starts right away.
+/// Waiting for the next event or frame does not use up any CPU.
+///
+/// This is an alternative to writing a loop with [`Drawing::poll_event()`] yourself. Just like
+/// [`run()`], the window is run on the main thread and the callback is run on another thread, so
+/// this must be called from the main thread and no other process is ever started. It calls
+/// [`start()`] for you.
+///
+/// Returning [`ControlFlow::Exit`] closes the window and returns from `run_with()`. If the
+/// window is closed first, `run_with()` returns without calling the callback again.
+///
+/// ```rust,no_run
+/// use turtle::{run_with, Config, ControlFlow, Event, event::{Key, PressedState}};
+///
+/// fn main() {
+///     let mut turtle = None;
+///     run_with(Config::default(), move |drawing, event| {
+///         let turtle = turtle.get_or_insert_with(|| drawing.add_turtle());
+///
+///         match event {
+///             Some(Event::Key(Key::Esc, PressedState::Pressed)) => return ControlFlow::Exit,
+///             Some(Event::Key(Key::LeftArrow, PressedState::Pressed)) => turtle.left(15.0),
+///             Some(Event::Key(Key::RightArrow, PressedState::Pressed)) => turtle.right(15.0),
+///             Some(_) => {},
+///             // Once per frame, keep moving
+///             None => turtle.forward(2.0),
+///         }
+///
+///         ControlFlow::Continue
+///     });
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Panics if `config.fps` is not greater than zero, or if this is not called on the main thread.
+/// If the callback panics, that panic is propagated once the window has been closed.
+///
+/// [`ControlFlow::Exit`]: enum.ControlFlow.html#variant.Exit
+/// [`fps`]: struct.Config.html#structfield.fps
+/// [`Drawing::poll_event()`]: struct.Drawing.html#method.poll_event
+/// [`run()`]: fn.run.html
+/// [`start()`]: fn.start.html
+pub fn run_with<F>(config: Config, mut callback: F)
+    where F: FnMut(&mut Drawing, Option<Event>) -> ControlFlow + Send + 'static,
+{
+    let Config {title, size, background, fps} = config;
+    assert!(fps.is_finite() && fps > 0.0, "Invalid frame rate: {}", fps);
+    let frame_duration = Duration::from_secs_f64(1.0 / fps);
+
+    run_client(move |client| {
+        // The events get their own client so that waiting for them never holds up the drawing
+        let mut events = EventStream::new(block_on(client.split()));
+        let mut drawing = Drawing::from(AsyncDrawing::with_client(client));
+        drawing.set_title(title);
+        drawing.set_size(size);
+        drawing.set_background_color(background);
+
+        let mut next_frame = Instant::now();
+        loop {
+            let wake = block_on(async {
+                tokio::select! {
+                    event = events.next() => Wake::Event(event),
+                    _ = time::delay_until(next_frame) => Wake::Frame,
+                }
+            });
+
+            let flow = match wake {
+                Wake::Event(Some(event)) => callback(&mut drawing, Some(event)),
+                // The window was closed
+                Wake::Event(None) => return,
+                Wake::Frame => {
+                    next_frame = Instant::now() + frame_duration;
+                    callback(&mut drawing, None)
+                },
+            };
+
+            if flow == ControlFlow::Exit {
+                // The window closes as soon as this thread stops using it
+                drawing.set_disconnect_behavior(DisconnectBehavior {
+                    close_after: Some(Duration::from_secs(0)),
+                    show_finished: false,
+                });
+                return;
+            }
+        }
+    });
+}
+
+/// The reason that `run_with()` stopped waiting
+enum Wake {
+    /// An event occurred, or `None` if the window was closed
+    Event(Option<Event>),
+    /// It is time for the next frame
+    Frame,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn runs_until_exit() {
+        let frames = Arc::new(AtomicUsize::new(0));
+        let counted = frames.clone();
+        run_with(Config {fps: 1000.0, size: Size {width: 300, height: 200}, ..Config::default()}, move |drawing, event| {
+            assert_eq!(drawing.size(), Size {width: 300, height: 200});
+            assert_ne!(std::thread::current().name(), Some("main"));
+            if event.is_none() {
+                counted.fetch_add(1, Ordering::SeqCst);
+            }
+
+            if counted.load(Ordering::SeqCst) == 3 { ControlFlow::Exit } else { ControlFlow::Continue }
+        });
+
+        assert_eq!(frames.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid frame rate: 0")]
+    fn rejects_zero_fps() {
+        run_with(Config {fps: 0.0, ..Config::default()}, |_, _| ControlFlow::Exit);
+    }
+}
@@ -0,0 +1,120 @@
+use std::f64::consts::{PI, FRAC_PI_2};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{Point, ImageHandle};
+use crate::radians::Radians;
+
+/// The number of points used to draw the circle shape
+const CIRCLE_POINTS: usize = 32;
+
+/// The shape that the turtle is drawn with in the window
+///
+/// Each shape is drawn with the turtle at its center and rotates as the turtle turns. Shapes that
+/// are not symmetric, like [`Classic`](#variant.Classic) and [`Arrow`](#variant.Arrow), point in
+/// the direction that the turtle is facing. Use
+/// [`Turtle::set_shape_size()`](struct.Turtle.html#method.set_shape_size) to make any shape larger
+/// or smaller.
+///
+/// ```rust
+/// use turtle::{Turtle, Shape};
+///
+/// let mut turtle = Turtle::new();
+/// turtle.set_shape(Shape::Arrow);
+///
+/// // A diamond that is taller than it is wide
+/// turtle.set_shape(Shape::Polygon(vec![
+///     [0.0, 20.0].into(),
+///     [8.0, 0.0].into(),
+///     [0.0, -20.0].into(),
+///     [-8.0, 0.0].into(),
+/// ]));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    /// A triangle pointing in the direction the turtle is facing (default)
+    #[default]
+    Classic,
+    /// An arrowhead with a notch at the back, pointing in the direction the turtle is facing
+    Arrow,
+    /// A circle with a radius of 8 pixels
+    Circle,
+    /// A square that is 16 pixels wide
+    Square,
+    /// A custom polygon with the given points, in pixels relative to the turtle's position
+    ///
+    /// The points are given as if the turtle was facing up (its default heading), so a point with
+    /// a positive y coordinate is in front of the turtle and a point with a positive x coordinate is
+    /// to the right of it. There must be at least three points and every point must be finite.
+    Polygon(Vec<Point>),
+    /// An image loaded with [`Drawing::load_image()`](struct.Drawing.html#method.load_image)
+    ///
+    /// The image is drawn centered on the turtle with the top of the image facing in the direction
+    /// that the turtle is facing. Each pixel of the image is one pixel wide at a shape size of 1.
+    /// The turtle keeps its previous shape if the image was loaded into a different drawing.
+    Image(ImageHandle),
+}
+
+impl Shape {
+    /// Returns true if this shape can be drawn
+    pub(crate) fn is_valid(&self) -> bool {
+        match self {
+            Shape::Polygon(points) => points.len() >= 3 && points.iter().all(|point| point.is_finite()),
+            _ => true,
+        }
+    }
+
+    /// Returns the points around the outline of this shape, as if the turtle was facing up
+    pub(crate) fn outline(&self) -> Vec<Point> {
+        match self {
+            Shape::Classic => vec![
+                Point {x: -15.0, y: 0.0},
+                Point {x: 0.0, y: 10.0},
+                Point {x: 15.0, y: 0.0},
+            ],
+            Shape::Arrow => vec![
+                Point {x: 0.0, y: 12.0},
+                Point {x: 9.0, y: -8.0},
+                Point {x: 0.0, y: -3.0},
+                Point {x: -9.0, y: -8.0},
+            ],
+            Shape::Circle => (0..CIRCLE_POINTS).map(|i| {
+                let angle = 2.0 * PI * i as f64 / CIRCLE_POINTS as f64;
+                Point {x: 8.0 * angle.cos(), y: 8.0 * angle.sin()}
+            }).collect(),
+            Shape::Square => vec![
+                Point {x: -8.0, y: 8.0},
+                Point {x: 8.0, y: 8.0},
+                Point {x: 8.0, y: -8.0},
+                Point {x: -8.0, y: -8.0},
+            ],
+            Shape::Polygon(points) => points.clone(),
+            &Shape::Image(handle) => {
+                let x = handle.width() as f64 / 2.0;
+                let y = handle.height() as f64 / 2.0;
+                vec![
+                    Point {x: -x, y},
+                    Point {x, y},
+                    Point {x, y: -y},
+                    Point {x: -x, y: -y},
+                ]
+            },
+        }
+    }
+
+    /// Returns the points around the outline of this shape when drawn for a turtle at the given
+    /// position and heading, scaled by the given shape size
+    pub(crate) fn outline_at(&self, position: Point, heading: Radians, size: f64) -> Vec<Point> {
+        // Shapes face up by default, which is a quarter turn from a heading of zero
+        let rotation = heading.to_radians() - FRAC_PI_2;
+        let (sin, cos) = rotation.sin_cos();
+
+        self.outline().into_iter().map(|Point {x, y}| {
+            let (x, y) = (x * size, y * size);
+            Point {
+                x: cos * x - sin * y + position.x,
+                y: sin * x + cos * y + position.y,
+            }
+        }).collect()
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{Color, Point, Speed, Easing, Shape, FillRule, BlendMode, Size};
+
+/// A snapshot of the state of a turtle that can be saved and restored later
+///
+/// Use [`Turtle::state()`] to take a snapshot and [`Turtle::set_state()`] to restore it. This type
+/// implements `Serialize` and `Deserialize` from the [`serde`] crate, so it can be saved to a file
+/// (e.g. as JSON) and loaded again the next time your program runs.
+///
+/// The layer of the turtle is not included because layers only exist while the program that
+/// created them is running.
+///
+/// ```rust
+/// use turtle::{Turtle, TurtleState};
+///
+/// let mut turtle = Turtle::new();
+/// turtle.set_pen_color("blue");
+/// turtle.set_pen_size(3.0);
+/// turtle.forward(100.0);
+///
+/// // Save the state as JSON...
+/// let json = serde_json::to_string(&turtle.state()).unwrap();
+///
+/// // ...and then continue from where we left off with a new turtle
+/// let state: TurtleState = serde_json::from_str(&json).unwrap();
+/// let mut turtle = Turtle::new();
+/// turtle.set_state(&state);
+/// assert_eq!(turtle.pen_color(), "blue".into());
+/// assert_eq!(turtle.position().y.round(), 100.0);
+/// ```
+///
+/// [`Turtle::state()`]: struct.Turtle.html#method.state
+/// [`Turtle::set_state()`]: struct.Turtle.html#method.set_state
+/// [`serde`]: https://docs.rs/serde
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurtleState {
+    /// The position of the turtle
+    pub position: Point,
+    /// The heading of the turtle in degrees, even if the turtle is using radians
+    pub heading: f64,
+    /// The speed of the turtle
+    pub speed: Speed,
+    /// The easing applied to every movement and rotation of the turtle
+    pub easing: Easing,
+    /// The state of the pen of the turtle
+    pub pen: PenState,
+    /// The color used to fill shapes
+    pub fill_color: Color,
+    /// The rule used to decide which parts of a shape are filled
+    pub fill_rule: FillRule,
+    /// True if the turtle is visible
+    pub is_visible: bool,
+    /// The shape of the turtle
+    pub shape: Shape,
+    /// The amount that the shape of the turtle is scaled by
+    pub shape_size: f64,
+}
+
+/// A snapshot of the state of the pen of a turtle
+///
+/// See [`TurtleState`](struct.TurtleState.html) for more information.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PenState {
+    /// True if the pen is down
+    pub is_enabled: bool,
+    /// The thickness of the pen
+    pub thickness: f64,
+    /// The color of the pen
+    pub color: Color,
+    /// The lengths of the dashes and the gaps between them, empty if lines are solid
+    pub dash_pattern: Vec<f64>,
+    /// How the lines drawn with the pen combine with what is underneath them
+    pub blend_mode: BlendMode,
+}
+
+/// A snapshot of the configuration of a drawing that can be saved and restored later
+///
+/// Use [`Drawing::state()`] to take a snapshot and [`Drawing::set_state()`] to restore it. Like
+/// [`TurtleState`], this type implements `Serialize` and `Deserialize` from the [`serde`] crate.
+///
+/// Only the configuration of the drawing is included, not what has been drawn.
+///
+/// ```rust
+/// use turtle::{Drawing, DrawingState};
+///
+/// let mut drawing = Drawing::new();
+/// drawing.set_title("My Drawing");
+/// drawing.set_background_color("light blue");
+///
+/// let json = serde_json::to_string(&drawing.state()).unwrap();
+///
+/// let state: DrawingState = serde_json::from_str(&json).unwrap();
+/// drawing.set_title("Something Else");
+/// drawing.set_state(&state);
+/// assert_eq!(drawing.title(), "My Drawing");
+/// ```
+///
+/// [`Drawing::state()`]: struct.Drawing.html#method.state
+/// [`Drawing::set_state()`]: struct.Drawing.html#method.set_state
+/// [`TurtleState`]: struct.TurtleState.html
+/// [`serde`]: https://docs.rs/serde
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrawingState {
+    /// The title of the window
+    pub title: String,
+    /// The background color of the drawing
+    pub background: Color,
+    /// The point in the center of the view into the drawing
+    pub center: Point,
+    /// The zoom of the view into the drawing
+    pub zoom: f64,
+    /// The size of the drawing
+    pub size: Size,
+    /// True if the window is maximized
+    pub is_maximized: bool,
+    /// True if the window is fullscreen
+    pub is_fullscreen: bool,
+}
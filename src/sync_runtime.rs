@@ -3,16 +3,38 @@
 
 use std::future::Future;
 
+#[cfg(not(target_arch = "wasm32"))]
 use once_cell::sync::OnceCell;
 use tokio::runtime::Runtime;
 
 /// The global runtime, spawned in the background the first time it is used
 ///
 /// If this is never used, it will never spawn a runtime.
+#[cfg(not(target_arch = "wasm32"))]
 static RUNTIME: OnceCell<Runtime> = OnceCell::new();
 
+#[cfg(not(target_arch = "wasm32"))]
 pub fn block_on<F: Future>(future: F) -> F::Output {
     let runtime = RUNTIME.get_or_init(|| Runtime::new()
         .expect("unable to spawn tokio runtime"));
     runtime.handle().block_on(future)
 }
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    /// The runtime of the web page, which can only run tasks on the thread calling `block_on()`
+    /// since a web page cannot spawn threads
+    ///
+    /// Tasks only make progress while `block_on()` is running.
+    static RUNTIME: std::cell::RefCell<Runtime> = std::cell::RefCell::new(
+        tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .build()
+            .expect("unable to create tokio runtime")
+    );
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    RUNTIME.with(|runtime| runtime.borrow_mut().block_on(future))
+}
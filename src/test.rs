@@ -0,0 +1,294 @@
+//! Regression tests for drawings that compare them against reference images
+//!
+//! Each check draws a picture in a new drawing, renders it in software, and compares it against a
+//! PNG image saved from an earlier run. The rendering does not depend on the display or the GPU,
+//! so the same drawing always produces the same pixels on every machine.
+//!
+//! Reference images are only written when the `TURTLE_UPDATE_GOLDEN` environment variable is set.
+//! Run the tests with it set once to create the reference images, and again after any intentional
+//! change to update them. Without it, a missing reference image is an error so that a deleted or
+//! misspelled reference cannot make a check pass. If a drawing does not match (or its reference is
+//! missing), the image that was drawn is saved next to the reference (e.g. `square.actual.png` for
+//! `square.png`) so the two can be compared.
+//!
+//! This module is only available with the "headless" feature, since it relies on drawings not
+//! opening a window.
+//!
+//! ```rust,no_run
+//! use turtle::test::assert_golden;
+//!
+//! // Usually called from a function marked with #[test]
+//! assert_golden("tests/golden/square.png", |drawing| {
+//!     let mut turtle = drawing.add_turtle();
+//!     for _ in 0..4 {
+//!         turtle.forward(100.0);
+//!         turtle.right(90.0);
+//!     }
+//! });
+//! ```
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use thiserror::Error;
+
+use crate::{Drawing, Color, Size};
+use crate::renderer_server::ImageData;
+
+/// The environment variable that causes every check to replace its reference image
+const UPDATE_VAR: &str = "TURTLE_UPDATE_GOLDEN";
+
+/// Used to give each rendered image its own temporary file
+static NEXT_RENDER: AtomicUsize = AtomicUsize::new(0);
+
+/// Controls how drawings are rendered and how closely they must match their reference image
+///
+/// ```rust
+/// use turtle::test::GoldenOptions;
+///
+/// // Allow a few pixels to differ slightly, e.g. for drawings with many overlapping lines
+/// let options = GoldenOptions {
+///     tolerance: 8,
+///     max_differing_pixels: 10,
+///     ..GoldenOptions::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldenOptions {
+    /// The size of the drawing (and the rendered image) in pixels
+    pub size: Size,
+    /// The background color of the drawing
+    pub background: Color,
+    /// The largest difference allowed in each channel of a pixel, from 0 to 255
+    ///
+    /// Pixels that differ by more than this in their red, green, blue, or alpha value are counted
+    /// as different.
+    pub tolerance: u8,
+    /// The number of pixels that are allowed to differ by more than the tolerance
+    pub max_differing_pixels: usize,
+}
+
+impl Default for GoldenOptions {
+    /// Renders a 400x300 drawing on a white background and allows no pixels to differ by more
+    /// than 2 in any channel
+    fn default() -> Self {
+        Self {
+            size: Size {width: 400, height: 300},
+            background: "white".into(),
+            tolerance: 2,
+            max_differing_pixels: 0,
+        }
+    }
+}
+
+/// An error produced when a drawing does not match its reference image
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct GoldenError(String);
+
+/// Panics if the picture drawn by the given function does not match the reference image at the
+/// given path.
+///
+/// This uses the default [`GoldenOptions`]. See [`check_golden_with()`] for more information.
+///
+/// [`GoldenOptions`]: struct.GoldenOptions.html
+/// [`check_golden_with()`]: fn.check_golden_with.html
+#[track_caller]
+pub fn assert_golden<P, F>(reference: P, draw: F)
+    where P: AsRef<Path>,
+          F: FnOnce(&mut Drawing),
+{
+    if let Err(err) = check_golden_with(reference, &GoldenOptions::default(), draw) {
+        panic!("{}", err);
+    }
+}
+
+/// Returns an error if the picture drawn by the given function does not match the reference
+/// image at the given path.
+///
+/// This uses the default [`GoldenOptions`]. See [`check_golden_with()`] for more information.
+///
+/// [`GoldenOptions`]: struct.GoldenOptions.html
+/// [`check_golden_with()`]: fn.check_golden_with.html
+pub fn check_golden<P, F>(reference: P, draw: F) -> Result<(), GoldenError>
+    where P: AsRef<Path>,
+          F: FnOnce(&mut Drawing),
+{
+    check_golden_with(reference, &GoldenOptions::default(), draw)
+}
+
+/// Returns an error if the picture drawn by the given function does not match the reference
+/// image at the given path, using the given options.
+///
+/// The function is given a new drawing with the size and background color from the options and
+/// with animations disabled. Once it returns, the drawing is rendered the same way as
+/// [`Drawing::save_png()`], so the turtles themselves are not included.
+///
+/// If the `TURTLE_UPDATE_GOLDEN` environment variable is set, the rendered image is saved as the
+/// reference image instead. Otherwise, the reference image must exist, the rendered image must be
+/// the same size as it, and no more than `options.max_differing_pixels` pixels can differ by more
+/// than `options.tolerance`.
+///
+/// ```rust,no_run
+/// use turtle::test::{check_golden_with, GoldenOptions, GoldenError};
+///
+/// fn main() -> Result<(), GoldenError> {
+///     let options = GoldenOptions {
+///         background: "black".into(),
+///         ..GoldenOptions::default()
+///     };
+///
+///     check_golden_with("tests/golden/circle.png", &options, |drawing| {
+///         let mut turtle = drawing.add_turtle();
+///         turtle.set_pen_color("yellow");
+///         turtle.circle(100.0);
+///     })
+/// }
+/// ```
+///
+/// [`Drawing::save_png()`]: ../struct.Drawing.html#method.save_png
+pub fn check_golden_with<P, F>(reference: P, options: &GoldenOptions, draw: F) -> Result<(), GoldenError>
+    where P: AsRef<Path>,
+          F: FnOnce(&mut Drawing),
+{
+    let update = env::var_os(UPDATE_VAR).is_some();
+    check_golden_inner(reference.as_ref(), options, update, draw)
+}
+
+/// Checks the drawing against the reference image, or replaces the reference image if `update`
+/// is true
+fn check_golden_inner<F>(reference: &Path, options: &GoldenOptions, update: bool, draw: F) -> Result<(), GoldenError>
+    where F: FnOnce(&mut Drawing),
+{
+    let actual = render(options, draw)?;
+
+    if update {
+        return save(&actual, reference);
+    }
+
+    if !reference.exists() {
+        let actual_path = actual_path(reference);
+        save(&actual, &actual_path)?;
+        return Err(GoldenError(format!(
+            "the reference image {} does not exist (set {} to create it), the drawing was saved to {}",
+            reference.display(), UPDATE_VAR, actual_path.display(),
+        )));
+    }
+
+    let expected = ImageData::open(reference)
+        .map_err(|err| GoldenError(format!("unable to read reference image: {}", err)))?;
+
+    let mismatch = if (actual.width, actual.height) != (expected.width, expected.height) {
+        Some(format!(
+            "the drawing is {}x{} but the reference image {} is {}x{}",
+            actual.width, actual.height, reference.display(), expected.width, expected.height,
+        ))
+    } else {
+        let differing_pixels = count_differing_pixels(&actual, &expected, options.tolerance);
+        if differing_pixels > options.max_differing_pixels {
+            Some(format!(
+                "{} pixels of the drawing differ from the reference image {} (at most {} are allowed)",
+                differing_pixels, reference.display(), options.max_differing_pixels,
+            ))
+        } else {
+            None
+        }
+    };
+
+    match mismatch {
+        Some(mismatch) => {
+            let actual_path = actual_path(reference);
+            save(&actual, &actual_path)?;
+            Err(GoldenError(format!("{}, the drawing was saved to {}", mismatch, actual_path.display())))
+        },
+
+        None => Ok(()),
+    }
+}
+
+/// Draws a picture in a new drawing and renders it into an image
+fn render<F: FnOnce(&mut Drawing)>(options: &GoldenOptions, draw: F) -> Result<ImageData, GoldenError> {
+    let mut drawing = Drawing::new();
+    drawing.set_size(options.size);
+    drawing.set_background_color(options.background);
+    drawing.disable_animation();
+
+    draw(&mut drawing);
+
+    let render_id = NEXT_RENDER.fetch_add(1, Ordering::SeqCst);
+    let path = env::temp_dir().join(format!("turtle-golden-{}-{}.png", process::id(), render_id));
+    drawing.save_png(&path)
+        .map_err(|err| GoldenError(format!("unable to render drawing: {}", err)))?;
+
+    let image = ImageData::open(&path);
+    // A leftover temporary file is harmless, so there is no need to fail if it cannot be removed
+    fs::remove_file(&path).unwrap_or(());
+    image.map_err(|err| GoldenError(format!("unable to render drawing: {}", err)))
+}
+
+/// Writes the given image to a PNG file, creating any missing directories along the way
+fn save(image: &ImageData, path: &Path) -> Result<(), GoldenError> {
+    let write = || {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, image.encode_png())
+    };
+
+    write().map_err(|err| GoldenError(format!("unable to save {}: {}", path.display(), err)))
+}
+
+/// Returns the path used to save a drawing that did not match the given reference image
+fn actual_path(reference: &Path) -> PathBuf {
+    let name = reference.file_stem().unwrap_or_default().to_string_lossy();
+    reference.with_file_name(format!("{}.actual.png", name))
+}
+
+/// Returns the number of pixels where any channel differs by more than the given tolerance
+///
+/// Both images must be the same size.
+fn count_differing_pixels(actual: &ImageData, expected: &ImageData, tolerance: u8) -> usize {
+    actual.pixels.chunks(4).zip(expected.pixels.chunks(4))
+        .filter(|(a, b)| a.iter().zip(b.iter()).any(|(&a, &b)| a.abs_diff(b) > tolerance))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_against_reference_images() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let reference = dir.join("square.png");
+        let square = |drawing: &mut Drawing| {
+            let mut turtle = drawing.add_turtle();
+            for _ in 0..4 {
+                turtle.forward(100.0);
+                turtle.right(90.0);
+            }
+        };
+
+        // A missing reference image is only created when updating
+        let err = check_golden_inner(&reference, &GoldenOptions::default(), false, square).unwrap_err();
+        assert!(err.to_string().contains("does not exist"), "{}", err);
+        assert!(!reference.exists());
+        check_golden_inner(&reference, &GoldenOptions::default(), true, square).unwrap();
+        assert!(reference.exists());
+        check_golden(&reference, square).unwrap();
+
+        let err = check_golden(&reference, |drawing| {
+            drawing.add_turtle().forward(100.0);
+        }).unwrap_err();
+        assert!(err.to_string().contains("pixels of the drawing differ"), "{}", err);
+        assert!(dir.join("square.actual.png").exists());
+
+        let small = GoldenOptions {size: Size {width: 200, height: 100}, ..GoldenOptions::default()};
+        let err = check_golden_with(&reference, &small, square).unwrap_err();
+        assert!(err.to_string().contains("200x100"), "{}", err);
+    }
+}
@@ -0,0 +1,127 @@
+//! The clock and timers used throughout the crate
+//!
+//! These are the timers from tokio everywhere except in a web browser. A browser does not give
+//! `std` a clock (`std::time::Instant::now()` panics there) and tokio's timers rely on it, so the
+//! `wasm` feature reads the clock from JavaScript instead. Nothing can sleep in a web page, so a
+//! timer that has not finished yet just asks to be polled again until its deadline passes.
+
+pub use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use tokio::time::{Instant, delay_for, delay_until, interval, timeout};
+
+#[cfg(target_arch = "wasm32")]
+pub use self::browser::{Instant, delay_for, delay_until, interval, timeout};
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use std::ops::{Add, AddAssign, Sub};
+    use std::pin::Pin;
+    use std::future::Future;
+    use std::task::{Context, Poll};
+
+    use futures_util::future::{self, Either};
+
+    use super::Duration;
+
+    /// A point in time, measured from when the web page was loaded
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Instant(Duration);
+
+    impl Instant {
+        pub fn now() -> Self {
+            Instant(Duration::from_secs_f64(js_sys::Date::now() / 1000.0))
+        }
+
+        pub fn duration_since(&self, earlier: Instant) -> Duration {
+            self.0.checked_sub(earlier.0).unwrap_or_default()
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Instant::now().duration_since(*self)
+        }
+    }
+
+    impl Add<Duration> for Instant {
+        type Output = Instant;
+
+        fn add(self, duration: Duration) -> Instant {
+            Instant(self.0 + duration)
+        }
+    }
+
+    impl AddAssign<Duration> for Instant {
+        fn add_assign(&mut self, duration: Duration) {
+            self.0 += duration;
+        }
+    }
+
+    impl Sub<Instant> for Instant {
+        type Output = Duration;
+
+        fn sub(self, earlier: Instant) -> Duration {
+            self.duration_since(earlier)
+        }
+    }
+
+    /// A future that completes at the given deadline
+    #[derive(Debug)]
+    pub struct Delay {
+        deadline: Instant,
+    }
+
+    impl Future for Delay {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+            if Instant::now() >= self.deadline {
+                Poll::Ready(())
+            } else {
+                // There is no way to be woken up later, so this checks again the next time the
+                // runtime gets to this task
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    pub fn delay_until(deadline: Instant) -> Delay {
+        Delay {deadline}
+    }
+
+    pub fn delay_for(duration: Duration) -> Delay {
+        delay_until(Instant::now() + duration)
+    }
+
+    /// Returned by `timeout()` when the future did not complete in time
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Elapsed;
+
+    pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+        futures_util::pin_mut!(future);
+        match future::select(future, delay_for(duration)).await {
+            Either::Left((output, _)) => Ok(output),
+            Either::Right(((), _)) => Err(Elapsed),
+        }
+    }
+
+    /// Completes at the start of each period, beginning right away
+    #[derive(Debug)]
+    pub struct Interval {
+        next_tick: Instant,
+        period: Duration,
+    }
+
+    impl Interval {
+        pub async fn tick(&mut self) -> Instant {
+            let tick = self.next_tick;
+            delay_until(tick).await;
+            self.next_tick = tick + self.period;
+            tick
+        }
+    }
+
+    pub fn interval(period: Duration) -> Interval {
+        Interval {next_tick: Instant::now(), period}
+    }
+}
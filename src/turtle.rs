@@ -1,6 +1,6 @@
 use std::fmt::{self, Debug};
 
-use crate::{Color, Point, Speed, Distance, Angle};
+use crate::{TurtleState, Color, Point, Rect, Path, Speed, Easing, Shape, FillRule, BlendMode, Distance, Angle, TextAlign, ImageHandle, StampId, LayerId};
 use crate::async_turtle::AsyncTurtle;
 use crate::sync_runtime::block_on;
 
@@ -206,6 +206,29 @@ impl Turtle {
         block_on(self.turtle.wait(secs))
     }
 
+    /// Returns the number of animations of this turtle that have not finished playing yet.
+    ///
+    /// Each turtle plays one animation at a time and every movement command waits for its
+    /// animation to finish, so this is always either `0` or `1`. The same turtle will therefore
+    /// always see `0`. This is mostly useful with turtles (like those added with
+    /// [`Drawing::add_turtle()`]) whose commands are run on another thread.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// turtle.forward(100.0);
+    /// // The animation finished before `forward` returned
+    /// assert_eq!(turtle.pending_animations(), 0);
+    /// ```
+    ///
+    /// See also [`Drawing::is_idle()`] to check every turtle in the drawing at once.
+    ///
+    /// [`Drawing::add_turtle()`]: struct.Drawing.html#method.add_turtle
+    /// [`Drawing::is_idle()`]: struct.Drawing.html#method.is_idle
+    pub fn pending_animations(&self) -> usize {
+        block_on(self.turtle.pending_animations())
+    }
+
     /// Draw a circular arc starting at the current position and going to the left of the turtle,
     /// thus globally turning counterclockwise.
     ///
@@ -241,7 +264,7 @@ impl Turtle {
     /// // Full + quarter circle.
     /// turtle.reset();
     /// turtle.arc_left(100.0, 360.0 + 90.0);
-    /// assert!((turtle.position() - [-100.0, 100.0].into()).len() <= 2.5);
+    /// assert!((turtle.position() - [-100.0, 100.0].into()).len() <= 0.5);
     /// assert!((turtle.heading() - 180.0).abs() <= 0.1);
     ///
     /// // Negative radius: flip center to the right.
@@ -297,7 +320,7 @@ impl Turtle {
     /// // Full + quarter circle.
     /// turtle.reset();
     /// turtle.arc_right(100.0, 360.0 + 90.0);
-    /// assert!((turtle.position() - [100.0, 100.0].into()).len() <= 2.5);
+    /// assert!((turtle.position() - [100.0, 100.0].into()).len() <= 0.5);
     /// assert!(turtle.heading().abs().min((turtle.heading() - 360.0).abs()) <= 0.1);
     ///
     /// // Negative radius: flip center to the left.
@@ -318,6 +341,426 @@ impl Turtle {
         block_on(self.turtle.arc_right(radius, extent))
     }
 
+    /// Draw a circle by moving the turtle once around it, starting and ending at the current
+    /// position.
+    ///
+    /// The center of the circle is `radius` units to the left of the turtle, with respect to its
+    /// current orientation, so the turtle turns counterclockwise as it moves. When `radius` is
+    /// negative, the center is to the right instead and the turtle turns clockwise. The turtle ends
+    /// up with the same heading that it started with.
+    ///
+    /// Once the turtle is back where it started, the circle is drawn exactly instead of with
+    /// many tiny lines, so it stays smooth when the drawing is exported with
+    /// [`Drawing::save_svg()`](struct.Drawing.html#method.save_svg). To fill a circle, call
+    /// [`begin_fill()`](struct.Turtle.html#method.begin_fill) before drawing it like any other
+    /// shape, or use [`Drawing::draw_circle()`](struct.Drawing.html#method.draw_circle).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use turtle::Turtle;
+    /// let mut turtle = Turtle::new();
+    ///
+    /// turtle.circle(100.0);
+    /// assert!((turtle.position() - [0.0, 0.0].into()).len() < 1e-9);
+    /// assert!((turtle.heading() - 90.0).abs() < 1e-9);
+    ///
+    /// // A circle on the other side
+    /// turtle.circle(-100.0);
+    /// ```
+    pub fn circle(&mut self, radius: Distance) {
+        block_on(self.turtle.circle(radius))
+    }
+
+    /// Moves the turtle along a smooth curve from its current position to `end`. If the pen is
+    /// down, the turtle will draw the curve as it moves.
+    ///
+    /// The curve is a cubic Bézier curve: it leaves the current position heading towards
+    /// `control1` and arrives at `end` coming from the direction of `control2`. The curve does not
+    /// usually pass through either control point. The turtle turns as it moves so that it always
+    /// faces in the direction that the curve is going in. See the [`Point` struct](struct.Point.html)
+    /// documentation for more information about the points that can be passed in.
+    ///
+    /// The curve is drawn exactly, so it stays smooth when the drawing is exported with
+    /// [`Drawing::save_svg()`](struct.Drawing.html#method.save_svg).
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// // An S-shaped curve
+    /// turtle.bezier_to([100.0, 0.0], [-100.0, 200.0], [0.0, 200.0]);
+    /// assert_eq!(turtle.position(), Point {x: 0.0, y: 200.0});
+    /// // The curve arrives heading right, away from the last control point
+    /// assert!(turtle.heading().abs().min((turtle.heading() - 360.0).abs()) < 1e-9);
+    /// ```
+    pub fn bezier_to<P: Into<Point>>(&mut self, control1: P, control2: P, end: P) {
+        block_on(self.turtle.bezier_to(control1, control2, end))
+    }
+
+    /// Moves the turtle along a smooth curve from its current position to `end`, bending towards
+    /// the given control point. If the pen is down, the turtle will draw the curve as it moves.
+    ///
+    /// The curve is a quadratic Bézier curve. It leaves the current position heading towards the
+    /// control point and arrives at `end` coming from the direction of the control point. Just
+    /// like with [`bezier_to()`](struct.Turtle.html#method.bezier_to), the turtle always faces in
+    /// the direction that the curve is going in.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// // An arch over the control point
+    /// turtle.quad_bezier_to([100.0, 100.0], [200.0, 0.0]);
+    /// assert_eq!(turtle.position(), Point {x: 200.0, y: 0.0});
+    /// // The curve arrives heading down and to the right
+    /// assert!((turtle.heading() - 315.0).abs() < 1e-9);
+    /// ```
+    pub fn quad_bezier_to<P: Into<Point>>(&mut self, control: P, end: P) {
+        block_on(self.turtle.quad_bezier_to(control, end))
+    }
+
+    /// Moves the turtle along the given path in a single smooth motion. If the pen is down, the
+    /// turtle will draw the path as it moves.
+    ///
+    /// The path can be a list of points (like `&[Point]` or `Vec<Point>`) to move in straight
+    /// lines between, or a [`Path`](struct.Path.html) made of lines, arcs, and curves. Just like
+    /// with [`bezier_to()`](struct.Turtle.html#method.bezier_to), the turtle always faces in the
+    /// direction that it is moving in. The turtle moves at its current speed the entire time
+    /// instead of stopping to turn at every corner. The whole path is undone at once by
+    /// [`undo()`](struct.Turtle.html#method.undo).
+    ///
+    /// ```rust
+    /// use turtle::{Turtle, Path, Point};
+    ///
+    /// let mut turtle = Turtle::new();
+    /// // A triangle
+    /// turtle.follow_path(&[
+    ///     Point {x: 100.0, y: 0.0},
+    ///     Point {x: 50.0, y: 100.0},
+    ///     Point {x: 0.0, y: 0.0},
+    /// ]);
+    /// assert_eq!(turtle.position(), Point {x: 0.0, y: 0.0});
+    ///
+    /// // A half circle followed by a straight line back to the start
+    /// turtle.follow_path(Path::new()
+    ///     .arc_around([50.0, 0.0], -180.0)
+    ///     .line_to([0.0, 0.0]));
+    /// assert_eq!(turtle.position().round(), Point {x: 0.0, y: 0.0});
+    /// // The turtle is facing left, in the direction of the line
+    /// assert_eq!(turtle.heading().round(), 180.0);
+    /// ```
+    ///
+    /// Paths with points that are infinite or `NaN` are ignored.
+    pub fn follow_path<P: Into<Path>>(&mut self, path: P) {
+        block_on(self.turtle.follow_path(path))
+    }
+
+    /// Writes the given text at the turtle's current position in the color of its pen.
+    ///
+    /// The text starts at the turtle and sits on a baseline that passes through the turtle's
+    /// position. Text is always written upright no matter which way the turtle is facing, and the
+    /// turtle does not move. Each line of the text (separated by `\n`) is written below the
+    /// previous one. The text is written even if the pen is up.
+    ///
+    /// The text is written in the default sans-serif font with a size of 16 pixels. Use
+    /// [`write_with()`](struct.Turtle.html#method.write_with) to change the size or alignment of
+    /// the text. [`Drawing::measure_text()`](struct.Drawing.html#method.measure_text) can be used
+    /// to find out how much space the text will take up.
+    ///
+    /// Emoji (like `"🐢"`) are written in color using an emoji font installed on the system. If no
+    /// emoji font can be found, each emoji is written as the font's "missing character" box.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use turtle::Turtle;
+    /// let mut turtle = Turtle::new();
+    ///
+    /// turtle.set_pen_color("blue");
+    /// turtle.write("Hello, turtle!");
+    /// // Writing does not move the turtle
+    /// assert_eq!(turtle.position(), [0.0, 0.0].into());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if no font could be found on this system.
+    pub fn write(&mut self, text: &str) {
+        block_on(self.turtle.write(text))
+    }
+
+    /// Writes the given text at the turtle's current position in the color of its pen, using the
+    /// given font size (in pixels) and alignment.
+    ///
+    /// This works just like [`write()`](struct.Turtle.html#method.write), except that `align`
+    /// controls where each line of the text is placed relative to the turtle:
+    ///
+    /// * [`TextAlign::Left`](enum.TextAlign.html#variant.Left) - the text starts at the turtle
+    /// * [`TextAlign::Center`](enum.TextAlign.html#variant.Center) - the text is centered on the
+    ///   turtle
+    /// * [`TextAlign::Right`](enum.TextAlign.html#variant.Right) - the text ends at the turtle
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use turtle::{Turtle, TextAlign};
+    /// let mut turtle = Turtle::new();
+    ///
+    /// // A title centered above the turtle
+    /// turtle.pen_up();
+    /// turtle.forward(100.0);
+    /// turtle.write_with(32.0, TextAlign::Center, "Turtle Power");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `font_size` is not positive and finite, or if no font could be found on this
+    /// system.
+    pub fn write_with(&mut self, font_size: f64, align: TextAlign, text: &str) {
+        block_on(self.turtle.write_with(font_size, align, text))
+    }
+
+    /// Leaves a copy of the turtle's current shape on the canvas at its current position.
+    ///
+    /// The stamp looks exactly like the turtle does right now, including its
+    /// [shape](struct.Turtle.html#method.set_shape), size, and heading. The turtle does not move
+    /// and, just like with [`write()`](struct.Turtle.html#method.write), the stamp is drawn even
+    /// if the pen is up. The returned [`StampId`](struct.StampId.html) can be passed to
+    /// [`clear_stamp()`](struct.Turtle.html#method.clear_stamp) to remove the stamp again.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use turtle::Turtle;
+    /// let mut turtle = Turtle::new();
+    /// turtle.pen_up();
+    ///
+    /// // A trail of footprints
+    /// let mut stamps = Vec::new();
+    /// for _ in 0..5 {
+    ///     stamps.push(turtle.stamp());
+    ///     turtle.forward(30.0);
+    /// }
+    ///
+    /// // Remove the oldest footprint
+    /// turtle.clear_stamp(stamps[0]);
+    /// ```
+    pub fn stamp(&mut self) -> StampId {
+        block_on(self.turtle.stamp())
+    }
+
+    /// Draws the given image on the canvas, centered at the turtle's current position.
+    ///
+    /// The image is placed exactly where the turtle would be drawn if the image was its
+    /// [shape](enum.Shape.html#variant.Image): the top of the image faces in the direction that
+    /// the turtle is facing and the image is scaled by the turtle's
+    /// [shape size](struct.Turtle.html#method.set_shape_size). The turtle does not move and, just
+    /// like with [`write()`](struct.Turtle.html#method.write), the image is drawn even if the pen
+    /// is up. Images are loaded using
+    /// [`Drawing::load_image()`](struct.Drawing.html#method.load_image).
+    ///
+    /// Just like with [`stamp()`](struct.Turtle.html#method.stamp), the returned
+    /// [`StampId`](struct.StampId.html) can be used to remove the image again.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use turtle::Drawing;
+    /// let mut drawing = Drawing::new();
+    /// let flower = drawing.load_image("flower.png").expect("unable to load image");
+    ///
+    /// let mut turtle = drawing.add_turtle();
+    /// turtle.pen_up();
+    /// // A ring of flowers, each facing away from the center
+    /// for _ in 0..12 {
+    ///     turtle.forward(150.0);
+    ///     turtle.stamp_image(flower);
+    ///     turtle.backward(150.0);
+    ///     turtle.right(30.0);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the image was loaded into a different drawing. Use
+    /// [`try_stamp_image()`](struct.Turtle.html#method.try_stamp_image) to get an error instead.
+    pub fn stamp_image(&mut self, image: ImageHandle) -> StampId {
+        block_on(self.turtle.stamp_image(image))
+    }
+
+    /// Draws the given image on the canvas, or returns an error if the window was unable to draw
+    /// it.
+    ///
+    /// This is the same as [`stamp_image()`](struct.Turtle.html#method.stamp_image), except that
+    /// it returns [`Error::UnknownImage`] instead of panicking if the image was loaded into a
+    /// different drawing.
+    ///
+    /// ```rust,no_run
+    /// # use turtle::Drawing;
+    /// let mut drawing = Drawing::new();
+    /// let flower = drawing.load_image("flower.png").expect("unable to load image");
+    ///
+    /// let mut other = Drawing::new();
+    /// let mut turtle = other.add_turtle();
+    /// // The image was loaded into a different drawing
+    /// assert!(turtle.try_stamp_image(flower).is_err());
+    /// ```
+    ///
+    /// [`Error::UnknownImage`]: enum.Error.html#variant.UnknownImage
+    pub fn try_stamp_image(&mut self, image: ImageHandle) -> Result<StampId, crate::Error> {
+        block_on(self.turtle.try_stamp_image(image))
+    }
+
+    /// Removes the given stamp created by this turtle from the canvas.
+    ///
+    /// Nothing happens if the stamp has already been removed, for example by
+    /// [`clear_stamps()`](struct.Turtle.html#method.clear_stamps) or by clearing the turtle's
+    /// drawings. Stamps created by other turtles are not affected.
+    ///
+    /// See [`stamp()`](struct.Turtle.html#method.stamp) for an example.
+    pub fn clear_stamp(&mut self, stamp: StampId) {
+        self.turtle.clear_stamp(stamp)
+    }
+
+    /// Removes every stamp created by this turtle from the canvas.
+    ///
+    /// Only stamps are removed. The lines and shapes drawn by the turtle are left as they are.
+    ///
+    /// ```rust
+    /// # use turtle::Turtle;
+    /// let mut turtle = Turtle::new();
+    /// turtle.stamp();
+    /// turtle.forward(100.0);
+    /// turtle.stamp();
+    ///
+    /// // Only the line is left
+    /// turtle.clear_stamps();
+    /// ```
+    pub fn clear_stamps(&mut self) {
+        self.turtle.clear_stamps()
+    }
+
+    /// Undoes the most recent thing the turtle did.
+    ///
+    /// Movements (including arcs, circles, and curves), rotations, [written text], and [stamps]
+    /// can be undone. Undoing removes anything that was drawn and moves the turtle back to the
+    /// position and heading it had before. If the turtle was filling a shape, the fill goes back
+    /// to the shape it had before as well. Nothing happens if there is nothing left to undo.
+    ///
+    /// Other changes, like setting the pen color or the speed, are not undone. The last 1000
+    /// commands can be undone, but only since the turtle's drawings were last
+    /// [cleared](struct.Turtle.html#method.clear).
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// turtle.forward(100.0);
+    /// turtle.right(90.0);
+    /// turtle.forward(50.0);
+    ///
+    /// // Oops, that was supposed to be a left turn
+    /// turtle.undo();
+    /// turtle.undo();
+    /// assert_eq!(turtle.position().round(), Point {x: 0.0, y: 100.0});
+    /// assert_eq!(turtle.heading(), 90.0);
+    /// turtle.left(90.0);
+    /// turtle.forward(50.0);
+    /// ```
+    ///
+    /// [written text]: struct.Turtle.html#method.write
+    /// [stamps]: struct.Turtle.html#method.stamp
+    pub fn undo(&mut self) {
+        self.undo_n(1)
+    }
+
+    /// Undoes the given number of the most recent things the turtle did.
+    ///
+    /// This is the same as calling [`undo()`](struct.Turtle.html#method.undo) `count` times. If
+    /// there are fewer than `count` things to undo, everything that can be undone is undone.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// for _ in 0..4 {
+    ///     turtle.forward(100.0);
+    ///     turtle.right(90.0);
+    /// }
+    ///
+    /// // Undo the last two sides of the square
+    /// turtle.undo_n(4);
+    /// assert_eq!(turtle.position().round(), Point {x: 100.0, y: 100.0});
+    /// ```
+    pub fn undo_n(&mut self, count: usize) {
+        self.turtle.undo_n(count)
+    }
+
+    /// Saves the turtle's position, heading, pen, and fill color so that they can be restored
+    /// later using [`pop_state()`](struct.Turtle.html#method.pop_state).
+    ///
+    /// Saved states are kept on a stack: each call to `pop_state()` restores the state from the
+    /// most recent call to `push_state()` that has not been popped yet. This makes drawings that
+    /// branch, like trees and L-systems, much simpler since each branch can return to exactly
+    /// where it started.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// fn branch(turtle: &mut Turtle, length: f64) {
+    ///     if length < 10.0 {
+    ///         return;
+    ///     }
+    ///
+    ///     turtle.forward(length);
+    ///     for &angle in &[-30.0, 30.0] {
+    ///         turtle.push_state();
+    ///         turtle.right(angle);
+    ///         branch(turtle, length * 0.6);
+    ///         turtle.pop_state();
+    ///     }
+    /// }
+    ///
+    /// let mut turtle = Turtle::new();
+    /// branch(&mut turtle, 100.0);
+    /// // Only the trunk moved the turtle, since every branch was undone by popping
+    /// assert_eq!(turtle.position().round(), Point {x: 0.0, y: 100.0});
+    /// ```
+    pub fn push_state(&mut self) {
+        self.turtle.push_state()
+    }
+
+    /// Restores the state that was most recently saved with
+    /// [`push_state()`](struct.Turtle.html#method.push_state).
+    ///
+    /// The turtle jumps back to its saved position and heading instantly without drawing
+    /// anything, even if its pen is down. Its pen (whether it is down, its size, and its color) and
+    /// fill color are restored as well. Other settings, like the speed and whether the turtle is
+    /// visible, are not changed.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// turtle.push_state();
+    ///
+    /// turtle.set_pen_color("red");
+    /// turtle.set_pen_size(5.0);
+    /// turtle.right(45.0);
+    /// turtle.forward(100.0);
+    ///
+    /// turtle.pop_state();
+    /// assert_eq!(turtle.position(), Point::origin());
+    /// assert_eq!(turtle.heading(), 90.0);
+    /// assert_eq!(turtle.pen_color(), "black".into());
+    /// assert_eq!(turtle.pen_size(), 1.0);
+    /// ```
+    ///
+    /// Restoring the position can be undone with [`undo()`](struct.Turtle.html#method.undo) just
+    /// like any other movement.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no saved states left to restore.
+    pub fn pop_state(&mut self) {
+        block_on(self.turtle.pop_state())
+    }
+
     pub(crate) fn into_async(self) -> AsyncTurtle {
         self.turtle
     }
@@ -382,6 +825,187 @@ impl Turtle {
         self.turtle.set_speed(speed)
     }
 
+    /// Returns the easing used for the turtle's movement and rotation animations.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// assert_eq!(turtle.easing(), Easing::Linear);
+    /// turtle.set_easing(Easing::EaseOut);
+    /// assert_eq!(turtle.easing(), Easing::EaseOut);
+    /// ```
+    pub fn easing(&self) -> Easing {
+        block_on(self.turtle.easing())
+    }
+
+    /// Sets how the speed of the turtle changes over the course of each movement or rotation.
+    ///
+    /// The easing is used for every animation of the turtle from now on, including arcs and
+    /// curves. It does not change how long each animation takes, which is still decided by the
+    /// [speed](struct.Turtle.html#method.set_speed) of the turtle. See the
+    /// [`Easing` enum](enum.Easing.html) for the easings that are available.
+    ///
+    /// ```rust,no_run
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// turtle.set_speed("slow");
+    /// // Glide smoothly from one corner of the square to the next
+    /// turtle.set_easing(Easing::EaseInOut);
+    /// for _ in 0..4 {
+    ///     turtle.forward(100.0);
+    ///     turtle.right(90.0);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the easing is a custom curve with a control point that is not between 0.0 and
+    /// 1.0.
+    pub fn set_easing(&mut self, easing: Easing) {
+        self.turtle.set_easing(easing)
+    }
+
+    /// Runs the given function with the turtle's speed temporarily set to the given value. The
+    /// previous speed is restored once the function returns.
+    ///
+    /// This is useful for running a few commands at a different speed without having to remember
+    /// to set the speed back afterwards. The value returned by the function is passed along.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// turtle.set_speed("normal");
+    ///
+    /// // Jump to the starting position without waiting for an animation
+    /// turtle.with_speed(Speed::instant(), |turtle| {
+    ///     turtle.pen_up();
+    ///     turtle.go_to([-200.0, 0.0]);
+    ///     turtle.pen_down();
+    /// });
+    ///
+    /// assert_eq!(turtle.speed(), Speed::from("normal"));
+    /// turtle.forward(100.0);
+    /// ```
+    ///
+    /// If the function panics, the speed is not restored.
+    pub fn with_speed<S, F, R>(&mut self, speed: S, f: F) -> R
+        where S: Into<Speed>,
+              F: FnOnce(&mut Self) -> R,
+    {
+        let prev_speed = self.speed();
+        self.set_speed(speed);
+        let result = f(self);
+        self.set_speed(prev_speed);
+        result
+    }
+
+    /// Moves the turtle forward by the given distance at the given speed, without changing the
+    /// turtle's speed for any of the commands that come after.
+    ///
+    /// This is the same as [`forward()`](#method.forward) in every other way.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// turtle.set_speed("slow");
+    /// turtle.forward_at(100.0, "fast");
+    /// assert_eq!(turtle.speed(), Speed::from("slow"));
+    /// # assert_eq!(turtle.position().round(), Point {x: 0.0, y: 100.0});
+    /// ```
+    pub fn forward_at<S: Into<Speed>>(&mut self, distance: Distance, speed: S) {
+        block_on(self.turtle.forward_at(distance, speed))
+    }
+
+    /// Moves the turtle forward by the given distance without any animation, no matter what the
+    /// turtle's speed is set to.
+    ///
+    /// This is useful for drawing setup strokes (like a frame around the drawing) before the rest
+    /// of the drawing is animated. It is the same as calling
+    /// [`forward_at()`](#method.forward_at) with [`Speed::instant()`]. To run several commands
+    /// without animation, use [`with_speed()`](#method.with_speed).
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// turtle.set_speed("slowest");
+    ///
+    /// // Draw a square frame instantly
+    /// for _ in 0..4 {
+    ///     turtle.forward_instant(200.0);
+    ///     turtle.with_speed(Speed::instant(), |turtle| turtle.right(90.0));
+    /// }
+    ///
+    /// // Everything else is still animated
+    /// assert_eq!(turtle.speed(), Speed::from("slowest"));
+    /// ```
+    ///
+    /// [`Speed::instant()`]: struct.Speed.html#method.instant
+    pub fn forward_instant(&mut self, distance: Distance) {
+        block_on(self.turtle.forward_instant(distance))
+    }
+
+    /// Moves the turtle forward by the given distance so that the movement takes exactly the
+    /// given amount of time, no matter what the turtle's speed is set to.
+    ///
+    /// This is useful for keeping several movements in sync or timing a drawing to music. Use a
+    /// negative distance to move backwards. The turtle's speed is not changed for any of the
+    /// commands that come after. Like every other animation, the movement is still instant if the
+    /// turtle's speed is [`instant`] or if animations have been turned off for the drawing.
+    ///
+    /// This is the same as [`forward()`](#method.forward) in every other way.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut turtle = Turtle::new();
+    /// // Both lines take half a second to draw, even though one is much longer
+    /// turtle.forward_in(10.0, Duration::from_millis(500));
+    /// turtle.forward_in(300.0, Duration::from_millis(500));
+    /// # assert_eq!(turtle.position().round(), Point {x: 0.0, y: 310.0});
+    /// ```
+    ///
+    /// [`instant`]: struct.Speed.html#method.instant
+    pub fn forward_in(&mut self, distance: Distance, duration: std::time::Duration) {
+        block_on(self.turtle.forward_in(distance, duration))
+    }
+
+    /// Rotates the turtle to the right (clockwise) by the given angle so that the rotation takes
+    /// exactly the given amount of time, no matter what the turtle's speed is set to.
+    ///
+    /// This is the same as [`right()`](#method.right) in every other way. See
+    /// [`forward_in()`](#method.forward_in) for more information.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut turtle = Turtle::new();
+    /// turtle.right_in(180.0, Duration::from_secs(1));
+    /// assert_eq!(turtle.heading().round(), 270.0);
+    /// ```
+    pub fn right_in(&mut self, angle: Angle, duration: std::time::Duration) {
+        block_on(self.turtle.right_in(angle, duration))
+    }
+
+    /// Rotates the turtle to the left (counterclockwise) by the given angle so that the rotation
+    /// takes exactly the given amount of time, no matter what the turtle's speed is set to.
+    ///
+    /// This is the same as [`left()`](#method.left) in every other way. See
+    /// [`forward_in()`](#method.forward_in) for more information.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut turtle = Turtle::new();
+    /// turtle.left_in(45.0, Duration::from_millis(250));
+    /// assert_eq!(turtle.heading().round(), 135.0);
+    /// ```
+    pub fn left_in(&mut self, angle: Angle, duration: std::time::Duration) {
+        block_on(self.turtle.left_in(angle, duration))
+    }
+
     /// Returns the turtle's current location (x, y)
     ///
     /// ```rust
@@ -497,7 +1121,7 @@ impl Turtle {
     /// [`use_radians()`](struct.Turtle.html#method.use_radians) methods.
     ///
     /// The turtle will attempt to rotate as little as possible in order to reach the given heading
-    /// (between -180 and 179 degrees).
+    /// (more than -180 and at most 180 degrees).
     /// Use [`set_speed()`](struct.Turtle.html#method.set_speed) to control the animation speed.
     ///
     /// Here are some common directions in degrees and radians:
@@ -667,24 +1291,154 @@ impl Turtle {
     ///     turtle.set_pen_size(1.0);
     ///     turtle.forward(200.0);
     ///
-    ///     turtle.set_pen_color("#f44336"); // red
-    ///     turtle.set_pen_size(50.0);
-    ///     turtle.forward(200.0);
+    ///     turtle.set_pen_color("#f44336"); // red
+    ///     turtle.set_pen_size(50.0);
+    ///     turtle.forward(200.0);
+    ///
+    ///     turtle.set_pen_color("#4CAF50"); // green
+    ///     turtle.set_pen_size(100.0);
+    ///     turtle.forward(200.0);
+    /// }
+    /// ```
+    ///
+    /// This will produce the following:
+    ///
+    /// ![turtle pen thickness](https://github.com/sunjay/turtle/raw/9240f8890d1032a0033ec5c5338a10ffa942dc21/docs/assets/images/docs/pen_thickness.png)
+    ///
+    /// Notice that while the turtle travels in a straight line, it produces different thicknesses
+    /// of lines which appear like large rectangles.
+    pub fn set_pen_size(&mut self, thickness: f64) {
+        self.turtle.set_pen_size(thickness)
+    }
+
+    /// Sets the thickness of the pen to the given physical size in millimeters.
+    ///
+    /// The thickness is converted to pixels using the
+    /// [DPI of the drawing](struct.Drawing.html#method.set_dpi). Changing the DPI afterwards does
+    /// not change the thickness of the pen.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// drawing.set_dpi(254.0);
+    ///
+    /// let mut turtle = drawing.add_turtle();
+    /// turtle.set_pen_size_mm(0.5);
+    /// assert_eq!(turtle.pen_size().round(), 5.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thickness is negative or not finite.
+    pub fn set_pen_size_mm(&mut self, thickness: f64) {
+        block_on(self.turtle.set_pen_size_mm(thickness))
+    }
+
+    /// Returns the lengths of the dashes and gaps that the pen draws lines with.
+    ///
+    /// The pattern is empty if lines are solid, which is the default. See
+    /// [`set_pen_dash_pattern()`](struct.Turtle.html#method.set_pen_dash_pattern) for more
+    /// information.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// assert!(turtle.pen_dash_pattern().is_empty());
+    /// turtle.set_pen_dash_pattern(&[10.0, 5.0]);
+    /// assert_eq!(turtle.pen_dash_pattern(), vec![10.0, 5.0]);
+    /// ```
+    pub fn pen_dash_pattern(&self) -> Vec<f64> {
+        block_on(self.turtle.pen_dash_pattern())
+    }
+
+    /// Sets the pattern of dashes and gaps that the pen draws lines with.
+    ///
+    /// The pattern lists the length of each dash followed by the length of the gap after it, in
+    /// pixels, and repeats for as long as the line goes on. A pattern with an odd number of
+    /// lengths is repeated twice, so `[5.0]` draws 5 pixel dashes with 5 pixel gaps. A dash with
+    /// a length of zero draws a dot as wide as the pen. Pass an empty pattern to go back to
+    /// drawing solid lines.
+    ///
+    /// The pattern carries on from one line to the next as the turtle moves, so the dashes stay
+    /// evenly spaced around corners, arcs, and circles. It starts over whenever the turtle moves
+    /// without drawing or the pattern is changed.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    ///
+    /// // A dashed square
+    /// turtle.set_pen_dash_pattern(&[10.0, 5.0]);
+    /// for _ in 0..4 {
+    ///     turtle.forward(100.0);
+    ///     turtle.right(90.0);
+    /// }
+    ///
+    /// // A dotted circle
+    /// turtle.set_pen_size(4.0);
+    /// turtle.set_pen_dash_pattern(&[0.0, 8.0]);
+    /// turtle.circle(50.0);
+    ///
+    /// // Back to solid lines
+    /// turtle.set_pen_dash_pattern(&[]);
+    /// turtle.forward(100.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the lengths is negative or not finite, or if every length is zero.
+    pub fn set_pen_dash_pattern(&mut self, pattern: &[f64]) {
+        self.turtle.set_pen_dash_pattern(pattern)
+    }
+
+    /// Returns the way that the lines drawn by the pen combine with what has already been drawn.
+    ///
+    /// See the [`BlendMode` enum](enum.BlendMode.html) for more information.
     ///
-    ///     turtle.set_pen_color("#4CAF50"); // green
-    ///     turtle.set_pen_size(100.0);
-    ///     turtle.forward(200.0);
-    /// }
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// assert_eq!(turtle.pen_blend_mode(), BlendMode::Alpha);
+    /// turtle.set_pen_blend_mode(BlendMode::Multiply);
+    /// assert_eq!(turtle.pen_blend_mode(), BlendMode::Multiply);
     /// ```
+    pub fn pen_blend_mode(&self) -> BlendMode {
+        block_on(self.turtle.pen_blend_mode())
+    }
+
+    /// Sets the way that the lines drawn by the pen combine with what has already been drawn.
     ///
-    /// This will produce the following:
+    /// Each line keeps the blend mode that it was drawn with, so changing the blend mode only
+    /// affects the lines drawn afterwards. Lines drawn with [`BlendMode::Additive`] get brighter
+    /// where they overlap, which makes a glowing effect on a dark background. Lines drawn with
+    /// [`BlendMode::Multiply`] get darker where they overlap, and lines drawn with
+    /// [`BlendMode::Erase`] remove what is underneath them.
     ///
-    /// ![turtle pen thickness](https://github.com/sunjay/turtle/raw/9240f8890d1032a0033ec5c5338a10ffa942dc21/docs/assets/images/docs/pen_thickness.png)
+    /// The blend mode applies to lines and curves, including circles drawn by moving the turtle.
+    /// Fills, text, and stamps are always drawn normally. When the drawing is exported as a mesh
+    /// or for a plotter, lines that erase are left out and every other line is drawn normally.
     ///
-    /// Notice that while the turtle travels in a straight line, it produces different thicknesses
-    /// of lines which appear like large rectangles.
-    pub fn set_pen_size(&mut self, thickness: f64) {
-        self.turtle.set_pen_size(thickness)
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    /// drawing.set_background_color("black");
+    ///
+    /// // Overlapping circles glow where they cross
+    /// turtle.set_pen_size(10.0);
+    /// turtle.set_pen_blend_mode(BlendMode::Additive);
+    /// for &color in &["#800000", "#008000", "#000080"] {
+    ///     turtle.set_pen_color(color);
+    ///     turtle.circle(60.0);
+    ///     turtle.right(120.0);
+    /// }
+    /// ```
+    ///
+    /// [`BlendMode::Additive`]: enum.BlendMode.html#variant.Additive
+    /// [`BlendMode::Multiply`]: enum.BlendMode.html#variant.Multiply
+    /// [`BlendMode::Erase`]: enum.BlendMode.html#variant.Erase
+    pub fn set_pen_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.turtle.set_pen_blend_mode(blend_mode)
     }
 
     /// Returns the color of the pen.
@@ -734,6 +1488,38 @@ impl Turtle {
         self.turtle.set_pen_color(color)
     }
 
+    /// Gradually changes the color of the pen to the given color over the given amount of time.
+    ///
+    /// This returns right away so the turtle can keep drawing while the color changes. Each line
+    /// uses the color of the pen at the moment the turtle starts drawing it, so drawing many short
+    /// lines gives a smooth gradient. The color changes in a straight line from the current pen
+    /// color, just like [`Color::lerp()`]. Setting the pen color (or resetting the turtle) stops
+    /// the change. Like every other animation, the color changes right away if animations have
+    /// been turned off for the drawing.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut turtle = Turtle::new();
+    /// turtle.set_pen_color("blue");
+    /// // The pen turns from blue to red as the turtle draws a circle
+    /// turtle.animate_pen_color("red", Duration::from_secs(3));
+    /// for _ in 0..360 {
+    ///     turtle.forward(2.0);
+    ///     turtle.right(1.0);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the color is not valid.
+    ///
+    /// [`Color::lerp()`]: struct.Color.html#method.lerp
+    pub fn animate_pen_color<C: Into<Color> + Copy + Debug>(&mut self, color: C, duration: std::time::Duration) {
+        self.turtle.animate_pen_color(color, duration)
+    }
+
     /// Returns the current fill color.
     ///
     /// This will be used to fill the shape when
@@ -767,6 +1553,39 @@ impl Turtle {
         self.turtle.set_fill_color(color)
     }
 
+    /// Returns the rule used to decide which parts of a filled shape are inside of it.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// # let mut turtle = Turtle::new();
+    /// assert_eq!(turtle.fill_rule(), FillRule::NonZero);
+    /// turtle.set_fill_rule(FillRule::EvenOdd);
+    /// assert_eq!(turtle.fill_rule(), FillRule::EvenOdd);
+    /// ```
+    ///
+    /// See the [`FillRule` enum](enum.FillRule.html) for more information.
+    pub fn fill_rule(&self) -> FillRule {
+        block_on(self.turtle.fill_rule())
+    }
+
+    /// Sets the rule used to decide which parts of a filled shape are inside of it.
+    ///
+    /// The rule only matters for shapes whose outline crosses over itself, like a star or a
+    /// spirograph. With the default [`FillRule::NonZero`](enum.FillRule.html#variant.NonZero),
+    /// every area enclosed by the outline is filled. With
+    /// [`FillRule::EvenOdd`](enum.FillRule.html#variant.EvenOdd), areas that the outline goes
+    /// around an even number of times are left unfilled.
+    ///
+    /// **Note:** Changing the fill rule after calling `begin_fill` will cause the filled shape to
+    /// update to the new rule.
+    ///
+    /// # Example
+    ///
+    /// See the [`FillRule` enum](enum.FillRule.html) for an example.
+    pub fn set_fill_rule(&mut self, fill_rule: FillRule) {
+        self.turtle.set_fill_rule(fill_rule)
+    }
+
     /// Return true if the turtle is currently filling the shape drawn
     /// by its movements.
     ///
@@ -888,6 +1707,179 @@ impl Turtle {
         self.turtle.show()
     }
 
+    /// Returns the shape that the turtle is drawn with.
+    ///
+    /// See [`Shape`](enum.Shape.html) for the shapes that are available.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// assert_eq!(turtle.shape(), Shape::Classic);
+    /// turtle.set_shape(Shape::Circle);
+    /// assert_eq!(turtle.shape(), Shape::Circle);
+    /// ```
+    pub fn shape(&self) -> Shape {
+        block_on(self.turtle.shape())
+    }
+
+    /// Sets the shape that the turtle is drawn with.
+    ///
+    /// The shape rotates with the turtle as it turns and is scaled by the
+    /// [shape size](struct.Turtle.html#method.set_shape_size). It only changes how the turtle
+    /// looks, not what it draws. See [`Shape`](enum.Shape.html) for the shapes that are available.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// turtle.set_shape(Shape::Arrow);
+    ///
+    /// // A custom shape, given as if the turtle was facing up
+    /// turtle.set_shape(Shape::Polygon(vec![
+    ///     [0.0, 15.0].into(),
+    ///     [10.0, -10.0].into(),
+    ///     [-10.0, -10.0].into(),
+    /// ]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shape is a polygon with fewer than three points or with a point that is not
+    /// finite.
+    pub fn set_shape(&mut self, shape: Shape) {
+        self.turtle.set_shape(shape)
+    }
+
+    /// Returns the amount that the shape of the turtle is scaled by.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// assert_eq!(turtle.shape_size(), 1.0);
+    /// turtle.set_shape_size(2.5);
+    /// assert_eq!(turtle.shape_size(), 2.5);
+    /// ```
+    pub fn shape_size(&self) -> f64 {
+        block_on(self.turtle.shape_size())
+    }
+
+    /// Scales the shape of the turtle by the given amount.
+    ///
+    /// A size of 1.0 (the default) draws the shape at its normal size, 2.0 draws it twice as
+    /// large, and 0.5 draws it half as large. The width of the outline around the shape does not
+    /// change.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// // A big turtle that is easy to see
+    /// turtle.set_shape_size(3.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not greater than zero or is not finite.
+    pub fn set_shape_size(&mut self, size: f64) {
+        self.turtle.set_shape_size(size)
+    }
+
+    /// Returns the layer that the turtle is currently drawing in.
+    ///
+    /// Every turtle starts in [`LayerId::BASE`](struct.LayerId.html#associatedconstant.BASE).
+    /// See [`set_layer()`](struct.Turtle.html#method.set_layer) for more information.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    /// assert_eq!(turtle.layer(), LayerId::BASE);
+    ///
+    /// let layer = drawing.add_layer();
+    /// turtle.set_layer(layer);
+    /// assert_eq!(turtle.layer(), layer);
+    /// ```
+    pub fn layer(&self) -> LayerId {
+        block_on(self.turtle.layer())
+    }
+
+    /// Moves the turtle into the given layer of its drawing.
+    ///
+    /// Everything that the turtle draws from now on is placed in the new layer. Anything it has
+    /// already drawn stays in the layer where it was drawn. While the layer is
+    /// [hidden](struct.Drawing.html#method.set_layer_visible), the turtle is hidden as well.
+    ///
+    /// Layers are created with [`Drawing::add_layer()`](struct.Drawing.html#method.add_layer). The
+    /// turtle stays in its current layer if the layer was added to a different drawing.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle = drawing.add_turtle();
+    /// let labels = drawing.add_layer();
+    ///
+    /// // The shapes go in the base layer
+    /// turtle.forward(100.0);
+    ///
+    /// // The labels go in their own layer so they can be hidden later
+    /// turtle.set_layer(labels);
+    /// turtle.write("end");
+    ///
+    /// drawing.set_layer_visible(labels, false);
+    /// ```
+    pub fn set_layer(&mut self, layer: LayerId) {
+        self.turtle.set_layer(layer)
+    }
+
+    /// Returns a snapshot of the state of the turtle that can be restored later with
+    /// [`set_state()`](#method.set_state).
+    ///
+    /// The snapshot includes the position, heading, speed, pen, fill color, and shape of the
+    /// turtle. See the [`TurtleState` struct](struct.TurtleState.html) for more information and
+    /// for how to save it to a file.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// turtle.set_pen_color("red");
+    /// turtle.right(45.0);
+    ///
+    /// let state = turtle.state();
+    /// assert_eq!(state.pen.color, "red".into());
+    /// assert_eq!(state.heading, 45.0);
+    /// ```
+    pub fn state(&self) -> TurtleState {
+        block_on(self.turtle.state())
+    }
+
+    /// Restores a snapshot of the state of the turtle that was taken with
+    /// [`state()`](#method.state).
+    ///
+    /// The turtle moves to the saved position and turns to the saved heading right away, without
+    /// drawing anything. Then the rest of the saved state is restored. What the turtle has
+    /// already drawn is not affected. The heading is restored correctly no matter whether the
+    /// turtle is using degrees or radians.
+    ///
+    /// ```rust
+    /// # use turtle::*;
+    /// let mut turtle = Turtle::new();
+    /// turtle.set_pen_size(5.0);
+    /// let state = turtle.state();
+    ///
+    /// turtle.forward(100.0);
+    /// turtle.set_pen_size(1.0);
+    ///
+    /// turtle.set_state(&state);
+    /// assert_eq!(turtle.position(), Point::origin());
+    /// assert_eq!(turtle.pen_size(), 5.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if any part of the state is invalid, for example if a color is not valid or the
+    /// shape size is not greater than zero.
+    pub fn set_state(&mut self, state: &TurtleState) {
+        block_on(self.turtle.set_state(state))
+    }
+
     /// Delete the turtle's drawings from the screen, re-center the turtle and reset all of the
     /// turtle's state (speed, color, etc.) back to the default.
     ///
@@ -943,6 +1935,39 @@ impl Turtle {
         self.turtle.clear()
     }
 
+    /// Returns the heading that the turtle would need to have in order to face the given point.
+    /// See the [`Point` struct](struct.Point.html) documentation for more information.
+    ///
+    /// The unit of the returned angle is degrees by default, but can be set using the
+    /// [`use_degrees()`](struct.Turtle.html#method.use_degrees) or
+    /// [`use_radians()`](struct.Turtle.html#method.use_radians) methods. Like
+    /// [`heading()`](struct.Turtle.html#method.heading), the value will be between 0.0 and 360.0
+    /// degrees (or 2&pi; radians).
+    ///
+    /// This does not rotate the turtle. Use [`turn_towards()`](struct.Turtle.html#method.turn_towards)
+    /// for that. To find out how far the turtle would turn, use the functions in the
+    /// [`angle` module](angle/index.html).
+    ///
+    /// If the given point is the turtle's current position, the returned angle will be zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use turtle::{Turtle, angle};
+    ///
+    /// let turtle = Turtle::new();
+    /// assert_eq!(turtle.towards([100.0, 0.0]), 0.0);
+    /// assert_eq!(turtle.towards([0.0, -100.0]), 270.0);
+    ///
+    /// // The turtle is facing north (90 degrees), so turning towards (-100, 100) would mean a
+    /// // 45 degree turn to the left
+    /// let turn = angle::difference_degrees(turtle.heading(), turtle.towards([-100.0, 100.0]));
+    /// assert_eq!(turn.round(), 45.0);
+    /// ```
+    pub fn towards<P: Into<Point>>(&self, target: P) -> Angle {
+        block_on(self.turtle.towards(target))
+    }
+
     /// Rotates the turtle to face the given point. See the [`Point` struct](struct.Point.html)
     /// documentation for more information.
     ///
@@ -983,6 +2008,84 @@ impl Turtle {
         block_on(self.turtle.turn_towards(target))
     }
 
+    /// Returns the smallest rectangle that contains everything drawn by this turtle, or `None` if
+    /// this turtle has not drawn anything yet.
+    ///
+    /// Drawings that have been removed with [`clear()`](struct.Turtle.html#method.clear) or
+    /// [`undo()`](struct.Turtle.html#method.undo) are not included. See
+    /// [`Drawing::bounds()`](struct.Drawing.html#method.bounds) for more information.
+    ///
+    /// ```rust
+    /// use turtle::Drawing;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle1 = drawing.add_turtle();
+    /// let mut turtle2 = drawing.add_turtle();
+    /// turtle1.set_pen_size(2.0);
+    /// turtle1.forward(100.0);
+    /// assert_eq!(turtle2.drawing_bounds(), None);
+    ///
+    /// turtle2.pen_up();
+    /// turtle2.go_to([-50.0, 0.0]);
+    /// turtle2.pen_down();
+    /// turtle2.go_to([-50.0, -50.0]);
+    ///
+    /// let bounds = turtle1.drawing_bounds().unwrap();
+    /// assert_eq!(bounds.min.round(), [-1.0, -1.0].into());
+    /// assert_eq!(bounds.max.round(), [1.0, 101.0].into());
+    /// assert_eq!(drawing.bounds().unwrap().min.round(), [-51.0, -51.0].into());
+    /// ```
+    pub fn drawing_bounds(&self) -> Option<Rect> {
+        block_on(self.turtle.drawing_bounds())
+    }
+
+    /// Returns the distance between this turtle and the other turtle.
+    ///
+    /// Both turtles must have been added to the same drawing with
+    /// [`Drawing::add_turtle()`](struct.Drawing.html#method.add_turtle). This is useful for games
+    /// like tag where one turtle needs to know how close it is to another.
+    ///
+    /// ```rust
+    /// use turtle::Drawing;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut turtle1 = drawing.add_turtle();
+    /// let mut turtle2 = drawing.add_turtle();
+    /// turtle1.forward(30.0);
+    /// turtle2.right(90.0);
+    /// turtle2.forward(40.0);
+    ///
+    /// assert_eq!(turtle1.distance_to(&turtle2).round(), 50.0);
+    /// ```
+    pub fn distance_to(&self, other: &Turtle) -> Distance {
+        block_on(self.turtle.distance_to(&other.turtle))
+    }
+
+    /// Returns true if the other turtle is at most `radius` away from this turtle.
+    ///
+    /// Check this after every move in a game loop to find out when two turtles run into each
+    /// other. See [`distance_to()`](struct.Turtle.html#method.distance_to) for more information.
+    ///
+    /// ```rust
+    /// use turtle::Drawing;
+    ///
+    /// let mut drawing = Drawing::new();
+    /// let mut chaser = drawing.add_turtle();
+    /// let mut runner = drawing.add_turtle();
+    /// runner.forward(100.0);
+    ///
+    /// assert!(!chaser.touches(&runner, 10.0));
+    /// chaser.forward(95.0);
+    /// assert!(chaser.touches(&runner, 10.0));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the radius is negative, infinite or `NaN`.
+    pub fn touches(&self, other: &Turtle, radius: Distance) -> bool {
+        block_on(self.turtle.touches(&other.turtle, radius))
+    }
+
     /// Convenience function that waits for a click to occur before returning.
     ///
     /// Useful for when you want the turtle to wait for the user to click before continuing. Use
@@ -1006,6 +2109,35 @@ impl Turtle {
     pub fn wait_for_click(&mut self) {
         block_on(self.turtle.wait_for_click())
     }
+
+    /// Pauses the program until any key is pressed, showing the given label in the title of the
+    /// window in the meantime.
+    ///
+    /// This makes it easy to step through a drawing one stage at a time, for example while
+    /// explaining how it works during a lesson. The title of the window goes back to what it was
+    /// once a key is pressed.
+    ///
+    /// Like [`wait_for_click()`](#method.wait_for_click), this ignores any other events that take
+    /// place before the key is pressed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use turtle::Turtle;
+    ///
+    /// fn main() {
+    ///     let mut turtle = Turtle::new();
+    ///
+    ///     for side in 1..=4 {
+    ///         turtle.forward(100.0);
+    ///         turtle.breakpoint(&format!("Drew side {}", side));
+    ///         turtle.right(90.0);
+    ///     }
+    /// }
+    /// ```
+    pub fn breakpoint(&mut self, label: &str) {
+        block_on(self.turtle.breakpoint(label))
+    }
 }
 
 #[cfg(test)]
@@ -1014,6 +2146,78 @@ mod tests {
 
     use crate::radians;
 
+    #[test]
+    #[should_panic(expected = "Invalid easing")]
+    fn rejects_out_of_range_easing() {
+        let mut turtle = Turtle::new();
+        turtle.set_easing(Easing::CubicBezier {x1: 0.5, y1: 1.5, x2: 0.5, y2: 1.0});
+    }
+
+    #[test]
+    fn timed_commands_keep_speed() {
+        let mut turtle = Turtle::new();
+        turtle.set_speed("slow");
+        turtle.forward_in(100.0, std::time::Duration::from_millis(10));
+        turtle.right_in(90.0, std::time::Duration::from_millis(10));
+        turtle.left_in(45.0, std::time::Duration::from_secs(0));
+        assert_eq!(turtle.speed(), Speed::from("slow"));
+        assert_eq!(turtle.position().round(), Point {x: 0.0, y: 100.0});
+        assert_eq!(turtle.heading().round(), 45.0);
+
+        // Non-finite values are ignored just like with forward() and right()
+        turtle.forward_in(f64::NAN, std::time::Duration::from_millis(10));
+        turtle.right_in(f64::INFINITY, std::time::Duration::from_millis(10));
+        assert_eq!(turtle.position().round(), Point {x: 0.0, y: 100.0});
+        assert_eq!(turtle.heading().round(), 45.0);
+    }
+
+    #[test]
+    fn animated_pen_color_changes_right_away_without_animation() {
+        let mut turtle = Turtle::new();
+        turtle.set_pen_color("blue");
+        turtle.animate_pen_color("red", std::time::Duration::from_secs(1));
+        assert_eq!(turtle.pen_color(), "red".into());
+
+        turtle.forward(10.0);
+        turtle.set_pen_color("green");
+        assert_eq!(turtle.pen_color(), "green".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid color")]
+    fn rejects_invalid_animated_pen_color() {
+        let mut turtle = Turtle::new();
+        turtle.animate_pen_color(Color {red: f64::NAN, green: 0.0, blue: 0.0, alpha: 1.0}, std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn follows_path_in_one_undo_step() {
+        let mut drawing = crate::Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        turtle.set_speed("instant");
+        turtle.follow_path(crate::Path::new()
+            .line_to([100.0, 0.0])
+            .quad_to([150.0, 50.0], [100.0, 100.0])
+            .arc_around([100.0, 50.0], 90.0));
+
+        // The arc ends to the left of its center
+        assert_eq!(turtle.position().round(), Point {x: 50.0, y: 50.0});
+        assert_eq!(drawing.primitives().len(), 3);
+
+        turtle.undo();
+        assert_eq!(turtle.position(), Point::origin());
+        assert!(drawing.primitives().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid radius: -1")]
+    fn rejects_negative_touch_radius() {
+        let mut drawing = crate::Drawing::new();
+        let turtle1 = drawing.add_turtle();
+        let turtle2 = drawing.add_turtle();
+        turtle1.touches(&turtle2, -1.0);
+    }
+
     #[test]
     fn is_using_radians_degrees() {
         // is_using_radians and is_using_degrees should be inverses of each other
@@ -1087,6 +2291,78 @@ mod tests {
         turtle.set_pen_size(-::std::f64::INFINITY);
     }
 
+    #[test]
+    #[should_panic(expected = "Invalid shape size: 0. The shape size must be greater than zero")]
+    fn set_shape_size_rejects_zero() {
+        let mut turtle = Turtle::new();
+        turtle.set_shape_size(0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "A polygon must have at least three points and every point must be finite")]
+    fn set_shape_rejects_degenerate_polygon() {
+        let mut turtle = Turtle::new();
+        turtle.set_shape(Shape::Polygon(vec![[0.0, 0.0].into(), [10.0, 0.0].into()]));
+    }
+
+    #[test]
+    fn pop_state_restores_in_reverse_order() {
+        let mut turtle = Turtle::new();
+        turtle.set_fill_color("blue");
+        turtle.push_state();
+        turtle.forward(50.0);
+        turtle.pen_up();
+        turtle.push_state();
+        turtle.set_fill_color("red");
+        turtle.left(90.0);
+        turtle.forward(50.0);
+
+        turtle.pop_state();
+        assert_eq!(turtle.position().round(), Point {x: 0.0, y: 50.0});
+        assert!(!turtle.is_pen_down());
+        turtle.pop_state();
+        assert_eq!(turtle.position(), Point::origin());
+        assert!(turtle.is_pen_down());
+        assert_eq!(turtle.fill_color(), "blue".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to pop turtle state: no state was saved with push_state()")]
+    fn pop_state_requires_saved_state() {
+        let mut turtle = Turtle::new();
+        turtle.push_state();
+        turtle.pop_state();
+        turtle.pop_state();
+    }
+
+    #[test]
+    fn set_state_restores_without_drawing() {
+        let mut turtle = Turtle::new();
+        turtle.forward(30.0);
+        turtle.right(90.0);
+        turtle.pen_up();
+        turtle.set_pen_dash_pattern(&[4.0, 2.0]);
+        turtle.hide();
+        let state = turtle.state();
+
+        let mut other = Turtle::new();
+        other.use_radians();
+        other.set_state(&state);
+        assert_eq!(other.state(), state);
+        assert!((other.heading() - 0.0).abs() < 1e-9);
+        assert!(other.drawing_bounds().is_none());
+    }
+
+    #[test]
+    fn reset_restores_shape() {
+        let mut turtle = Turtle::new();
+        turtle.set_shape(Shape::Square);
+        turtle.set_shape_size(2.0);
+        turtle.reset();
+        assert_eq!(turtle.shape(), Shape::Classic);
+        assert_eq!(turtle.shape_size(), 1.0);
+    }
+
     #[test]
     #[should_panic(expected = "Invalid color: Color { red: NaN, green: 0.0, blue: 0.0, alpha: 0.0 }. See the color module documentation for more information.")]
     fn rejects_invalid_pen_color() {
@@ -1111,6 +2387,29 @@ mod tests {
         });
     }
 
+    #[test]
+    #[should_panic(expected = "Invalid dash pattern: [0.0, 0.0]. Each length must be greater than or equal to zero and at least one must be greater than zero")]
+    fn rejects_empty_dash_pattern() {
+        let mut turtle = Turtle::new();
+        turtle.set_pen_dash_pattern(&[0.0, 0.0]);
+    }
+
+    #[test]
+    fn dash_pattern_continues_around_corners() {
+        let mut drawing = crate::Drawing::new();
+        let mut turtle = drawing.add_turtle();
+        turtle.set_pen_dash_pattern(&[8.0, 5.0]);
+        for _ in 0..4 {
+            turtle.forward(100.0);
+            turtle.right(90.0);
+        }
+
+        // 31 dashes around the whole square, two of which are split by a corner. If the pattern
+        // started over on each side, there would be 8 dashes on each side instead.
+        let (_, indices) = drawing.export_mesh();
+        assert_eq!(indices.len() / 3, 33 * 2);
+    }
+
     #[test]
     fn ignores_nan_inf_zero() {
         let mut turtle = Turtle::new();
@@ -1190,6 +2489,19 @@ mod tests {
         assert!((turtle.heading() - heading).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn arcs_end_exactly_on_circle() {
+        let mut turtle = Turtle::new();
+
+        turtle.arc_left(100.0, 360.0 * 3.0 + 90.0);
+        assert!((turtle.position() - [-100.0, 100.0].into()).len() < 1e-9);
+        assert!((turtle.heading() - 180.0).abs() < 1e-9);
+
+        turtle.arc_right(-50.0, -180.0);
+        assert!((turtle.position() - [-100.0, 0.0].into()).len() < 1e-9);
+        assert!(turtle.heading().abs().min((turtle.heading() - 360.0).abs()) < 1e-9);
+    }
+
     #[test]
     fn ignores_nan_inf() {
         let mut turtle = Turtle::new();
@@ -0,0 +1,455 @@
+use std::fmt::{self, Debug};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{Turtle, Point, Point3, Distance, Angle};
+
+/// The closest that a point can be to the camera of a perspective projection before it is clipped
+const NEAR_PLANE: f64 = 1.0;
+
+/// Controls how the three-dimensional drawing of a [`Turtle3D`] is flattened onto the window
+///
+/// In every projection, the origin of the 3D space is drawn at the origin of the drawing.
+///
+/// [`Turtle3D`]: struct.Turtle3D.html
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Projection {
+    /// Looks straight down the z-axis, so the z-coordinate of every point is ignored
+    ///
+    /// A turtle that never pitches or rolls draws exactly like a regular turtle. This is the
+    /// default projection.
+    #[default]
+    Orthographic,
+    /// Looks down at the origin from a corner so that the x, y, and z axes are drawn 120 degrees
+    /// apart at the same scale
+    Isometric,
+    /// Looks down the z-axis from a camera at the given distance from the origin, so that lines
+    /// further from the camera are drawn smaller
+    ///
+    /// The distance must be greater than zero. Lines that pass behind the camera are cut off.
+    Perspective {
+        /// The z-coordinate of the camera
+        distance: f64,
+    },
+}
+
+impl Projection {
+    /// Returns true if the projection can be used to draw
+    pub fn is_valid(self) -> bool {
+        match self {
+            Projection::Orthographic | Projection::Isometric => true,
+            Projection::Perspective {distance} => distance.is_finite() && distance > 0.0,
+        }
+    }
+
+    /// Returns the position in the drawing where the given point will be drawn, or `None` if the
+    /// point cannot be seen
+    ///
+    /// ```rust
+    /// use turtle::Projection;
+    ///
+    /// let projection = Projection::Perspective {distance: 500.0};
+    /// // Points at z = 0 are drawn at their original size
+    /// assert_eq!(projection.project([100.0, 50.0, 0.0]), Some([100.0, 50.0].into()));
+    /// // Points half way to the camera are drawn twice as far from the center
+    /// assert_eq!(projection.project([100.0, 50.0, 250.0]), Some([200.0, 100.0].into()));
+    /// // Points behind the camera can't be seen
+    /// assert_eq!(projection.project([100.0, 50.0, 600.0]), None);
+    /// ```
+    pub fn project<P: Into<Point3>>(self, point: P) -> Option<Point> {
+        let Point3 {x, y, z} = point.into();
+        match self {
+            Projection::Orthographic => Some(Point {x, y}),
+
+            Projection::Isometric => {
+                // Turn 45 degrees around the y-axis, then tilt down by asin(1 / sqrt(3)) so that
+                // the camera looks along the diagonal of a cube with one corner at the origin
+                let sqrt2 = 2f64.sqrt();
+                let sqrt3 = 3f64.sqrt();
+                Some(Point {
+                    x: (x - z) / sqrt2,
+                    y: y * sqrt2 / sqrt3 - (x + z) / sqrt2 / sqrt3,
+                })
+            },
+
+            Projection::Perspective {distance} => {
+                if z > distance - NEAR_PLANE {
+                    return None;
+                }
+
+                let scale = distance / (distance - z);
+                Some(Point {x: x * scale, y: y * scale})
+            },
+        }
+    }
+
+    /// Cuts off the part of the line from `start` to `end` that cannot be seen, returning the
+    /// part that is left (if any)
+    fn clip(self, start: Point3, end: Point3) -> Option<(Point3, Point3)> {
+        let max_z = match self {
+            Projection::Orthographic | Projection::Isometric => return Some((start, end)),
+            Projection::Perspective {distance} => distance - NEAR_PLANE,
+        };
+
+        let cut = |visible: Point3, hidden: Point3| {
+            let t = (max_z - visible.z) / (hidden.z - visible.z);
+            visible + (hidden - visible) * t
+        };
+        match (start.z <= max_z, end.z <= max_z) {
+            (true, true) => Some((start, end)),
+            (true, false) => Some((start, cut(start, end))),
+            (false, true) => Some((cut(end, start), end)),
+            (false, false) => None,
+        }
+    }
+}
+
+/// A turtle that moves in three dimensions
+///
+/// In addition to turning left and right, a 3D turtle can pitch its nose up and down and roll
+/// onto its sides. Everything it draws is flattened onto the window using a configurable
+/// [`Projection`]. This makes it possible to draw things like 3D L-systems and space curves.
+///
+/// The turtle starts at the origin, facing up the y-axis with its back towards you, exactly
+/// like a regular turtle. Turning left and right rotates it around its own "up" direction
+/// (initially the z-axis, pointing out of the screen).
+///
+/// ```rust,no_run
+/// use turtle::{Turtle3D, Projection};
+///
+/// fn main() {
+///     let mut turtle = Turtle3D::new();
+///     turtle.set_projection(Projection::Isometric);
+///
+///     // Draw four sides of a cube
+///     for _ in 0..4 {
+///         for _ in 0..4 {
+///             turtle.forward(100.0);
+///             turtle.right(90.0);
+///         }
+///         turtle.forward(100.0);
+///         turtle.pitch_up(90.0);
+///     }
+/// }
+/// ```
+///
+/// Drawing is done using a regular [`Turtle`] under the hood. Use [`turtle_mut()`] to change
+/// things like the pen color, pen size, and speed. Any turtle can be converted into a 3D turtle,
+/// including one added to a drawing with `drawing.add_turtle().into()`.
+///
+/// [`Projection`]: enum.Projection.html
+/// [`Turtle`]: struct.Turtle.html
+/// [`turtle_mut()`]: struct.Turtle3D.html#method.turtle_mut
+pub struct Turtle3D {
+    turtle: Turtle,
+    projection: Projection,
+    position: Point3,
+    /// The direction that the turtle is facing
+    heading: Point3,
+    /// The direction to the left of the turtle
+    left: Point3,
+    /// The direction out of the turtle's back, always equal to `heading.cross(left)`
+    up: Point3,
+    /// The position that the 2D turtle was last moved to, or `None` if it may have been moved
+    /// somewhere else
+    drawn_position: Option<Point>,
+}
+
+impl Debug for Turtle3D {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Turtle3D")
+            .field("position", &self.position)
+            .field("heading", &self.heading)
+            .field("up", &self.up)
+            .field("projection", &self.projection)
+            .field("turtle", &self.turtle)
+            .finish()
+    }
+}
+
+impl Default for Turtle3D {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Turtle> for Turtle3D {
+    /// Uses the given turtle to draw, starting from its current position (at z = 0) and heading
+    fn from(turtle: Turtle) -> Self {
+        let Point {x, y} = turtle.position();
+        let heading = turtle.heading();
+        let heading = if turtle.is_using_degrees() { heading.to_radians() } else { heading };
+
+        let (sin, cos) = heading.sin_cos();
+        Self {
+            projection: Projection::default(),
+            position: Point3 {x, y, z: 0.0},
+            heading: Point3 {x: cos, y: sin, z: 0.0},
+            left: Point3 {x: -sin, y: cos, z: 0.0},
+            up: Point3 {x: 0.0, y: 0.0, z: 1.0},
+            drawn_position: None,
+            turtle,
+        }
+    }
+}
+
+impl Turtle3D {
+    /// Creates a new 3D turtle, opening a new window just like
+    /// [`Turtle::new()`](struct.Turtle.html#method.new)
+    pub fn new() -> Self {
+        Turtle::new().into()
+    }
+
+    /// Returns the turtle used to draw
+    pub fn turtle(&self) -> &Turtle {
+        &self.turtle
+    }
+
+    /// Returns the turtle used to draw so that you can change its pen, speed, and other settings
+    ///
+    /// If you move this turtle, the 3D turtle will move it back (without drawing) the next time
+    /// it draws.
+    pub fn turtle_mut(&mut self) -> &mut Turtle {
+        self.drawn_position = None;
+        &mut self.turtle
+    }
+
+    /// Returns the turtle used to draw, discarding all of the 3D state
+    pub fn into_turtle(self) -> Turtle {
+        self.turtle
+    }
+
+    /// Returns the projection used to draw
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    /// Changes the projection used to draw
+    ///
+    /// Only lines drawn after this is called will use the new projection. The turtle moves to its
+    /// position in the new projection without drawing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the distance of a perspective projection is not greater than zero.
+    pub fn set_projection(&mut self, projection: Projection) {
+        assert!(projection.is_valid(), "Invalid projection: {:?}", projection);
+
+        self.projection = projection;
+        self.drawn_position = None;
+        self.move_to(self.position);
+    }
+
+    /// Returns the position of the turtle in 3D space
+    pub fn position(&self) -> Point3 {
+        self.position
+    }
+
+    /// Returns the direction that the turtle is facing as a point with a length of 1.0
+    pub fn heading(&self) -> Point3 {
+        self.heading
+    }
+
+    /// Returns the direction that the turtle's back is facing as a point with a length of 1.0
+    pub fn up(&self) -> Point3 {
+        self.up
+    }
+
+    /// Moves the turtle forward by the given distance, drawing a line if the pen is down.
+    ///
+    /// See [`Turtle::forward()`](struct.Turtle.html#method.forward) for more information.
+    pub fn forward(&mut self, distance: Distance) {
+        self.move_to(self.position + self.heading * distance);
+    }
+
+    /// Moves the turtle backward by the given distance, drawing a line if the pen is down.
+    ///
+    /// The turtle keeps facing the same direction.
+    pub fn backward(&mut self, distance: Distance) {
+        self.forward(-distance);
+    }
+
+    /// Moves the turtle to the given position in 3D space without changing its heading,
+    /// drawing a line if the pen is down.
+    pub fn go_to<P: Into<Point3>>(&mut self, position: P) {
+        self.move_to(position.into());
+    }
+
+    /// Turns the turtle left around its "up" direction by the given angle (also called "yaw").
+    ///
+    /// The unit of `angle` is the same as the unit used by the drawing turtle. See
+    /// [`Turtle::use_degrees()`](struct.Turtle.html#method.use_degrees).
+    pub fn left(&mut self, angle: Angle) {
+        let (heading, left) = rotate(self.heading, self.left, self.to_radians(angle));
+        self.set_orientation(heading, left);
+    }
+
+    /// Turns the turtle right around its "up" direction by the given angle (also called "yaw").
+    pub fn right(&mut self, angle: Angle) {
+        self.left(-angle);
+    }
+
+    /// Tilts the turtle's nose up by the given angle
+    pub fn pitch_up(&mut self, angle: Angle) {
+        let (heading, _) = rotate(self.heading, self.up, self.to_radians(angle));
+        self.set_orientation(heading, self.left);
+    }
+
+    /// Tilts the turtle's nose down by the given angle
+    pub fn pitch_down(&mut self, angle: Angle) {
+        self.pitch_up(-angle);
+    }
+
+    /// Rolls the turtle onto its left side by the given angle
+    pub fn roll_left(&mut self, angle: Angle) {
+        let (up, left) = rotate(self.up, self.left, self.to_radians(angle));
+        self.left = left;
+        self.up = up;
+        self.update_heading();
+    }
+
+    /// Rolls the turtle onto its right side by the given angle
+    pub fn roll_right(&mut self, angle: Angle) {
+        self.roll_left(-angle);
+    }
+
+    /// Moves the turtle back to the origin and resets its orientation, without drawing
+    pub fn home(&mut self) {
+        self.heading = Point3 {x: 0.0, y: 1.0, z: 0.0};
+        self.left = Point3 {x: -1.0, y: 0.0, z: 0.0};
+        self.up = Point3 {x: 0.0, y: 0.0, z: 1.0};
+        self.position = Point3::origin();
+        self.drawn_position = None;
+        self.move_to(self.position);
+    }
+
+    fn to_radians(&self, angle: Angle) -> f64 {
+        if self.turtle.is_using_degrees() { angle.to_radians() } else { angle }
+    }
+
+    /// Updates the orientation of the turtle, correcting for any rounding errors so that the
+    /// directions stay perpendicular
+    fn set_orientation(&mut self, heading: Point3, left: Point3) {
+        let heading = heading.normalize();
+        let up = heading.cross(left).normalize();
+        self.heading = heading;
+        self.left = up.cross(heading);
+        self.up = up;
+        self.update_heading();
+    }
+
+    /// Points the drawing turtle in the direction of the 3D turtle, as seen in the window
+    fn update_heading(&mut self) {
+        let start = self.projection.project(self.position);
+        let end = self.projection.project(self.position + self.heading);
+        if let (Some(start), Some(end)) = (start, end) {
+            let dir = end - start;
+            // Looking straight at or away from the camera has no direction in the window
+            if dir.len() > 1e-9 {
+                let angle = dir.atan2();
+                let angle = if self.turtle.is_using_degrees() { angle.to_degrees() } else { angle };
+                self.turtle.set_heading(angle);
+            }
+        }
+    }
+
+    /// Moves the turtle to the given position, drawing whatever part of that line can be seen
+    fn move_to(&mut self, position: Point3) {
+        if let Some((start, end)) = self.projection.clip(self.position, position) {
+            // Both points are visible after clipping
+            let start = self.projection.project(start).expect("bug: clipped point should be visible");
+            let end = self.projection.project(end).expect("bug: clipped point should be visible");
+
+            if self.drawn_position != Some(start) {
+                self.jump_to(start);
+            }
+            self.turtle.go_to(end);
+            self.drawn_position = Some(end);
+        }
+
+        self.position = position;
+    }
+
+    /// Moves the drawing turtle to the given position without drawing
+    fn jump_to(&mut self, position: Point) {
+        let is_pen_down = self.turtle.is_pen_down();
+        if is_pen_down {
+            self.turtle.pen_up();
+        }
+        self.turtle.go_to(position);
+        if is_pen_down {
+            self.turtle.pen_down();
+        }
+    }
+}
+
+/// Rotates the perpendicular directions `a` and `b` by the given angle (in radians) so that `a`
+/// moves towards `b`
+fn rotate(a: Point3, b: Point3, angle: f64) -> (Point3, Point3) {
+    let (sin, cos) = angle.sin_cos();
+    (a * cos + b * sin, b * cos - a * sin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Point3, expected: Point3) {
+        assert!((actual - expected).len() < 1e-9, "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn rotations_follow_orientation() {
+        let mut turtle = Turtle3D::new();
+        assert_close(turtle.heading(), [0.0, 1.0, 0.0].into());
+
+        // Nose up points straight out of the screen (and the turtle's back points down)
+        turtle.pitch_up(90.0);
+        assert_close(turtle.heading(), [0.0, 0.0, 1.0].into());
+        assert_close(turtle.up(), [0.0, -1.0, 0.0].into());
+
+        // Turning left is relative to the turtle, not the screen
+        turtle.left(90.0);
+        assert_close(turtle.heading(), [-1.0, 0.0, 0.0].into());
+
+        turtle.roll_right(90.0);
+        turtle.forward(10.0);
+        assert_close(turtle.position(), [-10.0, 0.0, 0.0].into());
+        assert_close(turtle.up(), [0.0, 0.0, 1.0].into());
+    }
+
+    #[test]
+    fn orthographic_matches_2d() {
+        let mut turtle = Turtle3D::new();
+        turtle.turtle_mut().use_radians();
+        turtle.right(std::f64::consts::FRAC_PI_2);
+        turtle.forward(100.0);
+        turtle.pitch_up(1.0);
+        turtle.forward(50.0);
+
+        let Point {x, y} = turtle.turtle().position();
+        assert!((x - 100.0 - 50.0 * 1f64.cos()).abs() < 1e-9);
+        assert!(y.abs() < 1e-9);
+        assert!((turtle.turtle().heading() - 0.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn isometric_axes() {
+        let project = |pt: [f64; 3]| Projection::Isometric.project(pt).unwrap();
+        let len = |pt: Point| pt.len();
+
+        let (x, y, z) = (project([1.0, 0.0, 0.0]), project([0.0, 1.0, 0.0]), project([0.0, 0.0, 1.0]));
+        assert!((len(x) - len(y)).abs() < 1e-9 && (len(y) - len(z)).abs() < 1e-9);
+        assert!(y.x.abs() < 1e-9 && y.y > 0.0);
+        assert!((x.x + z.x).abs() < 1e-9 && (x.y - z.y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn perspective_clips_behind_camera() {
+        let projection = Projection::Perspective {distance: 100.0};
+        let (start, end) = projection.clip([0.0, 0.0, 0.0].into(), [0.0, 0.0, 200.0].into()).unwrap();
+        assert_eq!(start, Point3::origin());
+        assert_eq!(end, Point3 {x: 0.0, y: 0.0, z: 100.0 - NEAR_PLANE});
+        assert!(projection.clip([0.0, 0.0, 150.0].into(), [0.0, 0.0, 200.0].into()).is_none());
+    }
+}
@@ -0,0 +1,219 @@
+//! Exposes the renderer protocol over WebSocket so that programs that are not written in Rust
+//! (e.g. web pages) can control a turtle window
+//!
+//! Each WebSocket connection is treated like a separate client of the renderer. Every text
+//! message received from the connection must be a single JSON-encoded `ClientRequest`. If the
+//! request has a response, the response is sent back as a JSON-encoded `BridgeResponse` before
+//! the next request is processed.
+//!
+//! Browsers let any web page open a WebSocket connection to any address, including one on the
+//! same computer. Browsers always say which page is connecting in the `Origin` header, so
+//! connections from pages that are not served from this computer are refused unless they were
+//! explicitly allowed.
+
+use std::io;
+use std::thread;
+use std::sync::Arc;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs, SocketAddr};
+
+use serde::Serialize;
+use tungstenite::{Message, http::StatusCode, handshake::server::{Request, Response, ErrorResponse}};
+
+use crate::ipc_protocol::{ProtocolClient, ClientRequest, ServerResponse};
+use crate::sync_runtime::block_on;
+
+/// A message sent back over the WebSocket connection
+#[derive(Debug, Serialize)]
+enum BridgeResponse {
+    /// The response from the renderer
+    Response(Box<ServerResponse>),
+    /// The request could not be sent to the renderer
+    Error(String),
+}
+
+/// Options for [`Drawing::serve_websocket_with()`]
+///
+/// [`Drawing::serve_websocket_with()`]: struct.Drawing.html#method.serve_websocket_with
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WebSocketOptions {
+    /// The origins of the web pages that are allowed to connect, in addition to pages served from
+    /// this computer (e.g. `"https://example.com"` or `"http://192.168.1.5:8000"`)
+    ///
+    /// Pages served from `localhost`, `127.0.0.1`, or `[::1]` are always allowed, as are programs
+    /// that are not web browsers. Use `"null"` to allow pages opened directly from a file.
+    pub allowed_origins: Vec<String>,
+}
+
+/// Starts accepting WebSocket connections at the given address in the background
+///
+/// Returns the address that the server is listening on.
+pub fn spawn<A: ToSocketAddrs>(client: ProtocolClient, addr: A, options: WebSocketOptions) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    let local_addr = listener.local_addr()?;
+    let options = Arc::new(options);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            // A failed connection attempt should not stop anyone else from connecting
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+
+            let client = block_on(client.split());
+            let options = options.clone();
+            thread::spawn(move || serve_connection(client, stream, &options));
+        }
+    });
+
+    Ok(local_addr)
+}
+
+fn serve_connection(client: ProtocolClient, stream: TcpStream, options: &WebSocketOptions) {
+    #[allow(clippy::result_large_err)] // The error type is decided by tungstenite
+    let check_origin = |req: &Request, res: Response| -> Result<Response, ErrorResponse> {
+        // Only browsers send an origin, and they always do
+        let origin = match req.headers().get("Origin") {
+            Some(origin) => origin.to_str().unwrap_or_default(),
+            None => return Ok(res),
+        };
+
+        if is_allowed_origin(origin, &options.allowed_origins) {
+            Ok(res)
+        } else {
+            let mut res = ErrorResponse::new(Some(format!("origin not allowed: {}", origin)));
+            *res.status_mut() = StatusCode::FORBIDDEN;
+            Err(res)
+        }
+    };
+
+    let mut socket = match tungstenite::accept_hdr(stream, check_origin) {
+        Ok(socket) => socket,
+        // Not a valid WebSocket connection or not allowed to connect
+        Err(_) => return,
+    };
+
+    loop {
+        let text = match socket.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Binary(_)) => {
+                let reply = BridgeResponse::Error("binary messages are not supported".to_string());
+                if !send_reply(&mut socket, &reply) {
+                    break;
+                }
+                continue;
+            },
+            // Ping, pong, and close messages are handled by tungstenite
+            Ok(_) => continue,
+            // The connection was closed
+            Err(_) => break,
+        };
+
+        let reply = match serde_json::from_str::<ClientRequest>(&text) {
+            Ok(req) if req.is_allowed_remotely() => block_on(client.forward(req)).map(|res| BridgeResponse::Response(Box::new(res))),
+            Ok(req) => Some(BridgeResponse::Error(format!("request not allowed over WebSocket: {:?}", req))),
+            Err(err) => Some(BridgeResponse::Error(format!("invalid request: {}", err))),
+        };
+
+        if let Some(reply) = reply {
+            if !send_reply(&mut socket, &reply) {
+                break;
+            }
+        }
+    }
+}
+
+/// Returns true if a web page with the given origin may connect
+///
+/// Pages served from this computer are always allowed. Any other page must be in the given list.
+fn is_allowed_origin(origin: &str, allowed_origins: &[String]) -> bool {
+    if allowed_origins.iter().any(|allowed| allowed.eq_ignore_ascii_case(origin)) {
+        return true;
+    }
+
+    // Origins look like "http://localhost:8000", or "http://[::1]:8000" for IPv6 addresses
+    let host = match origin.split_once("://") {
+        Some((_, host)) => host,
+        None => return false,
+    };
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next(),
+        None => host.split(':').next(),
+    };
+
+    matches!(host, Some("localhost") | Some("127.0.0.1") | Some("::1"))
+}
+
+/// Sends the reply over the socket, returning false if the connection has been closed
+fn send_reply(socket: &mut tungstenite::WebSocket<TcpStream>, reply: &BridgeResponse) -> bool {
+    let json = serde_json::to_string(reply)
+        .expect("bug: unable to serialize response to JSON");
+    socket.send(Message::Text(json)).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Drawing;
+
+    #[test]
+    fn forwards_requests_and_responses() {
+        let drawing = Drawing::new();
+        let addr = drawing.serve_websocket("127.0.0.1:0").expect("unable to start WebSocket bridge");
+
+        let (mut socket, _) = tungstenite::connect(format!("ws://{}", addr))
+            .expect("unable to connect to WebSocket bridge");
+
+        socket.send(Message::Text(r#"{"SetDrawingProp": {"Title": "Hello"}}"#.to_string())).unwrap();
+        socket.send(Message::Text(r#"{"DrawingProp": "Title"}"#.to_string())).unwrap();
+        let reply = socket.read().unwrap();
+        assert_eq!(reply, Message::Text(r#"{"Response":{"DrawingProp":{"Title":"Hello"}}}"#.to_string()));
+
+        socket.send(Message::Text(r#"{"Share": "oops"}"#.to_string())).unwrap();
+        match socket.read().unwrap() {
+            Message::Text(text) => assert!(text.starts_with(r#"{"Error":"#), "{}", text),
+            reply => panic!("unexpected reply: {:?}", reply),
+        }
+
+        // Invalid requests are reported back without closing the window
+        socket.send(Message::Text(r#"{"TurtleProp": [42, "Position"]}"#.to_string())).unwrap();
+        let reply = socket.read().unwrap();
+        assert_eq!(reply, Message::Text(r#"{"Response":{"Error":{"UnknownTurtle":42}}}"#.to_string()));
+        socket.send(Message::Text(r#"{"DrawingProp": "Title"}"#.to_string())).unwrap();
+        let reply = socket.read().unwrap();
+        assert_eq!(reply, Message::Text(r#"{"Response":{"DrawingProp":{"Title":"Hello"}}}"#.to_string()));
+    }
+
+    #[test]
+    fn refuses_other_origins() {
+        use tungstenite::client::IntoClientRequest;
+
+        let drawing = Drawing::new();
+        let addr = drawing.serve_websocket_with("127.0.0.1:0", WebSocketOptions {
+            allowed_origins: vec!["https://example.com".to_string()],
+        }).expect("unable to start WebSocket bridge");
+
+        let connect = |origin: &str| {
+            let mut req = format!("ws://{}", addr).into_client_request().unwrap();
+            req.headers_mut().insert("Origin", origin.parse().unwrap());
+            tungstenite::connect(req)
+        };
+
+        assert!(connect("http://localhost:8000").is_ok());
+        assert!(connect("https://example.com").is_ok());
+        assert!(connect("https://evil.example").is_err());
+        assert!(connect("http://localhost.evil.example").is_err());
+        assert!(connect("null").is_err());
+    }
+
+    #[test]
+    fn allows_local_origins() {
+        assert!(is_allowed_origin("http://localhost", &[]));
+        assert!(is_allowed_origin("http://127.0.0.1:8000", &[]));
+        assert!(is_allowed_origin("http://[::1]:8000", &[]));
+        assert!(!is_allowed_origin("http://[::2]:8000", &[]));
+        assert!(!is_allowed_origin("localhost", &[]));
+        assert!(is_allowed_origin("null", &["null".to_string()]));
+    }
+}
@@ -0,0 +1,37 @@
+/*
+ * Draws a square using the C bindings for turtle
+ *
+ * Build the library with `cargo build --release -p turtle-ffi`, then compile this file with:
+ *
+ *     cc examples/square.c -Iinclude -L../target/release -lturtle_ffi -o square
+ */
+
+#include <stdio.h>
+#include "turtle.h"
+
+int main(void) {
+    /* Must be the first thing that runs */
+    turtle_start();
+
+    TurtleDrawing *drawing = turtle_drawing_new();
+    if (drawing == NULL) {
+        fprintf(stderr, "unable to create drawing: %s\n", turtle_last_error());
+        return 1;
+    }
+
+    TurtleTurtle *turtle = turtle_drawing_add_turtle(drawing);
+    turtle_set_pen_color(turtle, "blue");
+    turtle_set_pen_size(turtle, 3.0);
+
+    for (int i = 0; i < 4; i++) {
+        if (turtle_forward(turtle, 200.0) != TURTLE_OK || turtle_right(turtle, 90.0) != TURTLE_OK) {
+            fprintf(stderr, "error: %s\n", turtle_last_error());
+            break;
+        }
+    }
+
+    turtle_turtle_free(turtle);
+    /* Waits until the window is closed */
+    turtle_drawing_free(drawing);
+    return 0;
+}
@@ -0,0 +1,87 @@
+/*
+ * C bindings for the turtle crate: https://turtle.rs
+ *
+ * Call turtle_start() at the very beginning of main(), before anything else. See the
+ * documentation in turtle-ffi/src/lib.rs for more information.
+ *
+ * Functions that can fail return TURTLE_ERROR (or NULL) and store a description of the error
+ * that can be retrieved with turtle_last_error().
+ */
+
+#ifndef TURTLE_H
+#define TURTLE_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum {
+    TURTLE_OK = 0,
+    TURTLE_ERROR = -1,
+} TurtleStatus;
+
+typedef struct TurtleDrawing TurtleDrawing;
+typedef struct TurtleTurtle TurtleTurtle;
+
+typedef enum {
+    /* A key was pressed or released: uses name and pressed */
+    TURTLE_EVENT_KEY = 0,
+    /* A mouse button was pressed or released: uses name and pressed */
+    TURTLE_EVENT_MOUSE_BUTTON = 1,
+    /* The mouse moved: uses x and y for the new position */
+    TURTLE_EVENT_MOUSE_MOVE = 2,
+    /* The mouse wheel scrolled: uses x and y for the amount scrolled */
+    TURTLE_EVENT_MOUSE_SCROLL = 3,
+    /* The window was resized: uses x and y for the new width and height */
+    TURTLE_EVENT_WINDOW_RESIZED = 4,
+    /* The window gained or lost focus: uses pressed (1 if the window gained focus) */
+    TURTLE_EVENT_WINDOW_FOCUSED = 5,
+    /* The mouse entered or left the window: uses pressed (1 if the mouse entered) */
+    TURTLE_EVENT_WINDOW_CURSOR = 6,
+    /* The window was closed */
+    TURTLE_EVENT_WINDOW_CLOSED = 7,
+} TurtleEventKind;
+
+typedef struct {
+    TurtleEventKind kind;
+    /* The name of the key or mouse button (e.g. "A", "Space", "LeftButton") */
+    char name[32];
+    int pressed;
+    double x;
+    double y;
+} TurtleEvent;
+
+void turtle_start(void);
+/* Like turtle_start(), but runs `executable` (or the current executable if NULL) with the given
+ * arguments whenever the window runs in a separate process. Needed by interpreters. */
+TurtleStatus turtle_start_with(const char *executable, const char *const *args, size_t num_args);
+const char *turtle_last_error(void);
+
+TurtleDrawing *turtle_drawing_new(void);
+void turtle_drawing_free(TurtleDrawing *drawing);
+TurtleTurtle *turtle_drawing_add_turtle(TurtleDrawing *drawing);
+TurtleStatus turtle_drawing_set_title(TurtleDrawing *drawing, const char *title);
+TurtleStatus turtle_drawing_set_background_color(TurtleDrawing *drawing, const char *color);
+/* Returns 1 if an event was stored in `event`, 0 if no event is available, and -1 on error */
+int turtle_drawing_poll_event(TurtleDrawing *drawing, TurtleEvent *event);
+
+void turtle_turtle_free(TurtleTurtle *turtle);
+TurtleStatus turtle_forward(TurtleTurtle *turtle, double distance);
+TurtleStatus turtle_backward(TurtleTurtle *turtle, double distance);
+TurtleStatus turtle_left(TurtleTurtle *turtle, double angle);
+TurtleStatus turtle_right(TurtleTurtle *turtle, double angle);
+/* 1 to 25, or 0 for instant */
+TurtleStatus turtle_set_speed(TurtleTurtle *turtle, int speed);
+TurtleStatus turtle_pen_down(TurtleTurtle *turtle);
+TurtleStatus turtle_pen_up(TurtleTurtle *turtle);
+TurtleStatus turtle_set_pen_size(TurtleTurtle *turtle, double thickness);
+TurtleStatus turtle_set_pen_color(TurtleTurtle *turtle, const char *color);
+TurtleStatus turtle_set_pen_color_rgba(TurtleTurtle *turtle, double red, double green, double blue, double alpha);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* TURTLE_H */
@@ -0,0 +1,665 @@
+//! C bindings for the turtle crate
+//!
+//! This crate exposes a small C API so that programs written in C, C++, Python (via `ctypes`),
+//! and other languages can draw using the turtle renderer. See `include/turtle.h` for the C
+//! declarations of everything in this crate and `examples/square.c` for an example.
+//!
+//! # Calling `turtle_start()`
+//!
+//! Just like a Rust program, a C program **must** call `turtle_start()` at the very beginning of
+//! `main()`. On some platforms, the window is run in a separate process that is spawned by
+//! re-running the current executable. When `turtle_start()` is called from within that process,
+//! it takes over the process to run the window and never returns. Any code that runs before the
+//! call will run twice and any output or side effects from that code will happen twice.
+//!
+//! If the turtle crate is being used from an interpreted language (e.g. Python), the executable
+//! being re-run is the interpreter and it needs to be told which script to run again. Call
+//! `turtle_start_with()` instead of `turtle_start()` to pass it the arguments it was started with
+//! (e.g. `sys.executable` and `sys.argv` in Python). The script must call it before it does
+//! anything else, just like `turtle_start()`.
+//!
+//! # Error handling
+//!
+//! Panics are never allowed to cross the FFI boundary. Functions that can fail return a
+//! `TurtleStatus` (or `NULL` for functions that return pointers). The message describing the
+//! most recent error on the current thread can be retrieved with `turtle_last_error()`.
+
+#[cfg(all(test, not(feature = "test")))]
+compile_error!("Make sure you run tests with `cargo test -p turtle-ffi --features test`");
+
+use std::ptr;
+use std::slice;
+use std::ffi::{CStr, CString, OsString};
+use std::cell::RefCell;
+use std::os::raw::{c_char, c_double, c_int};
+use std::panic::{self, AssertUnwindSafe};
+
+use turtle::{Drawing, Turtle, Color, Event, ProcessOptions};
+
+thread_local! {
+    /// The message from the most recent error on this thread
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// The result of a function that can fail
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurtleStatus {
+    /// The function completed successfully
+    TurtleOk = 0,
+    /// The function failed, use `turtle_last_error()` to find out why
+    TurtleError = -1,
+}
+
+/// A drawing, created with `turtle_drawing_new()`
+pub struct TurtleDrawing(Drawing);
+
+/// A turtle, created with `turtle_drawing_add_turtle()`
+pub struct TurtleTurtle(Turtle);
+
+/// The kind of event stored in a `TurtleEvent`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurtleEventKind {
+    /// A key was pressed or released: uses `name` and `pressed`
+    TurtleEventKey = 0,
+    /// A mouse button was pressed or released: uses `name` and `pressed`
+    TurtleEventMouseButton = 1,
+    /// The mouse moved: uses `x` and `y` for the new position
+    TurtleEventMouseMove = 2,
+    /// The mouse wheel scrolled: uses `x` and `y` for the amount scrolled
+    TurtleEventMouseScroll = 3,
+    /// The window was resized: uses `x` and `y` for the new width and height
+    TurtleEventWindowResized = 4,
+    /// The window gained or lost focus: uses `pressed` (1 if the window gained focus)
+    TurtleEventWindowFocused = 5,
+    /// The mouse entered or left the window: uses `pressed` (1 if the mouse entered)
+    TurtleEventWindowCursor = 6,
+    /// The window was closed
+    TurtleEventWindowClosed = 7,
+}
+
+/// The maximum length of `TurtleEvent::name`, including the terminating NUL byte
+const EVENT_NAME_LEN: usize = 32;
+
+/// An event from the window, filled in by `turtle_drawing_poll_event()`
+///
+/// Only some of the fields are used for each kind of event. See `TurtleEventKind`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TurtleEvent {
+    pub kind: TurtleEventKind,
+    /// The name of the key or mouse button as a NUL-terminated string (e.g. `"A"`, `"Space"`,
+    /// `"LeftButton"`)
+    pub name: [c_char; EVENT_NAME_LEN],
+    pub pressed: c_int,
+    pub x: c_double,
+    pub y: c_double,
+}
+
+impl TurtleEvent {
+    fn new(kind: TurtleEventKind) -> Self {
+        Self {kind, name: [0; EVENT_NAME_LEN], pressed: 0, x: 0.0, y: 0.0}
+    }
+
+    fn with_name<N: std::fmt::Debug>(mut self, name: N) -> Self {
+        let name = format!("{:?}", name);
+        // Leave room for the NUL byte at the end, which is already present in the array
+        for (dest, &byte) in self.name.iter_mut().zip(&name.as_bytes()[..name.len().min(EVENT_NAME_LEN - 1)]) {
+            *dest = byte as c_char;
+        }
+        self
+    }
+
+    fn with_pressed(mut self, pressed: bool) -> Self {
+        self.pressed = pressed as c_int;
+        self
+    }
+
+    fn with_xy(mut self, x: f64, y: f64) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Converts an event, returning `None` if the event cannot be represented
+    fn from_event(event: Event) -> Option<Self> {
+        use turtle::event::PressedState;
+        use TurtleEventKind::*;
+
+        let event = match event {
+            Event::Key(key, state) => Self::new(TurtleEventKey)
+                .with_name(key)
+                .with_pressed(state == PressedState::Pressed),
+            Event::MouseButton(button, state) => Self::new(TurtleEventMouseButton)
+                .with_name(button)
+                .with_pressed(state == PressedState::Pressed),
+            Event::MouseMove(point) => Self::new(TurtleEventMouseMove).with_xy(point.x, point.y),
+            Event::MouseScroll {x, y} => Self::new(TurtleEventMouseScroll).with_xy(x, y),
+            Event::WindowResized {width, height} => Self::new(TurtleEventWindowResized)
+                .with_xy(width as f64, height as f64),
+            Event::WindowFocused(focused) => Self::new(TurtleEventWindowFocused).with_pressed(focused),
+            Event::WindowCursor(entered) => Self::new(TurtleEventWindowCursor).with_pressed(entered),
+            Event::WindowClosed => Self::new(TurtleEventWindowClosed),
+            _ => return None,
+        };
+
+        Some(event)
+    }
+}
+
+fn set_last_error(message: String) {
+    // Interior NUL bytes would cut off the message, so they are removed
+    let message = CString::new(message.replace('\0', ""))
+        .expect("bug: NUL bytes should have been removed");
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+}
+
+/// Runs the given function, catching any panics and storing their message as the last error
+fn catch_panic<T, F: FnOnce() -> T>(f: F) -> Option<T> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            let message = err.downcast_ref::<&str>().map(|s| s.to_string())
+                .or_else(|| err.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown error".to_string());
+            set_last_error(message);
+            None
+        },
+    }
+}
+
+/// Runs the given function with the turtle behind the given pointer
+///
+/// # Safety
+///
+/// The pointer must either be `NULL` or have come from `turtle_drawing_add_turtle()` and not have
+/// been freed.
+unsafe fn with_turtle<F: FnOnce(&mut Turtle)>(turtle: *mut TurtleTurtle, f: F) -> TurtleStatus {
+    if turtle.is_null() {
+        set_last_error("turtle must not be NULL".to_string());
+        return TurtleStatus::TurtleError;
+    }
+
+    let turtle = &mut (*turtle).0;
+    match catch_panic(|| f(turtle)) {
+        Some(()) => TurtleStatus::TurtleOk,
+        None => TurtleStatus::TurtleError,
+    }
+}
+
+/// Must be called at the very beginning of `main()`, before anything else
+///
+/// See the crate documentation for more information.
+#[no_mangle]
+pub extern "C" fn turtle_start() {
+    // Panics here mean that something is seriously wrong, so there is no status to return
+    if catch_panic(turtle::start).is_none() {
+        std::process::abort();
+    }
+}
+
+/// Works just like `turtle_start()`, but runs the given executable with the given arguments
+/// whenever the window is run in a separate process
+///
+/// `executable` may be `NULL` to run the current executable again. `args` must point to
+/// `num_args` NUL-terminated strings, and may be `NULL` if `num_args` is 0. The executable is
+/// not included in the arguments.
+///
+/// # Safety
+///
+/// The executable must either be `NULL` or a valid NUL-terminated string. The arguments must be
+/// valid as described above.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_start_with(
+    executable: *const c_char,
+    args: *const *const c_char,
+    num_args: usize,
+) -> TurtleStatus {
+    if args.is_null() && num_args > 0 {
+        set_last_error("args must not be NULL unless num_args is 0".to_string());
+        return TurtleStatus::TurtleError;
+    }
+
+    let args = match num_args {
+        0 => &[],
+        _ => slice::from_raw_parts(args, num_args),
+    };
+    if args.iter().any(|arg| arg.is_null()) {
+        set_last_error("args must not contain NULL".to_string());
+        return TurtleStatus::TurtleError;
+    }
+
+    let options = ProcessOptions {
+        executable: match executable.is_null() {
+            true => None,
+            false => Some(os_string(CStr::from_ptr(executable)).into()),
+        },
+        args: args.iter().map(|&arg| os_string(CStr::from_ptr(arg))).collect(),
+        ..ProcessOptions::default()
+    };
+
+    // Panics here mean that something is seriously wrong, just like in `turtle_start()`
+    if catch_panic(|| turtle::start_with(options)).is_none() {
+        std::process::abort();
+    }
+
+    TurtleStatus::TurtleOk
+}
+
+/// Converts a string from C into a path or argument without losing any bytes where possible
+fn os_string(value: &CStr) -> OsString {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        std::ffi::OsStr::from_bytes(value.to_bytes()).to_os_string()
+    }
+
+    #[cfg(not(unix))]
+    {
+        value.to_string_lossy().into_owned().into()
+    }
+}
+
+/// Returns the message from the most recent error on the current thread, or `NULL` if no error
+/// has occurred
+///
+/// The returned string is valid until the next call to a function from this library on the
+/// same thread.
+#[no_mangle]
+pub extern "C" fn turtle_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| match &*last_error.borrow() {
+        Some(message) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+/// Opens a new window and returns the drawing in it, or `NULL` if the window could not be opened
+///
+/// The returned drawing must be freed with `turtle_drawing_free()`.
+#[no_mangle]
+pub extern "C" fn turtle_drawing_new() -> *mut TurtleDrawing {
+    match catch_panic(Drawing::new) {
+        Some(drawing) => Box::into_raw(Box::new(TurtleDrawing(drawing))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Frees the drawing
+///
+/// If no turtles from this drawing are still around, this waits for the window to be closed
+/// so that the drawing stays visible. Passing `NULL` does nothing.
+///
+/// # Safety
+///
+/// The pointer must have come from `turtle_drawing_new()` and must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_drawing_free(drawing: *mut TurtleDrawing) {
+    if drawing.is_null() {
+        return;
+    }
+
+    let drawing = Box::from_raw(drawing);
+    catch_panic(move || drop(drawing));
+}
+
+/// Adds a new turtle to the center of the drawing, returning `NULL` on error
+///
+/// The returned turtle must be freed with `turtle_turtle_free()`.
+///
+/// # Safety
+///
+/// The pointer must have come from `turtle_drawing_new()` and must not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_drawing_add_turtle(drawing: *mut TurtleDrawing) -> *mut TurtleTurtle {
+    if drawing.is_null() {
+        set_last_error("drawing must not be NULL".to_string());
+        return ptr::null_mut();
+    }
+
+    let drawing = &mut (*drawing).0;
+    match catch_panic(|| drawing.add_turtle()) {
+        Some(turtle) => Box::into_raw(Box::new(TurtleTurtle(turtle))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Sets the title of the window
+///
+/// # Safety
+///
+/// The drawing pointer must have come from `turtle_drawing_new()` and must not have been freed.
+/// The title must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_drawing_set_title(drawing: *mut TurtleDrawing, title: *const c_char) -> TurtleStatus {
+    if drawing.is_null() || title.is_null() {
+        set_last_error("drawing and title must not be NULL".to_string());
+        return TurtleStatus::TurtleError;
+    }
+
+    let drawing = &mut (*drawing).0;
+    let title = CStr::from_ptr(title).to_string_lossy().into_owned();
+    match catch_panic(|| drawing.set_title(title)) {
+        Some(()) => TurtleStatus::TurtleOk,
+        None => TurtleStatus::TurtleError,
+    }
+}
+
+/// Sets the background color of the drawing using a color name (e.g. `"pink"`) or a hex color
+/// string (e.g. `"#ff00ff"`)
+///
+/// # Safety
+///
+/// The drawing pointer must have come from `turtle_drawing_new()` and must not have been freed.
+/// The color must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_drawing_set_background_color(drawing: *mut TurtleDrawing, color: *const c_char) -> TurtleStatus {
+    if drawing.is_null() || color.is_null() {
+        set_last_error("drawing and color must not be NULL".to_string());
+        return TurtleStatus::TurtleError;
+    }
+
+    let drawing = &mut (*drawing).0;
+    let color = CStr::from_ptr(color).to_string_lossy();
+    match catch_panic(|| drawing.set_background_color(&*color)) {
+        Some(()) => TurtleStatus::TurtleOk,
+        None => TurtleStatus::TurtleError,
+    }
+}
+
+/// Gets the next event from the window, if any
+///
+/// Returns 1 and fills in `event` if an event was available, returns 0 if no events are
+/// currently available, and returns -1 on error.
+///
+/// # Safety
+///
+/// The drawing pointer must have come from `turtle_drawing_new()` and must not have been freed.
+/// The event pointer must point to a valid `TurtleEvent`.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_drawing_poll_event(drawing: *mut TurtleDrawing, event: *mut TurtleEvent) -> c_int {
+    if drawing.is_null() || event.is_null() {
+        set_last_error("drawing and event must not be NULL".to_string());
+        return -1;
+    }
+
+    let drawing = &mut (*drawing).0;
+    let next_event = catch_panic(|| loop {
+        match drawing.poll_event() {
+            Some(next) => match TurtleEvent::from_event(next) {
+                Some(next) => break Some(next),
+                // Skip events that C programs cannot represent
+                None => continue,
+            },
+            None => break None,
+        }
+    });
+
+    match next_event {
+        Some(Some(next)) => {
+            *event = next;
+            1
+        },
+        Some(None) => 0,
+        None => -1,
+    }
+}
+
+/// Frees the turtle. Passing `NULL` does nothing.
+///
+/// # Safety
+///
+/// The pointer must have come from `turtle_drawing_add_turtle()` and must not be used after this
+/// call.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_turtle_free(turtle: *mut TurtleTurtle) {
+    if turtle.is_null() {
+        return;
+    }
+
+    let turtle = Box::from_raw(turtle);
+    catch_panic(move || drop(turtle));
+}
+
+/// Moves the turtle forward by the given distance
+///
+/// # Safety
+///
+/// The turtle pointer must have come from `turtle_drawing_add_turtle()` and must not have been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_forward(turtle: *mut TurtleTurtle, distance: c_double) -> TurtleStatus {
+    with_turtle(turtle, |turtle| turtle.forward(distance))
+}
+
+/// Moves the turtle backward by the given distance
+///
+/// # Safety
+///
+/// The turtle pointer must have come from `turtle_drawing_add_turtle()` and must not have been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_backward(turtle: *mut TurtleTurtle, distance: c_double) -> TurtleStatus {
+    with_turtle(turtle, |turtle| turtle.backward(distance))
+}
+
+/// Rotates the turtle to the left by the given angle (in degrees)
+///
+/// # Safety
+///
+/// The turtle pointer must have come from `turtle_drawing_add_turtle()` and must not have been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_left(turtle: *mut TurtleTurtle, angle: c_double) -> TurtleStatus {
+    with_turtle(turtle, |turtle| turtle.left(angle))
+}
+
+/// Rotates the turtle to the right by the given angle (in degrees)
+///
+/// # Safety
+///
+/// The turtle pointer must have come from `turtle_drawing_add_turtle()` and must not have been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_right(turtle: *mut TurtleTurtle, angle: c_double) -> TurtleStatus {
+    with_turtle(turtle, |turtle| turtle.right(angle))
+}
+
+/// Sets the speed of the turtle to a value from 1 to 25, or 0 for instant
+///
+/// # Safety
+///
+/// The turtle pointer must have come from `turtle_drawing_add_turtle()` and must not have been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_set_speed(turtle: *mut TurtleTurtle, speed: c_int) -> TurtleStatus {
+    with_turtle(turtle, |turtle| if speed == 0 {
+        turtle.set_speed("instant")
+    } else {
+        turtle.set_speed(speed)
+    })
+}
+
+/// Starts drawing when the turtle moves
+///
+/// # Safety
+///
+/// The turtle pointer must have come from `turtle_drawing_add_turtle()` and must not have been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_pen_down(turtle: *mut TurtleTurtle) -> TurtleStatus {
+    with_turtle(turtle, |turtle| turtle.pen_down())
+}
+
+/// Stops drawing when the turtle moves
+///
+/// # Safety
+///
+/// The turtle pointer must have come from `turtle_drawing_add_turtle()` and must not have been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_pen_up(turtle: *mut TurtleTurtle) -> TurtleStatus {
+    with_turtle(turtle, |turtle| turtle.pen_up())
+}
+
+/// Sets the thickness of the pen
+///
+/// # Safety
+///
+/// The turtle pointer must have come from `turtle_drawing_add_turtle()` and must not have been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_set_pen_size(turtle: *mut TurtleTurtle, thickness: c_double) -> TurtleStatus {
+    with_turtle(turtle, |turtle| turtle.set_pen_size(thickness))
+}
+
+/// Sets the color of the pen using a color name (e.g. `"blue"`) or a hex color string (e.g.
+/// `"#0000ff"`)
+///
+/// # Safety
+///
+/// The turtle pointer must have come from `turtle_drawing_add_turtle()` and must not have been
+/// freed. The color must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_set_pen_color(turtle: *mut TurtleTurtle, color: *const c_char) -> TurtleStatus {
+    if color.is_null() {
+        set_last_error("color must not be NULL".to_string());
+        return TurtleStatus::TurtleError;
+    }
+
+    let color = CStr::from_ptr(color).to_string_lossy();
+    with_turtle(turtle, |turtle| turtle.set_pen_color(&*color))
+}
+
+/// Sets the pen color using red, green, and blue values from 0 to 255 and an alpha value from
+/// 0 to 1
+///
+/// # Safety
+///
+/// The turtle pointer must have come from `turtle_drawing_add_turtle()` and must not have been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn turtle_set_pen_color_rgba(
+    turtle: *mut TurtleTurtle,
+    red: c_double,
+    green: c_double,
+    blue: c_double,
+    alpha: c_double,
+) -> TurtleStatus {
+    with_turtle(turtle, |turtle| turtle.set_pen_color(Color::rgba(red, green, blue, alpha)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use turtle::event::{Key, MouseButton, PressedState};
+
+    fn c_string(value: &str) -> CString {
+        CString::new(value).unwrap()
+    }
+
+    fn last_error() -> String {
+        let message = turtle_last_error();
+        assert!(!message.is_null(), "expected an error to have been stored");
+        unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn draws_with_a_turtle() {
+        unsafe {
+            let drawing = turtle_drawing_new();
+            assert!(!drawing.is_null());
+            assert_eq!(turtle_drawing_set_title(drawing, c_string("Square").as_ptr()), TurtleStatus::TurtleOk);
+            assert_eq!(turtle_drawing_set_background_color(drawing, c_string("pink").as_ptr()), TurtleStatus::TurtleOk);
+
+            let turtle = turtle_drawing_add_turtle(drawing);
+            assert!(!turtle.is_null());
+            assert_eq!(turtle_set_speed(turtle, 0), TurtleStatus::TurtleOk);
+            assert_eq!(turtle_set_speed(turtle, 25), TurtleStatus::TurtleOk);
+            assert_eq!(turtle_set_pen_size(turtle, 3.0), TurtleStatus::TurtleOk);
+            assert_eq!(turtle_set_pen_color(turtle, c_string("#0000ff").as_ptr()), TurtleStatus::TurtleOk);
+            assert_eq!(turtle_set_pen_color_rgba(turtle, 255.0, 0.0, 0.0, 0.5), TurtleStatus::TurtleOk);
+            for _ in 0..4 {
+                assert_eq!(turtle_forward(turtle, 100.0), TurtleStatus::TurtleOk);
+                assert_eq!(turtle_right(turtle, 90.0), TurtleStatus::TurtleOk);
+            }
+            assert_eq!(turtle_pen_up(turtle), TurtleStatus::TurtleOk);
+            assert_eq!(turtle_backward(turtle, 50.0), TurtleStatus::TurtleOk);
+            assert_eq!(turtle_left(turtle, 45.0), TurtleStatus::TurtleOk);
+            assert_eq!(turtle_pen_down(turtle), TurtleStatus::TurtleOk);
+
+            // No events are ever sent without a window
+            let mut event = TurtleEvent::new(TurtleEventKind::TurtleEventWindowClosed);
+            assert_eq!(turtle_drawing_poll_event(drawing, &mut event), 0);
+
+            turtle_turtle_free(turtle);
+            turtle_drawing_free(drawing);
+        }
+    }
+
+    #[test]
+    fn reports_errors() {
+        unsafe {
+            let drawing = turtle_drawing_new();
+            let turtle = turtle_drawing_add_turtle(drawing);
+
+            // Panics are caught and their message is stored
+            assert_eq!(turtle_set_pen_color(turtle, c_string("not a color").as_ptr()), TurtleStatus::TurtleError);
+            assert!(!last_error().is_empty());
+            assert_eq!(turtle_set_pen_size(turtle, -1.0), TurtleStatus::TurtleError);
+
+            assert_eq!(turtle_forward(ptr::null_mut(), 10.0), TurtleStatus::TurtleError);
+            assert_eq!(last_error(), "turtle must not be NULL");
+            assert_eq!(turtle_set_pen_color(turtle, ptr::null()), TurtleStatus::TurtleError);
+            assert_eq!(last_error(), "color must not be NULL");
+            assert_eq!(turtle_drawing_set_title(drawing, ptr::null()), TurtleStatus::TurtleError);
+            assert_eq!(last_error(), "drawing and title must not be NULL");
+            assert!(turtle_drawing_add_turtle(ptr::null_mut()).is_null());
+            assert_eq!(turtle_drawing_poll_event(drawing, ptr::null_mut()), -1);
+
+            // Freeing NULL does nothing
+            turtle_turtle_free(ptr::null_mut());
+            turtle_drawing_free(ptr::null_mut());
+
+            turtle_turtle_free(turtle);
+            turtle_drawing_free(drawing);
+        }
+    }
+
+    #[test]
+    fn start_with_arguments() {
+        unsafe {
+            let script = c_string("script.py");
+            let args = [script.as_ptr()];
+            assert_eq!(turtle_start_with(c_string("python3").as_ptr(), args.as_ptr(), args.len()), TurtleStatus::TurtleOk);
+            assert_eq!(turtle_start_with(ptr::null(), ptr::null(), 0), TurtleStatus::TurtleOk);
+
+            assert_eq!(turtle_start_with(ptr::null(), ptr::null(), 1), TurtleStatus::TurtleError);
+            assert_eq!(last_error(), "args must not be NULL unless num_args is 0");
+            let args = [script.as_ptr(), ptr::null()];
+            assert_eq!(turtle_start_with(ptr::null(), args.as_ptr(), args.len()), TurtleStatus::TurtleError);
+            assert_eq!(last_error(), "args must not contain NULL");
+        }
+    }
+
+    #[test]
+    fn converts_events() {
+        let event = TurtleEvent::from_event(Event::Key(Key::A, PressedState::Pressed)).unwrap();
+        assert_eq!(event.kind, TurtleEventKind::TurtleEventKey);
+        assert_eq!(unsafe { CStr::from_ptr(event.name.as_ptr()) }.to_str().unwrap(), "A");
+        assert_eq!(event.pressed, 1);
+
+        let event = TurtleEvent::from_event(Event::MouseButton(MouseButton::LeftButton, PressedState::Released)).unwrap();
+        assert_eq!(unsafe { CStr::from_ptr(event.name.as_ptr()) }.to_str().unwrap(), "LeftButton");
+        assert_eq!(event.pressed, 0);
+
+        let event = TurtleEvent::from_event(Event::WindowResized {width: 300, height: 200}).unwrap();
+        assert_eq!(event.kind, TurtleEventKind::TurtleEventWindowResized);
+        assert_eq!((event.x, event.y), (300.0, 200.0));
+
+        // Names that are too long are cut off, leaving room for the NUL byte
+        let event = TurtleEvent::new(TurtleEventKind::TurtleEventKey).with_name("x".repeat(100));
+        assert_eq!(event.name[EVENT_NAME_LEN - 1], 0);
+    }
+}